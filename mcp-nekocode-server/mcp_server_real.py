@@ -1264,7 +1264,7 @@ class NekoCodeMCPServer:
     
     async def _tool_watch_status(self, args: Dict) -> Dict:
         """ファイル監視状態確認"""
-        cmd_args = ["watch-status"]
+        cmd_args = ["watch-status", "--json"]
         if "session_id" in args and args["session_id"]:
             cmd_args.append(args["session_id"])
         result = await self._run_nekocode(cmd_args)
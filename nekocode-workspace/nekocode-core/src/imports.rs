@@ -0,0 +1,130 @@
+//! Resolution of import specifiers to the files they name
+//!
+//! Shared by the tools that follow imports between files of a session: the
+//! dependency graph in nekoimpact and import rewriting in nekorefactor.
+
+use std::path::{Path, PathBuf};
+
+use crate::types::Language;
+
+/// Resolve an import to a file path based on language-specific rules.
+/// Returns `None` for external (package, system) imports.
+pub fn resolve_import_path(import_path: &str, current_file: &Path, language: Language) -> Option<PathBuf> {
+    let import_path = import_path.trim();
+    let parent = current_file.parent()?;
+
+    match language {
+        Language::JavaScript | Language::TypeScript => {
+            if !import_path.starts_with("./") && !import_path.starts_with("../") {
+                return None;
+            }
+
+            let resolved = parent.join(import_path);
+            if resolved.is_file() {
+                return Some(resolved);
+            }
+            for ext in &["js", "ts", "jsx", "tsx", "mjs"] {
+                let with_ext = resolved.with_extension(ext);
+                if with_ext.is_file() {
+                    return Some(with_ext);
+                }
+            }
+            for ext in &["js", "ts"] {
+                let index_file = resolved.join(format!("index.{}", ext));
+                if index_file.is_file() {
+                    return Some(index_file);
+                }
+            }
+            Some(resolved)
+        }
+
+        Language::Python => {
+            let module = import_path.trim_start_matches('.');
+            let level = import_path.len() - module.len();
+
+            let mut base = parent.to_path_buf();
+            for _ in 1..level {
+                base.pop();
+            }
+
+            let module_path = base.join(module.replace('.', "/"));
+            let candidates = [module_path.with_extension("py"), module_path.join("__init__.py")];
+
+            if level > 0 {
+                candidates.iter().find(|c| c.is_file()).cloned()
+                    .or_else(|| Some(candidates[0].clone()))
+            } else {
+                // Absolute import: only local modules count
+                candidates.into_iter().find(|c| c.is_file())
+            }
+        }
+
+        Language::C | Language::Cpp => {
+            if import_path.starts_with('<') {
+                None
+            } else {
+                Some(parent.join(import_path.trim_matches('"')))
+            }
+        }
+
+        Language::Go => {
+            if import_path.starts_with("./") || import_path.starts_with("../") {
+                Some(parent.join(import_path))
+            } else {
+                None
+            }
+        }
+
+        Language::Rust => {
+            // `crate::a::b::Item` -> try a/b/Item.rs, then a/b.rs, then a.rs
+            let (root, rest) = if let Some(rest) = import_path.strip_prefix("crate::") {
+                (crate_src_dir(current_file)?, rest)
+            } else if let Some(rest) = import_path.strip_prefix("super::") {
+                let is_mod_file = current_file.file_name().map(|n| n == "mod.rs").unwrap_or(false);
+                let root = if is_mod_file { parent.parent()? } else { parent };
+                (root.to_path_buf(), rest)
+            } else {
+                return None;
+            };
+
+            let segments: Vec<&str> = rest
+                .split("::")
+                .take_while(|s| !s.starts_with('{') && *s != "*")
+                .collect();
+
+            (1..=segments.len()).rev()
+                .map(|n| root.join(segments[..n].join("/")))
+                .flat_map(|p| [p.with_extension("rs"), p.join("mod.rs")])
+                .find(|p| p.is_file())
+        }
+
+        Language::CSharp => {
+            if import_path.starts_with("System") {
+                return None;
+            }
+            let cs_path = parent.join(import_path.replace('.', "/")).with_extension("cs");
+            cs_path.is_file().then_some(cs_path)
+        }
+
+        Language::Java => {
+            // `com.example.Foo` lives at `<source root>/com/example/Foo.java`;
+            // the source root is whichever ancestor contains that path
+            if import_path.ends_with(".*") {
+                return None;
+            }
+            let relative = PathBuf::from(import_path.replace('.', "/")).with_extension("java");
+            parent.ancestors()
+                .map(|dir| dir.join(&relative))
+                .find(|p| p.is_file())
+        }
+
+        Language::Unknown => None,
+    }
+}
+
+/// Find the `src` directory a Rust file belongs to
+fn crate_src_dir(file: &Path) -> Option<PathBuf> {
+    file.ancestors()
+        .find(|dir| dir.file_name().map(|n| n == "src").unwrap_or(false))
+        .map(Path::to_path_buf)
+}
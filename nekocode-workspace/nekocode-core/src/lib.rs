@@ -14,14 +14,16 @@ pub mod io;
 pub mod memory;
 pub mod traits;
 pub mod error;
+pub mod imports;
 
 // Re-exports for easy access
-pub use session::{SessionManager, SessionInfo, Session, SessionProvider};
+pub use session::{SessionManager, SessionInfo, Session, SessionProvider, SessionExport, EXPORT_SCHEMA_VERSION};
 pub use config::{Config, AnalysisConfig, GeneralConfig, MemoryConfig};
 pub use types::{Language, SymbolInfo, FunctionInfo, ClassInfo, FileInfo, AnalysisResult};
 pub use traits::{AnalysisProvider, LanguageSupport};
 pub use error::{NekocodeError, Result};
 pub use io::{FileProcessor, PathUtils};
+pub use imports::resolve_import_path;
 pub use memory::{MemoryManager, MemoryType};
 
 /// NekoCode Core version
@@ -77,6 +77,111 @@ impl SessionInfo {
         self.last_modified = Utc::now();
         self.is_dirty = true;
     }
+    
+    /// Replace, add (`index` is `None`) or remove (`result` is `None`) a single
+    /// analysis result, adjusting statistics for just that file instead of
+    /// recomputing them from all results
+    pub fn splice_result(&mut self, index: Option<usize>, result: Option<AnalysisResult>) {
+        if let Some(old) = index.map(|i| &self.analysis_results[i]) {
+            self.total_lines = self.total_lines.saturating_sub(old.metrics.lines_of_code);
+            let language = old.file_info.language;
+            if let Some(count) = self.languages.get_mut(&language) {
+                *count -= 1;
+                if *count == 0 {
+                    self.languages.remove(&language);
+                }
+            }
+            let path = old.file_info.path.clone();
+            self.file_hashes.remove(&path);
+        }
+        
+        if let Some(ref new) = result {
+            self.total_lines += new.metrics.lines_of_code;
+            *self.languages.entry(new.file_info.language).or_insert(0) += 1;
+            if let Some(ref hash) = new.file_info.hash {
+                self.file_hashes.insert(new.file_info.path.clone(), hash.clone());
+            }
+        }
+        
+        match (index, result) {
+            (Some(i), Some(new)) => self.analysis_results[i] = new,
+            (Some(i), None) => {
+                self.analysis_results.remove(i);
+            }
+            (None, Some(new)) => self.analysis_results.push(new),
+            (None, None) => return,
+        }
+        
+        self.file_count = self.analysis_results.len();
+        self.last_modified = Utc::now();
+        self.is_dirty = true;
+    }
+}
+
+/// Schema version written by `Export` and accepted by `Import`
+pub const EXPORT_SCHEMA_VERSION: u32 = 1;
+
+/// Portable snapshot of a session's analysis, as written by `Export`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionExport {
+    pub schema_version: u32,
+    pub nekocode_version: String,
+    pub path: PathBuf,
+    pub exported_at: DateTime<Utc>,
+    pub analysis_results: Vec<AnalysisResult>,
+}
+
+impl SessionExport {
+    /// Build an export snapshot from session info
+    pub fn from_info(info: &SessionInfo) -> Self {
+        Self {
+            schema_version: EXPORT_SCHEMA_VERSION,
+            nekocode_version: crate::VERSION.to_string(),
+            path: info.path.clone(),
+            exported_at: Utc::now(),
+            analysis_results: info.analysis_results.clone(),
+        }
+    }
+    
+    /// Parse an exported JSON document, rejecting incompatible schema versions
+    pub fn from_json(content: &str) -> Result<Self> {
+        let value: serde_json::Value = serde_json::from_str(content)?;
+        
+        let schema_version = value.get("schema_version")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| NekocodeError::Session(
+                "Export file has no schema_version; it was not produced by `nekocode export`".to_string()
+            ))?;
+        
+        if schema_version != EXPORT_SCHEMA_VERSION as u64 {
+            let producer = value.get("nekocode_version")
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown");
+            return Err(NekocodeError::Session(format!(
+                "Incompatible export schema version {} (written by nekocode {}); this build supports version {}",
+                schema_version, producer, EXPORT_SCHEMA_VERSION
+            )));
+        }
+        
+        Ok(serde_json::from_value(value)?)
+    }
+    
+    /// Turn the snapshot into fresh session info with the given ID
+    pub fn into_session_info(self, id: String) -> SessionInfo {
+        let mut info = SessionInfo::new(id, self.path);
+        
+        for result in &self.analysis_results {
+            if let Some(ref hash) = result.file_info.hash {
+                info.file_hashes.insert(result.file_info.path.clone(), hash.clone());
+            }
+        }
+        
+        info.analysis_results = self.analysis_results;
+        info.last_scan_time = Some(self.exported_at);
+        info.metadata.insert("imported_from_version".to_string(), self.nekocode_version);
+        info.update_stats();
+        info
+    }
 }
 
 /// Active session in memory
@@ -89,7 +194,7 @@ pub struct Session {
 impl Session {
     /// Create new session
     pub fn new(path: PathBuf) -> Result<Self> {
-        let id = Uuid::new_v4().to_string()[..8].to_string();
+        let id = SessionManager::generate_session_id();
         let info = SessionInfo::new(id, path);
         let session_dir = PathBuf::from(crate::SESSION_DIR);
         
@@ -213,11 +318,15 @@ pub struct SessionManager {
 impl SessionManager {
     /// Create new session manager
     pub fn new() -> Result<Self> {
-        let session_dir = PathBuf::from(crate::SESSION_DIR);
-        Ok(SessionManager {
+        Ok(Self::with_dir(PathBuf::from(crate::SESSION_DIR)))
+    }
+    
+    /// Session manager saving the sessions it creates in `session_dir`
+    pub fn with_dir(session_dir: PathBuf) -> Self {
+        SessionManager {
             sessions: HashMap::new(),
             session_dir,
-        })
+        }
     }
     
     /// Create new session
@@ -234,6 +343,24 @@ impl SessionManager {
         Ok(id)
     }
     
+    /// Persist externally built session info, replacing any session with the same ID
+    pub fn save_session_info(&mut self, info: SessionInfo) -> Result<String> {
+        let id = info.id.clone();
+        let mut session = Session {
+            info,
+            session_dir: self.session_dir.clone(),
+        };
+        session.save()?;
+        
+        self.sessions.insert(id.clone(), session);
+        Ok(id)
+    }
+    
+    /// Generate a new session ID
+    pub fn generate_session_id() -> String {
+        Uuid::new_v4().to_string()[..8].to_string()
+    }
+    
     /// Get session (load from disk if needed)
     pub fn get_session(&mut self, session_id: &str) -> Result<&Session> {
         // Load from disk if not in memory
@@ -346,4 +473,53 @@ pub trait SessionProvider {
     
     /// Save session to disk
     async fn save_session(&mut self, session_id: &str) -> Result<()>;
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FileInfo;
+    
+    fn result(path: &str, lines: u32, hash: &str) -> AnalysisResult {
+        let mut file_info = FileInfo::new(PathBuf::from(path));
+        file_info.hash = Some(hash.to_string());
+        let mut result = AnalysisResult::new(file_info);
+        result.metrics.lines_of_code = lines;
+        result
+    }
+    
+    #[test]
+    fn test_export_import_round_trip() {
+        let mut original = SessionInfo::new("a1b2c3d4".to_string(), PathBuf::from("project"));
+        original.splice_result(None, Some(result("src/app.py", 40, "h1")));
+        original.splice_result(None, Some(result("src/lib.rs", 25, "h2")));
+        
+        let json = serde_json::to_string(&SessionExport::from_info(&original)).unwrap();
+        let imported = SessionExport::from_json(&json).unwrap().into_session_info(original.id.clone());
+        
+        // Everything but timestamps and bookkeeping survives the trip
+        let comparable = |info: &SessionInfo| {
+            let mut value = serde_json::to_value(info).unwrap();
+            let fields = value.as_object_mut().unwrap();
+            for key in ["created_at", "last_accessed", "last_modified", "last_scan_time", "metadata", "is_dirty"] {
+                fields.remove(key);
+            }
+            value
+        };
+        assert_eq!(comparable(&imported), comparable(&original));
+        assert_eq!(imported.metadata["imported_from_version"], crate::VERSION);
+    }
+    
+    #[test]
+    fn test_import_rejects_unknown_schema_version() {
+        let info = SessionInfo::new("a1b2c3d4".to_string(), PathBuf::from("project"));
+        let mut export = serde_json::to_value(SessionExport::from_info(&info)).unwrap();
+        export["schema_version"] = serde_json::json!(EXPORT_SCHEMA_VERSION + 1);
+        
+        let error = SessionExport::from_json(&export.to_string()).unwrap_err().to_string();
+        assert!(error.contains("Incompatible export schema version"), "{}", error);
+        
+        export.as_object_mut().unwrap().remove("schema_version");
+        assert!(SessionExport::from_json(&export.to_string()).is_err());
+    }
+}
@@ -27,6 +27,8 @@ pub enum Language {
     Go,
     #[serde(rename = "rust")]
     Rust,
+    #[serde(rename = "java")]
+    Java,
     #[serde(rename = "unknown")]
     Unknown,
 }
@@ -42,6 +44,7 @@ impl Language {
             "cs" => Language::CSharp,
             "go" => Language::Go,
             "rs" => Language::Rust,
+            "java" => Language::Java,
             _ => Language::Unknown,
         }
     }
@@ -63,6 +66,7 @@ impl Language {
             Language::CSharp => &["cs"],
             Language::Go => &["go"],
             Language::Rust => &["rs"],
+            Language::Java => &["java"],
             Language::Unknown => &[],
         }
     }
@@ -77,6 +81,7 @@ impl Language {
             Language::CSharp => "C#",
             Language::Go => "Go",
             Language::Rust => "Rust",
+            Language::Java => "Java",
             Language::Unknown => "Unknown",
         }
     }
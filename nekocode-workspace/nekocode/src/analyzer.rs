@@ -9,10 +9,16 @@ use nekocode_core::{
     types::{
         AnalysisResult, FileInfo, FunctionInfo, ClassInfo,
         ImportInfo, ExportInfo, Language, CodeMetrics,
-        SymbolInfo, SymbolType, Visibility
+        SymbolInfo, SymbolType, Visibility, ParameterInfo
     }
 };
 
+use crate::complexity::{
+    cyclomatic_complexity, DecisionPoints,
+    RUST_DECISION_POINTS, CPP_DECISION_POINTS, GO_DECISION_POINTS, CSHARP_DECISION_POINTS,
+    JAVA_DECISION_POINTS
+};
+
 /// Analyzer configuration
 #[derive(Debug, Clone)]
 pub struct AnalyzerConfig {
@@ -57,6 +63,7 @@ pub trait Analyzer: Send + Sync {
                 Language::Cpp => matches!(ext, "cpp" | "cxx" | "cc" | "hpp" | "hxx" | "hh" | "c" | "h"),
                 Language::Go => matches!(ext, "go"),
                 Language::CSharp => matches!(ext, "cs"),
+                Language::Java => matches!(ext, "java"),
                 _ => false
             }
         } else {
@@ -65,6 +72,226 @@ pub trait Analyzer: Send + Sync {
     }
 }
 
+/// Create the analyzer for a language (C files are handled by the C++ analyzer)
+pub fn create_analyzer(language: Language) -> Result<Box<dyn Analyzer>> {
+    match language {
+        Language::JavaScript => Ok(Box::new(JavaScriptAnalyzer::new()?)),
+        Language::TypeScript => Ok(Box::new(TypeScriptAnalyzer::new()?)),
+        Language::Python => Ok(Box::new(PythonAnalyzer::new()?)),
+        Language::Rust => Ok(Box::new(RustAnalyzer::new()?)),
+        Language::Cpp | Language::C => Ok(Box::new(CppAnalyzer::new()?)),
+        Language::Go => Ok(Box::new(GoAnalyzer::new()?)),
+        Language::CSharp => Ok(Box::new(CSharpAnalyzer::new()?)),
+        Language::Java => Ok(Box::new(JavaAnalyzer::new()?)),
+        Language::Unknown => Err(NekocodeError::LanguageNotSupported(language.display_name().to_string())),
+    }
+}
+
+/// Get the Tree-sitter grammar for a language (C files use the C++ grammar)
+pub fn tree_sitter_language(language: Language) -> Option<tree_sitter::Language> {
+    match language {
+        Language::JavaScript => Some(tree_sitter_javascript::language()),
+        Language::TypeScript => Some(tree_sitter_typescript::language_typescript()),
+        Language::Python => Some(tree_sitter_python::language()),
+        Language::Rust => Some(tree_sitter_rust::language()),
+        Language::Cpp | Language::C => Some(tree_sitter_cpp::language()),
+        Language::Go => Some(tree_sitter_go::language()),
+        Language::CSharp => Some(tree_sitter_c_sharp::language()),
+        Language::Java => Some(tree_sitter_java::language()),
+        Language::Unknown => None,
+    }
+}
+
+/// Extract functions matched by `query_str` (captures: `@name`, `@function` or `@method`)
+/// and compute per-function cyclomatic complexity when `points` is given
+fn extract_functions_with_complexity(
+    tree: &Tree,
+    source: &str,
+    path: &Path,
+    ts_language: tree_sitter::Language,
+    query_str: &str,
+    language: Language,
+    points: Option<&DecisionPoints>,
+) -> Result<Vec<FunctionInfo>> {
+    let mut functions = Vec::new();
+    
+    let query = Query::new(ts_language, query_str)
+        .map_err(|e| NekocodeError::Analysis(format!("Query error: {}", e)))?;
+    
+    let mut cursor = QueryCursor::new();
+    let matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
+    
+    for mat in matches {
+        let mut name = String::new();
+        let mut func_node: Option<Node> = None;
+        let mut symbol_type = SymbolType::Function;
+        
+        for capture in mat.captures {
+            let capture_name = &query.capture_names()[capture.index as usize];
+            match capture_name.as_str() {
+                "name" => {
+                    if let Ok(text) = capture.node.utf8_text(source.as_bytes()) {
+                        name = text.to_string();
+                    }
+                }
+                "function" => {
+                    func_node = Some(capture.node);
+                }
+                "method" => {
+                    func_node = Some(capture.node);
+                    symbol_type = SymbolType::Method;
+                }
+                _ => {}
+            }
+        }
+        
+        let Some(node) = func_node else { continue };
+        if name.is_empty() {
+            continue;
+        }
+        
+        functions.push(function_info(node, name, symbol_type, path, language, source, points));
+    }
+    
+    Ok(functions)
+}
+
+/// A function defined by `node`, located and typed but without parameters or
+/// return type
+fn function_info(
+    node: Node,
+    name: String,
+    symbol_type: SymbolType,
+    path: &Path,
+    language: Language,
+    source: &str,
+    points: Option<&DecisionPoints>,
+) -> FunctionInfo {
+    let mut metadata = std::collections::HashMap::new();
+    let type_parameters = type_parameters(node, source);
+    if let Some(names) = &type_parameters {
+        metadata.insert("type_parameters".to_string(), names.join(","));
+    }
+    
+    FunctionInfo {
+        symbol: SymbolInfo {
+            id: String::new(),
+            name,
+            symbol_type,
+            file_path: path.to_path_buf(),
+            line_start: node.start_position().row as u32 + 1,
+            line_end: node.end_position().row as u32 + 1,
+            column_start: node.start_position().column as u32,
+            column_end: node.end_position().column as u32,
+            language,
+            visibility: None,
+            parent_id: None,
+            metadata,
+        },
+        parameters: Vec::new(),
+        return_type: None,
+        is_async: false,
+        is_static: false,
+        is_generic: type_parameters.is_some(),
+        complexity: points.map(|p| cyclomatic_complexity(node, p)),
+    }
+}
+
+/// Names declared by a function's type-parameter list (`fn f<'a, T: Clone>`,
+/// `function f<T>`, `void F<T>()`), or `None` when it has no such list
+fn type_parameters(function: Node, source: &str) -> Option<Vec<String>> {
+    let mut cursor = function.walk();
+    let list = function.child_by_field_name("type_parameters").or_else(|| {
+        function.children(&mut cursor).find(|child| child.kind() == "type_parameter_list")
+    })?;
+    
+    let mut cursor = list.walk();
+    let names = list.named_children(&mut cursor)
+        .filter_map(|param| type_parameter_name(param, source))
+        .collect();
+    Some(names)
+}
+
+/// `T`, `T: Clone`, `'a`, `const N: usize`, `in T` → the declared name
+fn type_parameter_name(param: Node, source: &str) -> Option<String> {
+    const NAME_KINDS: &[&str] = &["identifier", "type_identifier", "lifetime", "constrained_type_parameter"];
+    
+    if matches!(param.kind(), "identifier" | "type_identifier" | "lifetime") {
+        return param.utf8_text(source.as_bytes()).ok().map(str::to_string);
+    }
+    let mut cursor = param.walk();
+    let name = param.child_by_field_name("name").or_else(|| {
+        param.named_children(&mut cursor).find(|child| NAME_KINDS.contains(&child.kind()))
+    })?;
+    type_parameter_name(name, source)
+}
+
+/// Extract imports matched by `query_str` (captures: `@module`, `@import`).
+/// Quotes are stripped from string module specifiers; `<system>` includes are kept as-is.
+fn extract_imports(
+    tree: &Tree,
+    source: &str,
+    ts_language: tree_sitter::Language,
+    query_str: &str,
+) -> Result<Vec<ImportInfo>> {
+    let mut imports = Vec::new();
+    
+    let query = Query::new(ts_language, query_str)
+        .map_err(|e| NekocodeError::Analysis(format!("Query error: {}", e)))?;
+    
+    let mut cursor = QueryCursor::new();
+    let matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
+    
+    for mat in matches {
+        let mut module = String::new();
+        let mut line = 0;
+        
+        for capture in mat.captures {
+            let capture_name = &query.capture_names()[capture.index as usize];
+            match capture_name.as_str() {
+                "module" => {
+                    if let Ok(text) = capture.node.utf8_text(source.as_bytes()) {
+                        module = match capture.node.kind() {
+                            "string" | "string_literal" | "interpreted_string_literal" => {
+                                text.trim_matches(|c| c == '"' || c == '\'' || c == '`').to_string()
+                            }
+                            _ => text.to_string(),
+                        };
+                    }
+                }
+                "import" => {
+                    line = capture.node.start_position().row as u32 + 1;
+                }
+                _ => {}
+            }
+        }
+        
+        if module.is_empty() {
+            continue;
+        }
+        
+        imports.push(ImportInfo {
+            module,
+            imported_names: Vec::new(),
+            alias: None,
+            is_default: false,
+            is_namespace: false,
+            line,
+        });
+    }
+    
+    Ok(imports)
+}
+
+const JS_IMPORT_QUERY: &str = r#"
+    [
+      (import_statement
+        source: (string) @module) @import
+      (export_statement
+        source: (string) @module) @import
+    ]
+"#;
+
 /// JavaScript analyzer using Tree-sitter
 pub struct JavaScriptAnalyzer {
     parser: Parser,
@@ -139,6 +366,8 @@ impl JavaScriptAnalyzer {
                     "function" => {
                         func_info.symbol.line_start = capture.node.start_position().row as u32 + 1;
                         func_info.symbol.line_end = capture.node.end_position().row as u32 + 1;
+                        func_info.symbol.column_start = capture.node.start_position().column as u32;
+                        func_info.symbol.column_end = capture.node.end_position().column as u32;
                     }
                     _ => {}
                 }
@@ -201,6 +430,8 @@ impl JavaScriptAnalyzer {
                     "class" => {
                         class_info.symbol.line_start = capture.node.start_position().row as u32 + 1;
                         class_info.symbol.line_end = capture.node.end_position().row as u32 + 1;
+                        class_info.symbol.column_start = capture.node.start_position().column as u32;
+                        class_info.symbol.column_end = capture.node.end_position().column as u32;
                     }
                     _ => {}
                 }
@@ -245,6 +476,10 @@ impl Analyzer for JavaScriptAnalyzer {
             result.classes = self.extract_classes(&tree, content)?;
         }
         
+        if self.config.extract_imports {
+            result.imports = extract_imports(&tree, content, tree_sitter_javascript::language(), JS_IMPORT_QUERY)?;
+        }
+        
         Ok(result)
     }
     
@@ -253,6 +488,15 @@ impl Analyzer for JavaScriptAnalyzer {
     }
 }
 
+const TS_FUNCTION_QUERY: &str = r#"
+    [
+      (function_declaration
+        name: (identifier) @name) @function
+      (method_definition
+        name: (property_identifier) @name) @method
+    ]
+"#;
+
 /// TypeScript analyzer
 pub struct TypeScriptAnalyzer {
     parser: Parser,
@@ -294,6 +538,18 @@ impl Analyzer for TypeScriptAnalyzer {
         result.file_info.size_bytes = content.len() as u64;
         result.file_info.total_lines = content.lines().count() as u32;
         
+        if self.config.extract_functions {
+            result.functions = extract_functions_with_complexity(
+                &tree, content, path, tree_sitter_typescript::language_typescript(), TS_FUNCTION_QUERY, Language::TypeScript, None
+            )?;
+        }
+        
+        if self.config.extract_imports {
+            result.imports = extract_imports(
+                &tree, content, tree_sitter_typescript::language_typescript(), JS_IMPORT_QUERY
+            )?;
+        }
+        
         Ok(result)
     }
     
@@ -319,8 +575,220 @@ impl PythonAnalyzer {
             config: AnalyzerConfig::default(),
         })
     }
+    
+    fn extract_functions(&self, tree: &Tree, source: &str, path: &Path) -> Result<Vec<FunctionInfo>> {
+        let mut functions = Vec::new();
+        
+        let query_str = r#"
+            (function_definition
+              name: (identifier) @name) @function
+        "#;
+        
+        let query = Query::new(tree_sitter_python::language(), query_str)
+            .map_err(|e| NekocodeError::Analysis(format!("Query error: {}", e)))?;
+        
+        let mut cursor = QueryCursor::new();
+        let matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
+        
+        for mat in matches {
+            let mut name = String::new();
+            let mut func_node = None;
+            
+            for capture in mat.captures {
+                let capture_name = &query.capture_names()[capture.index as usize];
+                match capture_name.as_str() {
+                    "name" => {
+                        if let Ok(text) = capture.node.utf8_text(source.as_bytes()) {
+                            name = text.to_string();
+                        }
+                    }
+                    "function" => {
+                        func_node = Some(capture.node);
+                    }
+                    _ => {}
+                }
+            }
+            
+            let Some(node) = func_node else { continue };
+            
+            let decorators = Self::extract_decorators(node, source);
+            let is_static = decorators.iter()
+                .any(|d| matches!(d.as_str(), "@staticmethod" | "@classmethod"));
+            let symbol_type = if Self::enclosing_class(node).is_some() {
+                SymbolType::Method
+            } else {
+                SymbolType::Function
+            };
+            
+            let mut metadata = std::collections::HashMap::new();
+            if !decorators.is_empty() {
+                metadata.insert("decorators".to_string(), decorators.join(", "));
+            }
+            
+            let mut node_cursor = node.walk();
+            let is_async = node.children(&mut node_cursor).any(|child| child.kind() == "async");
+            
+            functions.push(FunctionInfo {
+                symbol: SymbolInfo {
+                    id: String::new(),
+                    name,
+                    symbol_type,
+                    file_path: path.to_path_buf(),
+                    line_start: node.start_position().row as u32 + 1,
+                    line_end: node.end_position().row as u32 + 1,
+                    column_start: node.start_position().column as u32,
+                    column_end: node.end_position().column as u32,
+                    language: Language::Python,
+                    visibility: Some(Visibility::Public),
+                    parent_id: None,
+                    metadata,
+                },
+                parameters: Vec::new(),
+                return_type: None,
+                is_async,
+                is_static,
+                is_generic: false,
+                complexity: None,
+            });
+        }
+        
+        Ok(functions)
+    }
+    
+    fn extract_classes(&self, tree: &Tree, source: &str, path: &Path) -> Result<Vec<ClassInfo>> {
+        let mut classes = Vec::new();
+        
+        let query_str = r#"
+            (class_definition
+              name: (identifier) @name) @class
+        "#;
+        
+        let query = Query::new(tree_sitter_python::language(), query_str)
+            .map_err(|e| NekocodeError::Analysis(format!("Query error: {}", e)))?;
+        
+        let mut cursor = QueryCursor::new();
+        let matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
+        
+        for mat in matches {
+            let mut name = String::new();
+            let mut class_node = None;
+            
+            for capture in mat.captures {
+                let capture_name = &query.capture_names()[capture.index as usize];
+                match capture_name.as_str() {
+                    "name" => {
+                        if let Ok(text) = capture.node.utf8_text(source.as_bytes()) {
+                            name = text.to_string();
+                        }
+                    }
+                    "class" => {
+                        class_node = Some(capture.node);
+                    }
+                    _ => {}
+                }
+            }
+            
+            let Some(node) = class_node else { continue };
+            
+            let mut metadata = std::collections::HashMap::new();
+            let decorators = Self::extract_decorators(node, source);
+            if !decorators.is_empty() {
+                metadata.insert("decorators".to_string(), decorators.join(", "));
+            }
+            
+            classes.push(ClassInfo {
+                symbol: SymbolInfo {
+                    id: String::new(),
+                    name,
+                    symbol_type: SymbolType::Class,
+                    file_path: path.to_path_buf(),
+                    line_start: node.start_position().row as u32 + 1,
+                    line_end: node.end_position().row as u32 + 1,
+                    column_start: node.start_position().column as u32,
+                    column_end: node.end_position().column as u32,
+                    language: Language::Python,
+                    visibility: Some(Visibility::Public),
+                    parent_id: None,
+                    metadata,
+                },
+                base_classes: Self::extract_base_classes(node, source),
+                interfaces: Vec::new(),
+                methods: Vec::new(),
+                fields: Vec::new(),
+                is_abstract: false,
+                is_interface: false,
+            });
+        }
+        
+        Ok(classes)
+    }
+    
+    /// Read base classes from the `superclasses` argument list, skipping keyword
+    /// arguments such as `metaclass=ABCMeta`
+    fn extract_base_classes(class_node: Node, source: &str) -> Vec<String> {
+        let mut base_classes = Vec::new();
+        
+        if let Some(superclasses) = class_node.child_by_field_name("superclasses") {
+            let mut cursor = superclasses.walk();
+            for child in superclasses.named_children(&mut cursor) {
+                if child.kind() == "keyword_argument" || child.kind() == "comment" {
+                    continue;
+                }
+                if let Ok(text) = child.utf8_text(source.as_bytes()) {
+                    base_classes.push(text.to_string());
+                }
+            }
+        }
+        
+        base_classes
+    }
+    
+    /// Collect `decorator` siblings when the definition is wrapped in a `decorated_definition`
+    fn extract_decorators(node: Node, source: &str) -> Vec<String> {
+        let mut decorators = Vec::new();
+        
+        if let Some(parent) = node.parent() {
+            if parent.kind() == "decorated_definition" {
+                let mut cursor = parent.walk();
+                for child in parent.children(&mut cursor) {
+                    if child.kind() == "decorator" {
+                        if let Ok(text) = child.utf8_text(source.as_bytes()) {
+                            decorators.push(text.trim().to_string());
+                        }
+                    }
+                }
+            }
+        }
+        
+        decorators
+    }
+    
+    /// Find the class whose body directly contains this function definition
+    fn enclosing_class(node: Node) -> Option<Node> {
+        let mut current = node.parent()?;
+        if current.kind() == "decorated_definition" {
+            current = current.parent()?;
+        }
+        if current.kind() != "block" {
+            return None;
+        }
+        let class_node = current.parent()?;
+        (class_node.kind() == "class_definition").then_some(class_node)
+    }
 }
 
+const PYTHON_IMPORT_QUERY: &str = r#"
+    [
+      (import_statement
+        name: (dotted_name) @module) @import
+      (import_statement
+        name: (aliased_import
+          name: (dotted_name) @module)) @import
+      (import_from_statement
+        module_name: (_) @module) @import
+    ]
+"#;
+
 #[async_trait]
 impl Analyzer for PythonAnalyzer {
     async fn analyze(&mut self, path: &Path, content: &str) -> Result<AnalysisResult> {
@@ -343,6 +811,18 @@ impl Analyzer for PythonAnalyzer {
         result.file_info.size_bytes = content.len() as u64;
         result.file_info.total_lines = content.lines().count() as u32;
         
+        if self.config.extract_functions {
+            result.functions = self.extract_functions(&tree, content, path)?;
+        }
+        
+        if self.config.extract_classes {
+            result.classes = self.extract_classes(&tree, content, path)?;
+        }
+        
+        if self.config.extract_imports {
+            result.imports = extract_imports(&tree, content, tree_sitter_python::language(), PYTHON_IMPORT_QUERY)?;
+        }
+        
         Ok(result)
     }
     
@@ -351,6 +831,16 @@ impl Analyzer for PythonAnalyzer {
     }
 }
 
+const RUST_IMPORT_QUERY: &str = r#"
+    (use_declaration
+      argument: (_) @module) @import
+"#;
+
+const RUST_FUNCTION_QUERY: &str = r#"
+    (function_item
+      name: (identifier) @name) @function
+"#;
+
 /// Rust analyzer
 pub struct RustAnalyzer {
     parser: Parser,
@@ -392,6 +882,22 @@ impl Analyzer for RustAnalyzer {
         result.file_info.size_bytes = content.len() as u64;
         result.file_info.total_lines = content.lines().count() as u32;
         
+        let points = self.config.calculate_complexity.then_some(&RUST_DECISION_POINTS);
+        
+        if self.config.extract_functions {
+            result.functions = extract_functions_with_complexity(
+                &tree, content, path, tree_sitter_rust::language(), RUST_FUNCTION_QUERY, Language::Rust, points
+            )?;
+        }
+        
+        if self.config.extract_imports {
+            result.imports = extract_imports(&tree, content, tree_sitter_rust::language(), RUST_IMPORT_QUERY)?;
+        }
+        
+        if let Some(points) = points {
+            result.metrics.cyclomatic_complexity = Some(cyclomatic_complexity(tree.root_node(), points));
+        }
+        
         Ok(result)
     }
     
@@ -400,6 +906,15 @@ impl Analyzer for RustAnalyzer {
     }
 }
 
+const CPP_IMPORT_QUERY: &str = r#"
+    (preproc_include
+      path: (_) @module) @import
+"#;
+
+const CPP_FUNCTION_QUERY: &str = r#"
+    (function_definition) @function
+"#;
+
 /// C++ analyzer
 pub struct CppAnalyzer {
     parser: Parser,
@@ -417,6 +932,209 @@ impl CppAnalyzer {
             config: AnalyzerConfig::default(),
         })
     }
+    
+    /// Extract function definitions with their scope path (`ns::Class::method`),
+    /// parameters and return type
+    fn extract_functions(&self, tree: &Tree, source: &str, path: &Path, points: Option<&DecisionPoints>) -> Result<Vec<FunctionInfo>> {
+        let mut functions = Vec::new();
+        let namespaces = Self::namespace_names(tree.root_node(), source);
+        let query = Query::new(tree_sitter_cpp::language(), CPP_FUNCTION_QUERY)
+            .map_err(|e| NekocodeError::Analysis(format!("Query error: {}", e)))?;
+        
+        let mut cursor = QueryCursor::new();
+        for mat in cursor.matches(&query, tree.root_node(), source.as_bytes()) {
+            let node = mat.captures[0].node;
+            let Some(declarator) = Self::function_declarator(node) else {
+                continue;
+            };
+            let Some(name_text) = declarator.child_by_field_name("declarator")
+                .and_then(|n| n.utf8_text(source.as_bytes()).ok()) else {
+                continue;
+            };
+            
+            // `void Foo::bar()` is attributed to `Foo` unless `Foo` is a namespace
+            let mut qualifiers = split_cpp_qualified_name(name_text);
+            let Some(name) = qualifiers.pop() else {
+                continue;
+            };
+            let enclosing = Self::enclosing_scopes(node, source);
+            
+            let class = qualifiers.last()
+                .filter(|q| !namespaces.contains(*q))
+                .cloned()
+                .or_else(|| enclosing.iter().rev().find(|(_, is_class)| *is_class).map(|(n, _)| n.clone()));
+            
+            let mut scope_path: Vec<String> = enclosing.into_iter().map(|(n, _)| n).collect();
+            scope_path.extend(qualifiers);
+            scope_path.push(name.clone());
+            
+            let mut function = function_info(node, name, SymbolType::Function, path, Language::Cpp, source, points);
+            function.symbol.metadata.insert("scope_path".to_string(), scope_path.join("::"));
+            if let Some(class) = class {
+                function.symbol.symbol_type = SymbolType::Method;
+                function.symbol.metadata.insert("class".to_string(), class);
+            }
+            
+            function.return_type = node.child_by_field_name("type")
+                .and_then(|t| t.utf8_text(source.as_bytes()).ok())
+                .map(str::to_string);
+            let mut node_cursor = node.walk();
+            function.is_static = node.children(&mut node_cursor)
+                .any(|c| c.kind() == "storage_class_specifier" && c.utf8_text(source.as_bytes()) == Ok("static"));
+            function.is_generic = node.parent().is_some_and(|p| p.kind() == "template_declaration");
+            function.parameters = declarator.child_by_field_name("parameters")
+                .map(|params| Self::extract_parameters(params, source))
+                .unwrap_or_default();
+            
+            functions.push(function);
+        }
+        
+        Ok(functions)
+    }
+    
+    /// The `function_declarator` of a definition, below any pointer or
+    /// reference declarators of the return type
+    fn function_declarator(node: Node) -> Option<Node> {
+        let mut declarator = node.child_by_field_name("declarator")?;
+        while declarator.kind() != "function_declarator" {
+            // `&` and `&&` declarators leave their inner declarator unnamed
+            declarator = declarator.child_by_field_name("declarator")
+                .or_else(|| declarator.named_child(declarator.named_child_count().checked_sub(1)?))?;
+        }
+        Some(declarator)
+    }
+    
+    /// Namespaces and classes enclosing `node`, outermost first; the flag marks classes
+    fn enclosing_scopes(node: Node, source: &str) -> Vec<(String, bool)> {
+        let mut scopes = Vec::new();
+        let mut current = node.parent();
+        
+        while let Some(parent) = current {
+            let is_class = matches!(parent.kind(), "class_specifier" | "struct_specifier");
+            if is_class || parent.kind() == "namespace_definition" {
+                if let Some(name) = parent.child_by_field_name("name").and_then(|n| n.utf8_text(source.as_bytes()).ok()) {
+                    // `namespace a::b {}` contributes two segments
+                    for segment in split_cpp_qualified_name(name).into_iter().rev() {
+                        scopes.push((segment, is_class));
+                    }
+                }
+            }
+            current = parent.parent();
+        }
+        
+        scopes.reverse();
+        scopes
+    }
+    
+    /// Names of every namespace declared in the file
+    fn namespace_names(root: Node, source: &str) -> std::collections::HashSet<String> {
+        let mut names = std::collections::HashSet::new();
+        let mut stack = vec![root];
+        
+        while let Some(node) = stack.pop() {
+            if node.kind() == "namespace_definition" {
+                if let Some(name) = node.child_by_field_name("name").and_then(|n| n.utf8_text(source.as_bytes()).ok()) {
+                    names.extend(split_cpp_qualified_name(name));
+                }
+            }
+            let mut cursor = node.walk();
+            stack.extend(node.children(&mut cursor));
+        }
+        
+        names
+    }
+    
+    /// Parse a `parameter_list` into typed parameters
+    fn extract_parameters(params: Node, source: &str) -> Vec<ParameterInfo> {
+        let mut parameters = Vec::new();
+        let mut cursor = params.walk();
+        
+        for param in params.named_children(&mut cursor) {
+            let text = |n: Node| n.utf8_text(source.as_bytes()).unwrap_or("").to_string();
+            
+            match param.kind() {
+                "parameter_declaration" | "optional_parameter_declaration" | "variadic_parameter_declaration" => {
+                    let declarator = param.child_by_field_name("declarator");
+                    let name_node = declarator.and_then(Self::declarator_identifier);
+                    let default_value = param.child_by_field_name("default_value").map(text);
+                    
+                    // The type is everything around the name, e.g. `const std::string&` or `int[4]`
+                    let type_end = declarator.map_or(param.end_byte(), |d| d.end_byte());
+                    let param_type = match name_node {
+                        Some(name) => format!(
+                            "{}{}",
+                            source[param.start_byte()..name.start_byte()].trim(),
+                            source[name.end_byte()..type_end].trim()
+                        ),
+                        None => source[param.start_byte()..type_end].trim().to_string(),
+                    };
+                    if param_type == "void" && name_node.is_none() {
+                        continue;
+                    }
+                    
+                    parameters.push(ParameterInfo {
+                        name: name_node.map(text).unwrap_or_default(),
+                        param_type: Some(param_type),
+                        is_optional: default_value.is_some(),
+                        default_value,
+                        is_variadic: param.kind() == "variadic_parameter_declaration",
+                    });
+                }
+                _ => {}
+            }
+        }
+        
+        // A C-style `...` is an unnamed token rather than a declaration
+        let mut cursor = params.walk();
+        if params.children(&mut cursor).any(|c| c.kind() == "...") {
+            parameters.push(ParameterInfo {
+                name: "...".to_string(),
+                param_type: None,
+                default_value: None,
+                is_optional: true,
+                is_variadic: true,
+            });
+        }
+        
+        parameters
+    }
+    
+    /// Find the identifier inside a (possibly pointer/reference/array) declarator
+    fn declarator_identifier(node: Node) -> Option<Node> {
+        match node.kind() {
+            "identifier" => Some(node),
+            _ => node.child_by_field_name("declarator").or_else(|| {
+                let mut cursor = node.walk();
+                let child = node.named_children(&mut cursor).find(|c| c.kind() == "identifier" || c.kind().ends_with("_declarator"));
+                child
+            }).and_then(Self::declarator_identifier),
+        }
+    }
+}
+
+/// Split `ns::Foo<T>::bar` into `["ns", "Foo<T>", "bar"]`, ignoring `::` inside template arguments
+fn split_cpp_qualified_name(name: &str) -> Vec<String> {
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0;
+    let mut chars = name.chars().peekable();
+    
+    while let Some(c) = chars.next() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth -= 1,
+            ':' if depth == 0 && chars.peek() == Some(&':') => {
+                chars.next();
+                segments.push(std::mem::take(&mut current).trim().to_string());
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    segments.push(current.trim().to_string());
+    segments.retain(|s| !s.is_empty());
+    segments
 }
 
 #[async_trait]
@@ -441,6 +1159,20 @@ impl Analyzer for CppAnalyzer {
         result.file_info.size_bytes = content.len() as u64;
         result.file_info.total_lines = content.lines().count() as u32;
         
+        let points = self.config.calculate_complexity.then_some(&CPP_DECISION_POINTS);
+        
+        if self.config.extract_functions {
+            result.functions = self.extract_functions(&tree, content, path, points)?;
+        }
+        
+        if self.config.extract_imports {
+            result.imports = extract_imports(&tree, content, tree_sitter_cpp::language(), CPP_IMPORT_QUERY)?;
+        }
+        
+        if let Some(points) = points {
+            result.metrics.cyclomatic_complexity = Some(cyclomatic_complexity(tree.root_node(), points));
+        }
+        
         Ok(result)
     }
     
@@ -449,6 +1181,20 @@ impl Analyzer for CppAnalyzer {
     }
 }
 
+const GO_IMPORT_QUERY: &str = r#"
+    (import_spec
+      path: (interpreted_string_literal) @module) @import
+"#;
+
+const GO_FUNCTION_QUERY: &str = r#"
+    [
+      (function_declaration
+        name: (identifier) @name) @function
+      (method_declaration
+        name: (field_identifier) @name) @method
+    ]
+"#;
+
 /// Go analyzer
 pub struct GoAnalyzer {
     parser: Parser,
@@ -490,6 +1236,22 @@ impl Analyzer for GoAnalyzer {
         result.file_info.size_bytes = content.len() as u64;
         result.file_info.total_lines = content.lines().count() as u32;
         
+        let points = self.config.calculate_complexity.then_some(&GO_DECISION_POINTS);
+        
+        if self.config.extract_functions {
+            result.functions = extract_functions_with_complexity(
+                &tree, content, path, tree_sitter_go::language(), GO_FUNCTION_QUERY, Language::Go, points
+            )?;
+        }
+        
+        if self.config.extract_imports {
+            result.imports = extract_imports(&tree, content, tree_sitter_go::language(), GO_IMPORT_QUERY)?;
+        }
+        
+        if let Some(points) = points {
+            result.metrics.cyclomatic_complexity = Some(cyclomatic_complexity(tree.root_node(), points));
+        }
+        
         Ok(result)
     }
     
@@ -498,6 +1260,17 @@ impl Analyzer for GoAnalyzer {
     }
 }
 
+const CSHARP_FUNCTION_QUERY: &str = r#"
+    [
+      (method_declaration
+        name: (identifier) @name) @method
+      (constructor_declaration
+        name: (identifier) @name) @method
+      (local_function_statement
+        name: (identifier) @name) @function
+    ]
+"#;
+
 /// C# analyzer
 pub struct CSharpAnalyzer {
     parser: Parser,
@@ -539,10 +1312,686 @@ impl Analyzer for CSharpAnalyzer {
         result.file_info.size_bytes = content.len() as u64;
         result.file_info.total_lines = content.lines().count() as u32;
         
+        let points = self.config.calculate_complexity.then_some(&CSHARP_DECISION_POINTS);
+        
+        if self.config.extract_functions {
+            result.functions = extract_functions_with_complexity(
+                &tree, content, path, tree_sitter_c_sharp::language(), CSHARP_FUNCTION_QUERY, Language::CSharp, points
+            )?;
+        }
+        
+        if let Some(points) = points {
+            result.metrics.cyclomatic_complexity = Some(cyclomatic_complexity(tree.root_node(), points));
+        }
+        
         Ok(result)
     }
     
     fn language(&self) -> Language {
         Language::CSharp
     }
-}
\ No newline at end of file
+}
+
+const JAVA_FUNCTION_QUERY: &str = r#"
+    [
+      (method_declaration
+        name: (identifier) @name) @method
+      (constructor_declaration
+        name: (identifier) @name) @method
+    ]
+"#;
+
+const JAVA_CLASS_QUERY: &str = r#"
+    [
+      (class_declaration
+        name: (identifier) @name) @class
+      (interface_declaration
+        name: (identifier) @name) @class
+      (enum_declaration
+        name: (identifier) @name) @class
+      (record_declaration
+        name: (identifier) @name) @class
+    ]
+"#;
+
+/// Java analyzer
+pub struct JavaAnalyzer {
+    parser: Parser,
+    config: AnalyzerConfig,
+}
+
+impl JavaAnalyzer {
+    pub fn new() -> Result<Self> {
+        let mut parser = Parser::new();
+        parser.set_language(tree_sitter_java::language())
+            .map_err(|e| NekocodeError::Analysis(format!("Failed to set Java language: {:?}", e)))?;
+        
+        Ok(Self {
+            parser,
+            config: AnalyzerConfig::default(),
+        })
+    }
+    
+    /// Extract methods and constructors with their scope path (`package::Class::method`),
+    /// parameters and return type
+    fn extract_functions(&self, tree: &Tree, source: &str, path: &Path, points: Option<&DecisionPoints>) -> Result<Vec<FunctionInfo>> {
+        let mut functions = extract_functions_with_complexity(
+            tree, source, path, tree_sitter_java::language(), JAVA_FUNCTION_QUERY, Language::Java, points
+        )?;
+        
+        let package = Self::package_name(tree.root_node(), source);
+        let query = Query::new(tree_sitter_java::language(), JAVA_FUNCTION_QUERY)
+            .map_err(|e| NekocodeError::Analysis(format!("Query error: {}", e)))?;
+        let method_index = query.capture_index_for_name("method").unwrap_or(0);
+        
+        let mut cursor = QueryCursor::new();
+        let nodes = cursor.matches(&query, tree.root_node(), source.as_bytes())
+            .filter_map(|mat| mat.captures.iter().find(|c| c.index == method_index).map(|c| c.node));
+        
+        for (function, node) in functions.iter_mut().zip(nodes) {
+            let classes = Self::enclosing_classes(node, source);
+            let mut scope_path: Vec<String> = package.iter().cloned().chain(classes.iter().cloned()).collect();
+            scope_path.push(function.symbol.name.clone());
+            
+            function.symbol.metadata.insert("scope_path".to_string(), scope_path.join("::"));
+            if let Some(class) = classes.last() {
+                function.symbol.metadata.insert("class".to_string(), class.clone());
+            }
+            if node.kind() == "constructor_declaration" {
+                function.symbol.metadata.insert("is_constructor".to_string(), "true".to_string());
+            }
+            
+            let modifiers = Self::modifiers(node, source);
+            function.symbol.visibility = Some(Self::visibility(&modifiers));
+            function.is_static = modifiers.iter().any(|m| m == "static");
+            function.return_type = node.child_by_field_name("type")
+                .and_then(|t| t.utf8_text(source.as_bytes()).ok())
+                .map(str::to_string);
+            function.parameters = node.child_by_field_name("parameters")
+                .map(|params| Self::extract_parameters(params, source))
+                .unwrap_or_default();
+        }
+        
+        Ok(functions)
+    }
+    
+    /// Extract classes, interfaces, enums and records
+    fn extract_classes(&self, tree: &Tree, source: &str, path: &Path) -> Result<Vec<ClassInfo>> {
+        let mut classes = Vec::new();
+        let package = Self::package_name(tree.root_node(), source);
+        
+        let query = Query::new(tree_sitter_java::language(), JAVA_CLASS_QUERY)
+            .map_err(|e| NekocodeError::Analysis(format!("Query error: {}", e)))?;
+        
+        let mut cursor = QueryCursor::new();
+        let matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
+        
+        for mat in matches {
+            let mut name = String::new();
+            let mut class_node = None;
+            
+            for capture in mat.captures {
+                let capture_name = &query.capture_names()[capture.index as usize];
+                match capture_name.as_str() {
+                    "name" => {
+                        if let Ok(text) = capture.node.utf8_text(source.as_bytes()) {
+                            name = text.to_string();
+                        }
+                    }
+                    "class" => {
+                        class_node = Some(capture.node);
+                    }
+                    _ => {}
+                }
+            }
+            
+            let Some(node) = class_node else { continue };
+            
+            let mut scope_path: Vec<String> = package.iter().cloned()
+                .chain(Self::enclosing_classes(node, source))
+                .collect();
+            scope_path.push(name.clone());
+            let mut metadata = std::collections::HashMap::new();
+            metadata.insert("scope_path".to_string(), scope_path.join("::"));
+            
+            let symbol_type = match node.kind() {
+                "interface_declaration" => SymbolType::Interface,
+                "enum_declaration" => SymbolType::Enum,
+                _ => SymbolType::Class,
+            };
+            let modifiers = Self::modifiers(node, source);
+            
+            // `extends` on a class is the base class; on an interface it lists parent interfaces
+            let mut base_classes = Vec::new();
+            let mut interfaces = Vec::new();
+            if let Some(superclass) = node.child_by_field_name("superclass") {
+                base_classes.extend(superclass.named_child(0)
+                    .and_then(|t| t.utf8_text(source.as_bytes()).ok())
+                    .map(str::to_string));
+            }
+            let mut node_cursor = node.walk();
+            for child in node.children(&mut node_cursor) {
+                if matches!(child.kind(), "super_interfaces" | "extends_interfaces") {
+                    interfaces.extend(Self::type_list(child, source));
+                }
+            }
+            
+            classes.push(ClassInfo {
+                symbol: SymbolInfo {
+                    id: String::new(),
+                    name,
+                    symbol_type,
+                    file_path: path.to_path_buf(),
+                    line_start: node.start_position().row as u32 + 1,
+                    line_end: node.end_position().row as u32 + 1,
+                    column_start: node.start_position().column as u32,
+                    column_end: node.end_position().column as u32,
+                    language: Language::Java,
+                    visibility: Some(Self::visibility(&modifiers)),
+                    parent_id: None,
+                    metadata,
+                },
+                base_classes,
+                interfaces,
+                methods: Vec::new(),
+                fields: Vec::new(),
+                is_abstract: modifiers.iter().any(|m| m == "abstract"),
+                is_interface: symbol_type == SymbolType::Interface,
+            });
+        }
+        
+        Ok(classes)
+    }
+    
+    /// Extract `import` declarations; wildcard imports keep their `.*` suffix
+    fn extract_imports(&self, tree: &Tree, source: &str) -> Vec<ImportInfo> {
+        let mut imports = Vec::new();
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        
+        for node in root.children(&mut cursor) {
+            if node.kind() != "import_declaration" {
+                continue;
+            }
+            
+            let mut module = String::new();
+            let mut is_wildcard = false;
+            let mut child_cursor = node.walk();
+            for child in node.children(&mut child_cursor) {
+                match child.kind() {
+                    "identifier" | "scoped_identifier" => {
+                        module = child.utf8_text(source.as_bytes()).unwrap_or("").to_string();
+                    }
+                    "asterisk" => is_wildcard = true,
+                    _ => {}
+                }
+            }
+            if module.is_empty() {
+                continue;
+            }
+            
+            let imported_names = match module.rsplit_once('.') {
+                Some((_, name)) if !is_wildcard => vec![name.to_string()],
+                _ => Vec::new(),
+            };
+            if is_wildcard {
+                module.push_str(".*");
+            }
+            
+            imports.push(ImportInfo {
+                module,
+                imported_names,
+                alias: None,
+                is_default: false,
+                is_namespace: is_wildcard,
+                line: node.start_position().row as u32 + 1,
+            });
+        }
+        
+        imports
+    }
+    
+    /// Name from the file's `package` declaration
+    fn package_name(root: Node, source: &str) -> Option<String> {
+        let mut cursor = root.walk();
+        let package = root.children(&mut cursor).find(|c| c.kind() == "package_declaration")?;
+        let mut package_cursor = package.walk();
+        let name = package.named_children(&mut package_cursor)
+            .find(|c| matches!(c.kind(), "identifier" | "scoped_identifier"))?;
+        name.utf8_text(source.as_bytes()).ok().map(str::to_string)
+    }
+    
+    /// Names of the type declarations enclosing `node`, outermost first
+    fn enclosing_classes(node: Node, source: &str) -> Vec<String> {
+        let mut classes = Vec::new();
+        let mut current = node.parent();
+        
+        while let Some(parent) = current {
+            if matches!(parent.kind(), "class_declaration" | "interface_declaration" | "enum_declaration" | "record_declaration") {
+                if let Some(name) = parent.child_by_field_name("name").and_then(|n| n.utf8_text(source.as_bytes()).ok()) {
+                    classes.push(name.to_string());
+                }
+            }
+            current = parent.parent();
+        }
+        
+        classes.reverse();
+        classes
+    }
+    
+    /// Keyword modifiers (`public`, `static`, ...) of a declaration, without annotations
+    fn modifiers(node: Node, source: &str) -> Vec<String> {
+        let mut cursor = node.walk();
+        let Some(modifiers) = node.children(&mut cursor).find(|c| c.kind() == "modifiers") else {
+            return Vec::new();
+        };
+        
+        let mut modifier_cursor = modifiers.walk();
+        let keywords = modifiers.children(&mut modifier_cursor)
+            .filter(|c| !matches!(c.kind(), "annotation" | "marker_annotation"))
+            .filter_map(|c| c.utf8_text(source.as_bytes()).ok())
+            .map(str::to_string)
+            .collect();
+        keywords
+    }
+    
+    /// Java members without an access modifier are package-private
+    fn visibility(modifiers: &[String]) -> Visibility {
+        if modifiers.iter().any(|m| m == "public") {
+            Visibility::Public
+        } else if modifiers.iter().any(|m| m == "protected") {
+            Visibility::Protected
+        } else if modifiers.iter().any(|m| m == "private") {
+            Visibility::Private
+        } else {
+            Visibility::Package
+        }
+    }
+    
+    /// Parse `formal_parameters` into typed parameters
+    fn extract_parameters(params: Node, source: &str) -> Vec<ParameterInfo> {
+        let mut parameters = Vec::new();
+        let mut cursor = params.walk();
+        let text = |n: Node| n.utf8_text(source.as_bytes()).unwrap_or("").to_string();
+        
+        for param in params.named_children(&mut cursor) {
+            match param.kind() {
+                "formal_parameter" => {
+                    parameters.push(ParameterInfo {
+                        name: param.child_by_field_name("name").map(text).unwrap_or_default(),
+                        param_type: param.child_by_field_name("type").map(text),
+                        default_value: None,
+                        is_optional: false,
+                        is_variadic: false,
+                    });
+                }
+                // `String... args`: the type is a plain child, the name sits in a declarator
+                "spread_parameter" => {
+                    let mut param_cursor = param.walk();
+                    let children: Vec<Node> = param.named_children(&mut param_cursor).collect();
+                    let name = children.iter()
+                        .find(|c| c.kind() == "variable_declarator")
+                        .and_then(|d| d.child_by_field_name("name"))
+                        .map(text)
+                        .unwrap_or_default();
+                    let param_type = children.iter()
+                        .find(|c| !matches!(c.kind(), "modifiers" | "variable_declarator"))
+                        .map(|t| format!("{}...", text(*t)));
+                    
+                    parameters.push(ParameterInfo {
+                        name,
+                        param_type,
+                        default_value: None,
+                        is_optional: true,
+                        is_variadic: true,
+                    });
+                }
+                _ => {}
+            }
+        }
+        
+        parameters
+    }
+    
+    /// Type names listed in an `implements` / `extends` clause
+    fn type_list(node: Node, source: &str) -> Vec<String> {
+        let mut names = Vec::new();
+        let mut cursor = node.walk();
+        for list in node.named_children(&mut cursor) {
+            let mut list_cursor = list.walk();
+            names.extend(list.named_children(&mut list_cursor)
+                .filter_map(|t| t.utf8_text(source.as_bytes()).ok())
+                .map(str::to_string));
+        }
+        names
+    }
+}
+
+#[async_trait]
+impl Analyzer for JavaAnalyzer {
+    async fn analyze(&mut self, path: &Path, content: &str) -> Result<AnalysisResult> {
+        let tree = self.parser.parse(content, None)
+            .ok_or_else(|| NekocodeError::Analysis("Failed to parse Java".to_string()))?;
+        
+        let mut result = AnalysisResult {
+            file_info: FileInfo::new(path.to_path_buf()),
+            symbols: Vec::new(),
+            functions: Vec::new(),
+            classes: Vec::new(),
+            imports: Vec::new(),
+            exports: Vec::new(),
+            dependencies: Vec::new(),
+            metrics: CodeMetrics::default(),
+            errors: Vec::new(),
+        };
+        
+        // Update file info with content data
+        result.file_info.size_bytes = content.len() as u64;
+        result.file_info.total_lines = content.lines().count() as u32;
+        
+        let points = self.config.calculate_complexity.then_some(&JAVA_DECISION_POINTS);
+        
+        if self.config.extract_functions {
+            result.functions = self.extract_functions(&tree, content, path, points)?;
+        }
+        
+        if self.config.extract_classes {
+            result.classes = self.extract_classes(&tree, content, path)?;
+        }
+        
+        if self.config.extract_imports {
+            result.imports = self.extract_imports(&tree, content);
+        }
+        
+        if let Some(points) = points {
+            result.metrics.cyclomatic_complexity = Some(cyclomatic_complexity(tree.root_node(), points));
+        }
+        
+        Ok(result)
+    }
+    
+    fn language(&self) -> Language {
+        Language::Java
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[tokio::test]
+    async fn test_rust_function_complexity() {
+        let source = r#"
+fn classify(a: i32, b: i32, c: i32) -> i32 {
+    if a > 0 { return 1; }
+    if b > 0 { return 2; }
+    if c > 0 { return 3; }
+    0
+}
+
+fn identity(x: i32) -> i32 {
+    x
+}
+"#;
+        let mut analyzer = RustAnalyzer::new().unwrap();
+        let result = analyzer.analyze(Path::new("lib.rs"), source).await.unwrap();
+        
+        assert_eq!(result.functions.len(), 2);
+        assert_eq!(result.functions[0].symbol.name, "classify");
+        assert_eq!(result.functions[0].complexity, Some(4));
+        assert_eq!(result.functions[1].complexity, Some(1));
+        assert_eq!(result.metrics.cyclomatic_complexity, Some(4));
+    }
+    
+    #[tokio::test]
+    async fn test_go_method_complexity() {
+        let source = r#"
+package main
+
+func (s *Server) Handle(ok bool, retry bool) {
+	for i := 0; i < 3; i++ {
+		if ok && retry {
+			return
+		}
+	}
+}
+"#;
+        let mut analyzer = GoAnalyzer::new().unwrap();
+        let result = analyzer.analyze(Path::new("server.go"), source).await.unwrap();
+        
+        assert_eq!(result.functions.len(), 1);
+        assert_eq!(result.functions[0].symbol.symbol_type, SymbolType::Method);
+        assert_eq!(result.functions[0].complexity, Some(4));
+    }
+    
+    #[tokio::test]
+    async fn test_python_base_classes_and_decorators() {
+        let source = r#"
+@dataclass
+class Dog(Animal, Mixin, metaclass=Meta):
+    @staticmethod
+    def create():
+        pass
+
+    @property
+    def name(self):
+        return "dog"
+
+    async def bark(self):
+        pass
+"#;
+        let mut analyzer = PythonAnalyzer::new().unwrap();
+        let result = analyzer.analyze(Path::new("dog.py"), source).await.unwrap();
+        
+        assert_eq!(result.classes.len(), 1);
+        let dog = &result.classes[0];
+        assert_eq!(dog.symbol.name, "Dog");
+        assert_eq!(dog.base_classes, vec!["Animal".to_string(), "Mixin".to_string()]);
+        assert_eq!(dog.symbol.metadata.get("decorators").map(String::as_str), Some("@dataclass"));
+        
+        let function = |name: &str| result.functions.iter().find(|f| f.symbol.name == name).unwrap();
+        assert!(function("create").is_static);
+        assert_eq!(function("create").symbol.symbol_type, SymbolType::Method);
+        assert!(!function("name").is_static);
+        assert_eq!(function("name").symbol.metadata.get("decorators").map(String::as_str), Some("@property"));
+        assert!(function("bark").is_async);
+    }
+    
+    #[tokio::test]
+    async fn test_import_extraction() {
+        let mut analyzer = JavaScriptAnalyzer::new().unwrap();
+        let result = analyzer.analyze(
+            Path::new("app.js"),
+            "import { a } from './a';\nimport b from \"lodash\";\nexport { c } from './c';\n"
+        ).await.unwrap();
+        let modules: Vec<_> = result.imports.iter().map(|i| i.module.as_str()).collect();
+        assert_eq!(modules, vec!["./a", "lodash", "./c"]);
+        assert_eq!(result.imports[1].line, 2);
+        
+        let mut analyzer = PythonAnalyzer::new().unwrap();
+        let result = analyzer.analyze(
+            Path::new("app.py"),
+            "import os.path\nimport numpy as np\nfrom .models import User\n"
+        ).await.unwrap();
+        let modules: Vec<_> = result.imports.iter().map(|i| i.module.as_str()).collect();
+        assert_eq!(modules, vec!["os.path", "numpy", ".models"]);
+        
+        let mut analyzer = CppAnalyzer::new().unwrap();
+        let result = analyzer.analyze(
+            Path::new("main.cpp"),
+            "#include <vector>\n#include \"util.h\"\n"
+        ).await.unwrap();
+        let modules: Vec<_> = result.imports.iter().map(|i| i.module.as_str()).collect();
+        assert_eq!(modules, vec!["<vector>", "util.h"]);
+    }
+    
+    #[tokio::test]
+    async fn test_cpp_scope_paths_and_parameters() {
+        let source = r#"
+namespace geo {
+class Point {
+public:
+    double dist(const Point& other, int scale = 1) const {
+        return 0.0;
+    }
+};
+
+void Point::reset(int* values, ...) {}
+
+static int helper(void) { return 0; }
+
+Point* make(int x) { return nullptr; }
+
+const Point& origin() { static Point p; return p; }
+}
+"#;
+        let mut analyzer = CppAnalyzer::new().unwrap();
+        let result = analyzer.analyze(Path::new("geo.cpp"), source).await.unwrap();
+        
+        let scope_paths: Vec<_> = result.functions.iter()
+            .map(|f| f.symbol.metadata.get("scope_path").cloned().unwrap_or_default())
+            .collect();
+        assert_eq!(scope_paths, vec!["geo::Point::dist", "geo::Point::reset", "geo::helper", "geo::make", "geo::origin"]);
+        
+        let dist = &result.functions[0];
+        assert_eq!(dist.symbol.name, "dist");
+        assert_eq!(dist.symbol.symbol_type, SymbolType::Method);
+        assert_eq!(dist.return_type.as_deref(), Some("double"));
+        assert_eq!(dist.parameters.len(), 2);
+        assert_eq!(dist.parameters[0].name, "other");
+        assert_eq!(dist.parameters[0].param_type.as_deref(), Some("const Point&"));
+        assert_eq!(dist.parameters[1].default_value.as_deref(), Some("1"));
+        assert!(dist.parameters[1].is_optional);
+        
+        let reset = &result.functions[1];
+        assert_eq!(reset.symbol.name, "reset");
+        assert_eq!(reset.symbol.metadata.get("class").map(String::as_str), Some("Point"));
+        assert_eq!(reset.parameters[0].param_type.as_deref(), Some("int*"));
+        assert!(reset.parameters[1].is_variadic);
+        
+        let helper = &result.functions[2];
+        assert_eq!(helper.symbol.symbol_type, SymbolType::Function);
+        assert!(helper.is_static);
+        assert!(helper.parameters.is_empty());
+        
+        // Pointer and reference returns wrap the function declarator
+        let make = &result.functions[3];
+        assert_eq!(make.symbol.name, "make");
+        assert_eq!(make.parameters[0].name, "x");
+        assert_eq!(make.symbol.line_start, 14);
+        assert_eq!(result.functions[4].symbol.name, "origin");
+    }
+    
+    #[tokio::test]
+    async fn test_java_package_scope_paths() {
+        let source = r#"package com.example;
+
+import java.util.List;
+import java.util.*;
+
+public class Main extends Base implements Runnable {
+    public static void main(String[] args) {
+        if (args.length > 0 && args[0] != null) {}
+    }
+
+    int sum(List<Integer> values, int... extra) { return 0; }
+
+    interface Listener { void onEvent(String name); }
+}
+
+enum Color { RED; Color next() { return RED; } }
+"#;
+        let mut analyzer = JavaAnalyzer::new().unwrap();
+        let result = analyzer.analyze(Path::new("Main.java"), source).await.unwrap();
+        
+        let modules: Vec<_> = result.imports.iter().map(|i| i.module.as_str()).collect();
+        assert_eq!(modules, vec!["java.util.List", "java.util.*"]);
+        assert_eq!(result.imports[0].imported_names, vec!["List".to_string()]);
+        
+        let scope_paths: Vec<_> = result.functions.iter()
+            .map(|f| f.symbol.metadata.get("scope_path").cloned().unwrap_or_default())
+            .collect();
+        assert_eq!(scope_paths, vec![
+            "com.example::Main::main",
+            "com.example::Main::sum",
+            "com.example::Main::Listener::onEvent",
+            "com.example::Color::next",
+        ]);
+        
+        let main = &result.functions[0];
+        assert!(main.is_static);
+        assert_eq!(main.symbol.symbol_type, SymbolType::Method);
+        assert_eq!(main.return_type.as_deref(), Some("void"));
+        assert_eq!(main.complexity, Some(3));
+        let sum = &result.functions[1];
+        assert_eq!(sum.symbol.visibility, Some(Visibility::Package));
+        assert_eq!(sum.parameters[0].param_type.as_deref(), Some("List<Integer>"));
+        assert_eq!(sum.parameters[1].name, "extra");
+        assert!(sum.parameters[1].is_variadic);
+        
+        let classes: Vec<_> = result.classes.iter().map(|c| (c.symbol.name.as_str(), c.symbol.symbol_type)).collect();
+        assert_eq!(classes, vec![
+            ("Main", SymbolType::Class),
+            ("Listener", SymbolType::Interface),
+            ("Color", SymbolType::Enum),
+        ]);
+        assert_eq!(result.classes[0].base_classes, vec!["Base".to_string()]);
+        assert_eq!(result.classes[0].interfaces, vec!["Runnable".to_string()]);
+        assert_eq!(result.classes[1].symbol.metadata.get("scope_path").map(String::as_str), Some("com.example::Main::Listener"));
+    }
+    
+    #[tokio::test]
+    async fn test_javascript_symbol_columns() {
+        let source = "class Shelf {\n    place(item) {\n        return item;\n    }\n}\n";
+        let mut analyzer = JavaScriptAnalyzer::new().unwrap();
+        let result = analyzer.analyze(Path::new("shelf.js"), source).await.unwrap();
+        
+        let class = &result.classes[0].symbol;
+        assert_eq!((class.column_start, class.column_end), (0, 1));
+        let method = &result.functions[0].symbol;
+        assert_eq!(method.name, "place");
+        assert_eq!((method.column_start, method.column_end), (4, 5));
+    }
+    
+    fn generics(result: &AnalysisResult) -> Vec<(&str, bool, Option<&str>)> {
+        result.functions.iter()
+            .map(|f| (f.symbol.name.as_str(), f.is_generic, f.symbol.metadata.get("type_parameters").map(String::as_str)))
+            .collect()
+    }
+    
+    #[tokio::test]
+    async fn test_rust_generic_functions() {
+        let source = "fn foo<T>() {}\nfn bar() {}\nfn pick<'a, K: Ord, const N: usize>(k: &'a K) {}\n";
+        let mut analyzer = RustAnalyzer::new().unwrap();
+        let result = analyzer.analyze(Path::new("lib.rs"), source).await.unwrap();
+        
+        assert_eq!(generics(&result), vec![
+            ("foo", true, Some("T")),
+            ("bar", false, None),
+            ("pick", true, Some("'a,K,N")),
+        ]);
+    }
+    
+    #[tokio::test]
+    async fn test_typescript_generic_functions() {
+        let source = "function foo<T>(x: T): T { return x; }\nfunction bar() {}\nclass Box {\n    map<U extends object>(f: () => U) {}\n}\n";
+        let mut analyzer = TypeScriptAnalyzer::new().unwrap();
+        let result = analyzer.analyze(Path::new("box.ts"), source).await.unwrap();
+        
+        assert_eq!(generics(&result), vec![
+            ("foo", true, Some("T")),
+            ("bar", false, None),
+            ("map", true, Some("U")),
+        ]);
+        assert_eq!(result.functions[2].symbol.symbol_type, SymbolType::Method);
+    }
+    
+    #[tokio::test]
+    async fn test_csharp_generic_methods() {
+        let source = "class Repo {\n    T Foo<T>() where T : new() { return new T(); }\n    void Bar() {}\n}\n";
+        let mut analyzer = CSharpAnalyzer::new().unwrap();
+        let result = analyzer.analyze(Path::new("Repo.cs"), source).await.unwrap();
+        
+        assert_eq!(generics(&result), vec![("Foo", true, Some("T")), ("Bar", false, None)]);
+    }
+}
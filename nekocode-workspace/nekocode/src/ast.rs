@@ -1,7 +1,8 @@
 //! AST (Abstract Syntax Tree) utilities
 
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use tree_sitter::{Node, Tree};
 
 use nekocode_core::{Result, NekocodeError};
@@ -29,6 +30,134 @@ pub enum ASTNodeType {
     Other(String),
 }
 
+/// Grammar node kinds (beyond the mapped types) that open a named scope
+const SCOPE_NODE_KINDS: &[&str] = &[
+    // JavaScript / TypeScript
+    "function_expression", "arrow_function", "generator_function_declaration",
+    "interface_declaration", "abstract_class_declaration", "internal_module",
+    // Rust
+    "impl_item", "trait_item", "mod_item", "struct_item", "enum_item",
+    // C++ / C#
+    "namespace_definition", "namespace_declaration", "class_specifier", "struct_specifier",
+    "struct_declaration", "interface_declaration", "constructor_declaration",
+    // Go
+    "func_literal", "type_declaration",
+    // Python
+    "lambda",
+];
+
+impl ASTNodeType {
+    /// Type name as shown to users (`Function`, or the grammar kind for `Other`)
+    pub fn type_string(&self) -> String {
+        match self {
+            ASTNodeType::Other(kind) => kind.clone(),
+            _ => format!("{:?}", self),
+        }
+    }
+}
+
+/// One segment of a [`PathPattern`]
+#[derive(Debug, Clone)]
+enum PathSegment {
+    /// `*` - matches any single segment
+    Any,
+    /// Exact segment name
+    Name(String),
+    /// Regex that must match the whole segment
+    Regex(Regex),
+}
+
+impl PathSegment {
+    fn matches(&self, segment: &str) -> bool {
+        match self {
+            PathSegment::Any => true,
+            PathSegment::Name(name) => name == segment,
+            PathSegment::Regex(regex) => regex.is_match(segment),
+        }
+    }
+}
+
+/// Scope path pattern such as `"MyClass::myMethod"`, `"*::render"` or (in regex mode) `"App::handle_.*"`
+///
+/// Patterns match the end of a scope path, so `"*::render"` finds `render` in any
+/// class regardless of nesting. A leading `::` anchors the pattern at the module root.
+#[derive(Debug, Clone)]
+pub struct PathPattern {
+    segments: Vec<PathSegment>,
+    anchored: bool,
+}
+
+impl PathPattern {
+    /// Parse a path pattern. In regex mode every segment except `*` is a regex
+    /// matched against the whole segment (use `.*foo.*` for substring matches).
+    pub fn parse(path: &str, regex: bool) -> Result<Self> {
+        let path = path.trim();
+        let (anchored, path) = match path.strip_prefix("::") {
+            Some(rest) => (true, rest),
+            None => (false, path),
+        };
+        
+        let segments = path
+            .split("::")
+            .map(|segment| {
+                let segment = segment.trim();
+                if segment == "*" {
+                    Ok(PathSegment::Any)
+                } else if regex {
+                    Regex::new(&format!("^(?:{})$", segment))
+                        .map(PathSegment::Regex)
+                        .map_err(|e| NekocodeError::Analysis(format!("Invalid regex segment '{}': {}", segment, e)))
+                } else {
+                    Ok(PathSegment::Name(segment.to_string()))
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        
+        Ok(Self { segments, anchored })
+    }
+    
+    /// Check whether a scope path (segments from outermost to innermost) matches
+    pub fn matches(&self, scope_path: &[&str]) -> bool {
+        if scope_path.len() < self.segments.len()
+            || (self.anchored && scope_path.len() != self.segments.len())
+        {
+            return false;
+        }
+        
+        let tail = &scope_path[scope_path.len() - self.segments.len()..];
+        self.segments.iter().zip(tail).all(|(segment, part)| segment.matches(part))
+    }
+}
+
+/// Pruning applied while dumping an AST
+#[derive(Debug, Clone, Default)]
+pub struct DumpFilter {
+    /// Deepest level to descend to; the root is level 0
+    pub max_depth: Option<usize>,
+    /// Lowercased type names to keep; ancestors of kept nodes stay for context
+    pub node_types: Option<HashSet<String>>,
+}
+
+impl DumpFilter {
+    /// Build a filter from CLI options (`node_types` is comma-separated and
+    /// matched case-insensitively against [`ASTNodeType::type_string`])
+    pub fn new(max_depth: Option<usize>, node_types: Option<&str>) -> Self {
+        let node_types = node_types.map(|list| {
+            list.split(',')
+                .map(|name| name.trim().to_lowercase())
+                .filter(|name| !name.is_empty())
+                .collect()
+        });
+        
+        Self { max_depth, node_types }
+    }
+    
+    fn matches(&self, node_type: &ASTNodeType) -> bool {
+        self.node_types.as_ref()
+            .is_none_or(|types| types.contains(&node_type.type_string().to_lowercase()))
+    }
+}
+
 /// AST node representation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ASTNode {
@@ -66,6 +195,16 @@ impl ASTNode {
         ast_node.start_column = node.start_position().column as u32;
         ast_node.end_column = node.end_position().column as u32;
         
+        // Record the declared name of named constructs (functions, classes, ...)
+        let name_node = node.child_by_field_name("name")
+            .or_else(|| (node.kind() == "impl_item").then(|| node.child_by_field_name("type")).flatten())
+            .or_else(|| (node.kind() == "function_definition").then(|| Self::function_declarator_name(node)).flatten());
+        if let Some(name_node) = name_node {
+            if let Ok(name) = name_node.utf8_text(source.as_bytes()) {
+                ast_node.metadata.insert("name".to_string(), name.to_string());
+            }
+        }
+        
         // Get text for leaf nodes
         if node.child_count() == 0 {
             ast_node.text = node.utf8_text(source.as_bytes())
@@ -83,6 +222,17 @@ impl ASTNode {
         Ok(ast_node)
     }
     
+    /// Name of a C/C++ `function_definition`, found by following the declarator
+    /// chain (pointers, references) down to the `function_declarator`.
+    /// Out-of-line definitions keep their qualifier, e.g. `Foo::bar`.
+    fn function_declarator_name(node: Node) -> Option<Node> {
+        let mut declarator = node.child_by_field_name("declarator")?;
+        while declarator.kind() != "function_declarator" {
+            declarator = declarator.child_by_field_name("declarator")?;
+        }
+        declarator.child_by_field_name("declarator")
+    }
+    
     /// Map tree-sitter node kind to AST node type
     fn map_node_kind(kind: &str) -> ASTNodeType {
         match kind {
@@ -131,6 +281,125 @@ impl ASTNode {
         nodes
     }
     
+    /// Declared name of this node, if any
+    pub fn name(&self) -> Option<&str> {
+        self.metadata.get("name").map(|n| n.as_str())
+    }
+    
+    /// Whether this node opens a named scope (module, class, function, ...)
+    pub fn is_scope(&self) -> bool {
+        match &self.node_type {
+            ASTNodeType::Program | ASTNodeType::Function | ASTNodeType::Class | ASTNodeType::Method => true,
+            ASTNodeType::Other(kind) => SCOPE_NODE_KINDS.contains(&kind.as_str()),
+            _ => false,
+        }
+    }
+    
+    /// Find the deepest node containing a line
+    pub fn find_node_at_line(&self, line: u32) -> Option<&ASTNode> {
+        if line < self.start_line || line > self.end_line {
+            return None;
+        }
+        
+        // Children first (deepest wins)
+        self.children.iter()
+            .find_map(|child| child.find_node_at_line(line))
+            .or(Some(self))
+    }
+    
+    /// Collect the scopes enclosing a line, from the outermost (module) to the innermost
+    pub fn find_scope_chain_at_line(&self, line: u32) -> Vec<&ASTNode> {
+        let mut chain = Vec::new();
+        let mut current = Some(self);
+        
+        while let Some(node) = current {
+            if line < node.start_line || line > node.end_line {
+                break;
+            }
+            if node.is_scope() {
+                chain.push(node);
+            }
+            current = node.children.iter()
+                .find(|child| line >= child.start_line && line <= child.end_line);
+        }
+        
+        chain
+    }
+    
+    /// Copy of this tree with `filter` applied. Subtrees below the depth
+    /// limit are never visited; the root is always kept.
+    pub fn pruned(&self, filter: &DumpFilter) -> ASTNode {
+        let children = self.pruned_children(filter, 0);
+        self.with_children(children)
+    }
+    
+    fn pruned_children(&self, filter: &DumpFilter, level: usize) -> Vec<ASTNode> {
+        if filter.max_depth.is_some_and(|max| level >= max) {
+            return Vec::new();
+        }
+        
+        self.children.iter()
+            .filter_map(|child| {
+                let children = child.pruned_children(filter, level + 1);
+                if children.is_empty() && !filter.matches(&child.node_type) {
+                    None
+                } else {
+                    Some(child.with_children(children))
+                }
+            })
+            .collect()
+    }
+    
+    fn with_children(&self, children: Vec<ASTNode>) -> ASTNode {
+        ASTNode {
+            node_type: self.node_type.clone(),
+            text: self.text.clone(),
+            start_line: self.start_line,
+            end_line: self.end_line,
+            start_column: self.start_column,
+            end_column: self.end_column,
+            children,
+            metadata: self.metadata.clone(),
+        }
+    }
+    
+    /// One-line description used by the tree and flat dumps
+    fn dump_label(&self) -> String {
+        let label = self.name().unwrap_or(&self.text);
+        format!(
+            "{} '{}' ({}:{}-{}:{})",
+            self.node_type.type_string(),
+            label,
+            self.start_line,
+            self.start_column,
+            self.end_line,
+            self.end_column
+        )
+    }
+    
+    /// Dump AST as an indented tree
+    pub fn dump_as_tree(&self, indent: usize) -> String {
+        let mut result = format!("{}{}\n", "  ".repeat(indent), self.dump_label());
+        for child in &self.children {
+            result.push_str(&child.dump_as_tree(indent + 1));
+        }
+        result
+    }
+    
+    /// Dump AST as a flat list, one node per line
+    pub fn dump_as_flat(&self) -> String {
+        let mut lines = Vec::new();
+        self.collect_flat(&mut lines);
+        lines.join("\n")
+    }
+    
+    fn collect_flat(&self, lines: &mut Vec<String>) {
+        lines.push(self.dump_label());
+        for child in &self.children {
+            child.collect_flat(lines);
+        }
+    }
+    
     /// Calculate depth of the AST
     pub fn depth(&self) -> usize {
         if self.children.is_empty() {
@@ -169,10 +438,7 @@ impl ASTStatistics {
     fn calculate_node_counts(&mut self, node: &ASTNode) {
         self.total_nodes += 1;
         
-        let type_name = match &node.node_type {
-            ASTNodeType::Other(s) => s.clone(),
-            _ => format!("{:?}", node.node_type),
-        };
+        let type_name = node.node_type.type_string();
         
         *self.node_counts.entry(type_name).or_insert(0) += 1;
         
@@ -219,6 +485,43 @@ impl ASTBuilder {
         Self::find_by_path_parts(ast, &parts)
     }
     
+    /// Find all named nodes whose scope path matches `path`, returning each
+    /// match with its `::`-joined scope path
+    ///
+    /// `*` matches any single segment; with `regex` every other segment is a regex.
+    pub fn query_all_by_path<'a>(ast: &'a ASTNode, path: &str, regex: bool) -> Result<Vec<(String, &'a ASTNode)>> {
+        let pattern = PathPattern::parse(path, regex)?;
+        let mut matches = Vec::new();
+        Self::collect_path_matches(ast, &pattern, &mut Vec::new(), &mut matches);
+        Ok(matches)
+    }
+    
+    fn collect_path_matches<'a>(
+        node: &'a ASTNode,
+        pattern: &PathPattern,
+        scopes: &mut Vec<&'a str>,
+        matches: &mut Vec<(String, &'a ASTNode)>,
+    ) {
+        // Qualified names (`Foo::bar`, `namespace a::b`) contribute one segment per part
+        let depth = scopes.len();
+        if let Some(name) = node.name() {
+            scopes.extend(name.split("::").filter(|part| !part.is_empty()));
+            if pattern.matches(scopes) {
+                matches.push((scopes.join("::"), node));
+            }
+            // Only scopes contribute to the path of their descendants
+            if !node.is_scope() {
+                scopes.truncate(depth);
+            }
+        }
+        
+        for child in &node.children {
+            Self::collect_path_matches(child, pattern, scopes, matches);
+        }
+        
+        scopes.truncate(depth);
+    }
+    
     fn find_by_path_parts<'a>(node: &'a ASTNode, parts: &[&str]) -> Option<&'a ASTNode> {
         if parts.is_empty() {
             return Some(node);
@@ -250,4 +553,125 @@ impl ASTBuilder {
         
         None
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tree_sitter::Parser;
+    
+    #[test]
+    fn test_scope_chain_at_line() {
+        let source = r#"import os
+
+class Greeter:
+    def greet(self, name):
+        if name:
+            return "hi " + name
+        return "hi"
+"#;
+        let mut parser = Parser::new();
+        parser.set_language(tree_sitter_python::language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let ast = ASTBuilder::build_from_tree(&tree, source).unwrap();
+        
+        let chain: Vec<_> = ast.find_scope_chain_at_line(6)
+            .iter()
+            .map(|n| (n.node_type.type_string(), n.name().unwrap_or("").to_string()))
+            .collect();
+        assert_eq!(chain, vec![
+            ("Program".to_string(), String::new()),
+            ("Class".to_string(), "Greeter".to_string()),
+            ("Function".to_string(), "greet".to_string()),
+        ]);
+        
+        assert_eq!(ast.find_scope_chain_at_line(1).len(), 1);
+        assert!(ast.find_scope_chain_at_line(100).is_empty());
+        assert_eq!(ast.find_node_at_line(6).map(|n| n.start_line), Some(6));
+    }
+    
+    #[test]
+    fn test_query_all_by_path_patterns() {
+        let source = r#"class Button:
+    def render(self):
+        pass
+
+    def render_icon(self):
+        pass
+
+class Label:
+    def render(self):
+        pass
+"#;
+        let mut parser = Parser::new();
+        parser.set_language(tree_sitter_python::language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let ast = ASTBuilder::build_from_tree(&tree, source).unwrap();
+        
+        let paths = |path: &str, regex: bool| -> Vec<String> {
+            ASTBuilder::query_all_by_path(&ast, path, regex)
+                .unwrap()
+                .into_iter()
+                .map(|(scope_path, _)| scope_path)
+                .collect()
+        };
+        
+        assert_eq!(paths("Button::render", false), vec!["Button::render"]);
+        assert_eq!(paths("*::render", false), vec!["Button::render", "Label::render"]);
+        assert_eq!(paths("Button::render.*", true), vec!["Button::render", "Button::render_icon"]);
+        assert!(paths("*::rend", true).is_empty());
+        assert!(paths("::render", false).is_empty());
+        assert!(ASTBuilder::query_all_by_path(&ast, "(", true).is_err());
+    }
+    
+    #[test]
+    fn test_query_cpp_qualified_paths() {
+        let source = "namespace geo {\nclass Point {\n    int x() const { return 0; }\n};\nvoid Point::reset() {}\n}\n";
+        let mut parser = Parser::new();
+        parser.set_language(tree_sitter_cpp::language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let ast = ASTBuilder::build_from_tree(&tree, source).unwrap();
+        
+        let paths: Vec<String> = ASTBuilder::query_all_by_path(&ast, "geo::Point::*", false)
+            .unwrap()
+            .into_iter()
+            .map(|(scope_path, _)| scope_path)
+            .collect();
+        assert_eq!(paths, vec!["geo::Point::x", "geo::Point::reset"]);
+    }
+    
+    #[test]
+    fn test_dump_filter() {
+        let source = r#"class Greeter:
+    def greet(self, name):
+        return "hi " + name
+
+def main():
+    pass
+"#;
+        let mut parser = Parser::new();
+        parser.set_language(tree_sitter_python::language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let ast = ASTBuilder::build_from_tree(&tree, source).unwrap();
+        
+        let shallow = ast.pruned(&DumpFilter::new(Some(1), None));
+        assert_eq!(shallow.depth(), 2);
+        
+        // Functions keep the class (and its body block) around them, nothing else
+        let functions = ast.pruned(&DumpFilter::new(None, Some("function")));
+        let names: Vec<_> = functions.dump_as_flat().lines()
+            .filter_map(|line| line.split('\'').nth(1).map(str::to_string))
+            .filter(|name| !name.is_empty())
+            .collect();
+        assert!(names.contains(&"Greeter".to_string()));
+        assert!(names.contains(&"greet".to_string()));
+        assert!(names.contains(&"main".to_string()));
+        assert!(!names.contains(&"name".to_string()));
+        assert_eq!(functions.find_nodes_by_type(&ASTNodeType::Function).len(), 2);
+        
+        // Nothing below the depth limit can match
+        let limited = ast.pruned(&DumpFilter::new(Some(1), Some("Function")));
+        assert_eq!(limited.find_nodes_by_type(&ASTNodeType::Function).len(), 1);
+        assert!(limited.find_nodes_by_type(&ASTNodeType::Class).is_empty());
+    }
+}
@@ -82,13 +82,17 @@ pub enum Commands {
         session_id: String,
     },
     
-    /// Query AST by path (e.g., "MyClass::myMethod")
+    /// Query AST by path (e.g., "MyClass::myMethod", "*::render")
     AstQuery {
         /// Session ID
         session_id: String,
         
-        /// Query path
+        /// Query path (`*` matches any single segment, a leading `::` anchors at the module root)
         path: String,
+        
+        /// Treat each path segment as a regex matched against the whole name
+        #[arg(long)]
+        regex: bool,
     },
     
     /// Dump AST tree
@@ -107,6 +111,14 @@ pub enum Commands {
         /// Force full output (ignore token limit)
         #[arg(long)]
         force: bool,
+        
+        /// Do not descend below this depth (the root is depth 0)
+        #[arg(long)]
+        depth: Option<usize>,
+        
+        /// Only include these node types, comma-separated (e.g. function,class)
+        #[arg(long)]
+        node_type: Option<String>,
     },
     
     /// Scope analysis for a specific line
@@ -0,0 +1,217 @@
+//! Cyclomatic complexity calculation
+//!
+//! Complexity is computed as `1 + decision points`, where the node kinds that
+//! count as decision points differ per grammar (e.g. Rust's `match_expression`
+//! vs Go's `type_switch_statement`).
+
+use tree_sitter::Node;
+
+use nekocode_core::types::Language;
+
+/// Language-specific set of syntax nodes that introduce a new execution path
+#[derive(Debug, Clone, Copy)]
+pub struct DecisionPoints {
+    /// Node kinds that each add one path (branches, loops, catch clauses, ...)
+    pub node_kinds: &'static [&'static str],
+    /// Short-circuit operators counted inside `binary_expression` nodes
+    pub logical_operators: &'static [&'static str],
+}
+
+pub const RUST_DECISION_POINTS: DecisionPoints = DecisionPoints {
+    node_kinds: &[
+        "if_expression",
+        "if_let_expression",
+        "match_expression",
+        "for_expression",
+        "while_expression",
+        "while_let_expression",
+        "try_expression",
+    ],
+    logical_operators: &["&&", "||"],
+};
+
+pub const GO_DECISION_POINTS: DecisionPoints = DecisionPoints {
+    node_kinds: &[
+        "if_statement",
+        "for_statement",
+        "expression_switch_statement",
+        "type_switch_statement",
+        "select_statement",
+    ],
+    logical_operators: &["&&", "||"],
+};
+
+pub const CSHARP_DECISION_POINTS: DecisionPoints = DecisionPoints {
+    node_kinds: &[
+        "if_statement",
+        "switch_statement",
+        "switch_expression",
+        "for_statement",
+        "for_each_statement",
+        "foreach_statement",
+        "while_statement",
+        "do_statement",
+        "conditional_expression",
+        "catch_clause",
+    ],
+    logical_operators: &["&&", "||", "??"],
+};
+
+pub const JAVA_DECISION_POINTS: DecisionPoints = DecisionPoints {
+    node_kinds: &[
+        "if_statement",
+        "switch_expression",
+        "for_statement",
+        "enhanced_for_statement",
+        "while_statement",
+        "do_statement",
+        "ternary_expression",
+        "catch_clause",
+    ],
+    logical_operators: &["&&", "||"],
+};
+
+pub const CPP_DECISION_POINTS: DecisionPoints = DecisionPoints {
+    node_kinds: &[
+        "if_statement",
+        "switch_statement",
+        "for_statement",
+        "for_range_loop",
+        "while_statement",
+        "do_statement",
+        "conditional_expression",
+        "catch_clause",
+    ],
+    logical_operators: &["&&", "||", "and", "or"],
+};
+
+impl DecisionPoints {
+    /// Get the decision point map for a language, if one is defined
+    pub fn for_language(language: Language) -> Option<&'static DecisionPoints> {
+        match language {
+            Language::Rust => Some(&RUST_DECISION_POINTS),
+            Language::Go => Some(&GO_DECISION_POINTS),
+            Language::CSharp => Some(&CSHARP_DECISION_POINTS),
+            Language::Cpp => Some(&CPP_DECISION_POINTS),
+            Language::Java => Some(&JAVA_DECISION_POINTS),
+            _ => None,
+        }
+    }
+}
+
+/// Count decision points in the subtree rooted at `node`
+pub fn count_decision_points(node: Node, points: &DecisionPoints) -> u32 {
+    let mut count = 0;
+
+    if points.node_kinds.contains(&node.kind()) {
+        count += 1;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if node.kind() == "binary_expression"
+            && !child.is_named()
+            && points.logical_operators.contains(&child.kind())
+        {
+            count += 1;
+        }
+        count += count_decision_points(child, points);
+    }
+
+    count
+}
+
+/// Calculate the cyclomatic complexity of the subtree rooted at `node`
+pub fn cyclomatic_complexity(node: Node, points: &DecisionPoints) -> u32 {
+    1 + count_decision_points(node, points)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tree_sitter::Parser;
+
+    fn complexity_of(language: tree_sitter::Language, source: &str, points: &DecisionPoints) -> u32 {
+        let mut parser = Parser::new();
+        parser.set_language(language).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        cyclomatic_complexity(tree.root_node(), points)
+    }
+
+    #[test]
+    fn test_rust_three_ifs() {
+        let source = r#"
+fn classify(a: i32, b: i32, c: i32) -> i32 {
+    if a > 0 { return 1; }
+    if b > 0 { return 2; }
+    if c > 0 { return 3; }
+    0
+}
+"#;
+        assert_eq!(complexity_of(tree_sitter_rust::language(), source, &RUST_DECISION_POINTS), 4);
+    }
+
+    #[test]
+    fn test_rust_match_and_logical_operators() {
+        let source = r#"
+fn check(x: Option<i32>, a: bool, b: bool) -> bool {
+    match x {
+        Some(_) => a && b,
+        None => a || b,
+    }
+}
+"#;
+        assert_eq!(complexity_of(tree_sitter_rust::language(), source, &RUST_DECISION_POINTS), 4);
+    }
+
+    #[test]
+    fn test_go_three_ifs() {
+        let source = r#"
+package main
+
+func classify(a, b, c int) int {
+	if a > 0 {
+		return 1
+	}
+	if b > 0 {
+		return 2
+	}
+	if c > 0 {
+		return 3
+	}
+	return 0
+}
+"#;
+        assert_eq!(complexity_of(tree_sitter_go::language(), source, &GO_DECISION_POINTS), 4);
+    }
+
+    #[test]
+    fn test_go_type_switch() {
+        let source = r#"
+package main
+
+func describe(v interface{}) string {
+	switch v.(type) {
+	case int:
+		return "int"
+	default:
+		return "other"
+	}
+}
+"#;
+        assert_eq!(complexity_of(tree_sitter_go::language(), source, &GO_DECISION_POINTS), 2);
+    }
+
+    #[test]
+    fn test_cpp_three_ifs() {
+        let source = r#"
+int classify(int a, int b, int c) {
+    if (a > 0) return 1;
+    if (b > 0) return 2;
+    if (c > 0) return 3;
+    return 0;
+}
+"#;
+        assert_eq!(complexity_of(tree_sitter_cpp::language(), source, &CPP_DECISION_POINTS), 4);
+    }
+}
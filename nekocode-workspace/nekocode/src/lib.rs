@@ -1,17 +1,18 @@
 //! NekoCode - Core analysis engine with Tree-sitter support
 
 pub mod analyzer;
+pub mod complexity;
 pub mod ast;
 pub mod cli;
 pub mod session;
 
 pub use analyzer::{
-    Analyzer, AnalyzerConfig,
+    Analyzer, AnalyzerConfig, create_analyzer, tree_sitter_language,
     JavaScriptAnalyzer, TypeScriptAnalyzer,
     PythonAnalyzer, RustAnalyzer,
-    CppAnalyzer, GoAnalyzer, CSharpAnalyzer
+    CppAnalyzer, GoAnalyzer, CSharpAnalyzer, JavaAnalyzer
 };
 
-pub use ast::{ASTBuilder, ASTNode, ASTNodeType, ASTStatistics};
+pub use ast::{ASTBuilder, ASTNode, ASTNodeType, ASTStatistics, DumpFilter};
 pub use session::{SessionCommands, SessionUpdater};
 pub use cli::Cli;
\ No newline at end of file
@@ -3,13 +3,13 @@
 use clap::Parser;
 use std::fs;
 
-use nekocode_core::{Result, NekocodeError, session::SessionManager};
+use nekocode_core::{Result, NekocodeError, session::{SessionManager, SessionExport}};
 use nekocode::{
     Cli,
-    SessionCommands, SessionUpdater,
+    SessionCommands, SessionUpdater, DumpFilter,
     JavaScriptAnalyzer, TypeScriptAnalyzer,
     PythonAnalyzer, RustAnalyzer,
-    CppAnalyzer, GoAnalyzer, CSharpAnalyzer,
+    CppAnalyzer, GoAnalyzer, CSharpAnalyzer, JavaAnalyzer,
     Analyzer
 };
 use nekocode::cli::Commands;
@@ -147,15 +147,16 @@ async fn main() -> Result<()> {
             println!("{}", stats);
         }
         
-        Commands::AstQuery { session_id, path } => {
+        Commands::AstQuery { session_id, path, regex } => {
             let mut commands = SessionCommands::new()?;
-            let result = commands.ast_query(&session_id, &path).await?;
+            let result = commands.ast_query(&session_id, &path, regex).await?;
             println!("{}", result);
         }
         
-        Commands::AstDump { session_id, format, limit, force } => {
+        Commands::AstDump { session_id, format, limit, force, depth, node_type } => {
             let mut commands = SessionCommands::new()?;
-            let mut result = commands.ast_dump(&session_id, &format).await?;
+            let filter = DumpFilter::new(depth, node_type.as_deref());
+            let mut result = commands.ast_dump(&session_id, &format, &filter).await?;
             
             if !force && result.lines().count() > 1000 {
                 let lines: Vec<&str> = result.lines().take(1000).collect();
@@ -172,8 +173,9 @@ async fn main() -> Result<()> {
         }
         
         Commands::ScopeAnalysis { session_id, line } => {
-            println!("🎯 Scope analysis for session {} at line {}", session_id, line);
-            println!("(Scope analysis functionality requires AST traversal implementation)");
+            let mut commands = SessionCommands::new()?;
+            let result = commands.scope_analysis(&session_id, line).await?;
+            println!("{}", result);
         }
         
         Commands::Export { session_id, output, format } => {
@@ -195,7 +197,7 @@ async fn main() -> Result<()> {
                     csv
                 }
                 _ => {
-                    serde_json::to_string_pretty(&session.info.analysis_results)?
+                    serde_json::to_string_pretty(&SessionExport::from_info(&session.info))?
                 }
             };
             
@@ -204,11 +206,21 @@ async fn main() -> Result<()> {
         }
         
         Commands::Import { input, session_id } => {
-            println!("📥 Import functionality not yet implemented");
-            println!("Input: {}", input.display());
-            if let Some(id) = session_id {
-                println!("Target session: {}", id);
+            if !input.exists() {
+                return Err(NekocodeError::FileNotFound(input.display().to_string()));
             }
+            
+            let content = fs::read_to_string(&input)?;
+            let export = SessionExport::from_json(&content)?;
+            
+            let id = session_id.unwrap_or_else(SessionManager::generate_session_id);
+            let info = export.into_session_info(id);
+            let file_count = info.file_count;
+            
+            let mut session_manager = SessionManager::new()?;
+            let id = session_manager.save_session_info(info)?;
+            
+            println!("✅ Imported {} files from {} into session {}", file_count, input.display(), id);
         }
     }
     
@@ -226,6 +238,7 @@ fn create_analyzer_for_path(path: &std::path::Path, language: Option<&str>) -> R
             "cpp" | "c++" | "cxx" => Ok(Box::new(CppAnalyzer::new()?)),
             "go" => Ok(Box::new(GoAnalyzer::new()?)),
             "csharp" | "cs" => Ok(Box::new(CSharpAnalyzer::new()?)),
+            "java" => Ok(Box::new(JavaAnalyzer::new()?)),
             _ => Err(NekocodeError::LanguageNotSupported(lang.to_string()))
         }
     } else {
@@ -242,6 +255,7 @@ fn create_analyzer_for_path(path: &std::path::Path, language: Option<&str>) -> R
             "cpp" | "cxx" | "cc" | "hpp" | "hxx" | "hh" | "c" | "h" => Ok(Box::new(CppAnalyzer::new()?)),
             "go" => Ok(Box::new(GoAnalyzer::new()?)),
             "cs" => Ok(Box::new(CSharpAnalyzer::new()?)),
+            "java" => Ok(Box::new(JavaAnalyzer::new()?)),
             _ => Err(NekocodeError::LanguageNotSupported(ext.to_string()))
         }
     }
@@ -11,11 +11,12 @@ use nekocode_core::{
 };
 
 use crate::analyzer::{
+    tree_sitter_language,
     Analyzer, JavaScriptAnalyzer, TypeScriptAnalyzer,
     PythonAnalyzer, RustAnalyzer, CppAnalyzer,
-    GoAnalyzer, CSharpAnalyzer
+    GoAnalyzer, CSharpAnalyzer, JavaAnalyzer
 };
-use crate::ast::{ASTBuilder, ASTStatistics};
+use crate::ast::{ASTBuilder, ASTNode, ASTStatistics, DumpFilter};
 
 /// Session commands for AST operations
 pub struct SessionCommands {
@@ -60,38 +61,157 @@ impl SessionCommands {
     }
     
     /// Query AST by path
-    pub async fn ast_query(&mut self, session_id: &str, path: &str) -> Result<String> {
+    pub async fn ast_query(&mut self, session_id: &str, path: &str, regex: bool) -> Result<String> {
         let session = self.session_manager.get_session_mut(session_id)?;
         
-        // This would require storing AST in session or rebuilding it
-        // For now, return a placeholder
-        Ok(format!("🔍 Querying AST path: {}\n(AST query functionality requires AST storage in session)", path))
+        let mut results = Vec::new();
+        
+        for analysis_result in &session.info.analysis_results {
+            let file_path = &analysis_result.file_info.path;
+            
+            let ast = match Self::build_ast_for_file(file_path, analysis_result.file_info.language) {
+                Ok(Some(ast)) => ast,
+                Ok(None) => continue,
+                Err(e) => {
+                    eprintln!("Failed to parse {}: {}", file_path.display(), e);
+                    continue;
+                }
+            };
+            
+            for (scope_path, node) in ASTBuilder::query_all_by_path(&ast, path, regex)? {
+                results.push(serde_json::json!({
+                    "file": file_path,
+                    "node_type": node.node_type.type_string(),
+                    "name": node.name(),
+                    "scope_path": scope_path,
+                    "start_line": node.start_line,
+                    "end_line": node.end_line,
+                }));
+            }
+        }
+        
+        let result = serde_json::json!({
+            "query_path": path,
+            "regex": regex,
+            "matches": results
+        });
+        
+        Ok(serde_json::to_string_pretty(&result)?)
     }
     
-    /// Dump AST in specified format
-    pub async fn ast_dump(&mut self, session_id: &str, format: &str) -> Result<String> {
+    /// Dump each session file's AST in the given format, pruned by `filter`
+    pub async fn ast_dump(&mut self, session_id: &str, format: &str, filter: &DumpFilter) -> Result<String> {
+        if !matches!(format, "tree" | "json" | "flat") {
+            return Ok(format!("Unknown format: {}. Supported: tree, json, flat", format));
+        }
+        
         let session = self.session_manager.get_session_mut(session_id)?;
         
-        match format {
-            "json" => {
-                // Return session analysis results as JSON
-                let json = serde_json::to_string_pretty(&session.info.analysis_results)?;
-                Ok(json)
-            }
-            "tree" => {
-                let mut output = String::new();
-                output.push_str(&format!("🌳 AST Tree for session {}\n", session_id));
-                for result in &session.info.analysis_results {
-                    output.push_str(&format!("\n📄 {}\n", result.file_info.path.display()));
-                    output.push_str(&format!("  Functions: {}\n", result.functions.len()));
-                    output.push_str(&format!("  Classes: {}\n", result.classes.len()));
+        let mut output = String::new();
+        let mut json_files = Vec::new();
+        
+        for result in &session.info.analysis_results {
+            let path = &result.file_info.path;
+            
+            let ast = match Self::build_ast_for_file(path, result.file_info.language) {
+                Ok(Some(ast)) => ast.pruned(filter),
+                Ok(None) => continue,
+                Err(e) => {
+                    eprintln!("Failed to parse {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+            
+            match format {
+                "json" => json_files.push(serde_json::json!({
+                    "file": path,
+                    "ast": ast,
+                })),
+                "tree" => {
+                    output.push_str(&format!("📄 {}\n", path.display()));
+                    output.push_str(&ast.dump_as_tree(1));
+                    output.push('\n');
+                }
+                _ => {
+                    output.push_str(&format!("=== {} ===\n", path.display()));
+                    output.push_str(&ast.dump_as_flat());
+                    output.push_str("\n\n");
                 }
-                Ok(output)
-            }
-            _ => {
-                Ok(format!("Unknown format: {}. Supported: json, tree", format))
             }
         }
+        
+        if format == "json" {
+            return Ok(serde_json::to_string_pretty(&json_files)?);
+        }
+        Ok(output)
+    }
+}
+
+impl SessionCommands {
+    /// Find the chain of scopes enclosing a line in each session file
+    pub async fn scope_analysis(&mut self, session_id: &str, line: u32) -> Result<String> {
+        let session = self.session_manager.get_session_mut(session_id)?;
+        
+        let mut results = Vec::new();
+        
+        for analysis_result in &session.info.analysis_results {
+            let path = &analysis_result.file_info.path;
+            
+            let ast = match Self::build_ast_for_file(path, analysis_result.file_info.language) {
+                Ok(Some(ast)) => ast,
+                Ok(None) => continue,
+                Err(e) => {
+                    eprintln!("Failed to parse {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+            
+            let chain = ast.find_scope_chain_at_line(line);
+            let Some(node) = ast.find_node_at_line(line) else { continue };
+            
+            let scope_path = chain.iter()
+                .filter_map(|scope| scope.name())
+                .collect::<Vec<_>>()
+                .join("::");
+            
+            results.push(serde_json::json!({
+                "file": path,
+                "node_type": node.node_type.type_string(),
+                "scope_path": scope_path,
+                "start_line": node.start_line,
+                "end_line": node.end_line,
+                "scope_chain": chain.iter().rev().map(|scope| serde_json::json!({
+                    "node_type": scope.node_type.type_string(),
+                    "name": scope.name(),
+                    "start_line": scope.start_line,
+                    "end_line": scope.end_line,
+                })).collect::<Vec<_>>(),
+            }));
+        }
+        
+        let result = serde_json::json!({
+            "line_number": line,
+            "scope_analysis": results
+        });
+        
+        Ok(serde_json::to_string_pretty(&result)?)
+    }
+    
+    /// Parse a file from disk into an AST (`None` for unsupported languages)
+    fn build_ast_for_file(path: &Path, language: Language) -> Result<Option<ASTNode>> {
+        let Some(ts_language) = tree_sitter_language(language) else {
+            return Ok(None);
+        };
+        
+        let content = fs::read_to_string(path)?;
+        
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(ts_language)
+            .map_err(|e| NekocodeError::Analysis(format!("Failed to set language: {:?}", e)))?;
+        let tree = parser.parse(&content, None)
+            .ok_or_else(|| NekocodeError::Analysis(format!("Failed to parse {}", path.display())))?;
+        
+        ASTBuilder::build_from_tree(&tree, &content).map(Some)
     }
 }
 
@@ -225,6 +345,9 @@ impl SessionUpdater {
             "cs" => {
                 CSharpAnalyzer::new().ok().map(|a| Box::new(a) as Box<dyn Analyzer>)
             }
+            "java" => {
+                JavaAnalyzer::new().ok().map(|a| Box::new(a) as Box<dyn Analyzer>)
+            }
             _ => None
         }
     }
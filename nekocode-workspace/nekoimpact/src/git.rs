@@ -0,0 +1,84 @@
+//! Git helpers for diff-based impact analysis
+//!
+//! All paths returned here are relative to the directory the command was run
+//! from (the session base path), not to the git root, so sessions created for
+//! a subdirectory of a repository line up with their own analysis results.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use nekocode_core::{Result, NekocodeError};
+
+/// Run a git command in `dir` and return its stdout
+fn run_git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| NekocodeError::Impact(format!("Failed to run git: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(NekocodeError::Impact(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// List files changed between `compare_ref` and the working tree, limited to
+/// `base_dir` and relative to it
+pub fn changed_files(base_dir: &Path, compare_ref: &str) -> Result<Vec<PathBuf>> {
+    let output = run_git(base_dir, &["diff", "--name-only", "--relative", compare_ref, "--"])?;
+    Ok(parse_name_only(&output))
+}
+
+/// Read the content of `relative_path` (relative to `base_dir`) at `git_ref`.
+/// Returns `None` if the file did not exist at that ref.
+pub fn show_file(base_dir: &Path, git_ref: &str, relative_path: &Path) -> Result<Option<String>> {
+    let spec = format!("{}:./{}", git_ref, to_git_path(relative_path));
+    match run_git(base_dir, &["show", &spec]) {
+        Ok(content) => Ok(Some(content)),
+        Err(_) => {
+            // Distinguish a missing file from an invalid ref
+            run_git(base_dir, &["rev-parse", "--verify", &format!("{}^{{commit}}", git_ref)])?;
+            Ok(None)
+        }
+    }
+}
+
+/// Parse `git diff --name-only` output
+fn parse_name_only(output: &str) -> Vec<PathBuf> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// Git always uses forward slashes in tree paths
+fn to_git_path(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_name_only() {
+        let files = parse_name_only("src/lib.rs\n\n  src/main.rs  \n");
+        assert_eq!(files, vec![PathBuf::from("src/lib.rs"), PathBuf::from("src/main.rs")]);
+    }
+
+    #[test]
+    fn test_to_git_path() {
+        assert_eq!(to_git_path(Path::new("src/core/impact.rs")), "src/core/impact.rs");
+    }
+}
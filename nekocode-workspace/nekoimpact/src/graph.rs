@@ -0,0 +1,277 @@
+//! File dependency graph built from resolved imports
+//!
+//! Nodes are the files of a session and edges are imports that resolve to
+//! another file of the same session. Edges that take part in an import cycle
+//! are highlighted when the graph is rendered.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+
+use nekocode_core::{resolve_import_path, AnalysisResult, NekocodeError, Result};
+
+/// Output format for dependency graphs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GraphFormat {
+    Dot,
+    Mermaid,
+    Json,
+}
+
+impl std::str::FromStr for GraphFormat {
+    type Err = NekocodeError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "dot" | "graphviz" => Ok(GraphFormat::Dot),
+            "mermaid" | "mmd" => Ok(GraphFormat::Mermaid),
+            "json" => Ok(GraphFormat::Json),
+            _ => Err(NekocodeError::Config(format!("Invalid graph format: {} (expected dot, mermaid or json)", s))),
+        }
+    }
+}
+
+/// Dependency graph between the files of a session
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DependencyGraph {
+    /// File paths, as stored in the session
+    pub nodes: BTreeSet<String>,
+    /// Adjacency list: file -> files it imports
+    pub edges: BTreeMap<String, BTreeSet<String>>,
+    /// Detected import cycles, each listed in import order
+    pub cycles: Vec<Vec<String>>,
+}
+
+impl DependencyGraph {
+    /// Build the graph from session analysis results
+    pub fn from_results(results: &[AnalysisResult]) -> Self {
+        let mut graph = DependencyGraph::default();
+
+        // Map every spelling of a session file path back to its node name
+        let mut lookup: HashMap<PathBuf, String> = HashMap::new();
+        for result in results {
+            let name = result.file_info.path.to_string_lossy().to_string();
+            lookup.insert(normalize_path(&result.file_info.path), name.clone());
+            if let Ok(canonical) = result.file_info.path.canonicalize() {
+                lookup.insert(canonical, name.clone());
+            }
+            graph.nodes.insert(name);
+        }
+
+        for result in results {
+            let from = result.file_info.path.to_string_lossy().to_string();
+            let language = result.file_info.language;
+
+            for import in &result.imports {
+                let Some(resolved) = resolve_import_path(&import.module, &result.file_info.path, language) else {
+                    continue;
+                };
+
+                let target = resolved.canonicalize().ok()
+                    .and_then(|canonical| lookup.get(&canonical))
+                    .or_else(|| lookup.get(&normalize_path(&resolved)));
+
+                if let Some(to) = target {
+                    if *to != from {
+                        graph.edges.entry(from.clone()).or_default().insert(to.clone());
+                    }
+                }
+            }
+        }
+
+        graph.cycles = graph.find_cycles();
+        graph
+    }
+
+    /// Number of edges in the graph
+    pub fn edge_count(&self) -> usize {
+        self.edges.values().map(|targets| targets.len()).sum()
+    }
+
+    /// Find import cycles using DFS
+    fn find_cycles(&self) -> Vec<Vec<String>> {
+        let mut cycles = Vec::new();
+        let mut visited = HashSet::new();
+
+        for node in &self.nodes {
+            if !visited.contains(node) {
+                let mut stack = Vec::new();
+                self.detect_cycle_dfs(node, &mut visited, &mut stack, &mut cycles);
+            }
+        }
+
+        cycles
+    }
+
+    /// DFS helper: `stack` holds the current import chain
+    fn detect_cycle_dfs(
+        &self,
+        file: &str,
+        visited: &mut HashSet<String>,
+        stack: &mut Vec<String>,
+        cycles: &mut Vec<Vec<String>>,
+    ) {
+        visited.insert(file.to_string());
+        stack.push(file.to_string());
+
+        if let Some(deps) = self.edges.get(file) {
+            for dep in deps {
+                if let Some(pos) = stack.iter().position(|f| f == dep) {
+                    // Back edge: the chain from `dep` to `file` is a cycle
+                    cycles.push(stack[pos..].to_vec());
+                } else if !visited.contains(dep) {
+                    self.detect_cycle_dfs(dep, visited, stack, cycles);
+                }
+            }
+        }
+
+        stack.pop();
+    }
+
+    /// Edges that belong to a detected cycle
+    fn cycle_edges(&self) -> HashSet<(&str, &str)> {
+        let mut edges = HashSet::new();
+        for cycle in &self.cycles {
+            for (i, from) in cycle.iter().enumerate() {
+                let to = &cycle[(i + 1) % cycle.len()];
+                edges.insert((from.as_str(), to.as_str()));
+            }
+        }
+        edges
+    }
+
+    /// Render in the requested format
+    pub fn render(&self, format: GraphFormat) -> String {
+        match format {
+            GraphFormat::Dot => self.to_dot(),
+            GraphFormat::Mermaid => self.to_mermaid(),
+            GraphFormat::Json => serde_json::to_string_pretty(self).unwrap_or_default(),
+        }
+    }
+
+    /// Render as Graphviz DOT
+    pub fn to_dot(&self) -> String {
+        let cycle_edges = self.cycle_edges();
+        let cycle_nodes: HashSet<&str> = self.cycles.iter().flatten().map(String::as_str).collect();
+
+        let mut out = String::from("digraph dependencies {\n");
+        out.push_str("    rankdir=LR;\n");
+        out.push_str("    node [shape=box, fontname=\"Helvetica\"];\n\n");
+
+        for node in &self.nodes {
+            if cycle_nodes.contains(node.as_str()) {
+                out.push_str(&format!("    \"{}\" [color=\"red\"];\n", escape_dot(node)));
+            } else {
+                out.push_str(&format!("    \"{}\";\n", escape_dot(node)));
+            }
+        }
+
+        if !self.edges.is_empty() {
+            out.push('\n');
+        }
+
+        for (from, targets) in &self.edges {
+            for to in targets {
+                let style = if cycle_edges.contains(&(from.as_str(), to.as_str())) {
+                    " [color=\"red\", style=\"bold\", penwidth=2]"
+                } else {
+                    ""
+                };
+                out.push_str(&format!("    \"{}\" -> \"{}\"{};\n", escape_dot(from), escape_dot(to), style));
+            }
+        }
+
+        out.push_str("}\n");
+        out
+    }
+
+    /// Render as a Mermaid flowchart
+    pub fn to_mermaid(&self) -> String {
+        let cycle_edges = self.cycle_edges();
+        let ids: HashMap<&str, String> = self.nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.as_str(), format!("n{}", i)))
+            .collect();
+
+        let mut out = String::from("graph LR\n");
+
+        for node in &self.nodes {
+            out.push_str(&format!("    {}[\"{}\"]\n", ids[node.as_str()], node.replace('"', "#quot;")));
+        }
+
+        let mut cycle_links = Vec::new();
+        let mut link_index = 0;
+        for (from, targets) in &self.edges {
+            for to in targets {
+                out.push_str(&format!("    {} --> {}\n", ids[from.as_str()], ids[to.as_str()]));
+                if cycle_edges.contains(&(from.as_str(), to.as_str())) {
+                    cycle_links.push(link_index.to_string());
+                }
+                link_index += 1;
+            }
+        }
+
+        if !cycle_links.is_empty() {
+            out.push_str(&format!("    linkStyle {} stroke:#e53935,stroke-width:3px\n", cycle_links.join(",")));
+        }
+
+        out
+    }
+}
+
+/// Lexically normalize a path (resolve `.` and `..` without touching the filesystem)
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push("..");
+                }
+            }
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+    normalized
+}
+
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(edges: &[(&str, &str)]) -> DependencyGraph {
+        let mut graph = DependencyGraph::default();
+        for (from, to) in edges {
+            graph.nodes.insert(from.to_string());
+            graph.nodes.insert(to.to_string());
+            graph.edges.entry(from.to_string()).or_default().insert(to.to_string());
+        }
+        graph.cycles = graph.find_cycles();
+        graph
+    }
+
+    #[test]
+    fn test_detects_cycle() {
+        let g = graph(&[("a.js", "b.js"), ("b.js", "c.js"), ("c.js", "a.js"), ("c.js", "d.js")]);
+        assert_eq!(g.cycles, vec![vec!["a.js".to_string(), "b.js".to_string(), "c.js".to_string()]]);
+
+        let dot = g.to_dot();
+        assert!(dot.contains("\"c.js\" -> \"a.js\" [color=\"red\""));
+        assert!(dot.contains("\"c.js\" -> \"d.js\";"));
+
+        let mermaid = g.to_mermaid();
+        assert!(mermaid.starts_with("graph LR\n"));
+        assert!(mermaid.contains("linkStyle 0,1,2 stroke"));
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(normalize_path(Path::new("src/./lib/../util.js")), PathBuf::from("src/util.js"));
+    }
+}
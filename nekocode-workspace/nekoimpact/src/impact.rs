@@ -10,6 +10,9 @@ use nekocode_core::{
     FunctionInfo, ClassInfo, SymbolInfo, Result, NekocodeError
 };
 
+use crate::git;
+use crate::graph::DependencyGraph;
+
 /// Risk levels for impact assessment
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum RiskLevel {
@@ -194,6 +197,59 @@ impl ImpactAnalyzer {
         self.compare_analysis_results(&base_results, &head_results)
     }
     
+    /// Analyze impact of the changes between `compare_ref` and the working tree
+    /// for the files covered by a session.
+    ///
+    /// The session's base path may be a subdirectory of the git repository;
+    /// only changes below it are considered. The session only supplies the
+    /// base path: its stored analysis may predate the working tree, so the
+    /// current content of each changed file is analyzed instead.
+    pub async fn analyze_git_diff(&mut self, session_id: &str, compare_ref: &str) -> Result<ImpactResult> {
+        let session_path = self.session_manager.get_session_mut(session_id)?.info.path.clone();
+        
+        let base_dir = session_path.canonicalize()
+            .map_err(|e| NekocodeError::Impact(format!(
+                "Session path {} is not accessible: {}", session_path.display(), e
+            )))?;
+        
+        let changed_files = git::changed_files(&base_dir, compare_ref)?;
+        
+        let mut base_results = Vec::new();
+        let mut head_results = Vec::new();
+        
+        for relative_path in changed_files {
+            let language = Language::from_path(&relative_path);
+            if language == Language::Unknown {
+                continue;
+            }
+            
+            // Report paths the same way the session stores them
+            let display_path = session_path.join(&relative_path);
+            
+            // Head: the file as it is in the working tree, if it still exists
+            let disk_path = base_dir.join(&relative_path);
+            if disk_path.is_file() {
+                let content = std::fs::read_to_string(&disk_path)?;
+                head_results.push(analyze_content(language, &display_path, &content).await?);
+            }
+            
+            // Base: analyze the file as it was at the compare ref
+            if let Some(content) = git::show_file(&base_dir, compare_ref, &relative_path)? {
+                base_results.push(analyze_content(language, &display_path, &content).await?);
+            }
+        }
+        
+        let mut result = self.compare_analysis_results(&base_results, &head_results)?;
+        result.risk_assessment.affected_file_count = result.affected_files.len();
+        Ok(result)
+    }
+    
+    /// Build the file dependency graph of a session from its resolved imports
+    pub fn dependency_graph(&mut self, session_id: &str) -> Result<DependencyGraph> {
+        let session = self.session_manager.get_session_mut(session_id)?;
+        Ok(DependencyGraph::from_results(&session.info.analysis_results))
+    }
+    
     /// Internal analysis implementation
     fn analyze_results_internal(&self, analysis_results: &[AnalysisResult]) -> Result<ImpactResult> {
         let mut changed_symbols = Vec::new();
@@ -527,4 +583,54 @@ impl ImpactAnalyzer {
             recommendation,
         }
     }
-}
\ No newline at end of file
+}
+
+/// Analyze file content with the language's analyzer
+async fn analyze_content(language: Language, path: &Path, content: &str) -> Result<AnalysisResult> {
+    let mut analyzer = nekocode::create_analyzer(language)?;
+    analyzer.analyze(path, content).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nekocode_core::SessionInfo;
+    
+    #[tokio::test]
+    async fn test_git_diff_analyzes_working_tree_in_subdirectory() {
+        let repo = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .current_dir(repo.path())
+                .status()
+                .unwrap();
+            assert!(status.success(), "git {:?} failed", args);
+        };
+        let app = repo.path().join("app");
+        std::fs::create_dir(&app).unwrap();
+        let committed = "function greet(name) { return name; }\n";
+        std::fs::write(app.join("lib.js"), committed).unwrap();
+        git(&["init", "-q"]);
+        git(&["add", "."]);
+        git(&["commit", "-qm", "initial"]);
+        
+        // The session was analyzed before the latest edit
+        let mut info = SessionInfo::new(SessionManager::generate_session_id(), app.clone());
+        info.analysis_results.push(analyze_content(Language::JavaScript, &app.join("lib.js"), committed).await.unwrap());
+        std::fs::write(app.join("lib.js"), "function greet(name) { return name; }\nfunction wave() {}\n").unwrap();
+        
+        let mut analyzer = ImpactAnalyzer {
+            session_manager: SessionManager::with_dir(repo.path().join(".nekocode_sessions")),
+        };
+        let id = analyzer.session_manager.save_session_info(info).unwrap();
+        let result = analyzer.analyze_git_diff(&id, "HEAD").await.unwrap();
+        
+        let changes: Vec<(&str, &ChangeType)> = result.changed_symbols.iter()
+            .map(|s| (s.name.as_str(), &s.change_type))
+            .collect();
+        assert_eq!(changes, vec![("wave", &ChangeType::FunctionAdded)]);
+        assert_eq!(result.changed_symbols[0].file_path, app.join("lib.js"));
+    }
+}
@@ -3,6 +3,9 @@
 pub mod impact;
 pub mod analyzer;
 pub mod cli;
+pub mod git;
+pub mod graph;
 
 pub use impact::{ImpactAnalyzer, ImpactResult, RiskLevel, ChangeType, ChangedSymbol};
-pub use analyzer::AnalysisOptions;
\ No newline at end of file
+pub use analyzer::AnalysisOptions;
+pub use graph::{DependencyGraph, GraphFormat};
\ No newline at end of file
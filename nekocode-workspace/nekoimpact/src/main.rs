@@ -3,12 +3,15 @@
 mod impact;
 mod analyzer;
 mod cli;
+mod git;
+mod graph;
 
 use clap::Parser;
 use nekocode_core::{SessionManager, Result, NekocodeError};
 use crate::cli::{Cli, Commands};
 use crate::impact::{ImpactAnalyzer, ImpactResult};
 use crate::analyzer::{AnalysisOptions, OutputFormat};
+use crate::graph::GraphFormat;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -43,17 +46,26 @@ async fn main() -> Result<()> {
             let output_format = OutputFormat::from_str(&format)
                 .ok_or_else(|| NekocodeError::Config(format!("Invalid format: {}", format)))?;
             
-            // TODO: Implement Git integration
-            println!("Git diff analysis not yet implemented");
-            println!("Session: {}, Compare ref: {}", session_id, compare_ref);
+            let result = analyzer.analyze_git_diff(&session_id, &compare_ref).await?;
+            print_result(&result, output_format, cli.verbose);
         }
         
         Commands::Graph { session_id, output, graph_format } => {
-            // TODO: Implement graph generation
-            println!("Graph generation not yet implemented");
-            println!("Session: {}, Format: {}", session_id, graph_format);
+            let format: GraphFormat = graph_format.parse()?;
+            
+            let graph = analyzer.dependency_graph(&session_id)?;
+            let rendered = graph.render(format);
+            
             if let Some(output) = output {
-                println!("Output: {}", output.display());
+                std::fs::write(&output, rendered)?;
+                eprintln!("✅ Wrote dependency graph ({} files, {} edges, {} cycles) to {}",
+                    graph.nodes.len(),
+                    graph.edge_count(),
+                    graph.cycles.len(),
+                    output.display()
+                );
+            } else {
+                print!("{}", rendered);
             }
         }
         
@@ -39,6 +39,15 @@ pub enum Commands {
         /// Debounce time in milliseconds
         #[arg(short, long, default_value = "500")]
         debounce: u64,
+        
+        /// Shell command to run after each re-analysis; NEKOCODE_CHANGED_FILES
+        /// holds the changed paths, one per line
+        #[arg(long, value_name = "COMMAND")]
+        exec: Option<String>,
+        
+        /// When changes arrive while the command runs: queue (run again after it) or cancel (restart it)
+        #[arg(long, default_value = "queue", requires = "exec")]
+        on_busy: String,
     },
     
     /// Stop watching a session
@@ -75,6 +84,19 @@ pub enum Commands {
         session_id: String,
     },
     
+    /// Show the recorded change history of a file
+    History {
+        /// Session ID
+        session_id: String,
+        
+        /// File path (relative to the session root)
+        file: PathBuf,
+        
+        /// Output format (text, json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+    
     /// Reset incremental tracking for a session
     Reset {
         /// Session ID to reset
@@ -1,13 +1,17 @@
 //! Incremental analysis engine for detecting and processing file changes
 
 use std::collections::HashMap;
-use std::path::{Path, PathBuf};
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
 use std::fs;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use walkdir::WalkDir;
 
-use nekocode_core::{Result, NekocodeError, SessionManager};
+use nekocode::create_analyzer;
+use nekocode_core::{Result, NekocodeError, SessionManager, SESSION_DIR};
+use nekocode_core::session::SessionInfo;
+use nekocode_core::types::Language;
 
 /// File metadata for change detection
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -91,6 +95,109 @@ pub struct FileChange {
     pub current_metadata: Option<FileMetadata>,
 }
 
+/// One detected change in a file's history
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChangeHistoryEntry {
+    /// When the change was detected
+    pub timestamp: DateTime<Utc>,
+    /// File path relative to session root
+    pub path: PathBuf,
+    /// Type of change
+    pub change_type: ChangeType,
+    /// Content hash before the change (None for Added files)
+    pub old_hash: Option<String>,
+    /// Content hash after the change (None for Deleted files)
+    pub new_hash: Option<String>,
+}
+
+impl ChangeHistoryEntry {
+    /// Create a history entry for a detected change
+    pub fn from_change(change: &FileChange, timestamp: DateTime<Utc>) -> Self {
+        Self {
+            timestamp,
+            path: change.path.clone(),
+            change_type: change.change_type.clone(),
+            old_hash: change.previous_metadata.as_ref().map(|m| m.content_hash.clone()),
+            new_hash: change.current_metadata.as_ref().map(|m| m.content_hash.clone()),
+        }
+    }
+}
+
+/// Append-only change history stored as JSONL (one entry per line)
+#[derive(Debug, Clone)]
+pub struct ChangeHistory {
+    path: PathBuf,
+}
+
+impl ChangeHistory {
+    /// Use a history file at an explicit path
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+    
+    /// History file for a session, stored next to the session file
+    pub fn for_session(session_id: &str) -> Self {
+        Self::new(PathBuf::from(SESSION_DIR).join(format!("{}.history.jsonl", session_id)))
+    }
+    
+    /// Append detected changes to the history
+    pub fn append(&self, changes: &[FileChange]) -> Result<()> {
+        if changes.is_empty() {
+            return Ok(());
+        }
+        
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(NekocodeError::Io)?;
+        }
+        
+        let timestamp = Utc::now();
+        let mut lines = String::new();
+        for change in changes {
+            lines.push_str(&serde_json::to_string(&ChangeHistoryEntry::from_change(change, timestamp))?);
+            lines.push('\n');
+        }
+        
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(NekocodeError::Io)?;
+        file.write_all(lines.as_bytes()).map_err(NekocodeError::Io)?;
+        
+        Ok(())
+    }
+    
+    /// Read all entries in the order they were recorded (malformed lines are skipped)
+    pub fn entries(&self) -> Result<Vec<ChangeHistoryEntry>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        
+        let content = fs::read_to_string(&self.path).map_err(NekocodeError::Io)?;
+        let entries = content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match serde_json::from_str(line) {
+                Ok(entry) => Some(entry),
+                Err(e) => {
+                    log::warn!("Skipping malformed history entry in {}: {}", self.path.display(), e);
+                    None
+                }
+            })
+            .collect();
+        
+        Ok(entries)
+    }
+    
+    /// Entries recorded for a single file (path relative to session root)
+    pub fn entries_for(&self, file_path: &Path) -> Result<Vec<ChangeHistoryEntry>> {
+        Ok(self.entries()?
+            .into_iter()
+            .filter(|entry| entry.path == file_path)
+            .collect())
+    }
+}
+
 /// Core change detection engine
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChangeDetector {
@@ -223,7 +330,8 @@ impl ChangeDetector {
                 "py" | "pyw" | "pyi" |
                 "cs" |
                 "go" |
-                "rs"
+                "rs" |
+                "java"
             )
         } else {
             false
@@ -261,6 +369,12 @@ pub struct IncrementalSummary {
     pub analysis_time_ms: u64,
     /// Estimated speedup compared to full analysis
     pub estimated_speedup: f64,
+    /// Number of files actually re-parsed
+    #[serde(default)]
+    pub reparsed_files: usize,
+    /// Added/modified files that could not be read or analyzed
+    #[serde(default)]
+    pub failed_files: Vec<PathBuf>,
 }
 
 impl IncrementalSummary {
@@ -294,12 +408,28 @@ impl IncrementalSummary {
             deleted_files,
             analysis_time_ms,
             estimated_speedup,
+            reparsed_files: 0,
+            failed_files: Vec::new(),
         }
     }
     
+    /// Create a summary for an incremental update that re-parsed `reparsed_files`
+    /// files; the speedup is the ratio of total files to re-parsed files
+    pub fn from_reparse(
+        total_files: usize,
+        changes: &[FileChange],
+        reparsed_files: usize,
+        analysis_time_ms: u64,
+    ) -> Self {
+        let mut summary = Self::new(total_files, changes, analysis_time_ms, 0);
+        summary.reparsed_files = reparsed_files;
+        summary.estimated_speedup = total_files.max(1) as f64 / reparsed_files.max(1) as f64;
+        summary
+    }
+    
     /// Format summary for display
     pub fn format_summary(&self) -> String {
-        format!(
+        let mut summary = format!(
             "⚡ Updated {} files in {}ms ({:.1}x speedup)\n\
              📊 Changes: {} modified, {} added, {} deleted\n\
              📁 Total files in session: {}",
@@ -310,7 +440,11 @@ impl IncrementalSummary {
             self.added_files,
             self.deleted_files,
             self.total_files
-        )
+        );
+        for path in &self.failed_files {
+            summary.push_str(&format!("\n⚠️  Failed to analyze: {}", path.display()));
+        }
+        summary
     }
 }
 
@@ -345,6 +479,12 @@ impl IncrementalAnalyzer {
     
     /// Detect and analyze changes for a session
     pub async fn analyze_changes(&mut self, session_id: &str) -> Result<IncrementalSummary> {
+        self.apply_changes(session_id).await
+    }
+    
+    /// Detect changes and re-parse only the added/modified files into the
+    /// stored session, dropping results for deleted files
+    pub async fn apply_changes(&mut self, session_id: &str) -> Result<IncrementalSummary> {
         let start_time = std::time::Instant::now();
         
         // Get or create detector
@@ -366,6 +506,8 @@ impl IncrementalAnalyzer {
                 deleted_files: 0,
                 analysis_time_ms: start_time.elapsed().as_millis() as u64,
                 estimated_speedup: 1.0,
+                reparsed_files: 0,
+                failed_files: Vec::new(),
             });
         }
         
@@ -385,25 +527,232 @@ impl IncrementalAnalyzer {
             }
         }
         
-        // TODO: Trigger actual re-analysis of changed files
-        // This would call into the main nekocode analyzer
-        // For now, we just report the changes
+        ChangeHistory::for_session(session_id).append(&changes)?;
         
-        let analysis_time_ms = start_time.elapsed().as_millis() as u64;
-        
-        // Estimate full analysis time (rough estimate: 50ms per file)
-        let estimated_full_time = detector.file_count() as u64 * 50;
+        let session = self.session_manager.get_session_mut(session_id)?;
+        let applied = apply_file_changes(&mut session.info, &changes).await?;
+        let total_files = session.info.analysis_results.len();
+        session.save()?;
         
-        Ok(IncrementalSummary::new(
-            detector.file_count(),
+        let mut summary = IncrementalSummary::from_reparse(
+            total_files,
             &changes,
-            analysis_time_ms,
-            estimated_full_time,
-        ))
+            applied.reparsed,
+            start_time.elapsed().as_millis() as u64,
+        );
+        summary.failed_files = applied.failed;
+        Ok(summary)
     }
     
     /// Get change detector for a session
     pub fn get_detector(&self, session_id: &str) -> Option<&ChangeDetector> {
         self.detectors.get(session_id)
     }
+    
+    /// Get the recorded change history of a file, oldest first.
+    /// `file_path` may be relative to the session root or include the session path.
+    pub fn history(&mut self, session_id: &str, file_path: &Path) -> Result<Vec<ChangeHistoryEntry>> {
+        let session = self.session_manager.get_session(session_id)?;
+        let relative = session_relative_path(&session.info.path, file_path);
+        
+        ChangeHistory::for_session(session_id).entries_for(&relative)
+    }
+}
+
+/// Outcome of splicing detected changes into a session
+#[derive(Debug, Default)]
+pub struct AppliedChanges {
+    /// Number of files re-parsed
+    pub reparsed: usize,
+    /// Added/modified files that could not be read or analyzed
+    pub failed: Vec<PathBuf>,
+}
+
+/// Splice detected changes into a session: re-parse added/modified files and
+/// remove results for deleted ones. A file that fails to re-parse loses its
+/// stale result, since the detector has already recorded its new hash.
+pub async fn apply_file_changes(info: &mut SessionInfo, changes: &[FileChange]) -> Result<AppliedChanges> {
+    let base_path = info.path.clone();
+    let mut applied = AppliedChanges::default();
+    
+    for change in changes {
+        let index = info.analysis_results.iter().position(|r| {
+            r.file_info.path.strip_prefix(&base_path).unwrap_or(&r.file_info.path) == change.path
+        });
+        
+        let result = match change.change_type {
+            ChangeType::Added | ChangeType::Modified => {
+                let full_path = base_path.join(&change.path);
+                let language = Language::from_path(&full_path);
+                if language == Language::Unknown {
+                    continue;
+                }
+                
+                let analyzed = match fs::read_to_string(&full_path) {
+                    Ok(content) => {
+                        let mut analyzer = create_analyzer(language)?;
+                        analyzer.analyze(&full_path, &content).await
+                    }
+                    Err(e) => Err(e.into()),
+                };
+                
+                match analyzed {
+                    Ok(mut result) => {
+                        applied.reparsed += 1;
+                        result.file_info.hash = change.current_metadata.as_ref().map(|m| m.content_hash.clone());
+                        Some(result)
+                    }
+                    Err(e) => {
+                        log::warn!("Failed to analyze {}: {}", full_path.display(), e);
+                        applied.failed.push(change.path.clone());
+                        None
+                    }
+                }
+            }
+            ChangeType::Deleted => None,
+        };
+        
+        info.splice_result(index, result);
+    }
+    
+    Ok(applied)
+}
+
+/// Normalize a user-supplied path to the form stored in history (relative to
+/// `base_path`, without `.` components)
+fn session_relative_path(base_path: &Path, file_path: &Path) -> PathBuf {
+    let normalize = |path: &Path| -> PathBuf {
+        path.components().filter(|c| !matches!(c, Component::CurDir)).collect()
+    };
+    
+    let base = normalize(base_path);
+    let file = normalize(file_path);
+    file.strip_prefix(&base).map(Path::to_path_buf).unwrap_or(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    
+    fn change(path: &str, change_type: ChangeType, old_hash: Option<&str>, new_hash: Option<&str>) -> FileChange {
+        let metadata = |hash: &str| FileMetadata {
+            path: PathBuf::from(path),
+            modified_time: Utc::now(),
+            content_hash: hash.to_string(),
+            size: 0,
+        };
+        FileChange {
+            path: PathBuf::from(path),
+            change_type,
+            previous_metadata: old_hash.map(metadata),
+            current_metadata: new_hash.map(metadata),
+        }
+    }
+    
+    #[test]
+    fn test_change_history_append_and_filter() {
+        let temp_dir = TempDir::new().unwrap();
+        let history = ChangeHistory::new(temp_dir.path().join("sessions/abc.history.jsonl"));
+        
+        assert!(history.entries().unwrap().is_empty());
+        
+        history.append(&[
+            change("src/a.js", ChangeType::Added, None, Some("1")),
+            change("src/b.js", ChangeType::Added, None, Some("2")),
+        ]).unwrap();
+        history.append(&[change("src/a.js", ChangeType::Modified, Some("1"), Some("3"))]).unwrap();
+        history.append(&[change("src/a.js", ChangeType::Deleted, Some("3"), None)]).unwrap();
+        
+        assert_eq!(history.entries().unwrap().len(), 4);
+        
+        let a = history.entries_for(Path::new("src/a.js")).unwrap();
+        let types: Vec<_> = a.iter().map(|e| e.change_type.clone()).collect();
+        assert_eq!(types, vec![ChangeType::Added, ChangeType::Modified, ChangeType::Deleted]);
+        assert_eq!(a[1].old_hash.as_deref(), Some("1"));
+        assert_eq!(a[1].new_hash.as_deref(), Some("3"));
+        assert_eq!(a[2].new_hash, None);
+    }
+    
+    #[tokio::test]
+    async fn test_apply_changes_reparses_only_changed_files() {
+        let temp_dir = TempDir::new().unwrap();
+        for i in 0..10 {
+            fs::write(temp_dir.path().join(format!("mod{}.js", i)), format!("function f{}() {{}}\n", i)).unwrap();
+        }
+        
+        let mut info = SessionInfo::new("inc".to_string(), temp_dir.path().to_path_buf());
+        let mut detector = ChangeDetector::new(temp_dir.path().to_path_buf());
+        
+        // Everything is new on the first pass
+        let changes = detector.detect_changes().unwrap();
+        assert_eq!(apply_file_changes(&mut info, &changes).await.unwrap().reparsed, 10);
+        assert_eq!(info.file_count, 10);
+        let analyzed_at: Vec<_> = info.analysis_results.iter().map(|r| r.file_info.analyzed_at).collect();
+        
+        // Modify one file of ten
+        fs::write(temp_dir.path().join("mod3.js"), "function f3() {}\nfunction g3() {}\n").unwrap();
+        let changes = detector.detect_changes().unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(apply_file_changes(&mut info, &changes).await.unwrap().reparsed, 1);
+        
+        assert_eq!(info.file_count, 10);
+        let modified = info.analysis_results.iter()
+            .position(|r| r.file_info.path.ends_with("mod3.js"))
+            .unwrap();
+        assert_eq!(info.analysis_results[modified].functions.len(), 2);
+        for (i, result) in info.analysis_results.iter().enumerate() {
+            if i != modified {
+                assert_eq!(result.file_info.analyzed_at, analyzed_at[i]);
+            }
+        }
+        
+        let summary = IncrementalSummary::from_reparse(info.file_count, &changes, 1, 5);
+        assert_eq!(summary.reparsed_files, 1);
+        assert_eq!(summary.estimated_speedup, 10.0);
+        
+        // Deleting a file drops its result and adjusts the statistics
+        let total_lines = info.total_lines;
+        let removed_lines = info.analysis_results[modified].metrics.lines_of_code;
+        fs::remove_file(temp_dir.path().join("mod3.js")).unwrap();
+        let changes = detector.detect_changes().unwrap();
+        assert_eq!(apply_file_changes(&mut info, &changes).await.unwrap().reparsed, 0);
+        assert_eq!(info.file_count, 9);
+        assert_eq!(info.total_lines, total_lines - removed_lines);
+        assert_eq!(info.languages.get(&Language::JavaScript), Some(&9));
+    }
+    
+    #[tokio::test]
+    async fn test_apply_changes_drops_stale_result_on_failure() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("a.js"), "function a() {}\n").unwrap();
+        fs::write(temp_dir.path().join("b.js"), "function b() {}\n").unwrap();
+        
+        let mut info = SessionInfo::new("inc".to_string(), temp_dir.path().to_path_buf());
+        let mut detector = ChangeDetector::new(temp_dir.path().to_path_buf());
+        let changes = detector.detect_changes().unwrap();
+        assert_eq!(apply_file_changes(&mut info, &changes).await.unwrap().reparsed, 2);
+        
+        // Invalid UTF-8 can't be read back, but the detector has already hashed it
+        fs::write(temp_dir.path().join("a.js"), [0xff, 0xfe, 0x00]).unwrap();
+        let changes = detector.detect_changes().unwrap();
+        let applied = apply_file_changes(&mut info, &changes).await.unwrap();
+        assert_eq!(applied.reparsed, 0);
+        assert_eq!(applied.failed, vec![PathBuf::from("a.js")]);
+        
+        assert_eq!(info.file_count, 1);
+        assert!(info.analysis_results.iter().all(|r| !r.file_info.path.ends_with("a.js")));
+        assert!(info.file_hashes.keys().all(|p| !p.ends_with("a.js")));
+        
+        let mut summary = IncrementalSummary::from_reparse(info.file_count, &changes, applied.reparsed, 5);
+        summary.failed_files = applied.failed;
+        assert!(summary.format_summary().contains("Failed to analyze: a.js"));
+    }
+    
+    #[test]
+    fn test_session_relative_path() {
+        assert_eq!(session_relative_path(Path::new("."), Path::new("./src/a.js")), PathBuf::from("src/a.js"));
+        assert_eq!(session_relative_path(Path::new("/repo"), Path::new("/repo/src/a.js")), PathBuf::from("src/a.js"));
+        assert_eq!(session_relative_path(Path::new("/repo"), Path::new("src/a.js")), PathBuf::from("src/a.js"));
+    }
 }
\ No newline at end of file
@@ -6,12 +6,13 @@ pub mod cli;
 
 pub use incremental::{
     ChangeDetector, FileChange, ChangeType, FileMetadata,
-    IncrementalAnalyzer, IncrementalSummary
+    ChangeHistory, ChangeHistoryEntry,
+    IncrementalAnalyzer, IncrementalSummary, AppliedChanges
 };
 
 pub use watch::{
     FileWatcher, WatchConfig, WatchStatus, WatchState,
-    WatchManager
+    WatchManager, ExecRunner, OnBusy
 };
 
 pub use cli::Cli;
\ No newline at end of file
@@ -7,6 +7,8 @@ use nekocode_core::{Result, NekocodeError};
 use nekoinc::{
     Cli, 
     IncrementalAnalyzer,
+    OnBusy,
+    WatchConfig,
     WatchManager
 };
 use nekoinc::cli::Commands;
@@ -45,9 +47,15 @@ async fn main() -> Result<()> {
             }
         }
         
-        Commands::Watch { session_id, debounce: _ } => {
+        Commands::Watch { session_id, debounce, exec, on_busy } => {
+            let config = WatchConfig {
+                debounce_ms: debounce,
+                exec,
+                on_busy: OnBusy::parse(&on_busy)?,
+                ..WatchConfig::default()
+            };
             let mut manager = WatchManager::new()?;
-            manager.start_watch(&session_id).await?;
+            manager.start_watch(&session_id, config).await?;
             
             println!("Press Ctrl+C to stop watching...");
             
@@ -155,6 +163,32 @@ async fn main() -> Result<()> {
             }
         }
         
+        Commands::History { session_id, file, format } => {
+            let mut analyzer = IncrementalAnalyzer::new()?;
+            let history = analyzer.history(&session_id, &file)?;
+            
+            match format.as_str() {
+                "json" => {
+                    println!("{}", serde_json::to_string_pretty(&history)?);
+                }
+                _ => {
+                    if history.is_empty() {
+                        println!("No recorded changes for {} in session {}", file.display(), session_id);
+                    } else {
+                        println!("📜 Change history for {} ({} changes):", file.display(), history.len());
+                        for entry in &history {
+                            println!("  {} {:?} {} -> {}",
+                                entry.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                                entry.change_type,
+                                entry.old_hash.as_deref().unwrap_or("-"),
+                                entry.new_hash.as_deref().unwrap_or("-")
+                            );
+                        }
+                    }
+                }
+            }
+        }
+        
         Commands::Reset { session_id } => {
             let mut analyzer = IncrementalAnalyzer::new()?;
             analyzer.initialize_session(&session_id)?;
@@ -1,10 +1,14 @@
 //! File watching system with auto session updates
 
+use std::collections::BTreeSet;
 use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::time::{Duration, Instant};
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::process::{Child, Command};
+use tokio::sync::{mpsc as tokio_mpsc, Mutex};
+use tokio::task::JoinHandle;
 use notify::{Watcher, RecursiveMode, Event, EventKind};
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
@@ -37,6 +41,31 @@ pub struct WatchConfig {
     pub max_events_per_second: usize,
     pub exclude_patterns: Vec<String>,
     pub include_extensions: Vec<String>,
+    /// Shell command run after each re-analysis
+    pub exec: Option<String>,
+    pub on_busy: OnBusy,
+}
+
+/// What happens to changes that arrive while the `exec` command still runs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OnBusy {
+    /// Run again after the current run, once for all changes in between
+    #[default]
+    Queue,
+    /// Kill the current run and start over
+    Cancel,
+}
+
+impl OnBusy {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "queue" => Ok(Self::Queue),
+            "cancel" => Ok(Self::Cancel),
+            _ => Err(NekocodeError::Config(
+                format!("Invalid --on-busy value '{}': expected queue or cancel", value)
+            )),
+        }
+    }
 }
 
 impl Default for WatchConfig {
@@ -74,7 +103,10 @@ impl Default for WatchConfig {
                 "cs".to_string(),
                 "go".to_string(),
                 "rs".to_string(),
+                "java".to_string(),
             ],
+            exec: None,
+            on_busy: OnBusy::Queue,
         }
     }
 }
@@ -85,6 +117,7 @@ pub struct FileWatcher {
     session_id: String,
     session_path: PathBuf,
     incremental_analyzer: Arc<Mutex<IncrementalAnalyzer>>,
+    exec: Option<tokio_mpsc::UnboundedSender<ExecRequest>>,
 }
 
 impl FileWatcher {
@@ -99,9 +132,22 @@ impl FileWatcher {
             session_id,
             session_path,
             incremental_analyzer,
+            exec: None,
         }
     }
     
+    /// Use `config` instead of the defaults
+    pub fn with_config(mut self, config: WatchConfig) -> Self {
+        self.config = config;
+        self
+    }
+    
+    /// Hand the changed files to `runner` after each re-analysis
+    pub fn with_exec(mut self, runner: &ExecRunner) -> Self {
+        self.exec = Some(runner.requests.clone());
+        self
+    }
+    
     /// Check if a file should be watched based on configuration
     fn should_watch_file(&self, path: &Path) -> bool {
         // Check if path contains any excluded patterns
@@ -145,6 +191,7 @@ impl FileWatcher {
         let mut last_update = Instant::now();
         let debounce_duration = Duration::from_millis(self.config.debounce_ms);
         let mut pending_changes = false;
+        let mut changed_paths = BTreeSet::new();
         
         println!("👀 Started watching session {} at path: {}", self.session_id, self.session_path.display());
         println!("📁 Monitoring {} supported file types", self.config.include_extensions.len());
@@ -162,6 +209,7 @@ impl FileWatcher {
                     
                     if should_process {
                         pending_changes = true;
+                        changed_paths.extend(event.paths.iter().filter(|path| self.should_watch_file(path)).cloned());
                         last_update = Instant::now();
                         println!("📝 File change detected: {:?}", event.paths);
                     }
@@ -174,6 +222,8 @@ impl FileWatcher {
                         // Trigger incremental analysis
                         let session_id = self.session_id.clone();
                         let analyzer = Arc::clone(&self.incremental_analyzer);
+                        let exec = self.exec.clone();
+                        let changed = std::mem::take(&mut changed_paths);
                         
                         tokio::spawn(async move {
                             let mut analyzer = analyzer.lock().await;
@@ -185,6 +235,9 @@ impl FileWatcher {
                                     eprintln!("❌ Failed to analyze changes: {}", e);
                                 }
                             }
+                            if let Some(exec) = exec {
+                                let _ = exec.send(ExecRequest::Run(changed));
+                            }
                         });
                         
                         pending_changes = false;
@@ -202,11 +255,225 @@ impl FileWatcher {
     }
 }
 
+enum ExecRequest {
+    /// Run the command for these changed files
+    Run(BTreeSet<PathBuf>),
+    Stop,
+}
+
+/// Runs a watch's `exec` command in the background, one run at a time
+pub struct ExecRunner {
+    requests: tokio_mpsc::UnboundedSender<ExecRequest>,
+    task: JoinHandle<()>,
+}
+
+impl ExecRunner {
+    pub fn spawn(command: String, session_id: String, on_busy: OnBusy) -> Self {
+        let (requests, receiver) = tokio_mpsc::unbounded_channel();
+        let task = tokio::spawn(run_commands(command, session_id, on_busy, receiver));
+        Self { requests, task }
+    }
+    
+    /// Run the command for `changed`, or handle it as `on_busy` says
+    pub fn run(&self, changed: BTreeSet<PathBuf>) {
+        let _ = self.requests.send(ExecRequest::Run(changed));
+    }
+    
+    /// Kill a command that is still running and wait until it has exited
+    pub async fn stop(self) {
+        let _ = self.requests.send(ExecRequest::Stop);
+        let _ = self.task.await;
+    }
+}
+
+async fn run_commands(
+    command: String,
+    session_id: String,
+    on_busy: OnBusy,
+    mut requests: tokio_mpsc::UnboundedReceiver<ExecRequest>,
+) {
+    let mut running: Option<RunningCommand> = None;
+    let mut queued: Option<BTreeSet<PathBuf>> = None;
+    
+    loop {
+        tokio::select! {
+            request = requests.recv() => match request {
+                Some(ExecRequest::Run(changed)) => {
+                    if running.is_some() && on_busy == OnBusy::Queue {
+                        queued.get_or_insert_with(BTreeSet::new).extend(changed);
+                        continue;
+                    }
+                    if running.is_some() {
+                        println!("⏹️  Cancelling the running command for new changes");
+                        kill_command(&mut running).await;
+                    }
+                    running = start_command(&command, &session_id, &changed);
+                }
+                Some(ExecRequest::Stop) | None => {
+                    kill_command(&mut running).await;
+                    break;
+                }
+            },
+            status = wait_for_command(&mut running) => {
+                running = None;
+                match status {
+                    Ok(status) if status.success() => println!("✅ Command finished: {}", command),
+                    Ok(status) => eprintln!("❌ Command failed ({}): {}", status, command),
+                    Err(e) => eprintln!("❌ Failed to wait for command: {}", e),
+                }
+                if let Some(changed) = queued.take() {
+                    running = start_command(&command, &session_id, &changed);
+                }
+            }
+        }
+    }
+}
+
+/// How long a command gets to exit after SIGTERM before it is killed
+#[cfg(unix)]
+const KILL_GRACE: Duration = Duration::from_secs(2);
+
+/// A started command. Everything the shell starts is killed with it: on Unix
+/// the shell leads its own process group, on Windows its processes share a job.
+struct RunningCommand {
+    child: Child,
+    #[cfg(windows)]
+    job: Option<job::Job>,
+}
+
+/// Start `command` in a shell, with the changed files in `NEKOCODE_CHANGED_FILES`
+fn start_command(command: &str, session_id: &str, changed: &BTreeSet<PathBuf>) -> Option<RunningCommand> {
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    #[cfg(unix)]
+    shell.process_group(0);
+    let changed_files: Vec<_> = changed.iter().map(|path| path.to_string_lossy()).collect();
+    
+    println!("▶️  Running: {}", command);
+    let child = shell.arg(command)
+        .env("NEKOCODE_CHANGED_FILES", changed_files.join("\n"))
+        .env("NEKOCODE_SESSION_ID", session_id)
+        .kill_on_drop(true)
+        .spawn();
+    match child {
+        #[cfg(windows)]
+        Ok(child) => {
+            let job = child.raw_handle().and_then(|process| {
+                job::Job::assign(process)
+                    .map_err(|e| log::warn!("Failed to put '{}' in a job object: {}", command, e))
+                    .ok()
+            });
+            Some(RunningCommand { child, job })
+        }
+        #[cfg(not(windows))]
+        Ok(child) => Some(RunningCommand { child }),
+        Err(e) => {
+            eprintln!("❌ Failed to run '{}': {}", command, e);
+            None
+        }
+    }
+}
+
+async fn wait_for_command(running: &mut Option<RunningCommand>) -> std::io::Result<ExitStatus> {
+    match running {
+        Some(running) => running.child.wait().await,
+        None => std::future::pending().await,
+    }
+}
+
+async fn kill_command(running: &mut Option<RunningCommand>) {
+    if let Some(mut running) = running.take() {
+        #[cfg(unix)]
+        if let Some(pid) = running.child.id() {
+            // The shell's pid is its process group id (see `start_command`).
+            // Ask the group to stop first, then kill whatever is left of it.
+            unsafe {
+                libc::killpg(pid as libc::pid_t, libc::SIGTERM);
+            }
+            let _ = tokio::time::timeout(KILL_GRACE, running.child.wait()).await;
+            unsafe {
+                libc::killpg(pid as libc::pid_t, libc::SIGKILL);
+            }
+        }
+        #[cfg(windows)]
+        if let Some(job) = &running.job {
+            job.terminate();
+        }
+        // Already exited is fine; `kill` also reaps the process
+        let _ = running.child.kill().await;
+    }
+}
+
+#[cfg(windows)]
+mod job {
+    use std::io;
+    use std::os::windows::io::RawHandle;
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation,
+        SetInformationJobObject, TerminateJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+        JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+    };
+    
+    /// A job object holding a command's processes; closing it kills them too
+    pub struct Job(HANDLE);
+    
+    // The handle is owned and only used by the task running the command
+    unsafe impl Send for Job {}
+    
+    impl Job {
+        /// Create a job and assign `process` to it; processes it starts later join the job
+        pub fn assign(process: RawHandle) -> io::Result<Job> {
+            unsafe {
+                let handle = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+                if handle.is_null() {
+                    return Err(io::Error::last_os_error());
+                }
+                let job = Job(handle);
+                let mut limits: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+                limits.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+                let set = SetInformationJobObject(
+                    handle,
+                    JobObjectExtendedLimitInformation,
+                    &limits as *const _ as *const _,
+                    std::mem::size_of_val(&limits) as u32,
+                );
+                if set == 0 || AssignProcessToJobObject(handle, process as HANDLE) == 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                Ok(job)
+            }
+        }
+        
+        pub fn terminate(&self) {
+            unsafe {
+                TerminateJobObject(self.0, 1);
+            }
+        }
+    }
+    
+    impl Drop for Job {
+        fn drop(&mut self) {
+            unsafe {
+                CloseHandle(self.0);
+            }
+        }
+    }
+}
+
 /// Watch manager for handling multiple watch sessions
 pub struct WatchManager {
     session_manager: SessionManager,
     incremental_analyzer: Arc<Mutex<IncrementalAnalyzer>>,
     active_watchers: Arc<Mutex<HashMap<String, WatchStatus>>>,
+    exec_runners: HashMap<String, ExecRunner>,
 }
 
 impl WatchManager {
@@ -216,11 +483,12 @@ impl WatchManager {
             session_manager: SessionManager::new()?,
             incremental_analyzer: Arc::new(Mutex::new(IncrementalAnalyzer::new()?)),
             active_watchers: Arc::new(Mutex::new(HashMap::new())),
+            exec_runners: HashMap::new(),
         })
     }
     
     /// Start watching a session
-    pub async fn start_watch(&mut self, session_id: &str) -> Result<()> {
+    pub async fn start_watch(&mut self, session_id: &str, config: WatchConfig) -> Result<()> {
         // Check if session exists
         let session = self.session_manager.get_session_mut(session_id)?;
         let session_path = session.info.path.clone();
@@ -242,11 +510,16 @@ impl WatchManager {
         }
         
         // Create watcher
-        let watcher = FileWatcher::new(
+        let mut watcher = FileWatcher::new(
             session_id.to_string(),
             session_path.clone(),
             Arc::clone(&self.incremental_analyzer)
-        );
+        ).with_config(config.clone());
+        if let Some(command) = config.exec {
+            let runner = ExecRunner::spawn(command, session_id.to_string(), config.on_busy);
+            watcher = watcher.with_exec(&runner);
+            self.exec_runners.insert(session_id.to_string(), runner);
+        }
         
         // Add to active watchers
         {
@@ -288,6 +561,9 @@ impl WatchManager {
     
     /// Stop watching a session
     pub async fn stop_watch(&mut self, session_id: &str) -> Result<()> {
+        if let Some(runner) = self.exec_runners.remove(session_id) {
+            runner.stop().await;
+        }
         let mut watchers = self.active_watchers.lock().await;
         if let Some(mut status) = watchers.remove(session_id) {
             status.status = WatchState::Stopped;
@@ -314,6 +590,9 @@ impl WatchManager {
     
     /// Stop all active watches
     pub async fn stop_all_watches(&mut self) -> Result<()> {
+        for (_, runner) in self.exec_runners.drain() {
+            runner.stop().await;
+        }
         let mut watchers = self.active_watchers.lock().await;
         let count = watchers.len();
         watchers.clear();
@@ -322,4 +601,89 @@ impl WatchManager {
     }
 }
 
-use std::collections::HashMap;
\ No newline at end of file
+use std::collections::HashMap;
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::fs;
+    
+    fn changed(names: &[&str]) -> BTreeSet<PathBuf> {
+        names.iter().map(PathBuf::from).collect()
+    }
+    
+    /// Wait until `log` has `lines` lines, for at most five seconds
+    async fn wait_for_lines(log: &Path, lines: usize) -> String {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            let content = fs::read_to_string(log).unwrap_or_default();
+            if content.lines().count() >= lines || Instant::now() > deadline {
+                return content;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_exec_runner_queues_and_cancels() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("runs.log");
+        let command = format!("sleep 0.3; echo $NEKOCODE_SESSION_ID $NEKOCODE_CHANGED_FILES >> {}", log.display());
+        
+        // Changes during a run are coalesced into one more run
+        let runner = ExecRunner::spawn(command.clone(), "s1".to_string(), OnBusy::Queue);
+        runner.run(changed(&["a.rs"]));
+        runner.run(changed(&["b.rs"]));
+        runner.run(changed(&["c.rs", "b.rs"]));
+        assert_eq!(wait_for_lines(&log, 2).await, "s1 a.rs\ns1 b.rs c.rs\n");
+        runner.stop().await;
+        
+        // Only the last run finishes
+        fs::remove_file(&log).unwrap();
+        let runner = ExecRunner::spawn(command, "s1".to_string(), OnBusy::Cancel);
+        runner.run(changed(&["a.rs"]));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        runner.run(changed(&["b.rs"]));
+        assert_eq!(wait_for_lines(&log, 1).await, "s1 b.rs\n");
+        
+        // Stopping kills a run in progress
+        runner.run(changed(&["c.rs"]));
+        let started = Instant::now();
+        runner.stop().await;
+        assert!(started.elapsed() < Duration::from_millis(300));
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        assert_eq!(fs::read_to_string(&log).unwrap(), "s1 b.rs\n");
+        
+        // So are the processes the shell started
+        let background = format!("(sleep 0.3; echo orphan >> {}) & wait", log.display());
+        let runner = ExecRunner::spawn(background, "s1".to_string(), OnBusy::Cancel);
+        runner.run(changed(&["d.rs"]));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        runner.stop().await;
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        assert_eq!(fs::read_to_string(&log).unwrap(), "s1 b.rs\n");
+        
+        // The command gets SIGTERM first so it can clean up
+        let trapped = format!("trap 'echo cleanup >> {}; exit' TERM; sleep 5 & wait", log.display());
+        let runner = ExecRunner::spawn(trapped, "s1".to_string(), OnBusy::Cancel);
+        runner.run(changed(&["e.rs"]));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let started = Instant::now();
+        runner.stop().await;
+        assert!(started.elapsed() < KILL_GRACE);
+        assert_eq!(fs::read_to_string(&log).unwrap(), "s1 b.rs\ncleanup\n");
+        
+        // And SIGKILL once the grace period is over
+        let stubborn = "trap '' TERM; sleep 5".to_string();
+        let runner = ExecRunner::spawn(stubborn, "s1".to_string(), OnBusy::Cancel);
+        runner.run(changed(&["f.rs"]));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let started = Instant::now();
+        runner.stop().await;
+        assert!(started.elapsed() >= KILL_GRACE);
+        assert!(started.elapsed() < Duration::from_secs(4));
+        
+        assert_eq!(OnBusy::parse("cancel").unwrap(), OnBusy::Cancel);
+        assert!(OnBusy::parse("restart").is_err());
+    }
+}
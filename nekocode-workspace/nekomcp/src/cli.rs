@@ -1,6 +1,6 @@
 //! CLI handling for nekomcp
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -31,6 +31,24 @@ pub enum Commands {
         /// Enable CORS for web clients
         #[arg(long)]
         cors: bool,
+        
+        #[command(flatten)]
+        limits: LimitArgs,
+    },
+    
+    /// Serve MCP over stdin/stdout (newline-delimited JSON-RPC)
+    ///
+    /// Implements `initialize`, `tools/list` and `tools/call` plus the
+    /// direct methods `health`, `capabilities`, `analyze`, `analyze/batch`,
+    /// `session/create`, `session/update`, `session/stats` and `session/list`.
+    ///
+    /// `analyze/batch` (tool `analyze_batch`) takes `paths` or `directory`,
+    /// `offset`, `limit` (default 50 files) and `stats_only`, and answers one
+    /// page of `results` with `total_files`, `offset` and `next_offset`,
+    /// which is absent on the last page.
+    Stdio {
+        #[command(flatten)]
+        limits: LimitArgs,
     },
     
     /// Test MCP server functionality
@@ -64,4 +82,21 @@ pub enum Commands {
         #[arg(long, default_value = "json")]
         format: String,
     },
-}
\ No newline at end of file
+}
+
+/// Request limits shared by `serve` and `stdio`. Flags override the values
+/// from `--config`, which override the built-in defaults.
+#[derive(Args, Debug, Clone, Default)]
+pub struct LimitArgs {
+    /// Configuration file written by `nekomcp config`; limits are read from its `server` section
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+    
+    /// Seconds before a request fails with a timeout error (0 disables the timeout)
+    #[arg(long, value_name = "SECS")]
+    pub request_timeout: Option<u64>,
+    
+    /// Requests handled at once; others wait for a free slot
+    #[arg(long, value_name = "N")]
+    pub max_concurrent_requests: Option<usize>,
+}
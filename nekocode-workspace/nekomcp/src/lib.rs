@@ -5,9 +5,11 @@
 
 pub mod cli;
 pub mod server;
+pub mod stdio;
 
 pub use cli::*;
 pub use server::*;
+pub use stdio::run_stdio;
 
 use anyhow::Result;
 
@@ -3,11 +3,15 @@
 //! This binary provides an MCP server that exposes NekoCode's code analysis
 //! capabilities to AI assistants like Claude Code.
 
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use clap::Parser;
-use nekomcp::{cli::*, server::*, init};
+use nekomcp::{cli::*, server::*, init, run_stdio};
 use serde_json::json;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// How long `health` waits for the server before giving up
+const HEALTH_CHECK_TIMEOUT_SECS: u64 = 5;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -23,9 +27,13 @@ async fn main() -> Result<()> {
     init()?;
 
     match cli.command {
-        Commands::Serve { port, host, cors } => {
+        Commands::Serve { port, host, cors, limits } => {
             log::info!("Starting NekoMCP server...");
-            start_server(&host, port, cors).await?;
+            start_server(&host, port, cors, request_limits(&limits)?).await?;
+        }
+        
+        Commands::Stdio { limits } => {
+            run_stdio(McpServerState::with_limits(request_limits(&limits)?)).await?;
         }
         
         Commands::Test { function, data } => {
@@ -52,6 +60,24 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// Resolve the request limits from the config file and command-line flags
+fn request_limits(args: &LimitArgs) -> Result<RequestLimits> {
+    let mut limits = match &args.config {
+        Some(path) => RequestLimits::from_config_file(path)?,
+        None => RequestLimits::default(),
+    };
+    if let Some(timeout) = args.request_timeout {
+        limits.request_timeout_secs = timeout;
+    }
+    if let Some(max) = args.max_concurrent_requests {
+        if max == 0 {
+            bail!("--max-concurrent-requests must be at least 1");
+        }
+        limits.max_concurrent_requests = max;
+    }
+    Ok(limits)
+}
+
 /// Test MCP server functionality
 async fn test_functionality(function: Option<&str>, data: Option<&PathBuf>) -> Result<()> {
     match function {
@@ -101,11 +127,56 @@ async fn test_functionality(function: Option<&str>, data: Option<&PathBuf>) -> R
 
 /// Check server health
 async fn check_health(url: &str) -> Result<()> {
-    println!("Checking health at: {}", url);
+    let base_url = url.trim_end_matches('/');
+    let health_url = format!("{}/health", base_url);
+    println!("Checking health at: {}", health_url);
     
-    // TODO: Implement actual health check with HTTP client
-    println!("Health check would connect to: {}/health", url);
-    println!("✅ Health check completed (mock)");
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(HEALTH_CHECK_TIMEOUT_SECS))
+        .build()?;
+    
+    let started = Instant::now();
+    let response = client
+        .get(&health_url)
+        .send()
+        .await
+        .with_context(|| format!("Failed to connect to {}", health_url))?;
+    let latency = started.elapsed();
+    
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        bail!("Health check failed: {} returned {} {}", health_url, status, body.trim());
+    }
+    
+    let health: HealthResponse = response
+        .json()
+        .await
+        .with_context(|| format!("Invalid health response from {}", health_url))?;
+    
+    println!("  Status code: {}", status.as_u16());
+    println!("  Latency: {} ms", latency.as_millis());
+    println!("  Server status: {}", health.status);
+    println!("  Version: {}", health.version);
+    
+    // Capabilities are informational; an older server may not expose them
+    let capabilities_url = format!("{}/capabilities", base_url);
+    match client.get(&capabilities_url).send().await {
+        Ok(response) if response.status().is_success() => {
+            match response.json::<CapabilitiesResponse>().await {
+                Ok(capabilities) => println!("  Capabilities: {}", capabilities.capabilities.join(", ")),
+                Err(e) => log::warn!("Invalid capabilities response from {}: {}", capabilities_url, e),
+            }
+        }
+        Ok(response) => log::warn!("{} returned {}", capabilities_url, response.status()),
+        Err(e) => log::warn!("Failed to fetch {}: {}", capabilities_url, e),
+    }
+    
+    if health.status != "healthy" {
+        bail!("Server reported status '{}'", health.status);
+    }
+    
+    println!("✅ Server is healthy");
     
     Ok(())
 }
@@ -137,10 +208,24 @@ fn show_capabilities() {
             "/health",
             "/capabilities",
             "/analyze",
+            "/analyze/batch",
             "/session/create",
             "/session/update", 
             "/session/stats",
             "/session/list"
+        ],
+        "tools": [
+            {
+                "name": "analyze_batch",
+                "method": "analyze/batch",
+                "arguments": {
+                    "paths": "files or directories to analyze (array of strings)",
+                    "directory": "a directory to analyze instead of paths",
+                    "offset": "index of the first file, from the previous page's next_offset",
+                    "limit": format!("files per page (default {})", DEFAULT_BATCH_PAGE_SIZE),
+                    "stats_only": "return only file info and metrics"
+                }
+            }
         ]
     });
     
@@ -158,7 +243,9 @@ fn generate_config(output: Option<&PathBuf>, format: &str) -> Result<()> {
                     "description": "MCP server for NekoCode code analysis",
                     "host": "127.0.0.1",
                     "port": 3000,
-                    "cors": false
+                    "cors": false,
+                    "request_timeout_secs": DEFAULT_REQUEST_TIMEOUT_SECS,
+                    "max_concurrent_requests": DEFAULT_MAX_CONCURRENT_REQUESTS
                 },
                 "features": {
                     "analyze": true,
@@ -186,7 +273,9 @@ fn generate_config(output: Option<&PathBuf>, format: &str) -> Result<()> {
             json!({
                 "server": {
                     "name": "nekomcp",
-                    "version": env!("CARGO_PKG_VERSION")
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "request_timeout_secs": DEFAULT_REQUEST_TIMEOUT_SECS,
+                    "max_concurrent_requests": DEFAULT_MAX_CONCURRENT_REQUESTS
                 }
             })
         }
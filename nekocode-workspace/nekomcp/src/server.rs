@@ -1,6 +1,6 @@
 //! MCP server implementation for NekoCode
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
 use axum::{
     extract::{Query, State}, 
     http::StatusCode,
@@ -8,20 +8,73 @@ use axum::{
     routing::{get, post},
     Router,
 };
+use nekocode::create_analyzer;
 use nekocode_core::{
+    io::FileProcessor,
     session::SessionManager,
     types::*,
 };
 use chrono;
-use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, path::PathBuf, sync::Arc};
-use tokio::sync::RwLock;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{collections::HashMap, path::{Path, PathBuf}, sync::Arc, time::Duration};
+use tokio::sync::{RwLock, Semaphore};
 use tower_http::cors::CorsLayer;
 
 /// MCP server state
 #[derive(Clone)]
 pub struct McpServerState {
     pub session_manager: Arc<RwLock<SessionManager>>,
+    limits: RequestLimits,
+    /// One permit per request allowed to run at once
+    pub(crate) permits: Arc<Semaphore>,
+}
+
+/// Seconds a request may run before it fails with [`DispatchError::Timeout`]
+pub const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 300;
+
+/// Requests handled at once when the configuration sets no limit
+pub const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 8;
+
+/// Limits applied to every dispatched request, read from the `server`
+/// section of the file written by `nekomcp config`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RequestLimits {
+    /// Seconds before a request fails with a timeout error; 0 disables the limit
+    pub request_timeout_secs: u64,
+    /// Requests handled at once; further requests wait for a free slot, and
+    /// the wait counts towards their timeout
+    pub max_concurrent_requests: usize,
+}
+
+impl Default for RequestLimits {
+    fn default() -> Self {
+        Self {
+            request_timeout_secs: DEFAULT_REQUEST_TIMEOUT_SECS,
+            max_concurrent_requests: DEFAULT_MAX_CONCURRENT_REQUESTS,
+        }
+    }
+}
+
+impl RequestLimits {
+    /// Read the limits from a configuration file; keys missing from its
+    /// `server` section keep their defaults
+    pub fn from_config_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config {}", path.display()))?;
+        let config: serde_json::Value = serde_json::from_str(&content)
+            .with_context(|| format!("{} is not a JSON config", path.display()))?;
+        let server = config.get("server").cloned().unwrap_or_default();
+        if server.is_null() {
+            return Ok(Self::default());
+        }
+        serde_json::from_value(server)
+            .with_context(|| format!("Invalid server section in {}", path.display()))
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        (self.request_timeout_secs > 0).then(|| Duration::from_secs(self.request_timeout_secs))
+    }
 }
 
 /// MCP request types
@@ -32,6 +85,28 @@ pub struct AnalyzeRequest {
     pub stats_only: Option<bool>,
 }
 
+/// Files returned per `analyze/batch` page when the request sets no `limit`
+pub const DEFAULT_BATCH_PAGE_SIZE: usize = 50;
+
+/// Serialized size after which an `analyze/batch` page is cut short, keeping
+/// responses within MCP client message limits
+pub const MAX_BATCH_RESPONSE_BYTES: usize = 512 * 1024;
+
+#[derive(Debug, Deserialize)]
+pub struct AnalyzeBatchRequest {
+    /// Files or directories; directories expand to the supported files below them
+    #[serde(default)]
+    pub paths: Vec<PathBuf>,
+    /// A directory to analyze, as an alternative to `paths`
+    pub directory: Option<PathBuf>,
+    /// Index of the first file to return, from a previous page's `next_offset`
+    #[serde(default)]
+    pub offset: usize,
+    /// Files per page (default [`DEFAULT_BATCH_PAGE_SIZE`])
+    pub limit: Option<usize>,
+    pub stats_only: Option<bool>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct SessionCreateRequest {
     pub path: PathBuf,
@@ -56,6 +131,28 @@ pub struct AnalyzeResponse {
     pub error: Option<String>,
 }
 
+/// One file of an `analyze/batch` page: either its analysis or why it failed
+#[derive(Debug, Serialize)]
+pub struct BatchFileResult {
+    pub path: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<AnalysisResult>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AnalyzeBatchResponse {
+    pub success: bool,
+    pub results: Vec<BatchFileResult>,
+    /// Files matched by the request across all pages
+    pub total_files: usize,
+    pub offset: usize,
+    /// Offset of the next page; absent on the last page
+    pub next_offset: Option<usize>,
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct SessionResponse {
     pub success: bool,
@@ -64,7 +161,7 @@ pub struct SessionResponse {
     pub error: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct CapabilitiesResponse {
     pub name: String,
     pub version: String,
@@ -72,7 +169,7 @@ pub struct CapabilitiesResponse {
     pub supported_languages: Vec<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct HealthResponse {
     pub status: String,
     pub version: String,
@@ -81,43 +178,417 @@ pub struct HealthResponse {
 
 impl McpServerState {
     pub fn new() -> Self {
+        Self::with_limits(RequestLimits::default())
+    }
+
+    pub fn with_limits(limits: RequestLimits) -> Self {
         Self {
             session_manager: Arc::new(RwLock::new(SessionManager::new().expect("Failed to create SessionManager"))),
+            limits,
+            permits: Arc::new(Semaphore::new(limits.max_concurrent_requests.max(1))),
         }
     }
 }
 
-/// Create MCP server router
-pub fn create_router(state: McpServerState, enable_cors: bool) -> Router {
-    let mut router = Router::new()
-        .route("/health", get(health_handler))
-        .route("/capabilities", get(capabilities_handler))
-        .route("/analyze", post(analyze_handler))
-        .route("/session/create", post(session_create_handler))
-        .route("/session/update", post(session_update_handler))
-        .route("/session/stats", get(session_stats_handler))
-        .route("/session/list", get(session_list_handler))
-        .with_state(state);
+/// Methods exposed by [`McpServerState::dispatch`]. The HTTP routes and the
+/// stdio transport both resolve to these names.
+pub const METHODS: &[&str] = &[
+    "health",
+    "capabilities",
+    "analyze",
+    "analyze/batch",
+    "session/create",
+    "session/update",
+    "session/stats",
+    "session/list",
+];
+
+/// Reasons a request could not be routed to a handler
+#[derive(Debug, Clone, PartialEq)]
+pub enum DispatchError {
+    /// No handler is registered under the requested method name
+    MethodNotFound(String),
+    /// The params did not match the handler's request type
+    InvalidParams(String),
+    /// The request did not finish, including any wait for a free slot,
+    /// within the configured timeout
+    Timeout(Duration),
+}
 
-    if enable_cors {
-        router = router.layer(CorsLayer::permissive());
+impl std::fmt::Display for DispatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DispatchError::MethodNotFound(method) => write!(f, "Method not found: {}", method),
+            DispatchError::InvalidParams(message) => write!(f, "Invalid params: {}", message),
+            DispatchError::Timeout(timeout) => write!(f, "Request timed out after {:?}", timeout),
+        }
     }
+}
 
-    router
+impl std::error::Error for DispatchError {}
+
+impl McpServerState {
+    /// Route a request to its handler. This is the single entry point shared
+    /// by every transport; handler failures are reported inside the response
+    /// (`success: false`), only routing problems and timeouts are returned as
+    /// errors.
+    ///
+    /// Requests other than `health` and `capabilities` take a slot from the
+    /// concurrency limit and run on their own task, so a handler stuck in
+    /// blocking work still times out.
+    pub async fn dispatch(&self, method: &str, params: serde_json::Value) -> Result<serde_json::Value, DispatchError> {
+        // Health checks stay responsive while analyses fill every slot
+        if matches!(method, "health" | "capabilities") {
+            return self.route(method, params).await;
+        }
+
+        let state = self.clone();
+        let name = method.to_string();
+        let mut task = tokio::spawn(async move {
+            let _permit = state.permits.clone().acquire_owned().await.expect("request semaphore is never closed");
+            state.route(&name, params).await
+        });
+
+        let joined = match self.limits.timeout() {
+            Some(timeout) => match tokio::time::timeout(timeout, &mut task).await {
+                Ok(joined) => joined,
+                Err(_) => {
+                    task.abort();
+                    log::warn!("{} timed out after {:?}", method, timeout);
+                    return Err(DispatchError::Timeout(timeout));
+                }
+            },
+            None => task.await,
+        };
+        joined.unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()))
+    }
+
+    async fn route(&self, method: &str, params: serde_json::Value) -> Result<serde_json::Value, DispatchError> {
+        let response = match method {
+            "health" => to_json(health()),
+            "capabilities" => to_json(capabilities()),
+            "analyze" => to_json(self.analyze(parse_params(params)?).await),
+            "analyze/batch" => to_json(self.analyze_batch(parse_params(params)?).await),
+            "session/create" => to_json(self.session_create(parse_params(params)?).await),
+            "session/update" => to_json(self.session_update(parse_params(params)?).await),
+            "session/stats" => to_json(self.session_stats(parse_params(params)?).await),
+            "session/list" => to_json(self.session_list().await),
+            _ => return Err(DispatchError::MethodNotFound(method.to_string())),
+        };
+        Ok(response)
+    }
+
+    /// Analyze a path
+    pub async fn analyze(&self, request: AnalyzeRequest) -> AnalyzeResponse {
+        log::info!("Analyzing path: {:?}", request.path);
+
+        let mut session_manager = self.session_manager.write().await;
+        
+        match session_manager.create_session(request.path.clone()) {
+            Ok(_session_id) => {
+                // For now, return a mock analysis result
+                // TODO: Integrate with actual analysis engine
+                let mock_file_info = FileInfo {
+                    name: request.path.file_name()
+                        .and_then(|n| n.to_str())
+                        .unwrap_or("unknown")
+                        .to_string(),
+                    path: request.path.clone(),
+                    language: Language::JavaScript, // Default to JavaScript
+                    size_bytes: 1000,
+                    total_lines: 100,
+                    code_lines: 80,
+                    comment_lines: 10,
+                    empty_lines: 10,
+                    code_ratio: 0.8,
+                    analyzed_at: chrono::Utc::now(),
+                    hash: Some("mock_hash".to_string()),
+                    metadata: std::collections::HashMap::new(),
+                };
+                
+                let mock_result = AnalysisResult {
+                    file_info: mock_file_info,
+                    symbols: vec![],
+                    functions: vec![],
+                    classes: vec![],
+                    imports: vec![],
+                    exports: vec![],
+                    dependencies: vec![],
+                    metrics: CodeMetrics {
+                        lines_of_code: 100,
+                        lines_with_comments: 10,
+                        blank_lines: 10,
+                        cyclomatic_complexity: Some(5),
+                        halstead_volume: Some(200.0),
+                        maintainability_index: Some(80.0),
+                    },
+                    errors: vec![],
+                };
+
+                let response_data = if request.stats_only.unwrap_or(false) {
+                    // Return only basic info and metrics for stats_only
+                    AnalysisResult {
+                        file_info: mock_result.file_info.clone(),
+                        symbols: vec![], // Empty symbols for stats_only
+                        functions: vec![], // Empty functions for stats_only
+                        classes: vec![], // Empty classes for stats_only
+                        imports: vec![], // Empty imports for stats_only  
+                        exports: vec![], // Empty exports for stats_only
+                        dependencies: vec![], // Empty dependencies for stats_only
+                        metrics: mock_result.metrics.clone(),
+                        errors: vec![],
+                    }
+                } else {
+                    mock_result
+                };
+
+                AnalyzeResponse {
+                    success: true,
+                    data: Some(response_data),
+                    error: None,
+                }
+            }
+            Err(e) => AnalyzeResponse {
+                success: false,
+                data: None,
+                error: Some(e.to_string()),
+            },
+        }
+    }
+
+    /// Analyze many files in one call, one page at a time. A file that fails
+    /// to read or parse gets an `error` entry instead of failing the batch.
+    /// A page ends after `limit` files or once it passes
+    /// [`MAX_BATCH_RESPONSE_BYTES`], whichever comes first; clients fetch the
+    /// rest by passing `next_offset` back as `offset`.
+    pub async fn analyze_batch(&self, request: AnalyzeBatchRequest) -> AnalyzeBatchResponse {
+        let mut paths = request.paths;
+        paths.extend(request.directory);
+        log::info!("Batch analyzing {} path(s) from offset {}", paths.len(), request.offset);
+
+        let failed = |error: String| AnalyzeBatchResponse {
+            success: false,
+            results: Vec::new(),
+            total_files: 0,
+            offset: request.offset,
+            next_offset: None,
+            error: Some(error),
+        };
+        if paths.is_empty() {
+            return failed("Provide `paths` or `directory`".to_string());
+        }
+
+        let mut files = Vec::new();
+        for path in &paths {
+            if path.is_dir() {
+                match FileProcessor::new(path).and_then(|processor| processor.discover_files()) {
+                    Ok(found) => files.extend(found),
+                    Err(e) => return failed(format!("{}: {}", path.display(), e)),
+                }
+            } else {
+                files.push(path.clone());
+            }
+        }
+        files.sort();
+        files.dedup();
+
+        let limit = request.limit.unwrap_or(DEFAULT_BATCH_PAGE_SIZE).max(1);
+        let stats_only = request.stats_only.unwrap_or(false);
+        let mut results = Vec::new();
+        let mut bytes = 0;
+        for path in files.iter().skip(request.offset).take(limit) {
+            let mut entry = analyze_file(path).await;
+            if stats_only {
+                if let Some(data) = entry.data.as_mut() {
+                    strip_to_stats(data);
+                }
+            }
+            bytes += serde_json::to_vec(&entry).map(|json| json.len()).unwrap_or(0);
+            results.push(entry);
+            if bytes > MAX_BATCH_RESPONSE_BYTES {
+                break;
+            }
+        }
+
+        let end = request.offset + results.len();
+        AnalyzeBatchResponse {
+            success: true,
+            results,
+            total_files: files.len(),
+            offset: request.offset,
+            next_offset: (end < files.len()).then_some(end),
+            error: None,
+        }
+    }
+
+    /// Create a session for a path
+    pub async fn session_create(&self, request: SessionCreateRequest) -> SessionResponse {
+        log::info!("Creating session for path: {:?}", request.path);
+
+        let mut session_manager = self.session_manager.write().await;
+        
+        match session_manager.create_session(request.path) {
+            Ok(session_id) => SessionResponse {
+                success: true,
+                session_id: Some(session_id),
+                data: None,
+                error: None,
+            },
+            Err(e) => SessionResponse {
+                success: false,
+                session_id: None,
+                data: None,
+                error: Some(e.to_string()),
+            },
+        }
+    }
+
+    /// Update a session
+    pub async fn session_update(&self, request: SessionUpdateRequest) -> SessionResponse {
+        log::info!("Updating session: {}", request.session_id);
+
+        let mut session_manager = self.session_manager.write().await;
+        
+        match session_manager.get_session_mut(&request.session_id) {
+            Ok(session) => {
+                // Touch the session to update access time
+                session.touch();
+                
+                // For now, return basic session info
+                // TODO: Implement actual incremental update logic
+                let update_result = serde_json::json!({
+                    "session_id": request.session_id,
+                    "path": session.path(),
+                    "last_accessed": session.info.last_accessed,
+                    "last_modified": session.info.last_modified,
+                    "file_count": session.info.file_count,
+                    "total_lines": session.info.total_lines,
+                });
+                
+                SessionResponse {
+                    success: true,
+                    session_id: Some(request.session_id),
+                    data: Some(update_result),
+                    error: None,
+                }
+            }
+            Err(e) => SessionResponse {
+                success: false,
+                session_id: Some(request.session_id),
+                data: None,
+                error: Some(e.to_string()),
+            },
+        }
+    }
+
+    /// Get statistics for a session
+    pub async fn session_stats(&self, request: SessionStatsRequest) -> SessionResponse {
+        let session_id = request.session_id;
+        log::info!("Getting stats for session: {}", session_id);
+
+        let mut session_manager = self.session_manager.write().await;
+        
+        match session_manager.get_session(&session_id) {
+            Ok(session) => {
+                let stats = serde_json::json!({
+                    "session_id": session_id,
+                    "path": session.path(),
+                    "created_at": session.info.created_at,
+                    "last_accessed": session.info.last_accessed,
+                    "last_modified": session.info.last_modified,
+                    "file_count": session.info.file_count,
+                    "total_lines": session.info.total_lines,
+                    "languages": session.info.languages,
+                    "version": session.info.version,
+                    "is_dirty": session.info.is_dirty,
+                });
+                
+                SessionResponse {
+                    success: true,
+                    session_id: Some(session_id),
+                    data: Some(stats),
+                    error: None,
+                }
+            }
+            Err(e) => SessionResponse {
+                success: false,
+                session_id: Some(session_id),
+                data: None,
+                error: Some(e.to_string()),
+            },
+        }
+    }
+
+    /// List all sessions
+    pub async fn session_list(&self) -> SessionResponse {
+        log::info!("Listing all sessions");
+
+        let session_manager = self.session_manager.read().await;
+        
+        match session_manager.list_sessions() {
+            Ok(sessions) => SessionResponse {
+                success: true,
+                session_id: None,
+                data: Some(serde_json::to_value(sessions).unwrap_or_default()),
+                error: None,
+            },
+            Err(e) => SessionResponse {
+                success: false,
+                session_id: None,
+                data: None,
+                error: Some(e.to_string()),
+            },
+        }
+    }
 }
 
-/// Health check handler
-async fn health_handler() -> Json<HealthResponse> {
-    Json(HealthResponse {
+/// Analyze one file of a batch, capturing any failure in the entry
+async fn analyze_file(path: &std::path::Path) -> BatchFileResult {
+    let analysis = async {
+        let content = std::fs::read_to_string(path)?;
+        let mut analyzer = create_analyzer(Language::from_path(path))?;
+        analyzer.analyze(path, &content).await
+    };
+    match analysis.await {
+        Ok(data) => BatchFileResult { path: path.to_path_buf(), data: Some(data), error: None },
+        Err(e) => BatchFileResult { path: path.to_path_buf(), data: None, error: Some(e.to_string()) },
+    }
+}
+
+/// Keep only the file info and metrics, as `analyze` does for `stats_only`
+fn strip_to_stats(result: &mut AnalysisResult) {
+    result.symbols.clear();
+    result.functions.clear();
+    result.classes.clear();
+    result.imports.clear();
+    result.exports.clear();
+    result.dependencies.clear();
+}
+
+/// Deserialize handler params; missing params are treated as an empty object
+fn parse_params<T: DeserializeOwned>(params: serde_json::Value) -> Result<T, DispatchError> {
+    let params = if params.is_null() {
+        serde_json::Value::Object(Default::default())
+    } else {
+        params
+    };
+    serde_json::from_value(params).map_err(|e| DispatchError::InvalidParams(e.to_string()))
+}
+
+fn to_json<T: Serialize>(response: T) -> serde_json::Value {
+    serde_json::to_value(response).unwrap_or_default()
+}
+
+fn health() -> HealthResponse {
+    HealthResponse {
         status: "healthy".to_string(),
         version: crate::VERSION.to_string(),
         uptime: 0, // TODO: Track actual uptime
-    })
+    }
 }
 
-/// Capabilities handler
-async fn capabilities_handler() -> Json<CapabilitiesResponse> {
-    Json(CapabilitiesResponse {
+fn capabilities() -> CapabilitiesResponse {
+    CapabilitiesResponse {
         name: "NekoCode MCP Server".to_string(),
         version: crate::VERSION.to_string(),
         capabilities: vec![
@@ -138,226 +609,104 @@ async fn capabilities_handler() -> Json<CapabilitiesResponse> {
             "go".to_string(),
             "rust".to_string(),
         ],
+    }
+}
+
+/// Create MCP server router
+pub fn create_router(state: McpServerState, enable_cors: bool) -> Router {
+    let mut router = Router::new()
+        .route("/health", get(health_handler))
+        .route("/capabilities", get(capabilities_handler))
+        .route("/analyze", post(analyze_handler))
+        .route("/analyze/batch", post(analyze_batch_handler))
+        .route("/session/create", post(session_create_handler))
+        .route("/session/update", post(session_update_handler))
+        .route("/session/stats", get(session_stats_handler))
+        .route("/session/list", get(session_list_handler))
+        .with_state(state);
+
+    if enable_cors {
+        router = router.layer(CorsLayer::permissive());
+    }
+
+    router
+}
+
+/// Dispatch an HTTP request, mapping routing errors to status codes
+async fn dispatch_http(
+    state: &McpServerState,
+    method: &str,
+    params: serde_json::Value,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    state.dispatch(method, params).await.map(Json).map_err(|e| {
+        log::warn!("{}", e);
+        match e {
+            DispatchError::MethodNotFound(_) => StatusCode::NOT_FOUND,
+            DispatchError::InvalidParams(_) => StatusCode::BAD_REQUEST,
+            DispatchError::Timeout(_) => StatusCode::GATEWAY_TIMEOUT,
+        }
     })
 }
 
+/// Health check handler
+async fn health_handler(State(state): State<McpServerState>) -> Result<Json<serde_json::Value>, StatusCode> {
+    dispatch_http(&state, "health", serde_json::Value::Null).await
+}
+
+/// Capabilities handler
+async fn capabilities_handler(State(state): State<McpServerState>) -> Result<Json<serde_json::Value>, StatusCode> {
+    dispatch_http(&state, "capabilities", serde_json::Value::Null).await
+}
+
 /// Analyze handler
 async fn analyze_handler(
     State(state): State<McpServerState>,
-    Json(request): Json<AnalyzeRequest>,
-) -> Result<Json<AnalyzeResponse>, StatusCode> {
-    log::info!("Analyzing path: {:?}", request.path);
-
-    let mut session_manager = state.session_manager.write().await;
-    
-    match session_manager.create_session(request.path.clone()) {
-        Ok(_session_id) => {
-            // For now, return a mock analysis result
-            // TODO: Integrate with actual analysis engine
-            let mock_file_info = FileInfo {
-                name: request.path.file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or("unknown")
-                    .to_string(),
-                path: request.path.clone(),
-                language: Language::JavaScript, // Default to JavaScript
-                size_bytes: 1000,
-                total_lines: 100,
-                code_lines: 80,
-                comment_lines: 10,
-                empty_lines: 10,
-                code_ratio: 0.8,
-                analyzed_at: chrono::Utc::now(),
-                hash: Some("mock_hash".to_string()),
-                metadata: std::collections::HashMap::new(),
-            };
-            
-            let mock_result = AnalysisResult {
-                file_info: mock_file_info,
-                symbols: vec![],
-                functions: vec![],
-                classes: vec![],
-                imports: vec![],
-                exports: vec![],
-                dependencies: vec![],
-                metrics: CodeMetrics {
-                    lines_of_code: 100,
-                    lines_with_comments: 10,
-                    blank_lines: 10,
-                    cyclomatic_complexity: Some(5),
-                    halstead_volume: Some(200.0),
-                    maintainability_index: Some(80.0),
-                },
-                errors: vec![],
-            };
-
-            let response_data = if request.stats_only.unwrap_or(false) {
-                // Return only basic info and metrics for stats_only
-                AnalysisResult {
-                    file_info: mock_result.file_info.clone(),
-                    symbols: vec![], // Empty symbols for stats_only
-                    functions: vec![], // Empty functions for stats_only
-                    classes: vec![], // Empty classes for stats_only
-                    imports: vec![], // Empty imports for stats_only  
-                    exports: vec![], // Empty exports for stats_only
-                    dependencies: vec![], // Empty dependencies for stats_only
-                    metrics: mock_result.metrics.clone(),
-                    errors: vec![],
-                }
-            } else {
-                mock_result
-            };
+    Json(params): Json<serde_json::Value>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    dispatch_http(&state, "analyze", params).await
+}
 
-            Ok(Json(AnalyzeResponse {
-                success: true,
-                data: Some(response_data),
-                error: None,
-            }))
-        }
-        Err(e) => Ok(Json(AnalyzeResponse {
-            success: false,
-            data: None,
-            error: Some(e.to_string()),
-        })),
-    }
+/// Batch analyze handler
+async fn analyze_batch_handler(
+    State(state): State<McpServerState>,
+    Json(params): Json<serde_json::Value>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    dispatch_http(&state, "analyze/batch", params).await
 }
 
 /// Session create handler
 async fn session_create_handler(
     State(state): State<McpServerState>,
-    Json(request): Json<SessionCreateRequest>,
-) -> Result<Json<SessionResponse>, StatusCode> {
-    log::info!("Creating session for path: {:?}", request.path);
-
-    let mut session_manager = state.session_manager.write().await;
-    
-    match session_manager.create_session(request.path) {
-        Ok(session_id) => Ok(Json(SessionResponse {
-            success: true,
-            session_id: Some(session_id),
-            data: None,
-            error: None,
-        })),
-        Err(e) => Ok(Json(SessionResponse {
-            success: false,
-            session_id: None,
-            data: None,
-            error: Some(e.to_string()),
-        })),
-    }
+    Json(params): Json<serde_json::Value>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    dispatch_http(&state, "session/create", params).await
 }
 
 /// Session update handler
 async fn session_update_handler(
     State(state): State<McpServerState>,
-    Json(request): Json<SessionUpdateRequest>,
-) -> Result<Json<SessionResponse>, StatusCode> {
-    log::info!("Updating session: {}", request.session_id);
-
-    let mut session_manager = state.session_manager.write().await;
-    
-    match session_manager.get_session_mut(&request.session_id) {
-        Ok(session) => {
-            // Touch the session to update access time
-            session.touch();
-            
-            // For now, return basic session info
-            // TODO: Implement actual incremental update logic
-            let update_result = serde_json::json!({
-                "session_id": request.session_id,
-                "path": session.path(),
-                "last_accessed": session.info.last_accessed,
-                "last_modified": session.info.last_modified,
-                "file_count": session.info.file_count,
-                "total_lines": session.info.total_lines,
-            });
-            
-            Ok(Json(SessionResponse {
-                success: true,
-                session_id: Some(request.session_id),
-                data: Some(update_result),
-                error: None,
-            }))
-        }
-        Err(e) => Ok(Json(SessionResponse {
-            success: false,
-            session_id: Some(request.session_id),
-            data: None,
-            error: Some(e.to_string()),
-        })),
-    }
+    Json(params): Json<serde_json::Value>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    dispatch_http(&state, "session/update", params).await
 }
 
 /// Session stats handler
 async fn session_stats_handler(
     State(state): State<McpServerState>,
     Query(params): Query<HashMap<String, String>>,
-) -> Result<Json<SessionResponse>, StatusCode> {
-    let session_id = params.get("session_id")
-        .ok_or(StatusCode::BAD_REQUEST)?;
-
-    log::info!("Getting stats for session: {}", session_id);
-
-    let mut session_manager = state.session_manager.write().await;
-    
-    match session_manager.get_session(session_id) {
-        Ok(session) => {
-            let stats = serde_json::json!({
-                "session_id": session_id,
-                "path": session.path(),
-                "created_at": session.info.created_at,
-                "last_accessed": session.info.last_accessed,
-                "last_modified": session.info.last_modified,
-                "file_count": session.info.file_count,
-                "total_lines": session.info.total_lines,
-                "languages": session.info.languages,
-                "version": session.info.version,
-                "is_dirty": session.info.is_dirty,
-            });
-            
-            Ok(Json(SessionResponse {
-                success: true,
-                session_id: Some(session_id.clone()),
-                data: Some(stats),
-                error: None,
-            }))
-        }
-        Err(e) => Ok(Json(SessionResponse {
-            success: false,
-            session_id: Some(session_id.clone()),
-            data: None,
-            error: Some(e.to_string()),
-        })),
-    }
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let params = serde_json::to_value(params).unwrap_or_default();
+    dispatch_http(&state, "session/stats", params).await
 }
 
 /// Session list handler
-async fn session_list_handler(
-    State(state): State<McpServerState>,
-) -> Json<SessionResponse> {
-    log::info!("Listing all sessions");
-
-    let session_manager = state.session_manager.read().await;
-    
-    match session_manager.list_sessions() {
-        Ok(sessions) => Json(SessionResponse {
-            success: true,
-            session_id: None,
-            data: Some(serde_json::to_value(sessions).unwrap_or_default()),
-            error: None,
-        }),
-        Err(e) => Json(SessionResponse {
-            success: false,
-            session_id: None,
-            data: None,
-            error: Some(e.to_string()),
-        }),
-    }
+async fn session_list_handler(State(state): State<McpServerState>) -> Result<Json<serde_json::Value>, StatusCode> {
+    dispatch_http(&state, "session/list", serde_json::Value::Null).await
 }
 
 /// Start the MCP server
-pub async fn start_server(host: &str, port: u16, enable_cors: bool) -> Result<()> {
-    let state = McpServerState::new();
+pub async fn start_server(host: &str, port: u16, enable_cors: bool, limits: RequestLimits) -> Result<()> {
+    let state = McpServerState::with_limits(limits);
     let app = create_router(state, enable_cors);
 
     let addr = format!("{}:{}", host, port);
@@ -367,7 +716,10 @@ pub async fn start_server(host: &str, port: u16, enable_cors: bool) -> Result<()
         .await
         .map_err(|e| anyhow!("Failed to bind to {}: {}", addr, e))?;
 
-    log::info!("NekoMCP server listening on {}", addr);
+    log::info!(
+        "NekoMCP server listening on {} (request timeout {}s, {} concurrent requests)",
+        addr, limits.request_timeout_secs, limits.max_concurrent_requests
+    );
     
     axum::serve(listener, app)
         .await
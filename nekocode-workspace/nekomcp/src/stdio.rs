@@ -0,0 +1,350 @@
+//! Newline-delimited JSON-RPC 2.0 transport over stdin/stdout
+//!
+//! Each line read from stdin is one JSON-RPC request or notification, and each
+//! response is written to stdout as a single line. Logging goes to stderr so it
+//! never interleaves with protocol output.
+//!
+//! Exposed methods:
+//!
+//! - MCP lifecycle: `initialize`, `notifications/initialized`, `ping`
+//! - MCP tools: `tools/list`, `tools/call` with one of the tool names
+//!   `analyze`, `analyze_batch`, `session_create`, `session_update`,
+//!   `session_stats`, `session_list`
+//! - Direct calls to the shared handlers: `health`, `capabilities`, `analyze`,
+//!   `analyze/batch`, `session/create`, `session/update`, `session/stats`,
+//!   `session/list` (the same names as the HTTP routes)
+//!
+//! Example MCP client configuration:
+//!
+//! ```json
+//! { "mcpServers": { "nekocode": { "command": "nekomcp", "args": ["stdio"] } } }
+//! ```
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+use crate::server::{DispatchError, McpServerState};
+
+/// MCP protocol revision reported by `initialize`
+pub const PROTOCOL_VERSION: &str = "2024-11-05";
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// Implementation-defined server error, from the -32000..-32099 range
+const REQUEST_TIMEOUT: i64 = -32001;
+
+/// MCP tools and the dispatch method each one maps to
+const TOOLS: &[(&str, &str, &str)] = &[
+    ("analyze", "analyze", "Analyze a file or directory"),
+    ("analyze_batch", "analyze/batch", "Analyze many files in one call, one page of results at a time"),
+    ("session_create", "session/create", "Create an analysis session for a path"),
+    ("session_update", "session/update", "Refresh an existing session"),
+    ("session_stats", "session/stats", "Show statistics for a session"),
+    ("session_list", "session/list", "List all sessions"),
+];
+
+#[derive(Debug, Deserialize)]
+struct RpcRequest {
+    #[serde(default)]
+    jsonrpc: String,
+    /// Absent for notifications, which get no response
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug, Serialize)]
+struct RpcResponse {
+    jsonrpc: &'static str,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<RpcError>,
+}
+
+#[derive(Debug, Serialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcResponse {
+    fn result(id: Value, result: Value) -> Self {
+        Self { jsonrpc: "2.0", id, result: Some(result), error: None }
+    }
+
+    fn error(id: Value, code: i64, message: impl Into<String>) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id,
+            result: None,
+            error: Some(RpcError { code, message: message.into() }),
+        }
+    }
+}
+
+impl From<DispatchError> for RpcError {
+    fn from(error: DispatchError) -> Self {
+        let code = match error {
+            DispatchError::MethodNotFound(_) => METHOD_NOT_FOUND,
+            DispatchError::InvalidParams(_) => INVALID_PARAMS,
+            DispatchError::Timeout(_) => REQUEST_TIMEOUT,
+        };
+        RpcError { code, message: error.to_string() }
+    }
+}
+
+/// Serve JSON-RPC requests from stdin until it is closed
+pub async fn run_stdio(state: McpServerState) -> Result<()> {
+    log::info!("NekoMCP serving JSON-RPC over stdio");
+
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+
+    while let Some(line) = lines.next_line().await? {
+        if let Some(response) = handle_line(&state, &line).await {
+            stdout.write_all(response.as_bytes()).await?;
+            stdout.write_all(b"\n").await?;
+            stdout.flush().await?;
+        }
+    }
+
+    log::info!("stdin closed, shutting down");
+    Ok(())
+}
+
+/// Handle one line of input, returning the serialized response if one is due
+pub async fn handle_line(state: &McpServerState, line: &str) -> Option<String> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+
+    let response = match serde_json::from_str::<Value>(line) {
+        Err(e) => Some(RpcResponse::error(Value::Null, PARSE_ERROR, format!("Parse error: {}", e))),
+        Ok(value) => handle_message(state, value).await,
+    };
+
+    response.map(|response| serde_json::to_string(&response).unwrap_or_default())
+}
+
+async fn handle_message(state: &McpServerState, value: Value) -> Option<RpcResponse> {
+    let request: RpcRequest = match serde_json::from_value(value) {
+        Ok(request) => request,
+        Err(e) => {
+            return Some(RpcResponse::error(Value::Null, INVALID_REQUEST, format!("Invalid request: {}", e)));
+        }
+    };
+
+    if request.jsonrpc != "2.0" {
+        let id = request.id.unwrap_or(Value::Null);
+        return Some(RpcResponse::error(id, INVALID_REQUEST, "Invalid request: jsonrpc must be \"2.0\""));
+    }
+
+    log::debug!("stdio request: {}", request.method);
+    let outcome = handle_method(state, &request.method, request.params).await;
+
+    // Notifications never get a response, not even an error
+    let id = request.id?;
+    Some(match outcome {
+        Ok(result) => RpcResponse::result(id, result),
+        Err(error) => RpcResponse { jsonrpc: "2.0", id, result: None, error: Some(error) },
+    })
+}
+
+async fn handle_method(state: &McpServerState, method: &str, params: Value) -> Result<Value, RpcError> {
+    match method {
+        "initialize" => Ok(json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": { "tools": {} },
+            "serverInfo": { "name": crate::NAME, "version": crate::VERSION },
+        })),
+        "notifications/initialized" | "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+        "tools/call" => call_tool(state, params).await,
+        _ => state.dispatch(method, params).await.map_err(RpcError::from),
+    }
+}
+
+/// Run a `tools/call` request through the shared dispatcher
+async fn call_tool(state: &McpServerState, params: Value) -> Result<Value, RpcError> {
+    let name = params.get("name").and_then(Value::as_str).unwrap_or_default();
+    let method = TOOLS
+        .iter()
+        .find(|(tool, _, _)| *tool == name)
+        .map(|(_, method, _)| *method)
+        .ok_or_else(|| RpcError { code: INVALID_PARAMS, message: format!("Unknown tool: {}", name) })?;
+
+    let arguments = params.get("arguments").cloned().unwrap_or(Value::Null);
+    let response = state.dispatch(method, arguments).await?;
+    let is_error = response.get("success").and_then(Value::as_bool) == Some(false);
+
+    Ok(json!({
+        "content": [{
+            "type": "text",
+            "text": serde_json::to_string_pretty(&response).unwrap_or_default(),
+        }],
+        "isError": is_error,
+    }))
+}
+
+fn tool_definitions() -> Vec<Value> {
+    TOOLS
+        .iter()
+        .map(|(name, _, description)| {
+            json!({
+                "name": name,
+                "description": description,
+                "inputSchema": input_schema(name),
+            })
+        })
+        .collect()
+}
+
+fn input_schema(tool: &str) -> Value {
+    match tool {
+        "analyze" => json!({
+            "type": "object",
+            "properties": {
+                "path": { "type": "string", "description": "File or directory to analyze" },
+                "language": { "type": "string" },
+                "stats_only": { "type": "boolean" },
+            },
+            "required": ["path"],
+        }),
+        "analyze_batch" => json!({
+            "type": "object",
+            "properties": {
+                "paths": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Files or directories to analyze",
+                },
+                "directory": { "type": "string", "description": "Directory to analyze instead of paths" },
+                "offset": { "type": "integer", "description": "next_offset from the previous page" },
+                "limit": { "type": "integer", "description": "Files per page" },
+                "stats_only": { "type": "boolean" },
+            },
+        }),
+        "session_create" => json!({
+            "type": "object",
+            "properties": { "path": { "type": "string" } },
+            "required": ["path"],
+        }),
+        "session_update" => json!({
+            "type": "object",
+            "properties": {
+                "session_id": { "type": "string" },
+                "verbose": { "type": "boolean" },
+            },
+            "required": ["session_id"],
+        }),
+        "session_stats" => json!({
+            "type": "object",
+            "properties": { "session_id": { "type": "string" } },
+            "required": ["session_id"],
+        }),
+        _ => json!({ "type": "object", "properties": {} }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::RequestLimits;
+
+    async fn call(line: &str) -> Value {
+        let state = McpServerState::new();
+        let response = handle_line(&state, line).await.expect("expected a response");
+        serde_json::from_str(&response).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_direct_method_shares_dispatch() {
+        let response = call(r#"{"jsonrpc":"2.0","id":1,"method":"health"}"#).await;
+        assert_eq!(response["id"], 1);
+        assert_eq!(response["result"]["status"], "healthy");
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        let response = call("{not json").await;
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+
+        let response = call(r#"{"jsonrpc":"2.0","id":2,"method":"no/such"}"#).await;
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+
+        let response = call(r#"{"jsonrpc":"2.0","id":3,"method":"session/stats","params":{}}"#).await;
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn test_notification_has_no_response() {
+        let state = McpServerState::new();
+        let line = r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#;
+        assert!(handle_line(&state, line).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_tools_list_and_call() {
+        let response = call(r#"{"jsonrpc":"2.0","id":4,"method":"tools/list"}"#).await;
+        let tools = response["result"]["tools"].as_array().unwrap();
+        assert_eq!(tools.len(), TOOLS.len());
+
+        let response = call(
+            r#"{"jsonrpc":"2.0","id":5,"method":"tools/call","params":{"name":"session_stats","arguments":{"session_id":"missing"}}}"#,
+        )
+        .await;
+        assert_eq!(response["result"]["isError"], true);
+    }
+
+    #[tokio::test]
+    async fn test_analyze_batch_pages_and_reports_file_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        std::fs::write(dir.join("a.py"), "def load():\n    return 1\n").unwrap();
+        std::fs::write(dir.join("b.js"), "function save() {}\n").unwrap();
+        let missing = dir.join("missing.py");
+
+        let state = McpServerState::new();
+        let params = json!({ "paths": [dir, missing], "limit": 2 });
+        let first = state.dispatch("analyze/batch", params).await.unwrap();
+        assert_eq!(first["success"], true);
+        assert_eq!(first["total_files"], 3);
+        assert_eq!(first["next_offset"], 2);
+        assert_eq!(first["results"][0]["data"]["functions"][0]["symbol"]["name"], "load");
+        assert_eq!(first["results"][1]["data"]["functions"][0]["symbol"]["name"], "save");
+
+        let params = json!({ "paths": [dir, missing], "offset": 2, "stats_only": true });
+        let last = state.dispatch("analyze/batch", params).await.unwrap();
+        assert!(last["next_offset"].is_null());
+        assert!(last["results"][0]["error"].is_string());
+
+        let response = call(r#"{"jsonrpc":"2.0","id":6,"method":"tools/call","params":{"name":"analyze_batch","arguments":{}}}"#).await;
+        assert_eq!(response["result"]["isError"], true);
+    }
+
+    #[tokio::test]
+    async fn test_request_timeout_while_waiting_for_a_slot() {
+        let limits = RequestLimits { request_timeout_secs: 1, max_concurrent_requests: 1 };
+        let state = McpServerState::with_limits(limits);
+        // Occupy the only slot so the next request cannot start
+        let _slot = state.permits.clone().acquire_owned().await.unwrap();
+
+        let line = r#"{"jsonrpc":"2.0","id":7,"method":"session/list"}"#;
+        let response: Value = serde_json::from_str(&handle_line(&state, line).await.unwrap()).unwrap();
+        assert_eq!(response["error"]["code"], REQUEST_TIMEOUT);
+        assert_eq!(response["error"]["message"], "Request timed out after 1s");
+
+        // Health checks do not need a slot
+        let line = r#"{"jsonrpc":"2.0","id":8,"method":"health"}"#;
+        let response: Value = serde_json::from_str(&handle_line(&state, line).await.unwrap()).unwrap();
+        assert_eq!(response["result"]["status"], "healthy");
+    }
+}
@@ -98,6 +98,10 @@ pub enum Commands {
     MoveClassConfirm {
         /// Preview ID to confirm
         preview_id: String,
+        
+        /// Output format (text, json); json includes the old → new line mapping
+        #[arg(short, long, default_value = "text")]
+        format: String,
     },
     
     /// List all previews
@@ -109,6 +113,10 @@ pub enum Commands {
         /// Show only pending previews
         #[arg(long)]
         pending: bool,
+        
+        /// Output format (text, json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
     },
     
     /// Extract function to new file
@@ -132,12 +140,16 @@ pub enum Commands {
         /// File to split
         file: PathBuf,
         
-        /// Split by (functions, classes, size)
-        #[arg(long, default_value = "classes")]
+        /// Split by (class, function)
+        #[arg(long, default_value = "class")]
         by: String,
         
         /// Output directory
         #[arg(short, long)]
         output: Option<PathBuf>,
+        
+        /// Apply the split immediately instead of only previewing it
+        #[arg(long)]
+        apply: bool,
     },
 }
\ No newline at end of file
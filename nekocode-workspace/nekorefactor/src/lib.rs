@@ -3,8 +3,10 @@
 pub mod preview;
 pub mod replace;
 pub mod moveclass;
+pub mod split;
 pub mod cli;
 
-pub use preview::{PreviewManager, PreviewEntry, PreviewOperation, MatchInfo};
+pub use preview::{PreviewManager, PreviewEntry, PreviewOperation, PreviewSummary, MatchInfo};
 pub use replace::{ReplaceEngine, ReplaceOptions};
-pub use moveclass::{MoveClassEngine, MoveOptions};
\ No newline at end of file
+pub use moveclass::{MoveClassEngine, MoveOptions};
+pub use split::{SplitEngine, SplitOptions, SplitBy, SplitOutput};
\ No newline at end of file
@@ -3,6 +3,7 @@
 mod preview;
 mod replace;
 mod moveclass;
+mod split;
 mod cli;
 
 use clap::Parser;
@@ -13,6 +14,7 @@ use crate::cli::{Cli, Commands};
 use crate::preview::{PreviewManager, InsertPosition};
 use crate::replace::{ReplaceEngine, ReplaceOptions};
 use crate::moveclass::{MoveClassEngine, MoveOptions};
+use crate::split::{SplitEngine, SplitOptions};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -154,7 +156,7 @@ async fn main() -> Result<()> {
             println!("Use 'nekorefactor moveclass-confirm {}' to apply", preview_id);
         }
         
-        Commands::MoveClassConfirm { preview_id } => {
+        Commands::MoveClassConfirm { preview_id, format } => {
             let preview = preview_manager.get_preview(&preview_id)
                 .ok_or_else(|| NekocodeError::Preview(format!("Preview not found: {}", preview_id)))?;
             
@@ -162,7 +164,9 @@ async fn main() -> Result<()> {
                 let mut engine = MoveClassEngine::default()?;
                 let result = engine.move_symbol(session_id, symbol_id, target_file).await?;
                 
-                if result.success {
+                if format == "json" {
+                    println!("{}", serde_json::to_string_pretty(&result)?);
+                } else if result.success {
                     println!("✅ Successfully moved {}", result.symbol_name);
                     println!("   {} lines moved", result.lines_moved);
                     if !result.imports_updated.is_empty() {
@@ -176,18 +180,19 @@ async fn main() -> Result<()> {
             }
         }
         
-        Commands::ListPreviews { detailed, pending } => {
-            let previews = preview_manager.list_previews();
+        Commands::ListPreviews { detailed, pending, format } => {
+            let previews: Vec<_> = preview_manager.list_previews().into_iter()
+                .filter(|preview| !(pending && (preview.confirmed || preview.applied)))
+                .collect();
             
-            if previews.is_empty() {
+            if format == "json" {
+                let summaries: Vec<_> = previews.iter().map(|preview| preview.summary()).collect();
+                println!("{}", serde_json::to_string_pretty(&summaries)?);
+            } else if previews.is_empty() {
                 println!("No previews found");
             } else {
                 println!("📋 Previews:");
                 for preview in previews {
-                    if pending && (preview.confirmed || preview.applied) {
-                        continue;
-                    }
-                    
                     if detailed {
                         println!("\n🆔 {}", preview.id);
                         println!("   Created: {}", preview.created_at.format("%Y-%m-%d %H:%M"));
@@ -198,13 +203,7 @@ async fn main() -> Result<()> {
                         } else {
                             "Pending"
                         });
-                        println!("   Operation: {:?}", match &preview.operation {
-                            preview::PreviewOperation::Replace { .. } => "Replace",
-                            preview::PreviewOperation::Insert { .. } => "Insert",
-                            preview::PreviewOperation::MoveLines { .. } => "MoveLines",
-                            preview::PreviewOperation::MoveClass { .. } => "MoveClass",
-                            preview::PreviewOperation::Delete { .. } => "Delete",
-                        });
+                        println!("   Operation: {:?}", preview.operation.kind());
                     } else {
                         let status = if preview.applied {
                             "✅"
@@ -241,13 +240,26 @@ async fn main() -> Result<()> {
             }
         }
         
-        Commands::SplitFile { file, by, output } => {
-            // TODO: Implement file splitting
-            println!("File splitting not yet implemented");
-            println!("File: {}", file.display());
-            println!("Split by: {}", by);
-            if let Some(output) = output {
-                println!("Output: {}", output.display());
+        Commands::SplitFile { file, by, output, apply } => {
+            let options = SplitOptions {
+                by: by.parse()?,
+                output_dir: output,
+            };
+            
+            let engine = SplitEngine::new(options);
+            let preview_op = engine.create_preview(&file).await?;
+            let preview_id = preview_manager.add_preview(preview_op)?;
+            
+            let preview = preview_manager.get_preview(&preview_id).unwrap();
+            println!("{}", preview.preview_text);
+            
+            if apply {
+                preview_manager.confirm_preview(&preview_id)?;
+                preview_manager.apply_preview(&preview_id)?;
+                println!("✅ Split {} applied successfully", file.display());
+            } else {
+                println!("\n✨ Preview ID: {}", preview_id);
+                println!("Use 'nekorefactor replace-confirm {}' to apply changes, or re-run with --apply", preview_id);
             }
         }
     }
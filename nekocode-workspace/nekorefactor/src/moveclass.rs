@@ -1,11 +1,13 @@
 //! Move class/function functionality
 
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use std::fs;
+use regex::Regex;
+use serde::Serialize;
 
 use nekocode_core::{
     Result, NekocodeError, SessionManager, Language,
-    SymbolInfo, FunctionInfo, ClassInfo
+    SymbolInfo, FunctionInfo, ClassInfo, AnalysisResult, resolve_import_path
 };
 
 /// Options for move operations
@@ -56,14 +58,17 @@ impl MoveClassEngine {
         target_file: &Path
     ) -> Result<MoveResult> {
         // Get session and find symbol
-        let symbol_info = {
+        let (symbol_info, analysis_results) = {
             let session = self.session_manager.get_session_mut(session_id)?;
-            Self::find_symbol_in_session(session, symbol_id)?
+            (Self::find_symbol_in_session(session, symbol_id)?, session.info.analysis_results.clone())
         };
         
         // Extract the symbol content from source file
         let source_file = &symbol_info.file_path;
         let symbol_content = self.extract_symbol_content(source_file, &symbol_info)?;
+        let lines_moved = symbol_content.lines().count();
+        // Leading comments move along, so the block can start above `line_start`
+        let old_start = symbol_info.line_end + 1 - lines_moved as u32;
         
         // Check if target file exists
         if !target_file.exists() && !self.options.create_target_if_missing {
@@ -78,25 +83,34 @@ impl MoveClassEngine {
             symbol_type: format!("{:?}", symbol_info.symbol_type),
             source_file: source_file.clone(),
             target_file: target_file.to_path_buf(),
-            lines_moved: symbol_content.lines().count(),
+            lines_moved,
+            line_mapping: Vec::new(),
             imports_updated: Vec::new(),
             success: false,
         };
         
         if self.options.dry_run {
+            let (_, new_start) = self.place_symbol(target_file, &symbol_content, symbol_info.language)?;
+            result.line_mapping = line_mapping(old_start, new_start, lines_moved);
             result.success = true;
             return Ok(result);
         }
         
         // Perform the move
-        self.perform_move(source_file, target_file, &symbol_info, &symbol_content)?;
+        let planned_start = self.perform_move(source_file, target_file, &symbol_info, &symbol_content)?;
         
         // Update imports if requested
         if self.options.update_imports {
-            let updated = self.update_imports_for_move(&symbol_info, source_file, target_file)?;
+            let updated = self.update_imports_for_move(&symbol_info, source_file, target_file, &analysis_results)?;
             result.imports_updated = updated;
         }
         
+        // Lines added above the symbol after it was placed shift it down
+        let target_content = fs::read_to_string(target_file)
+            .map_err(NekocodeError::Io)?;
+        let new_start = locate_lines(&target_content, &symbol_content, planned_start).unwrap_or(planned_start);
+        result.line_mapping = line_mapping(old_start, new_start, lines_moved);
+        
         result.success = true;
         Ok(result)
     }
@@ -160,21 +174,20 @@ impl MoveClassEngine {
         Ok(final_lines.join("\n"))
     }
     
-    /// Perform the actual move operation
+    /// Perform the actual move operation; returns the line the symbol starts
+    /// on in the target file
     fn perform_move(
         &self,
         source_file: &Path,
         target_file: &Path,
         symbol: &SymbolInfo,
         symbol_content: &str
-    ) -> Result<()> {
+    ) -> Result<u32> {
         // Remove from source file
         self.remove_symbol_from_file(source_file, symbol)?;
         
         // Add to target file
-        self.add_symbol_to_file(target_file, symbol_content, symbol.language)?;
-        
-        Ok(())
+        self.add_symbol_to_file(target_file, symbol_content, symbol.language)
     }
     
     /// Remove symbol from file
@@ -208,22 +221,33 @@ impl MoveClassEngine {
         Ok(())
     }
     
-    /// Add symbol to file
+    /// Add symbol to file; returns the line the symbol starts on
     fn add_symbol_to_file(
         &self,
         file: &Path,
         symbol_content: &str,
         language: Language
-    ) -> Result<()> {
+    ) -> Result<u32> {
+        let (new_content, start_line) = self.place_symbol(file, symbol_content, language)?;
+        fs::write(file, new_content)
+            .map_err(NekocodeError::Io)?;
+        
+        Ok(start_line)
+    }
+    
+    /// Content of `file` with the symbol added, and the line the symbol starts on
+    fn place_symbol(
+        &self,
+        file: &Path,
+        symbol_content: &str,
+        language: Language
+    ) -> Result<(String, u32)> {
         // Create file if it doesn't exist
         if !file.exists() {
             if self.options.create_target_if_missing {
                 // Create with appropriate header
-                let header = self.generate_file_header(file, language);
-                let content = format!("{}\n\n{}", header, symbol_content);
-                fs::write(file, content)
-                    .map_err(|e| NekocodeError::Io(e))?;
-                return Ok(());
+                let prefix = format!("{}\n\n", self.generate_file_header(file, language));
+                return Ok((format!("{}{}", prefix, symbol_content), next_line(&prefix)));
             } else {
                 return Err(NekocodeError::Refactoring(
                     format!("Target file does not exist: {}", file.display())
@@ -239,20 +263,16 @@ impl MoveClassEngine {
         let insertion_point = self.find_insertion_point(&existing, language);
         
         // Insert the symbol
-        let new_content = if insertion_point == existing.len() {
+        if insertion_point == existing.len() {
             // Append at end
-            format!("{}\n\n{}", existing, symbol_content)
+            let prefix = format!("{}\n\n", existing);
+            Ok((format!("{}{}", prefix, symbol_content), next_line(&prefix)))
         } else {
             // Insert at specific position
             let mut lines: Vec<&str> = existing.lines().collect();
             lines.insert(insertion_point, symbol_content);
-            lines.join("\n")
-        };
-        
-        fs::write(file, new_content)
-            .map_err(|e| NekocodeError::Io(e))?;
-        
-        Ok(())
+            Ok((lines.join("\n"), insertion_point as u32 + 1))
+        }
     }
     
     /// Generate file header for new files
@@ -336,25 +356,78 @@ impl MoveClassEngine {
     }
     
     /// Update imports after moving a symbol
+    ///
+    /// Every import in the session that resolves to `source_file` and names the
+    /// symbol is pointed at `target_file` instead. When the import also brings
+    /// in other names, the symbol is split off into its own import. JavaScript,
+    /// TypeScript and Python `from` imports are rewritten; other import styles
+    /// are left untouched.
     fn update_imports_for_move(
         &self,
         symbol: &SymbolInfo,
         source_file: &Path,
-        target_file: &Path
-    ) -> Result<Vec<PathBuf>> {
-        // This is a simplified version
-        // Real implementation would need to:
-        // 1. Find all files that import the symbol
-        // 2. Update their import statements
-        // 3. Handle different import styles for each language
-        
-        let mut updated_files = Vec::new();
-        
-        // TODO: Implement actual import updating logic
-        // This would require parsing imports in all project files
-        // and updating them to point to the new location
+        target_file: &Path,
+        analysis_results: &[AnalysisResult]
+    ) -> Result<Vec<ImportUpdate>> {
+        let mut updates = Vec::new();
+        let source = normalize_path(source_file);
+        let target = normalize_path(target_file);
+        
+        for result in analysis_results {
+            let file = &result.file_info.path;
+            let normalized = normalize_path(file);
+            if normalized == source || normalized == target {
+                continue;
+            }
+            
+            let language = result.file_info.language;
+            let candidates: Vec<_> = result.imports.iter()
+                .filter(|import| resolve_import_path(&import.module, file, language)
+                    .is_some_and(|resolved| normalize_path(&resolved) == source))
+                .collect();
+            if candidates.is_empty() {
+                continue;
+            }
+            
+            let content = fs::read_to_string(file)
+                .map_err(NekocodeError::Io)?;
+            let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+            
+            // Rewrite bottom-up so inserted lines don't shift pending edits
+            let mut file_updates = Vec::new();
+            for import in candidates {
+                let index = (import.line as usize).saturating_sub(1);
+                let Some(line) = lines.get(index) else { continue };
+                let Some(new_module) = module_specifier(file, source_file, target_file, &import.module, language) else { continue };
+                let Some(rewritten) = rewrite_import_line(line, &symbol.name, &import.module, &new_module, language) else { continue };
+                
+                file_updates.push((index, rewritten, ImportUpdate {
+                    file: file.clone(),
+                    line: import.line,
+                    old_module: import.module.clone(),
+                    new_module,
+                }));
+            }
+            if file_updates.is_empty() {
+                continue;
+            }
+            
+            file_updates.sort_by_key(|(index, _, _)| std::cmp::Reverse(*index));
+            for (index, rewritten, update) in file_updates {
+                lines.splice(index..=index, rewritten);
+                updates.push(update);
+            }
+            
+            let mut new_content = lines.join("\n");
+            if content.ends_with('\n') {
+                new_content.push('\n');
+            }
+            fs::write(file, new_content)
+                .map_err(NekocodeError::Io)?;
+        }
         
-        Ok(updated_files)
+        updates.sort_by(|a, b| (&a.file, a.line).cmp(&(&b.file, b.line)));
+        Ok(updates)
     }
     
     /// Remove extra blank lines from content
@@ -380,13 +453,325 @@ impl MoveClassEngine {
 }
 
 /// Result of a move operation
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct MoveResult {
     pub symbol_name: String,
     pub symbol_type: String,
     pub source_file: PathBuf,
     pub target_file: PathBuf,
     pub lines_moved: usize,
-    pub imports_updated: Vec<PathBuf>,
+    /// `(source line, target line)` for every moved line, leading comments
+    /// included, so editors can carry cursors and bookmarks along
+    pub line_mapping: Vec<(u32, u32)>,
+    pub imports_updated: Vec<ImportUpdate>,
     pub success: bool,
-}
\ No newline at end of file
+}
+
+/// An import that was rewritten to follow a moved symbol
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ImportUpdate {
+    pub file: PathBuf,
+    pub line: u32,
+    pub old_module: String,
+    pub new_module: String,
+}
+
+/// Line number that follows `prefix`
+fn next_line(prefix: &str) -> u32 {
+    prefix.matches('\n').count() as u32 + 1
+}
+
+/// `count` consecutive lines from `old_start` landing at `new_start`
+fn line_mapping(old_start: u32, new_start: u32, count: usize) -> Vec<(u32, u32)> {
+    (0..count as u32).map(|offset| (old_start + offset, new_start + offset)).collect()
+}
+
+/// Line where `block` starts in `content`; of several copies, the one
+/// nearest `expected`
+fn locate_lines(content: &str, block: &str, expected: u32) -> Option<u32> {
+    let lines: Vec<&str> = content.lines().collect();
+    let block: Vec<&str> = block.lines().collect();
+    if block.is_empty() {
+        return None;
+    }
+    lines.windows(block.len())
+        .enumerate()
+        .filter(|(_, window)| *window == block.as_slice())
+        .map(|(index, _)| index as u32 + 1)
+        .min_by_key(|start| start.abs_diff(expected))
+}
+
+/// Make a path absolute and resolve `.`/`..` lexically, so paths recorded
+/// relative to different directories can be compared
+fn normalize_path(path: &Path) -> PathBuf {
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let mut normalized = PathBuf::new();
+    for component in absolute.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => { normalized.pop(); }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Path of `to` relative to the directory `from_dir`, e.g. `../models/user.js`
+fn relative_path(from_dir: &Path, to: &Path) -> PathBuf {
+    let from: Vec<_> = normalize_path(from_dir).components().map(|c| c.as_os_str().to_owned()).collect();
+    let to_components: Vec<_> = normalize_path(to).components().map(|c| c.as_os_str().to_owned()).collect();
+    let common = from.iter().zip(&to_components).take_while(|(a, b)| a == b).count();
+    
+    let mut relative = PathBuf::new();
+    for _ in common..from.len() {
+        relative.push("..");
+    }
+    for component in &to_components[common..] {
+        relative.push(component);
+    }
+    relative
+}
+
+/// Module specifier that makes `importer` import from `target` in the same
+/// style as `original` (which pointed at `source`)
+fn module_specifier(importer: &Path, source: &Path, target: &Path, original: &str, language: Language) -> Option<String> {
+    let importer_dir = importer.parent()?;
+    
+    match language {
+        Language::JavaScript | Language::TypeScript => {
+            let relative = relative_path(importer_dir, target);
+            let mut specifier = relative.to_string_lossy().replace('\\', "/");
+            // Keep the extension only if the original import spelled one out
+            let had_extension = Path::new(original).extension().is_some_and(|ext| {
+                matches!(ext.to_str(), Some("js" | "ts" | "jsx" | "tsx" | "mjs"))
+            });
+            if !had_extension {
+                if let Some(ext) = relative.extension().and_then(|e| e.to_str()) {
+                    specifier.truncate(specifier.len() - ext.len() - 1);
+                }
+            }
+            if !specifier.starts_with("../") {
+                specifier.insert_str(0, "./");
+            }
+            Some(specifier)
+        }
+        Language::Python => {
+            let module_parts = |path: &Path| -> Vec<String> {
+                path.with_extension("").components()
+                    .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                    .collect()
+            };
+            
+            if original.starts_with('.') {
+                // Relative import: one dot for the importer's package, one more per level up
+                let parts = module_parts(&relative_path(importer_dir, target));
+                let ups = parts.iter().take_while(|p| *p == "..").count();
+                Some(format!("{}{}", ".".repeat(ups + 1), parts[ups..].join(".")))
+            } else {
+                // Absolute import: strip the module path from the source to find the import root
+                let depth = original.split('.').count();
+                let root = normalize_path(source).ancestors().nth(depth)?.to_path_buf();
+                let relative = normalize_path(target).strip_prefix(&root).ok()?.to_path_buf();
+                Some(module_parts(&relative).join("."))
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Rewrite one import line so `symbol` comes from `new_module`. Returns the
+/// replacement lines, or `None` if the line does not import `symbol` by name.
+fn rewrite_import_line(line: &str, symbol: &str, old_module: &str, new_module: &str, language: Language) -> Option<Vec<String>> {
+    let pattern = match language {
+        Language::JavaScript | Language::TypeScript => {
+            r#"^(?P<indent>\s*)import\s+(?P<names>.+?)\s+from\s+['"](?P<module>[^'"]+)['"]"#
+        }
+        Language::Python => r"^(?P<indent>\s*)from\s+(?P<module>\S+)\s+import\s+(?P<names>.+?)\s*$",
+        _ => return None,
+    };
+    let captures = Regex::new(pattern).ok()?.captures(line)?;
+    let indent = captures.name("indent").map_or("", |m| m.as_str());
+    let clause_match = captures.name("names")?;
+    let module_match = captures.name("module")?;
+    if module_match.as_str() != old_module {
+        return None;
+    }
+    let clause = clause_match.as_str();
+    
+    // Edit only the parsed spans; the same text may appear elsewhere on the line
+    let splice = |span: regex::Match, with: &str| {
+        format!("{}{}{}", &line[..span.start()], with, &line[span.end()..])
+    };
+    
+    let braced = clause.starts_with('{') || clause.starts_with('(');
+    let names: Vec<&str> = clause.trim_matches(|c| matches!(c, '{' | '}' | '(' | ')'))
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .collect();
+    if language != Language::Python && !braced && names.len() > 1 {
+        // `import Default, { A } from` is not split
+        return None;
+    }
+    
+    let entry = names.iter().find(|name| name.split_whitespace().next() == Some(symbol))?;
+    if names.len() == 1 {
+        return Some(vec![splice(module_match, new_module)]);
+    }
+    
+    // Split the symbol off into its own import next to the remaining names
+    let remaining: Vec<&str> = names.iter().filter(|name| *name != entry).copied().collect();
+    let kept = match language {
+        Language::Python => splice(clause_match, &remaining.join(", ")),
+        _ => splice(clause_match, &format!("{{ {} }}", remaining.join(", "))),
+    };
+    let moved = match language {
+        Language::Python => format!("{}from {} import {}", indent, new_module, entry),
+        _ => {
+            let quote = if line.contains('\'') { '\'' } else { '"' };
+            let semicolon = if line.trim_end().ends_with(';') { ";" } else { "" };
+            format!("{}import {{ {} }} from {}{}{}{}", indent, entry, quote, new_module, quote, semicolon)
+        }
+    };
+    Some(vec![kept, moved])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nekocode_core::types::SymbolType;
+
+    #[tokio::test]
+    async fn test_move_class_up_one_directory_rewrites_imports() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        fs::create_dir_all(dir.join("src/models")).unwrap();
+        let source = dir.join("src/models/user.js");
+        let target = dir.join("src/user.js");
+        let app = dir.join("src/app.js");
+        let admin = dir.join("src/models/admin.js");
+        
+        fs::write(&source, "class User {\n  constructor(name) {\n    this.name = name;\n  }\n}\n\nclass Guest {}\n\nmodule.exports = { User, Guest };\n").unwrap();
+        fs::write(&app, "import { User } from './models/user';\nimport { Other } from './other';\n\nconst u = new User('neko');\n").unwrap();
+        fs::write(&admin, "import { User, Guest } from './user';\n\nclass Admin extends User {}\n").unwrap();
+        
+        let mut analysis_results = Vec::new();
+        for file in [&source, &app, &admin] {
+            let content = fs::read_to_string(file).unwrap();
+            let mut analyzer = nekocode::create_analyzer(Language::JavaScript).unwrap();
+            analysis_results.push(analyzer.analyze(file, &content).await.unwrap());
+        }
+        
+        let symbol = SymbolInfo {
+            id: "User".to_string(),
+            name: "User".to_string(),
+            symbol_type: SymbolType::Class,
+            file_path: source.clone(),
+            line_start: 1,
+            line_end: 5,
+            column_start: 0,
+            column_end: 0,
+            language: Language::JavaScript,
+            visibility: None,
+            parent_id: None,
+            metadata: Default::default(),
+        };
+        let engine = MoveClassEngine::default().unwrap();
+        let content = engine.extract_symbol_content(&source, &symbol).unwrap();
+        engine.perform_move(&source, &target, &symbol, &content).unwrap();
+        let updates = engine.update_imports_for_move(&symbol, &source, &target, &analysis_results).unwrap();
+        
+        let mut new_modules: Vec<_> = updates.iter().map(|u| u.new_module.as_str()).collect();
+        new_modules.sort();
+        assert_eq!(new_modules, vec!["../user", "./user"]);
+        
+        assert_eq!(
+            fs::read_to_string(&app).unwrap(),
+            "import { User } from './user';\nimport { Other } from './other';\n\nconst u = new User('neko');\n"
+        );
+        assert_eq!(
+            fs::read_to_string(&admin).unwrap(),
+            "import { Guest } from './user';\nimport { User } from '../user';\n\nclass Admin extends User {}\n"
+        );
+        assert!(fs::read_to_string(&target).unwrap().contains("class User"));
+    }
+    
+    #[test]
+    fn test_rewrite_import_line_edits_parsed_spans() {
+        let lines = |lines: &[&str]| Some(lines.iter().map(|l| l.to_string()).collect::<Vec<_>>());
+        
+        // `m` also occurs in `from`
+        assert_eq!(
+            rewrite_import_line("from m import Shape", "Shape", "m", "shapes", Language::Python),
+            lines(&["from shapes import Shape"])
+        );
+        assert_eq!(
+            rewrite_import_line("    from m import Shape, Circle", "Shape", "m", "shapes", Language::Python),
+            lines(&["    from m import Circle", "    from shapes import Shape"])
+        );
+        assert_eq!(
+            rewrite_import_line("import { User } from './user'; // was './user'", "User", "./user", "../user", Language::JavaScript),
+            lines(&["import { User } from '../user'; // was './user'"])
+        );
+        assert_eq!(rewrite_import_line("import { User } from './other';", "User", "./user", "../user", Language::JavaScript), None);
+    }
+    
+    #[tokio::test]
+    async fn test_line_mapping_follows_moved_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        let source = dir.join("user.js");
+        let target = dir.join("models.js");
+        let app = dir.join("app.js");
+        let source_content = "const a = 1;\n// A user\nclass User {\n  constructor() {}\n}\n";
+        fs::write(&source, source_content).unwrap();
+        fs::write(&target, "import { x } from './x';\nconst y = 1;\n\nfunction f() {}\n").unwrap();
+        fs::write(&app, "import { User } from './user';\n\nnew User();\n").unwrap();
+        
+        let mut info = nekocode_core::SessionInfo::new("mapping".to_string(), dir.to_path_buf());
+        for file in [&source, &app] {
+            let content = fs::read_to_string(file).unwrap();
+            let mut analyzer = nekocode::create_analyzer(Language::JavaScript).unwrap();
+            info.analysis_results.push(analyzer.analyze(file, &content).await.unwrap());
+        }
+        info.analysis_results[0].symbols.push(SymbolInfo {
+            id: "User".to_string(),
+            name: "User".to_string(),
+            symbol_type: SymbolType::Class,
+            file_path: source.clone(),
+            line_start: 3,
+            line_end: 5,
+            column_start: 0,
+            column_end: 0,
+            language: Language::JavaScript,
+            visibility: None,
+            parent_id: None,
+            metadata: Default::default(),
+        });
+        let mut engine = MoveClassEngine {
+            session_manager: SessionManager::with_dir(dir.join(".nekocode_sessions")),
+            options: MoveOptions::default(),
+        };
+        engine.session_manager.save_session_info(info).unwrap();
+        
+        let result = engine.move_symbol("mapping", "User", &target).await.unwrap();
+        assert!(result.success);
+        assert_eq!(result.imports_updated.len(), 1);
+        assert_eq!(fs::read_to_string(&app).unwrap(), "import { User } from './models';\n\nnew User();\n");
+        
+        // The leading comment moves too, and lands after the target's header
+        assert_eq!(result.line_mapping, vec![(2, 3), (3, 4), (4, 5), (5, 6)]);
+        let old_lines: Vec<&str> = source_content.lines().collect();
+        let target_content = fs::read_to_string(&target).unwrap();
+        let new_lines: Vec<&str> = target_content.lines().collect();
+        for (old, new) in &result.line_mapping {
+            assert_eq!(old_lines[*old as usize - 1], new_lines[*new as usize - 1]);
+        }
+        
+        // An import added above the symbol afterwards shifts it down
+        let content = "// A user\nclass User {\n  constructor() {}\n}";
+        let shifted = format!("import {{ z }} from './z';\n{}", target_content);
+        assert_eq!(locate_lines(&shifted, content, 3), Some(4));
+        assert_eq!(locate_lines(&shifted, "class Missing {}", 3), None);
+    }
+}
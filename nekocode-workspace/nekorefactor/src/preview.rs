@@ -9,6 +9,8 @@ use uuid::Uuid;
 
 use nekocode_core::{Result, NekocodeError};
 
+use crate::split::{SplitBy, SplitOutput};
+
 /// Types of preview operations
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PreviewOperation {
@@ -44,6 +46,40 @@ pub enum PreviewOperation {
         end_line: u32,
         content: Vec<String>,
     },
+    SplitFile {
+        file: PathBuf,
+        by: SplitBy,
+        outputs: Vec<SplitOutput>,
+        remaining_content: String,
+    },
+}
+
+impl PreviewOperation {
+    /// Variant name, as shown by `list-previews`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            PreviewOperation::Replace { .. } => "Replace",
+            PreviewOperation::Insert { .. } => "Insert",
+            PreviewOperation::MoveLines { .. } => "MoveLines",
+            PreviewOperation::MoveClass { .. } => "MoveClass",
+            PreviewOperation::Delete { .. } => "Delete",
+            PreviewOperation::SplitFile { .. } => "SplitFile",
+        }
+    }
+    
+    /// Files the operation writes when applied
+    pub fn affected_files(&self) -> Vec<&Path> {
+        match self {
+            PreviewOperation::Replace { file, .. }
+            | PreviewOperation::Insert { file, .. }
+            | PreviewOperation::Delete { file, .. } => vec![file],
+            PreviewOperation::MoveLines { source, destination, .. } => vec![source, destination],
+            PreviewOperation::MoveClass { source_file, target_file, .. } => vec![source_file, target_file],
+            PreviewOperation::SplitFile { file, outputs, .. } => {
+                std::iter::once(file.as_path()).chain(outputs.iter().map(|o| o.path.as_path())).collect()
+            }
+        }
+    }
 }
 
 /// Position for insert operations
@@ -77,7 +113,30 @@ pub struct PreviewEntry {
     pub applied: bool,
 }
 
+/// Machine-readable listing of a preview (`list-previews --format json`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreviewSummary {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub confirmed: bool,
+    pub applied: bool,
+    pub operation: String,
+    pub affected_files: Vec<PathBuf>,
+}
+
 impl PreviewEntry {
+    /// Summary without the operation payload or preview text
+    pub fn summary(&self) -> PreviewSummary {
+        PreviewSummary {
+            id: self.id.clone(),
+            created_at: self.created_at,
+            confirmed: self.confirmed,
+            applied: self.applied,
+            operation: self.operation.kind().to_string(),
+            affected_files: self.operation.affected_files().into_iter().map(Path::to_path_buf).collect(),
+        }
+    }
+    
     /// Create new preview entry
     pub fn new(operation: PreviewOperation) -> Result<Self> {
         let id = Uuid::new_v4().to_string()[..8].to_string();
@@ -196,6 +255,35 @@ impl PreviewEntry {
                 
                 Ok(preview)
             }
+            
+            PreviewOperation::SplitFile { file, by, outputs, remaining_content } => {
+                let mut preview = String::new();
+                preview.push_str("✂️ Split File Operation Preview\n");
+                preview.push_str(&format!("📁 File: {}\n", file.display()));
+                preview.push_str(&format!("🔀 Split by: {}\n", by));
+                preview.push_str(&format!("📊 New files: {}\n\n", outputs.len()));
+                
+                for output in outputs {
+                    preview.push_str(&format!(
+                        "  📄 {} ({} lines): {}\n",
+                        output.path.display(),
+                        output.content.lines().count(),
+                        output.symbols.join(", ")
+                    ));
+                }
+                
+                let remaining: Vec<&str> = remaining_content.lines().collect();
+                preview.push_str(&format!("\n📝 {} after split ({} lines):\n", file.display(), remaining.len()));
+                for (i, line) in remaining.iter().enumerate().take(10) {
+                    preview.push_str(&format!("  {}: {}\n", i + 1, line));
+                }
+                
+                if remaining.len() > 10 {
+                    preview.push_str(&format!("  ... and {} more lines\n", remaining.len() - 10));
+                }
+                
+                Ok(preview)
+            }
         }
     }
 }
@@ -287,6 +375,9 @@ impl PreviewManager {
             PreviewOperation::Delete { file, start_line, end_line, .. } => {
                 self.apply_delete(file, *start_line, *end_line)?;
             }
+            PreviewOperation::SplitFile { file, outputs, remaining_content, .. } => {
+                self.apply_split_file(file, outputs, remaining_content)?;
+            }
             PreviewOperation::MoveClass { .. } => {
                 // MoveClass requires more complex handling
                 return Err(NekocodeError::Preview("MoveClass requires session context".to_string()));
@@ -449,6 +540,30 @@ impl PreviewManager {
         Ok(())
     }
     
+    /// Apply split file operation
+    fn apply_split_file(&self, file: &Path, outputs: &[SplitOutput], remaining_content: &str) -> Result<()> {
+        // Never clobber existing files with split output
+        if let Some(existing) = outputs.iter().find(|o| o.path.exists()) {
+            return Err(NekocodeError::Preview(
+                format!("Split target already exists: {}", existing.path.display())
+            ));
+        }
+        
+        for output in outputs {
+            if let Some(parent) = output.path.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(NekocodeError::Io)?;
+            }
+            fs::write(&output.path, &output.content)
+                .map_err(NekocodeError::Io)?;
+        }
+        
+        fs::write(file, remaining_content)
+            .map_err(NekocodeError::Io)?;
+        
+        Ok(())
+    }
+    
     /// List all previews
     pub fn list_previews(&self) -> Vec<&PreviewEntry> {
         let mut previews: Vec<_> = self.previews.values().collect();
@@ -470,4 +585,29 @@ impl Default for PreviewManager {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview_summary() {
+        let operation = PreviewOperation::MoveLines {
+            source: PathBuf::from("a.py"),
+            start_line: 1,
+            line_count: 1,
+            destination: PathBuf::from("b.py"),
+            insert_line: 1,
+            lines: vec!["x = 1".to_string()],
+        };
+        let entry = PreviewEntry::new(operation).unwrap();
+        let summary = entry.summary();
+
+        assert_eq!(summary.operation, "MoveLines");
+        assert_eq!(summary.affected_files, vec![PathBuf::from("a.py"), PathBuf::from("b.py")]);
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["id"], entry.id);
+        assert_eq!(json["applied"], false);
+    }
+}
@@ -0,0 +1,657 @@
+//! Split a source file into one file per top-level symbol
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use nekocode::create_analyzer;
+use nekocode_core::{Result, NekocodeError, Language, types::SymbolType};
+
+use crate::preview::PreviewOperation;
+
+/// Name (without extension) of the file that receives helpers shared by several split symbols
+pub const COMMON_FILE_STEM: &str = "common";
+
+/// What kind of top-level symbol each output file holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SplitBy {
+    Class,
+    Function,
+}
+
+impl std::str::FromStr for SplitBy {
+    type Err = NekocodeError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "class" | "classes" => Ok(SplitBy::Class),
+            "function" | "functions" => Ok(SplitBy::Function),
+            _ => Err(NekocodeError::Config(format!(
+                "Unsupported split mode: {} (expected class or function)", s
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for SplitBy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SplitBy::Class => write!(f, "class"),
+            SplitBy::Function => write!(f, "function"),
+        }
+    }
+}
+
+/// A file produced by a split
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SplitOutput {
+    pub path: PathBuf,
+    pub symbols: Vec<String>,
+    pub content: String,
+}
+
+/// Options for split operations
+#[derive(Debug, Clone)]
+pub struct SplitOptions {
+    pub by: SplitBy,
+    /// Directory for the new files (defaults to the directory of the split file)
+    pub output_dir: Option<PathBuf>,
+}
+
+/// A top-level symbol and its line range (1-based, inclusive)
+#[derive(Debug, Clone)]
+struct SymbolSpan {
+    name: String,
+    /// First line of the span, including leading comments/attributes/decorators
+    start: usize,
+    /// First line of the declaration itself
+    decl_start: usize,
+    end: usize,
+}
+
+/// Split engine for refactoring operations
+pub struct SplitEngine {
+    options: SplitOptions,
+}
+
+impl SplitEngine {
+    /// Create new split engine
+    pub fn new(options: SplitOptions) -> Self {
+        Self { options }
+    }
+
+    /// Analyze `file` and build a `PreviewOperation::SplitFile` describing every file to write
+    pub async fn create_preview(&self, file: &Path) -> Result<PreviewOperation> {
+        let content = fs::read_to_string(file)
+            .map_err(NekocodeError::Io)?;
+
+        let language = Language::from_path(file);
+        let mut analyzer = create_analyzer(language)?;
+        let analysis = analyzer.analyze(file, &content).await?;
+
+        // Ranges of every class/function, used to keep only top-level symbols
+        let ranges: Vec<(u32, u32)> = analysis.classes.iter()
+            .map(|c| (c.symbol.line_start, c.symbol.line_end))
+            .chain(analysis.functions.iter().map(|f| (f.symbol.line_start, f.symbol.line_end)))
+            .collect();
+        let is_top_level = |start: u32, end: u32| {
+            !ranges.iter().any(|&(s, e)| s <= start && end <= e && (s, e) != (start, end))
+        };
+
+        let lines: Vec<&str> = content.lines().collect();
+        let to_span = |name: &str, start: u32, end: u32| SymbolSpan {
+            name: name.to_string(),
+            start: Self::leading_comment_start(&lines, start as usize, language),
+            decl_start: start as usize,
+            end: end as usize,
+        };
+
+        let classes: Vec<SymbolSpan> = analysis.classes.iter()
+            .filter(|c| !c.symbol.name.is_empty() && is_top_level(c.symbol.line_start, c.symbol.line_end))
+            .map(|c| to_span(&c.symbol.name, c.symbol.line_start, c.symbol.line_end))
+            .collect();
+        let functions: Vec<SymbolSpan> = analysis.functions.iter()
+            .filter(|f| f.symbol.symbol_type == SymbolType::Function)
+            .filter(|f| !f.symbol.name.is_empty() && is_top_level(f.symbol.line_start, f.symbol.line_end))
+            .map(|f| to_span(&f.symbol.name, f.symbol.line_start, f.symbol.line_end))
+            .collect();
+
+        let (targets, helpers) = match self.options.by {
+            SplitBy::Class => (classes, functions),
+            SplitBy::Function => (functions.clone(), functions),
+        };
+
+        if targets.is_empty() {
+            return Err(NekocodeError::Refactoring(format!(
+                "No top-level {} symbols found in {}", self.options.by, file.display()
+            )));
+        }
+
+        self.build_operation(file, &lines, language, targets, helpers)
+    }
+
+    /// Lay out the new files and the rewritten original
+    fn build_operation(
+        &self,
+        file: &Path,
+        lines: &[&str],
+        language: Language,
+        targets: Vec<SymbolSpan>,
+        helper_candidates: Vec<SymbolSpan>,
+    ) -> Result<PreviewOperation> {
+        let span_text = |span: &SymbolSpan| lines[span.start - 1..span.end].join("\n");
+        let mentions = |name: &str, text: &str| -> Result<bool> {
+            let pattern = Regex::new(&format!(r"\b{}\b", regex::escape(name)))
+                .map_err(|e| NekocodeError::Refactoring(e.to_string()))?;
+            Ok(pattern.is_match(text))
+        };
+
+        // A helper is shared when two or more split symbols (other than itself) reference it
+        let mut common: Vec<SymbolSpan> = Vec::new();
+        for helper in &helper_candidates {
+            let mut users = 0;
+            for target in targets.iter().filter(|t| t.name != helper.name) {
+                users += mentions(&helper.name, &span_text(target))? as usize;
+            }
+            if users >= 2 {
+                common.push(helper.clone());
+            }
+        }
+        let common_names: HashSet<&str> = common.iter().map(|c| c.name.as_str()).collect();
+        let targets: Vec<SymbolSpan> = targets.into_iter()
+            .filter(|t| !common_names.contains(t.name.as_str()))
+            .collect();
+
+        // The remaining helpers follow the code that uses them: one used by a
+        // single split symbol moves into its file, one also used by the code
+        // left behind (or by the shared file) joins the shared file. Repeat
+        // until nothing moves, so helpers of moved helpers follow as well.
+        let target_names: HashSet<&str> = targets.iter().map(|t| t.name.as_str()).collect();
+        let mut attached: Vec<Vec<SymbolSpan>> = vec![Vec::new(); targets.len()];
+        let mut unplaced: Vec<SymbolSpan> = helper_candidates.iter()
+            .filter(|h| !target_names.contains(h.name.as_str()) && !common_names.contains(h.name.as_str()))
+            .cloned()
+            .collect();
+        loop {
+            let mut placed = None;
+            for (index, helper) in unplaced.iter().enumerate() {
+                let mut users = Vec::new();
+                for (t, target) in targets.iter().enumerate() {
+                    let group = std::iter::once(target).chain(&attached[t]).map(span_text).collect::<Vec<_>>().join("\n");
+                    if mentions(&helper.name, &group)? {
+                        users.push(t);
+                    }
+                }
+                let mut shared = false;
+                for other in &common {
+                    shared |= mentions(&helper.name, &span_text(other))?;
+                }
+                if users.is_empty() && !shared {
+                    continue;
+                }
+                let moved: Vec<&SymbolSpan> = targets.iter().chain(attached.iter().flatten()).chain(&common)
+                    .chain(std::iter::once(helper))
+                    .collect();
+                let rest = lines.iter().enumerate()
+                    .filter(|(i, _)| !moved.iter().any(|s| (s.start..=s.end).contains(&(i + 1))))
+                    .map(|(_, line)| *line)
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                let single_user = match users.as_slice() {
+                    [user] if !shared && !mentions(&helper.name, &rest)? => Some(*user),
+                    _ => None,
+                };
+                placed = Some((index, single_user));
+                break;
+            }
+            let Some((index, single_user)) = placed else {
+                break;
+            };
+            let helper = unplaced.remove(index);
+            match single_user {
+                Some(user) => attached[user].push(helper),
+                None => common.push(helper),
+            }
+        }
+        for helpers in &mut attached {
+            helpers.sort_by_key(|helper| helper.start);
+        }
+        common.sort_by_key(|helper| helper.start);
+
+        let source_dir = file.parent().map(Path::to_path_buf).unwrap_or_default();
+        let output_dir = self.options.output_dir.clone().unwrap_or_else(|| source_dir.clone());
+        let ext = file.extension().and_then(|e| e.to_str()).unwrap_or("txt");
+        let preamble = Self::collect_preamble(lines, language);
+
+        let mut outputs = Vec::new();
+        let mut stubs = Vec::new();
+
+        for (index, target) in targets.iter().enumerate() {
+            let stem = Self::file_stem(&target.name, language);
+            let path = output_dir.join(format!("{}.{}", stem, ext));
+            let bodies: Vec<String> = std::iter::once(target).chain(&attached[index])
+                .map(|span| Self::exported_body(lines, span, language))
+                .collect();
+            let body = bodies.join("\n\n");
+
+            // Shared helpers and other split symbols this file refers to
+            let mut imports: Vec<(String, Vec<&str>)> = Vec::new();
+            let mut used_common = Vec::new();
+            for helper in &common {
+                if mentions(&helper.name, &body)? {
+                    used_common.push(helper.name.as_str());
+                }
+            }
+            if !used_common.is_empty() {
+                imports.push((COMMON_FILE_STEM.to_string(), used_common));
+            }
+            for other in targets.iter().filter(|other| other.name != target.name) {
+                if mentions(&other.name, &body)? {
+                    imports.push((Self::file_stem(&other.name, language), vec![other.name.as_str()]));
+                }
+            }
+
+            let mut content = Self::file_header(&preamble, language, &output_dir, ext, &imports);
+            content.push_str(&body);
+            content.push('\n');
+
+            stubs.extend(Self::import_stub(lines, target, language, &source_dir, &path));
+            outputs.push(SplitOutput {
+                path,
+                symbols: std::iter::once(target).chain(&attached[index]).map(|span| span.name.clone()).collect(),
+                content,
+            });
+        }
+
+        if !common.is_empty() {
+            let path = output_dir.join(format!("{}.{}", COMMON_FILE_STEM, ext));
+            let mut content = Self::file_header(&preamble, language, &output_dir, ext, &[]);
+            let bodies: Vec<String> = common.iter()
+                .map(|helper| Self::exported_body(lines, helper, language))
+                .collect();
+            content.push_str(&bodies.join("\n\n"));
+            content.push('\n');
+
+            let names: Vec<&str> = common.iter().map(|c| c.name.as_str()).collect();
+            stubs.extend(Self::common_stub(&names, language, &source_dir, &path));
+            outputs.push(SplitOutput {
+                path,
+                symbols: common.iter().map(|c| c.name.clone()).collect(),
+                content,
+            });
+        }
+
+        // Rewrite the original: drop moved spans and put the stubs where the first one was
+        let moved: Vec<&SymbolSpan> = targets.iter().chain(attached.iter().flatten()).chain(common.iter()).collect();
+        let first_moved = moved.iter().map(|s| s.start).min().unwrap_or(1);
+        let mut remaining: Vec<String> = Vec::new();
+        for (i, line) in lines.iter().enumerate() {
+            let line_no = i + 1;
+            if line_no == first_moved {
+                remaining.extend(stubs.iter().cloned());
+            }
+            if !moved.iter().any(|s| s.start <= line_no && line_no <= s.end) {
+                remaining.push(line.to_string());
+            }
+        }
+
+        Ok(PreviewOperation::SplitFile {
+            file: file.to_path_buf(),
+            by: self.options.by,
+            outputs,
+            remaining_content: Self::remove_extra_blank_lines(&remaining) + "\n",
+        })
+    }
+
+    /// Walk upwards from a declaration to include doc comments, attributes and decorators
+    fn leading_comment_start(lines: &[&str], decl_line: usize, language: Language) -> usize {
+        let mut start = decl_line;
+        while start > 1 {
+            let line = lines[start - 2].trim();
+            let is_leading = line.starts_with("//")
+                || line.starts_with("/*")
+                || line.starts_with('*')
+                || (language == Language::Rust && line.starts_with("#["))
+                || (language == Language::Python && (line.starts_with('#') || line.starts_with('@')))
+                || (language == Language::CSharp && line.starts_with('['));
+            if !is_leading {
+                break;
+            }
+            start -= 1;
+        }
+        start
+    }
+
+    /// Import-like lines of the original file that every new file needs as well
+    fn collect_preamble(lines: &[&str], language: Language) -> Vec<String> {
+        let mut preamble = Vec::new();
+        let mut in_go_import_block = false;
+
+        for line in lines {
+            let trimmed = line.trim();
+            let keep = match language {
+                Language::Python => trimmed.starts_with("import ") || trimmed.starts_with("from "),
+                Language::JavaScript | Language::TypeScript => {
+                    trimmed.starts_with("import ")
+                        || (trimmed.contains("require(") && !line.starts_with(char::is_whitespace))
+                }
+                Language::Go => {
+                    if in_go_import_block {
+                        in_go_import_block = trimmed != ")";
+                        true
+                    } else if trimmed.starts_with("import (") {
+                        in_go_import_block = true;
+                        true
+                    } else {
+                        trimmed.starts_with("package ") || trimmed.starts_with("import ")
+                    }
+                }
+                Language::Cpp | Language::C => trimmed.starts_with("#include"),
+                Language::CSharp => trimmed.starts_with("using ") && trimmed.ends_with(';'),
+                Language::Java => trimmed.starts_with("package ") || trimmed.starts_with("import "),
+                // Rust split files are child modules and see the parent through `use super::*`
+                Language::Rust | Language::Unknown => false,
+            };
+            if keep {
+                preamble.push(line.to_string());
+            }
+        }
+
+        preamble
+    }
+
+    /// Header of a new file: the original imports plus imports of the other
+    /// new files it uses, given as (file stem, names)
+    fn file_header(
+        preamble: &[String],
+        language: Language,
+        output_dir: &Path,
+        ext: &str,
+        imports: &[(String, Vec<&str>)],
+    ) -> String {
+        let mut header: Vec<String> = if language == Language::Rust {
+            vec!["use super::*;".to_string()]
+        } else {
+            preamble.to_vec()
+        };
+
+        // Rust sees its siblings through the parent; Go, Java and C# share a package
+        for (stem, names) in imports {
+            let path = output_dir.join(format!("{}.{}", stem, ext));
+            match language {
+                Language::Python => {
+                    header.push(format!("from {} import {}", stem, names.join(", ")));
+                }
+                Language::JavaScript | Language::TypeScript => {
+                    header.push(format!("import {{ {} }} from './{}';", names.join(", "), stem));
+                }
+                Language::Cpp | Language::C => {
+                    header.push(format!("#include \"{}\"", Self::relative_path(output_dir, &path)));
+                }
+                _ => {}
+            }
+        }
+
+        if header.is_empty() {
+            String::new()
+        } else {
+            format!("{}\n\n", header.join("\n"))
+        }
+    }
+
+    /// Symbol source for the new file. JS/TS declarations get an `export` so the stub can
+    /// import them, and private Rust items become `pub(crate)` so the parent module still sees them
+    fn exported_body(lines: &[&str], span: &SymbolSpan, language: Language) -> String {
+        let mut body: Vec<String> = lines[span.start - 1..span.end].iter().map(|l| l.to_string()).collect();
+        let decl_idx = span.decl_start - span.start;
+        let decl = body[decl_idx].trim_start();
+
+        match language {
+            Language::JavaScript | Language::TypeScript if !decl.starts_with("export") => {
+                body[decl_idx] = format!("export {}", body[decl_idx]);
+            }
+            Language::Rust if !decl.starts_with("pub") => {
+                body[decl_idx] = format!("pub(crate) {}", body[decl_idx]);
+            }
+            _ => {}
+        }
+
+        body.join("\n")
+    }
+
+    /// Line(s) left in the original file so existing imports of the symbol keep working
+    fn import_stub(
+        lines: &[&str],
+        span: &SymbolSpan,
+        language: Language,
+        source_dir: &Path,
+        new_path: &Path,
+    ) -> Vec<String> {
+        let name = &span.name;
+        let module = Self::module_path(source_dir, new_path, language);
+        let decl = lines[span.decl_start - 1].trim_start();
+
+        match language {
+            Language::Rust => {
+                let stem = Self::file_stem(name, language);
+                let mut stub = Vec::new();
+                if new_path.parent() != Some(source_dir) {
+                    stub.push(format!("#[path = \"{}\"]", Self::relative_path(source_dir, new_path)));
+                }
+                stub.push(format!("mod {};", stem));
+                stub.push(format!("pub use {}::*;", stem));
+                stub
+            }
+            Language::Python => vec![format!("from {} import {}", module, name)],
+            Language::JavaScript | Language::TypeScript => {
+                if decl.starts_with("export default") {
+                    vec![
+                        format!("import {} from '{}';", name, module),
+                        format!("export default {};", name),
+                    ]
+                } else if decl.starts_with("export") {
+                    vec![
+                        format!("import {{ {} }} from '{}';", name, module),
+                        format!("export {{ {} }};", name),
+                    ]
+                } else {
+                    vec![format!("import {{ {} }} from '{}';", name, module)]
+                }
+            }
+            Language::Cpp | Language::C => vec![format!("#include \"{}\"", module)],
+            // Same package / namespace: nothing to import, just leave a pointer
+            Language::Go | Language::CSharp | Language::Java | Language::Unknown => {
+                vec![format!("// {} moved to {}", name, Self::relative_path(source_dir, new_path))]
+            }
+        }
+    }
+
+    /// Stub importing the shared helpers into the original file
+    fn common_stub(names: &[&str], language: Language, source_dir: &Path, common_path: &Path) -> Vec<String> {
+        let module = Self::module_path(source_dir, common_path, language);
+
+        match language {
+            Language::Rust => {
+                let mut stub = Vec::new();
+                if common_path.parent() != Some(source_dir) {
+                    stub.push(format!("#[path = \"{}\"]", Self::relative_path(source_dir, common_path)));
+                }
+                stub.push(format!("mod {};", COMMON_FILE_STEM));
+                stub.push(format!("pub use {}::*;", COMMON_FILE_STEM));
+                stub
+            }
+            Language::Python => vec![format!("from {} import {}", module, names.join(", "))],
+            Language::JavaScript | Language::TypeScript => {
+                vec![format!("import {{ {} }} from '{}';", names.join(", "), module)]
+            }
+            Language::Cpp | Language::C => vec![format!("#include \"{}\"", module)],
+            Language::Go | Language::CSharp | Language::Java | Language::Unknown => {
+                vec![format!("// {} moved to {}", names.join(", "), Self::relative_path(source_dir, common_path))]
+            }
+        }
+    }
+
+    /// How the original file refers to a new file in this language's import syntax
+    fn module_path(source_dir: &Path, target: &Path, language: Language) -> String {
+        let relative = Self::relative_path(source_dir, target);
+        match language {
+            Language::Python => relative.trim_end_matches(".py").replace('/', "."),
+            Language::JavaScript | Language::TypeScript => {
+                let without_ext = Path::new(&relative).with_extension("");
+                format!("./{}", without_ext.display())
+            }
+            _ => relative,
+        }
+    }
+
+    /// Path of `target` relative to `base` when it lives below it, otherwise the full path
+    fn relative_path(base: &Path, target: &Path) -> String {
+        target.strip_prefix(base)
+            .unwrap_or(target)
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+
+    /// File name for a symbol; Rust modules must be snake_case
+    fn file_stem(name: &str, language: Language) -> String {
+        if language != Language::Rust {
+            return name.to_string();
+        }
+
+        let mut stem = String::new();
+        for (i, ch) in name.chars().enumerate() {
+            if ch.is_uppercase() {
+                if i > 0 {
+                    stem.push('_');
+                }
+                stem.extend(ch.to_lowercase());
+            } else {
+                stem.push(ch);
+            }
+        }
+        stem
+    }
+
+    /// Collapse runs of blank lines left behind by removed symbols
+    fn remove_extra_blank_lines(lines: &[String]) -> String {
+        let mut result: Vec<&str> = Vec::new();
+        let mut prev_blank = false;
+
+        for line in lines {
+            let is_blank = line.trim().is_empty();
+            if is_blank && prev_blank {
+                continue;
+            }
+            result.push(line);
+            prev_blank = is_blank;
+        }
+
+        result.join("\n").trim_end().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_split_python_classes_with_shared_helper() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        let file = dir.join("animals.py");
+        fs::write(&file, r#"import os
+
+def normalize(name):
+    return name.strip()
+
+def unused():
+    pass
+
+class Dog:
+    def speak(self):
+        return normalize("woof")
+
+class Cat:
+    def speak(self):
+        return normalize("meow")
+"#).unwrap();
+
+        let engine = SplitEngine::new(SplitOptions { by: SplitBy::Class, output_dir: None });
+        let operation = engine.create_preview(&file).await.unwrap();
+
+        let PreviewOperation::SplitFile { outputs, remaining_content, .. } = operation else {
+            panic!("expected a SplitFile operation");
+        };
+
+        let names: Vec<String> = outputs.iter()
+            .map(|o| o.path.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, vec!["Dog.py", "Cat.py", "common.py"]);
+
+        assert!(outputs[0].content.starts_with("import os\nfrom common import normalize\n"));
+        assert!(outputs[0].content.contains("class Dog:"));
+        assert!(outputs[2].content.contains("def normalize(name):"));
+
+        assert!(remaining_content.contains("from Dog import Dog"));
+        assert!(remaining_content.contains("from Cat import Cat"));
+        assert!(remaining_content.contains("from common import normalize"));
+        assert!(remaining_content.contains("def unused():"));
+        assert!(!remaining_content.contains("class Dog"));
+    }
+
+    #[tokio::test]
+    async fn test_split_moves_helpers_with_their_users() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        let file = dir.join("shapes.py");
+        fs::write(&file, r#"def scale(x):
+    return x * 2
+
+def describe(x):
+    return str(x)
+
+class Square:
+    def area(self):
+        return scale(4)
+
+class Circle:
+    def label(self):
+        return describe(1)
+
+print(describe(2))
+"#).unwrap();
+
+        let engine = SplitEngine::new(SplitOptions { by: SplitBy::Class, output_dir: None });
+        let PreviewOperation::SplitFile { outputs, remaining_content, .. } = engine.create_preview(&file).await.unwrap() else {
+            panic!("expected a SplitFile operation");
+        };
+
+        // `scale` is only used by Square and moves with it; `describe` is
+        // also used by the code left behind, so it is shared
+        assert_eq!(outputs[0].symbols, vec!["Square", "scale"]);
+        assert!(outputs[0].content.contains("def scale(x):"));
+        assert_eq!(outputs[1].symbols, vec!["Circle"]);
+        assert!(outputs[1].content.starts_with("from common import describe\n"));
+        assert_eq!(outputs[2].symbols, vec!["describe"]);
+        assert!(!remaining_content.contains("def scale"));
+        assert!(remaining_content.contains("from common import describe"));
+
+        // Split symbols that call each other import the other's file
+        let file = dir.join("steps.py");
+        fs::write(&file, "def load():\n    return 1\n\ndef run():\n    return load()\n").unwrap();
+        let engine = SplitEngine::new(SplitOptions { by: SplitBy::Function, output_dir: None });
+        let PreviewOperation::SplitFile { outputs, .. } = engine.create_preview(&file).await.unwrap() else {
+            panic!("expected a SplitFile operation");
+        };
+        assert_eq!(outputs.len(), 2);
+        assert!(outputs[1].content.starts_with("from load import load\n"));
+    }
+
+    #[test]
+    fn test_rust_file_stem() {
+        assert_eq!(SplitEngine::file_stem("HttpServer", Language::Rust), "http_server");
+        assert_eq!(SplitEngine::file_stem("HttpServer", Language::Python), "HttpServer");
+    }
+}
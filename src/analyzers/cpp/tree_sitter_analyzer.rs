@@ -2,7 +2,8 @@
 //! 100x faster than PEST implementation!
 
 use anyhow::Result;
-use tree_sitter::{Parser, Query, QueryCursor, Node};
+use std::collections::HashSet;
+use tree_sitter::{Parser, QueryCursor, Node};
 use async_trait::async_trait;
 
 use crate::core::types::{
@@ -10,6 +11,7 @@ use crate::core::types::{
     Language, ComplexityInfo, ImportType
 };
 use crate::core::ast::{ASTNode, ASTNodeType, ASTStatistics};
+use crate::analyzers::pool::cached_query;
 use crate::analyzers::traits::LanguageAnalyzer;
 
 pub struct TreeSitterCppAnalyzer {
@@ -25,54 +27,56 @@ impl TreeSitterCppAnalyzer {
         Ok(Self { parser })
     }
     
-    /// Extract functions using tree-sitter query
+    /// Extract function definitions with their scope path (`ns::Class::method`),
+    /// typed parameters and return type
     fn extract_functions(&self, tree: &tree_sitter::Tree, source: &str) -> Result<Vec<FunctionInfo>> {
         let mut functions = Vec::new();
+        let namespaces = Self::namespace_names(tree.root_node(), source);
         
-        // Query for function definitions
-        let query_str = r#"
-            [
-              (function_definition
-                declarator: (function_declarator 
-                  declarator: (identifier) @name)) @function
-              (function_definition
-                declarator: (function_declarator 
-                  declarator: (qualified_identifier 
-                    name: (identifier) @name))) @function
-            ]
-        "#;
-        
-        let query = Query::new(&tree_sitter_cpp::LANGUAGE.into(), query_str)?;
+        let query = cached_query(&tree_sitter_cpp::LANGUAGE.into(), "(function_definition) @function")?;
         let mut cursor = QueryCursor::new();
         let matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
         
         for mat in matches {
-            let mut func_info = FunctionInfo::new(String::new());
-            let mut func_node = None;
+            let node = mat.captures[0].node;
+            let Some(declarator) = Self::function_declarator(node) else {
+                continue;
+            };
+            let Some(name_node) = declarator.child_by_field_name("declarator") else {
+                continue;
+            };
             
-            for capture in mat.captures {
-                match query.capture_names()[capture.index as usize].as_ref() {
-                    "name" => {
-                        func_info.name = capture.node.utf8_text(source.as_bytes())?.to_string();
-                    }
-                    "function" => {
-                        func_node = Some(capture.node);
-                        func_info.start_line = capture.node.start_position().row as u32 + 1;
-                        func_info.end_line = capture.node.end_position().row as u32 + 1;
-                    }
-                    _ => {}
-                }
-            }
+            // `void Foo::bar()` is attributed to `Foo` unless `Foo` is a namespace
+            let mut qualifiers = split_qualified_name(name_node.utf8_text(source.as_bytes())?);
+            let Some(name) = qualifiers.pop() else {
+                continue;
+            };
+            let enclosing = Self::enclosing_scopes(node, source);
+            let class = qualifiers.last()
+                .filter(|qualifier| !namespaces.contains(*qualifier))
+                .cloned()
+                .or_else(|| enclosing.iter().rev().find(|(_, is_class)| *is_class).map(|(name, _)| name.clone()));
             
-            // Extract parameters if we have a function node
-            if let Some(node) = func_node {
-                func_info.parameters = self.extract_parameters(node, source)?;
-                func_info.is_async = false; // C++ doesn't have async/await like JS/Python
-                
-                // Check for inline, virtual, static keywords
-                func_info.metadata.extend(self.extract_function_modifiers(node, source)?);
+            let mut scope_path: Vec<String> = enclosing.into_iter().map(|(name, _)| name).collect();
+            scope_path.extend(qualifiers);
+            scope_path.push(name.clone());
+            
+            let mut func_info = FunctionInfo::new(name);
+            func_info.start_line = node.start_position().row as u32 + 1;
+            func_info.end_line = node.end_position().row as u32 + 1;
+            func_info.parameters = self.extract_parameters(declarator, source)?;
+            func_info.is_async = false; // C++ doesn't have async/await like JS/Python
+            func_info.metadata.insert("scope_path".to_string(), scope_path.join("::"));
+            if let Some(class) = class {
+                func_info.metadata.insert("class".to_string(), class);
+            }
+            if let Some(return_type) = node.child_by_field_name("type") {
+                func_info.metadata.insert("return_type".to_string(), return_type.utf8_text(source.as_bytes())?.to_string());
             }
             
+            // Check for inline, virtual, static keywords
+            func_info.metadata.extend(self.extract_function_modifiers(node, source)?);
+            
             // Set default complexity (will be calculated separately)
             func_info.complexity = ComplexityInfo::default();
             
@@ -82,6 +86,56 @@ impl TreeSitterCppAnalyzer {
         Ok(functions)
     }
     
+    /// The `function_declarator` of a definition, below any pointer or
+    /// reference declarators of the return type
+    fn function_declarator(node: Node) -> Option<Node> {
+        let mut declarator = node.child_by_field_name("declarator")?;
+        while declarator.kind() != "function_declarator" {
+            declarator = declarator.child_by_field_name("declarator")?;
+        }
+        Some(declarator)
+    }
+    
+    /// Namespaces and classes enclosing `node`, outermost first; the flag marks classes
+    fn enclosing_scopes(node: Node, source: &str) -> Vec<(String, bool)> {
+        let mut scopes = Vec::new();
+        let mut current = node.parent();
+        
+        while let Some(parent) = current {
+            let is_class = matches!(parent.kind(), "class_specifier" | "struct_specifier");
+            if is_class || parent.kind() == "namespace_definition" {
+                if let Some(name) = parent.child_by_field_name("name").and_then(|n| n.utf8_text(source.as_bytes()).ok()) {
+                    // `namespace a::b {}` contributes two segments
+                    for segment in split_qualified_name(name).into_iter().rev() {
+                        scopes.push((segment, is_class));
+                    }
+                }
+            }
+            current = parent.parent();
+        }
+        
+        scopes.reverse();
+        scopes
+    }
+    
+    /// Names of every namespace declared in the file
+    fn namespace_names(root: Node, source: &str) -> HashSet<String> {
+        let mut names = HashSet::new();
+        let mut stack = vec![root];
+        
+        while let Some(node) = stack.pop() {
+            if node.kind() == "namespace_definition" {
+                if let Some(name) = node.child_by_field_name("name").and_then(|n| n.utf8_text(source.as_bytes()).ok()) {
+                    names.extend(split_qualified_name(name));
+                }
+            }
+            let mut cursor = node.walk();
+            stack.extend(node.children(&mut cursor));
+        }
+        
+        names
+    }
+    
     /// Extract classes using tree-sitter query
     fn extract_classes(&self, tree: &tree_sitter::Tree, source: &str) -> Result<Vec<ClassInfo>> {
         let mut classes = Vec::new();
@@ -97,7 +151,7 @@ impl TreeSitterCppAnalyzer {
             ]
         "#;
         
-        let query = Query::new(&tree_sitter_cpp::LANGUAGE.into(), query_str)?;
+        let query = cached_query(&tree_sitter_cpp::LANGUAGE.into(), query_str)?;
         let mut cursor = QueryCursor::new();
         let matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
         
@@ -130,14 +184,13 @@ impl TreeSitterCppAnalyzer {
             if let Some(node) = class_node {
                 class_info.methods = self.extract_class_methods(node, source)?;
                 
-                // Extract base classes (inheritance)
-                if let Some(base_clause) = node.child_by_field_name("base_class_clause") {
-                    let base_classes = self.extract_base_classes(base_clause, source)?;
-                    if !base_classes.is_empty() {
-                        class_info.parent_class = Some(base_classes[0].clone());
-                        if base_classes.len() > 1 {
-                            class_info.metadata.insert("multiple_inheritance".to_string(), base_classes.join(", "));
-                        }
+                // Extract base classes (inheritance); bases after the first act as interfaces
+                let base_classes = self.extract_base_classes(node, source)?;
+                if !base_classes.is_empty() {
+                    class_info.parent_class = Some(base_classes[0].clone());
+                    if base_classes.len() > 1 {
+                        class_info.metadata.insert("multiple_inheritance".to_string(), base_classes.join(", "));
+                        class_info.metadata.insert("interfaces".to_string(), base_classes[1..].join(", "));
                     }
                 }
             }
@@ -157,7 +210,7 @@ impl TreeSitterCppAnalyzer {
               path: (string_literal) @path) @include
         "#;
         
-        let query = Query::new(&tree_sitter_cpp::LANGUAGE.into(), query_str)?;
+        let query = cached_query(&tree_sitter_cpp::LANGUAGE.into(), query_str)?;
         let mut cursor = QueryCursor::new();
         let matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
         
@@ -184,27 +237,25 @@ impl TreeSitterCppAnalyzer {
         Ok(imports)
     }
     
-    /// Helper: Extract parameters from a function node
-    fn extract_parameters(&self, node: Node, source: &str) -> Result<Vec<String>> {
+    /// Parameters of a `function_declarator` as declared, e.g. `const Point& other`
+    /// or `int scale = 1`; a lone `void` means no parameters
+    fn extract_parameters(&self, declarator: Node, source: &str) -> Result<Vec<String>> {
         let mut params = Vec::new();
         
-        // Look for parameter_list in the function_declarator
-        if let Some(declarator) = node.child_by_field_name("declarator") {
-            if let Some(param_list) = declarator.child_by_field_name("parameters") {
-                let mut cursor = param_list.walk();
-                for child in param_list.children(&mut cursor) {
-                    if child.kind() == "parameter_declaration" {
-                        if let Some(declarator) = child.child_by_field_name("declarator") {
-                            if let Ok(param_text) = declarator.utf8_text(source.as_bytes()) {
-                                params.push(param_text.to_string());
-                            }
-                        } else if let Some(type_node) = child.child_by_field_name("type") {
-                            // For unnamed parameters, use the type
-                            if let Ok(type_text) = type_node.utf8_text(source.as_bytes()) {
-                                params.push(format!("unnamed_{}", type_text));
-                            }
+        if let Some(param_list) = declarator.child_by_field_name("parameters") {
+            let mut cursor = param_list.walk();
+            for child in param_list.children(&mut cursor) {
+                match child.kind() {
+                    "parameter_declaration" | "optional_parameter_declaration" | "variadic_parameter_declaration" => {
+                        let text = child.utf8_text(source.as_bytes())?;
+                        if text.trim() == "void" {
+                            continue;
                         }
+                        params.push(text.split_whitespace().collect::<Vec<_>>().join(" "));
                     }
+                    // A C-style `...` is a token rather than a declaration
+                    "..." => params.push("...".to_string()),
+                    _ => {}
                 }
             }
         }
@@ -248,7 +299,7 @@ impl TreeSitterCppAnalyzer {
             if child.kind() == "base_class_clause" {
                 let mut subchild_cursor = child.walk();
                 for subchild in child.children(&mut subchild_cursor) {
-                    if subchild.kind() == "type_identifier" {
+                    if matches!(subchild.kind(), "type_identifier" | "qualified_identifier" | "template_type") {
                         if let Ok(class_name) = subchild.utf8_text(source.as_bytes()) {
                             base_classes.push(class_name.to_string());
                         }
@@ -269,18 +320,19 @@ impl TreeSitterCppAnalyzer {
             for child in body.children(&mut cursor) {
                 if child.kind() == "function_definition" {
                     let mut method = FunctionInfo::new(String::new());
+                    let declarator = Self::function_declarator(child);
                     
-                    if let Some(declarator) = child.child_by_field_name("declarator") {
-                        if let Some(func_declarator) = declarator.child_by_field_name("declarator") {
-                            if let Ok(name) = func_declarator.utf8_text(source.as_bytes()) {
-                                method.name = name.to_string();
-                            }
+                    if let Some(name_node) = declarator.and_then(|d| d.child_by_field_name("declarator")) {
+                        if let Ok(name) = name_node.utf8_text(source.as_bytes()) {
+                            method.name = name.to_string();
                         }
                     }
                     
                     method.start_line = child.start_position().row as u32 + 1;
                     method.end_line = child.end_position().row as u32 + 1;
-                    method.parameters = self.extract_parameters(child, source)?;
+                    if let Some(declarator) = declarator {
+                        method.parameters = self.extract_parameters(declarator, source)?;
+                    }
                     method.metadata.insert("is_class_method".to_string(), "true".to_string());
                     
                     // Check for special methods (constructor, destructor)
@@ -314,6 +366,7 @@ impl TreeSitterCppAnalyzer {
     fn build_ast_recursive(&self, node: Node, source: &str, parent: &mut ASTNode, depth: usize) {
         // Map tree-sitter node types to our AST types
         let ast_type = match node.kind() {
+            "namespace_definition" => ASTNodeType::Namespace,
             "function_definition" => ASTNodeType::Function,
             "class_specifier" | "struct_specifier" => ASTNodeType::Class,
             "if_statement" => ASTNodeType::IfStatement,
@@ -330,10 +383,21 @@ impl TreeSitterCppAnalyzer {
             ast_node.depth = depth as u32;
             
             // Try to get node name
+            let mut qualified_name = None;
             if let Some(name_field) = node.child_by_field_name("name") {
                 if let Ok(name) = name_field.utf8_text(source.as_bytes()) {
                     ast_node.name = name.to_string();
                 }
+            } else if node.kind() == "function_definition" {
+                let name_field = Self::function_declarator(node).and_then(|d| d.child_by_field_name("declarator"));
+                if let Some(Ok(name)) = name_field.map(|n| n.utf8_text(source.as_bytes())) {
+                    // `void Foo::bar()` is named `bar` and scoped under `Foo`
+                    let segments = split_qualified_name(name);
+                    ast_node.name = segments.last().cloned().unwrap_or_default();
+                    if segments.len() > 1 {
+                        qualified_name = Some(segments.join("::"));
+                    }
+                }
             } else if let Some(declarator) = node.child_by_field_name("declarator") {
                 if let Some(name_field) = declarator.child_by_field_name("declarator") {
                     if let Ok(name) = name_field.utf8_text(source.as_bytes()) {
@@ -342,11 +406,19 @@ impl TreeSitterCppAnalyzer {
                 }
             }
             
+            let parent_scope = parent.scope_path.clone();
             parent.add_child(ast_node);
             
             // Use the newly created node as parent for its children
             let parent_index = parent.children.len() - 1;
             let new_parent = &mut parent.children[parent_index];
+            if let Some(qualified_name) = qualified_name {
+                new_parent.scope_path = if parent_scope.is_empty() {
+                    qualified_name
+                } else {
+                    format!("{}::{}", parent_scope, qualified_name)
+                };
+            }
             
             // Recurse through children with the new node as parent
             let mut cursor = node.walk();
@@ -363,6 +435,31 @@ impl TreeSitterCppAnalyzer {
     }
 }
 
+/// Split `ns::Foo<T>::bar` into `["ns", "Foo<T>", "bar"]`, ignoring `::` inside template arguments
+fn split_qualified_name(name: &str) -> Vec<String> {
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0;
+    let mut chars = name.chars().peekable();
+    
+    while let Some(c) = chars.next() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth -= 1,
+            ':' if depth == 0 && chars.peek() == Some(&':') => {
+                chars.next();
+                segments.push(std::mem::take(&mut current).trim().to_string());
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    segments.push(current.trim().to_string());
+    segments.retain(|s| !s.is_empty());
+    segments
+}
+
 #[async_trait]
 impl LanguageAnalyzer for TreeSitterCppAnalyzer {
     fn get_language(&self) -> Language {
@@ -377,6 +474,10 @@ impl LanguageAnalyzer for TreeSitterCppAnalyzer {
         vec![".cpp", ".cxx", ".cc", ".c++", ".hpp", ".hxx", ".hh", ".h++", ".h"]
     }
     
+    fn set_parse_timeout(&mut self, timeout: Option<std::time::Duration>) {
+        self.parser.set_timeout_micros(timeout.map_or(0, |timeout| timeout.as_micros() as u64));
+    }
+    
     async fn analyze(&mut self, content: &str, filename: &str) -> Result<AnalysisResult> {
         // Create file info
         let file_path = std::path::PathBuf::from(filename);
@@ -406,8 +507,14 @@ impl LanguageAnalyzer for TreeSitterCppAnalyzer {
         
         // 🚀 Parse with tree-sitter (ULTRA FAST!)
         let parse_start = std::time::Instant::now();
-        let tree = self.parser.parse(content, None)
-            .ok_or_else(|| anyhow::anyhow!("Failed to parse C++ file"))?;
+        let tree = match self.parser.parse(content, None) {
+            Some(tree) => tree,
+            None => {
+                // A timed-out parse is kept and resumed by the next `parse`
+                self.parser.reset();
+                anyhow::bail!("Failed to parse C++ file: parsing timed out");
+            }
+        };
         let parse_duration = parse_start.elapsed();
         
         if std::env::var("NEKOCODE_DEBUG").is_ok() {
@@ -443,4 +550,56 @@ impl LanguageAnalyzer for TreeSitterCppAnalyzer {
         
         Ok(result)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[tokio::test]
+    async fn test_scope_paths_and_parameters() {
+        let source = r#"
+namespace geo {
+class Point {
+public:
+    double dist(const Point& other, int scale = 1) const {
+        return 0.0;
+    }
+};
+
+void Point::reset(int* values, ...) {}
+
+static int helper(void) { return 0; }
+}
+"#;
+        let mut analyzer = TreeSitterCppAnalyzer::new().unwrap();
+        let result = analyzer.analyze(source, "geo.cpp").await.unwrap();
+        
+        let scope_paths: Vec<_> = result.functions.iter()
+            .map(|f| f.metadata.get("scope_path").cloned().unwrap_or_default())
+            .collect();
+        assert_eq!(scope_paths, vec!["geo::Point::dist", "geo::Point::reset", "geo::helper"]);
+        
+        let dist = &result.functions[0];
+        assert_eq!(dist.name, "dist");
+        assert_eq!(dist.metadata.get("class").map(String::as_str), Some("Point"));
+        assert_eq!(dist.metadata.get("return_type").map(String::as_str), Some("double"));
+        assert_eq!(dist.parameters, vec!["const Point& other", "int scale = 1"]);
+        
+        let reset = &result.functions[1];
+        assert_eq!(reset.name, "reset");
+        assert_eq!(reset.metadata.get("class").map(String::as_str), Some("Point"));
+        assert_eq!(reset.parameters, vec!["int* values", "..."]);
+        
+        let helper = &result.functions[2];
+        assert!(!helper.metadata.contains_key("class"));
+        assert!(helper.parameters.is_empty());
+        
+        let ast = result.ast_root.unwrap();
+        for path in ["geo::Point::dist", "geo::Point::reset"] {
+            let nodes = ast.query_by_path(path);
+            assert_eq!(nodes.len(), 1, "{}", path);
+            assert_eq!(nodes[0].scope_path, path);
+        }
+    }
+}
@@ -217,7 +217,7 @@ impl CSharpAnalyzer {
                         if !parent_list.is_empty() {
                             class.parent_class = Some(parent_list[0].to_string());
                             if parent_list.len() > 1 {
-                                class.metadata.insert("implements_interfaces".to_string(), parent_list[1..].join(", "));
+                                class.metadata.insert("interfaces".to_string(), parent_list[1..].join(", "));
                             }
                         }
                     }
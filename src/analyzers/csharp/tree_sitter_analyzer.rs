@@ -2,7 +2,7 @@
 //! 100x faster than PEST implementation!
 
 use anyhow::Result;
-use tree_sitter::{Parser, Query, QueryCursor, Node};
+use tree_sitter::{Parser, QueryCursor, Node};
 use async_trait::async_trait;
 
 use crate::core::types::{
@@ -10,6 +10,7 @@ use crate::core::types::{
     Language, ComplexityInfo, ImportType
 };
 use crate::core::ast::{ASTNode, ASTNodeType, ASTStatistics};
+use crate::analyzers::pool::cached_query;
 use crate::analyzers::traits::LanguageAnalyzer;
 
 pub struct TreeSitterCSharpAnalyzer {
@@ -41,7 +42,7 @@ impl TreeSitterCSharpAnalyzer {
             ]
         "#;
         
-        let query = Query::new(&tree_sitter_c_sharp::LANGUAGE.into(), query_str)?;
+        let query = cached_query(&tree_sitter_c_sharp::LANGUAGE.into(), query_str)?;
         let mut cursor = QueryCursor::new();
         let matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
         
@@ -83,6 +84,10 @@ impl TreeSitterCSharpAnalyzer {
                 
                 // Extract modifiers
                 func_info.metadata.extend(self.extract_method_modifiers(node, source)?);
+                
+                if let Some(namespace) = self.enclosing_namespace(node, source) {
+                    func_info.metadata.insert("namespace".to_string(), namespace);
+                }
             }
             
             // Set default complexity (will be calculated separately)
@@ -111,7 +116,7 @@ impl TreeSitterCSharpAnalyzer {
             ]
         "#;
         
-        let query = Query::new(&tree_sitter_c_sharp::LANGUAGE.into(), query_str)?;
+        let query = cached_query(&tree_sitter_c_sharp::LANGUAGE.into(), query_str)?;
         let mut cursor = QueryCursor::new();
         let matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
         
@@ -156,19 +161,26 @@ impl TreeSitterCSharpAnalyzer {
             if let Some(node) = class_node {
                 class_info.methods = self.extract_class_methods(node, source)?;
                 
-                // Extract base classes (inheritance)
-                if let Some(base_list) = node.child_by_field_name("base_list") {
-                    let base_classes = self.extract_base_classes(base_list, source)?;
+                // Extract base classes (inheritance). Only the first base can be a
+                // class, and not when it follows the `IName` interface convention.
+                let mut cursor = node.walk();
+                let base_list = node.children(&mut cursor).find(|child| child.kind() == "base_list");
+                if let Some(base_list) = base_list {
+                    let mut base_classes = self.extract_base_classes(base_list, source)?;
+                    if base_classes.first().is_some_and(|base| !Self::is_interface_name(base)) {
+                        class_info.parent_class = Some(base_classes.remove(0));
+                    }
                     if !base_classes.is_empty() {
-                        class_info.parent_class = Some(base_classes[0].clone());
-                        if base_classes.len() > 1 {
-                            class_info.metadata.insert("interfaces".to_string(), base_classes[1..].join(", "));
-                        }
+                        class_info.metadata.insert("interfaces".to_string(), base_classes.join(", "));
                     }
                 }
                 
                 // Extract modifiers
                 class_info.metadata.extend(self.extract_type_modifiers(node, source)?);
+                
+                if let Some(namespace) = self.enclosing_namespace(node, source) {
+                    class_info.metadata.insert("namespace".to_string(), namespace);
+                }
             }
             
             classes.push(class_info);
@@ -177,11 +189,86 @@ impl TreeSitterCSharpAnalyzer {
         Ok(classes)
     }
     
-    /// Extract using statements using tree-sitter query
-    fn extract_imports(&self, _tree: &tree_sitter::Tree, _source: &str) -> Result<Vec<ImportInfo>> {
-        // Temporarily disable C# import extraction to avoid query syntax issues
-        // TODO: Fix Tree-sitter query for C# using directives
-        Ok(Vec::new())
+    /// Extract `using` directives, including those nested in namespace bodies
+    fn extract_imports(&self, tree: &tree_sitter::Tree, source: &str) -> Result<Vec<ImportInfo>> {
+        let mut imports = Vec::new();
+        let mut stack = vec![tree.root_node()];
+        
+        while let Some(node) = stack.pop() {
+            match node.kind() {
+                "using_directive" => {
+                    // `using Alias = Some.Namespace;` keeps the alias in the `name` field
+                    let alias = node.child_by_field_name("name");
+                    let mut cursor = node.walk();
+                    let target = node.named_children(&mut cursor)
+                        .find(|child| Some(child.id()) != alias.map(|a| a.id()));
+                    
+                    let Some(target) = target else { continue };
+                    let mut import = ImportInfo::new(
+                        ImportType::CSharpUsing,
+                        target.utf8_text(source.as_bytes())?.to_string(),
+                    );
+                    import.line_number = node.start_position().row as u32 + 1;
+                    if let Some(alias) = alias {
+                        import.alias = Some(alias.utf8_text(source.as_bytes())?.to_string());
+                    }
+                    
+                    let mut cursor = node.walk();
+                    for keyword in node.children(&mut cursor) {
+                        match keyword.kind() {
+                            "static" => { import.metadata.insert("is_static".to_string(), "true".to_string()); }
+                            "global" => { import.metadata.insert("is_global".to_string(), "true".to_string()); }
+                            _ => {}
+                        }
+                    }
+                    
+                    imports.push(import);
+                }
+                // Only compilation units and namespaces can contain using directives
+                "compilation_unit" | "namespace_declaration" | "declaration_list" => {
+                    let mut cursor = node.walk();
+                    let children: Vec<_> = node.named_children(&mut cursor).collect();
+                    stack.extend(children.into_iter().rev());
+                }
+                _ => {}
+            }
+        }
+        
+        Ok(imports)
+    }
+    
+    /// Fully qualified namespace enclosing `node`, combining nested block
+    /// namespaces with a file-scoped `namespace Foo;` declared before it
+    fn enclosing_namespace(&self, node: Node, source: &str) -> Option<String> {
+        let mut parts = Vec::new();
+        let mut current = node.parent();
+        let mut root = node;
+        
+        while let Some(parent) = current {
+            if parent.kind() == "namespace_declaration" {
+                if let Some(name) = parent.child_by_field_name("name").and_then(|n| n.utf8_text(source.as_bytes()).ok()) {
+                    parts.push(name.to_string());
+                }
+            }
+            root = parent;
+            current = parent.parent();
+        }
+        
+        let mut cursor = root.walk();
+        let file_scoped = root.children(&mut cursor)
+            .find(|child| child.kind() == "file_scoped_namespace_declaration" && child.start_byte() < node.start_byte())
+            .and_then(|ns| ns.child_by_field_name("name"))
+            .and_then(|name| name.utf8_text(source.as_bytes()).ok());
+        if let Some(name) = file_scoped {
+            parts.push(name.to_string());
+        }
+        
+        if parts.is_empty() {
+            None
+        } else {
+            parts.reverse();
+            Some(parts.join("."))
+        }
     }
     
     /// Helper: Extract parameters from a method node
@@ -293,7 +380,7 @@ impl TreeSitterCSharpAnalyzer {
         
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
-            if child.kind() == "identifier" || child.kind() == "generic_name" {
+            if matches!(child.kind(), "identifier" | "generic_name" | "qualified_name") {
                 if let Ok(class_name) = child.utf8_text(source.as_bytes()) {
                     base_classes.push(class_name.to_string());
                 }
@@ -303,6 +390,13 @@ impl TreeSitterCSharpAnalyzer {
         Ok(base_classes)
     }
     
+    /// `IDisposable`, `IList<T>`: an `I` followed by an upper-case letter
+    fn is_interface_name(name: &str) -> bool {
+        let name = name.rsplit('.').next().unwrap_or(name);
+        let mut chars = name.chars();
+        chars.next() == Some('I') && chars.next().is_some_and(char::is_uppercase)
+    }
+    
     /// Extract methods from a class node
     fn extract_class_methods(&self, class_node: Node, source: &str) -> Result<Vec<FunctionInfo>> {
         let mut methods = Vec::new();
@@ -342,7 +436,30 @@ impl TreeSitterCSharpAnalyzer {
     /// Build AST from tree-sitter CST
     fn build_ast(&self, tree: &tree_sitter::Tree, source: &str) -> ASTNode {
         let mut root = ASTNode::new(ASTNodeType::FileRoot, String::new());
-        self.build_ast_recursive(tree.root_node(), source, &mut root, 0);
+        let mut file_namespace: Option<usize> = None;
+        
+        let mut cursor = tree.root_node().walk();
+        for child in tree.root_node().children(&mut cursor) {
+            // A file-scoped `namespace Foo;` has no body: every declaration after
+            // it is a sibling in the tree but belongs to the namespace
+            if child.kind() == "file_scoped_namespace_declaration" {
+                let name = child.child_by_field_name("name")
+                    .and_then(|n| n.utf8_text(source.as_bytes()).ok())
+                    .unwrap_or_default();
+                let mut namespace = ASTNode::new(ASTNodeType::Namespace, name.to_string());
+                namespace.start_line = child.start_position().row as u32 + 1;
+                namespace.end_line = tree.root_node().end_position().row as u32 + 1;
+                root.add_child(namespace);
+                file_namespace = Some(root.children.len() - 1);
+                continue;
+            }
+            
+            match file_namespace {
+                Some(index) => self.build_ast_recursive(child, source, &mut root.children[index], 2),
+                None => self.build_ast_recursive(child, source, &mut root, 1),
+            }
+        }
+        
         root
     }
     
@@ -350,6 +467,7 @@ impl TreeSitterCSharpAnalyzer {
     fn build_ast_recursive(&self, node: Node, source: &str, parent: &mut ASTNode, depth: usize) {
         // Map tree-sitter node types to our AST types
         let ast_type = match node.kind() {
+            "namespace_declaration" => ASTNodeType::Namespace,
             "method_declaration" | "constructor_declaration" | "local_function_statement" => ASTNodeType::Function,
             "class_declaration" | "struct_declaration" | "interface_declaration" | "enum_declaration" => ASTNodeType::Class,
             "if_statement" => ASTNodeType::IfStatement,
@@ -407,6 +525,10 @@ impl LanguageAnalyzer for TreeSitterCSharpAnalyzer {
         vec![".cs", ".csx"]
     }
     
+    fn set_parse_timeout(&mut self, timeout: Option<std::time::Duration>) {
+        self.parser.set_timeout_micros(timeout.map_or(0, |timeout| timeout.as_micros() as u64));
+    }
+    
     async fn analyze(&mut self, content: &str, filename: &str) -> Result<AnalysisResult> {
         // Create file info
         let file_path = std::path::PathBuf::from(filename);
@@ -436,8 +558,14 @@ impl LanguageAnalyzer for TreeSitterCSharpAnalyzer {
         
         // 🚀 Parse with tree-sitter (ULTRA FAST!)
         let parse_start = std::time::Instant::now();
-        let tree = self.parser.parse(content, None)
-            .ok_or_else(|| anyhow::anyhow!("Failed to parse C# file"))?;
+        let tree = match self.parser.parse(content, None) {
+            Some(tree) => tree,
+            None => {
+                // A timed-out parse is kept and resumed by the next `parse`
+                self.parser.reset();
+                anyhow::bail!("Failed to parse C# file: parsing timed out");
+            }
+        };
         let parse_duration = parse_start.elapsed();
         
         if std::env::var("NEKOCODE_DEBUG").is_ok() {
@@ -473,4 +601,66 @@ impl LanguageAnalyzer for TreeSitterCSharpAnalyzer {
         
         Ok(result)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[tokio::test]
+    async fn test_namespaces_and_using_directives() {
+        let source = r#"using System;
+using static System.Math;
+using Json = Newtonsoft.Json;
+
+namespace MyApp.Services
+{
+    using MyApp.Models;
+
+    public class Worker
+    {
+        public void DoWork() { }
+    }
+}
+"#;
+        let mut analyzer = TreeSitterCSharpAnalyzer::new().unwrap();
+        let result = analyzer.analyze(source, "Worker.cs").await.unwrap();
+        
+        let modules: Vec<_> = result.imports.iter().map(|i| (i.module_path.as_str(), i.line_number)).collect();
+        assert_eq!(modules, vec![("System", 1), ("System.Math", 2), ("Newtonsoft.Json", 3), ("MyApp.Models", 7)]);
+        assert_eq!(result.imports[1].metadata.get("is_static").map(String::as_str), Some("true"));
+        assert_eq!(result.imports[2].alias.as_deref(), Some("Json"));
+        
+        assert_eq!(result.classes[0].metadata.get("namespace").map(String::as_str), Some("MyApp.Services"));
+        let ast = result.ast_root.unwrap();
+        let matches = ast.query_by_path_with("MyApp.Services::Worker::DoWork", false).unwrap();
+        assert_eq!(matches.len(), 1);
+    }
+    
+    #[tokio::test]
+    async fn test_file_scoped_namespace() {
+        let source = "using MyApp.Models;\n\nnamespace MyApp.Services;\n\npublic class Worker\n{\n    public void DoWork() { }\n}\n";
+        let mut analyzer = TreeSitterCSharpAnalyzer::new().unwrap();
+        let result = analyzer.analyze(source, "Worker.cs").await.unwrap();
+        
+        assert_eq!(result.imports.len(), 1);
+        assert_eq!(result.functions[0].metadata.get("namespace").map(String::as_str), Some("MyApp.Services"));
+        let ast = result.ast_root.unwrap();
+        let matches = ast.query_by_path_with("::MyApp.Services::Worker::DoWork", false).unwrap();
+        assert_eq!(matches.len(), 1);
+    }
+    
+    #[tokio::test]
+    async fn test_base_list_splits_parent_and_interfaces() {
+        let source = "class Repo : IRepository, IDisposable { }\nclass Cache : Store<int>, IDisposable { }\n";
+        let mut analyzer = TreeSitterCSharpAnalyzer::new().unwrap();
+        let result = analyzer.analyze(source, "Repo.cs").await.unwrap();
+        
+        let repo = &result.classes[0];
+        assert_eq!(repo.parent_class, None);
+        assert_eq!(repo.metadata.get("interfaces").map(String::as_str), Some("IRepository, IDisposable"));
+        let cache = &result.classes[1];
+        assert_eq!(cache.parent_class.as_deref(), Some("Store<int>"));
+        assert_eq!(cache.metadata.get("interfaces").map(String::as_str), Some("IDisposable"));
+    }
+}
@@ -2,7 +2,7 @@
 //! 100x faster than PEST implementation!
 
 use anyhow::Result;
-use tree_sitter::{Parser, Query, QueryCursor, Node};
+use tree_sitter::{Parser, QueryCursor, Node};
 use async_trait::async_trait;
 
 use crate::core::types::{
@@ -10,6 +10,7 @@ use crate::core::types::{
     Language, ComplexityInfo, ImportType
 };
 use crate::core::ast::{ASTNode, ASTNodeType, ASTStatistics};
+use crate::analyzers::pool::cached_query;
 use crate::analyzers::traits::LanguageAnalyzer;
 
 pub struct TreeSitterGoAnalyzer {
@@ -39,7 +40,7 @@ impl TreeSitterGoAnalyzer {
             ]
         "#;
         
-        let query = Query::new(&tree_sitter_go::LANGUAGE.into(), query_str)?;
+        let query = cached_query(&tree_sitter_go::LANGUAGE.into(), query_str)?;
         let mut cursor = QueryCursor::new();
         let matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
         
@@ -105,7 +106,7 @@ impl TreeSitterGoAnalyzer {
             ]
         "#;
         
-        let query = Query::new(&tree_sitter_go::LANGUAGE.into(), query_str)?;
+        let query = cached_query(&tree_sitter_go::LANGUAGE.into(), query_str)?;
         let mut cursor = QueryCursor::new();
         let matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
         
@@ -169,7 +170,7 @@ impl TreeSitterGoAnalyzer {
             ]
         "#;
         
-        let query = Query::new(&tree_sitter_go::LANGUAGE.into(), query_str)?;
+        let query = cached_query(&tree_sitter_go::LANGUAGE.into(), query_str)?;
         let mut cursor = QueryCursor::new();
         let matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
         
@@ -252,7 +253,7 @@ impl TreeSitterGoAnalyzer {
               name: (field_identifier) @name) @method
         "#;
         
-        let query = Query::new(&tree_sitter_go::LANGUAGE.into(), query_str)?;
+        let query = cached_query(&tree_sitter_go::LANGUAGE.into(), query_str)?;
         let mut cursor = QueryCursor::new();
         let matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
         
@@ -306,12 +307,25 @@ impl TreeSitterGoAnalyzer {
         root
     }
     
+    /// Receiver type of a method without pointer or type arguments:
+    /// `func (w *Widget[T]) Render()` → `Widget`
+    fn receiver_type(method: Node, source: &str) -> Option<String> {
+        let receiver = method.child_by_field_name("receiver")?;
+        let mut cursor = receiver.walk();
+        let parameter = receiver.named_children(&mut cursor).find(|p| p.kind() == "parameter_declaration")?;
+        let type_text = parameter.child_by_field_name("type")?.utf8_text(source.as_bytes()).ok()?;
+        let name = type_text.trim_start_matches('*').split('[').next()?.trim();
+        (!name.is_empty()).then(|| name.to_string())
+    }
+    
     /// Recursive AST building
     fn build_ast_recursive(&self, node: Node, source: &str, parent: &mut ASTNode, depth: usize) {
         // Map tree-sitter node types to our AST types
         let ast_type = match node.kind() {
-            "function_declaration" | "method_declaration" => ASTNodeType::Function,
-            "type_declaration" => ASTNodeType::Class, // Go structs/interfaces are like classes
+            "function_declaration" => ASTNodeType::Function,
+            "method_declaration" => ASTNodeType::Method,
+            // One node per named type, also in grouped `type ( ... )` declarations
+            "type_spec" | "type_alias" => ASTNodeType::Class, // Go structs/interfaces are like classes
             "if_statement" => ASTNodeType::IfStatement,
             "for_statement" | "range_clause" => ASTNodeType::ForLoop,
             "import_declaration" => ASTNodeType::Import,
@@ -338,6 +352,14 @@ impl TreeSitterGoAnalyzer {
             let parent_index = parent.children.len() - 1;
             let new_parent = &mut parent.children[parent_index];
             
+            // Methods are declared outside their type; scope them under it as
+            // the other languages do
+            if node.kind() == "method_declaration" {
+                if let Some(receiver) = Self::receiver_type(node, source) {
+                    new_parent.scope_path = format!("{}::{}", receiver, new_parent.name);
+                }
+            }
+            
             // Recurse through children with the new node as parent
             let mut cursor = node.walk();
             for child in node.children(&mut cursor) {
@@ -367,6 +389,10 @@ impl LanguageAnalyzer for TreeSitterGoAnalyzer {
         vec![".go"]
     }
     
+    fn set_parse_timeout(&mut self, timeout: Option<std::time::Duration>) {
+        self.parser.set_timeout_micros(timeout.map_or(0, |timeout| timeout.as_micros() as u64));
+    }
+    
     async fn analyze(&mut self, content: &str, filename: &str) -> Result<AnalysisResult> {
         // Create file info
         let file_path = std::path::PathBuf::from(filename);
@@ -396,8 +422,14 @@ impl LanguageAnalyzer for TreeSitterGoAnalyzer {
         
         // 🚀 Parse with tree-sitter (ULTRA FAST!)
         let parse_start = std::time::Instant::now();
-        let tree = self.parser.parse(content, None)
-            .ok_or_else(|| anyhow::anyhow!("Failed to parse Go file"))?;
+        let tree = match self.parser.parse(content, None) {
+            Some(tree) => tree,
+            None => {
+                // A timed-out parse is kept and resumed by the next `parse`
+                self.parser.reset();
+                anyhow::bail!("Failed to parse Go file: parsing timed out");
+            }
+        };
         let parse_duration = parse_start.elapsed();
         
         if std::env::var("NEKOCODE_DEBUG").is_ok() {
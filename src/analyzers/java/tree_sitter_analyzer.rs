@@ -0,0 +1,584 @@
+//! 🚀 Tree-sitter based Java analyzer
+
+use anyhow::Result;
+use tree_sitter::{Parser, QueryCursor, Node};
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+use crate::core::types::{
+    AnalysisResult, ClassInfo, FileInfo, FunctionInfo, ImportInfo,
+    Language, ComplexityInfo, ImportType
+};
+use crate::core::ast::{ASTNode, ASTNodeType, ASTStatistics};
+use crate::analyzers::pool::cached_query;
+use crate::analyzers::traits::LanguageAnalyzer;
+
+pub struct TreeSitterJavaAnalyzer {
+    parser: Parser,
+}
+
+impl TreeSitterJavaAnalyzer {
+    pub fn new() -> Result<Self> {
+        let mut parser = Parser::new();
+        parser.set_language(&tree_sitter_java::LANGUAGE.into())
+            .map_err(|e| anyhow::anyhow!("Failed to set Java language: {:?}", e))?;
+
+        Ok(Self { parser })
+    }
+
+    /// Extract methods and constructors using tree-sitter query
+    fn extract_functions(&self, tree: &tree_sitter::Tree, source: &str) -> Result<Vec<FunctionInfo>> {
+        let mut functions = Vec::new();
+        let package = self.package_name(tree, source);
+
+        let query_str = r#"
+            [
+              (method_declaration
+                name: (identifier) @name) @method
+              (constructor_declaration
+                name: (identifier) @name) @constructor
+            ]
+        "#;
+
+        let query = cached_query(&tree_sitter_java::LANGUAGE.into(), query_str)?;
+        let mut cursor = QueryCursor::new();
+        let matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
+
+        for mat in matches {
+            let mut func_info = FunctionInfo::new(String::new());
+            let mut func_node = None;
+
+            for capture in mat.captures {
+                match query.capture_names()[capture.index as usize] {
+                    "name" => {
+                        func_info.name = capture.node.utf8_text(source.as_bytes())?.to_string();
+                    }
+                    kind @ ("method" | "constructor") => {
+                        func_node = Some(capture.node);
+                        func_info.start_line = capture.node.start_position().row as u32 + 1;
+                        func_info.end_line = capture.node.end_position().row as u32 + 1;
+                        func_info.metadata.insert("type".to_string(), kind.to_string());
+                    }
+                    _ => {}
+                }
+            }
+
+            if let Some(node) = func_node {
+                func_info.metadata = self.method_metadata(node, source, func_info.metadata)?;
+                func_info.parameters = self.extract_parameters(node, source)?;
+                if let Some(package) = &package {
+                    func_info.metadata.insert("package".to_string(), package.clone());
+                }
+            }
+
+            // Set default complexity (will be calculated separately)
+            func_info.complexity = ComplexityInfo::default();
+
+            functions.push(func_info);
+        }
+
+        Ok(functions)
+    }
+
+    /// Extract classes, interfaces, enums and records using tree-sitter query
+    fn extract_classes(&self, tree: &tree_sitter::Tree, source: &str) -> Result<Vec<ClassInfo>> {
+        let mut classes = Vec::new();
+        let package = self.package_name(tree, source);
+
+        let query_str = r#"
+            [
+              (class_declaration
+                name: (identifier) @name) @class
+              (interface_declaration
+                name: (identifier) @name) @interface
+              (enum_declaration
+                name: (identifier) @name) @enum
+              (record_declaration
+                name: (identifier) @name) @record
+            ]
+        "#;
+
+        let query = cached_query(&tree_sitter_java::LANGUAGE.into(), query_str)?;
+        let mut cursor = QueryCursor::new();
+        let matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
+
+        for mat in matches {
+            let mut class_info = ClassInfo::new(String::new());
+            let mut class_node = None;
+
+            for capture in mat.captures {
+                match query.capture_names()[capture.index as usize] {
+                    "name" => {
+                        class_info.name = capture.node.utf8_text(source.as_bytes())?.to_string();
+                    }
+                    kind @ ("class" | "interface" | "enum" | "record") => {
+                        class_node = Some(capture.node);
+                        class_info.start_line = capture.node.start_position().row as u32 + 1;
+                        class_info.end_line = capture.node.end_position().row as u32 + 1;
+                        class_info.metadata.insert("type".to_string(), kind.to_string());
+                        if kind == "interface" {
+                            class_info.metadata.insert("is_interface".to_string(), "true".to_string());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+
+            if let Some(node) = class_node {
+                class_info.methods = self.extract_class_methods(node, source)?;
+
+                // `extends` for classes; interfaces list their parents in `extends_interfaces`
+                if let Some(superclass) = node.child_by_field_name("superclass") {
+                    class_info.parent_class = superclass.named_child(0)
+                        .and_then(|t| t.utf8_text(source.as_bytes()).ok())
+                        .map(str::to_string);
+                }
+                let mut interfaces = Vec::new();
+                let mut cursor = node.walk();
+                for child in node.children(&mut cursor) {
+                    if matches!(child.kind(), "super_interfaces" | "extends_interfaces") {
+                        interfaces.extend(self.type_list(child, source));
+                    }
+                }
+                if !interfaces.is_empty() {
+                    class_info.metadata.insert("interfaces".to_string(), interfaces.join(", "));
+                }
+
+                let (modifiers, _) = self.modifiers(node, source);
+                if !modifiers.is_empty() {
+                    class_info.metadata.insert("modifiers".to_string(), modifiers.join(" "));
+                }
+                if let Some(package) = &package {
+                    class_info.metadata.insert("package".to_string(), package.clone());
+                }
+            }
+
+            classes.push(class_info);
+        }
+
+        Ok(classes)
+    }
+
+    /// Extract `import` declarations, including static and wildcard imports
+    fn extract_imports(&self, tree: &tree_sitter::Tree, source: &str) -> Result<Vec<ImportInfo>> {
+        let mut imports = Vec::new();
+
+        let mut cursor = tree.root_node().walk();
+        for node in tree.root_node().children(&mut cursor) {
+            if node.kind() != "import_declaration" {
+                continue;
+            }
+
+            let mut is_static = false;
+            let mut is_wildcard = false;
+            let mut path = String::new();
+            let mut child_cursor = node.walk();
+            for child in node.children(&mut child_cursor) {
+                match child.kind() {
+                    "static" => is_static = true,
+                    "asterisk" => is_wildcard = true,
+                    "identifier" | "scoped_identifier" => {
+                        path = child.utf8_text(source.as_bytes())?.to_string();
+                    }
+                    _ => {}
+                }
+            }
+
+            let mut import = if is_wildcard {
+                ImportInfo::new(ImportType::JavaImport, format!("{}.*", path))
+            } else {
+                let mut import = ImportInfo::new(ImportType::JavaImport, path.clone());
+                if let Some((_, name)) = path.rsplit_once('.') {
+                    import.imported_names.push(name.to_string());
+                }
+                import
+            };
+            import.line_number = node.start_position().row as u32 + 1;
+            if is_static {
+                import.metadata.insert("is_static".to_string(), "true".to_string());
+            }
+
+            imports.push(import);
+        }
+
+        Ok(imports)
+    }
+
+    /// Name from the file's `package` declaration
+    fn package_name(&self, tree: &tree_sitter::Tree, source: &str) -> Option<String> {
+        let mut cursor = tree.root_node().walk();
+        let package = tree.root_node().children(&mut cursor)
+            .find(|child| child.kind() == "package_declaration")?;
+
+        let mut cursor = package.walk();
+        let name = package.named_children(&mut cursor)
+            .find(|child| matches!(child.kind(), "identifier" | "scoped_identifier"))?;
+        name.utf8_text(source.as_bytes()).ok().map(str::to_string)
+    }
+
+    /// Keyword modifiers (`public`, `static`, ...) and annotations of a declaration
+    fn modifiers(&self, node: Node, source: &str) -> (Vec<String>, Vec<String>) {
+        let mut modifiers = Vec::new();
+        let mut annotations = Vec::new();
+
+        let mut cursor = node.walk();
+        let Some(modifiers_node) = node.children(&mut cursor).find(|child| child.kind() == "modifiers") else {
+            return (modifiers, annotations);
+        };
+
+        let mut cursor = modifiers_node.walk();
+        for child in modifiers_node.children(&mut cursor) {
+            let Ok(text) = child.utf8_text(source.as_bytes()) else { continue };
+            if matches!(child.kind(), "annotation" | "marker_annotation") {
+                annotations.push(text.to_string());
+            } else {
+                modifiers.push(text.to_string());
+            }
+        }
+
+        (modifiers, annotations)
+    }
+
+    /// Return type, modifiers and annotations of a method or constructor
+    fn method_metadata(&self, node: Node, source: &str, mut metadata: HashMap<String, String>) -> Result<HashMap<String, String>> {
+        if let Some(return_type) = node.child_by_field_name("type") {
+            metadata.insert("return_type".to_string(), return_type.utf8_text(source.as_bytes())?.to_string());
+        }
+        if node.kind() == "constructor_declaration" {
+            metadata.insert("is_constructor".to_string(), "true".to_string());
+        }
+
+        let (modifiers, annotations) = self.modifiers(node, source);
+        if modifiers.iter().any(|m| m == "static") {
+            metadata.insert("is_static".to_string(), "true".to_string());
+        }
+        if annotations.iter().any(|a| a == "@Override") {
+            metadata.insert("is_override".to_string(), "true".to_string());
+        }
+        if !modifiers.is_empty() {
+            metadata.insert("modifiers".to_string(), modifiers.join(" "));
+        }
+        if !annotations.is_empty() {
+            metadata.insert("annotations".to_string(), annotations.join(", "));
+        }
+
+        Ok(metadata)
+    }
+
+    /// Helper: Extract `Type name` parameters from a method node
+    fn extract_parameters(&self, node: Node, source: &str) -> Result<Vec<String>> {
+        let mut params = Vec::new();
+
+        if let Some(param_list) = node.child_by_field_name("parameters") {
+            let mut cursor = param_list.walk();
+            for child in param_list.named_children(&mut cursor) {
+                match child.kind() {
+                    "formal_parameter" => {
+                        let param_type = child.child_by_field_name("type")
+                            .map(|t| t.utf8_text(source.as_bytes()))
+                            .transpose()?
+                            .unwrap_or_default();
+                        let name = child.child_by_field_name("name")
+                            .map(|n| n.utf8_text(source.as_bytes()))
+                            .transpose()?
+                            .unwrap_or_default();
+                        params.push(format!("{} {}", param_type, name).trim().to_string());
+                    }
+                    // `String... args`
+                    "spread_parameter" => {
+                        let text = child.utf8_text(source.as_bytes())?;
+                        params.push(text.split_whitespace().collect::<Vec<_>>().join(" "));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(params)
+    }
+
+    /// Type names listed in `implements` / `extends` clauses
+    fn type_list(&self, node: Node, source: &str) -> Vec<String> {
+        let mut names = Vec::new();
+        let mut cursor = node.walk();
+        for list in node.named_children(&mut cursor) {
+            let mut list_cursor = list.walk();
+            for item in list.named_children(&mut list_cursor) {
+                if let Ok(name) = item.utf8_text(source.as_bytes()) {
+                    names.push(name.to_string());
+                }
+            }
+        }
+        names
+    }
+
+    /// Extract the methods and constructors declared directly in a type body
+    fn extract_class_methods(&self, class_node: Node, source: &str) -> Result<Vec<FunctionInfo>> {
+        let mut methods = Vec::new();
+        let Some(body) = class_node.child_by_field_name("body") else {
+            return Ok(methods);
+        };
+
+        // Enum members live in an `enum_body_declarations` after the constants
+        let mut members = Vec::new();
+        let mut cursor = body.walk();
+        for child in body.named_children(&mut cursor) {
+            if child.kind() == "enum_body_declarations" {
+                let mut inner = child.walk();
+                members.extend(child.named_children(&mut inner));
+            } else {
+                members.push(child);
+            }
+        }
+
+        for child in members {
+            if !matches!(child.kind(), "method_declaration" | "constructor_declaration") {
+                continue;
+            }
+
+            let mut method = FunctionInfo::new(String::new());
+            if let Some(name_node) = child.child_by_field_name("name") {
+                method.name = name_node.utf8_text(source.as_bytes())?.to_string();
+            }
+            method.start_line = child.start_position().row as u32 + 1;
+            method.end_line = child.end_position().row as u32 + 1;
+            method.parameters = self.extract_parameters(child, source)?;
+            method.metadata.insert("is_class_method".to_string(), "true".to_string());
+            method.metadata = self.method_metadata(child, source, method.metadata)?;
+
+            methods.push(method);
+        }
+
+        Ok(methods)
+    }
+
+    /// Build AST from tree-sitter CST
+    fn build_ast(&self, tree: &tree_sitter::Tree, source: &str) -> ASTNode {
+        let mut root = ASTNode::new(ASTNodeType::FileRoot, String::new());
+        let mut package: Option<usize> = None;
+
+        let mut cursor = tree.root_node().walk();
+        for child in tree.root_node().children(&mut cursor) {
+            // Declarations after `package a.b;` are siblings in the tree but
+            // belong to the package, so their scope paths start with it
+            if child.kind() == "package_declaration" {
+                let name = self.package_name(tree, source).unwrap_or_default();
+                let mut namespace = ASTNode::new(ASTNodeType::Namespace, name);
+                namespace.start_line = child.start_position().row as u32 + 1;
+                namespace.end_line = tree.root_node().end_position().row as u32 + 1;
+                root.add_child(namespace);
+                package = Some(root.children.len() - 1);
+                continue;
+            }
+
+            match package {
+                Some(index) => self.build_ast_recursive(child, source, &mut root.children[index], 2),
+                None => self.build_ast_recursive(child, source, &mut root, 1),
+            }
+        }
+
+        root
+    }
+
+    /// Recursive AST building
+    fn build_ast_recursive(&self, node: Node, source: &str, parent: &mut ASTNode, depth: usize) {
+        // Map tree-sitter node types to our AST types
+        let ast_type = match node.kind() {
+            "method_declaration" | "constructor_declaration" => ASTNodeType::Function,
+            "class_declaration" | "interface_declaration" | "enum_declaration" | "record_declaration" => ASTNodeType::Class,
+            "if_statement" => ASTNodeType::IfStatement,
+            "for_statement" | "enhanced_for_statement" | "while_statement" => ASTNodeType::ForLoop,
+            "import_declaration" => ASTNodeType::Import,
+            "local_variable_declaration" | "field_declaration" => ASTNodeType::Variable,
+            _ => ASTNodeType::Unknown,
+        };
+
+        if ast_type != ASTNodeType::Unknown {
+            let mut ast_node = ASTNode::new(ast_type, String::new());
+            ast_node.start_line = node.start_position().row as u32 + 1;
+            ast_node.end_line = node.end_position().row as u32 + 1;
+            ast_node.depth = depth as u32;
+
+            // Try to get node name
+            if let Some(name_field) = node.child_by_field_name("name") {
+                if let Ok(name) = name_field.utf8_text(source.as_bytes()) {
+                    ast_node.name = name.to_string();
+                }
+            }
+
+            parent.add_child(ast_node);
+
+            // Use the newly created node as parent for its children
+            let parent_index = parent.children.len() - 1;
+            let new_parent = &mut parent.children[parent_index];
+
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                self.build_ast_recursive(child, source, new_parent, depth + 1);
+            }
+        } else {
+            // For unknown nodes, just recurse through children with the same parent
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                self.build_ast_recursive(child, source, parent, depth + 1);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl LanguageAnalyzer for TreeSitterJavaAnalyzer {
+    fn get_language(&self) -> Language {
+        Language::Java
+    }
+
+    fn get_language_name(&self) -> &'static str {
+        "Java (Tree-sitter)"
+    }
+
+    fn get_supported_extensions(&self) -> Vec<&'static str> {
+        vec![".java"]
+    }
+
+    fn set_parse_timeout(&mut self, timeout: Option<std::time::Duration>) {
+        self.parser.set_timeout_micros(timeout.map_or(0, |timeout| timeout.as_micros() as u64));
+    }
+    
+    async fn analyze(&mut self, content: &str, filename: &str) -> Result<AnalysisResult> {
+        // Create file info
+        let file_path = std::path::PathBuf::from(filename);
+        let mut file_info = FileInfo::new(file_path);
+        file_info.total_lines = content.lines().count() as u32;
+
+        // Calculate basic line statistics
+        for line in content.lines() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                file_info.empty_lines += 1;
+            } else if trimmed.starts_with("//") || trimmed.starts_with("/*") || trimmed.starts_with("*") {
+                file_info.comment_lines += 1;
+            } else {
+                file_info.code_lines += 1;
+            }
+        }
+
+        file_info.code_ratio = if file_info.total_lines > 0 {
+            file_info.code_lines as f64 / file_info.total_lines as f64
+        } else {
+            0.0
+        };
+
+        // Create analysis result
+        let mut result = AnalysisResult::new(file_info, Language::Java);
+
+        let parse_start = std::time::Instant::now();
+        let tree = match self.parser.parse(content, None) {
+            Some(tree) => tree,
+            None => {
+                // A timed-out parse is kept and resumed by the next `parse`
+                self.parser.reset();
+                anyhow::bail!("Failed to parse Java file: parsing timed out");
+            }
+        };
+        let parse_duration = parse_start.elapsed();
+
+        if std::env::var("NEKOCODE_DEBUG").is_ok() {
+            eprintln!("⚡ [TREE-SITTER JAVA] Parse took: {:.3}ms", parse_duration.as_secs_f64() * 1000.0);
+        }
+
+        // Extract all constructs
+        let extract_start = std::time::Instant::now();
+        result.functions = self.extract_functions(&tree, content)?;
+        result.classes = self.extract_classes(&tree, content)?;
+        result.imports = self.extract_imports(&tree, content)?;
+        let extract_duration = extract_start.elapsed();
+
+        if std::env::var("NEKOCODE_DEBUG").is_ok() {
+            eprintln!("⚡ [TREE-SITTER JAVA] Extraction took: {:.3}ms", extract_duration.as_secs_f64() * 1000.0);
+        }
+
+        // Build AST
+        let ast_start = std::time::Instant::now();
+        let ast_root = self.build_ast(&tree, content);
+        let mut ast_stats = ASTStatistics::default();
+        ast_stats.update_from_root(&ast_root);
+        result.ast_root = Some(ast_root);
+        result.ast_statistics = Some(ast_stats);
+        let ast_duration = ast_start.elapsed();
+
+        if std::env::var("NEKOCODE_DEBUG").is_ok() {
+            eprintln!("⚡ [TREE-SITTER JAVA] AST build took: {:.3}ms", ast_duration.as_secs_f64() * 1000.0);
+        }
+
+        // Update statistics
+        result.update_statistics();
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_analyze_main_java() {
+        let source = r#"package com.example.app;
+
+import java.util.List;
+import java.util.*;
+import static java.lang.Math.max;
+
+public class Main extends Base implements Runnable, Comparable<Main> {
+    public Main(String name) { }
+
+    @Override
+    public void run() { }
+
+    public static int sum(List<Integer> values, int... extra) {
+        return 0;
+    }
+}
+
+interface Greeter {
+    String greet(String name);
+}
+
+enum Color {
+    RED, GREEN;
+
+    Color next() { return RED; }
+}
+"#;
+        let mut analyzer = TreeSitterJavaAnalyzer::new().unwrap();
+        let result = analyzer.analyze(source, "Main.java").await.unwrap();
+
+        let imports: Vec<_> = result.imports.iter().map(|i| (i.module_path.as_str(), i.line_number)).collect();
+        assert_eq!(imports, vec![("java.util.List", 3), ("java.util.*", 4), ("java.lang.Math.max", 5)]);
+        assert_eq!(result.imports[0].imported_names, vec!["List"]);
+        assert_eq!(result.imports[2].metadata.get("is_static").map(String::as_str), Some("true"));
+
+        let classes: Vec<_> = result.classes.iter()
+            .map(|c| (c.name.as_str(), c.metadata.get("type").unwrap().as_str()))
+            .collect();
+        assert_eq!(classes, vec![("Main", "class"), ("Greeter", "interface"), ("Color", "enum")]);
+        let main = &result.classes[0];
+        assert_eq!(main.parent_class.as_deref(), Some("Base"));
+        assert_eq!(main.metadata.get("interfaces").map(String::as_str), Some("Runnable, Comparable<Main>"));
+        assert_eq!(main.metadata.get("package").map(String::as_str), Some("com.example.app"));
+        let methods: Vec<_> = main.methods.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(methods, vec!["Main", "run", "sum"]);
+        assert_eq!(result.classes[2].methods[0].name, "next");
+
+        let sum = &main.methods[2];
+        assert_eq!(sum.parameters, vec!["List<Integer> values", "int... extra"]);
+        assert_eq!(sum.metadata.get("return_type").map(String::as_str), Some("int"));
+        assert_eq!(sum.metadata.get("is_static").map(String::as_str), Some("true"));
+        assert_eq!(main.methods[1].metadata.get("is_override").map(String::as_str), Some("true"));
+        assert_eq!(main.methods[0].metadata.get("is_constructor").map(String::as_str), Some("true"));
+
+        let ast = result.ast_root.unwrap();
+        let matches = ast.query_by_path_with("com.example.app::Main::run", false).unwrap();
+        assert_eq!(matches.len(), 1);
+    }
+}
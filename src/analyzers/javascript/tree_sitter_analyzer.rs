@@ -2,14 +2,16 @@
 //! 100x faster than PEST implementation!
 
 use anyhow::Result;
-use tree_sitter::{Parser, Query, QueryCursor, Node};
+use tree_sitter::{Parser, QueryCursor, Node};
 use async_trait::async_trait;
+use std::collections::HashMap;
 
 use crate::core::types::{
     AnalysisResult, ClassInfo, FileInfo, FunctionInfo, ImportInfo, 
-    ExportInfo, Language, FunctionCall, ComplexityInfo
+    ExportInfo, ExportType, Language, FunctionCall, ComplexityInfo
 };
 use crate::core::ast::{ASTBuilder, ASTNode, ASTNodeType, ASTStatistics};
+use crate::analyzers::pool::cached_query;
 use crate::analyzers::traits::LanguageAnalyzer;
 
 pub struct TreeSitterJavaScriptAnalyzer {
@@ -26,6 +28,13 @@ impl TreeSitterJavaScriptAnalyzer {
         Ok(Self { parser })
     }
     
+    /// Switch back to JavaScript after a TypeScript file
+    pub fn set_javascript(&mut self) -> Result<()> {
+        self.parser.set_language(&tree_sitter_javascript::LANGUAGE.into())
+            .map_err(|e| anyhow::anyhow!("Failed to set JavaScript language: {:?}", e))?;
+        Ok(())
+    }
+    
     pub fn set_typescript(&mut self) -> Result<()> {
         // Switch to TypeScript grammar for .ts/.tsx files
         self.parser.set_language(&tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into())
@@ -57,14 +66,15 @@ impl TreeSitterJavaScriptAnalyzer {
               (function_declaration
                 name: (identifier) @name) @function
               (function_expression
-                name: (identifier) @name) @function
+                name: (identifier)? @name) @function
               (arrow_function) @function
               (method_definition
                 name: (property_identifier) @name) @function
             ]
         "#;
         
-        let query = Query::new(&tree_sitter_javascript::LANGUAGE.into(), query_str)?;
+        // The parsed grammar, so TypeScript files match too
+        let query = cached_query(&tree.language(), query_str)?;
         let mut cursor = QueryCursor::new();
         let matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
         
@@ -88,6 +98,12 @@ impl TreeSitterJavaScriptAnalyzer {
             
             // Extract parameters if we have a function node
             if let Some(node) = func_node {
+                if func_info.name.is_empty() {
+                    if let Some(name) = self.resolve_assigned_name(node, source) {
+                        func_info.name = name;
+                    }
+                }
+                
                 func_info.parameters = self.extract_parameters(node, source)?;
                 func_info.is_async = self.is_async_function(node, source);
                 func_info.is_arrow_function = node.kind() == "arrow_function";
@@ -106,12 +122,13 @@ impl TreeSitterJavaScriptAnalyzer {
     fn extract_classes(&self, tree: &tree_sitter::Tree, source: &str) -> Result<Vec<ClassInfo>> {
         let mut classes = Vec::new();
         
+        // `identifier` in JavaScript, `type_identifier` in TypeScript
         let query_str = r#"
             (class_declaration
-              name: (identifier) @name) @class
+              name: (_) @name) @class
         "#;
         
-        let query = Query::new(&tree_sitter_javascript::LANGUAGE.into(), query_str)?;
+        let query = cached_query(&tree.language(), query_str)?;
         let mut cursor = QueryCursor::new();
         let matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
         
@@ -130,11 +147,11 @@ impl TreeSitterJavaScriptAnalyzer {
                         // Extract methods
                         class_info.methods = self.extract_class_methods(capture.node, source)?;
                         
-                        // Check for extends
-                        if let Some(heritage) = capture.node.child_by_field_name("heritage") {
-                            if let Some(extends) = heritage.child_by_field_name("parent") {
-                                class_info.parent_class = Some(extends.utf8_text(source.as_bytes())?.to_string());
-                            }
+                        // `extends Base implements Shape, Named`
+                        let (parent, interfaces) = self.extract_heritage(capture.node, source)?;
+                        class_info.parent_class = parent;
+                        if !interfaces.is_empty() {
+                            class_info.metadata.insert("interfaces".to_string(), interfaces.join(", "));
                         }
                     }
                     _ => {}
@@ -156,7 +173,7 @@ impl TreeSitterJavaScriptAnalyzer {
               source: (string) @source) @import
         "#;
         
-        let query = Query::new(&tree_sitter_javascript::LANGUAGE.into(), query_str)?;
+        let query = cached_query(&tree_sitter_javascript::LANGUAGE.into(), query_str)?;
         let mut cursor = QueryCursor::new();
         let matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
         
@@ -186,42 +203,247 @@ impl TreeSitterJavaScriptAnalyzer {
         Ok(imports)
     }
     
-    /// Extract exports using tree-sitter query
+    /// Extract ES module exports and top-level CommonJS `module.exports` /
+    /// `exports.x` assignments
     fn extract_exports(&self, tree: &tree_sitter::Tree, source: &str) -> Result<Vec<ExportInfo>> {
         let mut exports = Vec::new();
         
-        let query_str = r#"
-            (export_statement) @export
-        "#;
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        for node in root.named_children(&mut cursor) {
+            let export = match node.kind() {
+                "export_statement" => Some(self.es_export(node, source)?),
+                "expression_statement" => self.commonjs_export(node, source)?,
+                "ambient_declaration" => self.ambient_module_export(node, source)?,
+                _ => None,
+            };
+            if let Some(mut export) = export {
+                export.line_number = node.start_position().row as u32 + 1;
+                exports.push(export);
+            }
+        }
         
-        let query = Query::new(&tree_sitter_javascript::LANGUAGE.into(), query_str)?;
-        let mut cursor = QueryCursor::new();
-        let matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
+        Ok(exports)
+    }
+    
+    /// `export ...` in all its forms, including `export ... from` re-exports
+    fn es_export(&self, node: Node, source: &str) -> Result<ExportInfo> {
+        let mut cursor = node.walk();
+        let is_default = node.children(&mut cursor).any(|child| child.kind() == "default");
+        let mut export = ExportInfo::new(if is_default { ExportType::ES6Default } else { ExportType::ES6Export });
+        export.is_default = is_default;
+        
+        if let Some(module) = node.child_by_field_name("source") {
+            let module = module.utf8_text(source.as_bytes())?;
+            export.source_module = Some(module.trim_matches(|c| c == '"' || c == '\'' || c == '`').to_string());
+        }
         
-        for mat in matches {
-            let mut export_info = ExportInfo::new(
-                crate::core::types::ExportType::ES6Export
-            );
-            
-            for capture in mat.captures {
-                let node = capture.node;
-                export_info.line_number = node.start_position().row as u32 + 1;
-                export_info.is_default = false;  // Will be determined by parsing the text
-                
-                // Extract exported names
-                if let Ok(text) = node.utf8_text(source.as_bytes()) {
-                    if text.contains("function") {
-                        export_info.exported_names.push("function".to_string());
-                    } else if text.contains("class") {
-                        export_info.exported_names.push("class".to_string());
+        if let Some(declaration) = node.child_by_field_name("declaration") {
+            export.exported_names = self.declared_names(declaration, source)?;
+        } else if let Some(value) = node.child_by_field_name("value") {
+            // `export default expr`: an identifier names the exported binding
+            if value.kind() == "identifier" {
+                export.exported_names.push(value.utf8_text(source.as_bytes())?.to_string());
+            } else if let Some(name) = value.child_by_field_name("name") {
+                export.exported_names.push(name.utf8_text(source.as_bytes())?.to_string());
+            }
+        }
+        
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            match child.kind() {
+                // `export { a, b as c }` exports `a` and `c`
+                "export_clause" => {
+                    let mut specifiers = child.walk();
+                    for specifier in child.named_children(&mut specifiers) {
+                        let name = specifier.child_by_field_name("alias")
+                            .or_else(|| specifier.child_by_field_name("name"));
+                        if let Some(name) = name {
+                            export.exported_names.push(name.utf8_text(source.as_bytes())?.to_string());
+                        }
+                    }
+                }
+                // `export * as ns from './m'`
+                "namespace_export" => {
+                    let mut names = child.walk();
+                    if let Some(name) = child.named_children(&mut names).last() {
+                        export.exported_names.push(name.utf8_text(source.as_bytes())?.to_string());
                     }
                 }
+                _ => {}
             }
-            
-            exports.push(export_info);
         }
         
-        Ok(exports)
+        // `export * from './m'`
+        let mut cursor = node.walk();
+        if export.exported_names.is_empty() && node.children(&mut cursor).any(|child| child.kind() == "*") {
+            export.exported_names.push("*".to_string());
+        }
+        if is_default && export.exported_names.is_empty() {
+            export.exported_names.push("default".to_string());
+        }
+        
+        Ok(export)
+    }
+    
+    /// Names a declaration statement introduces, looking through `export` and
+    /// `declare`; `const a = 1, b = 2` declares several names at once
+    fn declared_names(&self, node: Node, source: &str) -> Result<Vec<String>> {
+        let mut node = node;
+        if node.kind() == "export_statement" {
+            match node.child_by_field_name("declaration") {
+                Some(declaration) => node = declaration,
+                None => return Ok(Vec::new()),
+            }
+        }
+        if node.kind() == "ambient_declaration" {
+            match node.named_child(0) {
+                Some(declaration) => node = declaration,
+                None => return Ok(Vec::new()),
+            }
+        }
+        
+        let mut names = Vec::new();
+        if matches!(node.kind(), "lexical_declaration" | "variable_declaration") {
+            let mut declarators = node.walk();
+            for declarator in node.named_children(&mut declarators) {
+                if let Some(name) = declarator.child_by_field_name("name") {
+                    names.push(name.utf8_text(source.as_bytes())?.to_string());
+                }
+            }
+        } else if let Some(name) = node.child_by_field_name("name") {
+            names.push(name.utf8_text(source.as_bytes())?.to_string());
+        }
+        Ok(names)
+    }
+    
+    /// `declare module 'name' { ... }`: everything declared in the body is
+    /// part of the module, with or without `export`
+    fn ambient_module_export(&self, node: Node, source: &str) -> Result<Option<ExportInfo>> {
+        let Some(module) = node.named_child(0).filter(|n| n.kind() == "module") else {
+            return Ok(None);
+        };
+        let (Some(name), Some(body)) = (module.child_by_field_name("name"), module.child_by_field_name("body")) else {
+            return Ok(None);
+        };
+        
+        let mut export = ExportInfo::new(ExportType::TsAmbientModule);
+        let name = name.utf8_text(source.as_bytes())?;
+        export.source_module = Some(name.trim_matches(|c| c == '"' || c == '\'').to_string());
+        let mut cursor = body.walk();
+        for declaration in body.named_children(&mut cursor) {
+            export.exported_names.extend(self.declared_names(declaration, source)?);
+        }
+        Ok(Some(export))
+    }
+    
+    /// Bodiless `function_signature`s: `declare function`, overloads and the
+    /// functions of `.d.ts` files
+    fn extract_function_signatures(&self, tree: &tree_sitter::Tree, source: &str) -> Result<Vec<FunctionInfo>> {
+        let mut functions = Vec::new();
+        for node in descendants_of_kind(tree.root_node(), &["function_signature"]) {
+            let mut function = FunctionInfo::new(String::new());
+            if let Some(name) = node.child_by_field_name("name") {
+                function.name = name.utf8_text(source.as_bytes())?.to_string();
+            }
+            function.start_line = node.start_position().row as u32 + 1;
+            function.end_line = node.end_position().row as u32 + 1;
+            function.parameters = self.extract_parameters(node, source)?;
+            mark_declaration(&mut function.metadata);
+            functions.push(function);
+        }
+        Ok(functions)
+    }
+    
+    /// Flag the functions, classes and methods of a `.d.ts` file or inside
+    /// `declare` blocks as declarations, and move the lines they span from
+    /// `code_lines` to the `declaration_lines` file metadata: they describe
+    /// code that lives elsewhere.
+    fn mark_declarations(&self, tree: &tree_sitter::Tree, source: &str, declaration_file: bool, result: &mut AnalysisResult) {
+        let ranges: Vec<(u32, u32)> = if declaration_file {
+            vec![(1, u32::MAX)]
+        } else {
+            descendants_of_kind(tree.root_node(), &["ambient_declaration", "function_signature"]).iter()
+                .map(|node| (node.start_position().row as u32 + 1, node.end_position().row as u32 + 1))
+                .collect()
+        };
+        let declared = |line: u32| ranges.iter().any(|&(start, end)| start <= line && line <= end);
+        
+        for class in &mut result.classes {
+            if declared(class.start_line) {
+                mark_declaration(&mut class.metadata);
+            }
+            for method in class.methods.iter_mut().filter(|method| declared(method.start_line)) {
+                mark_declaration(&mut method.metadata);
+            }
+        }
+        for function in result.functions.iter_mut().filter(|function| declared(function.start_line)) {
+            mark_declaration(&mut function.metadata);
+        }
+        
+        let declaration_lines = source.lines().enumerate()
+            .filter(|(index, line)| declared(*index as u32 + 1) && is_code_line(line))
+            .count() as u32;
+        if declaration_lines > 0 {
+            let file_info = &mut result.file_info;
+            file_info.code_lines -= declaration_lines;
+            file_info.code_ratio = file_info.code_lines as f64 / file_info.total_lines.max(1) as f64;
+            file_info.metadata.insert("declaration_lines".to_string(), declaration_lines.to_string());
+        }
+    }
+    
+    /// `module.exports = ...`, `module.exports.x = ...` and `exports.x = ...`
+    fn commonjs_export(&self, node: Node, source: &str) -> Result<Option<ExportInfo>> {
+        let Some(assignment) = node.named_child(0).filter(|n| n.kind() == "assignment_expression") else {
+            return Ok(None);
+        };
+        let (Some(left), Some(right)) = (assignment.child_by_field_name("left"), assignment.child_by_field_name("right")) else {
+            return Ok(None);
+        };
+        
+        let target = left.utf8_text(source.as_bytes())?;
+        let mut export = ExportInfo::new(ExportType::CommonJSExports);
+        
+        if target == "module.exports" {
+            match right.kind() {
+                // `module.exports = { a, b: impl, c() {} }` exports each key
+                "object" => {
+                    let mut properties = right.walk();
+                    for property in right.named_children(&mut properties) {
+                        let key = match property.kind() {
+                            "shorthand_property_identifier" => Some(property),
+                            "pair" => property.child_by_field_name("key"),
+                            "method_definition" => property.child_by_field_name("name"),
+                            _ => None,
+                        };
+                        if let Some(key) = key {
+                            export.exported_names.push(key.utf8_text(source.as_bytes())?.to_string());
+                        }
+                    }
+                }
+                "identifier" => {
+                    export.is_default = true;
+                    export.exported_names.push(right.utf8_text(source.as_bytes())?.to_string());
+                }
+                _ => {
+                    export.is_default = true;
+                    let name = match right.child_by_field_name("name") {
+                        Some(name) => name.utf8_text(source.as_bytes())?.to_string(),
+                        None => "default".to_string(),
+                    };
+                    export.exported_names.push(name);
+                }
+            }
+        } else if let Some(name) = target.strip_prefix("module.exports.").or_else(|| target.strip_prefix("exports.")) {
+            if name.contains('.') {
+                return Ok(None);
+            }
+            export.exported_names.push(name.to_string());
+        } else {
+            return Ok(None);
+        }
+        
+        Ok(Some(export))
     }
     
     /// Extract function calls using tree-sitter query
@@ -235,68 +457,46 @@ impl TreeSitterJavaScriptAnalyzer {
                 function: (identifier) @function) @call
               (call_expression
                 function: (member_expression
-                  property: (property_identifier) @property)) @method_call
-              (call_expression
-                function: (member_expression
-                  object: (identifier) @object
+                  object: (_) @object
                   property: (property_identifier) @property)) @method_call
             ]
         "#;
         
-        let query = Query::new(&self.get_current_language(), query_str)?;
+        let query = cached_query(&self.get_current_language(), query_str)?;
         let mut cursor = QueryCursor::new();
         let matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
         
         for mat in matches {
             let mut function_name = String::new();
+            let mut object_name = None;
             let mut line_number = 0;
+            let mut columns = (0, 0);
             
             for capture in mat.captures {
                 match query.capture_names()[capture.index as usize].as_ref() {
-                    "function" => {
+                    "function" | "property" => {
                         function_name = capture.node.utf8_text(source.as_bytes())?.to_string();
-                        line_number = capture.node.start_position().row as u32 + 1;
-                    }
-                    "property" => {
-                        // For method calls, use the property name as the function name
-                        let property_name = capture.node.utf8_text(source.as_bytes())?.to_string();
-                        if function_name.is_empty() {
-                            function_name = property_name;
-                            line_number = capture.node.start_position().row as u32 + 1;
-                        }
                     }
                     "object" => {
-                        // For method calls like obj.method(), we want to capture both
-                        let object_name = capture.node.utf8_text(source.as_bytes())?.to_string();
-                        if !function_name.is_empty() {
-                            function_name = format!("{}.{}", object_name, function_name);
-                        }
+                        // For method calls like obj.method(), keep the receiver separately
+                        object_name = Some(capture.node.utf8_text(source.as_bytes())?.to_string());
                     }
                     "call" | "method_call" => {
-                        if line_number == 0 {
-                            line_number = capture.node.start_position().row as u32 + 1;
-                        }
+                        line_number = capture.node.start_position().row as u32 + 1;
+                        columns = (
+                            capture.node.start_position().column as u32,
+                            capture.node.end_position().column as u32,
+                        );
                     }
                     _ => {}
                 }
             }
             
             if !function_name.is_empty() {
-                let function_call = FunctionCall::new(function_name, line_number);
-                
-                // Try to extract arguments count
-                let call_node = mat.captures.iter()
-                    .find(|c| {
-                        let name = &query.capture_names()[c.index as usize];
-                        *name == "call" || *name == "method_call"
-                    })
-                    .map(|c| c.node);
-                
-                if let Some(_node) = call_node {
-                    // For now, just create the function call without metadata
-                    // TODO: Could extend FunctionCall struct to include argument count
-                }
-                
+                let mut function_call = FunctionCall::new(function_name, line_number);
+                function_call.is_method_call = object_name.is_some();
+                function_call.object_name = object_name;
+                (function_call.column_start, function_call.column_end) = columns;
                 function_calls.push(function_call);
             }
         }
@@ -323,20 +523,46 @@ impl TreeSitterJavaScriptAnalyzer {
     }
     
     /// Helper: Check if function is async
-    fn is_async_function(&self, node: Node, source: &str) -> bool {
-        // Check if there's an async keyword before the function
-        if let Some(prev_sibling) = node.prev_sibling() {
-            if prev_sibling.kind() == "async" {
-                return true;
+    fn is_async_function(&self, node: Node, _source: &str) -> bool {
+        // function_declaration / function_expression / arrow_function / method_definition
+        // carry the `async` keyword as a direct anonymous child
+        let mut cursor = node.walk();
+        let is_async = node.children(&mut cursor).any(|child| child.kind() == "async");
+        is_async
+    }
+    
+    /// Helper: Name an anonymous arrow function / function expression after the
+    /// binding it is assigned to (`const handler = () => {}`, `obj.run = function() {}`,
+    /// `{ key: () => {} }`, class field `onClick = () => {}`)
+    fn resolve_assigned_name(&self, node: Node, source: &str) -> Option<String> {
+        let parent = node.parent()?;
+        
+        let target = match parent.kind() {
+            "variable_declarator" => parent.child_by_field_name("name")?,
+            "assignment_expression" => {
+                let left = parent.child_by_field_name("left")?;
+                if left.kind() == "member_expression" {
+                    left.child_by_field_name("property").unwrap_or(left)
+                } else {
+                    left
+                }
             }
-        }
+            "pair" => parent.child_by_field_name("key")?,
+            "field_definition" => parent.child_by_field_name("property")?,
+            "public_field_definition" => parent.child_by_field_name("name")?,
+            _ => return None,
+        };
         
-        // Check the function's text itself
-        if let Ok(text) = node.utf8_text(source.as_bytes()) {
-            return text.starts_with("async");
+        // Skip destructuring patterns such as `const { a } = ...`
+        if !matches!(
+            target.kind(),
+            "identifier" | "property_identifier" | "private_property_identifier" | "string" | "member_expression"
+        ) {
+            return None;
         }
         
-        false
+        let name = target.utf8_text(source.as_bytes()).ok()?;
+        Some(name.trim_matches(|c| c == '"' || c == '\'').to_string())
     }
     
     /// Extract methods from a class node
@@ -346,8 +572,12 @@ impl TreeSitterJavaScriptAnalyzer {
         if let Some(body) = class_node.child_by_field_name("body") {
             let mut cursor = body.walk();
             for child in body.children(&mut cursor) {
-                if child.kind() == "method_definition" {
+                // Signatures (`declare class` members, overloads) have no body
+                if matches!(child.kind(), "method_definition" | "method_signature" | "abstract_method_signature") {
                     let mut method = FunctionInfo::new(String::new());
+                    if child.kind() != "method_definition" {
+                        mark_declaration(&mut method.metadata);
+                    }
                     
                     if let Some(name_node) = child.child_by_field_name("name") {
                         method.name = name_node.utf8_text(source.as_bytes())?.to_string();
@@ -366,6 +596,37 @@ impl TreeSitterJavaScriptAnalyzer {
         Ok(methods)
     }
     
+    /// Parent class and implemented interfaces from a class's `class_heritage`
+    fn extract_heritage(&self, class_node: Node, source: &str) -> Result<(Option<String>, Vec<String>)> {
+        let mut parent = None;
+        let mut interfaces = Vec::new();
+        
+        let mut cursor = class_node.walk();
+        let heritage = class_node.children(&mut cursor).find(|child| child.kind() == "class_heritage");
+        if let Some(heritage) = heritage {
+            let mut cursor = heritage.walk();
+            for clause in heritage.named_children(&mut cursor) {
+                match clause.kind() {
+                    "extends_clause" => {
+                        if let Some(value) = clause.child_by_field_name("value") {
+                            parent = Some(value.utf8_text(source.as_bytes())?.to_string());
+                        }
+                    }
+                    "implements_clause" => {
+                        let mut cursor = clause.walk();
+                        for interface in clause.named_children(&mut cursor) {
+                            interfaces.push(interface.utf8_text(source.as_bytes())?.to_string());
+                        }
+                    }
+                    // JavaScript: `extends <expression>` without a clause node
+                    _ => parent = Some(clause.utf8_text(source.as_bytes())?.to_string()),
+                }
+            }
+        }
+        
+        Ok((parent, interfaces))
+    }
+    
     /// Build AST from tree-sitter CST
     fn build_ast(&self, tree: &tree_sitter::Tree, source: &str) -> ASTNode {
         let mut root = ASTNode::new(ASTNodeType::FileRoot, String::new());
@@ -400,6 +661,14 @@ impl TreeSitterJavaScriptAnalyzer {
                 if let Ok(name) = name_field.utf8_text(source.as_bytes()) {
                     ast_node.name = name.to_string();
                 }
+            } else if matches!(node.kind(), "arrow_function" | "function_expression") {
+                if let Some(name) = self.resolve_assigned_name(node, source) {
+                    ast_node.name = name;
+                }
+            }
+            
+            if matches!(ast_type, ASTNodeType::Function | ASTNodeType::Method) && self.is_async_function(node, source) {
+                ast_node.attributes.insert("async".to_string(), "true".to_string());
             }
             
             parent.add_child(ast_node);
@@ -423,6 +692,32 @@ impl TreeSitterJavaScriptAnalyzer {
     }
 }
 
+/// Metadata flag on symbols that only declare a type (`.d.ts`, `declare`, signatures)
+fn mark_declaration(metadata: &mut HashMap<String, String>) {
+    metadata.insert("is_declaration".to_string(), "true".to_string());
+}
+
+/// Neither blank nor a comment
+fn is_code_line(line: &str) -> bool {
+    let trimmed = line.trim();
+    !trimmed.is_empty() && !trimmed.starts_with("//") && !trimmed.starts_with("/*") && !trimmed.starts_with('*')
+}
+
+/// Every node of one of `kinds` under `root`, in source order
+fn descendants_of_kind<'tree>(root: Node<'tree>, kinds: &[&str]) -> Vec<Node<'tree>> {
+    let mut found = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if kinds.contains(&node.kind()) {
+            found.push(node);
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.named_children(&mut cursor));
+    }
+    found.sort_by_key(|node| node.start_byte());
+    found
+}
+
 #[async_trait]
 impl LanguageAnalyzer for TreeSitterJavaScriptAnalyzer {
     fn get_language(&self) -> Language {
@@ -437,6 +732,10 @@ impl LanguageAnalyzer for TreeSitterJavaScriptAnalyzer {
         vec![".js", ".jsx", ".mjs", ".cjs", ".ts", ".tsx"]
     }
     
+    fn set_parse_timeout(&mut self, timeout: Option<std::time::Duration>) {
+        self.parser.set_timeout_micros(timeout.map_or(0, |timeout| timeout.as_micros() as u64));
+    }
+    
     async fn analyze(&mut self, content: &str, filename: &str) -> Result<AnalysisResult> {
         // Determine language and set appropriate grammar
         let language = if filename.ends_with(".tsx") {
@@ -446,6 +745,7 @@ impl LanguageAnalyzer for TreeSitterJavaScriptAnalyzer {
             self.set_typescript()?;
             Language::TypeScript
         } else {
+            self.set_javascript()?;
             Language::JavaScript
         };
         
@@ -456,13 +756,12 @@ impl LanguageAnalyzer for TreeSitterJavaScriptAnalyzer {
         
         // Calculate basic line statistics
         for line in content.lines() {
-            let trimmed = line.trim();
-            if trimmed.is_empty() {
+            if is_code_line(line) {
+                file_info.code_lines += 1;
+            } else if line.trim().is_empty() {
                 file_info.empty_lines += 1;
-            } else if trimmed.starts_with("//") || trimmed.starts_with("/*") || trimmed.starts_with("*") {
-                file_info.comment_lines += 1;
             } else {
-                file_info.code_lines += 1;
+                file_info.comment_lines += 1;
             }
         }
         
@@ -477,8 +776,14 @@ impl LanguageAnalyzer for TreeSitterJavaScriptAnalyzer {
         
         // 🚀 Parse with tree-sitter (ULTRA FAST!)
         let parse_start = std::time::Instant::now();
-        let tree = self.parser.parse(content, None)
-            .ok_or_else(|| anyhow::anyhow!("Failed to parse file"))?;
+        let tree = match self.parser.parse(content, None) {
+            Some(tree) => tree,
+            None => {
+                // A timed-out parse is kept and resumed by the next `parse`
+                self.parser.reset();
+                anyhow::bail!("Failed to parse file: parsing timed out");
+            }
+        };
         let parse_duration = parse_start.elapsed();
         
         if std::env::var("NEKOCODE_DEBUG").is_ok() {
@@ -492,6 +797,10 @@ impl LanguageAnalyzer for TreeSitterJavaScriptAnalyzer {
         result.imports = self.extract_imports(&tree, content)?;
         result.exports = self.extract_exports(&tree, content)?;
         result.function_calls = self.extract_function_calls(&tree, content)?;
+        if language == Language::TypeScript {
+            result.functions.extend(self.extract_function_signatures(&tree, content)?);
+            self.mark_declarations(&tree, content, filename.ends_with(".d.ts"), &mut result);
+        }
         let extract_duration = extract_start.elapsed();
         
         if std::env::var("NEKOCODE_DEBUG").is_ok() {
@@ -516,4 +825,183 @@ impl LanguageAnalyzer for TreeSitterJavaScriptAnalyzer {
         
         Ok(result)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[tokio::test]
+    async fn test_async_detection() {
+        let source = r#"
+function load() { return 1; }
+async function fetchData() { await load(); }
+class Api {
+    async request() {}
+    asyncLike() {}
+}
+"#;
+        let mut analyzer = TreeSitterJavaScriptAnalyzer::new().unwrap();
+        let result = analyzer.analyze(source, "api.js").await.unwrap();
+        
+        let is_async = |name: &str| result.functions.iter().find(|f| f.name == name).map(|f| f.is_async);
+        assert_eq!(is_async("load"), Some(false));
+        assert_eq!(is_async("fetchData"), Some(true));
+        
+        let class = result.classes.iter().find(|c| c.name == "Api").unwrap();
+        let method = |name: &str| class.methods.iter().find(|m| m.name == name).map(|m| m.is_async);
+        assert_eq!(method("request"), Some(true));
+        assert_eq!(method("asyncLike"), Some(false));
+        
+        let ast = result.ast_root.unwrap();
+        let fetch = ast.query_by_path("fetchData");
+        assert_eq!(fetch[0].attributes.get("async").map(String::as_str), Some("true"));
+    }
+    
+    #[tokio::test]
+    async fn test_assigned_function_names() {
+        let source = r#"
+const handler = () => {};
+export const foo = function() {};
+module.exports.bar = async () => {};
+const api = {
+    fetch: () => {},
+    save() {}
+};
+class Widget {
+    onClick = () => {};
+}
+"#;
+        let mut analyzer = TreeSitterJavaScriptAnalyzer::new().unwrap();
+        let result = analyzer.analyze(source, "handlers.js").await.unwrap();
+        
+        let names: Vec<&str> = result.functions.iter().map(|f| f.name.as_str()).collect();
+        for expected in ["handler", "foo", "bar", "fetch", "save", "onClick"] {
+            assert!(names.contains(&expected), "missing {} in {:?}", expected, names);
+        }
+        assert!(!names.contains(&""), "anonymous function left in {:?}", names);
+        
+        let bar = result.functions.iter().find(|f| f.name == "bar").unwrap();
+        assert!(bar.is_arrow_function);
+        assert!(bar.is_async);
+        
+        let ast = result.ast_root.unwrap();
+        assert!(!ast.query_by_path("handler").is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_method_calls_keep_receiver() {
+        let source = r#"
+helper();
+service.process(1);
+this.items.push(2);
+"#;
+        let mut analyzer = TreeSitterJavaScriptAnalyzer::new().unwrap();
+        let result = analyzer.analyze(source, "calls.js").await.unwrap();
+        
+        assert_eq!(result.function_calls.len(), 3);
+        let helper = &result.function_calls[0];
+        assert_eq!((helper.function_name.as_str(), helper.is_method_call, helper.line_number), ("helper", false, 2));
+        
+        let process = &result.function_calls[1];
+        assert_eq!(process.function_name, "process");
+        assert_eq!(process.object_name.as_deref(), Some("service"));
+        assert!(process.is_method_call);
+        
+        assert_eq!((process.column_start, process.column_end), (0, 18));
+        
+        assert_eq!(result.function_calls[2].object_name.as_deref(), Some("this.items"));
+    }
+    
+    #[tokio::test]
+    async fn test_export_extraction() {
+        let source = r#"
+export default function App() {}
+export const a = 1, b = 2;
+export class Store {}
+export { helper, util as tools };
+export { parse } from './parser';
+export * from './types';
+module.exports = { load, save: saveImpl };
+exports.version = '1.0';
+"#;
+        let mut analyzer = TreeSitterJavaScriptAnalyzer::new().unwrap();
+        let result = analyzer.analyze(source, "index.js").await.unwrap();
+        
+        let exports: Vec<_> = result.exports.iter()
+            .map(|e| (e.export_type, e.exported_names.join(","), e.is_default, e.line_number))
+            .collect();
+        assert_eq!(exports, vec![
+            (ExportType::ES6Default, "App".to_string(), true, 2),
+            (ExportType::ES6Export, "a,b".to_string(), false, 3),
+            (ExportType::ES6Export, "Store".to_string(), false, 4),
+            (ExportType::ES6Export, "helper,tools".to_string(), false, 5),
+            (ExportType::ES6Export, "parse".to_string(), false, 6),
+            (ExportType::ES6Export, "*".to_string(), false, 7),
+            (ExportType::CommonJSExports, "load,save".to_string(), false, 8),
+            (ExportType::CommonJSExports, "version".to_string(), false, 9),
+        ]);
+        assert_eq!(result.exports[4].source_module.as_deref(), Some("./parser"));
+        assert_eq!(result.exports[5].source_module.as_deref(), Some("./types"));
+        assert_eq!(result.exports[0].source_module, None);
+    }
+    
+    #[tokio::test]
+    async fn test_class_heritage() {
+        let mut analyzer = TreeSitterJavaScriptAnalyzer::new().unwrap();
+        let js = analyzer.analyze("class Square extends Shape {}\n", "square.js").await.unwrap();
+        assert_eq!(js.classes[0].parent_class.as_deref(), Some("Shape"));
+        
+        let source = "class Square extends Shape implements Drawable, Comparable<Square> {\n    area() { return 1; }\n}\n";
+        let ts = analyzer.analyze(source, "square.ts").await.unwrap();
+        let class = &ts.classes[0];
+        assert_eq!(class.name, "Square");
+        assert_eq!(class.parent_class.as_deref(), Some("Shape"));
+        assert_eq!(class.metadata.get("interfaces").map(String::as_str), Some("Drawable, Comparable<Square>"));
+        assert_eq!(class.methods[0].name, "area");
+    }
+    
+    #[tokio::test]
+    async fn test_declaration_files() {
+        let source = r#"// Type definitions
+export declare function parse(input: string): Tree;
+export declare class Parser {
+    parse(input: string): Tree;
+}
+declare module "lodash" {
+    export function chunk<T>(items: T[]): T[][];
+    const VERSION: string;
+}
+"#;
+        let mut analyzer = TreeSitterJavaScriptAnalyzer::new().unwrap();
+        let result = analyzer.analyze(source, "foo.d.ts").await.unwrap();
+        
+        let is_declaration = |metadata: &HashMap<String, String>| metadata.get("is_declaration").map(String::as_str) == Some("true");
+        let names: Vec<&str> = result.functions.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["parse", "chunk"]);
+        assert!(result.functions.iter().all(|f| is_declaration(&f.metadata)));
+        let class = &result.classes[0];
+        assert!(is_declaration(&class.metadata));
+        assert_eq!(class.methods[0].name, "parse");
+        assert!(is_declaration(&class.methods[0].metadata));
+        
+        assert_eq!(result.file_info.code_lines, 0);
+        assert_eq!(result.file_info.metadata.get("declaration_lines").map(String::as_str), Some("8"));
+        
+        let exports: Vec<_> = result.exports.iter()
+            .map(|e| (e.export_type, e.exported_names.join(","), e.source_module.as_deref()))
+            .collect();
+        assert_eq!(exports, vec![
+            (ExportType::ES6Export, "parse".to_string(), None),
+            (ExportType::ES6Export, "Parser".to_string(), None),
+            (ExportType::TsAmbientModule, "chunk,VERSION".to_string(), Some("lodash")),
+        ]);
+        
+        // In a regular module only the `declare` block is a declaration
+        let source = "declare function legacy(): void;\nexport function run() {\n    legacy();\n}\n";
+        let result = analyzer.analyze(source, "run.ts").await.unwrap();
+        let flagged: Vec<_> = result.functions.iter().map(|f| (f.name.as_str(), is_declaration(&f.metadata))).collect();
+        assert!(flagged.contains(&("legacy", true)) && flagged.contains(&("run", false)), "{:?}", flagged);
+        assert_eq!(result.file_info.code_lines, 3);
+    }
+}
@@ -0,0 +1,818 @@
+//! Kotlin analyzer implementation
+//!
+//! tree-sitter-kotlin is not among the grammar crates this project builds
+//! against, so declarations are found by scanning a copy of the source in
+//! which comments and string literals (with their `${...}` templates) have
+//! been blanked out. Byte offsets and line numbers are identical in both
+//! copies, which lets brace matching run on the masked text while names and
+//! types are read from it directly.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use regex::Regex;
+
+use crate::core::types::{
+    AnalysisResult, ClassInfo, ComplexityInfo, FileInfo, FunctionInfo, ImportInfo,
+    ImportType, Language
+};
+use crate::core::ast::{ASTNode, ASTNodeType, ASTStatistics};
+use crate::analyzers::traits::LanguageAnalyzer;
+
+/// Soft keywords that may precede `class`, `interface`, `object` or `fun`
+const MODIFIERS: &str = "public|private|protected|internal|abstract|open|final|sealed|data|enum|annotation|inner|value|inline|expect|actual|override|suspend|operator|infix|tailrec|external|lateinit|const";
+
+/// A class-like declaration and the byte range of its body
+struct ClassSpan {
+    start: usize,
+    end: usize,
+    body: Option<(usize, usize)>,
+}
+
+pub struct KotlinAnalyzer {
+    class_re: Regex,
+    function_re: Regex,
+    import_re: Regex,
+    package_re: Regex,
+}
+
+impl KotlinAnalyzer {
+    pub fn new() -> Self {
+        let annotations = r"(?:@[\w.:]+(?:\([^)\n]*\))?\s+)*";
+        Self {
+            class_re: Regex::new(&format!(
+                r"(?m)(?:^|[{{;])[ \t]*{}((?:(?:{}|fun)\s+)*)(class|interface|companion\s+object|object)\b(?:[ \t]+(\w+))?",
+                annotations, MODIFIERS
+            )).expect("valid class regex"),
+            function_re: Regex::new(&format!(
+                r"(?m)(?:^|[{{;])[ \t]*{}((?:(?:{})\s+)*)fun\s+(?:<[^>\n]*>\s*)?(?:([\w.<>?, ]+?)\.)?(\w+)\s*\(",
+                annotations, MODIFIERS
+            )).expect("valid function regex"),
+            import_re: Regex::new(r"(?m)^[ \t]*import\s+(\w+(?:\.\w+)*(?:\.\*)?)(?:\s+as\s+(\w+))?")
+                .expect("valid import regex"),
+            package_re: Regex::new(r"(?m)^[ \t]*package\s+([\w.]+)").expect("valid package regex"),
+        }
+    }
+
+    fn calculate_complexity(&self, masked: &str) -> ComplexityInfo {
+        let mut complexity = ComplexityInfo::new();
+
+        if let Ok(re) = Regex::new(r"\b(?:if|for|while|when|catch)\b|&&|\|\||\?:") {
+            complexity.cyclomatic_complexity = 1 + re.find_iter(masked).count() as u32;
+        }
+
+        let mut current_depth = 0;
+        let mut max_depth = 0;
+        for ch in masked.chars() {
+            match ch {
+                '{' => {
+                    current_depth += 1;
+                    max_depth = max_depth.max(current_depth);
+                }
+                '}' => current_depth = (current_depth - 1).max(0),
+                _ => {}
+            }
+        }
+
+        complexity.max_nesting_depth = max_depth as u32;
+        complexity.update_rating();
+        complexity
+    }
+
+    fn extract_classes(&self, masked: &str, lines: &LineIndex, package: Option<&str>) -> (Vec<ClassInfo>, Vec<ClassSpan>) {
+        let bytes = masked.as_bytes();
+        let mut classes = Vec::new();
+        let mut spans = Vec::new();
+
+        for caps in self.class_re.captures_iter(masked) {
+            let whole = caps.get(0).unwrap();
+            let modifiers: Vec<&str> = caps.get(1).map_or("", |m| m.as_str()).split_whitespace().collect();
+            let keyword = caps.get(2).unwrap().as_str();
+            let is_companion = keyword.starts_with("companion");
+
+            let name = match caps.get(3) {
+                Some(name) => name.as_str().to_string(),
+                None if is_companion => "Companion".to_string(),
+                None => continue,
+            };
+
+            let (header_end, body_open) = scan_header(bytes, whole.end());
+            let body = body_open.and_then(|open| matching_brace(bytes, open).map(|close| (open, close)));
+            let end = body.map_or(header_end, |(_, close)| close);
+
+            let start = declaration_start(whole.start(), whole.as_str());
+            let mut class = ClassInfo::new(name);
+            class.start_line = lines.line_of(start);
+            class.end_line = lines.line_of(end);
+
+            let kind = match keyword {
+                "interface" => "interface",
+                "object" => "object",
+                _ if is_companion => "companion_object",
+                _ if modifiers.contains(&"enum") => "enum",
+                _ if modifiers.contains(&"annotation") => "annotation",
+                _ => "class",
+            };
+            class.metadata.insert("type".to_string(), kind.to_string());
+            if kind == "interface" {
+                class.metadata.insert("is_interface".to_string(), "true".to_string());
+            }
+            if modifiers.contains(&"data") {
+                class.metadata.insert("is_data_class".to_string(), "true".to_string());
+            }
+            if is_companion {
+                class.metadata.insert("is_companion".to_string(), "true".to_string());
+            }
+            if !modifiers.is_empty() {
+                class.metadata.insert("modifiers".to_string(), modifiers.join(" "));
+            }
+            if let Some(package) = package {
+                class.metadata.insert("package".to_string(), package.to_string());
+            }
+
+            // `class Foo(val a: Int) : Base(a), Api by delegate`
+            let header = &masked[whole.end()..header_end];
+            if let Some(params) = primary_constructor(header) {
+                let open = whole.end() + params;
+                if let Some(close) = matching(bytes, open, b'(', b')') {
+                    for param in split_top_level(&masked[open + 1..close]) {
+                        let words: Vec<&str> = param.split_whitespace().collect();
+                        if let Some(pos) = words.iter().position(|w| *w == "val" || *w == "var") {
+                            if let Some(name) = words.get(pos + 1) {
+                                class.properties.push(name.trim_end_matches(':').to_string());
+                            }
+                        }
+                    }
+                }
+            }
+            let supertypes = supertypes(header);
+            class.parent_class = supertypes.iter()
+                .find(|s| s.contains('('))
+                .map(|s| s[..s.find('(').unwrap()].trim().to_string());
+            let supertypes: Vec<String> = supertypes.iter()
+                .map(|s| s.split('(').next().unwrap_or(s).trim().to_string())
+                .collect();
+            if !supertypes.is_empty() {
+                class.metadata.insert("supertypes".to_string(), supertypes.join(", "));
+            }
+            // Supertypes without a constructor call are interfaces
+            let interfaces: Vec<&str> = supertypes.iter()
+                .map(String::as_str)
+                .filter(|s| Some(*s) != class.parent_class.as_deref())
+                .collect();
+            if !interfaces.is_empty() {
+                class.metadata.insert("interfaces".to_string(), interfaces.join(", "));
+            }
+
+            classes.push(class);
+            spans.push(ClassSpan { start, end, body });
+        }
+
+        // Record which class each companion object belongs to
+        for index in 0..classes.len() {
+            if classes[index].metadata.contains_key("is_companion") {
+                if let Some(outer) = innermost_class(&spans, spans[index].start, Some(index)) {
+                    let (companion, outer_name) = (classes[index].name.clone(), classes[outer].name.clone());
+                    classes[index].metadata.insert("companion_of".to_string(), outer_name);
+                    classes[outer].metadata.insert("companion".to_string(), companion);
+                }
+            }
+        }
+
+        (classes, spans)
+    }
+
+    fn extract_functions(
+        &self,
+        masked: &str,
+        lines: &LineIndex,
+        classes: &mut [ClassInfo],
+        spans: &[ClassSpan],
+        package: Option<&str>,
+    ) -> Vec<(FunctionInfo, usize, usize)> {
+        let bytes = masked.as_bytes();
+        let mut functions = Vec::new();
+
+        for caps in self.function_re.captures_iter(masked) {
+            let whole = caps.get(0).unwrap();
+            let modifiers: Vec<&str> = caps.get(1).map_or("", |m| m.as_str()).split_whitespace().collect();
+            let name = caps.get(3).unwrap().as_str();
+
+            let open = whole.end() - 1;
+            let Some(close) = matching(bytes, open, b'(', b')') else { continue };
+            let (header_end, body_open) = scan_header(bytes, close + 1);
+
+            let end = match body_open {
+                Some(open) => matching_brace(bytes, open).unwrap_or(header_end),
+                None if bytes.get(header_end) == Some(&b'=') => expression_end(bytes, header_end + 1),
+                None => header_end,
+            };
+
+            let start = declaration_start(whole.start(), whole.as_str());
+            let mut func = FunctionInfo::new(name.to_string());
+            func.start_line = lines.line_of(start);
+            func.end_line = lines.line_of(end);
+            func.parameters = split_top_level(&masked[open + 1..close])
+                .iter()
+                .map(|param| clean_parameter(param))
+                .filter(|param| !param.is_empty())
+                .collect();
+            func.is_async = modifiers.contains(&"suspend");
+
+            let return_type = masked[close + 1..header_end].trim();
+            if let Some(return_type) = return_type.strip_prefix(':') {
+                let return_type = return_type.split(" where ").next().unwrap_or(return_type);
+                func.metadata.insert("return_type".to_string(), collapse_whitespace(return_type));
+            }
+            if let Some(receiver) = caps.get(2) {
+                func.metadata.insert("receiver".to_string(), receiver.as_str().trim().to_string());
+                func.metadata.insert("is_extension".to_string(), "true".to_string());
+            }
+            if !modifiers.is_empty() {
+                func.metadata.insert("modifiers".to_string(), modifiers.join(" "));
+            }
+            if modifiers.contains(&"override") {
+                func.metadata.insert("is_override".to_string(), "true".to_string());
+            }
+            if let Some(package) = package {
+                func.metadata.insert("package".to_string(), package.to_string());
+            }
+
+            if let Some(owner) = innermost_class(spans, start, None) {
+                func.metadata.insert("is_class_method".to_string(), "true".to_string());
+                func.metadata.insert("class".to_string(), classes[owner].name.clone());
+                // Companion members are Kotlin's answer to static methods
+                if classes[owner].metadata.contains_key("is_companion") {
+                    func.metadata.insert("is_companion".to_string(), "true".to_string());
+                    func.metadata.insert("is_static".to_string(), "true".to_string());
+                }
+                classes[owner].methods.push(func.clone());
+            }
+
+            functions.push((func, start, end));
+        }
+
+        functions
+    }
+
+    fn extract_imports(&self, masked: &str, lines: &LineIndex) -> Vec<ImportInfo> {
+        let mut imports = Vec::new();
+
+        for caps in self.import_re.captures_iter(masked) {
+            let whole = caps.get(0).unwrap();
+            let path = caps.get(1).unwrap().as_str();
+
+            let mut import = ImportInfo::new(ImportType::KotlinImport, path.to_string());
+            import.line_number = lines.line_of(whole.start() + leading_whitespace(whole.as_str()));
+            import.alias = caps.get(2).map(|alias| alias.as_str().to_string());
+            if !path.ends_with(".*") {
+                if let Some((_, name)) = path.rsplit_once('.') {
+                    import.imported_names.push(name.to_string());
+                }
+            }
+            imports.push(import);
+        }
+
+        imports
+    }
+
+    /// Nest classes and functions by their byte ranges, under a namespace
+    /// node for the package so scope paths read `com.example::Main::run`
+    fn build_ast(
+        &self,
+        classes: &[ClassInfo],
+        spans: &[ClassSpan],
+        functions: &[(FunctionInfo, usize, usize)],
+        package: Option<&str>,
+        total_lines: u32,
+    ) -> ASTNode {
+        // (start, end, node type, name, start line, end line)
+        let mut decls: Vec<(usize, usize, ASTNodeType, String, u32, u32)> = Vec::new();
+        for (class, span) in classes.iter().zip(spans) {
+            decls.push((span.start, span.end, ASTNodeType::Class, class.name.clone(), class.start_line, class.end_line));
+        }
+        for (func, start, end) in functions {
+            let node_type = if func.metadata.contains_key("is_class_method") {
+                ASTNodeType::Method
+            } else {
+                ASTNodeType::Function
+            };
+            decls.push((*start, *end, node_type, func.name.clone(), func.start_line, func.end_line));
+        }
+        decls.sort_by_key(|d| (d.0, std::cmp::Reverse(d.1)));
+
+        let mut children: Vec<Vec<usize>> = vec![Vec::new(); decls.len() + 1];
+        let mut stack: Vec<usize> = Vec::new();
+        for (index, decl) in decls.iter().enumerate() {
+            while stack.last().is_some_and(|&open| decls[open].1 < decl.0) {
+                stack.pop();
+            }
+            let parent = stack.last().map_or(decls.len(), |&open| open);
+            children[parent].push(index);
+            stack.push(index);
+        }
+
+        fn attach(parent: &mut ASTNode, index: usize, decls: &[(usize, usize, ASTNodeType, String, u32, u32)], children: &[Vec<usize>]) {
+            let (_, _, node_type, name, start_line, end_line) = &decls[index];
+            let mut node = ASTNode::new(*node_type, name.clone());
+            node.start_line = *start_line;
+            node.end_line = *end_line;
+            parent.add_child(node);
+
+            let last = parent.children.len() - 1;
+            for &child in &children[index] {
+                attach(&mut parent.children[last], child, decls, children);
+            }
+        }
+
+        let mut root = ASTNode::new(ASTNodeType::FileRoot, String::new());
+        let scope = match package {
+            Some(package) => {
+                let mut namespace = ASTNode::new(ASTNodeType::Namespace, package.to_string());
+                namespace.start_line = 1;
+                namespace.end_line = total_lines;
+                root.add_child(namespace);
+                root.children.last_mut().unwrap()
+            }
+            None => &mut root,
+        };
+        for &index in &children[decls.len()] {
+            attach(scope, index, &decls, &children);
+        }
+
+        root
+    }
+}
+
+#[async_trait]
+impl LanguageAnalyzer for KotlinAnalyzer {
+    fn get_language(&self) -> Language {
+        Language::Kotlin
+    }
+
+    fn get_language_name(&self) -> &'static str {
+        "Kotlin"
+    }
+
+    fn get_supported_extensions(&self) -> Vec<&'static str> {
+        vec![".kt", ".kts"]
+    }
+
+    async fn analyze(&mut self, content: &str, filename: &str) -> Result<AnalysisResult> {
+        let file_path = std::path::PathBuf::from(filename);
+        let mut file_info = FileInfo::new(file_path);
+        file_info.total_lines = content.lines().count() as u32;
+
+        for line in content.lines() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                file_info.empty_lines += 1;
+            } else if trimmed.starts_with("//") || trimmed.starts_with("/*") || trimmed.starts_with('*') {
+                file_info.comment_lines += 1;
+            } else {
+                file_info.code_lines += 1;
+            }
+        }
+
+        file_info.code_ratio = if file_info.total_lines > 0 {
+            file_info.code_lines as f64 / file_info.total_lines as f64
+        } else {
+            0.0
+        };
+
+        let mut result = AnalysisResult::new(file_info, Language::Kotlin);
+
+        let masked = mask_source(content)?;
+        let lines = LineIndex::new(&masked);
+        let package = self.package_re.captures(&masked).map(|caps| caps[1].to_string());
+
+        let (mut classes, spans) = self.extract_classes(&masked, &lines, package.as_deref());
+        let functions = self.extract_functions(&masked, &lines, &mut classes, &spans, package.as_deref());
+        result.imports = self.extract_imports(&masked, &lines);
+
+        let ast_root = self.build_ast(&classes, &spans, &functions, package.as_deref(), result.file_info.total_lines);
+        let mut ast_stats = ASTStatistics::default();
+        ast_stats.update_from_root(&ast_root);
+        result.ast_root = Some(ast_root);
+        result.ast_statistics = Some(ast_stats);
+
+        result.classes = classes;
+        result.functions = functions.into_iter().map(|(func, _, _)| func).collect();
+        result.complexity = self.calculate_complexity(&masked);
+
+        result.update_statistics();
+        Ok(result)
+    }
+}
+
+impl Default for KotlinAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Byte offset to 1-based line number lookup
+struct LineIndex {
+    starts: Vec<usize>,
+}
+
+impl LineIndex {
+    fn new(source: &str) -> Self {
+        let starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self { starts }
+    }
+
+    fn line_of(&self, offset: usize) -> u32 {
+        self.starts.partition_point(|&start| start <= offset) as u32
+    }
+}
+
+/// Replace comments and string/char literal contents with spaces, keeping
+/// newlines and quotes so offsets and line numbers stay valid
+fn mask_source(content: &str) -> Result<String> {
+    let bytes = content.as_bytes();
+    let mut out = bytes.to_vec();
+    let mut i = 0;
+
+    let blank = |out: &mut Vec<u8>, from: usize, to: usize| {
+        for b in &mut out[from..to] {
+            if *b != b'\n' {
+                *b = b' ';
+            }
+        }
+    };
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                let end = bytes[i..].iter().position(|&b| b == b'\n').map_or(bytes.len(), |p| i + p);
+                blank(&mut out, i, end);
+                i = end;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                // Kotlin block comments nest
+                let (mut depth, mut j) = (1, i + 2);
+                while j < bytes.len() && depth > 0 {
+                    if bytes[j..].starts_with(b"/*") {
+                        depth += 1;
+                        j += 2;
+                    } else if bytes[j..].starts_with(b"*/") {
+                        depth -= 1;
+                        j += 2;
+                    } else {
+                        j += 1;
+                    }
+                }
+                blank(&mut out, i, j);
+                i = j;
+            }
+            b'"' | b'\'' => {
+                // Template expressions are blanked with the rest of the string
+                let open = if bytes[i..].starts_with(b"\"\"\"") { 3 } else { 1 };
+                let (content_end, next) = literal_end(bytes, i);
+                blank(&mut out, i + open, content_end);
+                i = next;
+            }
+            _ => i += 1,
+        }
+    }
+
+    String::from_utf8(out).context("Masking a Kotlin source split a UTF-8 character")
+}
+
+/// Where the string or char literal opening at `start` ends: the index of its
+/// closing quote (or of the newline or input end for an unterminated one), and
+/// the index just past the literal. `${...}` template expressions are skipped
+/// as code, so quotes and braces inside them (`"${if (a) "x" else "y"}"`) do
+/// not end the string.
+fn literal_end(bytes: &[u8], start: usize) -> (usize, usize) {
+    let quote = bytes[start];
+    let raw = bytes[start..].starts_with(b"\"\"\"");
+    let mut j = start + if raw { 3 } else { 1 };
+    while j < bytes.len() {
+        if raw && bytes[j..].starts_with(b"\"\"\"") {
+            return (j, j + 3);
+        }
+        match bytes[j] {
+            b if !raw && b == quote => return (j, j + 1),
+            b'\n' if !raw => return (j, j),
+            b'\\' if !raw => j += 2,
+            b'$' if quote == b'"' && bytes.get(j + 1) == Some(&b'{') => j = template_end(bytes, j + 2),
+            _ => j += 1,
+        }
+    }
+    (bytes.len(), bytes.len())
+}
+
+/// Index just past the `}` closing a template expression whose body starts at `start`
+fn template_end(bytes: &[u8], start: usize) -> usize {
+    let mut depth = 1;
+    let mut j = start;
+    while j < bytes.len() {
+        match bytes[j] {
+            b'{' => {
+                depth += 1;
+                j += 1;
+            }
+            b'}' => {
+                depth -= 1;
+                j += 1;
+                if depth == 0 {
+                    return j;
+                }
+            }
+            b'"' | b'\'' => j = literal_end(bytes, j).1,
+            _ => j += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// Index of the bracket closing the one at `open`
+fn matching(bytes: &[u8], open: usize, open_byte: u8, close_byte: u8) -> Option<usize> {
+    let mut depth = 0;
+    for (i, &b) in bytes.iter().enumerate().skip(open) {
+        if b == open_byte {
+            depth += 1;
+        } else if b == close_byte {
+            depth -= 1;
+            if depth == 0 {
+                return Some(i);
+            }
+        }
+    }
+    None
+}
+
+fn matching_brace(bytes: &[u8], open: usize) -> Option<usize> {
+    matching(bytes, open, b'{', b'}')
+}
+
+/// Walk a declaration header (type parameters, constructor, supertypes,
+/// return type) until its body starts. Returns the end of the header and the
+/// offset of the body's `{`, if any. Headers may continue onto the next line
+/// when it starts with `:`, `,`, `{`, `=` or `where`.
+fn scan_header(bytes: &[u8], from: usize) -> (usize, Option<usize>) {
+    let mut depth = 0i32;
+    let mut i = from;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'(' | b'[' | b'<' => depth += 1,
+            b'>' if i > 0 && bytes[i - 1] == b'-' => {}
+            b')' | b']' | b'>' => depth -= 1,
+            b'{' if depth <= 0 => return (i, Some(i)),
+            b'=' | b';' | b'}' if depth <= 0 => return (i, None),
+            b'\n' if depth <= 0 => {
+                let before = bytes[from..i].iter().rev().find(|b| !b.is_ascii_whitespace());
+                let after = &bytes[i..];
+                let next = after.iter().position(|b| !b.is_ascii_whitespace()).map(|p| &after[p..]);
+                let continues = matches!(before, Some(b',' | b':'))
+                    || next.is_some_and(|n| {
+                        matches!(n.first(), Some(b':' | b',' | b'{' | b'='))
+                            || (n.starts_with(b"where") && !n.get(5).is_some_and(|b| b.is_ascii_alphanumeric()))
+                    });
+                if !continues {
+                    return (i, None);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    (bytes.len(), None)
+}
+
+/// End of an expression body: the first line break outside brackets once
+/// the expression has started
+fn expression_end(bytes: &[u8], from: usize) -> usize {
+    let mut depth = 0i32;
+    let mut started = false;
+
+    for (i, &b) in bytes.iter().enumerate().skip(from) {
+        match b {
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' => {
+                depth -= 1;
+                if depth < 0 {
+                    return i;
+                }
+            }
+            b'\n' if depth == 0 && started => return i,
+            b if !b.is_ascii_whitespace() => started = true,
+            _ => {}
+        }
+    }
+
+    bytes.len()
+}
+
+/// Split on commas that are not nested inside brackets
+fn split_top_level(text: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut current = String::new();
+    let mut previous = ' ';
+
+    for c in text.chars() {
+        match c {
+            '(' | '[' | '{' | '<' => depth += 1,
+            '>' if previous == '-' => {}
+            ')' | ']' | '}' | '>' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(std::mem::take(&mut current));
+                previous = c;
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+        previous = c;
+    }
+    parts.push(current);
+
+    parts.into_iter()
+        .map(|part| part.trim().to_string())
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+/// `@Ann vararg name: Type = default` -> `vararg name: Type`
+fn clean_parameter(param: &str) -> String {
+    let without_default = split_default(param);
+    let words: Vec<&str> = without_default
+        .split_whitespace()
+        .filter(|w| !w.starts_with('@'))
+        .filter(|w| !matches!(*w, "val" | "var" | "crossinline" | "noinline" | "private" | "protected" | "internal" | "public" | "override"))
+        .collect();
+    collapse_whitespace(&words.join(" "))
+}
+
+/// Drop a `= default` that is not part of a nested expression
+fn split_default(param: &str) -> &str {
+    let mut depth = 0i32;
+    for (i, c) in param.char_indices() {
+        match c {
+            '(' | '[' | '{' | '<' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            '>' if !param[..i].ends_with('-') => depth -= 1,
+            '=' if depth == 0 => return &param[..i],
+            _ => {}
+        }
+    }
+    param
+}
+
+/// Supertype entries after the `:` of a class header
+fn supertypes(header: &str) -> Vec<String> {
+    let mut depth = 0i32;
+    for (i, c) in header.char_indices() {
+        match c {
+            '(' | '<' => depth += 1,
+            ')' | '>' => depth -= 1,
+            ':' if depth == 0 => {
+                let list = &header[i + 1..];
+                let list = list.split(" where ").next().unwrap_or(list);
+                return split_top_level(list)
+                    .into_iter()
+                    .map(|s| collapse_whitespace(s.split(" by ").next().unwrap_or(&s)))
+                    .collect();
+            }
+            _ => {}
+        }
+    }
+    Vec::new()
+}
+
+/// Offset of the primary constructor's `(` in a class header, if it has one
+fn primary_constructor(header: &str) -> Option<usize> {
+    let mut depth = 0i32;
+    for (i, c) in header.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth -= 1,
+            '(' if depth == 0 => return Some(i),
+            ':' | '{' if depth == 0 => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Innermost class whose body contains `offset`, skipping `exclude`
+fn innermost_class(spans: &[ClassSpan], offset: usize, exclude: Option<usize>) -> Option<usize> {
+    spans.iter()
+        .enumerate()
+        .filter(|(index, _)| Some(*index) != exclude)
+        .filter(|(_, span)| span.body.is_some_and(|(open, close)| open < offset && offset < close))
+        .max_by_key(|(_, span)| span.start)
+        .map(|(index, _)| index)
+}
+
+/// Offset of a matched declaration past the `{` / `;` and indentation before it
+fn declaration_start(offset: usize, matched: &str) -> usize {
+    offset + matched.len() - matched.trim_start_matches(|c: char| c == '{' || c == ';' || c.is_whitespace()).len()
+}
+
+fn leading_whitespace(text: &str) -> usize {
+    text.len() - text.trim_start().len()
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_analyze_main_kt() {
+        let source = r#"package com.example.app
+
+import kotlinx.coroutines.launch
+import java.util.*
+import com.example.util.Logger as Log
+
+/* block comment with class Fake { */
+data class User(val id: Long, var name: String = "a, b") : Entity(id), Comparable<User> {
+    override fun compareTo(other: User): Int = id.compareTo(other.id)
+
+    companion object {
+        fun create(name: String): User {
+            return User(0, name)
+        }
+    }
+}
+
+interface Repository<T> {
+    suspend fun findAll(): List<T>
+}
+
+object Registry {
+    fun register(vararg users: User, onDone: (Int) -> Unit = {}) { }
+}
+
+fun String.shout(): String = uppercase() + "!"
+
+fun main(args: Array<String>) {
+    val s = "fun fake() {"
+    println("${if (args.isEmpty()) "}" else "{ ${args.size} }"} done")
+}
+
+class Point { fun norm(): Double = 0.0 }
+"#;
+        let mut analyzer = KotlinAnalyzer::new();
+        let result = analyzer.analyze(source, "Main.kt").await.unwrap();
+
+        let imports: Vec<_> = result.imports.iter().map(|i| (i.module_path.as_str(), i.line_number)).collect();
+        assert_eq!(imports, vec![
+            ("kotlinx.coroutines.launch", 3),
+            ("java.util.*", 4),
+            ("com.example.util.Logger", 5),
+        ]);
+        assert_eq!(result.imports[2].alias.as_deref(), Some("Log"));
+        assert_eq!(result.imports[0].imported_names, vec!["launch"]);
+
+        let classes: Vec<_> = result.classes.iter()
+            .map(|c| (c.name.as_str(), c.metadata.get("type").unwrap().as_str(), c.start_line, c.end_line))
+            .collect();
+        assert_eq!(classes, vec![
+            ("User", "class", 8, 16),
+            ("Companion", "companion_object", 11, 15),
+            ("Repository", "interface", 18, 20),
+            ("Registry", "object", 22, 24),
+            ("Point", "class", 33, 33),
+        ]);
+
+        let user = &result.classes[0];
+        assert_eq!(user.metadata.get("is_data_class").map(String::as_str), Some("true"));
+        assert_eq!(user.metadata.get("companion").map(String::as_str), Some("Companion"));
+        assert_eq!(user.parent_class.as_deref(), Some("Entity"));
+        assert_eq!(user.metadata.get("supertypes").map(String::as_str), Some("Entity, Comparable<User>"));
+        assert_eq!(user.metadata.get("interfaces").map(String::as_str), Some("Comparable<User>"));
+        assert_eq!(user.properties, vec!["id", "name"]);
+        assert_eq!(result.classes[1].metadata.get("companion_of").map(String::as_str), Some("User"));
+
+        let functions: Vec<_> = result.functions.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(functions, vec!["compareTo", "create", "findAll", "register", "shout", "main", "norm"]);
+
+        let function = |name: &str| result.functions.iter().find(|f| f.name == name).unwrap();
+        assert_eq!(function("compareTo").parameters, vec!["other: User"]);
+        assert_eq!(function("compareTo").metadata.get("return_type").map(String::as_str), Some("Int"));
+        assert_eq!(function("create").metadata.get("is_companion").map(String::as_str), Some("true"));
+        assert_eq!((function("create").start_line, function("create").end_line), (12, 14));
+        assert!(function("findAll").is_async);
+        assert_eq!(function("findAll").metadata.get("return_type").map(String::as_str), Some("List<T>"));
+        assert_eq!(function("register").parameters, vec!["vararg users: User", "onDone: (Int) -> Unit"]);
+        assert_eq!(function("shout").metadata.get("receiver").map(String::as_str), Some("String"));
+        assert!(!function("main").metadata.contains_key("is_class_method"));
+        assert_eq!(function("norm").metadata.get("class").map(String::as_str), Some("Point"));
+
+        let ast = result.ast_root.unwrap();
+        let matches = ast.query_by_path_with("com.example.app::User::Companion::create", false).unwrap();
+        assert_eq!(matches.len(), 1);
+    }
+}
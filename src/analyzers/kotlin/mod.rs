@@ -0,0 +1,3 @@
+pub mod analyzer;
+
+pub use analyzer::KotlinAnalyzer;
@@ -1,7 +1,10 @@
 pub mod traits;
+pub mod pool;
 pub mod javascript;
 pub mod python;
 pub mod cpp;
 pub mod csharp;
 pub mod go;
-pub mod rust;
\ No newline at end of file
+pub mod rust;
+pub mod java;
+pub mod kotlin;
\ No newline at end of file
@@ -0,0 +1,141 @@
+//! Reuse of analyzers and compiled queries across files
+//!
+//! Creating a tree-sitter analyzer sets up a `Parser`, and every extraction
+//! pass needs a compiled `Query`; both are costly compared to analyzing a
+//! small file. Compiled queries are cached process-wide by grammar and query
+//! text, since a `Query` is immutable and can be shared between threads.
+//! Analyzers hold a mutable parser, so each one is checked out of the pool by
+//! a single analysis and returned when it is dropped; concurrent analyses of
+//! the same language get separate analyzers, and the pool grows to the
+//! number analyzed at once.
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex, OnceLock};
+use tree_sitter::Query;
+
+use crate::analyzers::traits::LanguageAnalyzer;
+use crate::core::types::Language;
+
+type QueryKey = (tree_sitter::Language, String);
+/// Idle analyzers by language
+type AnalyzerPool = Mutex<HashMap<Language, Vec<Box<dyn LanguageAnalyzer>>>>;
+
+static QUERIES: OnceLock<Mutex<HashMap<QueryKey, Arc<Query>>>> = OnceLock::new();
+static ANALYZERS: OnceLock<AnalyzerPool> = OnceLock::new();
+
+/// `source` compiled for `language`, compiling it on first use
+pub fn cached_query(language: &tree_sitter::Language, source: &str) -> Result<Arc<Query>> {
+    let queries = QUERIES.get_or_init(Default::default);
+    let key = (language.clone(), source.to_string());
+    if let Some(query) = queries.lock().unwrap_or_else(|e| e.into_inner()).get(&key) {
+        return Ok(query.clone());
+    }
+
+    // Compile outside the lock; two threads racing on a new query both
+    // compile it and the first to finish wins
+    let query = Arc::new(Query::new(language, source)?);
+    let mut queries = queries.lock().unwrap_or_else(|e| e.into_inner());
+    Ok(queries.entry(key).or_insert(query).clone())
+}
+
+/// An analyzer borrowed from the pool, returned to it on drop
+pub struct PooledAnalyzer {
+    key: Language,
+    analyzer: Option<Box<dyn LanguageAnalyzer>>,
+}
+
+impl Deref for PooledAnalyzer {
+    type Target = dyn LanguageAnalyzer;
+
+    fn deref(&self) -> &Self::Target {
+        self.analyzer.as_deref().expect("analyzer is present until dropped")
+    }
+}
+
+impl DerefMut for PooledAnalyzer {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.analyzer.as_deref_mut().expect("analyzer is present until dropped")
+    }
+}
+
+impl Drop for PooledAnalyzer {
+    fn drop(&mut self) {
+        // A panic may have left the parser mid-parse; let it go instead
+        if std::thread::panicking() {
+            return;
+        }
+        if let (Some(analyzer), Some(pool)) = (self.analyzer.take(), ANALYZERS.get()) {
+            pool.lock().unwrap_or_else(|e| e.into_inner()).entry(self.key).or_default().push(analyzer);
+        }
+    }
+}
+
+/// Take an idle analyzer for `language` from the pool, creating one when
+/// none is free; `None` for languages without an analyzer
+pub fn checkout(language: Language) -> Result<Option<PooledAnalyzer>> {
+    // TypeScript files are handled by the JavaScript analyzer
+    let key = match language {
+        Language::TypeScript => Language::JavaScript,
+        other => other,
+    };
+
+    let pool = ANALYZERS.get_or_init(Default::default);
+    let idle = pool.lock().unwrap_or_else(|e| e.into_inner()).get_mut(&key).and_then(Vec::pop);
+    let analyzer = match idle {
+        Some(analyzer) => analyzer,
+        None => match create(key)? {
+            Some(analyzer) => analyzer,
+            None => return Ok(None),
+        },
+    };
+    Ok(Some(PooledAnalyzer { key, analyzer: Some(analyzer) }))
+}
+
+fn create(language: Language) -> Result<Option<Box<dyn LanguageAnalyzer>>> {
+    use crate::analyzers::cpp::TreeSitterCppAnalyzer;
+    use crate::analyzers::csharp::TreeSitterCSharpAnalyzer;
+    use crate::analyzers::go::TreeSitterGoAnalyzer;
+    use crate::analyzers::java::TreeSitterJavaAnalyzer;
+    use crate::analyzers::javascript::TreeSitterJavaScriptAnalyzer;
+    use crate::analyzers::kotlin::KotlinAnalyzer;
+    use crate::analyzers::python::TreeSitterPythonAnalyzer;
+    use crate::analyzers::rust::TreeSitterRustAnalyzer;
+
+    let analyzer: Box<dyn LanguageAnalyzer> = match language {
+        Language::JavaScript => Box::new(TreeSitterJavaScriptAnalyzer::new().context("Failed to create tree-sitter JavaScript analyzer")?),
+        Language::Python => Box::new(TreeSitterPythonAnalyzer::new().context("Failed to create tree-sitter Python analyzer")?),
+        Language::Cpp => Box::new(TreeSitterCppAnalyzer::new().context("Failed to create tree-sitter C++ analyzer")?),
+        Language::CSharp => Box::new(TreeSitterCSharpAnalyzer::new().context("Failed to create tree-sitter C# analyzer")?),
+        Language::Go => Box::new(TreeSitterGoAnalyzer::new().context("Failed to create tree-sitter Go analyzer")?),
+        Language::Rust => Box::new(TreeSitterRustAnalyzer::new().context("Failed to create tree-sitter Rust analyzer")?),
+        Language::Java => Box::new(TreeSitterJavaAnalyzer::new().context("Failed to create tree-sitter Java analyzer")?),
+        Language::Kotlin => Box::new(KotlinAnalyzer::new()),
+        _ => return Ok(None),
+    };
+    Ok(Some(analyzer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_pooled_analyzers_are_reused() {
+        let query = cached_query(&tree_sitter_python::LANGUAGE.into(), "(function_definition) @f").unwrap();
+        let again = cached_query(&tree_sitter_python::LANGUAGE.into(), "(function_definition) @f").unwrap();
+        assert!(Arc::ptr_eq(&query, &again));
+
+        // A TypeScript file must not leave the shared analyzer on the TypeScript grammar
+        let mut analyzer = checkout(Language::TypeScript).unwrap().unwrap();
+        let ts = analyzer.analyze("function add(a: number): number { return a }\n", "add.ts").await.unwrap();
+        assert_eq!(ts.functions[0].name, "add");
+        drop(analyzer);
+        let mut analyzer = checkout(Language::JavaScript).unwrap().unwrap();
+        let js = analyzer.analyze("const f = (a) => a;\nfunction g() {}\n", "g.js").await.unwrap();
+        assert!(js.functions.iter().any(|f| f.name == "g"));
+
+        assert!(checkout(Language::Unknown).unwrap().is_none());
+    }
+}
@@ -2,14 +2,15 @@
 //! 100x faster than PEST implementation!
 
 use anyhow::Result;
-use tree_sitter::{Parser, Query, QueryCursor, Node};
+use tree_sitter::{Parser, QueryCursor, Node};
 use async_trait::async_trait;
 
 use crate::core::types::{
-    AnalysisResult, ClassInfo, FileInfo, FunctionInfo, ImportInfo, 
+    AnalysisResult, ClassInfo, ExportInfo, ExportType, FileInfo, FunctionInfo, ImportInfo, 
     Language, ComplexityInfo, ImportType
 };
 use crate::core::ast::{ASTNode, ASTNodeType, ASTStatistics};
+use crate::analyzers::pool::cached_query;
 use crate::analyzers::traits::LanguageAnalyzer;
 
 pub struct TreeSitterPythonAnalyzer {
@@ -37,7 +38,7 @@ impl TreeSitterPythonAnalyzer {
             ]
         "#;
         
-        let query = Query::new(&tree_sitter_python::LANGUAGE.into(), query_str)?;
+        let query = cached_query(&tree_sitter_python::LANGUAGE.into(), query_str)?;
         let mut cursor = QueryCursor::new();
         let matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
         
@@ -52,22 +53,10 @@ impl TreeSitterPythonAnalyzer {
                 if func_node.kind() == "lambda" {
                     func_info.name = "lambda".to_string();
                     func_info.metadata.insert("is_lambda".to_string(), "true".to_string());
+                    func_info.parameters = self.extract_parameters(func_node, source)?;
                 } else {
-                    // Try to extract function name from the node structure
-                    let mut node_cursor = func_node.walk();
-                    for child in func_node.children(&mut node_cursor) {
-                        if child.kind() == "identifier" && func_info.name.is_empty() {
-                            if let Ok(name) = child.utf8_text(source.as_bytes()) {
-                                func_info.name = name.to_string();
-                                break;
-                            }
-                        }
-                    }
+                    func_info = self.function_info(func_node, source)?;
                 }
-                
-                // Extract parameters
-                func_info.parameters = self.extract_parameters(func_node, source)?;
-                func_info.is_async = self.is_async_function(func_node, source);
             }
             
             // Set default complexity
@@ -87,7 +76,7 @@ impl TreeSitterPythonAnalyzer {
             (class_definition) @class
         "#;
         
-        let query = Query::new(&tree_sitter_python::LANGUAGE.into(), query_str)?;
+        let query = cached_query(&tree_sitter_python::LANGUAGE.into(), query_str)?;
         let mut cursor = QueryCursor::new();
         let matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
         
@@ -112,6 +101,17 @@ impl TreeSitterPythonAnalyzer {
                     }
                 }
                 
+                // `class Sq(Base, Shape)`: the first base is the parent, the rest
+                // (mixins, ABCs, protocols) are listed as interfaces
+                if let Some(bases) = class_node.child_by_field_name("superclasses") {
+                    let mut superclasses = self.extract_superclasses(bases, source)?.into_iter();
+                    class_info.parent_class = superclasses.next();
+                    let interfaces: Vec<String> = superclasses.collect();
+                    if !interfaces.is_empty() {
+                        class_info.metadata.insert("interfaces".to_string(), interfaces.join(", "));
+                    }
+                }
+                
                 // Extract methods
                 class_info.methods = self.extract_class_methods(class_node, source)?;
             }
@@ -134,42 +134,68 @@ impl TreeSitterPythonAnalyzer {
             ]
         "#;
         
-        let query = Query::new(&tree_sitter_python::LANGUAGE.into(), query_str)?;
+        let query = cached_query(&tree_sitter_python::LANGUAGE.into(), query_str)?;
         let mut cursor = QueryCursor::new();
         let matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
         
         for mat in matches {
-            let mut import_info = ImportInfo::new(ImportType::PythonImport, String::new());
-            
             for capture in mat.captures {
+                let node = capture.node;
+                let line_number = node.start_position().row as u32 + 1;
+                let mut cursor = node.walk();
+                let names: Vec<Node> = node.children_by_field_name("name", &mut cursor).collect();
+                
                 match query.capture_names()[capture.index as usize].as_ref() {
+                    // `from ..pkg.core import run as start` → module `..pkg.core`, names [`run`]
                     "from_import" => {
-                        import_info.import_type = ImportType::PythonFromImport;
-                        import_info.line_number = capture.node.start_position().row as u32 + 1;
-                        
-                        // Extract the whole import statement text
-                        if let Ok(import_text) = capture.node.utf8_text(source.as_bytes()) {
-                            import_info.module_path = import_text.to_string();
+                        let module = node.child_by_field_name("module_name")
+                            .and_then(|m| m.utf8_text(source.as_bytes()).ok())
+                            .unwrap_or("");
+                        let mut import_info = ImportInfo::new(ImportType::PythonFromImport, module.to_string());
+                        import_info.line_number = line_number;
+                        let single = names.len() == 1;
+                        for name in names {
+                            let (name, alias) = Self::import_name_and_alias(name, source);
+                            if single {
+                                import_info.alias = alias;
+                            }
+                            import_info.imported_names.push(name);
+                        }
+                        let mut children = node.walk();
+                        if node.children(&mut children).any(|child| child.kind() == "wildcard_import") {
+                            import_info.imported_names.push("*".to_string());
                         }
+                        imports.push(import_info);
                     }
+                    // `import os, numpy as np` → one import per module
                     "import" => {
-                        import_info.line_number = capture.node.start_position().row as u32 + 1;
-                        
-                        // Extract the whole import statement text
-                        if let Ok(import_text) = capture.node.utf8_text(source.as_bytes()) {
-                            import_info.module_path = import_text.to_string();
+                        for name in names {
+                            let (module, alias) = Self::import_name_and_alias(name, source);
+                            let mut import_info = ImportInfo::new(ImportType::PythonImport, module);
+                            import_info.line_number = line_number;
+                            import_info.alias = alias;
+                            imports.push(import_info);
                         }
                     }
                     _ => {}
                 }
             }
-            
-            imports.push(import_info);
         }
         
         Ok(imports)
     }
     
+    /// `pkg.core as core` → (`pkg.core`, Some(`core`)); plain dotted names have no alias
+    fn import_name_and_alias(node: Node, source: &str) -> (String, Option<String>) {
+        let text = |n: Node| n.utf8_text(source.as_bytes()).unwrap_or("").to_string();
+        if node.kind() == "aliased_import" {
+            let name = node.child_by_field_name("name").map(text).unwrap_or_default();
+            (name, node.child_by_field_name("alias").map(text))
+        } else {
+            (text(node), None)
+        }
+    }
+    
     /// Helper: Extract parameters from a function node
     fn extract_parameters(&self, node: Node, source: &str) -> Result<Vec<String>> {
         let mut params = Vec::new();
@@ -184,13 +210,14 @@ impl TreeSitterPythonAnalyzer {
                         }
                     }
                     "typed_parameter" => {
-                        if let Some(name_child) = child.child_by_field_name("name") {
+                        // The grammar gives typed parameters no `name` field
+                        if let Some(name_child) = child.named_child(0) {
                             if let Ok(param_text) = name_child.utf8_text(source.as_bytes()) {
                                 params.push(param_text.to_string());
                             }
                         }
                     }
-                    "default_parameter" => {
+                    "default_parameter" | "typed_default_parameter" => {
                         if let Some(name_child) = child.child_by_field_name("name") {
                             if let Ok(param_text) = name_child.utf8_text(source.as_bytes()) {
                                 params.push(param_text.to_string());
@@ -207,13 +234,10 @@ impl TreeSitterPythonAnalyzer {
     
     /// Helper: Check if function is async
     fn is_async_function(&self, node: Node, _source: &str) -> bool {
-        // Check if there's an async keyword before the function
-        if let Some(prev_sibling) = node.prev_sibling() {
-            if prev_sibling.kind() == "async" {
-                return true;
-            }
-        }
-        false
+        // `async def` is parsed as a function_definition with a leading `async` token
+        let mut cursor = node.walk();
+        let is_async = node.children(&mut cursor).any(|child| child.kind() == "async");
+        is_async
     }
     
     /// Extract superclasses from argument list
@@ -232,44 +256,108 @@ impl TreeSitterPythonAnalyzer {
         Ok(superclasses)
     }
     
-    /// Extract methods from a class node
+    /// Name, parameters, line range, return type and decorators of a
+    /// `function_definition`; the lines cover the definition itself, not its
+    /// decorators
+    fn function_info(&self, node: Node, source: &str) -> Result<FunctionInfo> {
+        let name = match node.child_by_field_name("name") {
+            Some(name_node) => name_node.utf8_text(source.as_bytes())?.to_string(),
+            None => String::new(),
+        };
+        let mut function = FunctionInfo::new(name);
+        function.start_line = node.start_position().row as u32 + 1;
+        function.end_line = node.end_position().row as u32 + 1;
+        function.parameters = self.extract_parameters(node, source)?;
+        function.is_async = self.is_async_function(node, source);
+        if let Some(return_type) = Self::return_type(node, source) {
+            function.metadata.insert("return_type".to_string(), return_type);
+        }
+        if let Some(parent) = node.parent() {
+            function.metadata.extend(self.extract_decorators(parent, source)?);
+        }
+        Ok(function)
+    }
+    
+    /// Extract methods from a class node, including decorated ones
     fn extract_class_methods(&self, class_node: Node, source: &str) -> Result<Vec<FunctionInfo>> {
         let mut methods = Vec::new();
         
         if let Some(body) = class_node.child_by_field_name("body") {
             let mut cursor = body.walk();
             for child in body.children(&mut cursor) {
-                if child.kind() == "function_definition" {
-                    let mut method = FunctionInfo::new(String::new());
-                    
-                    if let Some(name_node) = child.child_by_field_name("name") {
-                        method.name = name_node.utf8_text(source.as_bytes())?.to_string();
-                    }
-                    
-                    method.start_line = child.start_position().row as u32 + 1;
-                    method.end_line = child.end_position().row as u32 + 1;
-                    method.parameters = self.extract_parameters(child, source)?;
-                    method.is_async = self.is_async_function(child, source);
-                    method.metadata.insert("is_class_method".to_string(), "true".to_string());
-                    
-                    // Check for special methods
-                    if method.name.starts_with("__") && method.name.ends_with("__") {
-                        method.metadata.insert("is_dunder_method".to_string(), "true".to_string());
-                    }
-                    
-                    // Check for decorators
-                    if let Some(parent) = child.parent() {
-                        method.metadata.extend(self.extract_decorators(parent, source)?);
+                let definition = match child.kind() {
+                    "decorated_definition" => child.child_by_field_name("definition"),
+                    _ => Some(child),
+                };
+                let Some(definition) = definition.filter(|d| d.kind() == "function_definition") else {
+                    continue;
+                };
+                
+                let mut method = self.function_info(definition, source)?;
+                method.metadata.insert("is_class_method".to_string(), "true".to_string());
+                
+                // Check for special methods
+                if method.name.starts_with("__") && method.name.ends_with("__") {
+                    method.metadata.insert("is_dunder_method".to_string(), "true".to_string());
+                }
+                
+                let decorators = method.metadata.get("decorators").cloned().unwrap_or_default();
+                for (decorator, key) in [("@staticmethod", "is_static"), ("@classmethod", "is_classmethod"), ("@property", "is_property")] {
+                    if decorators.split(", ").any(|d| d == decorator) {
+                        method.metadata.insert(key.to_string(), "true".to_string());
                     }
-                    
-                    methods.push(method);
                 }
+                
+                methods.push(method);
             }
         }
         
         Ok(methods)
     }
     
+    /// Return annotation of a function (`def f() -> int`)
+    fn return_type(node: Node, source: &str) -> Option<String> {
+        node.child_by_field_name("return_type")
+            .and_then(|ret| ret.utf8_text(source.as_bytes()).ok())
+            .map(str::to_string)
+    }
+    
+    /// Names listed in a module-level `__all__`, the module's explicit public API
+    fn extract_exports(&self, tree: &tree_sitter::Tree, source: &str) -> Result<Vec<ExportInfo>> {
+        let mut exports = Vec::new();
+        
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        for statement in root.named_children(&mut cursor) {
+            if statement.kind() != "expression_statement" {
+                continue;
+            }
+            let Some(assignment) = statement.named_child(0) else { continue };
+            if !matches!(assignment.kind(), "assignment" | "augmented_assignment") {
+                continue;
+            }
+            let (Some(left), Some(right)) = (assignment.child_by_field_name("left"), assignment.child_by_field_name("right")) else {
+                continue;
+            };
+            if left.utf8_text(source.as_bytes())? != "__all__" || !matches!(right.kind(), "list" | "tuple") {
+                continue;
+            }
+            
+            let mut export = ExportInfo::new(ExportType::PythonGlobal);
+            let mut item_cursor = right.walk();
+            for item in right.named_children(&mut item_cursor) {
+                if item.kind() == "string" {
+                    let name = item.utf8_text(source.as_bytes())?.trim_matches(|c| c == '"' || c == '\'');
+                    export.exported_names.push(name.to_string());
+                }
+            }
+            export.line_number = statement.start_position().row as u32 + 1;
+            exports.push(export);
+        }
+        
+        Ok(exports)
+    }
+    
     /// Extract decorators from a decorated definition
     fn extract_decorators(&self, node: Node, source: &str) -> Result<std::collections::HashMap<String, String>> {
         let mut metadata = std::collections::HashMap::new();
@@ -353,6 +441,10 @@ impl TreeSitterPythonAnalyzer {
                 }
             }
             
+            if node.kind() == "function_definition" && self.is_async_function(node, source) {
+                ast_node.attributes.insert("async".to_string(), "true".to_string());
+            }
+            
             parent.add_child(ast_node);
             
             // Use the newly created node as parent for its children
@@ -388,6 +480,10 @@ impl LanguageAnalyzer for TreeSitterPythonAnalyzer {
         vec![".py", ".pyw", ".pyi"]
     }
     
+    fn set_parse_timeout(&mut self, timeout: Option<std::time::Duration>) {
+        self.parser.set_timeout_micros(timeout.map_or(0, |timeout| timeout.as_micros() as u64));
+    }
+    
     async fn analyze(&mut self, content: &str, filename: &str) -> Result<AnalysisResult> {
         // Create file info
         let file_path = std::path::PathBuf::from(filename);
@@ -417,8 +513,14 @@ impl LanguageAnalyzer for TreeSitterPythonAnalyzer {
         
         // 🚀 Parse with tree-sitter (ULTRA FAST!)
         let parse_start = std::time::Instant::now();
-        let tree = self.parser.parse(content, None)
-            .ok_or_else(|| anyhow::anyhow!("Failed to parse Python file"))?;
+        let tree = match self.parser.parse(content, None) {
+            Some(tree) => tree,
+            None => {
+                // A timed-out parse is kept and resumed by the next `parse`
+                self.parser.reset();
+                anyhow::bail!("Failed to parse Python file: parsing timed out");
+            }
+        };
         let parse_duration = parse_start.elapsed();
         
         if std::env::var("NEKOCODE_DEBUG").is_ok() {
@@ -430,6 +532,7 @@ impl LanguageAnalyzer for TreeSitterPythonAnalyzer {
         result.functions = self.extract_functions(&tree, content)?;
         result.classes = self.extract_classes(&tree, content)?;
         result.imports = self.extract_imports(&tree, content)?;
+        result.exports = self.extract_exports(&tree, content)?;
         let extract_duration = extract_start.elapsed();
         
         if std::env::var("NEKOCODE_DEBUG").is_ok() {
@@ -454,4 +557,73 @@ impl LanguageAnalyzer for TreeSitterPythonAnalyzer {
         
         Ok(result)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[tokio::test]
+    async fn test_async_detection() {
+        let source = "def load():\n    return 1\n\nasync def fetch_data():\n    await load()\n";
+        let mut analyzer = TreeSitterPythonAnalyzer::new().unwrap();
+        let result = analyzer.analyze(source, "api.py").await.unwrap();
+        
+        let is_async = |name: &str| result.functions.iter().find(|f| f.name == name).map(|f| f.is_async);
+        assert_eq!(is_async("load"), Some(false));
+        assert_eq!(is_async("fetch_data"), Some(true));
+        
+        let ast = result.ast_root.unwrap();
+        let fetch = ast.query_by_path("fetch_data");
+        assert_eq!(fetch[0].attributes.get("async").map(String::as_str), Some("true"));
+    }
+    
+    #[tokio::test]
+    async fn test_dunder_all_exports() {
+        let source = "__all__ = ['load', \"Store\"]\n__all__ += ('VERSION',)\n\ndef load(path: str) -> dict:\n    return {}\n";
+        let mut analyzer = TreeSitterPythonAnalyzer::new().unwrap();
+        let result = analyzer.analyze(source, "store.py").await.unwrap();
+        
+        let names: Vec<_> = result.exports.iter().flat_map(|e| e.exported_names.iter().map(String::as_str)).collect();
+        assert_eq!(names, vec!["load", "Store", "VERSION"]);
+        assert_eq!(result.functions[0].parameters, vec!["path"]);
+        assert_eq!(result.functions[0].metadata.get("return_type").map(String::as_str), Some("dict"));
+    }
+    
+    #[tokio::test]
+    async fn test_import_fields() {
+        let source = "import os, numpy as np\nfrom ..core import run as start\nfrom . import helpers, models\nfrom pkg import *\n";
+        let mut analyzer = TreeSitterPythonAnalyzer::new().unwrap();
+        let result = analyzer.analyze(source, "pkg/sub/deep.py").await.unwrap();
+        
+        let imports: Vec<_> = result.imports.iter()
+            .map(|i| (i.module_path.as_str(), i.imported_names.join(","), i.alias.as_deref(), i.line_number))
+            .collect();
+        assert_eq!(imports, vec![
+            ("os", String::new(), None, 1),
+            ("numpy", String::new(), Some("np"), 1),
+            ("..core", "run".to_string(), Some("start"), 2),
+            (".", "helpers,models".to_string(), None, 3),
+            ("pkg", "*".to_string(), None, 4),
+        ]);
+    }
+    
+    #[tokio::test]
+    async fn test_class_methods_keep_parameters_and_lines() {
+        let source = "class Cart:\n    def add(self, item, qty: int = 1) -> None:\n        pass\n\n    @staticmethod\n    def empty(reason):\n        return None\n";
+        let mut analyzer = TreeSitterPythonAnalyzer::new().unwrap();
+        let result = analyzer.analyze(source, "cart.py").await.unwrap();
+        
+        let methods = &result.classes[0].methods;
+        assert_eq!(methods.len(), 2);
+        assert_eq!(methods[0].parameters, vec!["self", "item", "qty"]);
+        assert_eq!((methods[0].start_line, methods[0].end_line), (2, 3));
+        assert_eq!(methods[1].name, "empty");
+        assert_eq!((methods[1].start_line, methods[1].end_line), (6, 7));
+        assert_eq!(methods[1].metadata.get("is_static").map(String::as_str), Some("true"));
+        assert_eq!(methods[1].metadata.get("decorators").map(String::as_str), Some("@staticmethod"));
+        
+        let ast = result.ast_root.unwrap();
+        assert_eq!(ast.query_by_path("Cart::empty")[0].start_line, 6);
+    }
+}
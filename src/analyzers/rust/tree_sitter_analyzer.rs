@@ -2,14 +2,15 @@
 //! 100x faster than PEST implementation!
 
 use anyhow::Result;
-use tree_sitter::{Parser, Query, QueryCursor, Node};
+use tree_sitter::{Parser, QueryCursor, Node};
 use async_trait::async_trait;
 
 use crate::core::types::{
     AnalysisResult, ClassInfo, FileInfo, FunctionInfo, ImportInfo, 
-    Language, ComplexityInfo, ImportType
+    Language, ComplexityInfo, ImportType, ExportInfo, ExportType
 };
 use crate::core::ast::{ASTNode, ASTNodeType, ASTStatistics};
+use crate::analyzers::pool::cached_query;
 use crate::analyzers::traits::LanguageAnalyzer;
 
 pub struct TreeSitterRustAnalyzer {
@@ -38,7 +39,7 @@ impl TreeSitterRustAnalyzer {
             ]
         "#;
         
-        let query = Query::new(&tree_sitter_rust::LANGUAGE.into(), query_str)?;
+        let query = cached_query(&tree_sitter_rust::LANGUAGE.into(), query_str)?;
         let mut cursor = QueryCursor::new();
         let matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
         
@@ -103,7 +104,7 @@ impl TreeSitterRustAnalyzer {
             ]
         "#;
         
-        let query = Query::new(&tree_sitter_rust::LANGUAGE.into(), query_str)?;
+        let query = cached_query(&tree_sitter_rust::LANGUAGE.into(), query_str)?;
         let mut cursor = QueryCursor::new();
         let matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
         
@@ -156,6 +157,7 @@ impl TreeSitterRustAnalyzer {
                     if let Some(trait_node) = node.child_by_field_name("trait") {
                         if let Ok(trait_name) = trait_node.utf8_text(source.as_bytes()) {
                             class_info.metadata.insert("implementing_trait".to_string(), trait_name.to_string());
+                            class_info.metadata.insert("interfaces".to_string(), trait_name.to_string());
                         }
                     }
                 }
@@ -181,7 +183,7 @@ impl TreeSitterRustAnalyzer {
               argument: (scoped_identifier)? @simple) @use_decl
         "#;
         
-        let query = Query::new(&tree_sitter_rust::LANGUAGE.into(), query_str)?;
+        let query = cached_query(&tree_sitter_rust::LANGUAGE.into(), query_str)?;
         let mut cursor = QueryCursor::new();
         let matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
         
@@ -219,6 +221,104 @@ impl TreeSitterRustAnalyzer {
         Ok(imports)
     }
     
+    /// Extract `pub` items and `pub use` re-exports, including those inside inline modules
+    fn extract_exports(&self, tree: &tree_sitter::Tree, source: &str) -> Result<Vec<ExportInfo>> {
+        let mut exports = Vec::new();
+        self.collect_exports(tree.root_node(), source, &mut exports)?;
+        Ok(exports)
+    }
+    
+    fn collect_exports(&self, parent: Node, source: &str, exports: &mut Vec<ExportInfo>) -> Result<()> {
+        let mut cursor = parent.walk();
+        for item in parent.named_children(&mut cursor) {
+            // `pub fn` in an `impl` block is callable wherever the type is
+            if item.kind() == "impl_item" {
+                if let Some(body) = item.child_by_field_name("body") {
+                    self.collect_exports(body, source, exports)?;
+                }
+                continue;
+            }
+            
+            let is_item = matches!(
+                item.kind(),
+                "function_item" | "struct_item" | "enum_item" | "union_item" | "trait_item"
+                    | "const_item" | "static_item" | "type_item" | "mod_item" | "use_declaration"
+            );
+            if !is_item {
+                continue;
+            }
+            
+            let mut item_cursor = item.walk();
+            let visibility = item.children(&mut item_cursor)
+                .find(|child| child.kind() == "visibility_modifier")
+                .map(|v| v.utf8_text(source.as_bytes()))
+                .transpose()?;
+            
+            if let Some(visibility) = &visibility {
+                // Only a bare `pub` is part of the crate's public API
+                let restricted = *visibility != "pub";
+                let mut names = Vec::new();
+                let export_type = if item.kind() == "use_declaration" {
+                    if let Some(argument) = item.child_by_field_name("argument") {
+                        self.use_tree_names(argument, source, &mut names)?;
+                    }
+                    if restricted { ExportType::RustPubCrate } else { ExportType::RustReexport }
+                } else {
+                    if let Some(name) = item.child_by_field_name("name") {
+                        names.push(name.utf8_text(source.as_bytes())?.to_string());
+                    }
+                    if restricted { ExportType::RustPubCrate } else { ExportType::RustPub }
+                };
+                
+                let mut export = ExportInfo::new(export_type);
+                export.exported_names = names;
+                export.line_number = item.start_position().row as u32 + 1;
+                exports.push(export);
+            }
+            
+            // Items of a `mod foo { ... }` are reachable through the module path
+            // only while every enclosing module is `pub`
+            if item.kind() == "mod_item" && visibility == Some("pub") {
+                if let Some(body) = item.child_by_field_name("body") {
+                    self.collect_exports(body, source, exports)?;
+                }
+            }
+        }
+        Ok(())
+    }
+    
+    /// Names a `use` tree brings into scope: the last path segment, the alias
+    /// of an `as` clause, or `*` for a glob
+    fn use_tree_names(&self, node: Node, source: &str, names: &mut Vec<String>) -> Result<()> {
+        match node.kind() {
+            "identifier" => names.push(node.utf8_text(source.as_bytes())?.to_string()),
+            "scoped_identifier" => {
+                if let Some(name) = node.child_by_field_name("name") {
+                    names.push(name.utf8_text(source.as_bytes())?.to_string());
+                }
+            }
+            "use_as_clause" => {
+                if let Some(alias) = node.child_by_field_name("alias") {
+                    names.push(alias.utf8_text(source.as_bytes())?.to_string());
+                }
+            }
+            "use_wildcard" => names.push("*".to_string()),
+            "scoped_use_list" => {
+                if let Some(list) = node.child_by_field_name("list") {
+                    self.use_tree_names(list, source, names)?;
+                }
+            }
+            "use_list" => {
+                let mut cursor = node.walk();
+                for child in node.named_children(&mut cursor) {
+                    self.use_tree_names(child, source, names)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+    
     /// Helper: Extract parameters from a function node
     fn extract_parameters(&self, node: Node, source: &str) -> Result<Vec<String>> {
         let mut params = Vec::new();
@@ -268,11 +368,19 @@ impl TreeSitterRustAnalyzer {
     
     /// Helper: Check if function is async
     fn is_async_function(&self, node: Node, _source: &str) -> bool {
-        // Look for async keyword in the function signature
+        // Look for async keyword in the function signature; tree-sitter-rust
+        // nests it inside a `function_modifiers` node
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
-            if child.kind() == "async" {
-                return true;
+            match child.kind() {
+                "async" => return true,
+                "function_modifiers" => {
+                    let mut modifier_cursor = child.walk();
+                    if child.children(&mut modifier_cursor).any(|m| m.kind() == "async") {
+                        return true;
+                    }
+                }
+                _ => {}
             }
         }
         false
@@ -294,6 +402,14 @@ impl TreeSitterRustAnalyzer {
                 "unsafe" | "const" | "async" | "extern" => {
                     modifiers.push(child.kind().to_string());
                 }
+                "function_modifiers" => {
+                    let mut modifier_cursor = child.walk();
+                    for modifier in child.children(&mut modifier_cursor) {
+                        if let Ok(text) = modifier.utf8_text(source.as_bytes()) {
+                            modifiers.push(text.to_string());
+                        }
+                    }
+                }
                 _ => {}
             }
         }
@@ -302,6 +418,10 @@ impl TreeSitterRustAnalyzer {
             metadata.insert("modifiers".to_string(), modifiers.join(" "));
         }
         
+        if let Some(return_type) = node.child_by_field_name("return_type") {
+            metadata.insert("return_type".to_string(), return_type.utf8_text(source.as_bytes())?.to_string());
+        }
+        
         Ok(metadata)
     }
     
@@ -402,6 +522,10 @@ impl TreeSitterRustAnalyzer {
                 }
             }
             
+            if node.kind() == "function_item" && self.is_async_function(node, source) {
+                ast_node.attributes.insert("async".to_string(), "true".to_string());
+            }
+            
             parent.add_child(ast_node);
             
             // Use the newly created node as parent for its children
@@ -437,6 +561,10 @@ impl LanguageAnalyzer for TreeSitterRustAnalyzer {
         vec![".rs"]
     }
     
+    fn set_parse_timeout(&mut self, timeout: Option<std::time::Duration>) {
+        self.parser.set_timeout_micros(timeout.map_or(0, |timeout| timeout.as_micros() as u64));
+    }
+    
     async fn analyze(&mut self, content: &str, filename: &str) -> Result<AnalysisResult> {
         // Create file info
         let file_path = std::path::PathBuf::from(filename);
@@ -466,8 +594,14 @@ impl LanguageAnalyzer for TreeSitterRustAnalyzer {
         
         // 🚀 Parse with tree-sitter (ULTRA FAST!)
         let parse_start = std::time::Instant::now();
-        let tree = self.parser.parse(content, None)
-            .ok_or_else(|| anyhow::anyhow!("Failed to parse Rust file"))?;
+        let tree = match self.parser.parse(content, None) {
+            Some(tree) => tree,
+            None => {
+                // A timed-out parse is kept and resumed by the next `parse`
+                self.parser.reset();
+                anyhow::bail!("Failed to parse Rust file: parsing timed out");
+            }
+        };
         let parse_duration = parse_start.elapsed();
         
         if std::env::var("NEKOCODE_DEBUG").is_ok() {
@@ -479,6 +613,7 @@ impl LanguageAnalyzer for TreeSitterRustAnalyzer {
         result.functions = self.extract_functions(&tree, content)?;
         result.classes = self.extract_classes(&tree, content)?;
         result.imports = self.extract_imports(&tree, content)?;
+        result.exports = self.extract_exports(&tree, content)?;
         let extract_duration = extract_start.elapsed();
         
         if std::env::var("NEKOCODE_DEBUG").is_ok() {
@@ -503,4 +638,79 @@ impl LanguageAnalyzer for TreeSitterRustAnalyzer {
         
         Ok(result)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[tokio::test]
+    async fn test_async_detection() {
+        let source = "fn load() -> u32 { 1 }\n\npub async fn fetch_data() -> u32 { load() }\n";
+        let mut analyzer = TreeSitterRustAnalyzer::new().unwrap();
+        let result = analyzer.analyze(source, "api.rs").await.unwrap();
+        
+        let is_async = |name: &str| result.functions.iter().find(|f| f.name == name).map(|f| f.is_async);
+        assert_eq!(is_async("load"), Some(false));
+        assert_eq!(is_async("fetch_data"), Some(true));
+        
+        let fetch = result.functions.iter().find(|f| f.name == "fetch_data").unwrap();
+        assert_eq!(fetch.metadata.get("modifiers").map(String::as_str), Some("pub async"));
+        
+        let ast = result.ast_root.unwrap();
+        let node = ast.query_by_path("fetch_data");
+        assert_eq!(node[0].attributes.get("async").map(String::as_str), Some("true"));
+    }
+    
+    #[tokio::test]
+    async fn test_pub_items_are_exported() {
+        let source = r#"fn helper() {}
+
+pub fn api() { helper() }
+
+pub(crate) struct Config;
+
+pub use crate::model::{User, Group as Team};
+
+pub mod nested {
+    pub const LIMIT: u32 = 3;
+    enum Hidden {}
+}
+
+mod private {
+    pub fn internal() {}
+}
+
+pub(crate) mod shared {
+    pub fn crate_only() {}
+}
+
+pub struct Client;
+
+impl Client {
+    pub fn connect() {}
+    fn retry() {}
+}
+"#;
+        let mut analyzer = TreeSitterRustAnalyzer::new().unwrap();
+        let result = analyzer.analyze(source, "lib.rs").await.unwrap();
+        
+        let exports: Vec<_> = result.exports.iter()
+            .map(|e| (e.export_type, e.exported_names.join(","), e.line_number))
+            .collect();
+        assert_eq!(exports, vec![
+            (ExportType::RustPub, "api".to_string(), 3),
+            (ExportType::RustPubCrate, "Config".to_string(), 5),
+            (ExportType::RustReexport, "User,Team".to_string(), 7),
+            (ExportType::RustPub, "nested".to_string(), 9),
+            (ExportType::RustPub, "LIMIT".to_string(), 10),
+            (ExportType::RustPubCrate, "shared".to_string(), 18),
+            (ExportType::RustPub, "Client".to_string(), 22),
+            (ExportType::RustPub, "connect".to_string(), 25),
+        ]);
+        let names: Vec<_> = result.exports.iter().flat_map(|e| e.exported_names.iter()).collect();
+        for hidden in ["helper", "internal", "crate_only", "retry"] {
+            assert!(!names.iter().any(|n| *n == hidden), "{} is not public API", hidden);
+        }
+    }
+}
@@ -5,6 +5,7 @@
 
 use anyhow::Result;
 use async_trait::async_trait;
+use std::time::Duration;
 
 use crate::core::types::{AnalysisResult, Language};
 
@@ -23,6 +24,11 @@ pub trait LanguageAnalyzer: Send + Sync {
     /// Analyze source code content and return analysis results
     async fn analyze(&mut self, content: &str, filename: &str) -> Result<AnalysisResult>;
     
+    /// Make `analyze` fail once parsing has taken longer than `timeout`;
+    /// `None` removes the limit. Analyzers without a tree-sitter parser
+    /// have nothing to interrupt and ignore it.
+    fn set_parse_timeout(&mut self, _timeout: Option<Duration>) {}
+    
     /// Check if this analyzer can handle the given file extension
     fn can_analyze_extension(&self, extension: &str) -> bool {
         self.get_supported_extensions()
@@ -5,19 +5,19 @@
 use anyhow::Result;
 use crate::core::session::SessionManager;
 use crate::core::incremental::IncrementalSummary;
+use crate::core::output::to_json;
 use serde_json;
 
 /// Handle the session-update command
 pub async fn handle_session_update(
+    session_manager: &mut SessionManager,
     session_id: &str, 
     verbose: bool, 
-    dry_run: bool
+    dry_run: bool,
 ) -> Result<String> {
-    let mut session_manager = SessionManager::new()?;
-    
     if dry_run {
         // For dry run, just show what would be updated without making changes
-        return handle_dry_run(&mut session_manager, session_id).await;
+        return handle_dry_run(session_manager, session_id).await;
     }
     
     // Perform the actual incremental update
@@ -46,7 +46,7 @@ pub async fn handle_session_update(
             }
         });
         
-        Ok(serde_json::to_string_pretty(&detailed_output)?)
+        Ok(to_json(&detailed_output)?)
     } else {
         // Return simple summary
         Ok(summary.format_summary())
@@ -132,17 +132,24 @@ mod tests {
     use std::fs;
     use crate::core::session::SessionManager;
     
+    /// Session manager keeping its sessions in a temporary directory
+    fn temp_session_manager() -> (TempDir, SessionManager) {
+        let dir = TempDir::new().unwrap();
+        let manager = SessionManager::with_dir(dir.path().to_path_buf()).unwrap();
+        (dir, manager)
+    }
+    
     #[tokio::test]
     async fn test_session_update_no_changes() {
         let temp_dir = TempDir::new().unwrap();
         let test_file = temp_dir.path().join("test.js");
         fs::write(&test_file, "console.log('test');").unwrap();
         
-        let mut session_manager = SessionManager::new().unwrap();
+        let (_sessions, mut session_manager) = temp_session_manager();
         let session_id = session_manager.create_session(temp_dir.path()).await.unwrap();
         
         // Update without any changes
-        let result = handle_session_update(&session_id, false, false).await.unwrap();
+        let result = handle_session_update(&mut session_manager, &session_id, false, false).await.unwrap();
         
         assert!(result.contains("0 modified"));
         assert!(result.contains("0 added"));
@@ -155,14 +162,14 @@ mod tests {
         let test_file = temp_dir.path().join("test.js");
         fs::write(&test_file, "console.log('test');").unwrap();
         
-        let mut session_manager = SessionManager::new().unwrap();
+        let (_sessions, mut session_manager) = temp_session_manager();
         let session_id = session_manager.create_session(temp_dir.path()).await.unwrap();
         
         // Add a new file
         let new_file = temp_dir.path().join("new.js");
         fs::write(&new_file, "console.log('new');").unwrap();
         
-        let result = handle_session_update(&session_id, false, true).await.unwrap();
+        let result = handle_session_update(&mut session_manager, &session_id, false, true).await.unwrap();
         
         assert!(result.contains("pending changes"));
         assert!(result.contains("new.js"));
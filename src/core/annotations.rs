@@ -0,0 +1,117 @@
+//! TODO/FIXME annotation scanning
+//!
+//! A tag counts when it appears as a whole, upper-case word inside a comment
+//! of the file's language (see [`Language::comment_syntax`]), so identifiers
+//! such as `todo_list` and tags inside string literals are not reported.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::core::types::Language;
+
+/// Tags reported when the config does not list any
+pub const DEFAULT_ANNOTATION_TAGS: &[&str] = &["TODO", "FIXME", "HACK", "XXX"];
+
+/// One tagged comment
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Annotation {
+    pub tag: String,
+    pub file: PathBuf,
+    pub line: u32,
+    /// Comment text after the tag, e.g. `handle timeouts` for `// TODO: handle timeouts`
+    pub text: String,
+}
+
+/// Find tagged comments in `content`, written in `language`; at most one
+/// annotation per line, for the first tag that appears
+pub fn scan_annotations(file: &Path, content: &str, language: Language, tags: &[String]) -> Vec<Annotation> {
+    let mut annotations = Vec::new();
+    let syntax = language.comment_syntax();
+    let comment_starts = syntax.comment_starts(content.lines());
+    // Text a comment or docstring closed on the same line ends with
+    let closers: Vec<&str> = syntax.block.iter().map(|(_, close)| *close)
+        .chain(syntax.multiline_strings.iter().copied())
+        .collect();
+
+    for (index, (line, comment_start)) in content.lines().zip(comment_starts).enumerate() {
+        let Some(comment_start) = comment_start else {
+            continue;
+        };
+        let comment = &line[comment_start..];
+
+        let found = tags.iter()
+            .filter_map(|tag| find_word(comment, tag).map(|position| (position, tag)))
+            .min_by_key(|(position, _)| *position);
+        if let Some((position, tag)) = found {
+            let mut text = &comment[position + tag.len()..];
+            // `TODO(owner): ...`
+            if let Some(close) = text.strip_prefix('(').and_then(|rest| rest.find(')')) {
+                text = &text[close + 2..];
+            }
+            let mut text = text
+                .trim_start_matches(|c: char| c == ':' || c.is_whitespace())
+                .trim_end();
+            if let Some(stripped) = closers.iter().find_map(|close| text.strip_suffix(close)) {
+                text = stripped.trim_end();
+            }
+            annotations.push(Annotation {
+                tag: tag.clone(),
+                file: file.to_path_buf(),
+                line: index as u32 + 1,
+                text: text.to_string(),
+            });
+        }
+    }
+
+    annotations
+}
+
+/// Position of `word` in `text` with no identifier characters on either side
+fn find_word(text: &str, word: &str) -> Option<usize> {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    text.match_indices(word).map(|(position, _)| position).find(|&position| {
+        !text[..position].chars().next_back().is_some_and(is_ident)
+            && !text[position + word.len()..].chars().next().is_some_and(is_ident)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_annotations() {
+        let tags: Vec<String> = DEFAULT_ANNOTATION_TAGS.iter().map(|t| t.to_string()).collect();
+        let source = "\
+fn main() {
+    let todo_list = vec![]; // TODO: persist the list
+    let url = \"http://example.com/TODO\"; /* HACK: work around the parser */
+    /*
+     * XXX unsafe when empty
+     */
+    let s = \"// FIXME not a comment\";
+    // TODOS are not tags
+    # FIXME is not a Rust comment
+}
+";
+        let found = scan_annotations(Path::new("main.rs"), source, Language::Rust, &tags);
+        let summary: Vec<_> = found.iter().map(|a| (a.tag.as_str(), a.line, a.text.as_str())).collect();
+        assert_eq!(summary, vec![
+            ("TODO", 2, "persist the list"),
+            ("HACK", 3, "work around the parser"),
+            ("XXX", 5, "unsafe when empty"),
+        ]);
+
+        let source = "\
+def load():
+    \"\"\"FIXME(neko) flaky on CI\"\"\"
+    tag = \"# TODO not a comment\"  # HACK: cache this
+";
+        let found = scan_annotations(Path::new("load.py"), source, Language::Python, &tags);
+        let summary: Vec<_> = found.iter().map(|a| (a.tag.as_str(), a.line, a.text.as_str())).collect();
+        assert_eq!(summary, vec![
+            ("FIXME", 2, "flaky on CI"),
+            ("HACK", 3, "cache this"),
+        ]);
+    }
+}
@@ -0,0 +1,230 @@
+//! Public interface of a session
+//!
+//! A file's public symbols are the names it exports explicitly (`export`,
+//! `pub`, Python's `__all__`). Files without explicit exports fall back to the
+//! language's visibility convention: Python names without a leading
+//! underscore, capitalized Go names and `public` Java/C#/Kotlin declarations.
+//! Methods of public classes are listed under their class when the language
+//! would let a caller reach them.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use crate::core::types::{AnalysisResult, ClassInfo, ExportType, FunctionInfo, Language};
+
+/// One public function, class, method or exported name
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiSymbol {
+    /// `function`, `class`, `method` or `export` (an exported name with no
+    /// function or class behind it, e.g. a constant or a re-export)
+    pub kind: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub parameters: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub return_type: Option<String>,
+    pub signature: String,
+    pub line: u32,
+}
+
+/// Public symbols of one file, in source order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiFile {
+    pub file: PathBuf,
+    pub language: Language,
+    pub symbols: Vec<ApiSymbol>,
+}
+
+/// Collect the public interface of every file, skipping files that expose nothing
+pub fn api_surface(results: &[AnalysisResult]) -> Vec<ApiFile> {
+    let mut files: Vec<ApiFile> = results.iter()
+        .map(|file| ApiFile {
+            file: file.file_info.path.clone(),
+            language: file.language,
+            symbols: file_symbols(file),
+        })
+        .filter(|file| !file.symbols.is_empty())
+        .collect();
+    files.sort_by(|a, b| a.file.cmp(&b.file));
+    files
+}
+
+fn file_symbols(file: &AnalysisResult) -> Vec<ApiSymbol> {
+    // Crate-restricted Rust items are not reachable from outside
+    let exported: HashSet<&str> = file.exports.iter()
+        .filter(|export| export.export_type != ExportType::RustPubCrate)
+        .flat_map(|export| export.exported_names.iter().map(String::as_str))
+        .collect();
+    let explicit = file.exports.iter().any(|export| export.export_type != ExportType::RustPubCrate);
+    let visible = |name: &str, metadata: &std::collections::HashMap<String, String>| {
+        if explicit { exported.contains(name) } else { visible_by_convention(&file.language, name, metadata) }
+    };
+
+    let mut symbols = Vec::new();
+    let mut defined = HashSet::new();
+
+    // Methods and nested functions also show up in `functions`
+    let top_level = file.functions.iter().filter(|function| {
+        !file.classes.iter().any(|c| c.start_line <= function.start_line && function.end_line <= c.end_line)
+            && !file.functions.iter().any(|outer| {
+                outer.start_line < function.start_line && function.end_line <= outer.end_line
+            })
+    });
+    for function in top_level {
+        defined.insert(function.name.as_str());
+        if visible(&function.name, &function.metadata) {
+            symbols.push(function_symbol("function", function, None));
+        }
+    }
+
+    for class in &file.classes {
+        defined.insert(class.name.as_str());
+        if !visible(&class.name, &class.metadata) {
+            continue;
+        }
+        symbols.push(class_symbol(class));
+        for method in class.methods.iter().filter(|m| method_visible(&file.language, m)) {
+            symbols.push(function_symbol("method", method, Some(&class.name)));
+        }
+    }
+
+    for export in file.exports.iter().filter(|export| export.export_type != ExportType::RustPubCrate) {
+        for name in &export.exported_names {
+            if defined.insert(name.as_str()) {
+                symbols.push(ApiSymbol {
+                    kind: "export".to_string(),
+                    name: name.clone(),
+                    class: None,
+                    parameters: Vec::new(),
+                    return_type: None,
+                    signature: match &export.source_module {
+                        Some(module) => format!("{} (from {})", name, module),
+                        None => name.clone(),
+                    },
+                    line: export.line_number,
+                });
+            }
+        }
+    }
+
+    symbols.sort_by_key(|symbol| symbol.line);
+    symbols
+}
+
+/// Public by naming or modifiers when the file has no explicit exports
+fn visible_by_convention(language: &Language, name: &str, metadata: &std::collections::HashMap<String, String>) -> bool {
+    let modifiers = metadata.get("modifiers").map(String::as_str).unwrap_or("");
+    match language {
+        Language::Python => !name.starts_with('_'),
+        Language::Go => name.starts_with(|c: char| c.is_uppercase()),
+        Language::Java | Language::CSharp => modifiers.split_whitespace().any(|m| m == "public"),
+        Language::Kotlin => !modifiers.split_whitespace().any(|m| matches!(m, "private" | "internal" | "protected")),
+        Language::C | Language::Cpp => true,
+        // Anything public is exported explicitly
+        _ => false,
+    }
+}
+
+/// Whether a method of a public class can be called from outside
+fn method_visible(language: &Language, method: &FunctionInfo) -> bool {
+    let modifiers = method.metadata.get("modifiers").map(String::as_str).unwrap_or("");
+    match language {
+        Language::Python => !method.name.starts_with('_') || (method.name.starts_with("__") && method.name.ends_with("__")),
+        Language::Rust => modifiers.split_whitespace().any(|m| m == "pub"),
+        Language::Java | Language::CSharp => modifiers.split_whitespace().any(|m| m == "public"),
+        Language::Kotlin => !modifiers.split_whitespace().any(|m| matches!(m, "private" | "internal" | "protected")),
+        Language::Go => method.name.starts_with(|c: char| c.is_uppercase()),
+        _ => !method.name.starts_with(['_', '#']),
+    }
+}
+
+fn function_symbol(kind: &str, function: &FunctionInfo, class: Option<&str>) -> ApiSymbol {
+    let return_type = function.metadata.get("return_type").cloned();
+    let name = match class {
+        Some(class) => format!("{}.{}", class, function.name),
+        None => function.name.clone(),
+    };
+    let mut signature = format!("{}{}({})", if function.is_async { "async " } else { "" }, name, function.parameters.join(", "));
+    if let Some(return_type) = &return_type {
+        signature.push_str(" -> ");
+        signature.push_str(return_type);
+    }
+
+    ApiSymbol {
+        kind: kind.to_string(),
+        name: function.name.clone(),
+        class: class.map(str::to_string),
+        parameters: function.parameters.clone(),
+        return_type,
+        signature,
+        line: function.start_line,
+    }
+}
+
+fn class_symbol(class: &ClassInfo) -> ApiSymbol {
+    ApiSymbol {
+        kind: "class".to_string(),
+        name: class.name.clone(),
+        class: None,
+        parameters: Vec::new(),
+        return_type: None,
+        signature: match &class.parent_class {
+            Some(parent) => format!("class {}({})", class.name, parent),
+            None => format!("class {}", class.name),
+        },
+        line: class.start_line,
+    }
+}
+
+/// Render the surface as one Markdown table per file
+pub fn render_markdown(files: &[ApiFile]) -> String {
+    let mut out = String::from("# API surface\n");
+    for file in files {
+        out.push_str(&format!("\n## {}\n\n", file.file.display()));
+        out.push_str("| Kind | Signature | Line |\n|------|-----------|------|\n");
+        for symbol in &file.symbols {
+            out.push_str(&format!("| {} | `{}` | {} |\n", symbol.kind, symbol.signature.replace('|', "\\|"), symbol.line));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::{ExportInfo, FileInfo};
+    use crate::core::types::test_support::function;
+
+    #[test]
+    fn test_api_surface_uses_dunder_all_then_naming_convention() {
+        let mut listed = AnalysisResult::new(FileInfo::new(PathBuf::from("store.py")), Language::Python);
+        let mut load = function("load", 3, 4);
+        load.parameters = vec!["path".to_string()];
+        load.metadata.insert("return_type".to_string(), "dict".to_string());
+        listed.functions = vec![load, function("helper", 6, 7)];
+        let mut all = ExportInfo::new(ExportType::PythonGlobal);
+        all.exported_names = vec!["load".to_string(), "VERSION".to_string()];
+        all.line_number = 1;
+        listed.exports.push(all);
+
+        let mut unlisted = AnalysisResult::new(FileInfo::new(PathBuf::from("util.py")), Language::Python);
+        let mut class = ClassInfo::new("Cache".to_string());
+        class.start_line = 1;
+        class.end_line = 10;
+        class.methods = vec![function("__init__", 2, 3), function("_evict", 4, 5), function("get", 6, 7)];
+        unlisted.functions = vec![function("_private", 12, 13), function("public", 15, 17), function("inner", 16, 16)];
+        unlisted.classes.push(class);
+
+        let surface = api_surface(&[unlisted, listed]);
+        assert_eq!(surface[0].file, PathBuf::from("store.py"));
+        let signatures: Vec<_> = surface[0].symbols.iter().map(|s| s.signature.as_str()).collect();
+        assert_eq!(signatures, vec!["VERSION", "load(path) -> dict"]);
+
+        let names: Vec<_> = surface[1].symbols.iter().map(|s| (s.kind.as_str(), s.name.as_str())).collect();
+        assert_eq!(names, vec![("class", "Cache"), ("method", "__init__"), ("method", "get"), ("function", "public")]);
+        assert!(render_markdown(&surface).contains("| function | `load(path) -> dict` | 3 |"));
+    }
+}
@@ -3,11 +3,14 @@
 //! This module contains the core AST types and functionality, ported from the C++ implementation
 //! to provide language-agnostic AST building and manipulation capabilities.
 
+use anyhow::Result;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use schemars::JsonSchema;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 /// AST node types corresponding to C++ ASTNodeType enum
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Hash, JsonSchema)]
 pub enum ASTNodeType {
     // Basic structure
     #[serde(rename = "file_root")]
@@ -150,6 +153,45 @@ impl ASTNodeType {
     }
 }
 
+impl std::str::FromStr for ASTNodeType {
+    type Err = anyhow::Error;
+    
+    fn from_str(s: &str) -> Result<Self> {
+        serde_json::from_value(serde_json::Value::String(s.to_string()))
+            .map_err(|_| anyhow::anyhow!("Unknown node type: {}", s))
+    }
+}
+
+/// Pruning applied while dumping an AST
+#[derive(Debug, Clone, Default)]
+pub struct DumpFilter {
+    /// Deepest level to descend to; the file root is level 0
+    pub max_depth: Option<u32>,
+    /// Node types to keep; ancestors of kept nodes stay for context
+    pub node_types: Option<HashSet<ASTNodeType>>,
+}
+
+impl DumpFilter {
+    /// Build a filter from CLI options (`node_types` is comma-separated)
+    pub fn parse(max_depth: Option<u32>, node_types: Option<&str>) -> Result<Self> {
+        let node_types = node_types
+            .map(|list| {
+                list.split(',')
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty())
+                    .map(str::parse)
+                    .collect::<Result<HashSet<_>>>()
+            })
+            .transpose()?;
+        
+        Ok(Self { max_depth, node_types })
+    }
+    
+    fn matches(&self, node_type: ASTNodeType) -> bool {
+        self.node_types.as_ref().is_none_or(|types| types.contains(&node_type))
+    }
+}
+
 /// Query path types for AST search operations
 #[derive(Debug, Clone)]
 enum QueryPath {
@@ -166,8 +208,81 @@ enum QueryPath {
     },
 }
 
+/// One segment of a [`PathPattern`]
+#[derive(Debug, Clone)]
+enum PathSegment {
+    /// `*` - matches any single segment
+    Any,
+    /// Exact segment name
+    Name(String),
+    /// Regex that must match the whole segment
+    Regex(Regex),
+}
+
+impl PathSegment {
+    fn matches(&self, segment: &str) -> bool {
+        match self {
+            PathSegment::Any => true,
+            PathSegment::Name(name) => name == segment,
+            PathSegment::Regex(regex) => regex.is_match(segment),
+        }
+    }
+}
+
+/// Scope path pattern such as `"*::render"`, `"App::*::handle"` or (in regex mode) `"App::handle_.*"`
+///
+/// Patterns are matched against the end of a node's `scope_path`, so `"*::render"` finds
+/// `render` in any class regardless of nesting. A leading `::` anchors the pattern at the
+/// file root instead (`"::App::*"` only matches direct members of a top-level `App`).
+#[derive(Debug, Clone)]
+pub struct PathPattern {
+    segments: Vec<PathSegment>,
+    anchored: bool,
+}
+
+impl PathPattern {
+    /// Parse a path pattern. In regex mode every segment except `*` is a regex
+    /// matched against the whole segment (use `.*foo.*` for substring matches).
+    pub fn parse(path: &str, regex: bool) -> Result<Self> {
+        let path = path.trim();
+        let (anchored, path) = match path.strip_prefix("::") {
+            Some(rest) => (true, rest),
+            None => (false, path),
+        };
+        
+        let segments = path
+            .split("::")
+            .map(|segment| {
+                let segment = segment.trim();
+                if segment == "*" {
+                    Ok(PathSegment::Any)
+                } else if regex {
+                    Regex::new(&format!("^(?:{})$", segment))
+                        .map(PathSegment::Regex)
+                        .map_err(|e| anyhow::anyhow!("Invalid regex segment '{}': {}", segment, e))
+                } else {
+                    Ok(PathSegment::Name(segment.to_string()))
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        
+        Ok(Self { segments, anchored })
+    }
+    
+    /// Check whether a `::`-separated scope path matches this pattern
+    pub fn matches(&self, scope_path: &str) -> bool {
+        let parts: Vec<&str> = scope_path.split("::").collect();
+        if parts.len() < self.segments.len() || (self.anchored && parts.len() != self.segments.len()) {
+            return false;
+        }
+        
+        let tail = &parts[parts.len() - self.segments.len()..];
+        self.segments.iter().zip(tail).all(|(segment, part)| segment.matches(part))
+    }
+}
+
 /// AST Node representing a single element in the syntax tree
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ASTNode {
     // Basic node information
     #[serde(rename = "type")]
@@ -280,6 +395,38 @@ impl ASTNode {
         unique_results
     }
     
+    /// Query nodes by path, optionally treating each segment as a regex
+    ///
+    /// Plain paths keep the exact behavior of [`ASTNode::query_by_path`]. Regex mode,
+    /// anchored paths (`"::App::run"`) and paths with a `*` segment beyond the
+    /// `parent::child` form (`"App::*::render"`) are matched with a [`PathPattern`].
+    pub fn query_by_path_with(&self, path: &str, regex: bool) -> Result<Vec<&ASTNode>> {
+        let segments: Vec<&str> = path.trim().split("::").map(str::trim).collect();
+        let deep_wildcard = segments.len() > 2 && segments.contains(&"*");
+        
+        if !regex && !deep_wildcard && !path.trim().starts_with("::") {
+            return Ok(self.query_by_path(path));
+        }
+        
+        let pattern = PathPattern::parse(path, regex)?;
+        let mut result = Vec::new();
+        self.query_by_pattern(&pattern, &mut result);
+        Ok(result)
+    }
+    
+    /// Collect all nodes (except the file root) whose scope path matches `pattern`
+    fn query_by_pattern<'a>(&'a self, pattern: &PathPattern, result: &mut Vec<&'a ASTNode>) {
+        if self.node_type != ASTNodeType::FileRoot {
+            let scope_path = if self.scope_path.is_empty() { &self.name } else { &self.scope_path };
+            if pattern.matches(scope_path) {
+                result.push(self);
+            }
+        }
+        for child in &self.children {
+            child.query_by_pattern(pattern, result);
+        }
+    }
+    
     /// Parse query path into structured query components
     fn parse_query_path(&self, path: &str) -> QueryPath {
         let path = path.trim();
@@ -465,6 +612,29 @@ impl ASTNode {
         }
     }
     
+    /// Chain of nodes enclosing a line, from this node down to the deepest one.
+    ///
+    /// A file root spans the whole file, so it always starts the chain. When
+    /// sibling ranges touch (one ends on the line the next starts), the
+    /// narrowest sibling wins so the answer is a single path.
+    pub fn find_scope_chain_at_line(&self, line: u32) -> Vec<&ASTNode> {
+        let contains = |node: &ASTNode| line >= node.start_line && line <= node.end_line;
+        if self.node_type != ASTNodeType::FileRoot && !contains(self) {
+            return Vec::new();
+        }
+        
+        let mut chain = vec![self];
+        let mut current = self;
+        while let Some(child) = current.children.iter()
+            .filter(|child| contains(child))
+            .min_by_key(|child| (child.end_line - child.start_line, std::cmp::Reverse(child.start_line)))
+        {
+            chain.push(child);
+            current = child;
+        }
+        chain
+    }
+    
     /// Get node type as string
     pub fn type_string(&self) -> &'static str {
         self.node_type.as_str()
@@ -497,6 +667,47 @@ impl ASTNode {
         result
     }
     
+    /// Copy of this tree with `filter` applied. Subtrees below the depth
+    /// limit are never visited; the root is always kept.
+    pub fn pruned(&self, filter: &DumpFilter) -> ASTNode {
+        let children = self.pruned_children(filter, 0);
+        self.with_children(children)
+    }
+    
+    fn pruned_children(&self, filter: &DumpFilter, level: u32) -> Vec<ASTNode> {
+        if filter.max_depth.is_some_and(|max| level >= max) {
+            return Vec::new();
+        }
+        
+        self.children.iter()
+            .filter_map(|child| {
+                let children = child.pruned_children(filter, level + 1);
+                if children.is_empty() && !filter.matches(child.node_type) {
+                    None
+                } else {
+                    Some(child.with_children(children))
+                }
+            })
+            .collect()
+    }
+    
+    fn with_children(&self, children: Vec<ASTNode>) -> ASTNode {
+        ASTNode {
+            node_type: self.node_type,
+            name: self.name.clone(),
+            full_name: self.full_name.clone(),
+            start_line: self.start_line,
+            end_line: self.end_line,
+            start_column: self.start_column,
+            end_column: self.end_column,
+            depth: self.depth,
+            scope_path: self.scope_path.clone(),
+            children,
+            attributes: self.attributes.clone(),
+            source_text: self.source_text.clone(),
+        }
+    }
+    
     /// Dump AST as flat list
     pub fn dump_as_flat(&self) -> String {
         let mut result = Vec::new();
@@ -522,7 +733,7 @@ impl ASTNode {
 }
 
 /// AST Statistics structure matching C++ ASTStatistics
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct ASTStatistics {
     pub total_nodes: u32,
     pub max_depth: u32,
@@ -592,14 +803,14 @@ impl ASTStatistics {
         // Process children
         for child in &node.children {
             let child_stats = self.collect_statistics_recursive(child);
-            stats.merge(child_stats);
+            stats.add(&child_stats);
         }
         
         stats
     }
     
-    /// Merge statistics from another instance
-    fn merge(&mut self, other: Self) {
+    /// Add the counts of `other`, e.g. one more file in combined statistics
+    pub fn add(&mut self, other: &Self) {
         self.total_nodes += other.total_nodes;
         self.max_depth = self.max_depth.max(other.max_depth);
         self.classes += other.classes;
@@ -608,12 +819,149 @@ impl ASTStatistics {
         self.variables += other.variables;
         self.control_structures += other.control_structures;
         
-        for (key, count) in other.node_type_counts {
-            *self.node_type_counts.entry(key).or_insert(0) += count;
+        for (key, count) in &other.node_type_counts {
+            *self.node_type_counts.entry(key.clone()).or_insert(0) += count;
+        }
+    }
+    
+    /// Take back the counts an earlier `add(other)` contributed. `max_depth`
+    /// is a maximum and cannot be taken back; it is left unchanged for the
+    /// caller to recompute from what remains.
+    pub fn subtract(&mut self, other: &Self) {
+        self.total_nodes = self.total_nodes.saturating_sub(other.total_nodes);
+        self.classes = self.classes.saturating_sub(other.classes);
+        self.functions = self.functions.saturating_sub(other.functions);
+        self.methods = self.methods.saturating_sub(other.methods);
+        self.variables = self.variables.saturating_sub(other.variables);
+        self.control_structures = self.control_structures.saturating_sub(other.control_structures);
+        
+        for (key, count) in &other.node_type_counts {
+            if let Some(total) = self.node_type_counts.get_mut(key) {
+                *total = total.saturating_sub(*count);
+                if *total == 0 {
+                    self.node_type_counts.remove(key);
+                }
+            }
+        }
+    }
+}
+
+/// A node referenced in an [`AstDiff`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiffNode {
+    pub scope_path: String,
+    pub node_type: String,
+    pub start_line: u32,
+    pub end_line: u32,
+}
+
+impl DiffNode {
+    fn from_node(scope_path: &str, node: &ASTNode) -> Self {
+        Self {
+            scope_path: scope_path.to_string(),
+            node_type: node.type_string().to_string(),
+            start_line: node.start_line,
+            end_line: node.end_line,
         }
     }
 }
 
+/// A node whose scope path changed between two ASTs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiffRename {
+    pub from: DiffNode,
+    pub to: DiffNode,
+}
+
+/// Structural difference between two ASTs, keyed by `scope_path`
+///
+/// Nodes present on only one side are reported as `added`/`removed`, except that a
+/// removed/added pair of the same type is paired up as:
+/// - `moved` when the name is unchanged but the parent scope differs
+/// - `renamed` when the parent scope and line span are unchanged but the name differs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AstDiff {
+    pub added: Vec<DiffNode>,
+    pub removed: Vec<DiffNode>,
+    pub renamed: Vec<DiffRename>,
+    pub moved: Vec<DiffRename>,
+}
+
+impl AstDiff {
+    /// Diff two ASTs; a missing side is treated as an empty file
+    pub fn between(base: Option<&ASTNode>, head: Option<&ASTNode>) -> Self {
+        let base_nodes = base.map(Self::index_nodes).unwrap_or_default();
+        let head_nodes = head.map(Self::index_nodes).unwrap_or_default();
+        
+        let mut removed: Vec<DiffNode> = base_nodes.iter()
+            .filter(|(path, _)| !head_nodes.contains_key(*path))
+            .map(|(path, node)| DiffNode::from_node(path, node))
+            .collect();
+        let mut added: Vec<DiffNode> = head_nodes.iter()
+            .filter(|(path, _)| !base_nodes.contains_key(*path))
+            .map(|(path, node)| DiffNode::from_node(path, node))
+            .collect();
+        
+        let moved = Self::pair_up(&mut removed, &mut added, |from, to| {
+            split_scope_path(&from.scope_path).1 == split_scope_path(&to.scope_path).1
+        });
+        let renamed = Self::pair_up(&mut removed, &mut added, |from, to| {
+            split_scope_path(&from.scope_path).0 == split_scope_path(&to.scope_path).0
+                && from.end_line - from.start_line == to.end_line - to.start_line
+        });
+        
+        Self { added, removed, renamed, moved }
+    }
+    
+    /// Whether the two ASTs are structurally identical
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.renamed.is_empty() && self.moved.is_empty()
+    }
+    
+    /// Map every node (except the file root) by scope path; the first node wins on duplicates
+    fn index_nodes(root: &ASTNode) -> BTreeMap<String, &ASTNode> {
+        fn visit<'a>(node: &'a ASTNode, index: &mut BTreeMap<String, &'a ASTNode>) {
+            if node.node_type != ASTNodeType::FileRoot {
+                let key = if node.scope_path.is_empty() { &node.name } else { &node.scope_path };
+                index.entry(key.clone()).or_insert(node);
+            }
+            for child in &node.children {
+                visit(child, index);
+            }
+        }
+        
+        let mut index = BTreeMap::new();
+        visit(root, &mut index);
+        index
+    }
+    
+    /// Move matching removed/added pairs (same node type) out of both lists
+    fn pair_up(
+        removed: &mut Vec<DiffNode>,
+        added: &mut Vec<DiffNode>,
+        is_match: impl Fn(&DiffNode, &DiffNode) -> bool,
+    ) -> Vec<DiffRename> {
+        let mut pairs = Vec::new();
+        let mut remaining = Vec::new();
+        
+        for from in removed.drain(..) {
+            let found = added.iter().position(|to| to.node_type == from.node_type && is_match(&from, to));
+            match found {
+                Some(index) => pairs.push(DiffRename { from, to: added.remove(index) }),
+                None => remaining.push(from),
+            }
+        }
+        
+        *removed = remaining;
+        pairs
+    }
+}
+
+/// Split a scope path into its parent scope and last segment
+fn split_scope_path(scope_path: &str) -> (&str, &str) {
+    scope_path.rsplit_once("::").unwrap_or(("", scope_path))
+}
+
 /// AST Builder for constructing syntax trees
 pub struct ASTBuilder {
     root: ASTNode,
@@ -704,6 +1052,29 @@ mod tests {
         assert_eq!(node.depth, 0);
     }
     
+    #[test]
+    fn test_scope_chain_at_line() {
+        let mut builder = ASTBuilder::new();
+        builder.enter_scope(ASTNodeType::Class, "Parser".to_string(), 1);
+        builder.enter_scope(ASTNodeType::Method, "first".to_string(), 2);
+        builder.exit_scope(5);
+        builder.enter_scope(ASTNodeType::Method, "second".to_string(), 5);
+        builder.exit_scope(9);
+        builder.exit_scope(10);
+        let ast = builder.build();
+        
+        // Line 5 is shared by both methods; the narrower one wins
+        let chain = ast.find_scope_chain_at_line(5);
+        let names: Vec<&str> = chain.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, vec!["", "Parser", "first"]);
+        
+        let chain = ast.find_scope_chain_at_line(7);
+        assert_eq!(chain.last().unwrap().scope_path, "Parser::second");
+        
+        // Outside every scope only the file root remains
+        assert_eq!(ast.find_scope_chain_at_line(42).len(), 1);
+    }
+    
     #[test]
     fn test_ast_builder() {
         let mut builder = ASTBuilder::new();
@@ -804,4 +1175,148 @@ mod tests {
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].name, "myMethod");
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_ast_query_wildcard_and_regex() {
+        let mut builder = ASTBuilder::new();
+        builder.enter_scope(ASTNodeType::Namespace, "app".to_string(), 1);
+        builder.enter_scope(ASTNodeType::Class, "Button".to_string(), 2);
+        builder.enter_scope(ASTNodeType::Method, "render".to_string(), 3);
+        builder.exit_scope(4);
+        builder.enter_scope(ASTNodeType::Method, "render_icon".to_string(), 5);
+        builder.exit_scope(6);
+        builder.exit_scope(7);
+        builder.enter_scope(ASTNodeType::Class, "Label".to_string(), 8);
+        builder.enter_scope(ASTNodeType::Method, "render".to_string(), 9);
+        builder.exit_scope(10);
+        builder.exit_scope(11);
+        builder.exit_scope(12);
+        let ast = builder.build();
+        
+        let scope_paths = |nodes: Vec<&ASTNode>| -> Vec<String> {
+            nodes.iter().map(|n| n.scope_path.clone()).collect()
+        };
+        
+        // Deep wildcard segment
+        let results = ast.query_by_path_with("app::*::render", false).unwrap();
+        assert_eq!(scope_paths(results), vec!["app::Button::render", "app::Label::render"]);
+        
+        // Regex segments match whole names
+        let results = ast.query_by_path_with("Button::render.*", true).unwrap();
+        assert_eq!(scope_paths(results), vec!["app::Button::render", "app::Button::render_icon"]);
+        let results = ast.query_by_path_with("*::rend", true).unwrap();
+        assert!(results.is_empty());
+        
+        // Anchored paths must start at the file root
+        assert!(ast.query_by_path_with("::Button::render", false).unwrap().is_empty());
+        assert_eq!(ast.query_by_path_with("::app::Label", false).unwrap().len(), 1);
+        
+        // Plain paths behave exactly like query_by_path
+        assert_eq!(
+            scope_paths(ast.query_by_path_with("render", false).unwrap()),
+            scope_paths(ast.query_by_path("render"))
+        );
+        
+        assert!(ast.query_by_path_with("app::(", true).is_err());
+    }
+    
+    #[test]
+    fn test_ast_diff() {
+        let build = |classes: &[(&str, &[&str])]| {
+            let mut builder = ASTBuilder::new();
+            let mut line = 1;
+            for (class, methods) in classes {
+                builder.enter_scope(ASTNodeType::Class, class.to_string(), line);
+                for method in *methods {
+                    line += 1;
+                    builder.enter_scope(ASTNodeType::Method, method.to_string(), line);
+                    line += 2;
+                    builder.exit_scope(line);
+                }
+                line += 1;
+                builder.exit_scope(line);
+                line += 1;
+            }
+            builder.build()
+        };
+        
+        let base = build(&[("Cart", &["add", "remove"]), ("Order", &["submit"])]);
+        let head = build(&[("Cart", &["add", "delete"]), ("Order", &["submit", "cancel"]), ("Invoice", &[])]);
+        
+        let diff = AstDiff::between(Some(&base), Some(&head));
+        let paths = |nodes: &[DiffNode]| nodes.iter().map(|n| n.scope_path.clone()).collect::<Vec<_>>();
+        assert_eq!(paths(&diff.added), vec!["Invoice", "Order::cancel"]);
+        assert!(diff.removed.is_empty());
+        assert_eq!(diff.renamed.len(), 1);
+        assert_eq!(diff.renamed[0].from.scope_path, "Cart::remove");
+        assert_eq!(diff.renamed[0].to.scope_path, "Cart::delete");
+        
+        // Moving a method to another class keeps its name
+        let moved = build(&[("Cart", &["add"]), ("Order", &["submit", "remove"])]);
+        let diff = AstDiff::between(Some(&base), Some(&moved));
+        assert_eq!(diff.moved.len(), 1);
+        assert_eq!(diff.moved[0].to.scope_path, "Order::remove");
+        
+        assert!(AstDiff::between(Some(&base), Some(&base)).is_empty());
+        assert_eq!(AstDiff::between(None, Some(&base)).added.len(), 5);
+    }
+    
+    #[test]
+    fn test_dump_filter() {
+        let mut builder = ASTBuilder::new();
+        builder.enter_scope(ASTNodeType::Class, "Parser".to_string(), 1);
+        builder.enter_scope(ASTNodeType::Method, "parse".to_string(), 2);
+        builder.add_node(ASTNodeType::Variable, "token".to_string(), 3);
+        builder.exit_scope(5);
+        builder.exit_scope(6);
+        builder.add_node(ASTNodeType::Variable, "VERSION".to_string(), 8);
+        let ast = builder.build();
+        
+        let shallow = ast.pruned(&DumpFilter::parse(Some(1), None).unwrap());
+        assert_eq!(shallow.children.len(), 2);
+        assert!(shallow.children[0].children.is_empty());
+        
+        // Variables are kept along with the class and method around them
+        let vars = ast.pruned(&DumpFilter::parse(None, Some("variable")).unwrap());
+        assert_eq!(vars.dump_as_flat().lines().count(), 5);
+        
+        let methods = ast.pruned(&DumpFilter::parse(None, Some("method")).unwrap());
+        assert_eq!(methods.children.len(), 1);
+        assert_eq!(methods.children[0].children[0].name, "parse");
+        assert!(methods.children[0].children[0].children.is_empty());
+        
+        // Nothing below the depth limit can match
+        let limited = ast.pruned(&DumpFilter::parse(Some(2), Some("variable")).unwrap());
+        assert_eq!(limited.children.len(), 1);
+        assert_eq!(limited.children[0].name, "VERSION");
+        
+        assert!(DumpFilter::parse(None, Some("function,bogus")).is_err());
+    }
+    
+    #[test]
+    fn test_statistics_add_and_subtract() {
+        let mut class = ASTNode::new(ASTNodeType::Class, "Store".to_string());
+        let mut method = ASTNode::new(ASTNodeType::Method, "save".to_string());
+        method.add_child(ASTNode::new(ASTNodeType::IfStatement, String::new()));
+        class.add_child(method);
+        let mut function = ASTNode::new(ASTNodeType::Function, "main".to_string());
+        function.add_child(ASTNode::new(ASTNodeType::Variable, "x".to_string()));
+        
+        let files: Vec<ASTStatistics> = [class, function].iter().map(|root| {
+            let mut stats = ASTStatistics::default();
+            stats.update_from_root(root);
+            stats
+        }).collect();
+        let mut combined = ASTStatistics::default();
+        files.iter().for_each(|stats| combined.add(stats));
+        assert_eq!((combined.total_nodes, combined.classes, combined.methods, combined.functions), (5, 1, 1, 1));
+        
+        // Removing a file leaves what a from-scratch combination of the rest gives
+        combined.subtract(&files[0]);
+        combined.max_depth = files[1].max_depth;
+        assert_eq!(combined, files[1]);
+        combined.subtract(&files[1]);
+        assert!(combined.node_type_counts.is_empty());
+        assert_eq!(combined.total_nodes + combined.variables, 0);
+    }
+}
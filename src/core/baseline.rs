@@ -0,0 +1,231 @@
+//! Baseline comparison for regression gating
+//!
+//! A baseline is a saved `DirectoryAnalysis`. Files are matched by their path
+//! relative to the analyzed directory, so a baseline taken in one checkout can
+//! be compared against another. Functions are matched by name within a file;
+//! when a name occurs more than once the first occurrence is compared.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::core::types::{AnalysisResult, DirectoryAnalysis};
+
+/// Write `analysis` to `path` as JSON
+pub fn save_baseline(analysis: &DirectoryAnalysis, path: &Path) -> Result<()> {
+    let json = serde_json::to_string_pretty(analysis)?;
+    std::fs::write(path, json)
+        .with_context(|| format!("Failed to write baseline {}", path.display()))
+}
+
+/// Read a baseline written by `save_baseline` (or `analyze --format json`)
+pub fn load_baseline(path: &Path) -> Result<DirectoryAnalysis> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read baseline {}", path.display()))?;
+    serde_json::from_str(&content)
+        .with_context(|| format!("{} is not a saved analysis", path.display()))
+}
+
+/// Differences between a baseline and the current analysis
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BaselineComparison {
+    pub new_files: Vec<PathBuf>,
+    pub removed_files: Vec<PathBuf>,
+    /// Files present in both analyses that changed in a tracked metric
+    pub files: Vec<FileDelta>,
+}
+
+/// Metric changes of one file present in both analyses
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileDelta {
+    pub file: PathBuf,
+    pub complexity_before: u32,
+    pub complexity_after: u32,
+    pub new_functions: Vec<String>,
+    pub removed_functions: Vec<String>,
+    /// Functions whose cyclomatic complexity went up
+    pub complexity_increases: Vec<FunctionComplexityChange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionComplexityChange {
+    pub name: String,
+    pub before: u32,
+    pub after: u32,
+}
+
+impl BaselineComparison {
+    /// Compare `current` against `baseline`, listing files in path order
+    pub fn between(baseline: &DirectoryAnalysis, current: &DirectoryAnalysis) -> Self {
+        let before = files_by_relative_path(baseline);
+        let after = files_by_relative_path(current);
+        let mut comparison = Self::default();
+
+        for (path, file) in &after {
+            match before.get(path) {
+                None => comparison.new_files.push(path.clone()),
+                Some(old) => {
+                    if let Some(delta) = file_delta(path, old, file) {
+                        comparison.files.push(delta);
+                    }
+                }
+            }
+        }
+        comparison.removed_files = before.keys().filter(|path| !after.contains_key(*path)).cloned().collect();
+
+        comparison.new_files.sort();
+        comparison.removed_files.sort();
+        comparison.files.sort_by(|a, b| a.file.cmp(&b.file));
+        comparison
+    }
+
+    /// Whether any file or function present in the baseline got more complex
+    pub fn complexity_increased(&self) -> bool {
+        self.files.iter().any(|delta| {
+            delta.complexity_after > delta.complexity_before || !delta.complexity_increases.is_empty()
+        })
+    }
+
+    /// Human-readable report, one section per changed file
+    pub fn render_text(&self) -> String {
+        let mut out = String::from("📊 Baseline comparison\n");
+        if self.new_files.is_empty() && self.removed_files.is_empty() && self.files.is_empty() {
+            out.push_str("No changes since the baseline\n");
+            return out;
+        }
+        for file in &self.new_files {
+            out.push_str(&format!("\n➕ {} (new file)\n", file.display()));
+        }
+        for file in &self.removed_files {
+            out.push_str(&format!("\n➖ {} (removed)\n", file.display()));
+        }
+        for delta in &self.files {
+            out.push_str(&format!(
+                "\n📄 {} complexity {} → {}\n",
+                delta.file.display(), delta.complexity_before, delta.complexity_after
+            ));
+            for name in &delta.new_functions {
+                out.push_str(&format!("  + {}\n", name));
+            }
+            for name in &delta.removed_functions {
+                out.push_str(&format!("  - {}\n", name));
+            }
+            for change in &delta.complexity_increases {
+                out.push_str(&format!("  ⬆ {} complexity {} → {}\n", change.name, change.before, change.after));
+            }
+        }
+        out
+    }
+}
+
+fn files_by_relative_path(analysis: &DirectoryAnalysis) -> HashMap<PathBuf, &AnalysisResult> {
+    analysis.files.iter()
+        .map(|file| {
+            let path = &file.file_info.path;
+            let relative = path.strip_prefix(&analysis.directory_path).unwrap_or(path);
+            (relative.to_path_buf(), file)
+        })
+        .collect()
+}
+
+fn file_delta(path: &Path, before: &AnalysisResult, after: &AnalysisResult) -> Option<FileDelta> {
+    let old: HashMap<&str, u32> = function_complexities(before);
+    let new: HashMap<&str, u32> = function_complexities(after);
+
+    let mut new_functions: Vec<String> = new.keys().filter(|name| !old.contains_key(*name)).map(|name| name.to_string()).collect();
+    let mut removed_functions: Vec<String> = old.keys().filter(|name| !new.contains_key(*name)).map(|name| name.to_string()).collect();
+    let mut complexity_increases: Vec<FunctionComplexityChange> = new.iter()
+        .filter_map(|(name, &after)| {
+            let &before = old.get(name)?;
+            (after > before).then(|| FunctionComplexityChange { name: name.to_string(), before, after })
+        })
+        .collect();
+    new_functions.sort();
+    removed_functions.sort();
+    complexity_increases.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let complexity_before = before.complexity.cyclomatic_complexity;
+    let complexity_after = after.complexity.cyclomatic_complexity;
+    let unchanged = complexity_before == complexity_after
+        && new_functions.is_empty()
+        && removed_functions.is_empty()
+        && complexity_increases.is_empty();
+    (!unchanged).then(|| FileDelta {
+        file: path.to_path_buf(),
+        complexity_before,
+        complexity_after,
+        new_functions,
+        removed_functions,
+        complexity_increases,
+    })
+}
+
+fn function_complexities(file: &AnalysisResult) -> HashMap<&str, u32> {
+    let mut complexities = HashMap::new();
+    for function in &file.functions {
+        complexities.entry(function.name.as_str()).or_insert(function.complexity.cyclomatic_complexity);
+    }
+    complexities
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::{DirectorySummary, FileInfo, FunctionInfo, Language};
+    use chrono::Utc;
+
+    fn analysis(root: &str, files: Vec<AnalysisResult>) -> DirectoryAnalysis {
+        let mut summary = DirectorySummary::default();
+        summary.total_files = files.len() as u32;
+        DirectoryAnalysis {
+            directory_path: PathBuf::from(root),
+            files,
+            summary,
+            generated_at: Utc::now(),
+            errors: Vec::new(),
+        }
+    }
+
+    fn file(path: &str, complexity: u32, functions: &[(&str, u32)]) -> AnalysisResult {
+        let mut result = AnalysisResult::new(FileInfo::new(PathBuf::from(path)), Language::Python);
+        result.complexity.cyclomatic_complexity = complexity;
+        result.functions = functions.iter()
+            .map(|&(name, complexity)| {
+                let mut function = FunctionInfo::new(name.to_string());
+                function.complexity.cyclomatic_complexity = complexity;
+                function
+            })
+            .collect();
+        result
+    }
+
+    #[test]
+    fn test_compare_matches_files_by_relative_path() {
+        let baseline = analysis("/old/checkout", vec![
+            file("/old/checkout/app.py", 3, &[("load", 2), ("save", 1)]),
+            file("/old/checkout/same.py", 1, &[("f", 1)]),
+            file("/old/checkout/gone.py", 1, &[]),
+        ]);
+        let current = analysis("/new/checkout", vec![
+            file("/new/checkout/app.py", 5, &[("load", 4), ("export", 1)]),
+            file("/new/checkout/same.py", 1, &[("f", 1)]),
+            file("/new/checkout/added.py", 1, &[]),
+        ]);
+
+        let comparison = BaselineComparison::between(&baseline, &current);
+        assert_eq!(comparison.new_files, vec![PathBuf::from("added.py")]);
+        assert_eq!(comparison.removed_files, vec![PathBuf::from("gone.py")]);
+        assert_eq!(comparison.files.len(), 1);
+        let delta = &comparison.files[0];
+        assert_eq!(delta.file, PathBuf::from("app.py"));
+        assert_eq!((delta.complexity_before, delta.complexity_after), (3, 5));
+        assert_eq!(delta.new_functions, vec!["export"]);
+        assert_eq!(delta.removed_functions, vec!["save"]);
+        assert_eq!(delta.complexity_increases[0].name, "load");
+        assert!(comparison.complexity_increased());
+        assert!(comparison.render_text().contains("⬆ load complexity 2 → 4"));
+
+        assert!(!BaselineComparison::between(&current, &current).complexity_increased());
+    }
+}
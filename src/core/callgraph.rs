@@ -0,0 +1,498 @@
+//! Function-level call graph
+//!
+//! Every function and method in a session becomes a node, and every
+//! `FunctionCall` is attributed to the innermost function whose line range
+//! contains it. Calls outside any function belong to a per-file `<module>`
+//! node. Callees are resolved by name: `this`/`self` calls to the enclosing
+//! class, receiver calls to methods, plain calls to functions in the same file
+//! first and then anywhere else. When several definitions remain the call is
+//! linked to all of them, so a function is never reported unreferenced just
+//! because its name is ambiguous. Unresolved callees are `external`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::PathBuf;
+
+use crate::core::session::AnalysisSession;
+use crate::core::types::{AnalysisResult, ExportType, FunctionCall, FunctionInfo, Language};
+
+/// Name of the pseudo-function holding a file's top-level calls
+pub const MODULE_NODE: &str = "<module>";
+
+/// A function or method in the graph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallGraphNode {
+    /// `file:name` or `file:Class.method`
+    pub id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+    pub file: PathBuf,
+    pub line: u32,
+    /// `main`, exported/public functions and `<module>` nodes
+    pub entry_point: bool,
+    pub callers: usize,
+}
+
+/// Caller-to-callee adjacency for a set of analyzed files
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallGraph {
+    pub nodes: Vec<CallGraphNode>,
+    /// Caller id to callee ids; unresolved callees appear as `external:<name>`
+    pub edges: BTreeMap<String, Vec<String>>,
+    pub external: Vec<String>,
+    pub entry_points: Vec<String>,
+    /// Functions with no callers that are not entry points (dead-code hints)
+    pub unreferenced: Vec<String>,
+}
+
+struct Definition<'a> {
+    node: CallGraphNode,
+    start_line: u32,
+    end_line: u32,
+    file: &'a AnalysisResult,
+}
+
+/// Build the call graph of a set of analysis results
+pub fn build_call_graph(results: &[AnalysisResult]) -> CallGraph {
+    let definitions = collect_definitions(results);
+    let mut edges: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    let mut module_nodes = Vec::new();
+
+    for file in results {
+        let path = file.file_info.path.display().to_string();
+        for call in &file.function_calls {
+            let caller = definitions.iter()
+                .filter(|d| std::ptr::eq(d.file, file) && d.start_line <= call.line_number && call.line_number <= d.end_line)
+                .min_by_key(|d| d.end_line - d.start_line);
+            let caller_id = match caller {
+                Some(caller) => caller.node.id.clone(),
+                None => {
+                    let id = format!("{}:{}", path, MODULE_NODE);
+                    if !module_nodes.iter().any(|n: &CallGraphNode| n.id == id) {
+                        module_nodes.push(CallGraphNode {
+                            id: id.clone(),
+                            name: MODULE_NODE.to_string(),
+                            class: None,
+                            file: file.file_info.path.clone(),
+                            line: 0,
+                            entry_point: true,
+                            callers: 0,
+                        });
+                    }
+                    id
+                }
+            };
+
+            let callees = resolve_callees(call, caller, file, &definitions);
+            let targets = edges.entry(caller_id).or_default();
+            if callees.is_empty() {
+                targets.insert(format!("external:{}", call.full_name()));
+            } else {
+                targets.extend(callees.into_iter().map(|d| d.node.id.clone()));
+            }
+        }
+    }
+
+    let mut nodes: Vec<CallGraphNode> = definitions.into_iter().map(|d| d.node).collect();
+    nodes.extend(module_nodes);
+    for node in &mut nodes {
+        node.callers = edges.iter()
+            .filter(|(caller, callees)| *caller != &node.id && callees.contains(&node.id))
+            .count();
+    }
+
+    let external: BTreeSet<String> = edges.values()
+        .flatten()
+        .filter_map(|callee| callee.strip_prefix("external:"))
+        .map(str::to_string)
+        .collect();
+
+    CallGraph {
+        entry_points: nodes.iter().filter(|n| n.entry_point).map(|n| n.id.clone()).collect(),
+        unreferenced: nodes.iter().filter(|n| !n.entry_point && n.callers == 0).map(|n| n.id.clone()).collect(),
+        edges: edges.into_iter().map(|(caller, callees)| (caller, callees.into_iter().collect())).collect(),
+        external: external.into_iter().collect(),
+        nodes,
+    }
+}
+
+/// Render the graph in Graphviz DOT format
+pub fn to_dot(graph: &CallGraph) -> String {
+    let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+    let mut dot = String::from("digraph callgraph {\n    rankdir=LR;\n");
+
+    for node in &graph.nodes {
+        let label = match &node.class {
+            Some(class) => format!("{}.{}", class, node.name),
+            None => node.name.clone(),
+        };
+        let style = if node.entry_point {
+            ", shape=doubleoctagon"
+        } else if node.callers == 0 {
+            ", style=dashed"
+        } else {
+            ""
+        };
+        dot.push_str(&format!("    {} [label={}{}];\n", quote(&node.id), quote(&label), style));
+    }
+    for name in &graph.external {
+        let id = format!("external:{}", name);
+        dot.push_str(&format!("    {} [label={}, shape=box, style=dotted];\n", quote(&id), quote(name)));
+    }
+    for (caller, callees) in &graph.edges {
+        for callee in callees {
+            dot.push_str(&format!("    {} -> {};\n", quote(caller), quote(callee)));
+        }
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+fn collect_definitions(results: &[AnalysisResult]) -> Vec<Definition<'_>> {
+    let mut definitions = Vec::new();
+
+    for file in results {
+        let path = file.file_info.path.display().to_string();
+        let methods = file.classes.iter()
+            .flat_map(|class| class.methods.iter().map(move |method| (Some(class.name.as_str()), method)));
+        // Some analyzers also list methods as functions; keep the method entry
+        let functions = file.free_functions().map(|function| (None, function));
+
+        for (class, function) in methods.chain(functions) {
+            let id = match class {
+                Some(class) => format!("{}:{}.{}", path, class, function.name),
+                None => format!("{}:{}", path, function.name),
+            };
+            let exported = is_exported(file, &function.name, function.start_line, class.is_none());
+            let public = is_public(file, &function.name, &function.metadata);
+
+            definitions.push(Definition {
+                node: CallGraphNode {
+                    id,
+                    name: function.name.clone(),
+                    class: class.map(str::to_string),
+                    file: file.file_info.path.clone(),
+                    line: function.start_line,
+                    entry_point: function.name == "main" || exported || public,
+                    callers: 0,
+                },
+                start_line: function.start_line,
+                end_line: function.end_line,
+                file,
+            });
+        }
+    }
+
+    definitions
+}
+
+/// Exported by an `export` statement, by name or on the definition's own line.
+/// Crate-restricted Rust items are not reachable from outside and don't count;
+/// Python's `__all__` only lowers confidence (see `find_unused`).
+fn is_exported(file: &AnalysisResult, name: &str, line: u32, top_level: bool) -> bool {
+    file.exports.iter().filter(|export| !matches!(export.export_type, ExportType::RustPubCrate | ExportType::PythonGlobal)).any(|export| {
+        export.exported_names.iter().any(|n| n == name) || (top_level && export.line_number == line)
+    })
+}
+
+/// Rust `pub`, C#/C++ `public` or a capitalized Go name
+fn is_public(file: &AnalysisResult, name: &str, metadata: &HashMap<String, String>) -> bool {
+    metadata.get("is_public").is_some_and(|v| v == "true")
+        || metadata.get("modifiers").is_some_and(|m| m.split_whitespace().any(|m| m == "pub" || m == "public"))
+        || (file.language == Language::Go && name.starts_with(|c: char| c.is_uppercase()))
+}
+
+/// Definitions a call may refer to, most specific match first
+fn resolve_callees<'a, 'b>(
+    call: &FunctionCall,
+    caller: Option<&Definition<'a>>,
+    file: &AnalysisResult,
+    definitions: &'b [Definition<'a>],
+) -> Vec<&'b Definition<'a>> {
+    let (qualifier, name) = match call.function_name.rsplit_once("::").or_else(|| call.function_name.rsplit_once('.')) {
+        Some((qualifier, name)) => (Some(qualifier), name),
+        None => (None, call.function_name.as_str()),
+    };
+    let receiver = call.object_name.as_deref().or(qualifier);
+    let named: Vec<&Definition> = definitions.iter().filter(|d| d.node.name == name).collect();
+    let same_file = |d: &&Definition| std::ptr::eq(d.file, file);
+
+    let candidates: Vec<&Definition> = match receiver {
+        Some("this") | Some("self") => {
+            let class = caller.and_then(|c| c.node.class.as_deref());
+            named.into_iter().filter(|d| same_file(d) && d.node.class.as_deref() == class && class.is_some()).collect()
+        }
+        Some(receiver) => {
+            let methods: Vec<&Definition> = named.iter().copied().filter(|d| d.node.class.is_some()).collect();
+            let by_class: Vec<&Definition> = methods.iter().copied()
+                .filter(|d| d.node.class.as_deref().is_some_and(|c| receiver_names_class(receiver, c)))
+                .collect();
+            if !by_class.is_empty() {
+                by_class
+            } else if !methods.is_empty() {
+                methods
+            } else {
+                // `module.func()` or `pkg::func()`
+                named.into_iter().filter(|d| d.node.class.is_none()).collect()
+            }
+        }
+        None => {
+            let functions: Vec<&Definition> = named.into_iter().filter(|d| d.node.class.is_none()).collect();
+            let local: Vec<&Definition> = functions.iter().copied().filter(same_file).collect();
+            if local.is_empty() { functions } else { local }
+        }
+    };
+
+    candidates
+}
+
+/// Check whether a call receiver refers to a class, either directly (`TestClass.create()`)
+/// or through a conventionally named instance (`testClass.process()`, `test_class.process()`)
+pub(crate) fn receiver_names_class(receiver: &str, class_name: &str) -> bool {
+    let normalize = |s: &str| s.replace('_', "").to_lowercase();
+    receiver == class_name || normalize(receiver) == normalize(class_name)
+}
+
+/// How sure `find_unused` is that a symbol is dead
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Confidence {
+    High,
+    Medium,
+    Low,
+}
+
+/// A function, method or class nothing in the session refers to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnusedSymbol {
+    /// `function`, `method` or `class`
+    pub kind: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+    pub file: PathBuf,
+    pub line: u32,
+    pub confidence: Confidence,
+    pub note: String,
+}
+
+/// List functions, methods and classes that are never referenced. Exported
+/// symbols, `main`, tests, HTTP handlers, constructors and other
+/// framework-invoked methods are skipped. Public API and names listed in a
+/// Python module's `__all__` are still reported, with low confidence.
+pub fn find_unused(results: &[AnalysisResult]) -> Vec<UnusedSymbol> {
+    let graph = build_call_graph(results);
+    let callers: HashMap<&str, usize> = graph.nodes.iter().map(|n| (n.id.as_str(), n.callers)).collect();
+    let mut unused = Vec::new();
+
+    for file in results {
+        let path = file.file_info.path.display().to_string();
+        // Without call data for the language, "no calls found" means nothing
+        let calls_tracked = results.iter().any(|r| r.language == file.language && !r.function_calls.is_empty());
+        let untracked = (Confidence::Low, "call sites are not recorded for this language; verify manually");
+        let listed = |name: &str| file.exports.iter()
+            .any(|export| export.export_type == ExportType::PythonGlobal && export.exported_names.iter().any(|n| n == name));
+
+        for function in file.free_functions() {
+            let id = format!("{}:{}", path, function.name);
+            if callers.get(id.as_str()).copied().unwrap_or(0) > 0
+                || is_exported(file, &function.name, function.start_line, true)
+                || is_entry_point(file, function)
+            {
+                continue;
+            }
+            let (confidence, note) = if !calls_tracked {
+                untracked
+            } else if listed(&function.name) {
+                (Confidence::Low, "listed in __all__; may be imported outside the session")
+            } else if is_public(file, &function.name, &function.metadata) {
+                (Confidence::Low, "public API; may be used outside the session")
+            } else {
+                (Confidence::High, "no calls found; dynamic calls (callbacks, reflection) are not tracked")
+            };
+            unused.push(UnusedSymbol {
+                kind: "function".to_string(),
+                name: function.name.clone(),
+                class: None,
+                file: file.file_info.path.clone(),
+                line: function.start_line,
+                confidence,
+                note: note.to_string(),
+            });
+        }
+
+        for class in &file.classes {
+            let mut any_method_used = false;
+            for method in &class.methods {
+                let id = format!("{}:{}.{}", path, class.name, method.name);
+                if callers.get(id.as_str()).copied().unwrap_or(0) > 0 {
+                    any_method_used = true;
+                    continue;
+                }
+                if is_entry_point(file, method) {
+                    continue;
+                }
+                let (confidence, note) = if !calls_tracked {
+                    untracked
+                } else if is_public(file, &method.name, &method.metadata) {
+                    (Confidence::Low, "public API; may be used outside the session")
+                } else {
+                    (Confidence::Medium, "no calls found; methods can be reached through dynamic dispatch")
+                };
+                unused.push(UnusedSymbol {
+                    kind: "method".to_string(),
+                    name: method.name.clone(),
+                    class: Some(class.name.clone()),
+                    file: file.file_info.path.clone(),
+                    line: method.start_line,
+                    confidence,
+                    note: note.to_string(),
+                });
+            }
+
+            if any_method_used
+                || is_exported(file, &class.name, class.start_line, true)
+                || is_class_referenced(results, &class.name)
+            {
+                continue;
+            }
+            let (confidence, note) = if !calls_tracked {
+                untracked
+            } else if listed(&class.name) {
+                (Confidence::Low, "listed in __all__; may be imported outside the session")
+            } else if is_public(file, &class.name, &class.metadata) {
+                (Confidence::Low, "public API; may be used outside the session")
+            } else if matches!(file.language, Language::JavaScript | Language::TypeScript) {
+                (Confidence::Medium, "no references found; `new` expressions are not tracked")
+            } else {
+                (Confidence::High, "no references found")
+            };
+            unused.push(UnusedSymbol {
+                kind: "class".to_string(),
+                name: class.name.clone(),
+                class: None,
+                file: file.file_info.path.clone(),
+                line: class.start_line,
+                confidence,
+                note: note.to_string(),
+            });
+        }
+    }
+
+    unused.sort_by(|a, b| (a.confidence, &a.file, a.line).cmp(&(b.confidence, &b.file, b.line)));
+    unused
+}
+
+/// Functions invoked by a runtime, test runner or framework rather than by code
+fn is_entry_point(file: &AnalysisResult, function: &FunctionInfo) -> bool {
+    const HANDLER_DECORATORS: &[&str] = &["route", ".get(", ".post(", ".put(", ".delete(", ".patch(", "api_view", "handler"];
+    let name = function.name.as_str();
+    let decorators = function.metadata.get("decorators").map(String::as_str).unwrap_or_default();
+    let flag = |key: &str| function.metadata.get(key).is_some_and(|v| v == "true");
+    let in_test_file = AnalysisSession::is_test_file(&file.file_info.path);
+
+    name == "main"
+        || name == "constructor"
+        || (name.starts_with("__") && name.ends_with("__"))
+        || flag("is_constructor") || flag("is_destructor") || flag("is_override") || flag("is_property")
+        || name.starts_with("test") || name.starts_with("Test") || name.starts_with("Benchmark")
+        || (in_test_file && matches!(name, "setUp" | "tearDown" | "beforeEach" | "afterEach" | "beforeAll" | "afterAll"))
+        || decorators.contains("test") || decorators.contains("fixture")
+        || HANDLER_DECORATORS.iter().any(|d| decorators.contains(d))
+}
+
+/// A class is referenced by a call to its name (constructor or static
+/// method), by a subclass, or by an import
+fn is_class_referenced(results: &[AnalysisResult], class_name: &str) -> bool {
+    results.iter().any(|file| {
+        file.function_calls.iter().any(|call| {
+            call.function_name == class_name || call.object_name.as_deref() == Some(class_name)
+        })
+            || file.classes.iter().any(|c| c.parent_class.as_deref() == Some(class_name))
+            || file.imports.iter().any(|i| i.imported_names.iter().any(|n| n == class_name))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::{ClassInfo, ExportInfo, ExportType, FileInfo};
+    use crate::core::types::test_support::function;
+
+    fn call(name: &str, object: Option<&str>, line: u32) -> FunctionCall {
+        let mut call = FunctionCall::new(name.to_string(), line);
+        call.object_name = object.map(str::to_string);
+        call.is_method_call = object.is_some();
+        call
+    }
+
+    #[test]
+    fn test_call_graph_resolves_calls_and_flags_roots() {
+        let mut file = AnalysisResult::new(FileInfo::new(PathBuf::from("app.js")), Language::JavaScript);
+        file.functions = vec![function("main", 1, 5), function("helper", 7, 9), function("unused", 11, 13)];
+        let mut class = ClassInfo::new("Store".to_string());
+        class.start_line = 15;
+        class.end_line = 25;
+        class.methods = vec![function("save", 16, 19), function("validate", 21, 24)];
+        file.classes = vec![class];
+        let mut export = ExportInfo::new(ExportType::ES6Export);
+        export.exported_names = vec!["Store".to_string()];
+        file.exports = vec![export];
+        file.function_calls = vec![
+            call("helper", None, 2),
+            call("save", Some("store"), 3),
+            call("log", Some("console"), 8),
+            call("validate", Some("this"), 17),
+            call("main", None, 27),
+        ];
+
+        let graph = build_call_graph(&[file]);
+
+        assert_eq!(graph.edges["app.js:main"], vec!["app.js:Store.save", "app.js:helper"]);
+        assert_eq!(graph.edges["app.js:helper"], vec!["external:console.log"]);
+        assert_eq!(graph.edges["app.js:Store.save"], vec!["app.js:Store.validate"]);
+        assert_eq!(graph.edges["app.js:<module>"], vec!["app.js:main"]);
+        assert_eq!(graph.external, vec!["console.log"]);
+        assert_eq!(graph.entry_points, vec!["app.js:main", "app.js:<module>"]);
+        assert_eq!(graph.unreferenced, vec!["app.js:unused"]);
+
+        let dot = to_dot(&graph);
+        assert!(dot.starts_with("digraph callgraph {"));
+        assert!(dot.contains("    \"app.js:main\" -> \"app.js:helper\";\n"));
+    }
+
+    #[test]
+    fn test_find_unused_skips_roots_and_downgrades_public_api() {
+        let mut lib = AnalysisResult::new(FileInfo::new(PathBuf::from("lib.py")), Language::Python);
+        lib.functions = vec![function("used", 1, 2), function("dead", 4, 5), function("api", 7, 8), function("test_used", 10, 12)];
+        let mut handler = function("index", 14, 16);
+        handler.metadata.insert("decorators".to_string(), "@app.route('/')".to_string());
+        lib.functions.push(handler);
+        let mut class = ClassInfo::new("Orphan".to_string());
+        class.start_line = 18;
+        class.end_line = 22;
+        class.methods = vec![function("__init__", 19, 20)];
+        lib.classes = vec![class];
+        lib.function_calls = vec![call("used", None, 11)];
+
+        let mut all = ExportInfo::new(ExportType::PythonGlobal);
+        all.exported_names = vec!["api".to_string()];
+        lib.exports.push(all);
+
+        let mut other = AnalysisResult::new(FileInfo::new(PathBuf::from("other.go")), Language::Go);
+        other.functions = vec![function("helper", 1, 3)];
+
+        let unused = find_unused(&[lib, other]);
+
+        let found: Vec<_> = unused.iter().map(|u| (u.kind.as_str(), u.name.as_str(), u.confidence)).collect();
+        assert_eq!(found, vec![
+            ("function", "dead", Confidence::High),
+            ("class", "Orphan", Confidence::High),
+            ("function", "api", Confidence::Low),
+            ("function", "helper", Confidence::Low),
+        ]);
+        assert!(unused[3].note.contains("not recorded"));
+    }
+}
@@ -9,6 +9,7 @@ use std::path::PathBuf;
 
 use crate::core::types::{DirectoryAnalysis, AnalysisConfig};
 use crate::core::session::AnalysisSession;
+use crate::core::output::to_json;
 
 /// Available commands
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -68,7 +69,7 @@ impl CommandResult {
         match self {
             CommandResult::Analysis { analysis, format } => {
                 match format.as_str() {
-                    "json" => Ok(serde_json::to_string_pretty(analysis)?),
+                    "json" => Ok(to_json(analysis)?),
                     _ => anyhow::bail!("Unsupported output format: {}", format),
                 }
             }
@@ -0,0 +1,190 @@
+//! Symbol differences between two files of a session
+//!
+//! Answers "did everything move over?" after splitting or merging files by
+//! hand, from the functions and classes already stored in the session.
+//! Symbols are matched by kind and name, methods as `Class::method`; a name
+//! found in both files with a different signature is reported separately.
+//! Overloads pair up by identical signature first, then in source order.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::core::types::{AnalysisResult, FunctionInfo};
+
+/// A function, class or method of one file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileSymbol {
+    /// `function`, `class` or `method`
+    pub kind: String,
+    /// Methods are `Class::method`
+    pub name: String,
+    pub signature: String,
+    pub line: u32,
+}
+
+/// A symbol in both files whose signature differs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignatureMismatch {
+    pub kind: String,
+    pub name: String,
+    pub signature_a: String,
+    pub signature_b: String,
+    pub line_a: u32,
+    pub line_b: u32,
+}
+
+/// Result of the `compare-files` session command
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileComparison {
+    pub file_a: PathBuf,
+    pub file_b: PathBuf,
+    /// Symbols with the same signature in both files
+    pub common: usize,
+    pub only_in_a: Vec<FileSymbol>,
+    pub only_in_b: Vec<FileSymbol>,
+    pub common_but_different_signature: Vec<SignatureMismatch>,
+}
+
+/// Compare the symbols of two analyzed files
+pub fn compare_files(a: &AnalysisResult, b: &AnalysisResult) -> FileComparison {
+    let mut symbols_b = group(file_symbols(b));
+    let mut comparison = FileComparison {
+        file_a: a.file_info.path.clone(),
+        file_b: b.file_info.path.clone(),
+        common: 0,
+        only_in_a: Vec::new(),
+        only_in_b: Vec::new(),
+        common_but_different_signature: Vec::new(),
+    };
+
+    for (key, mut in_a) in group(file_symbols(a)) {
+        let mut in_b = symbols_b.remove(&key).unwrap_or_default();
+        // Identical signatures first, so an overload is not reported as changed
+        in_a.retain(|symbol| match in_b.iter().position(|other| other.signature == symbol.signature) {
+            Some(index) => {
+                in_b.remove(index);
+                comparison.common += 1;
+                false
+            }
+            None => true,
+        });
+        let paired = in_a.len().min(in_b.len());
+        for (symbol_a, symbol_b) in in_a.drain(..paired).zip(in_b.drain(..paired)) {
+            comparison.common_but_different_signature.push(SignatureMismatch {
+                kind: symbol_a.kind,
+                name: symbol_a.name,
+                signature_a: symbol_a.signature,
+                signature_b: symbol_b.signature,
+                line_a: symbol_a.line,
+                line_b: symbol_b.line,
+            });
+        }
+        comparison.only_in_a.extend(in_a);
+        comparison.only_in_b.extend(in_b);
+    }
+    comparison.only_in_b.extend(symbols_b.into_values().flatten());
+
+    comparison.only_in_a.sort_by_key(|symbol| symbol.line);
+    comparison.only_in_b.sort_by_key(|symbol| symbol.line);
+    comparison.common_but_different_signature.sort_by_key(|mismatch| mismatch.line_a);
+    comparison
+}
+
+/// Symbols keyed by kind and name, each list in source order
+fn group(symbols: Vec<FileSymbol>) -> BTreeMap<(String, String), Vec<FileSymbol>> {
+    let mut groups: BTreeMap<(String, String), Vec<FileSymbol>> = BTreeMap::new();
+    for symbol in symbols {
+        groups.entry((symbol.kind.clone(), symbol.name.clone())).or_default().push(symbol);
+    }
+    groups
+}
+
+/// Classes, their methods and the free functions of `file`
+fn file_symbols(file: &AnalysisResult) -> Vec<FileSymbol> {
+    let mut symbols = Vec::new();
+    for class in &file.classes {
+        let signature = match &class.parent_class {
+            Some(parent) => format!("class {}({})", class.name, parent),
+            None => format!("class {}", class.name),
+        };
+        symbols.push(FileSymbol { kind: "class".to_string(), name: class.name.clone(), signature, line: class.start_line });
+        for method in &class.methods {
+            symbols.push(FileSymbol {
+                kind: "method".to_string(),
+                name: format!("{}::{}", class.name, method.name),
+                signature: function_signature(method),
+                line: method.start_line,
+            });
+        }
+    }
+    for function in file.free_functions() {
+        symbols.push(FileSymbol {
+            kind: "function".to_string(),
+            name: function.name.clone(),
+            signature: function_signature(function),
+            line: function.start_line,
+        });
+    }
+    symbols
+}
+
+/// `name(params) -> return_type`, as impact analysis prints signatures
+fn function_signature(function: &FunctionInfo) -> String {
+    let params = function.parameters.join(", ");
+    match function.metadata.get("return_type") {
+        Some(return_type) => format!("{}({}) -> {}", function.name, params, return_type),
+        None => format!("{}({})", function.name, params),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::{ClassInfo, FileInfo, Language};
+    use crate::core::types::test_support::function;
+
+    fn taking(parameters: &[&str], mut function: FunctionInfo) -> FunctionInfo {
+        function.parameters = parameters.iter().map(|p| p.to_string()).collect();
+        function
+    }
+
+    fn file(path: &str, functions: Vec<FunctionInfo>, classes: Vec<ClassInfo>) -> AnalysisResult {
+        let mut result = AnalysisResult::new(FileInfo::new(PathBuf::from(path)), Language::Python);
+        result.functions = functions;
+        result.classes = classes;
+        result
+    }
+
+    #[test]
+    fn test_compare_files_after_split() {
+        let mut user = ClassInfo::new("User".to_string());
+        user.start_line = 10;
+        user.methods = vec![taking(&["self"], function("save", 11, 11)), taking(&["self"], function("load", 14, 14))];
+        let original = file("models.py", vec![
+            taking(&["url"], function("connect", 1, 1)),
+            taking(&["text"], function("parse", 5, 5)),
+            // Also listed as a method of User
+            taking(&["self"], function("save", 11, 11)),
+        ], vec![user]);
+
+        let mut moved = ClassInfo::new("User".to_string());
+        moved.start_line = 1;
+        moved.methods = vec![taking(&["self", "force"], function("save", 2, 2))];
+        let split = file("user.py", vec![taking(&["url"], function("connect", 8, 8)), taking(&[], function("helper", 12, 12))], vec![moved]);
+
+        let comparison = compare_files(&original, &split);
+        assert_eq!(comparison.common, 2);
+        let names = |symbols: &[FileSymbol]| symbols.iter().map(|s| s.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(&comparison.only_in_a), vec!["parse", "User::load"]);
+        assert_eq!(names(&comparison.only_in_b), vec!["helper"]);
+        assert_eq!(comparison.common_but_different_signature, vec![SignatureMismatch {
+            kind: "method".to_string(),
+            name: "User::save".to_string(),
+            signature_a: "save(self)".to_string(),
+            signature_b: "save(self, force)".to_string(),
+            line_a: 11,
+            line_b: 2,
+        }]);
+    }
+}
@@ -1,9 +1,60 @@
 //! Configuration management for NekoCode Rust
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use crate::core::annotations::DEFAULT_ANNOTATION_TAGS;
+use crate::core::lint::LintThresholds;
+use crate::core::output::to_json;
+
+/// Config file used when `--config` is not given
+pub const DEFAULT_CONFIG_FILE: &str = ".nekocode_config.json";
+
+/// Settable keys and the kind of value each expects. Keys under `custom.`
+/// are free-form and stored in [`Config::custom`].
+const CONFIG_KEYS: &[(&str, ValueKind)] = &[
+    ("general.io_threads", ValueKind::Count),
+    ("general.cpu_threads", ValueKind::Number),
+    ("general.verbose", ValueKind::Bool),
+    ("general.progress", ValueKind::Bool),
+    ("analysis.include_tests", ValueKind::Bool),
+    ("analysis.include_comments", ValueKind::Bool),
+    ("analysis.max_file_size_mb", ValueKind::Count),
+    ("analysis.exclude_patterns", ValueKind::List),
+    ("analysis.annotation_tags", ValueKind::List),
+    ("memory.storage_path", ValueKind::Path),
+    ("memory.max_memories", ValueKind::Count),
+    ("memory.cleanup_days", ValueKind::Number),
+    ("lint.max_function_lines", ValueKind::Count),
+    ("lint.max_file_lines", ValueKind::Count),
+    ("lint.max_parameters", ValueKind::Number),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ValueKind {
+    /// Non-negative integer
+    Number,
+    /// Integer of at least 1
+    Count,
+    Bool,
+    /// Comma-separated list
+    List,
+    Path,
+}
+
+impl ValueKind {
+    fn describe(self) -> &'static str {
+        match self {
+            ValueKind::Number => "a non-negative integer",
+            ValueKind::Count => "an integer of at least 1",
+            ValueKind::Bool => "true or false",
+            ValueKind::List => "a comma-separated list",
+            ValueKind::Path => "a path",
+        }
+    }
+}
 
 /// Application configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +68,10 @@ pub struct Config {
     /// Memory system settings
     pub memory: MemoryConfig,
     
+    /// Limits checked by the `lint` session command
+    #[serde(default)]
+    pub lint: LintThresholds,
+    
     /// Custom key-value pairs
     pub custom: HashMap<String, String>,
 }
@@ -35,6 +90,13 @@ pub struct AnalysisConfig {
     pub include_comments: bool,
     pub max_file_size_mb: u32,
     pub exclude_patterns: Vec<String>,
+    /// Comment tags reported by the `annotations` session command
+    #[serde(default = "default_annotation_tags")]
+    pub annotation_tags: Vec<String>,
+}
+
+fn default_annotation_tags() -> Vec<String> {
+    DEFAULT_ANNOTATION_TAGS.iter().map(|tag| tag.to_string()).collect()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -64,12 +126,14 @@ impl Default for Config {
                     "dist".to_string(),
                     "build".to_string(),
                 ],
+                annotation_tags: default_annotation_tags(),
             },
             memory: MemoryConfig {
                 storage_path: PathBuf::from(".nekocode_memories"),
                 max_memories: 1000,
                 cleanup_days: 30,
             },
+            lint: LintThresholds::default(),
             custom: HashMap::new(),
         }
     }
@@ -83,7 +147,12 @@ pub struct ConfigManager {
 
 impl ConfigManager {
     pub fn new() -> Self {
-        let config_path = PathBuf::from(".nekocode_config.json");
+        Self::with_path(PathBuf::from(DEFAULT_CONFIG_FILE))
+    }
+    
+    /// Use the config file at `config_path`; defaults apply if it is missing
+    /// or unreadable, so the config commands can create or repair it
+    pub fn with_path(config_path: PathBuf) -> Self {
         let config = Self::load_config(&config_path).unwrap_or_default();
         
         Self {
@@ -92,54 +161,192 @@ impl ConfigManager {
         }
     }
     
+    /// Load the config file at `config_path`, as given with `--config`. A
+    /// missing file or one with problems is an error instead of falling
+    /// back to the defaults.
+    pub fn load(config_path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(config_path)
+            .with_context(|| format!("Failed to read config {}", config_path.display()))?;
+        let problems = Self::problems(&content, false);
+        if !problems.is_empty() {
+            anyhow::bail!("{} is not a valid config:\n  {}", config_path.display(), problems.join("\n  "));
+        }
+        
+        Ok(Self {
+            config: serde_json::from_str(&content)?,
+            config_path: config_path.to_path_buf(),
+        })
+    }
+    
     pub fn get(&self) -> &Config {
         &self.config
     }
     
+    pub fn config_path(&self) -> &Path {
+        &self.config_path
+    }
+    
+    /// Set a known key (e.g. `general.verbose`) or a `custom.*` entry and save.
+    /// Unknown keys and values of the wrong type are rejected.
     pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
-        // Parse key path (e.g., "general.verbose", "analysis.include_tests")
-        let parts: Vec<&str> = key.split('.').collect();
-        
-        match parts.as_slice() {
-            ["general", "io_threads"] => {
-                self.config.general.io_threads = value.parse()?;
-            }
-            ["general", "cpu_threads"] => {
-                self.config.general.cpu_threads = value.parse()?;
-            }
-            ["general", "verbose"] => {
-                self.config.general.verbose = value.parse()?;
-            }
-            ["general", "progress"] => {
-                self.config.general.progress = value.parse()?;
+        if let Some(custom_key) = key.strip_prefix("custom.") {
+            if custom_key.is_empty() {
+                anyhow::bail!("Custom keys need a name, e.g. custom.team");
             }
-            ["analysis", "include_tests"] => {
-                self.config.analysis.include_tests = value.parse()?;
-            }
-            ["analysis", "include_comments"] => {
-                self.config.analysis.include_comments = value.parse()?;
+            self.config.custom.insert(custom_key.to_string(), value.to_string());
+            return self.save();
+        }
+        
+        let kind = CONFIG_KEYS.iter()
+            .find(|(name, _)| *name == key)
+            .map(|(_, kind)| *kind)
+            .ok_or_else(|| anyhow::anyhow!(
+                "Unknown configuration key '{}'. Valid keys: {}, custom.<name>",
+                key,
+                CONFIG_KEYS.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(", ")
+            ))?;
+        let invalid = || anyhow::anyhow!("Invalid value '{}' for {}: expected {}", value, key, kind.describe());
+        
+        let number = || value.parse::<u32>().map_err(|_| invalid());
+        let count = || number().and_then(|n| if n >= 1 { Ok(n) } else { Err(invalid()) });
+        let flag = || value.parse::<bool>().map_err(|_| invalid());
+        let list = || -> Vec<String> {
+            value.split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+        
+        match key {
+            "general.io_threads" => self.config.general.io_threads = count()?,
+            "general.cpu_threads" => self.config.general.cpu_threads = number()?,
+            "general.verbose" => self.config.general.verbose = flag()?,
+            "general.progress" => self.config.general.progress = flag()?,
+            "analysis.include_tests" => self.config.analysis.include_tests = flag()?,
+            "analysis.include_comments" => self.config.analysis.include_comments = flag()?,
+            "analysis.max_file_size_mb" => self.config.analysis.max_file_size_mb = count()?,
+            "analysis.exclude_patterns" => self.config.analysis.exclude_patterns = list(),
+            "analysis.annotation_tags" => {
+                let tags = list();
+                if tags.is_empty() {
+                    return Err(invalid());
+                }
+                self.config.analysis.annotation_tags = tags;
             }
-            ["analysis", "max_file_size_mb"] => {
-                self.config.analysis.max_file_size_mb = value.parse()?;
+            "memory.storage_path" => {
+                if value.trim().is_empty() {
+                    return Err(invalid());
+                }
+                self.config.memory.storage_path = PathBuf::from(value);
             }
-            ["memory", "max_memories"] => {
-                self.config.memory.max_memories = value.parse()?;
+            "memory.max_memories" => self.config.memory.max_memories = count()?,
+            "memory.cleanup_days" => self.config.memory.cleanup_days = number()?,
+            "lint.max_function_lines" => self.config.lint.max_function_lines = count()?,
+            "lint.max_file_lines" => self.config.lint.max_file_lines = count()?,
+            "lint.max_parameters" => self.config.lint.max_parameters = number()?,
+            _ => unreachable!("every entry in CONFIG_KEYS is handled"),
+        }
+        
+        self.save()
+    }
+    
+    /// Check the config file for problems: invalid JSON, unknown sections or
+    /// keys, wrongly typed values and out-of-range numbers. A missing file is
+    /// valid (defaults apply).
+    pub fn validate(&self) -> Result<Vec<String>> {
+        if !self.config_path.exists() {
+            return Ok(vec![format!("{} does not exist", self.config_path.display())]);
+        }
+        
+        let content = std::fs::read_to_string(&self.config_path)?;
+        Ok(Self::problems(&content, false))
+    }
+    
+    /// Problems in a config file's content; with `partial`, missing sections
+    /// and keys are allowed because they are filled from another config
+    fn problems(content: &str, partial: bool) -> Vec<String> {
+        let raw: serde_json::Value = match serde_json::from_str(content) {
+            Ok(raw) => raw,
+            Err(e) => return vec![format!("Invalid JSON: {}", e)],
+        };
+        let Some(sections) = raw.as_object() else {
+            return vec!["Top level must be a JSON object".to_string()];
+        };
+        
+        let mut problems = Vec::new();
+        for (section, fields) in sections {
+            if section == "custom" {
+                continue;
             }
-            ["memory", "cleanup_days"] => {
-                self.config.memory.cleanup_days = value.parse()?;
+            let Some(fields) = fields.as_object() else {
+                problems.push(format!("Unknown or malformed section '{}'", section));
+                continue;
+            };
+            for field in fields.keys() {
+                let key = format!("{}.{}", section, field);
+                if !CONFIG_KEYS.iter().any(|(name, _)| *name == key) {
+                    problems.push(format!("Unknown key '{}'", key));
+                }
             }
-            _ => {
-                // Store as custom key-value
-                self.config.custom.insert(key.to_string(), value.to_string());
+        }
+        
+        let raw = if partial { merge_json(serde_json::to_value(Config::default()).unwrap(), raw) } else { raw };
+        match serde_json::from_value::<Config>(raw) {
+            Ok(config) => {
+                for (key, value) in [
+                    ("general.io_threads", config.general.io_threads),
+                    ("analysis.max_file_size_mb", config.analysis.max_file_size_mb),
+                    ("memory.max_memories", config.memory.max_memories),
+                    ("lint.max_function_lines", config.lint.max_function_lines),
+                    ("lint.max_file_lines", config.lint.max_file_lines),
+                ] {
+                    if value == 0 {
+                        problems.push(format!("{} must be at least 1", key));
+                    }
+                }
             }
+            Err(e) => problems.push(format!("Invalid value: {}", e)),
         }
         
-        self.save()?;
-        Ok(())
+        problems
+    }
+    
+    /// Write the full active configuration, defaults included, to `path`
+    pub fn export(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, self.show()?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+    
+    /// Load the config at `path` and save it as the active config. With
+    /// `merge`, only the keys present in the file are changed; otherwise the
+    /// file must be a complete config and replaces the active one. The file
+    /// is validated like `validate` and nothing is saved if it has problems.
+    pub fn import(&mut self, path: &Path, merge: bool) -> Result<()> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let problems = Self::problems(&content, merge);
+        if !problems.is_empty() {
+            anyhow::bail!("{} is not a valid config:\n  {}", path.display(), problems.join("\n  "));
+        }
+        
+        let raw: serde_json::Value = serde_json::from_str(&content)?;
+        self.config = if merge {
+            serde_json::from_value(merge_json(serde_json::to_value(&self.config)?, raw))?
+        } else {
+            serde_json::from_value(raw)?
+        };
+        self.save()
+    }
+    
+    /// Restore and save the default configuration
+    pub fn reset(&mut self) -> Result<()> {
+        self.config = Config::default();
+        self.save()
     }
     
     pub fn show(&self) -> Result<String> {
-        Ok(serde_json::to_string_pretty(&self.config)?)
+        Ok(to_json(&self.config)?)
     }
     
     pub fn save(&self) -> Result<()> {
@@ -148,15 +355,133 @@ impl ConfigManager {
         Ok(())
     }
     
-    fn load_config(path: &PathBuf) -> Result<Config> {
+    fn load_config(path: &Path) -> Result<Config> {
         let content = std::fs::read_to_string(path)?;
         let config: Config = serde_json::from_str(&content)?;
         Ok(config)
     }
 }
 
+/// Overlay the sections of `overlay` onto `base` key by key
+fn merge_json(mut base: serde_json::Value, overlay: serde_json::Value) -> serde_json::Value {
+    if let (Some(base), serde_json::Value::Object(overlay)) = (base.as_object_mut(), overlay) {
+        for (section, fields) in overlay {
+            match (base.get_mut(&section).and_then(|s| s.as_object_mut()), fields) {
+                (Some(existing), serde_json::Value::Object(fields)) => existing.extend(fields),
+                (_, fields) => {
+                    base.insert(section, fields);
+                }
+            }
+        }
+    }
+    base
+}
+
 impl Default for ConfigManager {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    
+    #[test]
+    fn test_set_validates_keys_and_values() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = ConfigManager::with_path(temp_dir.path().join("config.json"));
+        
+        manager.set("general.io_threads", "8").unwrap();
+        manager.set("analysis.exclude_patterns", "vendor, out").unwrap();
+        manager.set("custom.team", "core").unwrap();
+        manager.set("analysis.annotation_tags", "TODO,BUG").unwrap();
+        assert_eq!(manager.get().general.io_threads, 8);
+        assert_eq!(manager.get().analysis.exclude_patterns, vec!["vendor", "out"]);
+        assert_eq!(manager.get().analysis.annotation_tags, vec!["TODO", "BUG"]);
+        assert!(manager.set("analysis.annotation_tags", " , ").is_err());
+        manager.set("lint.max_function_lines", "80").unwrap();
+        assert_eq!(manager.get().lint.max_function_lines, 80);
+        assert!(manager.set("lint.max_file_lines", "0").is_err());
+        assert_eq!(manager.get().custom["team"], "core");
+        
+        let err = manager.set("general.io_threads", "many").unwrap_err().to_string();
+        assert!(err.contains("expected an integer"));
+        assert!(manager.set("general.io_threads", "0").is_err());
+        assert!(manager.set("general.verbose", "yes").is_err());
+        
+        let err = manager.set("general.thread", "4").unwrap_err().to_string();
+        assert!(err.contains("Valid keys"));
+        assert!(err.contains("general.io_threads"));
+        
+        // Rejected values are not persisted
+        let reloaded = ConfigManager::with_path(manager.config_path().to_path_buf());
+        assert_eq!(reloaded.get().general.io_threads, 8);
+        assert!(reloaded.validate().unwrap().is_empty());
+    }
+    
+    #[test]
+    fn test_validate_reports_problems() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.json");
+        let manager = ConfigManager::with_path(path.clone());
+        assert!(manager.validate().unwrap()[0].ends_with("does not exist"));
+        assert!(ConfigManager::load(&path).is_err());
+        manager.save().unwrap();
+        assert!(manager.validate().unwrap().is_empty());
+        assert!(ConfigManager::load(&path).is_ok());
+        
+        let mut raw = serde_json::to_value(Config::default()).unwrap();
+        raw["general"]["threads"] = serde_json::json!(4);
+        raw["general"]["io_threads"] = serde_json::json!(0);
+        std::fs::write(&path, raw.to_string()).unwrap();
+        let problems = manager.validate().unwrap();
+        assert_eq!(problems.len(), 2);
+        assert!(problems.iter().any(|p| p.contains("general.threads")));
+        assert!(problems.iter().any(|p| p.contains("general.io_threads")));
+        let err = ConfigManager::load(&path).err().unwrap().to_string();
+        assert!(err.contains("general.threads"));
+        
+        raw["general"]["io_threads"] = serde_json::json!("four");
+        std::fs::write(&path, raw.to_string()).unwrap();
+        assert!(manager.validate().unwrap().iter().any(|p| p.starts_with("Invalid value")));
+        
+        std::fs::write(&path, "{ not json").unwrap();
+        assert!(manager.validate().unwrap()[0].starts_with("Invalid JSON"));
+    }
+    
+    #[test]
+    fn test_export_import_and_reset() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut shared = ConfigManager::with_path(temp_dir.path().join("shared.json"));
+        shared.set("general.io_threads", "8").unwrap();
+        shared.set("custom.team", "core").unwrap();
+        let exported = temp_dir.path().join("nekocode_config.json");
+        shared.export(&exported).unwrap();
+        
+        let mut local = ConfigManager::with_path(temp_dir.path().join("local.json"));
+        local.set("general.verbose", "true").unwrap();
+        local.import(&exported, false).unwrap();
+        assert_eq!(local.get().general.io_threads, 8);
+        assert!(!local.get().general.verbose);
+        
+        // A partial file only changes the keys it lists when merged
+        let partial = temp_dir.path().join("partial.json");
+        std::fs::write(&partial, r#"{"general": {"verbose": true}, "custom": {"owner": "neko"}}"#).unwrap();
+        assert!(local.import(&partial, false).is_err());
+        local.import(&partial, true).unwrap();
+        assert!(local.get().general.verbose);
+        assert_eq!(local.get().general.io_threads, 8);
+        assert_eq!(local.get().custom.len(), 2);
+        
+        std::fs::write(&partial, r#"{"general": {"threads": 4}}"#).unwrap();
+        let err = local.import(&partial, true).unwrap_err().to_string();
+        assert!(err.contains("general.threads"));
+        
+        local.reset().unwrap();
+        let reloaded = ConfigManager::with_path(local.config_path().to_path_buf());
+        assert_eq!(reloaded.get().general.io_threads, 4);
+        assert!(reloaded.get().custom.is_empty());
+    }
+}
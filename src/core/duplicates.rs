@@ -0,0 +1,247 @@
+//! Duplicate code detection
+//!
+//! Function bodies are tokenized with identifiers, numbers and string literals
+//! normalized away, so copies that only differ in naming still match. Each
+//! body is then reduced to a set of hashed token shingles and functions are
+//! grouped when the Jaccard similarity of their shingle sets reaches the
+//! threshold.
+
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+
+/// Default similarity a pair of functions needs to be reported
+pub const DEFAULT_SIMILARITY_THRESHOLD: f64 = 0.9;
+
+/// Functions with fewer normalized tokens than this are ignored; short
+/// getters and one-liners are trivially similar and not worth reporting
+pub const MIN_FUNCTION_TOKENS: usize = 20;
+
+/// Number of consecutive tokens hashed together into one shingle
+const SHINGLE_SIZE: usize = 5;
+
+/// Keywords kept verbatim; every other identifier is normalized
+const KEYWORDS: &[&str] = &[
+    "if", "else", "elif", "for", "while", "do", "loop", "match", "switch", "case",
+    "default", "return", "break", "continue", "try", "catch", "except", "finally",
+    "throw", "raise", "fn", "func", "function", "def", "lambda", "let", "const",
+    "var", "mut", "new", "class", "struct", "async", "await", "yield", "in", "of",
+    "and", "or", "not", "is", "true", "false", "null", "nil", "None", "self", "this",
+];
+
+/// A function whose body is compared against the others
+#[derive(Debug, Clone)]
+pub struct FunctionSource {
+    pub name: String,
+    pub file: PathBuf,
+    pub start_line: u32,
+    pub end_line: u32,
+    pub source: String,
+}
+
+/// Location of one member of a duplicate cluster
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateLocation {
+    pub name: String,
+    pub file: PathBuf,
+    pub start_line: u32,
+    pub end_line: u32,
+}
+
+/// A group of functions whose bodies are near-identical
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateCluster {
+    /// Lowest pairwise similarity that linked this cluster together
+    pub similarity: f64,
+    pub functions: Vec<DuplicateLocation>,
+}
+
+/// Split source into normalized tokens
+pub fn normalize_tokens(source: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_alphabetic() || c == '_' || c == '$' {
+            let mut word = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_alphanumeric() || c == '_' || c == '$' {
+                    word.push(c);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            if KEYWORDS.contains(&word.as_str()) {
+                tokens.push(word);
+            } else {
+                tokens.push("ID".to_string());
+            }
+        } else if c.is_ascii_digit() {
+            while chars.peek().is_some_and(|c| c.is_alphanumeric() || *c == '.' || *c == '_') {
+                chars.next();
+            }
+            tokens.push("NUM".to_string());
+        } else if c == '"' || c == '\'' || c == '`' {
+            let quote = c;
+            chars.next();
+            while let Some(c) = chars.next() {
+                if c == '\\' {
+                    chars.next();
+                } else if c == quote {
+                    break;
+                }
+            }
+            tokens.push("STR".to_string());
+        } else {
+            tokens.push(c.to_string());
+            chars.next();
+        }
+    }
+
+    tokens
+}
+
+/// Hash every window of `SHINGLE_SIZE` tokens
+fn shingles(tokens: &[String]) -> HashSet<u64> {
+    let size = SHINGLE_SIZE.min(tokens.len()).max(1);
+    tokens
+        .windows(size)
+        .map(|window| {
+            let mut hasher = DefaultHasher::new();
+            window.hash(&mut hasher);
+            hasher.finish()
+        })
+        .collect()
+}
+
+fn jaccard(a: &HashSet<u64>, b: &HashSet<u64>) -> f64 {
+    let intersection = a.intersection(b).count();
+    let union = a.len() + b.len() - intersection;
+    if union == 0 {
+        0.0
+    } else {
+        intersection as f64 / union as f64
+    }
+}
+
+fn find_root(parents: &mut [usize], mut index: usize) -> usize {
+    while parents[index] != index {
+        parents[index] = parents[parents[index]];
+        index = parents[index];
+    }
+    index
+}
+
+/// Group functions whose normalized bodies are at least `threshold` similar
+pub fn find_duplicates(functions: &[FunctionSource], threshold: f64) -> Vec<DuplicateCluster> {
+    let candidates: Vec<(&FunctionSource, HashSet<u64>)> = functions
+        .iter()
+        .filter_map(|function| {
+            let tokens = normalize_tokens(&function.source);
+            (tokens.len() >= MIN_FUNCTION_TOKENS).then(|| (function, shingles(&tokens)))
+        })
+        .collect();
+
+    let mut parents: Vec<usize> = (0..candidates.len()).collect();
+    let mut cluster_similarity: Vec<f64> = vec![1.0; candidates.len()];
+
+    for i in 0..candidates.len() {
+        for j in (i + 1)..candidates.len() {
+            let (a, b) = (&candidates[i].1, &candidates[j].1);
+            // Jaccard can never exceed the size ratio, so skip hopeless pairs
+            let (small, large) = (a.len().min(b.len()), a.len().max(b.len()));
+            if (small as f64) < threshold * large as f64 {
+                continue;
+            }
+
+            let similarity = jaccard(a, b);
+            if similarity >= threshold {
+                let (root_i, root_j) = (find_root(&mut parents, i), find_root(&mut parents, j));
+                let merged = cluster_similarity[root_i].min(cluster_similarity[root_j]).min(similarity);
+                parents[root_j] = root_i;
+                cluster_similarity[root_i] = merged;
+            }
+        }
+    }
+
+    let mut groups: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for index in 0..candidates.len() {
+        let root = find_root(&mut parents, index);
+        groups.entry(root).or_default().push(index);
+    }
+
+    let mut clusters: Vec<DuplicateCluster> = groups
+        .into_iter()
+        .filter(|(_, members)| members.len() > 1)
+        .map(|(root, members)| DuplicateCluster {
+            similarity: cluster_similarity[root],
+            functions: members
+                .into_iter()
+                .map(|index| {
+                    let function = candidates[index].0;
+                    DuplicateLocation {
+                        name: function.name.clone(),
+                        file: function.file.clone(),
+                        start_line: function.start_line,
+                        end_line: function.end_line,
+                    }
+                })
+                .collect(),
+        })
+        .collect();
+
+    clusters.sort_by_key(|cluster| std::cmp::Reverse(cluster.functions.len()));
+    clusters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn function(name: &str, source: &str) -> FunctionSource {
+        FunctionSource {
+            name: name.to_string(),
+            file: PathBuf::from(format!("{}.js", name)),
+            start_line: 1,
+            end_line: source.lines().count() as u32,
+            source: source.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_normalize_tokens_ignores_names_and_whitespace() {
+        let a = normalize_tokens("function add(a, b) { return a + b * 2; }");
+        let b = normalize_tokens("function  sum(x,y)\n{\n  return x + y * 10;\n}");
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_find_duplicates_groups_renamed_copies() {
+        let original = "function total(items) {\n  let sum = 0;\n  for (const item of items) {\n    if (item.price > 0) { sum += item.price * item.qty; }\n  }\n  return sum;\n}";
+        let renamed = "function grandTotal(rows) {\n  let acc = 0;\n  for (const row of rows) {\n    if (row.cost > 0) { acc += row.cost * row.count; }\n  }\n  return acc;\n}";
+        let unrelated = "function greet(user) {\n  const message = 'Hello, ' + user.name;\n  console.log(message);\n  return message.length;\n}";
+
+        let functions = vec![
+            function("total", original),
+            function("grandTotal", renamed),
+            function("greet", unrelated),
+        ];
+        let clusters = find_duplicates(&functions, DEFAULT_SIMILARITY_THRESHOLD);
+
+        assert_eq!(clusters.len(), 1);
+        let names: Vec<_> = clusters[0].functions.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["total", "grandTotal"]);
+        assert_eq!(clusters[0].similarity, 1.0);
+    }
+
+    #[test]
+    fn test_short_functions_are_ignored() {
+        let functions = vec![function("a", "function a() { return 1; }"), function("b", "function b() { return 2; }")];
+        assert!(find_duplicates(&functions, 0.5).is_empty());
+    }
+}
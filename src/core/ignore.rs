@@ -0,0 +1,215 @@
+//! `.nekocodeignore` support
+//!
+//! The ignore file lives at the analysis root and uses gitignore syntax:
+//! `#` comments, `!` negation, a trailing `/` for directory-only rules, and a
+//! leading or inner `/` to anchor a pattern to the root. `*` and `?` never
+//! cross a `/`, while `**` matches any number of directories.
+//!
+//! As in git, a file inside an ignored directory cannot be re-included.
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the ignore file looked up at the analysis root
+pub const IGNORE_FILE_NAME: &str = ".nekocodeignore";
+
+#[derive(Debug, Clone)]
+struct IgnoreRule {
+    regex: Regex,
+    negated: bool,
+    dir_only: bool,
+}
+
+/// Parsed `.nekocodeignore` rules, evaluated relative to `root`
+#[derive(Debug, Clone)]
+pub struct IgnoreFile {
+    root: PathBuf,
+    rules: Vec<IgnoreRule>,
+}
+
+impl IgnoreFile {
+    /// Load `.nekocodeignore` from `root`; `None` if there is no such file
+    pub fn load(root: &Path) -> Result<Option<Self>> {
+        let path = root.join(IGNORE_FILE_NAME);
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(root, &content)
+            .with_context(|| format!("Invalid pattern in {}", path.display()))
+            .map(Some)
+    }
+
+    /// Parse ignore rules from the content of an ignore file
+    pub fn parse(root: &Path, content: &str) -> Result<Self> {
+        let mut rules = Vec::new();
+
+        for line in content.lines() {
+            let line = line.trim_end();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (negated, pattern) = match line.strip_prefix('!') {
+                Some(rest) => (true, rest),
+                None => (false, line.strip_prefix('\\').unwrap_or(line)),
+            };
+            let (dir_only, pattern) = match pattern.strip_suffix('/') {
+                Some(rest) => (true, rest),
+                None => (false, pattern),
+            };
+            if pattern.is_empty() {
+                continue;
+            }
+
+            let regex = Regex::new(&glob_to_regex(pattern))
+                .with_context(|| format!("Invalid ignore pattern: {}", line))?;
+            rules.push(IgnoreRule { regex, negated, dir_only });
+        }
+
+        Ok(Self { root: root.to_path_buf(), rules })
+    }
+
+    /// Check whether `path` is ignored, either directly or through one of its
+    /// parent directories. Paths outside the root are never ignored.
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return false;
+        };
+        let components: Vec<String> = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect();
+
+        (1..=components.len()).any(|len| {
+            let candidate = components[..len].join("/");
+            let candidate_is_dir = len < components.len() || is_dir;
+            self.matches(&candidate, candidate_is_dir)
+        })
+    }
+
+    /// Evaluate rules against one root-relative path; the last match wins
+    fn matches(&self, relative: &str, is_dir: bool) -> bool {
+        let mut ignored = false;
+        for rule in &self.rules {
+            if rule.dir_only && !is_dir {
+                continue;
+            }
+            if rule.regex.is_match(relative) {
+                ignored = !rule.negated;
+            }
+        }
+        ignored
+    }
+}
+
+/// Translate a gitignore glob into an anchored regex over `/`-separated paths
+fn glob_to_regex(pattern: &str) -> String {
+    // A slash anywhere but the end anchors the pattern to the root
+    let anchored = pattern.contains('/');
+    let pattern = pattern.strip_prefix('/').unwrap_or(pattern);
+
+    let mut regex = String::from(if anchored { "^" } else { "^(?:.*/)?" });
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                let at_segment_start = i == 0 || chars[i - 1] == '/';
+                if at_segment_start && chars.get(i + 2) == Some(&'/') {
+                    // `**/` matches zero or more directories
+                    regex.push_str("(?:.*/)?");
+                    i += 3;
+                } else {
+                    regex.push_str(".*");
+                    i += 2;
+                }
+            }
+            '*' => {
+                regex.push_str("[^/]*");
+                i += 1;
+            }
+            '?' => {
+                regex.push_str("[^/]");
+                i += 1;
+            }
+            '[' => {
+                match chars[i..].iter().position(|&c| c == ']') {
+                    Some(end) if end > 1 => {
+                        let class: String = chars[i + 1..i + end].iter().collect();
+                        let class = class.strip_prefix('!').map(|rest| format!("^{}", rest)).unwrap_or(class);
+                        regex.push('[');
+                        regex.push_str(&class.replace('\\', "\\\\"));
+                        regex.push(']');
+                        i += end + 1;
+                    }
+                    _ => {
+                        regex.push_str("\\[");
+                        i += 1;
+                    }
+                }
+            }
+            '\\' if i + 1 < chars.len() => {
+                regex.push_str(&regex::escape(&chars[i + 1].to_string()));
+                i += 2;
+            }
+            c => {
+                regex.push_str(&regex::escape(&c.to_string()));
+                i += 1;
+            }
+        }
+    }
+
+    regex.push('$');
+    regex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ignore(content: &str) -> IgnoreFile {
+        IgnoreFile::parse(Path::new("/repo"), content).unwrap()
+    }
+
+    #[test]
+    fn test_unanchored_and_anchored_patterns() {
+        let rules = ignore("*.min.js\n/vendor\ndocs/generated/\n");
+
+        assert!(rules.is_ignored(Path::new("/repo/app.min.js"), false));
+        assert!(rules.is_ignored(Path::new("/repo/web/lib/app.min.js"), false));
+        assert!(rules.is_ignored(Path::new("/repo/vendor/lib.js"), false));
+        assert!(!rules.is_ignored(Path::new("/repo/src/vendor/lib.js"), false));
+        assert!(rules.is_ignored(Path::new("/repo/docs/generated/api.js"), false));
+        assert!(!rules.is_ignored(Path::new("/repo/src/app.js"), false));
+    }
+
+    #[test]
+    fn test_no_substring_matching() {
+        let rules = ignore("test/\n");
+
+        assert!(rules.is_ignored(Path::new("/repo/packages/a/test/unit.js"), false));
+        assert!(!rules.is_ignored(Path::new("/repo/packages/latest/index.js"), false));
+    }
+
+    #[test]
+    fn test_negation_and_double_star() {
+        let rules = ignore("# generated code\n**/gen/**\n*.js\n!keep/this.js\n");
+
+        assert!(rules.is_ignored(Path::new("/repo/a/gen/b/c.rs"), false));
+        assert!(rules.is_ignored(Path::new("/repo/keep/other.js"), false));
+        assert!(!rules.is_ignored(Path::new("/repo/keep/this.js"), false));
+        assert!(!rules.is_ignored(Path::new("/repo/src/main.rs"), false));
+    }
+
+    #[test]
+    fn test_missing_file_is_not_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(IgnoreFile::load(dir.path()).unwrap().is_none());
+    }
+}
@@ -6,15 +6,24 @@
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use regex::Regex;
+use std::cell::OnceCell;
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 use chrono::{DateTime, Utc};
 
-use crate::core::types::{AnalysisResult, DirectoryAnalysis, FunctionInfo, ClassInfo, Language};
+use crate::core::types::{relative_to_root, AnalysisConfig, AnalysisResult, DirectoryAnalysis, ExportType, FunctionInfo, FunctionCall, ClassInfo, Language};
+use crate::core::patch::{parse_unified_diff, FilePatch};
+use crate::core::output::to_json;
+use crate::core::callgraph::receiver_names_class;
+use crate::core::sarif::{sarif_log, SarifResult, SarifRule};
+use crate::core::symbol_index::SymbolIndex;
 use crate::core::session::AnalysisSession;
 
-/// Risk levels for impact assessment
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// Risk levels for impact assessment, ordered from low to high
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema)]
 pub enum RiskLevel {
     #[serde(rename = "low")]
     Low,
@@ -25,6 +34,16 @@ pub enum RiskLevel {
 }
 
 impl RiskLevel {
+    /// `low`, `medium` or `high`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "low" => Some(RiskLevel::Low),
+            "medium" => Some(RiskLevel::Medium),
+            "high" => Some(RiskLevel::High),
+            _ => None,
+        }
+    }
+    
     pub fn emoji(&self) -> &'static str {
         match self {
             RiskLevel::Low => "🟢",
@@ -43,7 +62,7 @@ impl RiskLevel {
 }
 
 /// Type of change detected
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub enum ChangeType {
     #[serde(rename = "function_added")]
     FunctionAdded,
@@ -79,7 +98,7 @@ impl ChangeType {
 }
 
 /// Information about a symbol that has changed
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ChangedSymbol {
     pub name: String,
     pub symbol_type: String, // "function" or "class"
@@ -88,29 +107,174 @@ pub struct ChangedSymbol {
     pub change_type: ChangeType,
     pub signature_before: Option<String>,
     pub signature_after: Option<String>,
+    /// Parameter-level breakdown of a signature change
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature_diff: Option<SignatureDiff>,
     pub references: Vec<SymbolReference>,
     pub risk_level: RiskLevel,
     pub breaking_change: bool,
 }
 
+/// What changed between two signatures of a function. Parameter types are
+/// only compared when the analyzer reports them (`name: Type`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct SignatureDiff {
+    pub added_parameters: Vec<String>,
+    pub removed_parameters: Vec<String>,
+    /// The parameters both versions share are in a different order
+    pub reordered: bool,
+    pub type_changes: Vec<ParameterTypeChange>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub return_type: Option<ReturnTypeChange>,
+}
+
+/// A parameter kept under the same name but with a different type
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ParameterTypeChange {
+    pub name: String,
+    pub before: String,
+    pub after: String,
+}
+
+/// `None` means the function had no return type annotation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ReturnTypeChange {
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+impl SignatureDiff {
+    /// Compare the parameters and return types of two versions of a function
+    /// written in `language`; `None` when nothing the analyzers report differs
+    pub fn between(before: &FunctionInfo, after: &FunctionInfo, language: Language) -> Option<Self> {
+        let old: Vec<_> = before.parameters.iter().map(|p| split_parameter(p, language)).collect();
+        let new: Vec<_> = after.parameters.iter().map(|p| split_parameter(p, language)).collect();
+        let find = |params: &[(String, Option<String>)], name: &str| params.iter().find(|(n, _)| n == name).cloned();
+        
+        let added_parameters = new.iter().filter(|(name, _)| find(&old, name).is_none()).map(|(name, _)| name.clone()).collect();
+        let removed_parameters = old.iter().filter(|(name, _)| find(&new, name).is_none()).map(|(name, _)| name.clone()).collect();
+        
+        let kept_old: Vec<_> = old.iter().filter(|(name, _)| find(&new, name).is_some()).map(|(name, _)| name).collect();
+        let kept_new: Vec<_> = new.iter().filter(|(name, _)| find(&old, name).is_some()).map(|(name, _)| name).collect();
+        
+        let type_changes = old.iter()
+            .filter_map(|(name, before)| {
+                let (_, after) = find(&new, name)?;
+                match (before, after) {
+                    (Some(before), Some(after)) if *before != after => Some(ParameterTypeChange {
+                        name: name.clone(),
+                        before: before.clone(),
+                        after,
+                    }),
+                    _ => None,
+                }
+            })
+            .collect();
+        
+        let return_before = before.metadata.get("return_type");
+        let return_after = after.metadata.get("return_type");
+        let return_type = (return_before != return_after).then(|| ReturnTypeChange {
+            before: return_before.cloned(),
+            after: return_after.cloned(),
+        });
+        
+        let diff = Self { added_parameters, removed_parameters, reordered: kept_old != kept_new, type_changes, return_type };
+        (diff != Self::default()).then_some(diff)
+    }
+    
+    /// One line per change, for reports
+    pub fn describe(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        lines.extend(self.added_parameters.iter().map(|name| format!("added parameter `{}`", name)));
+        lines.extend(self.removed_parameters.iter().map(|name| format!("removed parameter `{}`", name)));
+        if self.reordered {
+            lines.push("reordered parameters".to_string());
+        }
+        lines.extend(self.type_changes.iter().map(|change| {
+            format!("changed type of `{}` `{}` → `{}`", change.name, change.before, change.after)
+        }));
+        if let Some(change) = &self.return_type {
+            lines.push(match (&change.before, &change.after) {
+                (Some(before), Some(after)) => format!("changed return type `{}` → `{}`", before, after),
+                (None, Some(after)) => format!("added return type `{}`", after),
+                (Some(before), None) => format!("removed return type `{}`", before),
+                (None, None) => unreachable!("an unchanged return type is not recorded"),
+            });
+        }
+        lines
+    }
+}
+
+/// Split a parameter as the analyzer for `language` reports it into its
+/// name and type: `name: Type` in Python, JavaScript, TypeScript, Rust and
+/// Kotlin, `name Type` in Go and `Type name` in the C family and Java. A
+/// `= default` is dropped.
+fn split_parameter(parameter: &str, language: Language) -> (String, Option<String>) {
+    let declaration = parameter.split('=').next().unwrap_or(parameter).trim();
+    match language {
+        Language::Go => match declaration.split_once(char::is_whitespace) {
+            Some((name, ty)) => (name.to_string(), Some(ty.trim().to_string())),
+            None => (declaration.to_string(), None),
+        },
+        Language::Cpp | Language::C | Language::CSharp | Language::Java => {
+            match declaration.rsplit_once(char::is_whitespace) {
+                Some((ty, name)) => {
+                    // `int *p` and `Point &p`: the sigils belong to the type
+                    let sigils = name.len() - name.trim_start_matches(['*', '&']).len();
+                    let (sigils, name) = name.split_at(sigils);
+                    (name.to_string(), Some(format!("{}{}", ty.trim(), sigils)))
+                }
+                None => (declaration.to_string(), None),
+            }
+        }
+        _ => match declaration.split_once(':') {
+            Some((name, ty)) => (name.trim().to_string(), Some(ty.trim().to_string())),
+            None => (declaration.to_string(), None),
+        },
+    }
+}
+
 /// Reference to a symbol in the codebase
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SymbolReference {
     pub file_path: PathBuf,
     pub line_number: u32,
+    /// 0-based column, when the analyzer reports one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column: Option<u32>,
     pub context: String, // surrounding code context
     pub usage_type: String, // "call", "declaration", "import", etc.
+    /// Last commit touching the line (`--blame`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blame: Option<BlameInfo>,
+}
+
+/// Author and commit that last changed a line, from `git blame`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct BlameInfo {
+    pub author: String,
+    pub commit: String,
+}
+
+impl SymbolReference {
+    /// `file:line`, or `file:line:column` (1-based, as editors expect) when the column is known
+    pub fn location(&self) -> String {
+        match self.column {
+            Some(column) => format!("{}:{}:{}", self.file_path.display(), self.line_number, column + 1),
+            None => format!("{}:{}", self.file_path.display(), self.line_number),
+        }
+    }
 }
 
 /// Circular dependency information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CircularDependency {
     pub files: Vec<PathBuf>,
     pub description: String,
 }
 
 /// Complete impact analysis result
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ImpactAnalysisResult {
     pub analysis_path: PathBuf,
     pub modified_files: Vec<PathBuf>,
@@ -125,13 +289,57 @@ pub struct ImpactAnalysisResult {
     pub generated_at: DateTime<Utc>,
 }
 
+impl ImpactAnalysisResult {
+    /// Report every file relative to `analysis_path`, or as a canonical full
+    /// path when `absolute` is set. Analysis results carry the analyzed path as
+    /// a prefix while git reports paths relative to it; this makes them agree.
+    pub fn normalize_paths(&mut self, absolute: bool) {
+        let root = self.analysis_path.clone();
+        let base = root.canonicalize().ok()
+            .map(|canonical| if canonical.is_file() { canonical.parent().map(Path::to_path_buf).unwrap_or(canonical) } else { canonical });
+        let normalize = |path: &mut PathBuf| {
+            let relative = relative_to_root(path, &root);
+            *path = match &base {
+                Some(base) if absolute && relative.is_relative() => base.join(relative),
+                _ => relative,
+            };
+        };
+        
+        self.modified_files.iter_mut().for_each(normalize);
+        self.affected_files.iter_mut().for_each(normalize);
+        for symbol in &mut self.changed_symbols {
+            normalize(&mut symbol.file_path);
+            symbol.references.iter_mut().for_each(|reference| normalize(&mut reference.file_path));
+        }
+        for dependency in &mut self.circular_dependencies {
+            dependency.files.iter_mut().for_each(normalize);
+        }
+        for change in &mut self.complexity_change.file_changes {
+            normalize(&mut change.file_path);
+        }
+    }
+}
+
 /// Complexity change information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ComplexityChange {
     pub before_avg: f64,
     pub after_avg: f64,
     pub change_delta: f64,
     pub complexity_increased: bool,
+    #[serde(default)]
+    pub file_changes: Vec<FileComplexityChange>,
+}
+
+/// Cyclomatic complexity of a single changed file before and after
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FileComplexityChange {
+    pub file_path: PathBuf,
+    /// `None` if the file did not exist at the compare ref
+    pub before: Option<u32>,
+    /// `None` if the file was deleted
+    pub after: Option<u32>,
+    pub delta: i64,
 }
 
 /// Impact analyzer configuration
@@ -140,8 +348,19 @@ pub struct ImpactConfig {
     pub include_tests: bool,
     pub compare_ref: Option<String>,
     pub skip_circular: bool,
+    /// Overall risk at which `analyze-impact` exits with status 2
     pub risk_threshold: RiskLevel,
     pub verbose: bool,
+    /// Guess changes from naming patterns instead of comparing with git (for non-git directories)
+    pub heuristic: bool,
+    /// Attribute each reference to the author who last changed its line
+    pub blame: bool,
+    /// Files analyzed concurrently; 0 means one per CPU
+    pub threads: usize,
+    /// Report full file paths instead of paths relative to the analyzed path
+    pub absolute_paths: bool,
+    /// Unified diff to take changed files and lines from instead of git
+    pub diff: Option<String>,
 }
 
 impl Default for ImpactConfig {
@@ -150,8 +369,13 @@ impl Default for ImpactConfig {
             include_tests: false,
             compare_ref: None,
             skip_circular: false,
-            risk_threshold: RiskLevel::Low,
+            risk_threshold: RiskLevel::High,
             verbose: false,
+            heuristic: false,
+            blame: false,
+            threads: 0,
+            absolute_paths: false,
+            diff: None,
         }
     }
 }
@@ -174,45 +398,66 @@ impl ImpactAnalyzer {
             println!("🔍 Starting impact analysis for: {}", path.display());
         }
         
-        // Perform analysis based on git comparison mode
-        let (current_analysis, changed_files_for_detection) = if let Some(ref compare_ref) = self.config.compare_ref {
+        let patches = self.config.diff.as_deref().map(parse_unified_diff);
+        
+        // Without an explicit ref, compare the working tree against HEAD unless heuristics or a diff were given
+        let compare_ref = match self.config.compare_ref {
+            Some(ref compare_ref) => Some(compare_ref.as_str()),
+            None if self.config.heuristic || patches.is_some() => None,
+            None => Some("HEAD"),
+        };
+        
+        let current_analysis = self.analyze_current_state(path).await?;
+        
+        let (changed_files_for_detection, changed_symbols) = if let Some(patches) = &patches {
+            if self.config.verbose {
+                println!("📊 Reading changes from a diff of {} files", patches.len());
+            }
+            let changed_files = patches.iter()
+                .map(|patch| patched_file(&current_analysis, patch).map_or_else(|| patch.path.clone(), |file| file.file_info.path.clone()))
+                .collect();
+            (changed_files, self.detect_changed_symbols_in_patches(&current_analysis, patches))
+        } else if let Some(compare_ref) = compare_ref {
             if self.config.verbose {
                 println!("📊 Comparing against git reference: {}", compare_ref);
             }
-            let git_changed_files = self.get_changed_files_from_git(path, compare_ref)?;
+            let git_changed_files = self.get_changed_files_from_git(path, compare_ref)
+                .with_context(|| if self.config.compare_ref.is_none() {
+                    "Failed to compare with HEAD. Pass --compare-ref, or use --heuristic outside a git repository".to_string()
+                } else {
+                    format!("Failed to compare with {}", compare_ref)
+                })?;
             
             if git_changed_files.is_empty() {
                 if self.config.verbose {
-                    println!("📄 No changed files found, analyzing all files");
+                    println!("📄 No changed files found");
                 }
-                (self.analyze_current_state(path).await?, Vec::new())
+                (Vec::new(), Vec::new())
             } else {
                 if self.config.verbose {
                     println!("🔍 Git mode: Analyzing all files for references, detecting changes in {} files", git_changed_files.len());
                 }
-                // Analyze all files for complete reference graph, but track changed files
-                let analysis = self.analyze_current_state(path).await?;
-                (analysis, git_changed_files)
+                let changed_symbols = self.detect_changed_symbols_in_files(&current_analysis, &git_changed_files, compare_ref).await?;
+                (git_changed_files, changed_symbols)
             }
         } else {
             if self.config.verbose {
-                println!("🔍 Analyzing all files (no git comparison)");
+                println!("🔍 Heuristic mode: guessing changed symbols from naming patterns");
             }
-            (self.analyze_current_state(path).await?, Vec::new())
-        };
-        
-        // Detect changes (either in specific files from git, or simulated for all files)
-        let changed_symbols = if !changed_files_for_detection.is_empty() {
-            // Git mode: detect actual deletions and changes using my improved implementation
-            self.detect_changed_symbols_in_files(&current_analysis, &changed_files_for_detection).await?
-        } else {
-            self.detect_changed_symbols(&current_analysis)?
+            (Vec::new(), self.detect_changed_symbols(&current_analysis)?)
         };
         
         // Find references for changed symbols
+        let index = SymbolIndex::build(&current_analysis.files);
         let mut symbols_with_refs = Vec::new();
+        let mut blame = BlameCache::default();
         for mut symbol in changed_symbols {
-            symbol.references = self.find_symbol_references(&symbol, &current_analysis)?;
+            symbol.references = self.find_symbol_references(&symbol, &current_analysis, &index)?;
+            if self.config.blame {
+                for reference in &mut symbol.references {
+                    reference.blame = blame.line(&reference.file_path, reference.line_number);
+                }
+            }
             symbol.risk_level = self.assess_risk_level(&symbol);
             symbols_with_refs.push(symbol);
         }
@@ -236,17 +481,17 @@ impl ImpactAnalyzer {
             .map(|s| s.references.len())
             .sum::<usize>() as u32;
         
-        // Calculate complexity changes (simplified for initial implementation)
-        let complexity_change = self.calculate_complexity_change(&current_analysis);
+        // Compare complexity of the changed files before and after
+        let complexity_change = self.calculate_complexity_change(&current_analysis, &changed_files_for_detection, compare_ref).await;
         
         let analysis_time_ms = start_time.elapsed().as_millis() as u64;
         
-        Ok(ImpactAnalysisResult {
+        let mut result = ImpactAnalysisResult {
             analysis_path: path.to_path_buf(),
-            modified_files: if !changed_files_for_detection.is_empty() { 
-                changed_files_for_detection 
-            } else { 
-                vec![path.to_path_buf()] 
+            modified_files: if compare_ref.is_some() || patches.is_some() {
+                changed_files_for_detection
+            } else {
+                vec![path.to_path_buf()]
             },
             changed_symbols: symbols_with_refs,
             affected_files,
@@ -257,12 +502,17 @@ impl ImpactAnalyzer {
             complexity_change,
             analysis_time_ms,
             generated_at: Utc::now(),
-        })
+        };
+        result.normalize_paths(self.config.absolute_paths);
+        Ok(result)
     }
     
     /// Analyze current state of the codebase
     async fn analyze_current_state(&self, path: &Path) -> Result<DirectoryAnalysis> {
-        let mut session = AnalysisSession::default();
+        let mut session = AnalysisSession::with_config(AnalysisConfig {
+            max_threads: self.config.threads,
+            ..Default::default()
+        });
         session.analyze_path(path, self.config.include_tests).await
             .context("Failed to analyze current state")
     }
@@ -315,6 +565,7 @@ impl ImpactAnalyzer {
                         },
                         signature_before: None,
                         signature_after: Some(self.format_function_signature(function)),
+                        signature_diff: None,
                         references: Vec::new(),
                         risk_level: RiskLevel::Low, // Will be calculated later
                         breaking_change,
@@ -343,6 +594,7 @@ impl ImpactAnalyzer {
                         },
                         signature_before: None,
                         signature_after: Some(format!("class {}", class.name)),
+                        signature_diff: None,
                         references: Vec::new(),
                         risk_level: RiskLevel::Low,
                         breaking_change: !class.methods.is_empty() || class.name.contains("Manager"),
@@ -363,7 +615,7 @@ impl ImpactAnalyzer {
     }
     
     /// Detect changed symbols specifically in the provided files (git mode)
-    async fn detect_changed_symbols_in_files(&self, analysis: &DirectoryAnalysis, changed_files: &[PathBuf]) -> Result<Vec<ChangedSymbol>> {
+    async fn detect_changed_symbols_in_files(&self, analysis: &DirectoryAnalysis, changed_files: &[PathBuf], compare_ref: &str) -> Result<Vec<ChangedSymbol>> {
         let mut changed_symbols = Vec::new();
         
         // First pass: count references for each function to identify widely-used functions
@@ -391,7 +643,9 @@ impl ImpactAnalyzer {
         // Only look for changed symbols in the files that were actually modified
         for file in &analysis.files {
             // Skip files that weren't changed according to git
-            if !changed_file_set.contains(&file.file_info.path) {
+            let relative_path = file.file_info.path.strip_prefix(&analysis.directory_path)
+                .unwrap_or(&file.file_info.path);
+            if !changed_file_set.contains(relative_path) && !changed_file_set.contains(&file.file_info.path) {
                 if self.config.verbose {
                     println!("🔍 Skipping file (not in changed set): {}", file.file_info.path.display());
                 }
@@ -402,129 +656,92 @@ impl ImpactAnalyzer {
                 println!("🔍 Comparing file: {}", file.file_info.path.display());
             }
             
-            // Get the old version of this file from git for comparison
-            if let Some(ref compare_ref) = self.config.compare_ref {
-                match self.analyze_file_at_git_ref(&file.file_info.path, compare_ref).await {
-                    Ok(old_functions) => {
-                        // Compare old vs new functions to detect changes
-                        let current_functions: HashSet<String> = file.functions.iter()
-                            .map(|f| f.name.clone())
-                            .collect();
-                        let old_function_names: HashSet<String> = old_functions.iter()
-                            .map(|f| f.name.clone())
-                            .collect();
-                        
-                        // Find deleted functions (in old but not in current)
-                        for old_func in &old_functions {
-                            if !current_functions.contains(&old_func.name) {
-                                let usage_count = function_usage_count.get(&old_func.name).unwrap_or(&0);
-                                let breaking_change = *usage_count > 0; // Any usage makes deletion breaking
-                                
-                                if self.config.verbose {
-                                    println!("📄 Found {} functions, {} classes in old version", old_functions.len(), 0);
-                                    println!("📄 File {} was deleted: {} functions, {} classes removed", 
-                                            file.file_info.path.display(), old_functions.len(), 0);
-                                }
-                                
-                                changed_symbols.push(ChangedSymbol {
-                                    name: old_func.name.clone(),
-                                    symbol_type: "function".to_string(),
-                                    file_path: file.file_info.path.clone(),
-                                    line_number: old_func.start_line,
-                                    change_type: ChangeType::FunctionRemoved,
-                                    signature_before: Some(self.format_function_signature(old_func)),
-                                    signature_after: None,
-                                    references: Vec::new(),
-                                    risk_level: RiskLevel::Low,
-                                    breaking_change,
-                                });
-                            }
-                        }
-                        
-                        // Find added functions (in current but not in old)
-                        for function in &file.functions {
-                            if !old_function_names.contains(&function.name) {
-                                let usage_count = function_usage_count.get(&function.name).unwrap_or(&0);
-                                let breaking_change = false; // New functions are not breaking
-                                
-                                changed_symbols.push(ChangedSymbol {
-                                    name: function.name.clone(),
-                                    symbol_type: "function".to_string(),
-                                    file_path: file.file_info.path.clone(),
-                                    line_number: function.start_line,
-                                    change_type: ChangeType::FunctionAdded,
-                                    signature_before: None,
-                                    signature_after: Some(self.format_function_signature(function)),
-                                    references: Vec::new(),
-                                    risk_level: RiskLevel::Low,
-                                    breaking_change,
-                                });
-                            } else {
-                                // Function exists in both - check for signature changes
-                                if let Some(old_func) = old_functions.iter().find(|f| f.name == function.name) {
-                                    let old_sig = self.format_function_signature(old_func);
-                                    let new_sig = self.format_function_signature(function);
-                                    
-                                    if old_sig != new_sig {
-                                        let usage_count = function_usage_count.get(&function.name).unwrap_or(&0);
-                                        let breaking_change = *usage_count > 0; // Usage makes changes potentially breaking
-                                        
-                                        changed_symbols.push(ChangedSymbol {
-                                            name: function.name.clone(),
-                                            symbol_type: "function".to_string(),
-                                            file_path: file.file_info.path.clone(),
-                                            line_number: function.start_line,
-                                            change_type: ChangeType::SignatureChanged,
-                                            signature_before: Some(old_sig),
-                                            signature_after: Some(new_sig),
-                                            references: Vec::new(),
-                                            risk_level: RiskLevel::Low,
-                                            breaking_change,
-                                        });
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    Err(_) => {
-                        // Fallback to old behavior if git analysis fails
-                        for function in &file.functions {
-                            let usage_count = function_usage_count.get(&function.name).unwrap_or(&0);
-                            let breaking_change = function.parameters.len() > 1 || *usage_count >= 2;
-                            
-                            changed_symbols.push(ChangedSymbol {
-                                name: function.name.clone(),
-                                symbol_type: "function".to_string(),
-                                file_path: file.file_info.path.clone(),
-                                line_number: function.start_line,
-                                change_type: ChangeType::FunctionModified,
-                                signature_before: None,
-                                signature_after: Some(self.format_function_signature(function)),
-                                references: Vec::new(),
-                                risk_level: RiskLevel::Low,
-                                breaking_change,
-                            });
-                        }
+            // A file missing at the ref has no old functions, so everything in it is added
+            let (old_functions, old_public_api) = self.analyze_file_at_git_ref(&file.file_info.path, compare_ref).await?;
+            let public_api = public_api(file);
+            
+            // Compare old vs new functions to detect changes
+            let current_functions: HashSet<String> = file.functions.iter()
+                .map(|f| f.name.clone())
+                .collect();
+            let old_function_names: HashSet<String> = old_functions.iter()
+                .map(|f| f.name.clone())
+                .collect();
+            
+            // Find deleted functions (in old but not in current)
+            for old_func in &old_functions {
+                if !current_functions.contains(&old_func.name) {
+                    let usage_count = function_usage_count.get(&old_func.name).unwrap_or(&0);
+                    // Any usage makes deletion breaking, as does removing public API
+                    let breaking_change = *usage_count > 0 || old_public_api.contains(&old_func.name);
+                    
+                    if self.config.verbose {
+                        println!("📄 Found {} functions, {} classes in old version", old_functions.len(), 0);
+                        println!("📄 File {} was deleted: {} functions, {} classes removed", 
+                                file.file_info.path.display(), old_functions.len(), 0);
                     }
+                    
+                    changed_symbols.push(ChangedSymbol {
+                        name: old_func.name.clone(),
+                        symbol_type: "function".to_string(),
+                        file_path: file.file_info.path.clone(),
+                        line_number: old_func.start_line,
+                        change_type: ChangeType::FunctionRemoved,
+                        signature_before: Some(self.format_function_signature(old_func)),
+                        signature_after: None,
+                        signature_diff: None,
+                        references: Vec::new(),
+                        risk_level: RiskLevel::Low,
+                        breaking_change,
+                    });
                 }
-            } else {
-                // No git comparison available, use old logic
-                for function in &file.functions {
+            }
+            
+            // Find added functions (in current but not in old)
+            for function in &file.functions {
+                if !old_function_names.contains(&function.name) {
                     let usage_count = function_usage_count.get(&function.name).unwrap_or(&0);
-                    let breaking_change = function.parameters.len() > 1 || *usage_count >= 2;
+                    let breaking_change = false; // New functions are not breaking
                     
                     changed_symbols.push(ChangedSymbol {
                         name: function.name.clone(),
                         symbol_type: "function".to_string(),
                         file_path: file.file_info.path.clone(),
                         line_number: function.start_line,
-                        change_type: ChangeType::FunctionModified,
+                        change_type: ChangeType::FunctionAdded,
                         signature_before: None,
                         signature_after: Some(self.format_function_signature(function)),
+                        signature_diff: None,
                         references: Vec::new(),
                         risk_level: RiskLevel::Low,
                         breaking_change,
                     });
+                } else {
+                    // Function exists in both - check for signature changes
+                    if let Some(old_func) = old_functions.iter().find(|f| f.name == function.name) {
+                        let old_sig = self.format_function_signature(old_func);
+                        let new_sig = self.format_function_signature(function);
+                        
+                        if old_sig != new_sig {
+                            let usage_count = function_usage_count.get(&function.name).unwrap_or(&0);
+                            // Usage or public API makes changes potentially breaking
+                            let breaking_change = *usage_count > 0 || public_api.contains(&function.name);
+                            
+                            changed_symbols.push(ChangedSymbol {
+                                name: function.name.clone(),
+                                symbol_type: "function".to_string(),
+                                file_path: file.file_info.path.clone(),
+                                line_number: function.start_line,
+                                change_type: ChangeType::SignatureChanged,
+                                signature_before: Some(old_sig),
+                                signature_after: Some(new_sig),
+                                signature_diff: SignatureDiff::between(old_func, function, file.language),
+                                references: Vec::new(),
+                                risk_level: RiskLevel::Low,
+                                breaking_change,
+                            });
+                        }
+                    }
                 }
             }
             
@@ -538,6 +755,7 @@ impl ImpactAnalyzer {
                     change_type: ChangeType::ClassModified,
                     signature_before: None,
                     signature_after: Some(format!("class {}", class.name)),
+                    signature_diff: None,
                     references: Vec::new(),
                     risk_level: RiskLevel::Low,
                     breaking_change: !class.methods.is_empty(),
@@ -556,21 +774,112 @@ impl ImpactAnalyzer {
         Ok(changed_symbols)
     }
     
-    /// Find references to a changed symbol
-    fn find_symbol_references(&self, symbol: &ChangedSymbol, analysis: &DirectoryAnalysis) 
+    /// Detect changed symbols from a unified diff: functions and classes whose
+    /// line range contains a changed line. A symbol made up entirely of added
+    /// lines is new; otherwise it is modified, and the change counts as
+    /// breaking when its first line changed and it is used or exported.
+    fn detect_changed_symbols_in_patches(&self, analysis: &DirectoryAnalysis, patches: &[FilePatch]) -> Vec<ChangedSymbol> {
+        let mut changed_symbols = Vec::new();
+        
+        let mut function_usage_count = HashMap::new();
+        for file in &analysis.files {
+            for call in &file.function_calls {
+                *function_usage_count.entry(call.function_name.as_str()).or_insert(0) += 1;
+            }
+        }
+        
+        for patch in patches {
+            let Some(file) = patched_file(analysis, patch) else {
+                if self.config.verbose {
+                    println!("🔍 Skipping {} (deleted or not analyzed)", patch.path.display());
+                }
+                continue;
+            };
+            let public_api = public_api(file);
+            
+            for function in &file.functions {
+                if !patch.touches(function.start_line, function.end_line) {
+                    continue;
+                }
+                let added = patch.adds(function.start_line, function.end_line);
+                let used = function_usage_count.get(function.name.as_str()).is_some_and(|&count| count > 0);
+                changed_symbols.push(ChangedSymbol {
+                    name: function.name.clone(),
+                    symbol_type: "function".to_string(),
+                    file_path: file.file_info.path.clone(),
+                    line_number: function.start_line,
+                    change_type: if added { ChangeType::FunctionAdded } else { ChangeType::FunctionModified },
+                    signature_before: None,
+                    signature_after: Some(self.format_function_signature(function)),
+                    signature_diff: None,
+                    references: Vec::new(),
+                    risk_level: RiskLevel::Low,
+                    breaking_change: !added && patch.touches(function.start_line, function.start_line)
+                        && (used || public_api.contains(&function.name)),
+                });
+            }
+            
+            for class in &file.classes {
+                if !patch.touches(class.start_line, class.end_line) {
+                    continue;
+                }
+                let added = patch.adds(class.start_line, class.end_line);
+                changed_symbols.push(ChangedSymbol {
+                    name: class.name.clone(),
+                    symbol_type: "class".to_string(),
+                    file_path: file.file_info.path.clone(),
+                    line_number: class.start_line,
+                    change_type: if added { ChangeType::ClassAdded } else { ChangeType::ClassModified },
+                    signature_before: None,
+                    signature_after: Some(format!("class {}", class.name)),
+                    signature_diff: None,
+                    references: Vec::new(),
+                    risk_level: RiskLevel::Low,
+                    breaking_change: !added && patch.touches(class.start_line, class.start_line)
+                        && public_api.contains(&class.name),
+                });
+            }
+        }
+        
+        if self.config.verbose {
+            println!("🔍 Detected {} changed symbols in {} patched files", changed_symbols.len(), patches.len());
+        }
+        
+        changed_symbols
+    }
+    
+    /// Find references to a changed symbol. `index` is built over
+    /// `analysis.files` and limits the search to files mentioning the symbol.
+    fn find_symbol_references(&self, symbol: &ChangedSymbol, analysis: &DirectoryAnalysis, index: &SymbolIndex) 
         -> Result<Vec<SymbolReference>> {
         let mut references = Vec::new();
         
-        for file in &analysis.files {
+        // Methods only match calls whose receiver resolves to their class
+        let owner = self.containing_class(symbol, analysis);
+        
+        // Class usage is also guessed from function names containing the
+        // class name, which the index does not record
+        let candidates: Vec<&AnalysisResult> = if symbol.symbol_type == "class" {
+            analysis.files.iter().collect()
+        } else {
+            index.files_mentioning(&symbol.name).iter().map(|&file| &analysis.files[file]).collect()
+        };
+        
+        for file in candidates {
+            // Variables bound to class instances, read from the source only
+            // when a method call's receiver needs resolving
+            let bindings = OnceCell::new();
+            
             // Look for function calls that match our symbol
             for call in &file.function_calls {
-                if call.function_name == symbol.name || 
-                   call.full_name().contains(&symbol.name) {
+                if let Some(usage_type) = self.match_call(call, symbol, owner, file, analysis, &bindings) {
                     references.push(SymbolReference {
                         file_path: file.file_info.path.clone(),
                         line_number: call.line_number,
+                        column: (call.column_end > 0).then_some(call.column_start),
                         context: format!("{}()", call.full_name()),
-                        usage_type: "call".to_string(),
+                        usage_type: usage_type.to_string(),
+                        blame: None,
                     });
                 }
             }
@@ -581,8 +890,10 @@ impl ImpactAnalyzer {
                     references.push(SymbolReference {
                         file_path: file.file_info.path.clone(),
                         line_number: import.line_number,
+                        column: None,
                         context: format!("import {} from '{}'", symbol.name, import.module_path),
                         usage_type: "import".to_string(),
+                        blame: None,
                     });
                 }
             }
@@ -593,20 +904,33 @@ impl ImpactAnalyzer {
                     references.push(SymbolReference {
                         file_path: file.file_info.path.clone(),
                         line_number: export.line_number,
+                        column: None,
                         context: format!("export {}", symbol.name),
                         usage_type: "export".to_string(),
+                        blame: None,
                     });
                 }
             }
             
             // Look for function definitions that match (in case of overloading/inheritance)
             for function in &file.functions {
-                if function.name == symbol.name && file.file_info.path != symbol.file_path {
+                let is_related = match owner {
+                    // A method is only related to overrides in subclasses
+                    Some(class) => enclosing_class(file, function.start_line)
+                        .map(|c| c.parent_class.as_deref() == Some(class.name.as_str()))
+                        .unwrap_or(false),
+                    None => true,
+                };
+                if function.name == symbol.name && file.file_info.path != symbol.file_path && is_related {
+                    // `.d.ts` and `declare` signatures describe the symbol rather than define it
+                    let declaration = function.metadata.get("is_declaration").is_some_and(|flag| flag == "true");
                     references.push(SymbolReference {
                         file_path: file.file_info.path.clone(),
                         line_number: function.start_line,
-                        context: format!("function {}({})", function.name, function.parameters.join(", ")),
-                        usage_type: "definition".to_string(),
+                        column: None,
+                        context: format!("{}function {}({})", if declaration { "declare " } else { "" }, function.name, function.parameters.join(", ")),
+                        usage_type: if declaration { "declaration" } else { "definition" }.to_string(),
+                        blame: None,
                     });
                 }
             }
@@ -620,8 +944,10 @@ impl ImpactAnalyzer {
                         references.push(SymbolReference {
                             file_path: file.file_info.path.clone(),
                             line_number: function.start_line,
+                            column: None,
                             context: format!("new {}()", symbol.name),
                             usage_type: "constructor".to_string(),
+                            blame: None,
                         });
                     }
                 }
@@ -633,8 +959,10 @@ impl ImpactAnalyzer {
                             references.push(SymbolReference {
                                 file_path: file.file_info.path.clone(),
                                 line_number: class.start_line,
+                                column: None,
                                 context: format!("class {} extends {}", class.name, parent),
                                 usage_type: "inheritance".to_string(),
+                                blame: None,
                             });
                         }
                     }
@@ -645,6 +973,84 @@ impl ImpactAnalyzer {
         Ok(references)
     }
     
+    /// Find the class a changed function belongs to, if it is a method
+    fn containing_class<'a>(&self, symbol: &ChangedSymbol, analysis: &'a DirectoryAnalysis) -> Option<&'a ClassInfo> {
+        if symbol.symbol_type == "class" {
+            return None;
+        }
+        
+        let file = analysis.files.iter().find(|f| f.file_info.path == symbol.file_path)?;
+        file.classes.iter()
+            .find(|c| c.methods.iter().any(|m| m.name == symbol.name && m.start_line == symbol.line_number))
+            .or_else(|| enclosing_class(file, symbol.line_number))
+    }
+    
+    /// Decide whether a call references the symbol, returning its usage type:
+    /// `method_call` for calls on an instance of the owning class,
+    /// `possible_method_call` when the receiver's class cannot be worked out,
+    /// `call` otherwise. A receiver bound to some other class is no reference.
+    fn match_call(
+        &self,
+        call: &FunctionCall,
+        symbol: &ChangedSymbol,
+        owner: Option<&ClassInfo>,
+        file: &AnalysisResult,
+        analysis: &DirectoryAnalysis,
+        bindings: &OnceCell<InstanceBindings>,
+    ) -> Option<&'static str> {
+        // Qualified names such as `Namespace::func` or `module.func`
+        let (qualifier, name) = match call.function_name.rsplit_once("::").or_else(|| call.function_name.rsplit_once('.')) {
+            Some((qualifier, name)) => (Some(qualifier), name),
+            None => (None, call.function_name.as_str()),
+        };
+        if name != symbol.name {
+            return None;
+        }
+        
+        let receiver = call.object_name.as_deref().or(qualifier);
+        
+        match owner {
+            None => match receiver {
+                None => Some("call"),
+                Some(receiver) if qualifier.is_some() || is_module_receiver(file, receiver) => Some("call"),
+                Some(_) => None,
+            },
+            Some(class) => {
+                let inside_owner = enclosing_class(file, call.line_number)
+                    .map(|c| {
+                        (c.name == class.name && file.file_info.path == symbol.file_path)
+                            || c.parent_class.as_deref() == Some(class.name.as_str())
+                    })
+                    .unwrap_or(false);
+                
+                let is_owner_or_subclass = |name: &str| {
+                    receiver_names_class(name, &class.name)
+                        || analysis.files.iter()
+                            .flat_map(|f| &f.classes)
+                            .any(|c| c.parent_class.as_deref() == Some(class.name.as_str()) && receiver_names_class(name, &c.name))
+                };
+                
+                match receiver {
+                    Some("this") | Some("self") | None => inside_owner.then_some("method_call"),
+                    Some(receiver) if is_owner_or_subclass(receiver) => Some("method_call"),
+                    Some(receiver) if qualifier.is_some() || is_module_receiver(file, receiver) => None,
+                    Some(receiver) => {
+                        let bindings = bindings.get_or_init(|| {
+                            std::fs::read_to_string(&file.file_info.path)
+                                .map(|content| instance_bindings(&content))
+                                .unwrap_or_default()
+                        });
+                        match bindings.get(receiver) {
+                            Some(classes) if classes.iter().any(|c| is_owner_or_subclass(c)) => Some("method_call"),
+                            Some(_) => None,
+                            None => Some("possible_method_call"),
+                        }
+                    }
+                }
+            }
+        }
+    }
+    
     /// Assess risk level for a changed symbol
     fn assess_risk_level(&self, symbol: &ChangedSymbol) -> RiskLevel {
         let ref_count = symbol.references.len();
@@ -702,29 +1108,78 @@ impl ImpactAnalyzer {
         }
     }
     
-    /// Calculate complexity changes
-    fn calculate_complexity_change(&self, analysis: &DirectoryAnalysis) -> ComplexityChange {
-        let avg_complexity = if !analysis.files.is_empty() {
-            analysis.files.iter()
-                .map(|f| f.complexity.cyclomatic_complexity as f64)
-                .sum::<f64>() / analysis.files.len() as f64
-        } else {
-            0.0
+    /// Calculate complexity changes by comparing changed files against `compare_ref`
+    async fn calculate_complexity_change(&self, analysis: &DirectoryAnalysis, changed_files: &[PathBuf], compare_ref: Option<&str>) -> ComplexityChange {
+        let compare_ref = match compare_ref {
+            Some(compare_ref) if !changed_files.is_empty() => compare_ref,
+            _ => {
+                // Nothing to compare against: report the current state without a delta
+                let avg_complexity = average(analysis.files.iter().map(|f| f.complexity.cyclomatic_complexity));
+                return ComplexityChange {
+                    before_avg: avg_complexity,
+                    after_avg: avg_complexity,
+                    change_delta: 0.0,
+                    complexity_increased: false,
+                    file_changes: Vec::new(),
+                };
+            }
         };
         
-        // Simplified - in practice this would compare before/after
+        let mut file_changes = Vec::new();
+        
+        for file_path in changed_files {
+            // Git paths are relative to the analyzed directory; analysis paths include it
+            let current = analysis.files.iter().find(|f| {
+                f.file_info.path.strip_prefix(&analysis.directory_path).unwrap_or(&f.file_info.path) == file_path
+            });
+            let after = current.map(|f| f.complexity.cyclomatic_complexity);
+            let full_path = current
+                .map(|f| f.file_info.path.clone())
+                .unwrap_or_else(|| analysis.directory_path.join(file_path));
+            
+            // Complexity is only informational, so an unreadable ref leaves no before value
+            let before = match self.analyze_file_result_at_git_ref(&full_path, compare_ref).await {
+                Ok(result) => result.map(|r| r.complexity.cyclomatic_complexity),
+                Err(e) => {
+                    if self.config.verbose {
+                        println!("📄 No version of {} at {}: {}", file_path.display(), compare_ref, e);
+                    }
+                    None
+                }
+            };
+            
+            if before.is_none() && after.is_none() {
+                continue;
+            }
+            
+            file_changes.push(FileComplexityChange {
+                file_path: file_path.clone(),
+                before,
+                after,
+                delta: after.unwrap_or(0) as i64 - before.unwrap_or(0) as i64,
+            });
+        }
+        
+        let before_avg = average(file_changes.iter().filter_map(|c| c.before));
+        let after_avg = average(file_changes.iter().filter_map(|c| c.after));
+        let change_delta = after_avg - before_avg;
+        
         ComplexityChange {
-            before_avg: avg_complexity,
-            after_avg: avg_complexity + 0.1, // Simulate small increase
-            change_delta: 0.1,
-            complexity_increased: true,
+            before_avg,
+            after_avg,
+            change_delta,
+            complexity_increased: change_delta > 0.0,
+            file_changes,
         }
     }
     
     /// Format function signature for display
     fn format_function_signature(&self, function: &FunctionInfo) -> String {
         let params = function.parameters.join(", ");
-        format!("{}({})", function.name, params)
+        match function.metadata.get("return_type") {
+            Some(return_type) => format!("{}({}) -> {}", function.name, params, return_type),
+            None => format!("{}({})", function.name, params),
+        }
     }
     
     /// Analyze functions at a specific git reference
@@ -867,6 +1322,7 @@ impl ImpactAnalyzer {
                             change_type: ChangeType::FunctionRemoved,
                             signature_before: Some(self.format_function_signature(&deleted_func)),
                             signature_after: None,
+                            signature_diff: None,
                             references: Vec::new(), // Will be filled later
                             risk_level: RiskLevel::Low, // Will be calculated later
                             breaking_change: true, // Deletions are always breaking
@@ -900,14 +1356,13 @@ impl ImpactAnalyzer {
         }
         
         // Get the git root directory to run the command from
+        // Start from current working directory and walk up to find .git
+        let start_path = if repo_path.is_absolute() {
+            repo_path.to_path_buf()
+        } else {
+            std::env::current_dir()?.join(repo_path)
+        };
         let git_root = {
-            // Start from current working directory and walk up to find .git
-            let start_path = if repo_path.is_absolute() {
-                repo_path.to_path_buf()
-            } else {
-                std::env::current_dir()?.join(repo_path)
-            };
-            
             let mut current = start_path.as_path();
             loop {
                 if current.join(".git").exists() {
@@ -947,11 +1402,11 @@ impl ImpactAnalyzer {
                 let git_relative_path = PathBuf::from(line.trim());
                 // Convert git relative path to our analysis path format
                 // If the git root is different from repo_path, we need to adjust
-                if git_root == *repo_path {
+                if git_root == start_path {
                     git_relative_path
                 } else {
                     // Git path is relative to git_root, we need it relative to repo_path
-                    if let Ok(relative_to_git_root) = repo_path.strip_prefix(&git_root) {
+                    if let Ok(relative_to_git_root) = start_path.strip_prefix(&git_root) {
                         if let Ok(stripped) = git_relative_path.strip_prefix(relative_to_git_root) {
                             stripped.to_path_buf()
                         } else {
@@ -963,14 +1418,10 @@ impl ImpactAnalyzer {
                 }
             })
             .filter(|path| {
-                // Only include supported file types for analysis
-                if let Some(ext) = path.extension() {
-                    matches!(ext.to_str(), Some("js") | Some("ts") | Some("jsx") | Some("tsx") | 
-                                          Some("py") | Some("cpp") | Some("hpp") | Some("c") | 
-                                          Some("h") | Some("cs") | Some("go") | Some("rs"))
-                } else {
-                    false
-                }
+                // Only include file types a language analyzer is registered for
+                path.extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| Language::from_extension(&format!(".{}", ext)) != Language::Unknown)
             })
             .collect();
             
@@ -985,72 +1436,83 @@ impl ImpactAnalyzer {
     }
     
     /// Analyze a file at a specific git reference (commit, branch, tag)
-    async fn analyze_file_at_git_ref(&self, file_path: &Path, git_ref: &str) -> Result<Vec<FunctionInfo>> {
+    async fn analyze_file_at_git_ref(&self, file_path: &Path, git_ref: &str) -> Result<(Vec<FunctionInfo>, HashSet<String>)> {
+        let (functions, public_api) = self.analyze_file_result_at_git_ref(file_path, git_ref).await?
+            .map(|result| {
+                let public_api = public_api(&result);
+                (result.functions, public_api)
+            })
+            .unwrap_or_default();
+        
+        if self.config.verbose {
+            println!("📄 Found {} functions, {} classes in old version", functions.len(), 0);
+        }
+        
+        Ok((functions, public_api))
+    }
+    
+    /// Analyze the full content of a file as it was at a git reference
+    async fn analyze_file_result_at_git_ref(&self, file_path: &Path, git_ref: &str) -> Result<Option<AnalysisResult>> {
         use std::process::Command;
         use crate::core::session::AnalysisSession;
         
-        // Get the relative path from the file_path
-        let relative_path = if let Some(parent) = file_path.parent() {
-            file_path.strip_prefix(parent).unwrap_or(file_path)
-        } else {
-            file_path
+        let file_name = file_path.file_name()
+            .ok_or_else(|| anyhow::anyhow!("Invalid file path: {}", file_path.display()))?;
+        let working_dir = match file_path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
         };
         
         if self.config.verbose {
-            println!("📄 Getting file content: {}:{}", git_ref, relative_path.display());
+            println!("📄 Getting file content: {}:{}", git_ref, file_path.display());
         }
         
-        // Get file content from git
+        // `./` makes the path relative to the working directory instead of the repository root
         let output = Command::new("git")
             .arg("show")
-            .arg(format!("{}:{}", git_ref, relative_path.display()))
-            .current_dir(file_path.parent().unwrap_or_else(|| Path::new(".")))
+            .arg(format!("{}:./{}", git_ref, file_name.to_string_lossy()))
+            .current_dir(working_dir)
             .output()
             .map_err(|e| anyhow::anyhow!("Failed to run git show command: {}", e))?;
             
         if !output.status.success() {
+            // With a valid ref, the file just did not exist there yet
+            let ref_exists = Command::new("git")
+                .args(["rev-parse", "--verify", "--quiet"])
+                .arg(format!("{}^{{commit}}", git_ref))
+                .current_dir(working_dir)
+                .output()
+                .map(|output| output.status.success())
+                .unwrap_or(false);
+            if ref_exists {
+                return Ok(None);
+            }
             let error = String::from_utf8_lossy(&output.stderr);
             anyhow::bail!("Git show failed: {}", error);
         }
-        
+
         let file_content = String::from_utf8_lossy(&output.stdout);
         
         if self.config.verbose {
-            println!("📄 Analyzing {} at {} ({} chars)", relative_path.display(), git_ref, file_content.len());
+            println!("📄 Analyzing {} at {} ({} chars)", file_path.display(), git_ref, file_content.len());
         }
         
-        // Create a temporary file to analyze the old content
-        let temp_file = std::env::temp_dir().join(format!("nekocode_git_{}.js", uuid::Uuid::new_v4()));
+        // Keep the original extension so the right language analyzer is used
+        let extension = file_path.extension()
+            .map(|ext| format!(".{}", ext.to_string_lossy()))
+            .unwrap_or_default();
+        let temp_file = std::env::temp_dir().join(format!("nekocode_git_{}{}", uuid::Uuid::new_v4(), extension));
         std::fs::write(&temp_file, file_content.as_bytes())
             .context("Failed to write temporary file")?;
-            
-        if self.config.verbose {
-            println!("📄 Created temporary file: {}", temp_file.display());
-        }
         
-        // Analyze the temporary file
         let mut session = AnalysisSession::default();
-        let analysis_result = session.analyze_path(&temp_file, false).await
-            .context("Failed to analyze temporary file")?;
-            
+        let analysis_result = session.analyze_path(&temp_file, true).await;
+        
         // Clean up the temporary file
         let _ = std::fs::remove_file(&temp_file);
         
-        if self.config.verbose {
-            println!("📄 Successfully analyzed temp file, found {} files", analysis_result.files.len());
-        }
-        
-        // Extract functions from the analysis result
-        let mut functions = Vec::new();
-        for file_result in analysis_result.files {
-            functions.extend(file_result.functions);
-        }
-        
-        if self.config.verbose {
-            println!("📄 Found {} functions, {} classes in old version", functions.len(), 0);
-        }
-        
-        Ok(functions)
+        let analysis_result = analysis_result.context("Failed to analyze temporary file")?;
+        Ok(analysis_result.files.into_iter().next())
     }
     
     /// Analyze only the changed files
@@ -1088,6 +1550,164 @@ impl ImpactAnalyzer {
     }
 }
 
+/// `git blame` results, one run per file
+#[derive(Default)]
+struct BlameCache {
+    /// Blame of each line (index = line - 1); `None` when git can't blame the file
+    files: HashMap<PathBuf, Option<Vec<Option<BlameInfo>>>>,
+}
+
+impl BlameCache {
+    /// Blame for a 1-based line. `None` outside a git repository, for
+    /// untracked files and for uncommitted lines.
+    fn line(&mut self, file: &Path, line: u32) -> Option<BlameInfo> {
+        let lines = self.files.entry(file.to_path_buf()).or_insert_with(|| blame_file(file));
+        lines.as_ref()?.get((line as usize).checked_sub(1)?)?.clone()
+    }
+}
+
+fn blame_file(file: &Path) -> Option<Vec<Option<BlameInfo>>> {
+    let dir = file.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let output = std::process::Command::new("git")
+        .current_dir(dir)
+        .args(["blame", "--line-porcelain", "--"])
+        .arg(file.file_name()?)
+        .output()
+        .ok()?;
+    output.status.success().then(|| parse_blame_porcelain(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse `git blame --line-porcelain`: a header per line, starting with the
+/// commit hash, and the line itself prefixed with a tab
+fn parse_blame_porcelain(porcelain: &str) -> Vec<Option<BlameInfo>> {
+    let mut lines = Vec::new();
+    let mut header = true;
+    let mut commit = "";
+    let mut author = "";
+    for line in porcelain.lines() {
+        if header {
+            commit = line.split(' ').next().unwrap_or("");
+            header = false;
+        } else if line.starts_with('\t') {
+            let uncommitted = commit.bytes().all(|b| b == b'0');
+            lines.push((!uncommitted).then(|| BlameInfo { author: author.to_string(), commit: commit.to_string() }));
+            header = true;
+        } else if let Some(name) = line.strip_prefix("author ") {
+            author = name;
+        }
+    }
+    lines
+}
+
+/// The analyzed file a diff path refers to. Diff paths are relative to the
+/// repository root, which may lie above the analyzed directory, so the diff
+/// path only has to end with the file's path relative to that directory.
+fn patched_file<'a>(analysis: &'a DirectoryAnalysis, patch: &FilePatch) -> Option<&'a AnalysisResult> {
+    analysis.files.iter().find(|file| {
+        let relative = relative_to_root(&file.file_info.path, &analysis.directory_path);
+        patch.path == file.file_info.path || (relative.is_relative() && patch.path.ends_with(&relative))
+    })
+}
+
+/// Names a file exposes as public API; `pub(crate)` and similar Rust
+/// exports stay inside the crate and are left out
+fn public_api(file: &AnalysisResult) -> HashSet<String> {
+    file.exports.iter()
+        .filter(|export| export.export_type != ExportType::RustPubCrate)
+        .flat_map(|export| export.exported_names.iter().cloned())
+        .collect()
+}
+
+/// Find the innermost class whose body contains a line
+fn enclosing_class(file: &AnalysisResult, line: u32) -> Option<&ClassInfo> {
+    file.classes.iter()
+        .filter(|c| c.start_line <= line && line <= c.end_line)
+        .min_by_key(|c| c.end_line - c.start_line)
+}
+
+/// Variable name → classes it is bound to in a file
+type InstanceBindings = HashMap<String, HashSet<String>>;
+
+/// Patterns binding a variable to a class, as (regex, whether the class
+/// comes before the variable)
+fn binding_patterns() -> &'static [(Regex, bool)] {
+    static PATTERNS: OnceLock<Vec<(Regex, bool)>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        [
+            // `svc = new Service()`, `svc = Service()`, `let svc = Service::new()`,
+            // `svc := &Service{}`, `val svc: Base = Service(...)`
+            (r"(\w+)\s*(?::\s*[\w.<>\[\]]+\s*)?:?=\s*(?:new\s+|&)?([A-Z]\w*)(?:<[^>]*>)?\s*(?:::|\(|\{)", false),
+            // `svc: Service` (TypeScript, Kotlin, Python and Rust annotations)
+            (r"(\w+)\s*:\s*&?(?:mut\s+)?([A-Z]\w*)", false),
+            // `Service svc = ...`, `Service* svc;`, `(Service svc)` in C-family languages
+            (r"\b([A-Z]\w*)(?:<[^>]*>)?[\s*&]+(\w+)\s*[=;(,)]", true),
+            // Go `var svc Service` and parameters `(svc *Service)`
+            (r"(?:\bvar\s+|[(,]\s*)(\w+)\s+\*?([A-Z]\w*)\b", false),
+        ]
+        .into_iter()
+        .map(|(pattern, class_first)| (Regex::new(pattern).expect("valid binding pattern"), class_first))
+        .collect()
+    })
+}
+
+/// Variables that `content` binds to class instances. Names are not scoped,
+/// so a variable bound to different classes in different functions keeps all
+/// of them.
+fn instance_bindings(content: &str) -> InstanceBindings {
+    let mut bindings = InstanceBindings::new();
+    for (pattern, class_first) in binding_patterns() {
+        for captures in pattern.captures_iter(content) {
+            let (class, variable) = if *class_first { (&captures[1], &captures[2]) } else { (&captures[2], &captures[1]) };
+            bindings.entry(variable.to_string()).or_default().insert(class.to_string());
+        }
+    }
+    bindings
+}
+
+/// Check whether a call receiver is an imported module or namespace (`utils.helper()`)
+fn is_module_receiver(file: &AnalysisResult, receiver: &str) -> bool {
+    file.imports.iter().any(|import| {
+        import.alias.as_deref() == Some(receiver)
+            || import.imported_names.iter().any(|n| n == receiver)
+            || Path::new(&import.module_path).file_stem().map(|stem| stem == receiver).unwrap_or(false)
+    })
+}
+
+/// Average of a set of complexity values (0.0 when empty)
+fn average(values: impl Iterator<Item = u32>) -> f64 {
+    let (sum, count) = values.fold((0u64, 0u64), |(sum, count), v| (sum + v as u64, count + 1));
+    if count == 0 {
+        0.0
+    } else {
+        sum as f64 / count as f64
+    }
+}
+
+/// SARIF rules for breaking changes, one per `ChangeType`
+const IMPACT_RULES: &[SarifRule] = &[
+    SarifRule { id: "function-removed", description: "A function that other code references was removed" },
+    SarifRule { id: "function-modified", description: "A referenced function changed in a breaking way" },
+    SarifRule { id: "signature-changed", description: "The signature of a referenced function changed" },
+    SarifRule { id: "function-added", description: "A function was added" },
+    SarifRule { id: "class-removed", description: "A class that other code references was removed" },
+    SarifRule { id: "class-modified", description: "A referenced class changed in a breaking way" },
+    SarifRule { id: "class-added", description: "A class was added" },
+    SarifRule { id: "type-changed", description: "The type of a referenced symbol changed" },
+];
+
+fn impact_rule_id(change_type: &ChangeType) -> &'static str {
+    match change_type {
+        ChangeType::FunctionAdded => "function-added",
+        ChangeType::FunctionRemoved => "function-removed",
+        ChangeType::FunctionModified => "function-modified",
+        ChangeType::ClassAdded => "class-added",
+        ChangeType::ClassRemoved => "class-removed",
+        ChangeType::ClassModified => "class-modified",
+        ChangeType::SignatureChanged => "signature-changed",
+        ChangeType::TypeChanged => "type-changed",
+    }
+}
+
 /// Output formatters for different formats
 pub struct OutputFormatter;
 
@@ -1143,8 +1763,19 @@ impl OutputFormatter {
         output.push(format!("• Average complexity change: {:+.1}", result.complexity_change.change_delta));
         if result.complexity_change.complexity_increased {
             output.push("• ⬆️ Complexity increased".to_string());
-        } else {
+        } else if result.complexity_change.change_delta < 0.0 {
             output.push("• ⬇️ Complexity decreased".to_string());
+        } else {
+            output.push("• ➡️ Complexity unchanged".to_string());
+        }
+        for change in result.complexity_change.file_changes.iter().filter(|c| c.delta != 0) {
+            output.push(format!("  {} {}: {} → {} ({:+})",
+                if change.delta > 0 { "⬆️" } else { "⬇️" },
+                change.file_path.display(),
+                change.before.map(|c| c.to_string()).unwrap_or_else(|| "-".to_string()),
+                change.after.map(|c| c.to_string()).unwrap_or_else(|| "-".to_string()),
+                change.delta
+            ));
         }
         
         output.join("\n")
@@ -1152,10 +1783,35 @@ impl OutputFormatter {
     
     /// Format as JSON
     pub fn format_json(result: &ImpactAnalysisResult) -> Result<String> {
-        serde_json::to_string_pretty(result)
+        to_json(result)
             .context("Failed to serialize impact analysis result to JSON")
     }
     
+    /// Format as a SARIF 2.1.0 log: one result per breaking change, at the
+    /// changed symbol, with the references it breaks as related locations
+    pub fn format_sarif(result: &ImpactAnalysisResult) -> Result<String> {
+        let results: Vec<SarifResult> = result.changed_symbols.iter()
+            .filter(|symbol| symbol.breaking_change)
+            .map(|symbol| SarifResult {
+                rule_id: impact_rule_id(&symbol.change_type),
+                level: match symbol.risk_level {
+                    RiskLevel::High => "error",
+                    RiskLevel::Medium => "warning",
+                    RiskLevel::Low => "note",
+                },
+                message: format!("{} '{}' breaks {} reference(s)",
+                    symbol.change_type.as_str(), symbol.name, symbol.references.len()),
+                file: symbol.file_path.clone(),
+                line: symbol.line_number,
+                related: symbol.references.iter()
+                    .map(|reference| (reference.file_path.clone(), reference.line_number, reference.context.clone()))
+                    .collect(),
+            })
+            .collect();
+        to_json(&sarif_log(IMPACT_RULES, &results))
+            .context("Failed to serialize impact analysis result to SARIF")
+    }
+    
     /// Format as GitHub comment
     pub fn format_github_comment(result: &ImpactAnalysisResult) -> String {
         let mut output = Vec::new();
@@ -1207,10 +1863,10 @@ impl OutputFormatter {
                         output.push("  **Broken References:**".to_string());
                         for (i, reference) in func.references.iter().enumerate() {
                             if i < 5 { // Limit to first 5 references to avoid spam
-                                output.push(format!("  - `{}:{}` - {}",
-                                    reference.file_path.display(),
-                                    reference.line_number,
-                                    reference.context
+                                output.push(format!("  - `{}` - {}{}",
+                                    reference.location(),
+                                    reference.context,
+                                    reference.blame.as_ref().map(|b| format!(" (@{})", b.author)).unwrap_or_default()
                                 ));
                             }
                         }
@@ -1225,13 +1881,9 @@ impl OutputFormatter {
             if !modified_functions.is_empty() {
                 output.push("**Modified Functions:**".to_string());
                 for func in modified_functions {
-                    // Clean up file path display (remove duplicate src/ prefixes)
-                    let clean_path = func.file_path.display().to_string()
-                        .replace("src/src/", "src/");
-                    
                     output.push(format!("- `{}()` in `{}:{}`", 
                         func.name, 
-                        clean_path, 
+                        func.file_path.display(), 
                         func.line_number
                     ));
                     output.push(format!("  - **References found**: {} locations", func.references.len()));
@@ -1244,6 +1896,11 @@ impl OutputFormatter {
                         };
                         output.push(format!("  - **Breaking change**: {}", change_desc));
                     }
+                    if let Some(diff) = &func.signature_diff {
+                        for change in diff.describe() {
+                            output.push(format!("    - {}", change));
+                        }
+                    }
                 }
                 output.push("".to_string());
             }
@@ -1373,7 +2030,8 @@ mod tests {
         let config = ImpactConfig::default();
         assert_eq!(config.include_tests, false);
         assert_eq!(config.skip_circular, false);
-        assert_eq!(config.risk_threshold, RiskLevel::Low);
+        assert_eq!(config.risk_threshold, RiskLevel::High);
+        assert!(!config.heuristic, "git comparison should be the default");
         
         let custom_config = ImpactConfig {
             include_tests: true,
@@ -1381,11 +2039,87 @@ mod tests {
             skip_circular: true,
             risk_threshold: RiskLevel::High,
             verbose: true,
+            heuristic: false,
+            blame: false,
+            threads: 4,
+            absolute_paths: false,
+            diff: None,
         };
         assert_eq!(custom_config.include_tests, true);
         assert_eq!(custom_config.risk_threshold, RiskLevel::High);
     }
     
+    #[test]
+    fn test_parse_blame_porcelain() {
+        let porcelain = "\
+1f2e3d4c5b6a79881f2e3d4c5b6a79881f2e3d4c 1 1 2
+author Alice
+author-mail <alice@example.com>
+summary Add helper
+filename util.js
+\tfunction helper() {
+1f2e3d4c5b6a79881f2e3d4c5b6a79881f2e3d4c 2 2
+author Alice
+filename util.js
+\t  return 1;
+0000000000000000000000000000000000000000 3 3 1
+author Not Committed Yet
+filename util.js
+\t}
+";
+        let lines = parse_blame_porcelain(porcelain);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1].as_ref().map(|b| b.author.as_str()), Some("Alice"));
+        assert_eq!(lines[0].as_ref().map(|b| b.commit.as_str()), Some("1f2e3d4c5b6a79881f2e3d4c5b6a79881f2e3d4c"));
+        assert!(lines[2].is_none(), "uncommitted lines have no author");
+        
+        // Outside a git repository attribution is simply omitted
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = dir.path().join("a.js");
+        std::fs::write(&file, "a();\n").unwrap();
+        assert!(BlameCache::default().line(&file, 1).is_none());
+    }
+    
+    #[test]
+    fn test_signature_diff() {
+        let mut before = FunctionInfo::new("load".to_string());
+        before.parameters = vec!["path: str".to_string(), "x".to_string(), "mode: int = 0".to_string()];
+        let mut after = before.clone();
+        after.parameters = vec!["mode: str = 'r'".to_string(), "path: str".to_string(), "options".to_string()];
+        after.metadata.insert("return_type".to_string(), "dict".to_string());
+
+        let diff = SignatureDiff::between(&before, &after, Language::Python).unwrap();
+        assert_eq!(diff.describe(), vec![
+            "added parameter `options`",
+            "removed parameter `x`",
+            "reordered parameters",
+            "changed type of `mode` `int` → `str`",
+            "added return type `dict`",
+        ]);
+        assert!(SignatureDiff::between(&before, &before, Language::Python).is_none());
+    }
+
+    #[test]
+    fn test_signature_diff_typed_languages() {
+        let mut before = FunctionInfo::new("scale".to_string());
+        before.parameters = vec!["const Point& other".to_string(), "int scale = 1".to_string(), "std::string *label".to_string()];
+        let mut after = before.clone();
+        after.parameters = vec!["const Point& other".to_string(), "double scale = 1.0".to_string(), "std::string *label".to_string()];
+        let diff = SignatureDiff::between(&before, &after, Language::Cpp).unwrap();
+        assert_eq!(diff.describe(), vec!["changed type of `scale` `int` → `double`"]);
+        assert_eq!(split_parameter("std::string *label", Language::Cpp), ("label".to_string(), Some("std::string*".to_string())));
+        
+        before.parameters = vec!["String... args".to_string()];
+        after.parameters = vec!["List<String> args".to_string()];
+        let diff = SignatureDiff::between(&before, &after, Language::Java).unwrap();
+        assert_eq!(diff.describe(), vec!["changed type of `args` `String...` → `List<String>`"]);
+        
+        before.parameters = vec!["ctx context.Context".to_string(), "id int".to_string()];
+        after.parameters = vec!["ctx context.Context".to_string(), "id int64".to_string()];
+        let diff = SignatureDiff::between(&before, &after, Language::Go).unwrap();
+        assert_eq!(diff.describe(), vec!["changed type of `id` `int` → `int64`"]);
+    }
+
     #[test]
     fn test_risk_level_methods() {
         assert_eq!(RiskLevel::Low.emoji(), "🟢");
@@ -1395,6 +2129,10 @@ mod tests {
         assert_eq!(RiskLevel::Low.as_str(), "Low");
         assert_eq!(RiskLevel::Medium.as_str(), "Medium");
         assert_eq!(RiskLevel::High.as_str(), "High");
+        
+        assert_eq!(RiskLevel::from_name("medium"), Some(RiskLevel::Medium));
+        assert_eq!(RiskLevel::from_name("High"), None);
+        assert!(RiskLevel::Low < RiskLevel::Medium && RiskLevel::Medium < RiskLevel::High);
     }
     
     #[test]
@@ -1415,6 +2153,7 @@ mod tests {
             change_type: ChangeType::FunctionModified,
             signature_before: None,
             signature_after: Some("testFunction(param)".to_string()),
+            signature_diff: None,
             references: Vec::new(),
             risk_level: RiskLevel::Medium,
             breaking_change: true,
@@ -1445,6 +2184,150 @@ mod tests {
         assert!(has_updated_manager, "Should detect UpdatedUserManager as changed");
     }
     
+    /// Run git in `dir` with a throwaway identity
+    fn git(dir: &Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+    
+    /// A git repository in a temporary directory with `files` committed
+    fn committed_repo(files: &[(&str, &str)]) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        for (name, content) in files {
+            std::fs::write(dir.path().join(name), content).unwrap();
+        }
+        git(dir.path(), &["add", "."]);
+        git(dir.path(), &["commit", "-qm", "initial"]);
+        dir
+    }
+    
+    #[test]
+    fn test_changed_files_from_git_cover_every_analyzed_language() {
+        let files = [
+            ("App.java", "class App {}\n"),
+            ("Main.kt", "fun main() {}\n"),
+            ("build.gradle.kts", "plugins {}\n"),
+            ("app.js", "let a;\n"),
+            ("notes.md", "# Notes\n"),
+        ];
+        let dir = committed_repo(&files);
+        for (name, content) in files {
+            std::fs::write(dir.path().join(name), format!("{}\n", content)).unwrap();
+        }
+        
+        let analyzer = ImpactAnalyzer::new(ImpactConfig::default());
+        let mut changed = analyzer.get_changed_files_from_git(dir.path(), "HEAD").unwrap();
+        changed.sort();
+        let expected: Vec<PathBuf> = ["App.java", "Main.kt", "app.js", "build.gradle.kts"].iter().map(PathBuf::from).collect();
+        assert_eq!(changed, expected);
+    }
+    
+    #[tokio::test]
+    async fn test_changed_symbols_in_file_new_since_ref() {
+        let dir = committed_repo(&[("old.js", "function kept(a) { return a; }\n")]);
+        std::fs::write(dir.path().join("new.js"), "function added(a, b, c) { return a; }\n").unwrap();
+
+        let analysis = AnalysisSession::default().analyze_path(dir.path(), false).await.unwrap();
+        let analyzer = ImpactAnalyzer::new(ImpactConfig::default());
+        let changed = vec![PathBuf::from("new.js")];
+
+        let symbols = analyzer.detect_changed_symbols_in_files(&analysis, &changed, "HEAD").await.unwrap();
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].name, "added");
+        assert_eq!(symbols[0].change_type, ChangeType::FunctionAdded);
+        assert!(!symbols[0].breaking_change);
+
+        // Any other git failure is reported instead of guessed around
+        assert!(analyzer.detect_changed_symbols_in_files(&analysis, &changed, "no-such-ref").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_complexity_change_without_compare_ref() {
+        let analysis = create_test_analysis();
+        let analyzer = ImpactAnalyzer::new(ImpactConfig::default());
+        
+        let change = analyzer.calculate_complexity_change(&analysis, &[], None).await;
+        
+        assert_eq!(change.change_delta, 0.0);
+        assert_eq!(change.before_avg, change.after_avg);
+        assert!(!change.complexity_increased);
+        assert!(change.file_changes.is_empty());
+    }
+    
+    #[test]
+    fn test_method_references_resolve_receiver() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test_class.js");
+        // Receivers are resolved through the bindings in the source
+        std::fs::write(&path, "const queue = new JobQueue();\nconst svc = new TestClass();\n").unwrap();
+        let mut analysis = DirectoryAnalysis::new(dir.path().to_path_buf());
+        
+        let mut process = FunctionInfo::new("process".to_string());
+        process.start_line = 3;
+        let mut test_class = ClassInfo::new("TestClass".to_string());
+        test_class.start_line = 1;
+        test_class.end_line = 10;
+        test_class.methods = vec![process.clone()];
+        
+        let call = |name: &str, object: Option<&str>, line: u32| {
+            let mut call = FunctionCall::new(name.to_string(), line);
+            call.object_name = object.map(str::to_string);
+            call.is_method_call = object.is_some();
+            call
+        };
+        
+        let mut result = AnalysisResult::new(FileInfo::new(path.clone()), Language::JavaScript);
+        result.classes = vec![test_class];
+        result.functions = vec![process];
+        let mut positioned = call("process", Some("this"), 5);
+        (positioned.column_start, positioned.column_end) = (8, 22);
+        result.function_calls = vec![
+            positioned,                                // inside TestClass
+            call("process", Some("testClass"), 12),    // instance named after the class
+            call("process", Some("queue"), 13),        // instance of another class
+            call("process", None, 14),                 // free function call outside the class
+            call("process", Some("svc"), 15),          // `svc = new TestClass()`
+            call("process", Some("worker"), 16),       // receiver of unknown class
+        ];
+        analysis.files = vec![result];
+        
+        let analyzer = ImpactAnalyzer::new(ImpactConfig::default());
+        let symbol = ChangedSymbol {
+            name: "process".to_string(),
+            symbol_type: "function".to_string(),
+            file_path: path.clone(),
+            line_number: 3,
+            change_type: ChangeType::SignatureChanged,
+            signature_before: None,
+            signature_after: None,
+            signature_diff: None,
+            references: vec![],
+            risk_level: RiskLevel::Low,
+            breaking_change: true,
+        };
+        
+        let references = analyzer.find_symbol_references(&symbol, &analysis, &SymbolIndex::build(&analysis.files)).unwrap();
+        let lines: Vec<u32> = references.iter().map(|r| r.line_number).collect();
+        assert_eq!(lines, vec![5, 12, 15, 16]);
+        let usage: Vec<&str> = references.iter().map(|r| r.usage_type.as_str()).collect();
+        assert_eq!(usage, vec!["method_call", "method_call", "method_call", "possible_method_call"]);
+        assert_eq!(references[0].location(), format!("{}:5:9", path.display()));
+        assert_eq!(references[1].column, None);
+        
+        // Session files written before columns were tracked still load
+        let old: FunctionCall = serde_json::from_str(
+            r#"{"function_name":"process","object_name":null,"line_number":5,"is_method_call":false}"#
+        ).unwrap();
+        assert_eq!((old.column_start, old.column_end), (0, 0));
+    }
+    
     #[test]
     fn test_risk_assessment() {
         let config = ImpactConfig::default();
@@ -1459,6 +2342,7 @@ mod tests {
             change_type: ChangeType::FunctionModified,
             signature_before: None,
             signature_after: None,
+            signature_diff: None,
             references: vec![], // No references
             risk_level: RiskLevel::Low,
             breaking_change: false,
@@ -1476,11 +2360,14 @@ mod tests {
             change_type: ChangeType::SignatureChanged,
             signature_before: None,
             signature_after: None,
+            signature_diff: None,
             references: vec![SymbolReference {
                 file_path: PathBuf::from("/tmp/other.js"),
                 line_number: 5,
+                column: None,
                 context: "criticalAPI()".to_string(),
                 usage_type: "call".to_string(),
+                blame: None,
             }; 15], // Many references
             risk_level: RiskLevel::Low,
             breaking_change: true,
@@ -1503,6 +2390,7 @@ mod tests {
                 change_type: ChangeType::FunctionModified,
                 signature_before: None,
                 signature_after: Some("testFunc()".to_string()),
+                signature_diff: None,
                 references: vec![],
                 risk_level: RiskLevel::Medium,
                 breaking_change: false,
@@ -1517,6 +2405,7 @@ mod tests {
                 after_avg: 2.5,
                 change_delta: 0.5,
                 complexity_increased: true,
+                file_changes: vec![],
             },
             analysis_time_ms: 100,
             generated_at: Utc::now(),
@@ -1545,6 +2434,7 @@ mod tests {
                 after_avg: 1.0,
                 change_delta: 0.0,
                 complexity_increased: false,
+                file_changes: vec![],
             },
             analysis_time_ms: 50,
             generated_at: Utc::now(),
@@ -1574,11 +2464,14 @@ mod tests {
                     change_type: ChangeType::FunctionModified,
                     signature_before: None,
                     signature_after: Some("addUser(user, options)".to_string()),
+                    signature_diff: None,
                     references: vec![SymbolReference {
                         file_path: PathBuf::from("/tmp/test/other.js"),
                         line_number: 20,
+                        column: None,
                         context: "addUser()".to_string(),
                         usage_type: "call".to_string(),
+                        blame: None,
                     }],
                     risk_level: RiskLevel::High,
                     breaking_change: true,
@@ -1597,6 +2490,7 @@ mod tests {
                 after_avg: 3.2,
                 change_delta: 0.2,
                 complexity_increased: true,
+                file_changes: vec![],
             },
             analysis_time_ms: 75,
             generated_at: Utc::now(),
@@ -1614,4 +2508,134 @@ mod tests {
         assert!(output.contains("## 🔄 Circular Dependencies"));
         assert!(output.contains("## 📈 Complexity Changes"));
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_output_formatter_sarif() {
+        let symbol = |name: &str, change_type, breaking_change| ChangedSymbol {
+            name: name.to_string(),
+            symbol_type: "function".to_string(),
+            file_path: PathBuf::from("src/users.js"),
+            line_number: 15,
+            change_type,
+            signature_before: Some(format!("{}(user)", name)),
+            signature_after: None,
+            signature_diff: None,
+            references: vec![SymbolReference {
+                file_path: PathBuf::from("src/app.js"),
+                line_number: 20,
+                column: None,
+                context: format!("{}(user)", name),
+                usage_type: "call".to_string(),
+                blame: None,
+            }],
+            risk_level: RiskLevel::High,
+            breaking_change,
+        };
+        let result = ImpactAnalysisResult {
+            analysis_path: PathBuf::from("."),
+            modified_files: vec![PathBuf::from("src/users.js")],
+            changed_symbols: vec![
+                symbol("addUser", ChangeType::FunctionRemoved, true),
+                symbol("listUsers", ChangeType::FunctionAdded, false),
+            ],
+            affected_files: vec![PathBuf::from("src/app.js")],
+            circular_dependencies: vec![],
+            overall_risk: RiskLevel::High,
+            breaking_changes_count: 1,
+            references_count: 1,
+            complexity_change: ComplexityChange {
+                before_avg: 0.0,
+                after_avg: 0.0,
+                change_delta: 0.0,
+                complexity_increased: false,
+                file_changes: vec![],
+            },
+            analysis_time_ms: 10,
+            generated_at: Utc::now(),
+        };
+        
+        let log: serde_json::Value = serde_json::from_str(&OutputFormatter::format_sarif(&result).unwrap()).unwrap();
+        crate::core::sarif::check_schema(&log).unwrap();
+        let run = &log["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "function-removed");
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0]["ruleId"], "function-removed");
+        assert_eq!(results[0]["level"], "error");
+        assert_eq!(results[0]["locations"][0]["physicalLocation"]["region"]["startLine"], 15);
+        assert_eq!(results[0]["relatedLocations"][0]["physicalLocation"]["artifactLocation"]["uri"], "src/app.js");
+    }
+    
+    #[test]
+    fn test_normalize_paths_strips_the_analyzed_path() {
+        let reference = SymbolReference {
+            file_path: PathBuf::from("proj/src/caller.js"),
+            line_number: 3,
+            column: None,
+            context: "run()".to_string(),
+            usage_type: "call".to_string(),
+            blame: None,
+        };
+        let mut result = ImpactAnalysisResult {
+            analysis_path: PathBuf::from("proj/src"),
+            // git paths are already relative to the analyzed path
+            modified_files: vec![PathBuf::from("lib/run.js")],
+            changed_symbols: vec![ChangedSymbol {
+                name: "run".to_string(),
+                symbol_type: "function".to_string(),
+                file_path: PathBuf::from("proj/src/lib/run.js"),
+                line_number: 1,
+                change_type: ChangeType::FunctionModified,
+                signature_before: None,
+                signature_after: None,
+                signature_diff: None,
+                references: vec![reference],
+                risk_level: RiskLevel::Low,
+                breaking_change: false,
+            }],
+            affected_files: vec![PathBuf::from("proj/src/caller.js")],
+            circular_dependencies: vec![],
+            overall_risk: RiskLevel::Low,
+            breaking_changes_count: 0,
+            references_count: 1,
+            complexity_change: ComplexityChange {
+                before_avg: 1.0,
+                after_avg: 1.0,
+                change_delta: 0.0,
+                complexity_increased: false,
+                file_changes: vec![],
+            },
+            analysis_time_ms: 1,
+            generated_at: Utc::now(),
+        };
+        
+        result.normalize_paths(false);
+        assert_eq!(result.modified_files, vec![PathBuf::from("lib/run.js")]);
+        assert_eq!(result.changed_symbols[0].file_path, PathBuf::from("lib/run.js"));
+        assert_eq!(result.changed_symbols[0].references[0].location(), "caller.js:3");
+        assert_eq!(result.affected_files, vec![PathBuf::from("caller.js")]);
+        assert!(OutputFormatter::format_github_comment(&result).contains("`run()` in `lib/run.js:1`"));
+    }
+    
+    #[test]
+    fn test_detect_changed_symbols_in_patches() {
+        let mut analysis = create_test_analysis();
+        let file = &mut analysis.files[0];
+        file.functions[0].end_line = 14;
+        file.functions[1].end_line = 24;
+        let mut added = FunctionInfo::new("archiveUser".to_string());
+        (added.start_line, added.end_line) = (40, 42);
+        file.functions.push(added);
+        
+        // Paths in the diff are relative to a repository root above the analyzed directory
+        let diff = "--- a/web/test/example.js\n+++ b/web/test/example.js\n@@ -10,2 +10,2 @@\n-function getUserById(id) {\n+function getUserById(id, options) {\n   return db.find(id);\n@@ -37,0 +40,3 @@\n+function archiveUser() {\n+  return null;\n+}\n";
+        let analyzer = ImpactAnalyzer::new(ImpactConfig::default());
+        let symbols = analyzer.detect_changed_symbols_in_patches(&analysis, &parse_unified_diff(diff));
+        
+        let found: Vec<_> = symbols.iter().map(|s| (s.name.as_str(), s.change_type.clone(), s.breaking_change)).collect();
+        assert_eq!(found, vec![
+            ("getUserById", ChangeType::FunctionModified, true),
+            ("archiveUser", ChangeType::FunctionAdded, false),
+        ]);
+    }
+}
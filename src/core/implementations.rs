@@ -0,0 +1,117 @@
+//! Implementers of an interface or trait
+//!
+//! Analyzers record implemented interfaces in the `interfaces` class metadata
+//! (comma-separated): Java/TypeScript `implements`, Kotlin supertypes without
+//! a constructor call, C# bases following the `IName` convention, Rust
+//! `impl Trait for Type` blocks, and the bases after the first for Python and
+//! C++. Subclasses are reported too, since an abstract base class plays the
+//! same role in languages without interfaces. Go interfaces are satisfied
+//! implicitly and are not tracked.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::core::types::AnalysisResult;
+
+/// A class, struct or impl block that implements or extends the symbol
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Implementation {
+    pub name: String,
+    /// `implements` for interfaces and traits, `extends` for subclasses
+    pub relation: String,
+    pub file: PathBuf,
+    pub line: u32,
+}
+
+/// Find every class that implements or extends `symbol`. Generic arguments
+/// and module paths are ignored, so `Comparable` matches `Comparable<User>`
+/// and `Display` matches `fmt::Display`.
+pub fn find_implementations(results: &[AnalysisResult], symbol: &str) -> Vec<Implementation> {
+    let symbol = base_name(symbol);
+    let mut implementations = Vec::new();
+
+    for file in results {
+        for class in &file.classes {
+            let implements = class.metadata.get("interfaces")
+                .is_some_and(|interfaces| split_type_list(interfaces).any(|interface| base_name(interface) == symbol));
+            let extends = class.parent_class.as_deref().is_some_and(|parent| base_name(parent) == symbol);
+            if implements || extends {
+                implementations.push(Implementation {
+                    name: class.name.clone(),
+                    relation: if implements { "implements" } else { "extends" }.to_string(),
+                    file: file.file_info.path.clone(),
+                    line: class.start_line,
+                });
+            }
+        }
+    }
+
+    implementations.sort_by(|a, b| a.file.cmp(&b.file).then(a.line.cmp(&b.line)));
+    implementations
+}
+
+/// `Shape, Comparable<K, V>` → `Shape`, `Comparable<K, V>`
+fn split_type_list(list: &str) -> impl Iterator<Item = &str> {
+    let mut depth = 0;
+    let mut start = 0;
+    let mut items = Vec::new();
+    for (index, c) in list.char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                items.push(&list[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(&list[start..]);
+    items.into_iter().map(str::trim).filter(|item| !item.is_empty())
+}
+
+/// `std::fmt::Display` → `Display`, `Comparable<User>` → `Comparable`
+fn base_name(name: &str) -> &str {
+    let name = name.split(['<', '(', '[']).next().unwrap_or(name).trim();
+    name.rsplit(['.', ':']).next().unwrap_or(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::{ClassInfo, FileInfo, Language};
+
+    fn class(name: &str, line: u32, parent: Option<&str>, interfaces: Option<&str>) -> ClassInfo {
+        let mut class = ClassInfo::new(name.to_string());
+        class.start_line = line;
+        class.parent_class = parent.map(str::to_string);
+        if let Some(interfaces) = interfaces {
+            class.metadata.insert("interfaces".to_string(), interfaces.to_string());
+        }
+        class
+    }
+
+    #[test]
+    fn test_find_implementations_matches_base_names() {
+        let mut java = AnalysisResult::new(FileInfo::new(PathBuf::from("User.java")), Language::Java);
+        java.classes = vec![
+            class("User", 3, Some("Entity"), Some("Comparable<Map<K, V>>, Serializable")),
+            class("Admin", 20, Some("User"), None),
+        ];
+        let mut rust = AnalysisResult::new(FileInfo::new(PathBuf::from("user.rs")), Language::Rust);
+        rust.classes = vec![class("User", 1, None, None), class("User", 5, None, Some("std::fmt::Display"))];
+
+        let results = [rust, java];
+
+        let found = find_implementations(&results, "Comparable");
+        assert_eq!(found.len(), 1);
+        assert_eq!((found[0].name.as_str(), found[0].relation.as_str(), found[0].line), ("User", "implements", 3));
+
+        let display = find_implementations(&results, "fmt::Display");
+        assert_eq!((display[0].file.to_str(), display[0].line), (Some("user.rs"), 5));
+
+        let subclasses = find_implementations(&results, "User");
+        assert_eq!((subclasses[0].name.as_str(), subclasses[0].relation.as_str()), ("Admin", "extends"));
+        assert_eq!(subclasses.len(), 1);
+    }
+}
@@ -0,0 +1,161 @@
+//! Size limits for functions and files
+//!
+//! Works from the line ranges and parameter lists the analyzers already
+//! record, so every supported language is covered without external tooling.
+//! A value over its threshold is a `warning`; at twice the threshold or more
+//! it becomes an `error`. Receiver parameters (`self`, `&mut self`, `cls`)
+//! are not counted.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::core::types::{AnalysisResult, FunctionInfo};
+
+/// Limits checked by the `lint` session command (`lint.*` config keys)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LintThresholds {
+    pub max_function_lines: u32,
+    pub max_file_lines: u32,
+    pub max_parameters: u32,
+}
+
+impl Default for LintThresholds {
+    fn default() -> Self {
+        Self {
+            max_function_lines: 50,
+            max_file_lines: 500,
+            max_parameters: 5,
+        }
+    }
+}
+
+/// One exceeded limit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LintViolation {
+    /// `function-length`, `file-length` or `parameter-count`
+    pub rule: String,
+    /// `warning`, or `error` at twice the threshold
+    pub severity: String,
+    pub file: PathBuf,
+    pub line: u32,
+    /// Function name; absent for file-level violations
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    pub value: u32,
+    pub threshold: u32,
+}
+
+/// Check every file and function against `thresholds`, in file and line order
+pub fn lint(results: &[AnalysisResult], thresholds: &LintThresholds) -> Vec<LintViolation> {
+    let mut violations = Vec::new();
+
+    for file in results {
+        let path = &file.file_info.path;
+        if let Some(violation) = check("file-length", file.file_info.total_lines, thresholds.max_file_lines) {
+            violations.push(violation.at(path, 1, None));
+        }
+
+        let functions = file.free_functions()
+            .chain(file.classes.iter().flat_map(|class| class.methods.iter()));
+        for function in functions {
+            let lines = function_lines(function);
+            if let Some(violation) = check("function-length", lines, thresholds.max_function_lines) {
+                violations.push(violation.at(path, function.start_line, Some(&function.name)));
+            }
+            let parameters = function.parameters.iter().filter(|p| !is_receiver(p)).count() as u32;
+            if let Some(violation) = check("parameter-count", parameters, thresholds.max_parameters) {
+                violations.push(violation.at(path, function.start_line, Some(&function.name)));
+            }
+        }
+    }
+
+    violations.sort_by(|a, b| a.file.cmp(&b.file).then(a.line.cmp(&b.line)).then(a.rule.cmp(&b.rule)));
+    violations
+}
+
+/// Rule, severity and numbers of a violation not yet tied to a location
+struct Exceeded {
+    rule: &'static str,
+    severity: &'static str,
+    value: u32,
+    threshold: u32,
+}
+
+impl Exceeded {
+    fn at(self, file: &std::path::Path, line: u32, symbol: Option<&str>) -> LintViolation {
+        LintViolation {
+            rule: self.rule.to_string(),
+            severity: self.severity.to_string(),
+            file: file.to_path_buf(),
+            line,
+            symbol: symbol.map(str::to_string),
+            value: self.value,
+            threshold: self.threshold,
+        }
+    }
+}
+
+fn check(rule: &'static str, value: u32, threshold: u32) -> Option<Exceeded> {
+    if value <= threshold {
+        return None;
+    }
+    let severity = if value >= threshold.saturating_mul(2) { "error" } else { "warning" };
+    Some(Exceeded { rule, severity, value, threshold })
+}
+
+/// Lines spanned by the function; 0 when the analyzer recorded no end line
+fn function_lines(function: &FunctionInfo) -> u32 {
+    if function.end_line < function.start_line {
+        return 0;
+    }
+    function.end_line - function.start_line + 1
+}
+
+/// `self`, `&self`, `&mut self`, `self: Box<Self>`, `cls`
+fn is_receiver(parameter: &str) -> bool {
+    let name = parameter.split(':').next().unwrap_or(parameter).trim();
+    let name = name.trim_start_matches('&').trim_start_matches("mut ").trim();
+    name == "self" || name == "cls"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::{ClassInfo, FileInfo, Language};
+    use crate::core::types::test_support::function;
+
+    fn taking(parameters: &[&str], mut function: FunctionInfo) -> FunctionInfo {
+        function.parameters = parameters.iter().map(|p| p.to_string()).collect();
+        function
+    }
+
+    #[test]
+    fn test_lint_reports_exceeded_thresholds() {
+        let mut file = AnalysisResult::new(FileInfo::new(PathBuf::from("app.py")), Language::Python);
+        file.file_info.total_lines = 1200;
+        let long = taking(&["a"], function("long", 10, 80));
+        let method = taking(&["self", "a", "b", "c"], function("save", 100, 110));
+        file.functions = vec![long, method.clone(), function("short", 200, 205)];
+        let mut class = ClassInfo::new("Store".to_string());
+        class.methods = vec![method];
+        file.classes.push(class);
+
+        let results = [file];
+        let thresholds = LintThresholds { max_function_lines: 50, max_file_lines: 1000, max_parameters: 2 };
+        let found: Vec<_> = lint(&results, &thresholds).into_iter()
+            .map(|v| (v.rule, v.severity, v.line, v.symbol, v.value, v.threshold))
+            .collect();
+        assert_eq!(found, vec![
+            ("file-length".to_string(), "warning".to_string(), 1, None, 1200, 1000),
+            ("function-length".to_string(), "warning".to_string(), 10, Some("long".to_string()), 71, 50),
+            ("parameter-count".to_string(), "warning".to_string(), 100, Some("save".to_string()), 3, 2),
+        ]);
+
+        // 71 lines is more than twice the limit
+        let strict = LintThresholds { max_function_lines: 30, ..thresholds };
+        let long = lint(&results, &strict).into_iter().find(|v| v.rule == "function-length").unwrap();
+        assert_eq!(long.severity, "error");
+        assert_eq!(lint(&results, &LintThresholds { max_file_lines: 2000, max_parameters: 3, ..thresholds }).len(), 1);
+        assert!(is_receiver("&mut self") && is_receiver("self: Box<Self>") && !is_receiver("selfish"));
+    }
+}
@@ -3,8 +3,8 @@
 //! This module implements the time-axis memory revolution system
 //! for storing and retrieving analysis results, user memos, and cached data.
 
-use anyhow::Result;
-use chrono::{DateTime, Duration, Utc};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
@@ -105,6 +105,38 @@ impl MemoryManager {
         Ok(entry.id)
     }
     
+    /// Replace the content of an existing memory entry, keeping its `created_at`
+    /// and bumping `updated_at`
+    pub fn update(&self, name: &str, memory_type: MemoryType, content: &str) -> Result<MemoryEntry> {
+        let mut entry = self.load(name, memory_type)?;
+        entry.content = content.to_string();
+        entry.updated_at = Utc::now();
+        
+        let json = serde_json::to_string_pretty(&entry)?;
+        fs::write(self.entry_path(&entry), json)?;
+        
+        Ok(entry)
+    }
+    
+    /// Delete memory entries of a type whose name matches `name`, which may be a
+    /// glob using `*` (e.g. `"*"` clears the whole type). Returns the number deleted.
+    pub fn delete(&self, name: &str, memory_type: MemoryType) -> Result<usize> {
+        let mut deleted = 0;
+        
+        for entry in self.list_by_type(memory_type.clone())? {
+            if glob_matches(name, &entry.name) {
+                fs::remove_file(self.entry_path(&entry))?;
+                deleted += 1;
+            }
+        }
+        
+        if deleted == 0 {
+            anyhow::bail!("Memory not found: {} of type {}", name, memory_type);
+        }
+        
+        Ok(deleted)
+    }
+    
     /// Load a memory entry by name and type
     pub fn load(&self, name: &str, memory_type: MemoryType) -> Result<MemoryEntry> {
         let entries = self.list_by_type(memory_type.clone())?;
@@ -137,13 +169,13 @@ impl MemoryManager {
         }
     }
     
-    /// Get timeline view of memories
-    pub fn timeline(&self, memory_type: Option<MemoryType>, days: u32) -> Result<Vec<MemoryEntry>> {
-        let cutoff = Utc::now() - Duration::days(days as i64);
+    /// Get timeline view of memories created in `since..=until` (`until`
+    /// defaults to now)
+    pub fn timeline(&self, memory_type: Option<MemoryType>, since: DateTime<Utc>, until: Option<DateTime<Utc>>) -> Result<Vec<MemoryEntry>> {
         let mut entries = self.list(memory_type)?;
         
         // Filter by date
-        entries.retain(|entry| entry.created_at >= cutoff);
+        entries.retain(|entry| entry.created_at >= since && until.is_none_or(|until| entry.created_at <= until));
         
         // Sort by creation time, newest first
         entries.sort_by(|a, b| b.created_at.cmp(&a.created_at));
@@ -151,19 +183,21 @@ impl MemoryManager {
         Ok(entries)
     }
     
-    /// Search memories by content
-    pub fn search(&self, query: &str) -> Result<Vec<MemoryEntry>> {
-        let entries = self.list(None)?;
-        let query_lower = query.to_lowercase();
-        
+    /// Search memories whose name or content contains `query` (case-insensitive),
+    /// newest first. Each match carries a highlighted excerpt in `metadata["snippet"]`.
+    pub fn search(&self, query: &str, memory_type: Option<MemoryType>) -> Result<Vec<MemoryEntry>> {
         let mut matches = Vec::new();
-        for entry in entries {
-            if entry.name.to_lowercase().contains(&query_lower) ||
-               entry.content.to_lowercase().contains(&query_lower) {
+        
+        for mut entry in self.list(memory_type)? {
+            let snippet = highlight_snippet(&entry.content, query, SNIPPET_CONTEXT_CHARS)
+                .or_else(|| highlight_snippet(&entry.name, query, SNIPPET_CONTEXT_CHARS));
+            if let Some(snippet) = snippet {
+                entry.metadata.insert("snippet".to_string(), snippet);
                 matches.push(entry);
             }
         }
         
+        matches.sort_by_key(|entry| std::cmp::Reverse(entry.created_at));
         Ok(matches)
     }
     
@@ -206,8 +240,7 @@ impl MemoryManager {
         let mut cleaned = 0;
         for entry in entries {
             if entry.created_at < cutoff {
-                let type_dir = self.storage_path.join(entry.memory_type.to_string());
-                let file_path = type_dir.join(format!("{}.json", entry.id));
+                let file_path = self.entry_path(&entry);
                 
                 if file_path.exists() {
                     fs::remove_file(file_path)?;
@@ -219,6 +252,13 @@ impl MemoryManager {
         Ok(cleaned)
     }
     
+    /// File an entry is stored in
+    fn entry_path(&self, entry: &MemoryEntry) -> PathBuf {
+        self.storage_path
+            .join(entry.memory_type.to_string())
+            .join(format!("{}.json", entry.id))
+    }
+    
     fn list_by_type(&self, memory_type: MemoryType) -> Result<Vec<MemoryEntry>> {
         let type_dir = self.storage_path.join(memory_type.to_string());
         if !type_dir.exists() {
@@ -247,6 +287,82 @@ impl MemoryManager {
     }
 }
 
+/// Match a name against a pattern where `*` matches any run of characters
+fn glob_matches(pattern: &str, name: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == name;
+    }
+    
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if !name.starts_with(first) || name.len() < first.len() + last.len() || !name.ends_with(last) {
+        return false;
+    }
+    
+    let mut rest = &name[first.len()..name.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    
+    true
+}
+
+/// Characters of context shown on each side of a search match
+const SNIPPET_CONTEXT_CHARS: usize = 30;
+
+/// Find `needle` in `haystack` ignoring case, returning the byte range of the match
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<(usize, usize)> {
+    let needle: Vec<char> = needle.chars().flat_map(char::to_lowercase).collect();
+    if needle.is_empty() {
+        return None;
+    }
+    
+    for (start, _) in haystack.char_indices() {
+        let mut matched = 0;
+        for (offset, c) in haystack[start..].char_indices() {
+            for lower in c.to_lowercase() {
+                if needle.get(matched) != Some(&lower) {
+                    matched = usize::MAX;
+                    break;
+                }
+                matched += 1;
+            }
+            if matched == usize::MAX {
+                break;
+            }
+            if matched == needle.len() {
+                return Some((start, start + offset + c.len_utf8()));
+            }
+        }
+    }
+    
+    None
+}
+
+/// Build a one-line excerpt around the first match of `query`, with the match wrapped in `**`
+fn highlight_snippet(text: &str, query: &str, context: usize) -> Option<String> {
+    let (start, end) = find_ignore_case(text, query)?;
+    
+    let before: Vec<char> = text[..start].chars().collect();
+    let after: Vec<char> = text[end..].chars().collect();
+    let before_start = before.len().saturating_sub(context);
+    let after_end = after.len().min(context);
+    
+    let snippet = format!(
+        "{}{}**{}**{}{}",
+        if before_start > 0 { "…" } else { "" },
+        before[before_start..].iter().collect::<String>(),
+        &text[start..end],
+        after[..after_end].iter().collect::<String>(),
+        if after_end < after.len() { "…" } else { "" },
+    );
+    
+    Some(snippet.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
 /// Memory system statistics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryStats {
@@ -276,4 +392,113 @@ impl std::fmt::Display for MemoryStats {
         
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// Parse a timeline bound: an RFC 3339 timestamp (`2025-08-13T09:00:00Z`), a
+/// date (`2025-08-13`, midnight UTC) or a duration ago (`2h`, `90min`, `3days`)
+pub fn parse_time_bound(value: &str) -> Result<DateTime<Utc>> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+    }
+    
+    let ago = humantime::parse_duration(value)
+        .with_context(|| format!("Invalid time '{}': expected an RFC 3339 timestamp, a YYYY-MM-DD date or a duration like 2h", value))?;
+    Ok(Utc::now() - Duration::from_std(ago)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    
+    #[test]
+    fn test_search_by_content_and_type() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = MemoryManager::new(temp_dir.path().to_path_buf()).unwrap();
+        
+        manager.save("db-notes", MemoryType::Memo, "The Connection pool leaks under load").unwrap();
+        manager.save("api-dump", MemoryType::Api, "GET /connections returned 500").unwrap();
+        manager.save("unrelated", MemoryType::Cache, "nothing to see").unwrap();
+        
+        let results = manager.search("CONNECTION", None).unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].created_at >= results[1].created_at);
+        
+        let memos = manager.search("connection", Some(MemoryType::Memo)).unwrap();
+        assert_eq!(memos.len(), 1);
+        assert_eq!(memos[0].metadata["snippet"], "The **Connection** pool leaks under load");
+        
+        // Name-only matches are found too
+        assert_eq!(manager.search("unrel", None).unwrap().len(), 1);
+        assert!(manager.search("missing", None).unwrap().is_empty());
+    }
+    
+    #[test]
+    fn test_update_and_delete() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = MemoryManager::new(temp_dir.path().to_path_buf()).unwrap();
+        
+        manager.save("todo", MemoryType::Memo, "first draft").unwrap();
+        let original = manager.load("todo", MemoryType::Memo).unwrap();
+        
+        let updated = manager.update("todo", MemoryType::Memo, "second draft").unwrap();
+        assert_eq!(updated.id, original.id);
+        assert_eq!(updated.created_at, original.created_at);
+        assert!(updated.updated_at >= original.updated_at);
+        assert_eq!(manager.load("todo", MemoryType::Memo).unwrap().content, "second draft");
+        assert_eq!(manager.list(Some(MemoryType::Memo)).unwrap().len(), 1);
+        
+        assert!(manager.update("missing", MemoryType::Memo, "x").is_err());
+        
+        assert_eq!(manager.delete("todo", MemoryType::Memo).unwrap(), 1);
+        assert!(manager.delete("todo", MemoryType::Memo).is_err());
+        
+        // Glob deletion clears matching entries of one type only
+        manager.save("build-1", MemoryType::Cache, "a").unwrap();
+        manager.save("build-2", MemoryType::Cache, "b").unwrap();
+        manager.save("other", MemoryType::Cache, "c").unwrap();
+        manager.save("build-3", MemoryType::Memo, "d").unwrap();
+        assert_eq!(manager.delete("build-*", MemoryType::Cache).unwrap(), 2);
+        assert_eq!(manager.delete("*", MemoryType::Cache).unwrap(), 1);
+        assert_eq!(manager.list(None).unwrap().len(), 1);
+    }
+    
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("*", "anything"));
+        assert!(glob_matches("build-*", "build-42"));
+        assert!(glob_matches("*-log-*", "api-log-7"));
+        assert!(glob_matches("exact", "exact"));
+        assert!(!glob_matches("exact", "exactly"));
+        assert!(!glob_matches("a*a", "a"));
+    }
+    
+    #[test]
+    fn test_highlight_snippet_truncates_context() {
+        let text = format!("{}needle{}", "a".repeat(50), "b".repeat(50));
+        let snippet = highlight_snippet(&text, "NEEDLE", 5).unwrap();
+        assert_eq!(snippet, "…aaaaa**needle**bbbbb…");
+        
+        assert_eq!(highlight_snippet("設計メモ: Rust移行", "rust", 3).unwrap(), "…モ: **Rust**移行");
+        assert!(highlight_snippet("abc", "", 3).is_none());
+    }
+    
+    #[test]
+    fn test_timeline_window() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = MemoryManager::new(temp_dir.path().to_path_buf()).unwrap();
+        manager.save("recent", MemoryType::Memo, "just now").unwrap();
+        
+        assert_eq!(manager.timeline(None, parse_time_bound("2h").unwrap(), None).unwrap().len(), 1);
+        let before = parse_time_bound("1h").unwrap();
+        assert!(manager.timeline(None, parse_time_bound("2h").unwrap(), Some(before)).unwrap().is_empty());
+        assert!(manager.timeline(Some(MemoryType::Cache), parse_time_bound("2h").unwrap(), None).unwrap().is_empty());
+        
+        assert_eq!(parse_time_bound("2025-08-13").unwrap().to_rfc3339(), "2025-08-13T00:00:00+00:00");
+        assert_eq!(parse_time_bound("2025-08-13T09:30:00+02:00").unwrap().to_rfc3339(), "2025-08-13T07:30:00+00:00");
+        assert!(parse_time_bound("last tuesday").is_err());
+    }
+}
@@ -7,4 +7,20 @@ pub mod preview;
 pub mod ast;
 pub mod moveclass;
 pub mod impact;
-pub mod incremental;
\ No newline at end of file
+pub mod incremental;
+pub mod duplicates;
+pub mod callgraph;
+pub mod ignore;
+pub mod schema;
+pub mod api_surface;
+pub mod annotations;
+pub mod baseline;
+pub mod implementations;
+pub mod lint;
+pub mod patch;
+pub mod output;
+pub mod symbol_index;
+pub mod style;
+pub mod unreachable;
+pub mod sarif;
+pub mod compare;
@@ -0,0 +1,42 @@
+//! JSON style of command output
+//!
+//! Commands print JSON pretty by default; the global `--compact` flag
+//! switches every command to single-line JSON. The choice is process-wide so
+//! results formatted deep inside session commands follow it too. Files
+//! nekocode writes for itself or for people to edit (config, baselines,
+//! sessions) stay pretty.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static COMPACT: AtomicBool = AtomicBool::new(false);
+
+/// Print JSON output on one line instead of indented
+pub fn set_compact(compact: bool) {
+    COMPACT.store(compact, Ordering::Relaxed);
+}
+
+/// Serialize command output in the selected style
+pub fn to_json<T: Serialize + ?Sized>(value: &T) -> serde_json::Result<String> {
+    serialize(value, COMPACT.load(Ordering::Relaxed))
+}
+
+fn serialize<T: Serialize + ?Sized>(value: &T, compact: bool) -> serde_json::Result<String> {
+    if compact {
+        serde_json::to_string(value)
+    } else {
+        serde_json::to_string_pretty(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compact_output() {
+        let value = serde_json::json!({ "files": [1, 2] });
+        assert!(serialize(&value, false).unwrap().contains('\n'));
+        assert_eq!(serialize(&value, true).unwrap(), r#"{"files":[1,2]}"#);
+    }
+}
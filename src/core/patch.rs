@@ -0,0 +1,179 @@
+//! Unified diff parsing
+//!
+//! Reads the output of `git diff` or `diff -u` into the set of lines each file
+//! changed, numbered in the new version of the file. Removed lines have no
+//! line in the new version: removals replaced by added lines count as those
+//! lines, other removals are recorded at the line before them (line 1 at the
+//! top of a file). Git's `a/` and `b/` prefixes are stripped from paths.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// Changes a diff makes to one file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FilePatch {
+    /// Path of the new version, or of the old one for deleted files
+    pub path: PathBuf,
+    /// The diff removes the file
+    pub deleted: bool,
+    /// Lines of the new version that were added
+    pub added_lines: BTreeSet<u32>,
+    /// Added lines plus the lines next to removals
+    pub changed_lines: BTreeSet<u32>,
+}
+
+impl FilePatch {
+    /// Whether any changed line falls within `start..=end`
+    pub fn touches(&self, start: u32, end: u32) -> bool {
+        start <= end && self.changed_lines.range(start..=end).next().is_some()
+    }
+
+    /// Whether every line of `start..=end` was added
+    pub fn adds(&self, start: u32, end: u32) -> bool {
+        start <= end && (start..=end).all(|line| self.added_lines.contains(&line))
+    }
+}
+
+/// Parse a unified diff; files appear in diff order, binary and
+/// mode-only changes are skipped
+pub fn parse_unified_diff(diff: &str) -> Vec<FilePatch> {
+    let mut patches: Vec<FilePatch> = Vec::new();
+    let mut old_path: Option<PathBuf> = None;
+    // Lines still expected from the current hunk's old and new sides
+    let mut old_remaining = 0u32;
+    let mut new_remaining = 0u32;
+    let mut new_line = 0u32;
+    // Lines were removed and no added line has replaced them yet
+    let mut pending_removal = false;
+
+    for line in diff.lines() {
+        if old_remaining > 0 || new_remaining > 0 {
+            let Some(patch) = patches.last_mut() else { break };
+            match line.chars().next() {
+                Some('+') => {
+                    patch.added_lines.insert(new_line);
+                    patch.changed_lines.insert(new_line);
+                    pending_removal = false;
+                    new_line += 1;
+                    new_remaining = new_remaining.saturating_sub(1);
+                }
+                Some('-') => {
+                    pending_removal = true;
+                    old_remaining = old_remaining.saturating_sub(1);
+                }
+                // `\ No newline at end of file`
+                Some('\\') => {}
+                // Context lines; some tools strip the space from empty ones
+                _ => {
+                    if std::mem::take(&mut pending_removal) {
+                        patch.changed_lines.insert(new_line.saturating_sub(1).max(1));
+                    }
+                    new_line += 1;
+                    old_remaining = old_remaining.saturating_sub(1);
+                    new_remaining = new_remaining.saturating_sub(1);
+                }
+            }
+            if old_remaining == 0 && new_remaining == 0 && std::mem::take(&mut pending_removal) {
+                patch.changed_lines.insert(new_line.saturating_sub(1).max(1));
+            }
+            continue;
+        }
+
+        if let Some(path) = line.strip_prefix("--- ") {
+            old_path = diff_path(path);
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            let new_path = diff_path(path);
+            let deleted = new_path.is_none();
+            if let Some(path) = new_path.or_else(|| old_path.take()) {
+                patches.push(FilePatch { path, deleted, ..Default::default() });
+            }
+        } else if let Some(header) = line.strip_prefix("@@ ") {
+            if let Some((old_count, new_start, new_count)) = parse_hunk_header(header) {
+                old_remaining = old_count;
+                new_remaining = new_count;
+                // An empty new side (`+5,0`) means the removal follows line 5
+                new_line = if new_count == 0 { new_start + 1 } else { new_start };
+            }
+        }
+    }
+
+    patches
+}
+
+/// `a/src/app.py\t2024-01-01 ...` → `src/app.py`; `/dev/null` → None
+fn diff_path(path: &str) -> Option<PathBuf> {
+    let path = path.split('\t').next().unwrap_or(path).trim_end();
+    if path == "/dev/null" {
+        return None;
+    }
+    let path = path.strip_prefix("a/").or_else(|| path.strip_prefix("b/")).unwrap_or(path);
+    Some(Path::new(path).to_path_buf())
+}
+
+/// `-12,3 +12,4 @@ fn main()` → (3, 12, 4); counts default to 1
+fn parse_hunk_header(header: &str) -> Option<(u32, u32, u32)> {
+    let mut ranges = header.split_whitespace();
+    let old = ranges.next()?.strip_prefix('-')?;
+    let new = ranges.next()?.strip_prefix('+')?;
+    let count = |range: &str| -> Option<(u32, u32)> {
+        match range.split_once(',') {
+            Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
+        }
+    };
+    let (_, old_count) = count(old)?;
+    let (new_start, new_count) = count(new)?;
+    Some((old_count, new_start, new_count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_unified_diff() {
+        let diff = "\
+diff --git a/src/app.py b/src/app.py
+index 1111111..2222222 100644
+--- a/src/app.py
++++ b/src/app.py
+@@ -1,5 +1,6 @@
+ def load(path):
+-    return open(path)
++    with open(path) as f:
++        return f.read()
+
+ def save(path, data):
+     pass
+@@ -20,3 +21,2 @@ def helper():
+     a = 1
+--- removed comment line
+     return a
+diff --git a/old.py b/old.py
+deleted file mode 100644
+--- a/old.py
++++ /dev/null
+@@ -1,2 +0,0 @@
+-def gone():
+-    pass
+--- /tmp/new.js\t2024-01-01 10:00:00
++++ /tmp/new.js\t2024-01-01 10:00:01
+@@ -0,0 +1,2 @@
++function added() {
++}
+";
+        let patches = parse_unified_diff(diff);
+        assert_eq!(patches.len(), 3);
+
+        let app = &patches[0];
+        assert_eq!(app.path, PathBuf::from("src/app.py"));
+        assert_eq!(app.added_lines.iter().copied().collect::<Vec<_>>(), vec![2, 3]);
+        assert_eq!(app.changed_lines.iter().copied().collect::<Vec<_>>(), vec![2, 3, 21]);
+        assert!(app.touches(1, 3) && app.touches(20, 22) && !app.touches(5, 6));
+        assert!(app.adds(2, 3) && !app.adds(1, 3));
+
+        assert_eq!((patches[1].path.to_str(), patches[1].deleted), (Some("old.py"), true));
+        assert_eq!(patches[2].path, PathBuf::from("/tmp/new.js"));
+        assert!(patches[2].adds(1, 2));
+    }
+}
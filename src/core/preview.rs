@@ -5,12 +5,23 @@
 
 use anyhow::Result;
 use chrono::{DateTime, Utc};
+use regex::{NoExpand, Regex, RegexBuilder};
 use serde::{Deserialize, Serialize};
+use similar::TextDiff;
 use std::collections::HashMap;
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::ops::Range;
+use std::path::{Component, Path, PathBuf};
 
 use crate::core::moveclass::{MoveClassEngine, MoveOptions};
+use crate::core::session::SESSION_DIR;
+
+/// Lines of context shown around each change unless `--context` says otherwise
+pub const DEFAULT_PREVIEW_CONTEXT: u32 = 3;
+
+fn default_context() -> u32 {
+    DEFAULT_PREVIEW_CONTEXT
+}
 
 /// Types of preview operations
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -19,8 +30,20 @@ pub enum PreviewOperation {
         file: PathBuf,
         pattern: String,
         replacement: String,
+        #[serde(default)]
+        options: ReplaceOptions,
         matches: Vec<MatchInfo>,
     },
+    /// A replacement across every file of a session, confirmed all-or-nothing
+    SessionReplace {
+        session_id: String,
+        pattern: String,
+        replacement: String,
+        #[serde(default)]
+        options: ReplaceOptions,
+        /// One `Replace` operation per file with at least one match
+        files: Vec<PreviewOperation>,
+    },
     Insert {
         file: PathBuf,
         position: u32,
@@ -42,6 +65,54 @@ pub enum PreviewOperation {
     },
 }
 
+impl PreviewOperation {
+    /// Session the operation works on, if any
+    pub fn session_id(&self) -> Option<&str> {
+        match self {
+            PreviewOperation::SessionReplace { session_id, .. }
+            | PreviewOperation::MoveClass { session_id, .. } => Some(session_id),
+            _ => None,
+        }
+    }
+}
+
+/// How a replace pattern is matched
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplaceOptions {
+    /// Treat the pattern as a regex; the replacement may then use `$1` / `${name}`
+    pub use_regex: bool,
+    pub ignore_case: bool,
+    pub whole_word: bool,
+}
+
+impl ReplaceOptions {
+    /// Compile the pattern; literal patterns are escaped so both modes share one matcher
+    fn build_regex(&self, pattern: &str) -> Result<Regex> {
+        let mut source = if self.use_regex {
+            pattern.to_string()
+        } else {
+            regex::escape(pattern)
+        };
+        if self.whole_word {
+            source = format!(r"\b(?:{})\b", source);
+        }
+        
+        RegexBuilder::new(&source)
+            .case_insensitive(self.ignore_case)
+            .build()
+            .map_err(|e| anyhow::anyhow!("Invalid pattern '{}': {}", pattern, e))
+    }
+    
+    /// Replace every match in `text`, expanding capture groups only in regex mode
+    fn replace_all(&self, regex: &Regex, text: &str, replacement: &str) -> String {
+        if self.use_regex {
+            regex.replace_all(text, replacement).into_owned()
+        } else {
+            regex.replace_all(text, NoExpand(replacement)).into_owned()
+        }
+    }
+}
+
 /// Information about a text match
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MatchInfo {
@@ -49,9 +120,54 @@ pub struct MatchInfo {
     pub column_start: u32,
     pub column_end: u32,
     pub matched_text: String,
+    /// Text this match is replaced with, after capture-group expansion
+    #[serde(default)]
+    pub replaced_text: String,
+    /// The lines the match spans
     pub line_content: String,
 }
 
+impl MatchInfo {
+    /// Line the match ends on
+    fn last_line(&self) -> u32 {
+        self.line_number + self.line_content.lines().count().max(1) as u32 - 1
+    }
+}
+
+/// New content a confirmed preview would write to one file
+#[derive(Debug, Clone)]
+struct FileChange {
+    path: PathBuf,
+    /// `None` if the file does not exist yet
+    original: Option<String>,
+    updated: String,
+}
+
+/// Original content of one file touched by a confirmed preview
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FileSnapshot {
+    path: PathBuf,
+    /// `None` if the operation created the file
+    original: Option<String>,
+    /// Hash of the content the operation wrote, to detect later edits
+    applied_hash: String,
+}
+
+fn content_hash(content: &str) -> String {
+    format!("{:016x}", xxhash_rust::xxh3::xxh3_64(content.as_bytes()))
+}
+
+/// Directory of the undo snapshots kept in `session_dir`. Previews of a
+/// session get a subdirectory named after it, so deleting the session can
+/// take its snapshots along.
+pub(crate) fn undo_dir(session_dir: &Path, session_id: Option<&str>) -> PathBuf {
+    let dir = session_dir.join("undo");
+    match session_id {
+        Some(session_id) => dir.join(session_id),
+        None => dir,
+    }
+}
+
 /// A preview entry
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PreviewEntry {
@@ -60,12 +176,15 @@ pub struct PreviewEntry {
     pub created_at: DateTime<Utc>,
     pub preview_text: String,
     pub confirmed: bool,
+    /// Lines of context around each change, in `preview_text` and `diff`
+    #[serde(default = "default_context")]
+    pub context: u32,
 }
 
 impl PreviewEntry {
-    pub fn new(operation: PreviewOperation) -> Result<Self> {
+    pub fn new(operation: PreviewOperation, context: u32) -> Result<Self> {
         let id = uuid::Uuid::new_v4().to_string()[..8].to_string();
-        let preview_text = Self::generate_preview(&operation)?;
+        let preview_text = Self::generate_preview(&operation, context as usize)?;
         
         Ok(Self {
             id,
@@ -73,23 +192,115 @@ impl PreviewEntry {
             created_at: Utc::now(),
             preview_text,
             confirmed: false,
+            context,
         })
     }
     
-    fn generate_preview(operation: &PreviewOperation) -> Result<String> {
+    /// `lines[range]` numbered like the file; nothing if the file no longer has them
+    fn context_lines(lines: &[&str], range: Range<usize>) -> String {
+        let Some(context) = lines.get(range.clone()) else {
+            return String::new();
+        };
+        context.iter().zip(range.start + 1..)
+            .map(|(line, number)| format!("  {:>5} | {}\n", number, line))
+            .collect()
+    }
+    
+    /// Split matches, in line order, where their context would neither
+    /// overlap nor touch, so each hunk shows its lines once
+    fn hunks(matches: &[MatchInfo], context: usize) -> Vec<&[MatchInfo]> {
+        let mut hunks = Vec::new();
+        let mut start = 0;
+        for i in 1..=matches.len() {
+            if i == matches.len() || matches[i].line_number > matches[i - 1].last_line() + 2 * context as u32 + 1 {
+                hunks.push(&matches[start..i]);
+                start = i;
+            }
+        }
+        hunks
+    }
+    
+    /// The lines of a hunk: context around it and between its matches, and
+    /// each matched line before and after the replacement
+    fn hunk_lines(lines: &[&str], hunk: &[MatchInfo], replace: impl Fn(&str) -> String, context: usize) -> String {
+        // Lines after the first of a multi-line match line up with it
+        let indent = |text: &str| text.replace('\n', "\n          ");
+        let first = hunk[0].line_number as usize - 1;
+        let mut text = Self::context_lines(lines, first.saturating_sub(context)..first);
+        let mut next = first;
+        for match_info in hunk {
+            let line = match_info.line_number as usize - 1;
+            // Later matches on lines already shown
+            if line < next {
+                continue;
+            }
+            text.push_str(&Self::context_lines(lines, next..line));
+            text.push_str(&format!("  Before: {}\n", indent(&match_info.line_content)));
+            text.push_str(&format!("  After:  {}\n", indent(&replace(&match_info.line_content))));
+            next = match_info.last_line() as usize;
+        }
+        let after = next.min(lines.len());
+        text.push_str(&Self::context_lines(lines, after..(after + context).min(lines.len())));
+        text
+    }
+    
+    fn generate_preview(operation: &PreviewOperation, context: usize) -> Result<String> {
         match operation {
-            PreviewOperation::Replace { file, pattern, replacement, matches } => {
+            PreviewOperation::Replace { file, pattern, replacement, options, matches } => {
+                let regex = options.build_regex(pattern)?;
+                let content = fs::read_to_string(file).unwrap_or_default();
+                let lines: Vec<&str> = content.lines().collect();
                 let mut preview = format!("Replace Operation Preview\n");
                 preview.push_str(&format!("File: {}\n", file.display()));
-                preview.push_str(&format!("Pattern: '{}'\n", pattern));
+                preview.push_str(&format!("Pattern: '{}'{}\n", pattern, if options.use_regex { " (regex)" } else { "" }));
                 preview.push_str(&format!("Replacement: '{}'\n", replacement));
                 preview.push_str(&format!("Matches: {}\n\n", matches.len()));
                 
-                for (i, match_info) in matches.iter().enumerate() {
-                    preview.push_str(&format!("Match {}: Line {}\n", i + 1, match_info.line_number));
-                    preview.push_str(&format!("  Before: {}\n", match_info.line_content));
-                    let replaced = match_info.line_content.replace(pattern, replacement);
-                    preview.push_str(&format!("  After:  {}\n\n", replaced));
+                let mut number = 0;
+                for hunk in Self::hunks(matches, context) {
+                    for match_info in hunk {
+                        number += 1;
+                        preview.push_str(&format!("Match {}: Line {}, column {}: '{}' -> '{}'\n",
+                            number, match_info.line_number, match_info.column_start + 1,
+                            match_info.matched_text, match_info.replaced_text));
+                    }
+                    let replace = |line: &str| options.replace_all(&regex, line, replacement);
+                    preview.push_str(&Self::hunk_lines(&lines, hunk, replace, context));
+                    preview.push('\n');
+                }
+                
+                Ok(preview)
+            }
+            
+            PreviewOperation::SessionReplace { session_id, pattern, replacement, options, files } => {
+                let total: usize = files.iter().map(|op| match op {
+                    PreviewOperation::Replace { matches, .. } => matches.len(),
+                    _ => 0,
+                }).sum();
+                
+                let mut preview = String::from("Session Replace Operation Preview\n");
+                preview.push_str(&format!("Session: {}\n", session_id));
+                preview.push_str(&format!("Pattern: '{}'{}\n", pattern, if options.use_regex { " (regex)" } else { "" }));
+                preview.push_str(&format!("Replacement: '{}'\n", replacement));
+                preview.push_str(&format!("Matches: {} in {} files\n\n", total, files.len()));
+                
+                let regex = options.build_regex(pattern)?;
+                for op in files {
+                    if let PreviewOperation::Replace { file, matches, .. } = op {
+                        let content = fs::read_to_string(file).unwrap_or_default();
+                        let lines: Vec<&str> = content.lines().collect();
+                        preview.push_str(&format!("{} ({} matches)\n", file.display(), matches.len()));
+                        for hunk in Self::hunks(matches, context) {
+                            for match_info in hunk {
+                                preview.push_str(&format!("  Line {}, column {}: '{}' -> '{}'\n",
+                                    match_info.line_number, match_info.column_start + 1,
+                                    match_info.matched_text, match_info.replaced_text));
+                            }
+                            let replace = |line: &str| options.replace_all(&regex, line, replacement);
+                            preview.push_str(&Self::hunk_lines(&lines, hunk, replace, context));
+                            preview.push('\n');
+                        }
+                    }
                 }
                 
                 Ok(preview)
@@ -101,6 +312,22 @@ impl PreviewEntry {
                 preview.push_str(&format!("Position: Line {}\n", position));
                 preview.push_str(&format!("Content to insert:\n{}\n", content));
                 
+                let file_content = fs::read_to_string(file).unwrap_or_default();
+                let lines: Vec<&str> = file_content.lines().collect();
+                if context > 0 && !lines.is_empty() {
+                    let at = if *position == 0 || *position as usize > lines.len() {
+                        lines.len()
+                    } else {
+                        *position as usize - 1
+                    };
+                    preview.push_str("\nIn context:\n");
+                    preview.push_str(&Self::context_lines(&lines, at.saturating_sub(context)..at));
+                    for line in content.lines() {
+                        preview.push_str(&format!("  {:>5} | {}\n", "+", line));
+                    }
+                    preview.push_str(&Self::context_lines(&lines, at..(at + context).min(lines.len())));
+                }
+                
                 Ok(preview)
             }
             
@@ -135,8 +362,11 @@ impl PreviewEntry {
 /// Preview manager
 pub struct PreviewManager {
     previews: HashMap<String, PreviewEntry>,
-    temp_dir: PathBuf,
+    /// Session directory the undo snapshots are stored in
+    session_dir: PathBuf,
     storage_file: PathBuf,
+    /// Context for previews created from now on
+    context: u32,
 }
 
 impl PreviewManager {
@@ -158,11 +388,18 @@ impl PreviewManager {
         
         Ok(Self {
             previews,
-            temp_dir,
+            session_dir: std::env::current_dir()?.join(SESSION_DIR),
             storage_file,
+            context: DEFAULT_PREVIEW_CONTEXT,
         })
     }
     
+    /// Lines of context around each change in previews created afterwards,
+    /// like `diff -U`
+    pub fn set_context(&mut self, context: u32) {
+        self.context = context;
+    }
+    
     /// Save previews to persistent storage
     fn save_to_storage(&self) -> Result<()> {
         let content = serde_json::to_string_pretty(&self.previews)?;
@@ -171,18 +408,64 @@ impl PreviewManager {
     }
     
     /// Create a replace preview
-    pub fn create_replace_preview(&mut self, file: &Path, pattern: &str, replacement: &str) -> Result<String> {
+    pub fn create_replace_preview(&mut self, file: &Path, pattern: &str, replacement: &str, options: ReplaceOptions) -> Result<String> {
         let content = fs::read_to_string(file)?;
-        let matches = self.find_matches(&content, pattern)?;
+        let matches = self.find_matches(&content, pattern, replacement, &options)?;
         
         let operation = PreviewOperation::Replace {
             file: file.to_path_buf(),
             pattern: pattern.to_string(),
             replacement: replacement.to_string(),
+            options,
             matches,
         };
         
-        let preview = PreviewEntry::new(operation)?;
+        let preview = PreviewEntry::new(operation, self.context)?;
+        let id = preview.id.clone();
+        self.previews.insert(id.clone(), preview);
+        self.save_to_storage()?;
+        
+        Ok(id)
+    }
+    
+    /// Create one preview covering a replacement in every file of a session
+    pub fn create_session_replace_preview(
+        &mut self,
+        session_id: &str,
+        files: &[PathBuf],
+        pattern: &str,
+        replacement: &str,
+        options: ReplaceOptions,
+    ) -> Result<String> {
+        let mut operations = Vec::new();
+        
+        for file in files {
+            let content = fs::read_to_string(file).map_err(|e| anyhow::anyhow!(
+                "Failed to read {}: {} (run session-update if files were removed)", file.display(), e
+            ))?;
+            let matches = self.find_matches(&content, pattern, replacement, &options)?;
+            if matches.is_empty() {
+                continue;
+            }
+            
+            operations.push(PreviewOperation::Replace {
+                file: file.clone(),
+                pattern: pattern.to_string(),
+                replacement: replacement.to_string(),
+                options: options.clone(),
+                matches,
+            });
+        }
+        
+        let operation = PreviewOperation::SessionReplace {
+            session_id: session_id.to_string(),
+            pattern: pattern.to_string(),
+            replacement: replacement.to_string(),
+            options,
+            files: operations,
+        };
+        
+        let preview = PreviewEntry::new(operation, self.context)?;
         let id = preview.id.clone();
         self.previews.insert(id.clone(), preview);
         self.save_to_storage()?;
@@ -198,7 +481,7 @@ impl PreviewManager {
             content: content.to_string(),
         };
         
-        let preview = PreviewEntry::new(operation)?;
+        let preview = PreviewEntry::new(operation, self.context)?;
         let id = preview.id.clone();
         self.previews.insert(id.clone(), preview);
         self.save_to_storage()?;
@@ -232,7 +515,7 @@ impl PreviewManager {
             lines: extracted_lines,
         };
         
-        let preview = PreviewEntry::new(operation)?;
+        let preview = PreviewEntry::new(operation, self.context)?;
         let id = preview.id.clone();
         self.previews.insert(id.clone(), preview);
         self.save_to_storage()?;
@@ -264,7 +547,7 @@ impl PreviewManager {
             class_content,
         };
         
-        let preview = PreviewEntry::new(operation)?;
+        let preview = PreviewEntry::new(operation, self.context)?;
         let id = preview.id.clone();
         self.previews.insert(id.clone(), preview);
         self.save_to_storage()?;
@@ -291,8 +574,10 @@ impl PreviewManager {
             preview.operation.clone()
         };
         
-        // Execute the operation
-        let result = self.execute_operation(&operation)?;
+        // Execute the operation, keeping the original files for `undo`
+        let changes = self.file_changes(&operation)?;
+        let result = self.execute_operation(&operation, &changes)?;
+        self.save_snapshot(id, &changes)?;
         
         // Mark as confirmed
         if let Some(preview) = self.previews.get_mut(id) {
@@ -303,13 +588,149 @@ impl PreviewManager {
         Ok(result)
     }
     
-    fn execute_operation(&self, operation: &PreviewOperation) -> Result<String> {
+    /// Render the changes a preview would make as a unified diff that
+    /// `git apply` accepts. Files are compared against their current content.
+    pub fn diff(&self, id: &str) -> Result<String> {
+        let preview = self.previews.get(id)
+            .ok_or_else(|| anyhow::anyhow!("Preview not found: {}", id))?;
+        let cwd = std::env::current_dir().unwrap_or_default();
+        
+        let mut diff = String::new();
+        for change in self.file_changes(&preview.operation)? {
+            let path = change.path.strip_prefix(&cwd).unwrap_or(&change.path);
+            // Outside the working directory, drop the root like `git diff --no-index`
+            let path = path.components()
+                .filter(|c| !matches!(c, Component::RootDir | Component::Prefix(_)))
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            let old_header = match change.original {
+                Some(_) => format!("a/{}", path),
+                None => String::from("/dev/null"),
+            };
+            let original = change.original.unwrap_or_default();
+            
+            diff.push_str(&TextDiff::from_lines(&original, &change.updated)
+                .unified_diff()
+                .context_radius(preview.context as usize)
+                .header(&old_header, &format!("b/{}", path))
+                .to_string());
+        }
+        
+        Ok(diff)
+    }
+    
+    /// Restore the files a confirmed preview changed. Refuses if any of them
+    /// was edited after the operation was applied.
+    pub fn undo(&mut self, id: &str) -> Result<String> {
+        let snapshot_file = self.snapshot_file(id);
+        if !snapshot_file.exists() {
+            anyhow::bail!("Nothing to undo for preview {}: it was not confirmed or was already undone", id);
+        }
+        let snapshots: Vec<FileSnapshot> = serde_json::from_str(&fs::read_to_string(&snapshot_file)?)?;
+        
+        // Check every file before restoring any of them
+        for snapshot in &snapshots {
+            let current = fs::read_to_string(&snapshot.path).map_err(|e| anyhow::anyhow!(
+                "Cannot undo preview {}: failed to read {}: {}", id, snapshot.path.display(), e
+            ))?;
+            if content_hash(&current) != snapshot.applied_hash {
+                anyhow::bail!(
+                    "Cannot undo preview {}: {} was modified after the operation was applied",
+                    id, snapshot.path.display()
+                );
+            }
+        }
+        
+        for snapshot in &snapshots {
+            match &snapshot.original {
+                Some(original) => fs::write(&snapshot.path, original)?,
+                None => fs::remove_file(&snapshot.path)?,
+            }
+        }
+        fs::remove_file(&snapshot_file)?;
+        
+        if let Some(preview) = self.previews.get_mut(id) {
+            preview.confirmed = false;
+        }
+        self.save_to_storage()?;
+        
+        Ok(format!("Undo completed: {} files restored", snapshots.len()))
+    }
+    
+    fn snapshot_file(&self, id: &str) -> PathBuf {
+        let session_id = self.previews.get(id).and_then(|preview| preview.operation.session_id());
+        undo_dir(&self.session_dir, session_id).join(format!("{}.json", id))
+    }
+    
+    /// Store the original content of every changed file, keyed by preview ID
+    fn save_snapshot(&self, id: &str, changes: &[FileChange]) -> Result<()> {
+        let snapshots: Vec<FileSnapshot> = changes.iter()
+            .map(|change| FileSnapshot {
+                path: change.path.clone(),
+                original: change.original.clone(),
+                applied_hash: content_hash(&change.updated),
+            })
+            .collect();
+        
+        let snapshot_file = self.snapshot_file(id);
+        if let Some(dir) = snapshot_file.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(snapshot_file, serde_json::to_string_pretty(&snapshots)?)?;
+        Ok(())
+    }
+    
+    fn execute_operation(&self, operation: &PreviewOperation, changes: &[FileChange]) -> Result<String> {
+        // Restore already written files if a later write fails
+        for (i, change) in changes.iter().enumerate() {
+            if let Err(e) = fs::write(&change.path, &change.updated) {
+                for written in &changes[..i] {
+                    let _ = match &written.original {
+                        Some(original) => fs::write(&written.path, original),
+                        None => fs::remove_file(&written.path),
+                    };
+                }
+                anyhow::bail!("Failed to write {}: {}; all files were left unchanged", change.path.display(), e);
+            }
+        }
+        
+        Ok(match operation {
+            PreviewOperation::Replace { file, .. } => {
+                format!("Replace operation completed in {}", file.display())
+            }
+            PreviewOperation::SessionReplace { .. } => {
+                format!("Replace operation completed in {} files", changes.len())
+            }
+            PreviewOperation::Insert { file, .. } => {
+                format!("Insert operation completed in {}", file.display())
+            }
+            PreviewOperation::MoveLines { source, destination, .. } => {
+                format!("Move lines operation completed: {} -> {}", source.display(), destination.display())
+            }
+            PreviewOperation::MoveClass { symbol_id, target, .. } => {
+                format!("Move class operation completed: symbol {} moved to {}", symbol_id, target.display())
+            }
+        })
+    }
+    
+    /// Compute every file an operation writes, without touching the disk.
+    /// Confirming and `diff` share this, so the diff is exactly what gets written.
+    fn file_changes(&self, operation: &PreviewOperation) -> Result<Vec<FileChange>> {
         match operation {
-            PreviewOperation::Replace { file, pattern, replacement, .. } => {
+            PreviewOperation::Replace { file, pattern, replacement, options, .. } => {
                 let content = fs::read_to_string(file)?;
-                let new_content = content.replace(pattern, replacement);
-                fs::write(file, new_content)?;
-                Ok(format!("Replace operation completed in {}", file.display()))
+                let regex = options.build_regex(pattern)?;
+                let updated = options.replace_all(&regex, &content, replacement);
+                Ok(vec![FileChange { path: file.clone(), original: Some(content), updated }])
+            }
+            
+            PreviewOperation::SessionReplace { files, .. } => {
+                let mut changes = Vec::new();
+                for op in files {
+                    changes.extend(self.file_changes(op)?);
+                }
+                Ok(changes)
             }
             
             PreviewOperation::Insert { file, position, content } => {
@@ -323,10 +744,8 @@ impl PreviewManager {
                 };
                 
                 lines.insert(insert_pos, content);
-                let new_content = lines.join("\n");
-                fs::write(file, new_content)?;
-                
-                Ok(format!("Insert operation completed in {}", file.display()))
+                let updated = lines.join("\n");
+                Ok(vec![FileChange { path: file.clone(), original: Some(file_content), updated }])
             }
             
             PreviewOperation::MoveLines { source, start, count, destination, position, .. } => {
@@ -345,75 +764,109 @@ impl PreviewManager {
                 // Remove lines from source
                 source_lines.drain(start_idx..end_idx);
                 let new_source_content = source_lines.join("\n");
-                fs::write(source, new_source_content)?;
                 
-                // Read destination file
-                let dest_content = if destination.exists() {
-                    fs::read_to_string(destination)?
+                // Read destination file, which may be the source we just changed
+                // spelled differently, e.g. `a.txt` and `./a.txt`
+                let same_file = destination.canonicalize().ok() == Some(source.canonicalize()?);
+                let dest_original = if same_file {
+                    Some(new_source_content.clone())
+                } else if destination.exists() {
+                    Some(fs::read_to_string(destination)?)
                 } else {
-                    String::new()
+                    None
                 };
-                
+                let dest_content = dest_original.clone().unwrap_or_default();
                 let mut dest_lines: Vec<&str> = dest_content.lines().collect();
                 
+                // `position` counts lines of the original file. Within one file,
+                // positions past the moved range shift up by the removed lines,
+                // and positions inside it leave the lines where they were.
+                let mut position = *position as usize;
+                if same_file && position > start_idx + 1 {
+                    position = if position > end_idx {
+                        position - (end_idx - start_idx)
+                    } else {
+                        start_idx + 1
+                    };
+                }
+                
                 // Insert lines at destination
-                let insert_pos = if *position == 0 || *position as usize > dest_lines.len() {
+                let insert_pos = if position == 0 || position > dest_lines.len() {
                     dest_lines.len()
                 } else {
-                    (*position - 1) as usize
+                    position - 1
                 };
                 
                 for (i, line) in moved_lines.iter().enumerate() {
                     dest_lines.insert(insert_pos + i, line);
                 }
-                
                 let new_dest_content = dest_lines.join("\n");
-                fs::write(destination, new_dest_content)?;
                 
-                Ok(format!("Move lines operation completed: {} -> {}", source.display(), destination.display()))
+                if same_file {
+                    return Ok(vec![FileChange { path: source.clone(), original: Some(source_content), updated: new_dest_content }]);
+                }
+                Ok(vec![
+                    FileChange { path: source.clone(), original: Some(source_content), updated: new_source_content },
+                    FileChange { path: destination.clone(), original: dest_original, updated: new_dest_content },
+                ])
             }
             
-            PreviewOperation::MoveClass { session_id, symbol_id, target, class_content } => {
-                // Use MoveClassEngine for execution
-                let engine = MoveClassEngine::new();
-                
+            PreviewOperation::MoveClass { target, class_content, .. } => {
                 // For now, create a simple implementation
                 // In a full implementation, this would:
                 // 1. Load the session and AST
                 // 2. Find the source file containing the symbol
                 // 3. Use engine.move_class() with proper parameters
                 
-                // Simulate the move by writing to target file
+                // Simulate the move by appending to the target file
                 if target.exists() {
-                    let mut content = fs::read_to_string(target)?;
-                    content.push('\n');
-                    content.push_str(class_content);
-                    fs::write(target, content)?;
+                    let original = fs::read_to_string(target)?;
+                    let mut updated = original.clone();
+                    updated.push('\n');
+                    updated.push_str(class_content);
+                    Ok(vec![FileChange { path: target.clone(), original: Some(original), updated }])
                 } else {
-                    fs::write(target, class_content)?;
+                    Ok(vec![FileChange { path: target.clone(), original: None, updated: class_content.clone() }])
                 }
-                
-                Ok(format!("Move class operation completed: symbol {} moved to {}", symbol_id, target.display()))
             }
         }
     }
     
-    fn find_matches(&self, content: &str, pattern: &str) -> Result<Vec<MatchInfo>> {
+    fn find_matches(&self, content: &str, pattern: &str, replacement: &str, options: &ReplaceOptions) -> Result<Vec<MatchInfo>> {
+        let regex = options.build_regex(pattern)?;
         let mut matches = Vec::new();
+        // Line number at byte `counted`, carried forward from match to match
+        let (mut counted, mut line_number) = (0, 1);
         
-        for (line_num, line) in content.lines().enumerate() {
-            let mut start = 0;
-            while let Some(pos) = line[start..].find(pattern) {
-                let actual_pos = start + pos;
-                matches.push(MatchInfo {
-                    line_number: (line_num + 1) as u32,
-                    column_start: actual_pos as u32,
-                    column_end: (actual_pos + pattern.len()) as u32,
-                    matched_text: pattern.to_string(),
-                    line_content: line.to_string(),
-                });
-                start = actual_pos + pattern.len();
-            }
+        // Search the whole content, as `replace_all` does, so a pattern can
+        // span lines and the preview shows what confirming would change
+        for captures in regex.captures_iter(content) {
+            let Some(found) = captures.get(0) else { continue };
+            line_number += content[counted..found.start()].matches('\n').count();
+            counted = found.start();
+            
+            let line_start = content[..found.start()].rfind('\n').map_or(0, |i| i + 1);
+            // A match ending in a newline ends on the line that newline closes
+            let line_end = match found.as_str().strip_suffix('\n') {
+                Some(_) => found.end() - 1,
+                None => content[found.end()..].find('\n').map_or(content.len(), |i| found.end() + i),
+            };
+            let replaced_text = if options.use_regex {
+                let mut expanded = String::new();
+                captures.expand(replacement, &mut expanded);
+                expanded
+            } else {
+                replacement.to_string()
+            };
+            
+            matches.push(MatchInfo {
+                line_number: line_number as u32,
+                column_start: (found.start() - line_start) as u32,
+                column_end: (found.end() - line_start) as u32,
+                matched_text: found.as_str().to_string(),
+                replaced_text,
+                line_content: content[line_start..line_end].lines().collect::<Vec<_>>().join("\n"),
+            });
         }
         
         Ok(matches)
@@ -424,4 +877,227 @@ impl Default for PreviewManager {
     fn default() -> Self {
         Self::new().expect("Failed to create PreviewManager")
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn manager(dir: &Path) -> PreviewManager {
+        PreviewManager {
+            previews: HashMap::new(),
+            session_dir: dir.to_path_buf(),
+            storage_file: dir.join("previews.json"),
+            context: DEFAULT_PREVIEW_CONTEXT,
+        }
+    }
+    
+    #[test]
+    fn test_regex_replace_expands_capture_groups() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("lib.js");
+        fs::write(&file, "load_old();\nconst x = save_old(load_old);\n").unwrap();
+        
+        let mut manager = manager(dir.path());
+        let options = ReplaceOptions { use_regex: true, ..Default::default() };
+        let id = manager.create_replace_preview(&file, r"(\w+)_old", "${1}_new", options).unwrap();
+        
+        let preview = manager.get_preview(&id).unwrap();
+        let PreviewOperation::Replace { matches, .. } = &preview.operation else { unreachable!() };
+        let replaced: Vec<_> = matches.iter().map(|m| (m.matched_text.as_str(), m.replaced_text.as_str())).collect();
+        assert_eq!(replaced, vec![("load_old", "load_new"), ("save_old", "save_new"), ("load_old", "load_new")]);
+        assert!(preview.preview_text.contains("'save_old' -> 'save_new'"));
+        assert!(preview.preview_text.contains("After:  const x = save_new(load_new);"));
+        
+        manager.confirm_preview(&id).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "load_new();\nconst x = save_new(load_new);\n");
+    }
+    
+    #[test]
+    fn test_session_replace_is_one_preview() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a.py");
+        let b = dir.path().join("b.py");
+        let c = dir.path().join("c.py");
+        fs::write(&a, "def fetch_old():\n    return fetch_old\n").unwrap();
+        fs::write(&b, "from a import fetch_old\n").unwrap();
+        fs::write(&c, "print('untouched')\n").unwrap();
+        
+        let mut manager = manager(dir.path());
+        let options = ReplaceOptions { use_regex: true, ..Default::default() };
+        let files = vec![a.clone(), b.clone(), c.clone()];
+        let id = manager.create_session_replace_preview("s1", &files, r"(\w+)_old", "${1}_new", options).unwrap();
+        
+        let preview = manager.get_preview(&id).unwrap();
+        assert!(preview.preview_text.contains("Matches: 3 in 2 files"));
+        assert!(preview.preview_text.contains(&format!("{} (2 matches)", a.display())));
+        // Both matches of a.py share one hunk with the default context
+        assert!(preview.preview_text.contains(concat!(
+            "  Line 1, column 5: 'fetch_old' -> 'fetch_new'\n",
+            "  Line 2, column 12: 'fetch_old' -> 'fetch_new'\n",
+            "  Before: def fetch_old():\n  After:  def fetch_new():\n",
+            "  Before:     return fetch_old\n  After:      return fetch_new\n\n")));
+        
+        manager.confirm_preview(&id).unwrap();
+        assert_eq!(fs::read_to_string(&a).unwrap(), "def fetch_new():\n    return fetch_new\n");
+        assert_eq!(fs::read_to_string(&b).unwrap(), "from a import fetch_new\n");
+        assert_eq!(fs::read_to_string(&c).unwrap(), "print('untouched')\n");
+    }
+    
+    #[test]
+    fn test_regex_matches_across_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.rs");
+        fs::write(&file, "// keep\nfn a(\n    x,\n) {}\nfn b() {}\n").unwrap();
+        
+        let mut manager = manager(dir.path());
+        let options = ReplaceOptions { use_regex: true, ..Default::default() };
+        let id = manager.create_replace_preview(&file, r"\(\s+(\w+),\s+\)", "($1)", options).unwrap();
+        let text = &manager.get_preview(&id).unwrap().preview_text;
+        assert!(text.contains("Matches: 1\n"), "{}", text);
+        assert!(text.contains(concat!(
+            "      1 | // keep\n",
+            "  Before: fn a(\n              x,\n          ) {}\n",
+            "  After:  fn a(x) {}\n",
+            "      5 | fn b() {}\n\n")), "{}", text);
+        
+        manager.confirm_preview(&id).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "// keep\nfn a(x) {}\nfn b() {}\n");
+    }
+    
+    #[test]
+    fn test_literal_replace_with_case_and_word_options() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = manager(dir.path());
+        let content = "Item item items $1";
+        
+        let literal = ReplaceOptions::default();
+        assert_eq!(manager.find_matches(content, "item", "x", &literal).unwrap().len(), 2);
+        
+        let options = ReplaceOptions { ignore_case: true, whole_word: true, ..Default::default() };
+        let matches = manager.find_matches(content, "item", "x", &options).unwrap();
+        let columns: Vec<_> = matches.iter().map(|m| m.column_start).collect();
+        assert_eq!(columns, vec![0, 5]);
+        
+        // Without --regex, `$` in the pattern and the replacement is literal
+        let matches = manager.find_matches(content, "$1", "$2", &literal).unwrap();
+        assert_eq!(matches[0].replaced_text, "$2");
+    }
+    
+    #[test]
+    fn test_diff_renders_unified_hunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("app.js");
+        let lines: Vec<String> = (1..=10).map(|i| format!("line{}", i)).collect();
+        fs::write(&file, format!("{}\n", lines.join("\n"))).unwrap();
+        
+        let mut manager = manager(dir.path());
+        let id = manager.create_replace_preview(&file, "line5", "changed", ReplaceOptions::default()).unwrap();
+        let diff = manager.diff(&id).unwrap();
+        
+        let path = file.strip_prefix(std::env::current_dir().unwrap()).unwrap_or(&file);
+        let path = path.to_string_lossy().replace('\\', "/");
+        let path = path.trim_start_matches('/');
+        assert!(diff.starts_with(&format!("--- a/{}\n+++ b/{}\n", path, path)));
+        assert!(diff.contains("@@ -2,7 +2,7 @@\n line2\n line3\n line4\n-line5\n+changed\n line6\n"));
+        
+        // The diff is computed without writing anything
+        assert!(fs::read_to_string(&file).unwrap().contains("line5"));
+        
+        let id = manager.create_moveclass_preview("s1", "Foo", &dir.path().join("new.js")).unwrap();
+        assert!(manager.diff(&id).unwrap().starts_with("--- /dev/null\n"));
+    }
+    
+    #[test]
+    fn test_undo_restores_and_detects_later_edits() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("a.txt");
+        let destination = dir.path().join("b.txt");
+        fs::write(&source, "one\ntwo\nthree").unwrap();
+        
+        let mut manager = manager(dir.path());
+        let id = manager.create_movelines_preview(&source, 2, 1, &destination, 1).unwrap();
+        manager.confirm_preview(&id).unwrap();
+        assert_eq!(fs::read_to_string(&destination).unwrap(), "two");
+        assert!(undo_dir(dir.path(), None).join(format!("{}.json", id)).exists());
+        
+        manager.undo(&id).unwrap();
+        assert_eq!(fs::read_to_string(&source).unwrap(), "one\ntwo\nthree");
+        assert!(!destination.exists());
+        assert!(manager.undo(&id).is_err());
+        
+        let id = manager.create_replace_preview(&source, "two", "2", ReplaceOptions::default()).unwrap();
+        manager.confirm_preview(&id).unwrap();
+        fs::write(&source, "edited by hand").unwrap();
+        let error = manager.undo(&id).unwrap_err().to_string();
+        assert!(error.contains("was modified after the operation was applied"));
+        assert_eq!(fs::read_to_string(&source).unwrap(), "edited by hand");
+    }
+    
+    #[test]
+    fn test_movelines_within_one_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.txt");
+        let original = "1\n2\n3\n4\n5\n6\n7\n8";
+        let mut manager = manager(dir.path());
+        let mut moved = |start, count, position| {
+            fs::write(&file, original).unwrap();
+            let id = manager.create_movelines_preview(&file, start, count, &file, position).unwrap();
+            manager.confirm_preview(&id).unwrap();
+            fs::read_to_string(&file).unwrap()
+        };
+        
+        assert_eq!(moved(5, 3, 2), "1\n5\n6\n7\n2\n3\n4\n8");
+        // Downwards, the position still names a line of the original file
+        assert_eq!(moved(2, 2, 7), "1\n4\n5\n6\n2\n3\n7\n8");
+        assert_eq!(moved(2, 2, 0), "1\n4\n5\n6\n7\n8\n2\n3");
+        assert_eq!(moved(2, 2, 3), original);
+        
+        // Another spelling of the same path is still one file
+        fs::write(&file, original).unwrap();
+        fs::create_dir(dir.path().join("sub")).unwrap();
+        let respelled = dir.path().join("sub").join("..").join("a.txt");
+        let id = manager.create_movelines_preview(&file, 5, 3, &respelled, 2).unwrap();
+        manager.confirm_preview(&id).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "1\n5\n6\n7\n2\n3\n4\n8");
+    }
+    
+    #[test]
+    fn test_preview_context_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.txt");
+        fs::write(&file, "1\n2\n3\n4\nold\n6\n7\n8\n9").unwrap();
+        
+        let mut manager = manager(dir.path());
+        let id = manager.create_replace_preview(&file, "old", "new", ReplaceOptions::default()).unwrap();
+        let preview = manager.get_preview(&id).unwrap();
+        assert!(preview.preview_text.contains(
+            "      2 | 2\n      3 | 3\n      4 | 4\n  Before: old\n  After:  new\n      6 | 6\n      7 | 7\n      8 | 8\n\n"));
+        assert!(!preview.preview_text.contains("| 1\n"));
+        
+        manager.set_context(1);
+        let id = manager.create_replace_preview(&file, "old", "new", ReplaceOptions::default()).unwrap();
+        let text = &manager.get_preview(&id).unwrap().preview_text;
+        assert!(text.contains("      4 | 4\n  Before: old\n  After:  new\n      6 | 6\n\n"));
+        assert!(!text.contains("| 3\n") && !text.contains("| 7\n"));
+        assert!(manager.diff(&id).unwrap().contains("@@ -4,3 +4,3 @@\n 4\n-old\n+new\n 6\n"));
+        
+        // Matches whose context overlaps share one hunk
+        let spread = dir.path().join("b.txt");
+        fs::write(&spread, "1\nold\n3\nold\n5\n6\n7\nold").unwrap();
+        let id = manager.create_replace_preview(&spread, "old", "new", ReplaceOptions::default()).unwrap();
+        let text = &manager.get_preview(&id).unwrap().preview_text;
+        assert!(text.contains(concat!(
+            "Match 1: Line 2, column 1: 'old' -> 'new'\nMatch 2: Line 4, column 1: 'old' -> 'new'\n",
+            "      1 | 1\n  Before: old\n  After:  new\n      3 | 3\n  Before: old\n  After:  new\n      5 | 5\n\n",
+            "Match 3: Line 8, column 1: 'old' -> 'new'\n      7 | 7\n  Before: old\n  After:  new\n\n")), "{}", text);
+        
+        let id = manager.create_insert_preview(&file, 2, "inserted").unwrap();
+        let text = &manager.get_preview(&id).unwrap().preview_text;
+        assert!(text.ends_with("In context:\n      1 | 1\n      + | inserted\n      2 | 2\n"));
+        
+        manager.set_context(0);
+        let id = manager.create_insert_preview(&file, 2, "inserted").unwrap();
+        assert!(!manager.get_preview(&id).unwrap().preview_text.contains("In context"));
+    }
+}
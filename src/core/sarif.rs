@@ -0,0 +1,180 @@
+//! SARIF 2.1.0 logs for code-scanning integrations
+//!
+//! GitHub code scanning and most CI annotators ingest SARIF: one run per
+//! tool, a rule table on the tool driver, and results that point at a file
+//! and line through `physicalLocation`. `analyze-impact --format sarif` and
+//! `lint --format sarif` both build their logs here.
+
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+use crate::core::lint::LintViolation;
+
+pub const SARIF_VERSION: &str = "2.1.0";
+pub const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// A rule listed on the tool driver
+#[derive(Debug, Clone, PartialEq)]
+pub struct SarifRule {
+    pub id: &'static str,
+    pub description: &'static str,
+}
+
+/// One finding; `level` is `error`, `warning` or `note`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SarifResult {
+    pub rule_id: &'static str,
+    pub level: &'static str,
+    pub message: String,
+    pub file: PathBuf,
+    pub line: u32,
+    /// Other places the finding concerns, such as references to a removed function
+    pub related: Vec<(PathBuf, u32, String)>,
+}
+
+/// A SARIF log with a single nekocode run. Only rules that have results are
+/// listed, and each result refers to its rule by index as well as by id.
+pub fn sarif_log(rules: &[SarifRule], results: &[SarifResult]) -> Value {
+    let used: Vec<&SarifRule> = rules.iter()
+        .filter(|rule| results.iter().any(|result| result.rule_id == rule.id))
+        .collect();
+
+    let results: Vec<Value> = results.iter().map(|result| {
+        let mut value = json!({
+            "ruleId": result.rule_id,
+            "level": result.level,
+            "message": { "text": result.message },
+            "locations": [location(&result.file, result.line, None)]
+        });
+        if let Some(index) = used.iter().position(|rule| rule.id == result.rule_id) {
+            value["ruleIndex"] = json!(index);
+        }
+        if !result.related.is_empty() {
+            value["relatedLocations"] = result.related.iter().enumerate()
+                .map(|(id, (file, line, text))| {
+                    let mut related = location(file, *line, Some(text));
+                    related["id"] = json!(id);
+                    related
+                })
+                .collect();
+        }
+        value
+    }).collect();
+
+    let rules: Vec<Value> = used.iter()
+        .map(|rule| json!({ "id": rule.id, "shortDescription": { "text": rule.description } }))
+        .collect();
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": SARIF_VERSION,
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "nekocode",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules
+                }
+            },
+            "results": results
+        }]
+    })
+}
+
+/// `physicalLocation` for a file and 1-based line. SARIF URIs use forward
+/// slashes, and a line of 0 (unknown) is reported as the first line.
+fn location(file: &Path, line: u32, message: Option<&str>) -> Value {
+    let mut location = json!({
+        "physicalLocation": {
+            "artifactLocation": { "uri": file.to_string_lossy().replace('\\', "/") },
+            "region": { "startLine": line.max(1) }
+        }
+    });
+    if let Some(text) = message {
+        location["message"] = json!({ "text": text });
+    }
+    location
+}
+
+/// Rules reported by the `lint` session command
+pub const LINT_RULES: &[SarifRule] = &[
+    SarifRule { id: "function-length", description: "Function is longer than the configured limit" },
+    SarifRule { id: "file-length", description: "File is longer than the configured limit" },
+    SarifRule { id: "parameter-count", description: "Function takes more parameters than the configured limit" },
+];
+
+/// SARIF log for lint violations; their `warning`/`error` severities are
+/// SARIF levels already
+pub fn lint_sarif(violations: &[LintViolation]) -> Value {
+    let results: Vec<SarifResult> = violations.iter().filter_map(|violation| {
+        let rule = LINT_RULES.iter().find(|rule| rule.id == violation.rule)?;
+        let subject = match &violation.symbol {
+            Some(symbol) => format!("'{}'", symbol),
+            None => "File".to_string(),
+        };
+        let measure = if violation.rule == "parameter-count" { "parameters" } else { "lines" };
+        Some(SarifResult {
+            rule_id: rule.id,
+            level: if violation.severity == "error" { "error" } else { "warning" },
+            message: format!("{} has {} {} (limit {})", subject, violation.value, measure, violation.threshold),
+            file: violation.file.clone(),
+            line: violation.line,
+            related: Vec::new(),
+        })
+    }).collect();
+    sarif_log(LINT_RULES, &results)
+}
+
+/// Validate `log` against the SARIF 2.1.0 schema in `tests/fixtures`. The
+/// fixture is a subset of the upstream schema covering the objects nekocode
+/// writes, not the upstream file itself; swap in sarif-schema-2.1.0.json
+/// unmodified to validate against the full definitions.
+#[cfg(test)]
+pub(crate) fn check_schema(log: &Value) -> std::result::Result<(), String> {
+    let schema: Value = serde_json::from_str(include_str!("../../tests/fixtures/sarif-2.1.0.json"))
+        .map_err(|e| e.to_string())?;
+    let validator = jsonschema::JSONSchema::compile(&schema).map_err(|e| e.to_string())?;
+    let result = validator.validate(log).map_err(|errors| {
+        errors.map(|error| format!("{}: {}", error.instance_path, error)).collect::<Vec<_>>().join("; ")
+    });
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lint_sarif_matches_schema() {
+        let violation = |rule: &str, severity: &str, line, symbol: Option<&str>| LintViolation {
+            rule: rule.to_string(),
+            severity: severity.to_string(),
+            file: PathBuf::from("src\\big.rs"),
+            line,
+            symbol: symbol.map(str::to_string),
+            value: 120,
+            threshold: 50,
+        };
+        let log = lint_sarif(&[
+            violation("file-length", "warning", 1, None),
+            violation("function-length", "error", 0, Some("run")),
+        ]);
+        check_schema(&log).unwrap();
+
+        let run = &log["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 2);
+        let result = &run["results"][1];
+        assert_eq!(result["ruleId"], "function-length");
+        assert_eq!(result["level"], "error");
+        assert_eq!(result["message"]["text"], "'run' has 120 lines (limit 50)");
+        let location = &result["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "src/big.rs");
+        assert_eq!(location["region"]["startLine"], 1);
+
+        assert!(check_schema(&json!({ "version": "2.0.0", "runs": [] })).is_err());
+        assert!(check_schema(&json!({ "version": "2.1.0", "runs": [{ "tool": {} }] })).is_err());
+        let mut bad_level = log.clone();
+        bad_level["runs"][0]["results"][0]["level"] = json!("critical");
+        assert!(check_schema(&bad_level).is_err());
+    }
+}
@@ -0,0 +1,64 @@
+//! JSON Schema for the JSON that nekocode emits
+//!
+//! Schemas are generated from the serde types themselves, so they always
+//! match the real output.
+
+use anyhow::Result;
+use schemars::schema_for;
+
+use crate::core::impact::ImpactAnalysisResult;
+use crate::core::session::SessionInfo;
+use crate::core::types::{AnalysisResult, DirectoryAnalysis};
+
+/// Result types a schema can be generated for, with what each describes
+pub const SCHEMA_TYPES: &[(&str, &str)] = &[
+    ("analysis", "`analyze` output for a directory (DirectoryAnalysis)"),
+    ("file", "analysis of a single file (AnalysisResult)"),
+    ("session", "stored session data (SessionInfo)"),
+    ("impact", "`analyze-impact` JSON output (ImpactAnalysisResult)"),
+];
+
+/// Generate the JSON Schema for one of [`SCHEMA_TYPES`]
+pub fn schema_for_type(name: &str) -> Result<serde_json::Value> {
+    let schema = match name {
+        "analysis" => schema_for!(DirectoryAnalysis),
+        "file" => schema_for!(AnalysisResult),
+        "session" => schema_for!(SessionInfo),
+        "impact" => schema_for!(ImpactAnalysisResult),
+        _ => anyhow::bail!(
+            "Unknown schema type '{}'. Available: {}",
+            name,
+            SCHEMA_TYPES.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(", ")
+        ),
+    };
+    
+    Ok(serde_json::to_value(schema)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::{FileInfo, Language};
+    use std::path::PathBuf;
+    
+    #[test]
+    fn test_schema_matches_serialized_output() {
+        for (name, _) in SCHEMA_TYPES {
+            let schema = schema_for_type(name).unwrap();
+            assert!(schema["definitions"].is_object(), "{} has no definitions", name);
+        }
+        
+        let schema = schema_for_type("file").unwrap();
+        let properties = schema["properties"].as_object().unwrap();
+        let result = AnalysisResult::new(FileInfo::new(PathBuf::from("a.rs")), Language::Rust);
+        let output = serde_json::to_value(&result).unwrap();
+        for key in output.as_object().unwrap().keys() {
+            assert!(properties.contains_key(key), "schema is missing {}", key);
+        }
+        
+        let function = &schema["definitions"]["FunctionInfo"]["properties"];
+        assert!(function["complexity"].is_object());
+        
+        assert!(schema_for_type("nope").unwrap_err().to_string().contains("analysis"));
+    }
+}
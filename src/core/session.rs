@@ -6,21 +6,37 @@
 use anyhow::{Context, Result};
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
-use std::collections::HashMap;
+use futures::StreamExt;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
 use std::fs;
+use std::sync::OnceLock;
 
 use crate::core::types::{
-    AnalysisConfig, AnalysisResult, DirectoryAnalysis, FileInfo, Language,
+    relative_to_root, AnalysisConfig, AnalysisResult, DirectoryAnalysis, FileInfo, FileStyle, Language, LineStats,
 };
-use crate::core::ast::{ASTNode, ASTStatistics};
+use crate::core::ast::{ASTNode, ASTStatistics, AstDiff, DumpFilter};
+use crate::core::ignore::{IgnoreFile, IGNORE_FILE_NAME};
+use crate::core::output::to_json;
+use crate::core::symbol_index::SymbolIndex;
+use crate::core::duplicates::{find_duplicates, FunctionSource, DEFAULT_SIMILARITY_THRESHOLD};
+use crate::core::callgraph::{build_call_graph, find_unused, to_dot};
+use crate::core::api_surface::{api_surface, render_markdown};
+use crate::core::annotations::{scan_annotations, DEFAULT_ANNOTATION_TAGS};
+use crate::core::implementations::find_implementations;
+use crate::core::lint::{lint, LintThresholds};
+use crate::core::compare::compare_files;
+use crate::core::sarif::lint_sarif;
+use crate::core::style::style_report;
+use crate::core::unreachable::find_unreachable;
 use crate::core::incremental::{ChangeDetector, FileChange, IncrementalSummary};
-use crate::analyzers::javascript::{JavaScriptAnalyzer, TreeSitterJavaScriptAnalyzer};
-use crate::analyzers::traits::LanguageAnalyzer;
+use crate::core::preview::undo_dir;
+use crate::commands::watch::{PidManager, WatchLog};
 
 /// Session storage for managing multiple analysis sessions
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SessionInfo {
     pub id: String,
     pub path: PathBuf,
@@ -34,23 +50,125 @@ pub struct SessionInfo {
     
     // 🚀 Incremental analysis data
     pub change_detector: Option<ChangeDetector>,
+    
+    /// Name index over `analysis_results`; kept in memory only, built on the
+    /// first lookup and dropped whenever the results change
+    #[serde(skip)]
+    #[schemars(skip)]
+    symbol_index: OnceLock<SymbolIndex>,
+}
+
+/// `metadata` key recording how file paths are stored: `relative` to the
+/// session root, or `absolute` (`session-create --absolute`). Sessions saved
+/// before paths were normalized have no entry.
+const PATHS_METADATA_KEY: &str = "paths";
+
+impl SessionInfo {
+    /// Name index over the analysis results, built on first use
+    pub fn symbol_index(&self) -> &SymbolIndex {
+        self.symbol_index.get_or_init(|| SymbolIndex::build(&self.analysis_results))
+    }
+
+    /// Whether file paths are stored in full rather than relative to the session root
+    pub fn stores_absolute_paths(&self) -> bool {
+        self.metadata.get(PATHS_METADATA_KEY).map(String::as_str) == Some("absolute")
+    }
+
+    /// The form `file`, as found on disk, is stored in
+    pub fn stored_path(&self, file: &Path) -> PathBuf {
+        if self.stores_absolute_paths() {
+            // A deleted file can no longer be canonicalized, but its directory usually can
+            file.canonicalize().ok()
+                .or_else(|| Some(file.parent()?.canonicalize().ok()?.join(file.file_name()?)))
+                .unwrap_or_else(|| file.to_path_buf())
+        } else {
+            relative_to_root(file, &self.path)
+        }
+    }
+
+    /// Where to read a stored file from
+    pub fn source_path(&self, file: &Path) -> PathBuf {
+        if file.is_absolute() {
+            return file.to_path_buf();
+        }
+        // A single-file session stores just the file name
+        match self.path.parent() {
+            Some(parent) if self.path.is_file() => parent.join(file),
+            _ => self.path.join(file),
+        }
+    }
+
+    /// Stored path relative to the session root, whichever form it is stored in
+    pub fn relative_path(&self, file: &Path) -> PathBuf {
+        relative_to_root(file, &self.path)
+    }
+
+    /// Analysis of `file`, given as stored, as a path on disk, or by a
+    /// trailing part of its path (`user.py`) when that names a single file
+    pub fn find_file(&self, file: &Path) -> Result<&AnalysisResult> {
+        let stored = self.stored_path(file);
+        if let Some(result) = self.analysis_results.iter()
+            .find(|result| result.file_info.path == file || result.file_info.path == stored)
+        {
+            return Ok(result);
+        }
+        let matches: Vec<&AnalysisResult> = self.analysis_results.iter()
+            .filter(|result| result.file_info.path.ends_with(file))
+            .collect();
+        match matches.as_slice() {
+            [result] => Ok(result),
+            [] => anyhow::bail!("File not in session {}: {}", self.id, file.display()),
+            _ => anyhow::bail!("'{}' matches {} files in session {}; give more of the path",
+                file.display(), matches.len(), self.id),
+        }
+    }
 }
 
 /// Session directory management
-const SESSION_DIR: &str = ".nekocode_sessions";
+pub(crate) const SESSION_DIR: &str = ".nekocode_sessions";
+
+/// Outcome of [`SessionManager::prune`]
+#[derive(Debug, Clone, Serialize)]
+pub struct PruneReport {
+    pub dry_run: bool,
+    /// Sessions deleted, or that would be deleted in a dry run
+    pub removed: Vec<PrunedSession>,
+    /// Matching sessions left alone because a watcher is running for them
+    pub skipped_watched: Vec<String>,
+    pub kept: usize,
+    pub freed_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PrunedSession {
+    pub id: String,
+    pub path: PathBuf,
+    pub last_accessed: DateTime<Utc>,
+    pub bytes: u64,
+}
 
 /// Global session manager with file-based persistence
 pub struct SessionManager {
     sessions: HashMap<String, AnalysisSession>,
     session_info: HashMap<String, SessionInfo>,
     session_dir: PathBuf,
+    /// Files analyzed concurrently when a session is created or updated; 0 means one per CPU
+    analysis_threads: usize,
+    /// Tags `annotations` reports when no `--tags` are given
+    annotation_tags: Vec<String>,
+    /// Limits `lint` starts from; its flags override them
+    lint_thresholds: LintThresholds,
 }
 
 impl SessionManager {
     /// Create a new session manager with file-based persistence
     pub fn new() -> Result<Self> {
-        let session_dir = std::env::current_dir()?.join(SESSION_DIR);
-        
+        Self::with_dir(std::env::current_dir()?.join(SESSION_DIR))
+    }
+    
+    /// Session manager keeping its sessions in `session_dir` instead of
+    /// `.nekocode_sessions` in the working directory
+    pub fn with_dir(session_dir: PathBuf) -> Result<Self> {
         // Create session directory if it doesn't exist
         if !session_dir.exists() {
             fs::create_dir_all(&session_dir)
@@ -61,6 +179,9 @@ impl SessionManager {
             sessions: HashMap::new(),
             session_info: HashMap::new(),
             session_dir,
+            analysis_threads: 0,
+            annotation_tags: DEFAULT_ANNOTATION_TAGS.iter().map(|tag| tag.to_string()).collect(),
+            lint_thresholds: LintThresholds::default(),
         };
         
         // Load existing sessions from disk
@@ -83,7 +204,7 @@ impl SessionManager {
                 if let Some(session_id) = path.file_stem().and_then(|s| s.to_str()) {
                     if let Ok(session_info) = self.load_session_info(session_id) {
                         // Create analysis session from stored data
-                        let mut session = AnalysisSession::new();
+                        let session = AnalysisSession::new();
                         
                         // Store session info
                         self.session_info.insert(session_id.to_string(), session_info);
@@ -102,9 +223,17 @@ impl SessionManager {
         let content = fs::read_to_string(&session_file)
             .with_context(|| format!("Failed to read session file: {}", session_file.display()))?;
         
-        let session_info: SessionInfo = serde_json::from_str(&content)
+        let mut session_info: SessionInfo = serde_json::from_str(&content)
             .with_context(|| format!("Failed to parse session file: {}", session_file.display()))?;
         
+        // Older sessions stored paths as they were discovered
+        if !session_info.metadata.contains_key(PATHS_METADATA_KEY) {
+            for result in &mut session_info.analysis_results {
+                result.file_info.path = relative_to_root(&result.file_info.path, &session_info.path);
+            }
+            session_info.metadata.insert(PATHS_METADATA_KEY.to_string(), "relative".to_string());
+        }
+        
         Ok(session_info)
     }
     
@@ -120,15 +249,46 @@ impl SessionManager {
         Ok(())
     }
     
+    /// Tags the `annotations` command reports when it is not given `--tags`
+    pub fn set_annotation_tags(&mut self, tags: Vec<String>) {
+        self.annotation_tags = tags;
+    }
+    
+    /// Limits the `lint` command checks unless its flags override them
+    pub fn set_lint_thresholds(&mut self, thresholds: LintThresholds) {
+        self.lint_thresholds = thresholds;
+    }
+    
+    /// Limit how many files are analyzed concurrently; 0 means one per CPU
+    pub fn set_analysis_threads(&mut self, threads: usize) {
+        self.analysis_threads = threads;
+        for session in self.sessions.values_mut() {
+            session.config.max_threads = threads;
+        }
+    }
+    
+    fn new_analysis_session(&self) -> AnalysisSession {
+        AnalysisSession::with_config(AnalysisConfig {
+            max_threads: self.analysis_threads,
+            ..Default::default()
+        })
+    }
+    
     pub async fn create_session(&mut self, path: &Path) -> Result<String> {
+        self.create_session_with_paths(path, false).await
+    }
+    
+    /// Create a session, storing file paths relative to `path`, or as
+    /// canonical full paths when `absolute` is set
+    pub async fn create_session_with_paths(&mut self, path: &Path, absolute: bool) -> Result<String> {
         let session_id = uuid::Uuid::new_v4().to_string()[..8].to_string();
-        let mut session = AnalysisSession::new();
+        let mut session = self.new_analysis_session();
         
         // Initialize session with path analysis  
         let analysis_results = session.analyze_path(path, false).await?;
         
         // Extract analysis results from DirectoryAnalysis
-        let files = analysis_results.files;
+        let mut files = analysis_results.files;
         
         // Calculate combined AST statistics
         let combined_ast_stats = Self::calculate_combined_ast_stats(&files);
@@ -139,16 +299,22 @@ impl SessionManager {
             log::warn!("Failed to initialize change detector: {}", e);
         }
 
-        let session_info = SessionInfo {
+        let paths = if absolute { "absolute" } else { "relative" };
+        let mut session_info = SessionInfo {
             id: session_id.clone(),
             path: path.to_path_buf(),
             created_at: Utc::now(),
             last_accessed: Utc::now(),
-            metadata: HashMap::new(),
-            analysis_results: files,
+            metadata: HashMap::from([(PATHS_METADATA_KEY.to_string(), paths.to_string())]),
+            analysis_results: Vec::new(),
             combined_ast_stats,
             change_detector: Some(change_detector),
+            symbol_index: OnceLock::new(),
         };
+        for result in &mut files {
+            result.file_info.path = session_info.stored_path(&result.file_info.path);
+        }
+        session_info.analysis_results = files;
         
         // Save to disk
         self.save_session_info(&session_info)?;
@@ -167,18 +333,7 @@ impl SessionManager {
         for result in analysis_results {
             if let Some(ref stats) = result.ast_statistics {
                 has_ast_data = true;
-                combined.total_nodes += stats.total_nodes;
-                combined.max_depth = combined.max_depth.max(stats.max_depth);
-                combined.classes += stats.classes;
-                combined.functions += stats.functions;
-                combined.methods += stats.methods;
-                combined.variables += stats.variables;
-                combined.control_structures += stats.control_structures;
-                
-                // Merge node type counts
-                for (node_type, count) in &stats.node_type_counts {
-                    *combined.node_type_counts.entry(node_type.clone()).or_insert(0) += count;
-                }
+                combined.add(stats);
             }
         }
         
@@ -189,6 +344,30 @@ impl SessionManager {
         }
     }
     
+    /// Update combined AST statistics for one re-analyzed file: take back its
+    /// `old` contribution and add the `new` one
+    fn replace_ast_stats(combined: &mut Option<ASTStatistics>, old: Option<&AnalysisResult>, new: Option<&AnalysisResult>) {
+        if let (Some(combined), Some(old)) = (combined.as_mut(), old.and_then(|r| r.ast_statistics.as_ref())) {
+            combined.subtract(old);
+        }
+        if let Some(new) = new.and_then(|r| r.ast_statistics.as_ref()) {
+            combined.get_or_insert_with(ASTStatistics::default).add(new);
+        }
+    }
+    
+    /// Finish incremental `replace_ast_stats` updates: `max_depth` cannot be
+    /// subtracted, so it is taken from the files again
+    fn settle_ast_stats(combined: &mut Option<ASTStatistics>, analysis_results: &[AnalysisResult]) {
+        let max_depth = analysis_results.iter()
+            .filter_map(|r| r.ast_statistics.as_ref())
+            .map(|stats| stats.max_depth)
+            .max();
+        match (combined.as_mut(), max_depth) {
+            (Some(stats), Some(max_depth)) => stats.max_depth = max_depth,
+            _ => *combined = None,
+        }
+    }
+    
     pub fn get_session(&mut self, session_id: &str) -> Option<&mut AnalysisSession> {
         // Update last accessed time first
         if self.session_info.contains_key(session_id) {
@@ -210,6 +389,107 @@ impl SessionManager {
         self.session_info.values().collect()
     }
     
+    /// Directory holding the session files
+    pub fn session_dir(&self) -> &Path {
+        &self.session_dir
+    }
+    
+    /// Files belonging to a session: its JSON, the watch event log, the
+    /// watcher's PID and lock files and the undo snapshots of its confirmed
+    /// previews. Incremental state lives in the JSON.
+    fn session_artifacts(&self, session_id: &str) -> Vec<PathBuf> {
+        let mut artifacts = vec![
+            self.session_dir.join(format!("{}.json", session_id)),
+            WatchLog::for_session(&self.session_dir, session_id).path().to_path_buf(),
+            PidManager::get_pid_file(session_id),
+            PidManager::get_lock_file(session_id),
+        ];
+        if let Ok(entries) = fs::read_dir(undo_dir(&self.session_dir, Some(session_id))) {
+            artifacts.extend(entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()));
+        }
+        artifacts
+    }
+    
+    /// Bytes the session's artifacts take on disk
+    fn session_bytes(&self, session_id: &str) -> u64 {
+        self.session_artifacts(session_id).iter()
+            .filter_map(|path| fs::metadata(path).ok())
+            .map(|metadata| metadata.len())
+            .sum()
+    }
+    
+    fn is_watched(session_id: &str) -> bool {
+        matches!(PidManager::read_pid_file(session_id), Ok(Some(pid)) if PidManager::is_process_running(pid))
+    }
+    
+    /// Delete a session and its artifacts, returning the bytes freed. A
+    /// session with a running watcher is refused; stop the watcher first.
+    pub fn delete_session(&mut self, session_id: &str) -> Result<u64> {
+        if !self.session_info.contains_key(session_id) {
+            anyhow::bail!("Session not found: {}", session_id);
+        }
+        if Self::is_watched(session_id) {
+            anyhow::bail!("Session {} is being watched; run watch-stop {} first", session_id, session_id);
+        }
+        
+        let mut freed = 0;
+        for path in self.session_artifacts(session_id) {
+            if let Ok(metadata) = fs::metadata(&path) {
+                fs::remove_file(&path)
+                    .with_context(|| format!("Failed to remove {}", path.display()))?;
+                freed += metadata.len();
+            }
+        }
+        let _ = fs::remove_dir(undo_dir(&self.session_dir, Some(session_id)));
+        self.sessions.remove(session_id);
+        self.session_info.remove(session_id);
+        Ok(freed)
+    }
+    
+    /// Delete sessions not accessed for `older_than_days` days and/or all but
+    /// the `keep` most recently accessed ones; a session matching either rule
+    /// is removed. With `dry_run` nothing is deleted.
+    pub fn prune(&mut self, older_than_days: Option<u32>, keep: Option<usize>, dry_run: bool) -> Result<PruneReport> {
+        if older_than_days.is_none() && keep.is_none() {
+            anyhow::bail!("Nothing to prune by: give --older-than-days and/or --keep");
+        }
+        
+        let mut sessions: Vec<&SessionInfo> = self.session_info.values().collect();
+        sessions.sort_by(|a, b| b.last_accessed.cmp(&a.last_accessed).then(a.id.cmp(&b.id)));
+        let cutoff = older_than_days.map(|days| Utc::now() - chrono::Duration::days(days as i64));
+        let expired: Vec<PrunedSession> = sessions.iter()
+            .enumerate()
+            .filter(|(rank, session)| {
+                cutoff.is_some_and(|cutoff| session.last_accessed < cutoff)
+                    || keep.is_some_and(|keep| *rank >= keep)
+            })
+            .map(|(_, session)| PrunedSession {
+                id: session.id.clone(),
+                path: session.path.clone(),
+                last_accessed: session.last_accessed,
+                bytes: self.session_bytes(&session.id),
+            })
+            .collect();
+        
+        let total = sessions.len();
+        let (skipped, removed): (Vec<_>, Vec<_>) = expired.into_iter().partition(|session| Self::is_watched(&session.id));
+        let mut report = PruneReport {
+            dry_run,
+            kept: total - removed.len(),
+            skipped_watched: skipped.into_iter().map(|session| session.id).collect(),
+            freed_bytes: 0,
+            removed: Vec::new(),
+        };
+        for mut session in removed {
+            if !dry_run {
+                session.bytes = self.delete_session(&session.id)?;
+            }
+            report.freed_bytes += session.bytes;
+            report.removed.push(session);
+        }
+        Ok(report)
+    }
+    
     /// Perform incremental update on a session
     pub async fn update_session_incremental(&mut self, session_id: &str) -> Result<IncrementalSummary> {
         let start_time = std::time::Instant::now();
@@ -248,8 +528,9 @@ impl SessionManager {
         let session = self.sessions.get_mut(session_id)
             .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
         
-        // Re-analyze only changed files
+        // Re-analyze only changed files, updating the combined statistics file by file
         let mut updated_results = session_info.analysis_results.clone();
+        let mut combined_ast_stats = session_info.combined_ast_stats.clone();
         let mut files_processed = 0;
         
         for change in &changes {
@@ -260,17 +541,13 @@ impl SessionManager {
                 crate::core::incremental::ChangeType::Modified => {
                     if full_path.exists() {
                         match session.analyze_file(&full_path).await {
-                            Ok(analysis_result) => {
-                                // Remove old result if it exists (compare relative paths)
-                                let session_path = &session_info.path;
-                                updated_results.retain(|r| {
-                                    if let Ok(relative_path) = r.file_info.path.strip_prefix(session_path) {
-                                        relative_path != change.path
-                                    } else {
-                                        true // Keep if can't make relative
-                                    }
-                                });
-                                // Add new result
+                            Ok(mut analysis_result) => {
+                                // Replace the old result, if any
+                                let stored_path = session_info.stored_path(&full_path);
+                                let old = updated_results.iter().position(|r| r.file_info.path == stored_path)
+                                    .map(|index| updated_results.remove(index));
+                                Self::replace_ast_stats(&mut combined_ast_stats, old.as_ref(), Some(&analysis_result));
+                                analysis_result.file_info.path = stored_path;
                                 updated_results.push(analysis_result);
                                 files_processed += 1;
                             }
@@ -281,23 +558,21 @@ impl SessionManager {
                     }
                 }
                 crate::core::incremental::ChangeType::Deleted => {
-                    // Remove deleted file from results (compare relative paths)
-                    let session_path = &session_info.path;
-                    updated_results.retain(|r| {
-                        if let Ok(relative_path) = r.file_info.path.strip_prefix(session_path) {
-                            relative_path != change.path
-                        } else {
-                            true // Keep if can't make relative
-                        }
-                    });
+                    // Remove deleted file from results
+                    let stored_path = session_info.stored_path(&full_path);
+                    let old = updated_results.iter().position(|r| r.file_info.path == stored_path)
+                        .map(|index| updated_results.remove(index));
+                    Self::replace_ast_stats(&mut combined_ast_stats, old.as_ref(), None);
                     files_processed += 1;
                 }
             }
         }
         
         // Update session info
+        Self::settle_ast_stats(&mut combined_ast_stats, &updated_results);
+        session_info.symbol_index.take();
         session_info.analysis_results = updated_results;
-        session_info.combined_ast_stats = Self::calculate_combined_ast_stats(&session_info.analysis_results);
+        session_info.combined_ast_stats = combined_ast_stats;
         session_info.last_accessed = Utc::now();
         
         // Clone session info for saving to avoid borrowing issues
@@ -337,7 +612,7 @@ impl SessionManager {
                     "node_type_counts": stats.node_type_counts
                 }
             });
-            Ok(serde_json::to_string_pretty(&result)?)
+            Ok(to_json(&result)?)
         } else {
             Ok(serde_json::json!({
                 "ast_statistics": {
@@ -355,7 +630,10 @@ impl SessionManager {
     }
     
     /// Query AST by path
-    pub fn handle_ast_query(&self, session_id: &str, path: &str) -> Result<String> {
+    ///
+    /// With `snippet`, each match also carries its source text, read from the
+    /// file as it is on disk now.
+    pub fn handle_ast_query(&self, session_id: &str, path: &str, regex: bool, snippet: Option<SnippetOptions>) -> Result<String> {
         let session_info = self.get_session_info(session_id)
             .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
             
@@ -364,9 +642,13 @@ impl SessionManager {
         // Search through all analysis results
         for analysis_result in &session_info.analysis_results {
             if let Some(ref ast_root) = analysis_result.ast_root {
-                let matches = ast_root.query_by_path(path);
+                let matches = ast_root.query_by_path_with(path, regex)?;
+                let content = match snippet {
+                    Some(_) if !matches.is_empty() => fs::read_to_string(session_info.source_path(&analysis_result.file_info.path)).ok(),
+                    _ => None,
+                };
                 for node in matches {
-                    results.push(serde_json::json!({
+                    let mut result = serde_json::json!({
                         "file": analysis_result.file_info.path,
                         "node_type": node.type_string(),
                         "name": node.name,
@@ -374,21 +656,28 @@ impl SessionManager {
                         "start_line": node.start_line,
                         "end_line": node.end_line,
                         "attributes": node.attributes
-                    }));
+                    });
+                    if let Some(options) = snippet {
+                        result["source"] = content.as_deref()
+                            .map(|content| options.extract(content, node.start_line, node.end_line))
+                            .unwrap_or(serde_json::Value::Null);
+                    }
+                    results.push(result);
                 }
             }
         }
         
         let result = serde_json::json!({
             "query_path": path,
+            "regex": regex,
             "matches": results
         });
         
-        Ok(serde_json::to_string_pretty(&result)?)
+        Ok(to_json(&result)?)
     }
     
     /// Analyze scope at specific line
-    pub fn handle_scope_analysis(&self, session_id: &str, line: u32) -> Result<String> {
+    pub fn handle_scope_analysis(&self, session_id: &str, line: u32, file: Option<&Path>, chain: bool) -> Result<String> {
         let session_info = self.get_session_info(session_id)
             .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
             
@@ -396,7 +685,16 @@ impl SessionManager {
         
         // Search through all analysis results for nodes at the specified line
         for analysis_result in &session_info.analysis_results {
-            if let Some(ref ast_root) = analysis_result.ast_root {
+            if let Some(file) = file {
+                if !analysis_result.file_info.path.ends_with(file) {
+                    continue;
+                }
+            }
+            let Some(ref ast_root) = analysis_result.ast_root else {
+                continue;
+            };
+            
+            if !chain {
                 if let Some(node) = ast_root.find_node_at_line(line) {
                     results.push(serde_json::json!({
                         "file": analysis_result.file_info.path,
@@ -409,7 +707,44 @@ impl SessionManager {
                         "attributes": node.attributes
                     }));
                 }
+                continue;
+            }
+            
+            if line > analysis_result.file_info.total_lines {
+                continue;
             }
+            
+            let scope_chain = ast_root.find_scope_chain_at_line(line);
+            let Some((node, ancestors)) = scope_chain.split_last() else {
+                continue;
+            };
+            let depth = ancestors.len();
+            
+            // Ancestors are listed from the immediate parent up to the file root
+            let ancestors: Vec<_> = ancestors.iter().enumerate().rev()
+                .map(|(depth, ancestor)| serde_json::json!({
+                    "node_type": ancestor.type_string(),
+                    "name": ancestor.name,
+                    "scope_path": ancestor.scope_path,
+                    "start_line": ancestor.start_line,
+                    "end_line": ancestor.end_line,
+                    "depth": depth
+                }))
+                .collect();
+            
+            results.push(serde_json::json!({
+                "file": analysis_result.file_info.path,
+                "node": {
+                    "node_type": node.type_string(),
+                    "name": node.name,
+                    "scope_path": node.scope_path,
+                    "start_line": node.start_line,
+                    "end_line": node.end_line,
+                    "depth": depth,
+                    "attributes": node.attributes
+                },
+                "ancestors": ancestors
+            }));
         }
         
         let result = serde_json::json!({
@@ -417,11 +752,80 @@ impl SessionManager {
             "scope_analysis": results
         });
         
-        Ok(serde_json::to_string_pretty(&result)?)
+        Ok(to_json(&result)?)
+    }
+    
+    /// Compare the AST structure of two sessions, matching files by path relative to each session root
+    pub fn handle_ast_diff(&self, base_session_id: &str, head_session_id: &str) -> Result<String> {
+        let base = self.get_session_info(base_session_id)
+            .ok_or_else(|| anyhow::anyhow!("Session not found: {}", base_session_id))?;
+        let head = self.get_session_info(head_session_id)
+            .ok_or_else(|| anyhow::anyhow!("Session not found: {}", head_session_id))?;
+        
+        let index_asts = |session: &SessionInfo| -> BTreeMap<PathBuf, Option<ASTNode>> {
+            session.analysis_results.iter()
+                .map(|r| {
+                    (session.relative_path(&r.file_info.path), r.ast_root.clone())
+                })
+                .collect()
+        };
+        let base_files = index_asts(base);
+        let head_files = index_asts(head);
+        
+        let mut all_files: Vec<&PathBuf> = base_files.keys().chain(head_files.keys()).collect();
+        all_files.sort();
+        all_files.dedup();
+        
+        let mut files = Vec::new();
+        let (mut added, mut removed, mut renamed, mut moved) = (0, 0, 0, 0);
+        
+        for file in all_files {
+            let base_ast = base_files.get(file).and_then(|ast| ast.as_ref());
+            let head_ast = head_files.get(file).and_then(|ast| ast.as_ref());
+            let diff = AstDiff::between(base_ast, head_ast);
+            if diff.is_empty() {
+                continue;
+            }
+            
+            added += diff.added.len();
+            removed += diff.removed.len();
+            renamed += diff.renamed.len();
+            moved += diff.moved.len();
+            
+            let status = match (base_files.contains_key(file), head_files.contains_key(file)) {
+                (false, _) => "added",
+                (_, false) => "removed",
+                _ => "modified",
+            };
+            
+            files.push(serde_json::json!({
+                "file": file,
+                "status": status,
+                "added": diff.added,
+                "removed": diff.removed,
+                "renamed": diff.renamed,
+                "moved": diff.moved,
+            }));
+        }
+        
+        let result = serde_json::json!({
+            "base_session": base_session_id,
+            "head_session": head_session_id,
+            "summary": {
+                "files_changed": files.len(),
+                "added": added,
+                "removed": removed,
+                "renamed": renamed,
+                "moved": moved,
+            },
+            "files": files
+        });
+        
+        Ok(to_json(&result)?)
     }
     
-    /// Dump AST structure
-    pub fn handle_ast_dump(&self, session_id: &str, format: &str) -> Result<String> {
+    /// Dump AST structure, pruned by `filter`
+    pub fn handle_ast_dump(&self, session_id: &str, format: &str, filter: &DumpFilter) -> Result<String> {
         let session_info = self.get_session_info(session_id)
             .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
             
@@ -430,13 +834,14 @@ impl SessionManager {
         for analysis_result in &session_info.analysis_results {
             if let Some(ref ast_root) = analysis_result.ast_root {
                 output.push_str(&format!("=== {} ===\n", analysis_result.file_info.path.display()));
+                let ast_root = &ast_root.pruned(filter);
                 
                 match format {
                     "tree" => {
                         output.push_str(&ast_root.dump_as_tree(0));
                     }
                     "json" => {
-                        let json = serde_json::to_string_pretty(ast_root)?;
+                        let json = to_json(ast_root)?;
                         output.push_str(&json);
                         output.push('\n');
                     }
@@ -460,6 +865,7 @@ impl SessionManager {
         let mut total_files = 0;
         let mut total_lines = 0;
         let mut total_code_lines = 0;
+        let mut total_comment_lines = 0;
         let mut total_functions = 0;
         let mut total_classes = 0;
         let mut language_counts = std::collections::HashMap::new();
@@ -468,6 +874,7 @@ impl SessionManager {
             total_files += 1;
             total_lines += result.file_info.total_lines;
             total_code_lines += result.file_info.code_lines;
+            total_comment_lines += result.file_info.comment_lines;
             total_functions += result.functions.len();
             total_classes += result.classes.len();
             
@@ -492,6 +899,7 @@ impl SessionManager {
                 "total_files": total_files,
                 "total_lines": total_lines,
                 "total_code_lines": total_code_lines,
+                "total_comment_lines": total_comment_lines,
                 "code_ratio": if total_lines > 0 { total_code_lines as f64 / total_lines as f64 } else { 0.0 }
             },
             "code_statistics": {
@@ -503,9 +911,11 @@ impl SessionManager {
         }))
     }
     
-    /// Calculate complexity analysis from AST data
-    fn calculate_session_complexity(&self, session_info: &SessionInfo) -> Result<serde_json::Value> {
+    /// Calculate per-function complexity. With `min_complexity`, only functions
+    /// at or above it are listed; totals and the distribution cover every function.
+    fn calculate_session_complexity(&self, session_info: &SessionInfo, min_complexity: Option<u32>) -> Result<serde_json::Value> {
         let mut complexity_by_file = Vec::new();
+        let mut hotspots = Vec::new();
         let mut total_complexity = 0;
         let mut complexity_distribution = std::collections::HashMap::new();
         
@@ -523,12 +933,23 @@ impl SessionManager {
                     complexity += ast_stats.control_structures / result.functions.len().max(1) as u32;
                 }
                 
-                function_complexities.push(serde_json::json!({
-                    "name": function.name,
-                    "complexity": complexity,
-                    "line_start": function.start_line,
-                    "line_end": function.end_line
-                }));
+                if complexity >= min_complexity.unwrap_or(0) {
+                    function_complexities.push(serde_json::json!({
+                        "name": function.name,
+                        "complexity": complexity,
+                        "line_start": function.start_line,
+                        "line_end": function.end_line,
+                        "line_stats": function.line_stats,
+                        "comment_ratio": function.line_stats.comment_ratio()
+                    }));
+                    hotspots.push((complexity, serde_json::json!({
+                        "file": result.file_info.path,
+                        "name": function.name,
+                        "complexity": complexity,
+                        "line_start": function.start_line,
+                        "line_end": function.end_line
+                    })));
+                }
                 
                 file_complexity += complexity;
                 total_complexity += complexity;
@@ -557,18 +978,105 @@ impl SessionManager {
             }
         }
         
+        hotspots.sort_by_key(|(complexity, _)| std::cmp::Reverse(*complexity));
+        let hotspots: Vec<_> = hotspots.into_iter().map(|(_, hotspot)| hotspot).collect();
+        
         Ok(serde_json::json!({
             "session_id": session_info.id,
+            "min_complexity": min_complexity,
             "total_complexity": total_complexity,
             "total_functions": session_info.analysis_results.iter().map(|r| r.functions.len()).sum::<usize>(),
             "average_complexity": if !session_info.analysis_results.is_empty() {
                 total_complexity as f64 / session_info.analysis_results.iter().map(|r| r.functions.len()).sum::<usize>() as f64
             } else { 0.0 },
             "complexity_distribution": complexity_distribution,
+            "hotspots": hotspots,
             "files": complexity_by_file
         }))
     }
     
+    /// Aggregate quality metrics taken from the `stats` and `complexity`
+    /// results, as nekocode JSON or in the shape a CI quality tool ingests:
+    /// `sonar` (SonarQube measures) or `codeclimate` (Code Climate measurements)
+    fn calculate_session_metrics(&self, session_info: &SessionInfo, format: &str) -> Result<serde_json::Value> {
+        let stats = self.calculate_session_stats(session_info)?;
+        let complexity = self.calculate_session_complexity(session_info, None)?;
+        let count = |value: &serde_json::Value| value.as_u64().unwrap_or(0);
+        
+        let files = count(&stats["file_statistics"]["total_files"]);
+        let lines = count(&stats["file_statistics"]["total_lines"]);
+        let code_lines = count(&stats["file_statistics"]["total_code_lines"]);
+        let comment_lines = count(&stats["file_statistics"]["total_comment_lines"]);
+        let functions = count(&stats["code_statistics"]["total_functions"]);
+        let classes = count(&stats["code_statistics"]["total_classes"]);
+        let total_complexity = count(&complexity["total_complexity"]);
+        // No functions makes the average NaN, which serializes as null
+        let average_complexity = complexity["average_complexity"].as_f64().unwrap_or(0.0);
+        let max_complexity = count(&complexity["hotspots"][0]["complexity"]);
+        let comment_ratio = if code_lines + comment_lines > 0 {
+            comment_lines as f64 / (code_lines + comment_lines) as f64
+        } else {
+            0.0
+        };
+        
+        Ok(match format {
+            "json" => serde_json::json!({
+                "session_id": session_info.id,
+                "project_path": session_info.path,
+                "metrics": {
+                    "files": files,
+                    "lines": lines,
+                    "code_lines": code_lines,
+                    "comment_lines": comment_lines,
+                    "functions": functions,
+                    "classes": classes,
+                    "total_complexity": total_complexity,
+                    "average_complexity": average_complexity,
+                    "max_complexity": max_complexity,
+                    "comment_ratio": comment_ratio
+                }
+            }),
+            "sonar" => {
+                // Measures use SonarQube's metric keys and string values
+                let measures: Vec<_> = [
+                    ("files", files.to_string()),
+                    ("lines", lines.to_string()),
+                    ("ncloc", code_lines.to_string()),
+                    ("comment_lines", comment_lines.to_string()),
+                    ("comment_lines_density", format!("{:.1}", comment_ratio * 100.0)),
+                    ("functions", functions.to_string()),
+                    ("classes", classes.to_string()),
+                    ("complexity", total_complexity.to_string()),
+                ].into_iter()
+                    .map(|(metric, value)| serde_json::json!({ "metric": metric, "value": value }))
+                    .collect();
+                serde_json::json!({
+                    "component": {
+                        "key": session_info.path.display().to_string(),
+                        "qualifier": "TRK",
+                        "measures": measures
+                    }
+                })
+            }
+            "codeclimate" => {
+                let measurements: Vec<_> = [
+                    ("files", serde_json::json!(files)),
+                    ("lines_of_code", serde_json::json!(code_lines)),
+                    ("comment_lines", serde_json::json!(comment_lines)),
+                    ("functions", serde_json::json!(functions)),
+                    ("classes", serde_json::json!(classes)),
+                    ("average_complexity", serde_json::json!(average_complexity)),
+                    ("max_complexity", serde_json::json!(max_complexity)),
+                    ("comment_ratio", serde_json::json!(comment_ratio)),
+                ].into_iter()
+                    .map(|(name, value)| serde_json::json!({ "type": "measurement", "name": name, "value": value }))
+                    .collect();
+                serde_json::Value::from(measurements)
+            }
+            _ => anyhow::bail!("Unknown metrics format '{}': expected json, sonar or codeclimate", format),
+        })
+    }
+    
     /// Calculate project structure analysis
     fn calculate_session_structure(&self, session_info: &SessionInfo) -> Result<serde_json::Value> {
         let mut structure_by_language = std::collections::HashMap::new();
@@ -623,47 +1131,79 @@ impl SessionManager {
         }))
     }
     
+    /// Parse `find <TERM> [--type class|function|method|ast] [--exact]`
+    fn parse_find_args(args: &[String]) -> Result<(String, Option<String>, bool)> {
+        let mut term = None;
+        let mut kind = None;
+        let mut exact = false;
+        let mut args = args.iter();
+        
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--exact" => exact = true,
+                "--type" => {
+                    let value = args.next()
+                        .ok_or_else(|| anyhow::anyhow!("--type requires a value: class, function, method or ast"))?;
+                    if !["class", "function", "method", "ast"].contains(&value.as_str()) {
+                        anyhow::bail!("Invalid symbol type '{}': expected class, function, method or ast", value);
+                    }
+                    kind = Some(value.clone());
+                }
+                _ if term.is_none() => term = Some(arg.clone()),
+                _ => anyhow::bail!("Unexpected argument for find: '{}'", arg),
+            }
+        }
+        
+        Ok((term.unwrap_or_default(), kind, exact))
+    }
+    
     /// Find symbols matching the search term
-    fn find_session_symbols(&self, session_info: &SessionInfo, term: &str) -> Result<serde_json::Value> {
-        let mut matches = Vec::new();
+    ///
+    /// With `kind`, only matches of that kind (`class`, `function`, `method`
+    /// or `ast`) are returned. `exact` requires the name to equal `term`
+    /// instead of containing it case-insensitively.
+    fn find_session_symbols(&self, session_info: &SessionInfo, term: &str, kind: Option<&str>, exact: bool) -> Result<serde_json::Value> {
         let term_lower = term.to_lowercase();
-        
-        for result in &session_info.analysis_results {
-            // Search in classes
-            for class in &result.classes {
-                if class.name.to_lowercase().contains(&term_lower) {
-                    matches.push(serde_json::json!({
-                        "type": "class",
-                        "name": class.name,
-                        "file": result.file_info.path,
-                        "line_start": class.start_line,
-                        "line_end": class.end_line,
-                        "scope": "global"
-                    }));
-                }
+        let name_matches = |name: &str| {
+            if exact {
+                name == term
+            } else {
+                name.to_lowercase().contains(&term_lower)
             }
-            
-            // Search in functions
-            for function in &result.functions {
-                if function.name.to_lowercase().contains(&term_lower) {
-                    matches.push(serde_json::json!({
-                        "type": "function",
-                        "name": function.name,
-                        "file": result.file_info.path,
-                        "line_start": function.start_line,
-                        "line_end": function.end_line,
-                        "scope": "global",
-                        "parameters": function.parameters
-                    }));
-                }
+        };
+        let wants = |candidate: &str| kind.is_none_or(|kind| kind == candidate);
+        
+        // Definitions come from the index, tagged with their file's position
+        let definitions = if exact {
+            session_info.symbol_index().definitions_named(term)
+        } else {
+            session_info.symbol_index().definitions_matching(name_matches)
+        };
+        let mut found: Vec<(usize, serde_json::Value)> = Vec::new();
+        for &definition in definitions.iter().filter(|d| wants(d.kind)) {
+            let mut entry = serde_json::json!({
+                "type": definition.kind,
+                "name": definition.name,
+                "file": session_info.analysis_results[definition.file].file_info.path,
+                "line_start": definition.line_start,
+                "line_end": definition.line_end,
+                "scope": definition.scope,
+            });
+            if definition.kind != "class" {
+                entry["parameters"] = serde_json::json!(definition.parameters);
             }
-            
-            // Search in AST nodes if available
-            if let Some(ref ast_root) = result.ast_root {
-                let ast_matches = self.search_ast_nodes(ast_root, &term_lower);
-                for ast_match in ast_matches {
+            found.push((definition.file, entry));
+        }
+        
+        // AST nodes are not indexed and are searched file by file
+        if wants("ast") {
+            let mut covered = HashSet::new();
+            for (file, result) in session_info.analysis_results.iter().enumerate() {
+                let Some(ref ast_root) = result.ast_root else { continue };
+                for ast_match in self.search_ast_nodes(ast_root, &name_matches) {
+                    covered.insert((file, ast_match.name.as_str(), ast_match.start_line));
                     let name = if ast_match.name.is_empty() { "anonymous" } else { &ast_match.name };
-                    matches.push(serde_json::json!({
+                    found.push((file, serde_json::json!({
                         "type": "ast_node",
                         "name": name,
                         "node_type": ast_match.type_string(),
@@ -671,31 +1211,57 @@ impl SessionManager {
                         "line_start": ast_match.start_line,
                         "line_end": ast_match.end_line,
                         "scope_path": ast_match.scope_path
-                    }));
+                    })));
                 }
             }
+            
+            // Definitions the file's AST lacks (analyzers that found nothing
+            // build no AST) are listed the way an AST node would be
+            for definition in definitions.iter().filter(|d| !covered.contains(&(d.file, d.name.as_str(), d.line_start))) {
+                let scope_path = if definition.kind == "method" {
+                    format!("{}::{}", definition.scope, definition.name)
+                } else {
+                    definition.name.clone()
+                };
+                found.push((definition.file, serde_json::json!({
+                    "type": "ast_node",
+                    "name": definition.name,
+                    "node_type": definition.kind,
+                    "file": session_info.analysis_results[definition.file].file_info.path,
+                    "line_start": definition.line_start,
+                    "line_end": definition.line_end,
+                    "scope_path": scope_path,
+                    "synthesized": true
+                })));
+            }
         }
         
+        // File by file, definitions before AST nodes, as a scan would list them
+        found.sort_by_key(|(file, _)| *file);
+        let matches: Vec<serde_json::Value> = found.into_iter().map(|(_, entry)| entry).collect();
+        
         Ok(serde_json::json!({
             "session_id": session_info.id,
             "search_term": term,
+            "type_filter": kind,
+            "exact": exact,
             "total_matches": matches.len(),
             "matches": matches
         }))
     }
     
     /// Helper method to search AST nodes recursively
-    fn search_ast_nodes<'a>(&self, node: &'a ASTNode, term: &str) -> Vec<&'a ASTNode> {
+    fn search_ast_nodes<'a>(&self, node: &'a ASTNode, name_matches: &dyn Fn(&str) -> bool) -> Vec<&'a ASTNode> {
         let mut results = Vec::new();
         
         // Check current node
-        if !node.name.is_empty() && node.name.to_lowercase().contains(term) {
+        if !node.name.is_empty() && name_matches(&node.name) {
             results.push(node);
         }
         
         // Search children recursively
         for child in &node.children {
-            results.extend(self.search_ast_nodes(child, term));
+            results.extend(self.search_ast_nodes(child, name_matches));
         }
         
         results
@@ -709,14 +1275,23 @@ impl SessionManager {
         // Build dependency graph for all languages
         for result in &session_info.analysis_results {
             let file_path = result.file_info.path.to_string_lossy().to_string();
+            let source_path = session_info.source_path(&result.file_info.path);
             let mut resolved_deps = Vec::new();
             
             // Extract imports for all languages
             for import in &result.imports {
-                if let Some(resolved_path) = self.resolve_import_path(&import.module_path, &result.file_info.path, &result.language) {
+                if result.language == Language::Python {
+                    resolved_deps.extend(self.resolve_python_import(import, &source_path));
+                } else if let Some(resolved_path) = self.resolve_import_path(&import.module_path, &source_path, &result.language) {
                     resolved_deps.push(resolved_path);
                 }
             }
+            // Dependencies are keyed the way the session stores paths
+            for dep in &mut resolved_deps {
+                *dep = session_info.stored_path(Path::new(dep.as_str())).to_string_lossy().to_string();
+            }
+            let mut seen = HashSet::new();
+            resolved_deps.retain(|dep| seen.insert(dep.clone()));
             
             dependencies.insert(file_path, resolved_deps);
         }
@@ -742,6 +1317,105 @@ impl SessionManager {
         }))
     }
     
+    /// Find near-identical function bodies across the session's files
+    fn find_session_duplicates(&self, session_info: &SessionInfo, threshold: f64) -> Result<serde_json::Value> {
+        let mut functions = Vec::new();
+        
+        for result in &session_info.analysis_results {
+            let content = match fs::read_to_string(session_info.source_path(&result.file_info.path)) {
+                Ok(content) => content,
+                Err(e) => {
+                    eprintln!("⚠️  Skipping {}: {}", result.file_info.path.display(), e);
+                    continue;
+                }
+            };
+            let lines: Vec<&str> = content.lines().collect();
+            
+            let methods = result.classes.iter().flat_map(|class| class.methods.iter());
+            for function in result.functions.iter().chain(methods) {
+                if function.start_line == 0 || function.end_line < function.start_line {
+                    continue;
+                }
+                let start = (function.start_line as usize - 1).min(lines.len());
+                let end = (function.end_line as usize).min(lines.len());
+                functions.push(FunctionSource {
+                    name: function.name.clone(),
+                    file: result.file_info.path.clone(),
+                    start_line: function.start_line,
+                    end_line: function.end_line,
+                    source: lines[start..end].join("\n"),
+                });
+            }
+        }
+        
+        let clusters = find_duplicates(&functions, threshold);
+        
+        Ok(serde_json::json!({
+            "session_id": session_info.id,
+            "threshold": threshold,
+            "functions_compared": functions.len(),
+            "duplicate_clusters": clusters.len(),
+            "clusters": clusters
+        }))
+    }
+    
+    fn find_session_unused(&self, session_info: &SessionInfo) -> Result<serde_json::Value> {
+        let unused = find_unused(&session_info.analysis_results);
+        
+        Ok(serde_json::json!({
+            "session_id": session_info.id,
+            "unused_symbols": unused.len(),
+            "note": "Detection is static: dynamic dispatch, reflection and callbacks can hide real usage",
+            "symbols": unused
+        }))
+    }
+    
+    /// TODO/FIXME-style comments across the session, grouped by tag in the
+    /// order of `tags`
+    fn find_session_annotations(&self, session_info: &SessionInfo, tags: &[String]) -> Result<serde_json::Value> {
+        let mut annotations = Vec::new();
+        for result in &session_info.analysis_results {
+            match fs::read_to_string(session_info.source_path(&result.file_info.path)) {
+                Ok(content) => annotations.extend(scan_annotations(&result.file_info.path, &content, result.language, tags)),
+                Err(e) => eprintln!("⚠️  Skipping {}: {}", result.file_info.path.display(), e),
+            }
+        }
+        
+        let by_tag: Vec<_> = tags.iter()
+            .map(|tag| {
+                let matches: Vec<_> = annotations.iter().filter(|a| a.tag == *tag).collect();
+                serde_json::json!({
+                    "tag": tag,
+                    "count": matches.len(),
+                    "annotations": matches
+                })
+            })
+            .collect();
+        
+        Ok(serde_json::json!({
+            "session_id": session_info.id,
+            "total": annotations.len(),
+            "tags": by_tag
+        }))
+    }
+    
+    fn find_session_unreachable(&self, session_info: &SessionInfo) -> Result<serde_json::Value> {
+        let mut unreachable = Vec::new();
+        for result in &session_info.analysis_results {
+            let path = &result.file_info.path;
+            match fs::read_to_string(session_info.source_path(path)) {
+                Ok(content) => unreachable.extend(find_unreachable(path, result.language, &content)?),
+                Err(e) => eprintln!("⚠️  Skipping {}: {}", path.display(), e),
+            }
+        }
+        
+        Ok(serde_json::json!({
+            "session_id": session_info.id,
+            "total": unreachable.len(),
+            "unreachable": unreachable
+        }))
+    }
+    
     /// Helper method for cycle detection using DFS
     fn detect_cycle_dfs(
         &self,
@@ -753,21 +1427,75 @@ impl SessionManager {
         visited.insert(file.to_string());
         rec_stack.insert(file.to_string());
         
+        let mut found = None;
         if let Some(deps) = dependencies.get(file) {
             for dep in deps {
                 if !visited.contains(dep) {
-                    if let Some(cycle) = self.detect_cycle_dfs(dep, dependencies, visited, rec_stack) {
-                        return Some(cycle);
-                    }
+                    found = self.detect_cycle_dfs(dep, dependencies, visited, rec_stack);
                 } else if rec_stack.contains(dep) {
                     // Found a cycle
-                    return Some(vec![file.to_string(), dep.clone()]);
+                    found = Some(vec![file.to_string(), dep.clone()]);
+                }
+                if found.is_some() {
+                    break;
                 }
             }
         }
         
+        // Leave the stack clean so later roots don't report stale back edges
         rec_stack.remove(file);
-        None
+        found
+    }
+    
+    /// Files that may hold a Python module, most likely first. Each leading
+    /// dot after the first climbs one package up; `pkg.core` is `pkg/core.py`
+    /// or the package `pkg/core/__init__.py`. Absolute imports are looked up
+    /// next to the importing file, then in each enclosing directory.
+    fn python_module_candidates(module: &str, current_file: &Path) -> Option<Vec<PathBuf>> {
+        let dots = module.len() - module.trim_start_matches('.').len();
+        let mut base = current_file.parent()?;
+        for _ in 1..dots {
+            base = base.parent()?;
+        }
+        let rest = &module[dots..];
+        if rest.is_empty() {
+            return Some(vec![base.join("__init__.py")]);
+        }
+        let roots: Vec<&Path> = if dots == 0 {
+            base.ancestors().filter(|dir| !dir.as_os_str().is_empty()).collect()
+        } else {
+            vec![base]
+        };
+        let relative = rest.replace('.', "/");
+        Some(roots.into_iter()
+            .flat_map(|root| {
+                let target = root.join(&relative);
+                [target.with_extension("py"), target.join("__init__.py")]
+            })
+            .collect())
+    }
+    
+    /// Resolve the files a Python import depends on. `from pkg import core`
+    /// may name a submodule rather than an attribute of `pkg/__init__.py`, so
+    /// each imported name is tried as a module before falling back to `pkg`.
+    fn resolve_python_import(&self, import: &crate::core::types::ImportInfo, current_file: &Path) -> Vec<String> {
+        let module = import.module_path.trim();
+        let mut resolved = Vec::new();
+        let mut needs_module = import.imported_names.is_empty();
+        
+        for name in &import.imported_names {
+            let submodule = if module.ends_with('.') { format!("{}{}", module, name) } else { format!("{}.{}", module, name) };
+            let found = Self::python_module_candidates(&submodule, current_file)
+                .and_then(|candidates| candidates.into_iter().find(|candidate| candidate.exists()));
+            match found {
+                Some(path) => resolved.push(path.to_string_lossy().to_string()),
+                None => needs_module = true,
+            }
+        }
+        if needs_module {
+            resolved.extend(self.resolve_import_path(module, current_file, &Language::Python));
+        }
+        resolved
     }
     
     /// Resolve import path based on language-specific rules
@@ -815,24 +1543,14 @@ impl SessionManager {
             
             // Python - handle relative and absolute imports
             Language::Python => {
-                if clean_import_path.starts_with('.') {
-                    // Relative import
-                    if let Some(parent) = current_file.parent() {
-                        let module_path = clean_import_path.trim_start_matches('.');
-                        let py_path = parent.join(module_path.replace('.', "/") + ".py");
-                        return Some(py_path.to_string_lossy().to_string());
-                    }
-                } else {
-                    // Absolute import - convert module.submodule to path
-                    if let Some(parent) = current_file.parent() {
-                        // Simple heuristic: look for module in same directory structure
-                        let py_path = parent.join(clean_import_path.replace('.', "/") + ".py");
-                        if py_path.exists() {
-                            return Some(py_path.to_string_lossy().to_string());
-                        }
-                    }
+                let relative = clean_import_path.starts_with('.');
+                let candidates = Self::python_module_candidates(clean_import_path, current_file)?;
+                match candidates.iter().find(|candidate| candidate.exists()) {
+                    Some(found) => Some(found.to_string_lossy().to_string()),
+                    // Keep unresolved relative imports in the graph (for analysis)
+                    None if relative => Some(candidates[0].to_string_lossy().to_string()),
+                    None => None,
                 }
-                None
             }
             
             // C/C++ - handle include paths
@@ -921,38 +1639,230 @@ impl SessionManager {
         match command {
             "stats" => {
                 let stats = self.calculate_session_stats(session_info)?;
-                Ok(serde_json::to_string_pretty(&stats)?)
+                Ok(to_json(&stats)?)
             }
             "complexity" => {
-                let complexity = self.calculate_session_complexity(session_info)?;
-                Ok(serde_json::to_string_pretty(&complexity)?)
+                let min_complexity = match args.first() {
+                    Some(arg) => Some(arg.parse::<u32>()
+                        .map_err(|_| anyhow::anyhow!("Invalid minimum complexity '{}': expected a non-negative integer", arg))?),
+                    None => None,
+                };
+                let complexity = self.calculate_session_complexity(session_info, min_complexity)?;
+                Ok(to_json(&complexity)?)
+            }
+            "metrics" => {
+                let format = match args {
+                    [] => "json",
+                    [flag, format] if flag == "--format" => format.as_str(),
+                    _ => anyhow::bail!("Usage: metrics [--format json|sonar|codeclimate]"),
+                };
+                let metrics = self.calculate_session_metrics(session_info, format)?;
+                Ok(to_json(&metrics)?)
             }
             "structure" => {
                 let structure = self.calculate_session_structure(session_info)?;
-                Ok(serde_json::to_string_pretty(&structure)?)
+                Ok(to_json(&structure)?)
             }
             "find" => {
-                let term = args.get(0).unwrap_or(&String::new()).clone();
-                let results = self.find_session_symbols(session_info, &term)?;
-                Ok(serde_json::to_string_pretty(&results)?)
+                let (term, kind, exact) = Self::parse_find_args(args)?;
+                let results = self.find_session_symbols(session_info, &term, kind.as_deref(), exact)?;
+                Ok(to_json(&results)?)
             }
             "include-cycles" => {
                 let cycles = self.find_session_include_cycles(session_info)?;
-                Ok(serde_json::to_string_pretty(&cycles)?)
+                Ok(to_json(&cycles)?)
             }
-            _ => anyhow::bail!("Unknown session command: {}", command),
-        }
-    }
-}
-
-/// Main analysis session coordinator
-pub struct AnalysisSession {
-    config: AnalysisConfig,
-}
-
-impl AnalysisSession {
-    pub fn new() -> Self {
-        Self {
+            "duplicates" => {
+                let threshold = match args.first() {
+                    Some(arg) => arg.parse::<f64>()
+                        .ok()
+                        .filter(|t| (0.0..=1.0).contains(t))
+                        .ok_or_else(|| anyhow::anyhow!("Invalid similarity threshold '{}': expected a number between 0 and 1", arg))?,
+                    None => DEFAULT_SIMILARITY_THRESHOLD,
+                };
+                let duplicates = self.find_session_duplicates(session_info, threshold)?;
+                Ok(to_json(&duplicates)?)
+            }
+            "annotations" => {
+                let tags: Vec<String> = match args {
+                    [] => self.annotation_tags.clone(),
+                    [flag, tags] if flag == "--tags" => tags.split(',')
+                        .map(str::trim)
+                        .filter(|tag| !tag.is_empty())
+                        .map(str::to_string)
+                        .collect(),
+                    _ => anyhow::bail!("Usage: annotations [--tags TODO,FIXME,...]"),
+                };
+                let annotations = self.find_session_annotations(session_info, &tags)?;
+                Ok(to_json(&annotations)?)
+            }
+            "unreachable" => {
+                let unreachable = self.find_session_unreachable(session_info)?;
+                Ok(to_json(&unreachable)?)
+            }
+            "unused" => {
+                let unused = self.find_session_unused(session_info)?;
+                Ok(to_json(&unused)?)
+            }
+            "callgraph" => {
+                let graph = build_call_graph(&session_info.analysis_results);
+                match args.first().map(String::as_str) {
+                    Some("--dot") => Ok(to_dot(&graph)),
+                    Some(arg) => anyhow::bail!("Unknown callgraph option: {} (expected --dot)", arg),
+                    None => Ok(to_json(&serde_json::json!({
+                        "session_id": session_info.id,
+                        "functions": graph.nodes.len(),
+                        "graph": graph
+                    }))?),
+                }
+            }
+            "api-surface" => {
+                let files = api_surface(&session_info.analysis_results);
+                match args.first().map(String::as_str) {
+                    Some("--markdown") => Ok(render_markdown(&files)),
+                    Some(arg) => anyhow::bail!("Unknown api-surface option: {} (expected --markdown)", arg),
+                    None => Ok(to_json(&serde_json::json!({
+                        "session_id": session_info.id,
+                        "symbols": files.iter().map(|f| f.symbols.len()).sum::<usize>(),
+                        "files": files
+                    }))?),
+                }
+            }
+            "implementations" => {
+                let [symbol] = args else {
+                    anyhow::bail!("Usage: implementations <interface-or-trait>");
+                };
+                let implementations = find_implementations(&session_info.analysis_results, symbol);
+                Ok(to_json(&serde_json::json!({
+                    "session_id": session_info.id,
+                    "symbol": symbol,
+                    "total": implementations.len(),
+                    "implementations": implementations
+                }))?)
+            }
+            "lint" => {
+                let (thresholds, format) = Self::parse_lint_args(args, self.lint_thresholds)?;
+                let violations = lint(&session_info.analysis_results, &thresholds);
+                if format == "sarif" {
+                    return Ok(to_json(&lint_sarif(&violations))?);
+                }
+                Ok(to_json(&serde_json::json!({
+                    "session_id": session_info.id,
+                    "thresholds": thresholds,
+                    "total": violations.len(),
+                    "errors": violations.iter().filter(|v| v.severity == "error").count(),
+                    "violations": violations
+                }))?)
+            }
+            "compare-files" => {
+                let [file_a, file_b] = args else {
+                    anyhow::bail!("Usage: compare-files <fileA> <fileB>");
+                };
+                let a = session_info.find_file(Path::new(file_a))?;
+                let b = session_info.find_file(Path::new(file_b))?;
+                Ok(to_json(&compare_files(a, b))?)
+            }
+            "style" => {
+                // Sessions saved before styles were recorded are detected from
+                // the source files; files that can no longer be read are left out
+                let styles: Vec<(PathBuf, FileStyle)> = session_info.analysis_results.iter()
+                    .filter_map(|result| {
+                        let path = &result.file_info.path;
+                        let style = match &result.file_info.style {
+                            Some(style) => style.clone(),
+                            None => FileStyle::detect(&std::fs::read_to_string(session_info.source_path(path)).ok()?),
+                        };
+                        Some((path.clone(), style))
+                    })
+                    .collect();
+                let report = style_report(styles.iter().map(|(path, style)| (path.as_path(), style)));
+                Ok(to_json(&serde_json::json!({
+                    "session_id": session_info.id,
+                    "files_analyzed": styles.len(),
+                    "indentation": report.indentation,
+                    "line_endings": report.line_endings,
+                    "trailing_whitespace": report.trailing_whitespace
+                }))?)
+            }
+            _ => anyhow::bail!("Unknown session command: {}", command),
+        }
+    }
+    
+    /// `lint [--max-function-lines N] [--max-file-lines N] [--max-parameters N]
+    /// [--format json|sarif]`, starting from `thresholds`; a flag given twice
+    /// keeps the last value
+    fn parse_lint_args(args: &[String], mut thresholds: LintThresholds) -> Result<(LintThresholds, String)> {
+        let usage = "Usage: lint [--max-function-lines N] [--max-file-lines N] [--max-parameters N] [--format json|sarif]";
+        let mut format = "json".to_string();
+        let mut args = args.iter();
+        while let Some(flag) = args.next() {
+            let value = args.next().ok_or_else(|| anyhow::anyhow!(usage))?;
+            if flag == "--format" {
+                if !matches!(value.as_str(), "json" | "sarif") {
+                    anyhow::bail!("Unknown lint format '{}': expected json or sarif", value);
+                }
+                format = value.clone();
+                continue;
+            }
+            let limit = value.parse::<u32>()
+                .map_err(|_| anyhow::anyhow!("Invalid value '{}' for {}: expected a non-negative integer", value, flag))?;
+            match flag.as_str() {
+                "--max-function-lines" => thresholds.max_function_lines = limit,
+                "--max-file-lines" => thresholds.max_file_lines = limit,
+                "--max-parameters" => thresholds.max_parameters = limit,
+                _ => anyhow::bail!(usage),
+            }
+        }
+        Ok((thresholds, format))
+    }
+}
+
+/// Default cap on the source text `ast-query --output-lines` prints per match
+pub const DEFAULT_SNIPPET_MAX_BYTES: usize = 8192;
+
+/// Source text to attach to `ast-query` matches
+#[derive(Debug, Clone, Copy)]
+pub struct SnippetOptions {
+    /// Lines shown before and after the matched node
+    pub context: u32,
+    /// Longer snippets are cut off and marked `truncated`
+    pub max_bytes: usize,
+}
+
+impl SnippetOptions {
+    /// Lines `start_line - context ..= end_line + context` (1-based) of `content`
+    fn extract(&self, content: &str, start_line: u32, end_line: u32) -> serde_json::Value {
+        let lines: Vec<&str> = content.lines().collect();
+        let first = start_line.saturating_sub(self.context).max(1);
+        let last = end_line.max(start_line).saturating_add(self.context).min(lines.len() as u32);
+        
+        let mut text = lines.get(first as usize - 1..last as usize).unwrap_or_default().join("\n");
+        let truncated = text.len() > self.max_bytes;
+        if truncated {
+            let mut cut = self.max_bytes;
+            while !text.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            text.truncate(cut);
+        }
+        
+        serde_json::json!({
+            "start_line": first,
+            "end_line": last,
+            "text": text,
+            "truncated": truncated
+        })
+    }
+}
+
+/// Main analysis session coordinator
+pub struct AnalysisSession {
+    config: AnalysisConfig,
+}
+
+impl AnalysisSession {
+    pub fn new() -> Self {
+        Self {
             config: AnalysisConfig::default(),
         }
     }
@@ -965,7 +1875,9 @@ impl AnalysisSession {
     pub async fn analyze_path(&mut self, path: &Path, include_tests: bool) -> Result<DirectoryAnalysis> {
         self.config.include_test_files = include_tests;
         
-        if path.is_file() {
+        if path.is_file() && is_archive(path) {
+            self.analyze_archive(path).await
+        } else if path.is_file() {
             self.analyze_single_file(path).await
         } else if path.is_dir() {
             self.analyze_directory(path).await
@@ -974,6 +1886,58 @@ impl AnalysisSession {
         }
     }
     
+    /// Analyze a file or directory like `analyze_path`, but hand each file's
+    /// result to `on_file` as soon as it is ready (in completion order) instead
+    /// of collecting them. The returned analysis has the summary and errors
+    /// but no `files`. An error from `on_file` stops the analysis.
+    pub async fn analyze_path_streaming<F>(&mut self, path: &Path, include_tests: bool, mut on_file: F) -> Result<DirectoryAnalysis>
+    where
+        F: FnMut(AnalysisResult) -> Result<()>,
+    {
+        self.config.include_test_files = include_tests;
+        
+        if path.is_file() && is_archive(path) {
+            let mut directory_analysis = self.analyze_archive(path).await?;
+            for result in std::mem::take(&mut directory_analysis.files) {
+                on_file(result)?;
+            }
+            return Ok(directory_analysis);
+        }
+        if path.is_file() {
+            let mut directory_analysis = DirectoryAnalysis::new(
+                path.parent().unwrap_or_else(|| Path::new(".")).to_path_buf()
+            );
+            let result = self.analyze_file(path).await
+                .with_context(|| format!("Failed to analyze file: {}", path.display()))?;
+            directory_analysis.summary.add_file(&result);
+            on_file(result)?;
+            return Ok(directory_analysis);
+        }
+        if !path.is_dir() {
+            anyhow::bail!("Path does not exist or is not accessible: {}", path.display());
+        }
+        
+        let mut directory_analysis = DirectoryAnalysis::new(path.to_path_buf());
+        let mut outcomes = self.spawn_analysis(self.discover_files(path)?);
+        while let Some((_, file_path, result)) = outcomes.recv().await {
+            match result {
+                Ok(result) => {
+                    directory_analysis.summary.add_file(&result);
+                    on_file(result)?;
+                }
+                Err(e) if self.config.fail_on_error => {
+                    return Err(e.context(format!("Failed to analyze file: {}", file_path.display())));
+                }
+                Err(e) => {
+                    log::warn!("⚠️  Failed to analyze {}: {:#}", file_path.display(), e);
+                    directory_analysis.record_error(file_path, &e);
+                }
+            }
+        }
+        
+        Ok(directory_analysis)
+    }
+    
     /// Analyze a single file
     async fn analyze_single_file(&self, file_path: &Path) -> Result<DirectoryAnalysis> {
         let mut directory_analysis = DirectoryAnalysis::new(
@@ -989,9 +1953,36 @@ impl AnalysisSession {
         Ok(directory_analysis)
     }
     
+    /// Analyze the source files inside a `.tar`, `.tar.gz`/`.tgz`,
+    /// `.tar.bz2`, `.tar.xz` or `.zip` archive. Files with a source extension
+    /// (and `.nekocodeignore`) are unpacked into a temporary directory,
+    /// analyzed with the usual discovery filters and removed again. File paths
+    /// are reported relative to the archive root, with the archive as
+    /// `directory_path`.
+    pub async fn analyze_archive(&self, archive: &Path) -> Result<DirectoryAnalysis> {
+        let extracted = ExtractedArchive::unpack(archive, |path| {
+            path.file_name().is_some_and(|name| name == IGNORE_FILE_NAME)
+                || path.extension().and_then(|e| e.to_str())
+                    .is_some_and(|extension| self.config.included_extensions.contains(&format!(".{}", extension)))
+        })?;
+        let mut directory_analysis = self.analyze_directory(&extracted.dir).await?;
+        
+        directory_analysis.directory_path = archive.to_path_buf();
+        for file in &mut directory_analysis.files {
+            file.file_info.path = relative_to_root(&file.file_info.path, &extracted.dir);
+        }
+        for error in &mut directory_analysis.errors {
+            error.path = relative_to_root(&error.path, &extracted.dir);
+        }
+        Ok(directory_analysis)
+    }
+    
     /// Analyze a directory
     async fn analyze_directory(&self, dir_path: &Path) -> Result<DirectoryAnalysis> {
-        println!("🔍 [RUST] Starting directory analysis: {}", dir_path.display());
+        let verbose = self.config.verbose_output;
+        if verbose {
+            log::info!("🔍 Starting directory analysis: {}", dir_path.display());
+        }
         let start_total = std::time::Instant::now();
         
         let mut directory_analysis = DirectoryAnalysis::new(dir_path.to_path_buf());
@@ -1000,86 +1991,168 @@ impl AnalysisSession {
         let start_scan = std::time::Instant::now();
         let files = self.discover_files(dir_path)?;
         let scan_duration = start_scan.elapsed();
-        println!("📁 [RUST] File discovery took: {:.3}s, found {} files", scan_duration.as_secs_f64(), files.len());
-        
-        if self.config.verbose_output {
-            println!("📁 Found {} files to analyze", files.len());
+        if verbose {
+            log::info!("📁 File discovery took: {:.3}s, found {} files", scan_duration.as_secs_f64(), files.len());
             for file in &files {
-                println!("  - {}", file.display());
+                log::debug!("  - {}", file.display());
             }
         }
         
         // Analyze files in parallel
         let start_analysis = std::time::Instant::now();
-        println!("⚡ [RUST] Starting {} analysis (parallel={})", 
-                 if self.config.enable_parallel_processing { "PARALLEL" } else { "SEQUENTIAL" },
-                 self.config.enable_parallel_processing);
-        
-        let results: Result<Vec<_>> = if self.config.enable_parallel_processing {
-            // 🚀 Use spawn_blocking with chunk processing for better parallelization
-            let total_files = files.len();
-            println!("🔧 [RUST] Creating {} spawn_blocking tasks for parallel processing", total_files);
-            
-            let futures: Vec<_> = files.into_iter().enumerate().map(|(i, file_path)| {
-                let config = self.config.clone();
-                tokio::task::spawn_blocking(move || {
-                    if i % 100 == 0 || i == total_files - 1 {
-                        println!("🔄 [RUST] Processing file {}/{} on thread {:?}: {}", 
-                                i + 1, total_files, 
-                                std::thread::current().id(), 
-                                file_path.display());
-                    }
-                    // Create a temporary session for this task
-                    let temp_session = AnalysisSession::with_config(config);
-                    // Use the sync version of the runtime
-                    tokio::runtime::Handle::current().block_on(async {
-                        temp_session.analyze_file(&file_path).await
-                    })
-                })
-            }).collect();
-            
-            println!("🚀 [RUST] Spawned {} blocking tasks, waiting for completion...", futures.len());
-            
-            // Process futures concurrently
-            let results = futures::future::join_all(futures).await;
-            results.into_iter().collect::<Result<Vec<_>, _>>().map_err(|e| anyhow::anyhow!("Task join error: {}", e))?
-                .into_iter().collect()
-        } else {
-            let mut results = Vec::new();
-            for file_path in &files {
-                results.push(self.analyze_file(file_path).await);
-            }
-            results.into_iter().collect()
-        };
+        if verbose {
+            log::info!("⚡ Starting {} analysis",
+                       if self.config.enable_parallel_processing { "parallel" } else { "sequential" });
+        }
         
-        directory_analysis.files = results?;
+        self.analyze_files_into(&mut directory_analysis, files).await?;
         let analysis_duration = start_analysis.elapsed();
-        println!("🔄 [RUST] File analysis took: {:.3}s ({} files)", analysis_duration.as_secs_f64(), directory_analysis.files.len());
+        if verbose {
+            log::info!("🔄 File analysis took: {:.3}s ({} files)", analysis_duration.as_secs_f64(), directory_analysis.files.len());
+        }
         
         let start_summary = std::time::Instant::now();
         directory_analysis.update_summary();
         let summary_duration = start_summary.elapsed();
-        println!("📊 [RUST] Summary generation took: {:.3}s", summary_duration.as_secs_f64());
-        
         let total_duration = start_total.elapsed();
-        println!("🏁 [RUST] Total directory analysis took: {:.3}s", total_duration.as_secs_f64());
+        if verbose {
+            log::debug!("📊 Summary generation took: {:.3}s", summary_duration.as_secs_f64());
+            log::info!("🏁 Analyzed {} files in {:.3}s", directory_analysis.files.len(), total_duration.as_secs_f64());
+        }
         
-        if self.config.verbose_output {
-            println!("✅ Analyzed {} files successfully", directory_analysis.files.len());
+        Ok(directory_analysis)
+    }
+    
+    /// Analyze an explicit list of files, e.g. from `git diff --name-only`,
+    /// instead of walking a directory. Missing files are recorded under
+    /// `errors`; files the extension, exclude or test-file filters reject are skipped.
+    pub async fn analyze_files(&self, files: &[PathBuf]) -> Result<DirectoryAnalysis> {
+        let mut directory_analysis = DirectoryAnalysis::new(std::env::current_dir()?);
+        
+        let mut selected = Vec::new();
+        for path in files {
+            if !path.is_file() {
+                let error = anyhow::anyhow!("File not found: {}", path.display());
+                if self.config.fail_on_error {
+                    return Err(error);
+                }
+                log::warn!("⚠️  {}", error);
+                directory_analysis.record_error(path.clone(), &error);
+            } else if self.should_analyze_file(path) {
+                selected.push(path.clone());
+            }
         }
         
+        self.analyze_files_into(&mut directory_analysis, selected).await?;
+        directory_analysis.update_summary();
         Ok(directory_analysis)
     }
     
+    /// Analyze `files` and add the results, or the failures, to `directory_analysis`
+    async fn analyze_files_into(&self, directory_analysis: &mut DirectoryAnalysis, files: Vec<PathBuf>) -> Result<()> {
+        let mut outcomes = self.spawn_analysis(files);
+        let mut indexed = Vec::new();
+        while let Some(outcome) = outcomes.recv().await {
+            indexed.push(outcome);
+        }
+        
+        // Keep results in discovery order regardless of completion order
+        indexed.sort_by_key(|(index, _, _)| *index);
+        
+        for (_, path, result) in indexed {
+            match result {
+                Ok(result) => directory_analysis.files.push(result),
+                Err(e) if self.config.fail_on_error => {
+                    return Err(e.context(format!("Failed to analyze file: {}", path.display())));
+                }
+                Err(e) => {
+                    log::warn!("⚠️  Failed to analyze {}: {:#}", path.display(), e);
+                    directory_analysis.record_error(path, &e);
+                }
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Analyze `files` in a background task and send each outcome, tagged with
+    /// the file's position in `files`, as soon as it completes. Analysis stops
+    /// early when the receiver is dropped.
+    fn spawn_analysis(&self, files: Vec<PathBuf>) -> tokio::sync::mpsc::Receiver<(usize, PathBuf, Result<AnalysisResult>)> {
+        let concurrency = self.analysis_concurrency();
+        let (sender, receiver) = tokio::sync::mpsc::channel(concurrency);
+        let config = self.config.clone();
+        
+        tokio::spawn(async move {
+            if config.enable_parallel_processing {
+                // Each file is analyzed in its own task on the runtime's worker threads,
+                // with at most `concurrency` in flight at once
+                let mut outcomes = futures::stream::iter(files.into_iter().enumerate())
+                    .map(|(index, file_path)| {
+                        let session = AnalysisSession::with_config(config.clone());
+                        async move {
+                            let task_path = file_path.clone();
+                            let result = tokio::spawn(async move { session.analyze_file(&task_path).await })
+                                .await
+                                .map_err(|e| anyhow::anyhow!("Task join error: {}", e))
+                                .and_then(|result| result);
+                            (index, file_path, result)
+                        }
+                    })
+                    .buffer_unordered(concurrency);
+                while let Some(outcome) = outcomes.next().await {
+                    if sender.send(outcome).await.is_err() {
+                        break;
+                    }
+                }
+            } else {
+                let session = AnalysisSession::with_config(config);
+                for (index, file_path) in files.into_iter().enumerate() {
+                    let result = session.analyze_file(&file_path).await;
+                    if sender.send((index, file_path, result)).await.is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+        
+        receiver
+    }
+    
+    /// Number of files analyzed concurrently; `max_threads == 0` means one per CPU
+    fn analysis_concurrency(&self) -> usize {
+        if self.config.max_threads > 0 {
+            self.config.max_threads
+        } else {
+            std::thread::available_parallelism().map_or(1, |n| n.get())
+        }
+    }
+    
     /// Discover files in a directory based on configuration
     fn discover_files(&self, dir_path: &Path) -> Result<Vec<PathBuf>> {
         let mut files = Vec::new();
+        let ignore = IgnoreFile::load(dir_path)?;
+        // With symlinks followed, the same file can be reached through several links
+        let mut seen = HashSet::new();
         
-        for entry in WalkDir::new(dir_path)
-            .follow_links(false)
+        let entries = WalkDir::new(dir_path)
+            .follow_links(self.config.follow_symlinks)
             .into_iter()
-            .filter_map(|e| e.ok())
-        {
+            // Prune ignored and hidden directories instead of walking into them
+            .filter_entry(|e| {
+                !Self::is_ignored(ignore.as_ref(), e.path(), e.file_type().is_dir())
+                    && !self.is_hidden_below(dir_path, e.path())
+            });
+        for entry in entries {
+            let entry = match entry {
+                Ok(entry) => entry,
+                // walkdir stops at a link back to one of its ancestors
+                Err(e) if e.loop_ancestor().is_some() => {
+                    log::warn!("Skipping symlink loop at {}", e.path().map_or_else(String::new, |p| p.display().to_string()));
+                    continue;
+                }
+                Err(_) => continue,
+            };
             let path = entry.path();
             
             // Skip directories
@@ -1087,27 +2160,114 @@ impl AnalysisSession {
                 continue;
             }
             
-            // Check if path should be excluded
-            if self.should_exclude_path(path) {
+            if self.config.follow_symlinks && !seen.insert(path.canonicalize().unwrap_or_else(|_| path.to_path_buf())) {
                 continue;
             }
+            if self.should_analyze_file(path) {
+                files.push(path.to_path_buf());
+            }
+        }
+        
+        Ok(files)
+    }
+    
+    /// Whether `path` is, or lies inside, a dotfile or dot-directory below
+    /// `root`, unless hidden files were requested. The root itself may be hidden.
+    fn is_hidden_below(&self, root: &Path, path: &Path) -> bool {
+        if self.config.include_hidden {
+            return false;
+        }
+        let Ok(relative) = path.strip_prefix(root) else {
+            return false;
+        };
+        relative.components().any(|component| {
+            matches!(component, std::path::Component::Normal(name) if name.to_string_lossy().starts_with('.'))
+        })
+    }
+    
+    /// Check if a file passes the exclude, extension and test-file filters
+    fn should_analyze_file(&self, path: &Path) -> bool {
+        // Check if path should be excluded
+        if self.should_exclude_path(path) {
+            return false;
+        }
+        
+        // Check if file extension is supported
+        let Some(extension) = path.extension().and_then(|e| e.to_str()) else {
+            return false;
+        };
+        if !self.config.included_extensions.contains(&format!(".{}", extension)) {
+            return false;
+        }
+        
+        // Skip oversized files (e.g. minified bundles) if a limit is set
+        if let Some(max_size) = self.config.max_file_size_bytes {
+            if fs::metadata(path).is_ok_and(|metadata| metadata.len() > max_size) {
+                log::info!("Skipping {}: larger than {} bytes", path.display(), max_size);
+                return false;
+            }
+        }
+        
+        // Skip test files if not requested
+        self.config.include_test_files || !Self::is_test_file(path)
+    }
+    
+    /// Re-analyze only the given changed files and update `analysis` in place.
+    ///
+    /// Deleted or no longer eligible files are dropped from the results. Returns the
+    /// number of files that were re-analyzed or removed.
+    pub async fn reanalyze_files(&self, analysis: &mut DirectoryAnalysis, changed: &[PathBuf]) -> Result<usize> {
+        let mut updated = 0;
+        let ignore = IgnoreFile::load(&analysis.directory_path)?;
+        
+        for path in changed {
+            let existing = analysis.files.iter().position(|r| r.file_info.path == *path);
             
-            // Check if file extension is supported
-            if let Some(extension) = path.extension().and_then(|e| e.to_str()) {
-                let ext_with_dot = format!(".{}", extension);
-                
-                if self.config.included_extensions.contains(&ext_with_dot) {
-                    // Skip test files if not requested
-                    if !self.config.include_test_files && self.is_test_file(path) {
+            let eligible = self.should_analyze_file(path)
+                && !Self::is_ignored(ignore.as_ref(), path, false)
+                && !self.is_hidden_below(&analysis.directory_path, path);
+            if path.is_file() && eligible {
+                let result = match self.analyze_file(path).await {
+                    Ok(result) => result,
+                    Err(e) if self.config.fail_on_error => {
+                        return Err(e.context(format!("Failed to analyze file: {}", path.display())));
+                    }
+                    Err(e) => {
+                        // Keep the last good result, but report the file as broken
+                        analysis.record_error(path.clone(), &e);
+                        updated += 1;
                         continue;
                     }
-                    
-                    files.push(path.to_path_buf());
+                };
+                analysis.clear_error(path);
+                match existing {
+                    Some(index) => analysis.files[index] = result,
+                    None => analysis.files.push(result),
+                }
+                updated += 1;
+            } else {
+                let had_error = analysis.errors.iter().any(|e| e.path == *path);
+                analysis.clear_error(path);
+                if let Some(index) = existing {
+                    analysis.files.remove(index);
+                }
+                if had_error || existing.is_some() {
+                    updated += 1;
                 }
             }
         }
         
-        Ok(files)
+        if updated > 0 {
+            analysis.update_summary();
+            analysis.generated_at = Utc::now();
+        }
+        
+        Ok(updated)
+    }
+    
+    /// Check a path against the root's `.nekocodeignore`, if there is one
+    fn is_ignored(ignore: Option<&IgnoreFile>, path: &Path, is_dir: bool) -> bool {
+        ignore.is_some_and(|ignore| ignore.is_ignored(path, is_dir))
     }
     
     /// Check if a path should be excluded based on patterns
@@ -1124,26 +2284,35 @@ impl AnalysisSession {
     }
     
     /// Check if a file is a test file
-    fn is_test_file(&self, path: &Path) -> bool {
+    pub(crate) fn is_test_file(path: &Path) -> bool {
+        const TEST_WORDS: &[&str] = &["test", "tests", "spec", "specs"];
+        const TEST_DIRS: &[&str] = &["test", "tests", "__tests__", "spec", "specs"];
+        
         let file_name = path.file_name()
             .and_then(|n| n.to_str())
-            .unwrap_or("")
-            .to_lowercase();
+            .unwrap_or("");
+        let stem = file_name.rsplit_once('.').map_or(file_name, |(stem, _)| stem);
         
-        let path_str = path.to_string_lossy().to_lowercase();
+        // Whole words of the name: `foo.test.js`, `foo_test.go`, `test_foo.py`, `foo-spec.ts`
+        let lower_stem = stem.to_lowercase();
+        if lower_stem.split(['.', '_', '-']).any(|word| TEST_WORDS.contains(&word)) {
+            return true;
+        }
         
-        // Check for specific test file patterns
-        file_name.contains("test") ||
-        file_name.contains("spec") ||
-        path_str.contains("__tests__") ||
-        path_str.ends_with(".test.js") ||
-        path_str.ends_with(".test.ts") ||
-        path_str.ends_with(".spec.js") ||
-        path_str.ends_with(".spec.ts") ||
-        path_str.contains("/test/") ||
-        path_str.contains("/tests/") ||
-        path_str.contains("/spec/") ||
-        path_str.contains("/specs/")
+        // CamelCase suffix or prefix: `ParserTests.cs`, `TestParser.java`
+        let camel_prefix = stem.strip_prefix("Test")
+            .is_some_and(|rest| rest.starts_with(|c: char| c.is_uppercase()));
+        if stem.ends_with("Test") || stem.ends_with("Tests") || camel_prefix {
+            return true;
+        }
+        
+        // Test directories anywhere above the file
+        path.parent().is_some_and(|parent| {
+            parent.components().any(|component| {
+                let name = component.as_os_str().to_string_lossy().to_lowercase();
+                TEST_DIRS.contains(&name.as_str())
+            })
+        })
     }
     
     /// Analyze a specific file
@@ -1156,105 +2325,89 @@ impl AnalysisSession {
         let metadata = tokio::fs::metadata(file_path).await
             .with_context(|| format!("Failed to get metadata for: {}", file_path.display()))?;
         
-        let mut file_info = FileInfo::new(file_path.to_path_buf());
+        let language = self.config.language_override
+            .unwrap_or_else(|| Language::detect(file_path, &content));
+        
+        let mut file_info = Self::file_info_for(file_path, &content, language);
         file_info.size_bytes = metadata.len();
-        file_info.total_lines = content.lines().count() as u32;
-        
-        // Calculate basic line statistics
-        for line in content.lines() {
-            let trimmed = line.trim();
-            if trimmed.is_empty() {
-                file_info.empty_lines += 1;
-            } else if trimmed.starts_with("//") || trimmed.starts_with("/*") || trimmed.starts_with("*") {
-                file_info.comment_lines += 1;
-            } else {
-                file_info.code_lines += 1;
-            }
-        }
         
-        file_info.code_ratio = if file_info.total_lines > 0 {
-            file_info.code_lines as f64 / file_info.total_lines as f64
-        } else {
-            0.0
-        };
+        self.analyze_source(&content, file_path, language, file_info).await
+    }
+    
+    /// Analyze source that has no file on disk (e.g. an unsaved editor buffer
+    /// piped through stdin). The language cannot be detected and must be given.
+    pub async fn analyze_content(&self, content: &str, language: Language) -> Result<AnalysisResult> {
+        if language == Language::Unknown {
+            anyhow::bail!("A language is required to analyze content without a file path");
+        }
         
-        // Determine language
-        let language = if let Some(extension) = file_path.extension().and_then(|e| e.to_str()) {
-            Language::from_extension(&format!(".{}", extension))
-        } else {
-            Language::Unknown
-        };
+        let path = Path::new("<stdin>");
+        let mut file_info = Self::file_info_for(path, content, language);
+        file_info.size_bytes = content.len() as u64;
         
+        self.analyze_source(content, path, language, file_info).await
+    }
+    
+    /// Basic line statistics for `content`
+    fn file_info_for(file_path: &Path, content: &str, language: Language) -> FileInfo {
+        let mut file_info = FileInfo::new(file_path.to_path_buf());
+        file_info.set_line_stats(content, language);
+        file_info
+    }
+    
+    /// Run the analyzer for `language` over `content`
+    async fn analyze_source(&self, content: &str, file_path: &Path, language: Language, file_info: FileInfo) -> Result<AnalysisResult> {
         // Create base analysis result
         let mut result = AnalysisResult::new(file_info, language);
         
-        // Perform language-specific analysis
-        match language {
-            Language::JavaScript | Language::TypeScript => {
-                // 🚀 Always use Tree-sitter (fastest parser)
-                let mut analyzer = TreeSitterJavaScriptAnalyzer::new()
-                    .map_err(|e| anyhow::anyhow!("Failed to create tree-sitter analyzer: {}", e))?;
-                result = analyzer.analyze(&content, file_path.to_string_lossy().as_ref()).await?;
-                result.language = language; // Ensure correct language is set
-            }
-            Language::Python => {
-                // 🚀 Always use Tree-sitter (100x faster than PEST)
-                use crate::analyzers::python::TreeSitterPythonAnalyzer;
-                let mut analyzer = TreeSitterPythonAnalyzer::new()
-                    .map_err(|e| anyhow::anyhow!("Failed to create tree-sitter Python analyzer: {}", e))?;
-                result = analyzer.analyze(&content, file_path.to_string_lossy().as_ref()).await?;
-                result.language = language; // Ensure correct language is set
-            }
-            Language::Cpp => {
-                // 🚀 Always use Tree-sitter (100x faster than PEST)
-                use crate::analyzers::cpp::TreeSitterCppAnalyzer;
-                let mut analyzer = TreeSitterCppAnalyzer::new()
-                    .map_err(|e| anyhow::anyhow!("Failed to create tree-sitter C++ analyzer: {}", e))?;
-                result = analyzer.analyze(&content, file_path.to_string_lossy().as_ref()).await?;
-                result.language = language; // Ensure correct language is set
-            }
-            Language::CSharp => {
-                // 🚀 Always use Tree-sitter (100x faster than PEST)
-                use crate::analyzers::csharp::TreeSitterCSharpAnalyzer;
-                let mut analyzer = TreeSitterCSharpAnalyzer::new()
-                    .map_err(|e| anyhow::anyhow!("Failed to create tree-sitter C# analyzer: {}", e))?;
-                result = analyzer.analyze(&content, file_path.to_string_lossy().as_ref()).await?;
-                result.language = language; // Ensure correct language is set
-            }
-            Language::Go => {
-                // 🚀 Always use Tree-sitter (100x faster than PEST)
-                use crate::analyzers::go::TreeSitterGoAnalyzer;
-                let mut analyzer = TreeSitterGoAnalyzer::new()
-                    .map_err(|e| anyhow::anyhow!("Failed to create tree-sitter Go analyzer: {}", e))?;
-                result = analyzer.analyze(&content, file_path.to_string_lossy().as_ref()).await?;
+        // Perform language-specific analysis with a pooled analyzer, so parsers
+        // are reused across files
+        match crate::analyzers::pool::checkout(language)? {
+            Some(mut analyzer) => {
+                analyzer.set_parse_timeout(self.config.per_file_timeout_ms.map(std::time::Duration::from_millis));
+                result = analyzer.analyze(content, file_path.to_string_lossy().as_ref()).await?;
                 result.language = language; // Ensure correct language is set
             }
-            Language::Rust => {
-                // 🚀 Always use Tree-sitter (100x faster than PEST)
-                use crate::analyzers::rust::TreeSitterRustAnalyzer;
-                let mut analyzer = TreeSitterRustAnalyzer::new()
-                    .map_err(|e| anyhow::anyhow!("Failed to create tree-sitter Rust analyzer: {}", e))?;
-                result = analyzer.analyze(&content, file_path.to_string_lossy().as_ref()).await?;
-                result.language = language; // Ensure correct language is set
-            }
-            Language::Unknown => {
+            None if language == Language::Unknown => {
                 if self.config.verbose_output {
-                    println!("⚠️  Skipping unknown file type: {}", file_path.display());
+                    log::warn!("⚠️  Skipping unknown file type: {}", file_path.display());
                 }
             }
-            _ => {
+            None => {
                 if self.config.verbose_output {
-                    println!("⚠️  Language not yet implemented: {:?} for {}", language, file_path.display());
+                    log::warn!("⚠️  Language not yet implemented: {:?} for {}", language, file_path.display());
                 }
             }
         }
         
+        // Analyzers count lines with their own prefix checks; the
+        // language-aware count replaces them
+        result.file_info.set_line_stats(content, language);
+        Self::fill_function_line_stats(&mut result, content);
+        result.file_info.style = Some(FileStyle::detect(content));
+        
         // Update statistics
         result.update_statistics();
         
         Ok(result)
     }
     
+    /// Set `line_stats` of every function and method from the source lines it spans
+    fn fill_function_line_stats(result: &mut AnalysisResult, content: &str) {
+        let lines: Vec<&str> = content.lines().collect();
+        let functions = result.functions.iter_mut()
+            .chain(result.classes.iter_mut().flat_map(|class| class.methods.iter_mut()));
+        
+        for function in functions {
+            if function.start_line == 0 {
+                continue;
+            }
+            let start = (function.start_line as usize - 1).min(lines.len());
+            let end = (function.end_line.max(function.start_line) as usize).min(lines.len());
+            function.line_stats = LineStats::from_lines(lines[start..end].iter().copied(), result.language);
+        }
+    }
+    
     // Session-specific methods for new functionality
     pub fn get_stats(&self) -> String {
         "Session statistics placeholder".to_string()
@@ -1281,4 +2434,605 @@ impl Default for AnalysisSession {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+
+/// File name endings of the archives `analyze_archive` can unpack
+const ARCHIVE_SUFFIXES: &[&str] = &[".tar", ".tar.gz", ".tgz", ".tar.bz2", ".tbz2", ".tar.xz", ".txz", ".zip"];
+
+/// Whether `path` names an archive that `analyze_path` unpacks
+pub fn is_archive(path: &Path) -> bool {
+    let name = path.file_name().map(|name| name.to_string_lossy().to_lowercase()).unwrap_or_default();
+    ARCHIVE_SUFFIXES.iter().any(|suffix| name.ends_with(suffix))
+}
+
+/// An archive unpacked into a temporary directory, removed on drop
+struct ExtractedArchive {
+    dir: PathBuf,
+}
+
+impl ExtractedArchive {
+    /// Unpack the regular files of `archive` whose path inside it passes
+    /// `keep`. Entries are read one at a time and skipped ones are never
+    /// written; entries that would land outside the directory are refused.
+    fn unpack(archive: &Path, keep: impl Fn(&Path) -> bool) -> Result<Self> {
+        let dir = std::env::temp_dir().join(format!("nekocode_archive_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        let extracted = Self { dir };
+        
+        let file = std::fs::File::open(archive)
+            .with_context(|| format!("Failed to open {}", archive.display()))?;
+        let name = archive.file_name().map(|name| name.to_string_lossy().to_lowercase()).unwrap_or_default();
+        let unpacked = if name.ends_with(".zip") {
+            extracted.unpack_zip(file, &keep)
+        } else {
+            let reader: Box<dyn std::io::Read> = if name.ends_with(".gz") || name.ends_with(".tgz") {
+                Box::new(flate2::read::GzDecoder::new(file))
+            } else if name.ends_with(".bz2") || name.ends_with(".tbz2") {
+                Box::new(bzip2::read::BzDecoder::new(file))
+            } else if name.ends_with(".xz") || name.ends_with(".txz") {
+                Box::new(xz2::read::XzDecoder::new(file))
+            } else {
+                Box::new(file)
+            };
+            extracted.unpack_tar(reader, &keep)
+        };
+        unpacked.with_context(|| format!("Failed to unpack {}", archive.display()))?;
+        Ok(extracted)
+    }
+    
+    fn unpack_tar(&self, reader: impl std::io::Read, keep: &dyn Fn(&Path) -> bool) -> Result<()> {
+        let mut archive = tar::Archive::new(reader);
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            if !entry.header().entry_type().is_file() || !keep(&entry.path()?) {
+                continue;
+            }
+            // `unpack_in` refuses paths with `..` or a root
+            entry.unpack_in(&self.dir)?;
+        }
+        Ok(())
+    }
+    
+    fn unpack_zip(&self, file: std::fs::File, keep: &dyn Fn(&Path) -> bool) -> Result<()> {
+        let mut archive = zip::ZipArchive::new(file)?;
+        for index in 0..archive.len() {
+            let mut entry = archive.by_index(index)?;
+            // `enclosed_name` is `None` for paths that escape the archive root
+            let Some(path) = entry.enclosed_name().filter(|path| entry.is_file() && keep(path)) else {
+                continue;
+            };
+            let target = self.dir.join(path);
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::io::copy(&mut entry, &mut std::fs::File::create(&target)?)?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ExtractedArchive {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.dir) {
+            log::warn!("Failed to remove {}: {}", self.dir.display(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ast::ASTNodeType;
+    
+    /// A session with id `test` over `root` holding `analysis_results`
+    fn session_info(root: &Path, analysis_results: Vec<AnalysisResult>) -> SessionInfo {
+        SessionInfo {
+            id: "test".to_string(),
+            path: root.to_path_buf(),
+            created_at: Utc::now(),
+            last_accessed: Utc::now(),
+            metadata: HashMap::new(),
+            analysis_results,
+            combined_ast_stats: None,
+            change_detector: None,
+            symbol_index: OnceLock::new(),
+        }
+    }
+    
+    #[test]
+    fn test_is_test_file() {
+        let is_test = |path: &str| AnalysisSession::is_test_file(Path::new(path));
+        
+        assert!(is_test("src/foo.test.js"));
+        assert!(is_test("src/button.spec.ts"));
+        assert!(is_test("pkg/parser_test.go"));
+        assert!(is_test("tests/test_parser.py"));
+        assert!(is_test("Project/ParserTests.cs"));
+        assert!(is_test("src/__tests__/button.js"));
+        assert!(is_test("app/tests/helpers.rs"));
+        
+        assert!(!is_test("src/contest.js"));
+        assert!(!is_test("tools/fastest.py"));
+        assert!(!is_test("cmd/latest.go"));
+        assert!(!is_test("packages/latest/index.js"));
+        assert!(!is_test("src/Testimony.cs"));
+    }
+    
+    #[tokio::test]
+    async fn test_find_type_filter_and_exact_match() {
+        use crate::analyzers::python::TreeSitterPythonAnalyzer;
+        use crate::analyzers::traits::LanguageAnalyzer;
+        
+        let source = "class Widget:\n    def render(self):\n        pass\n\n    def render_icon(self):\n        pass\n\ndef render(widget):\n    widget.render()\n";
+        let mut analyzer = TreeSitterPythonAnalyzer::new().unwrap();
+        let result = analyzer.analyze(source, "ui.py").await.unwrap();
+        
+        let dir = tempfile::tempdir().unwrap();
+        let manager = SessionManager::with_dir(dir.path().to_path_buf()).unwrap();
+        let mut session_info = session_info(dir.path(), vec![result]);
+        
+        let find = |args: &[&str]| -> Vec<(String, String)> {
+            let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+            let (term, kind, exact) = SessionManager::parse_find_args(&args).unwrap();
+            let found = manager.find_session_symbols(&session_info, &term, kind.as_deref(), exact).unwrap();
+            found["matches"].as_array().unwrap()
+                .iter()
+                .map(|m| (m["type"].as_str().unwrap().to_string(), m["name"].as_str().unwrap().to_string()))
+                .collect()
+        };
+        let pair = |kind: &str, name: &str| (kind.to_string(), name.to_string());
+        
+        assert_eq!(find(&["render", "--type", "function", "--exact"]), vec![pair("function", "render")]);
+        assert_eq!(find(&["render", "--type", "method", "--exact"]), vec![pair("method", "render")]);
+        assert_eq!(find(&["render", "--exact", "--type", "ast"]), vec![pair("ast_node", "render"), pair("ast_node", "render")]);
+        assert_eq!(find(&["RENDER_", "--type", "method"]), vec![pair("method", "render_icon")]);
+        assert!(find(&["Render", "--exact"]).is_empty());
+        
+        let args = ["x".to_string(), "--type".to_string(), "variable".to_string()];
+        assert!(SessionManager::parse_find_args(&args).is_err());
+        
+        // A file without an AST still has its definitions listed as AST nodes
+        let mut no_ast = session_info.analysis_results[0].clone();
+        no_ast.file_info.path = PathBuf::from("copy.py");
+        no_ast.ast_root = None;
+        session_info.analysis_results.push(no_ast);
+        session_info.symbol_index.take();
+        let found = manager.find_session_symbols(&session_info, "render", Some("ast"), true).unwrap();
+        let synthesized: Vec<_> = found["matches"].as_array().unwrap().iter()
+            .filter(|m| m["file"] == "copy.py")
+            .map(|m| (m["scope_path"].as_str().unwrap(), m["node_type"].as_str().unwrap(), m["synthesized"] == true))
+            .collect();
+        assert_eq!(synthesized, vec![("Widget::render", "method", true), ("render", "function", true)]);
+    }
+    
+    #[tokio::test]
+    async fn test_analyze_content_with_explicit_language() {
+        let session = AnalysisSession::new();
+        let result = session.analyze_content("fn main() {}\nfn helper(x: i32) -> i32 { x }\n", Language::Rust).await.unwrap();
+        
+        assert_eq!(result.language, Language::Rust);
+        let names: Vec<_> = result.functions.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["main", "helper"]);
+        
+        assert!(session.analyze_content("fn main() {}", Language::Unknown).await.is_err());
+        assert_eq!(Language::from_name("TS"), Some(Language::TypeScript));
+        assert_eq!(Language::from_name("cobol"), None);
+    }
+    
+    #[tokio::test]
+    async fn test_analyze_file_detects_language_from_content() {
+        let dir = tempfile::tempdir().unwrap();
+        
+        let script = dir.path().join("deploy");
+        std::fs::write(&script, "#!/usr/bin/env python3\n\ndef main():\n    pass\n").unwrap();
+        let header = dir.path().join("widget.h");
+        std::fs::write(&header, "#pragma once\n\nclass Widget {\npublic:\n    void draw();\n};\n").unwrap();
+        let c_header = dir.path().join("point.h");
+        std::fs::write(&c_header, "/* a class of points */\nstruct point { int x; int y; };\n").unwrap();
+        
+        let session = AnalysisSession::new();
+        let result = session.analyze_file(&script).await.unwrap();
+        assert_eq!(result.language, Language::Python);
+        assert_eq!(result.functions[0].name, "main");
+        
+        let result = session.analyze_file(&header).await.unwrap();
+        assert_eq!(result.language, Language::Cpp);
+        assert_eq!(result.classes[0].name, "Widget");
+        
+        assert_eq!(session.analyze_file(&c_header).await.unwrap().language, Language::C);
+        
+        // An explicit language wins over detection
+        let mut config = AnalysisConfig::default();
+        config.language_override = Some(Language::C);
+        let session = AnalysisSession::with_config(config);
+        assert_eq!(session.analyze_file(&header).await.unwrap().language, Language::C);
+        
+        assert_eq!(Language::from_shebang("#!/usr/bin/env -S node --harmony"), Some(Language::JavaScript));
+        assert_eq!(Language::from_shebang("#!/usr/bin/python3.11"), Some(Language::Python));
+        assert_eq!(Language::from_shebang("#!/bin/sh"), None);
+    }
+    
+    #[tokio::test]
+    async fn test_function_line_stats() {
+        let source = "// Adds numbers\nfunction add(a, b) {\n    // sum them\n\n    return a + b;\n}\n\nclass Box {\n    open() {\n        return 1;\n    }\n}\n";
+        let session = AnalysisSession::new();
+        let result = session.analyze_content(source, Language::JavaScript).await.unwrap();
+        
+        let add = result.functions.iter().find(|f| f.name == "add").unwrap();
+        assert_eq!(add.line_stats, LineStats { code_lines: 3, comment_lines: 1, empty_lines: 1 });
+        assert_eq!(add.line_stats.comment_ratio(), 0.25);
+        
+        let open = result.classes[0].methods.iter().find(|m| m.name == "open").unwrap();
+        assert_eq!(open.line_stats.code_lines, 3);
+    }
+    
+    #[test]
+    fn test_comment_lines_per_language() {
+        let count = |source: &str, language| {
+            let stats = LineStats::from_lines(source.lines(), language);
+            (stats.code_lines, stats.comment_lines, stats.empty_lines)
+        };
+        
+        // Docstrings are comments; a triple-quoted value and `#` inside strings are code
+        let python = "\"\"\"Module doc.\n\nMore.\n\"\"\"\n# note\ndef f():\n    '''Doc.'''\n    s = \"\"\"\n# not a comment\n\"\"\"\n    return '#'  # done\n";
+        assert_eq!(count(python, Language::Python), (5, 5, 1));
+        
+        // `*` only continues a comment inside a block; `//` in a string is code
+        let js = "/*\n * Doc\n */\nconst a = 1; /* tail */\nconst url = \"http://x\";\nconst t = `\n// in template\n`;\n* not a comment\n";
+        assert_eq!(count(js, Language::JavaScript), (6, 3, 0));
+        
+        let rust = "/// Doc\nfn f<'a>(x: &'a str) {} // trailing\n/* a\n   b */ let y = 1;\n";
+        assert_eq!(count(rust, Language::Rust), (2, 2, 0));
+        
+        let go = "// Package main\npackage main\n\nvar s = `\n/* raw */\n`\n";
+        assert_eq!(count(go, Language::Go), (4, 1, 1));
+        
+        // Shell and Ruby files have no analyzer but their `#` comments still count
+        assert_eq!(count("#!/bin/sh\n# setup\necho hi\n", Language::Unknown), (1, 2, 0));
+    }
+    
+    #[tokio::test]
+    async fn test_per_file_timeout_and_size_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("small.js"), "function ok() { return 1; }\n").unwrap();
+        let bundle = "function f(a) { if (a) { return [a, a + 1].map(x => x * 2); } return null; }\n".repeat(20_000);
+        std::fs::write(dir.path().join("bundle.js"), &bundle).unwrap();
+        
+        let mut config = AnalysisConfig::default();
+        config.per_file_timeout_ms = Some(1);
+        let analysis = AnalysisSession::with_config(config.clone()).analyze_path(dir.path(), false).await.unwrap();
+        assert!(analysis.errors.iter().any(|e| e.path.ends_with("bundle.js") && e.message.contains("timed out")));
+        
+        config.per_file_timeout_ms = Some(60_000);
+        config.max_file_size_bytes = Some(1024);
+        let analysis = AnalysisSession::with_config(config).analyze_path(dir.path(), false).await.unwrap();
+        assert!(analysis.errors.is_empty());
+        assert_eq!(analysis.files.len(), 1);
+        assert!(analysis.files[0].file_info.path.ends_with("small.js"));
+
+        // A timed-out parse must not leak into the next file the analyzer sees
+        let mut analyzer = crate::analyzers::pool::checkout(Language::Python).unwrap().unwrap();
+        analyzer.set_parse_timeout(Some(std::time::Duration::from_millis(1)));
+        let large = "def f(a):\n    if a:\n        return [x * 2 for x in (a, a + 1)]\n    return None\n".repeat(20_000);
+        assert!(analyzer.analyze(&large, "large.py").await.unwrap_err().to_string().contains("timed out"));
+        analyzer.set_parse_timeout(None);
+        let result = analyzer.analyze("def ok():\n    return 1\n", "small.py").await.unwrap();
+        assert_eq!(result.functions.len(), 1);
+        assert_eq!(result.functions[0].name, "ok");
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_hidden_directories_and_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("app.js"), "function app() {}\n").unwrap();
+        std::fs::create_dir(dir.path().join(".venv")).unwrap();
+        std::fs::write(dir.path().join(".venv").join("vendored.js"), "function v() {}\n").unwrap();
+        std::fs::write(outside.path().join("built.js"), "function built() {}\n").unwrap();
+        std::os::unix::fs::symlink(outside.path(), dir.path().join("out-link")).unwrap();
+        // A link back to the root must not loop forever
+        std::os::unix::fs::symlink(dir.path(), dir.path().join("self")).unwrap();
+        
+        let names = |config: AnalysisConfig| {
+            let mut names: Vec<String> = AnalysisSession::with_config(config).discover_files(dir.path()).unwrap().iter()
+                .map(|path| path.strip_prefix(dir.path()).unwrap().to_string_lossy().to_string())
+                .collect();
+            names.sort();
+            names
+        };
+        assert_eq!(names(AnalysisConfig::default()), vec!["app.js"]);
+        
+        let mut config = AnalysisConfig::default();
+        config.include_hidden = true;
+        assert_eq!(names(config.clone()), vec![".venv/vendored.js", "app.js"]);
+        
+        config.include_hidden = false;
+        config.follow_symlinks = true;
+        assert_eq!(names(config), vec!["app.js", "out-link/built.js"]);
+    }
+    
+    #[tokio::test]
+    async fn test_session_metrics_formats() {
+        let source = "# Shapes\nclass Square:\n    def area(self):\n        return 4\n\ndef unit():\n    return Square()\n";
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("shapes.py");
+        std::fs::write(&file, source).unwrap();
+        let result = AnalysisSession::new().analyze_file(&file).await.unwrap();
+        
+        let manager = SessionManager::with_dir(dir.path().to_path_buf()).unwrap();
+        let session_info = session_info(dir.path(), vec![result]);
+        
+        let native = manager.calculate_session_metrics(&session_info, "json").unwrap();
+        assert_eq!(native["metrics"]["files"], 1);
+        assert_eq!(native["metrics"]["classes"], 1);
+        assert_eq!(native["metrics"]["comment_lines"], 1);
+        assert!(native["metrics"]["max_complexity"].as_u64().unwrap() >= 1);
+        
+        let sonar = manager.calculate_session_metrics(&session_info, "sonar").unwrap();
+        let measures = sonar["component"]["measures"].as_array().unwrap();
+        assert!(measures.iter().any(|m| m["metric"] == "ncloc" && m["value"] == native["metrics"]["code_lines"].to_string()));
+        
+        let codeclimate = manager.calculate_session_metrics(&session_info, "codeclimate").unwrap();
+        assert!(codeclimate.as_array().unwrap().iter().all(|m| m["type"] == "measurement"));
+        
+        assert!(manager.calculate_session_metrics(&session_info, "xml").is_err());
+    }
+    
+    #[test]
+    fn test_snippet_context_and_truncation() {
+        let content = "one\ntwo\nthree\nfour\nfive\n";
+        let options = SnippetOptions { context: 1, max_bytes: DEFAULT_SNIPPET_MAX_BYTES };
+        
+        let snippet = options.extract(content, 3, 3);
+        assert_eq!(snippet["text"], "two\nthree\nfour");
+        assert_eq!(snippet["start_line"], 2);
+        assert_eq!(snippet["truncated"], false);
+        
+        // Context is clamped to the file
+        let snippet = SnippetOptions { context: 10, ..options }.extract(content, 1, 2);
+        assert_eq!((snippet["start_line"].as_u64(), snippet["end_line"].as_u64()), (Some(1), Some(5)));
+        
+        let snippet = SnippetOptions { context: 0, max_bytes: 5 }.extract("héllo wörld", 1, 1);
+        assert_eq!(snippet["text"], "héll");
+        assert_eq!(snippet["truncated"], true);
+    }
+    
+    #[tokio::test]
+    async fn test_analyze_files_from_list() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("app.py");
+        std::fs::write(&source, "def main():\n    pass\n").unwrap();
+        let readme = dir.path().join("README.md");
+        std::fs::write(&readme, "# app\n").unwrap();
+        let deleted = dir.path().join("gone.py");
+        
+        let session = AnalysisSession::new();
+        let analysis = session.analyze_files(&[source.clone(), readme, deleted.clone()]).await.unwrap();
+        assert_eq!(analysis.files.len(), 1);
+        assert_eq!(analysis.files[0].file_info.path, source);
+        assert_eq!(analysis.summary.total_functions, 1);
+        assert_eq!(analysis.errors.len(), 1);
+        assert_eq!(analysis.errors[0].path, deleted);
+    }
+    
+    #[tokio::test]
+    async fn test_analyze_path_streaming() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.py"), "def a():\n    pass\n").unwrap();
+        std::fs::write(dir.path().join("b.py"), "def b():\n    pass\n\ndef c():\n    pass\n").unwrap();
+        
+        let mut streamed = Vec::new();
+        let analysis = AnalysisSession::new()
+            .analyze_path_streaming(dir.path(), false, |result| {
+                streamed.push(result.file_info.name);
+                Ok(())
+            })
+            .await
+            .unwrap();
+        streamed.sort();
+        assert_eq!(streamed, vec!["a.py", "b.py"]);
+        assert!(analysis.files.is_empty());
+        assert_eq!(analysis.summary.total_files, 2);
+        assert_eq!(analysis.summary.total_functions, 3);
+        
+        // A failing consumer stops the analysis
+        let err = AnalysisSession::new()
+            .analyze_path_streaming(dir.path(), false, |_| anyhow::bail!("consumer gone"))
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "consumer gone");
+    }
+    
+    #[test]
+    fn test_incremental_ast_stats_match_a_full_recompute() {
+        let file = |name: &str, nodes: &[(ASTNodeType, u32)]| {
+            let mut root = ASTNode::new(ASTNodeType::FileRoot, String::new());
+            for (node_type, count) in nodes {
+                for i in 0..*count {
+                    root.add_child(ASTNode::new(node_type.clone(), format!("n{}", i)));
+                }
+            }
+            let mut stats = ASTStatistics::default();
+            stats.update_from_root(&root);
+            let mut result = AnalysisResult::new(FileInfo::new(PathBuf::from(name)), Language::Python);
+            result.ast_statistics = Some(stats);
+            result
+        };
+        let old = vec![
+            file("a.py", &[(ASTNodeType::Class, 2), (ASTNodeType::Function, 1)]),
+            file("b.py", &[(ASTNodeType::Function, 3)]),
+            file("c.py", &[(ASTNodeType::Variable, 4)]),
+        ];
+        let mut combined = SessionManager::calculate_combined_ast_stats(&old);
+        
+        // a.py changes, c.py is deleted, d.py is added
+        let mut results = old.clone();
+        let modified = file("a.py", &[(ASTNodeType::Function, 2)]);
+        let added = file("d.py", &[(ASTNodeType::Method, 1)]);
+        SessionManager::replace_ast_stats(&mut combined, Some(&results[0]), Some(&modified));
+        SessionManager::replace_ast_stats(&mut combined, Some(&results[2]), None);
+        SessionManager::replace_ast_stats(&mut combined, None, Some(&added));
+        results = vec![modified, results[1].clone(), added];
+        SessionManager::settle_ast_stats(&mut combined, &results);
+        assert_eq!(combined, SessionManager::calculate_combined_ast_stats(&results));
+        
+        // Deleting every file leaves no statistics
+        for result in &results {
+            SessionManager::replace_ast_stats(&mut combined, Some(result), None);
+        }
+        SessionManager::settle_ast_stats(&mut combined, &[]);
+        assert_eq!(combined, None);
+    }
+    
+    #[tokio::test]
+    async fn test_analyze_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = [("project/src/app.py", "def main():\n    pass\n"), ("project/src/notes.txt", "not code\n")];
+        
+        let archive = dir.path().join("project.tar.gz");
+        let encoder = flate2::write::GzEncoder::new(std::fs::File::create(&archive).unwrap(), flate2::Compression::default());
+        let mut tar = tar::Builder::new(encoder);
+        for (path, content) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            tar.append_data(&mut header, path, content.as_bytes()).unwrap();
+        }
+        tar.into_inner().unwrap().finish().unwrap();
+        
+        let zipped = dir.path().join("project.zip");
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&zipped).unwrap());
+        for (path, content) in files {
+            zip.start_file(path, zip::write::SimpleFileOptions::default()).unwrap();
+            std::io::Write::write_all(&mut zip, content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+        
+        assert!(is_archive(&archive) && is_archive(Path::new("SRC.ZIP")) && !is_archive(Path::new("app.py")));
+        for archive in [&archive, &zipped] {
+            let analysis = AnalysisSession::new().analyze_path(archive, false).await.unwrap();
+            assert_eq!(&analysis.directory_path, archive);
+            assert_eq!(analysis.files.len(), 1);
+            assert_eq!(analysis.files[0].file_info.path, Path::new("project").join("src").join("app.py"));
+            assert_eq!(analysis.summary.total_functions, 1);
+            
+            // Entries the filter rejects are not written at all
+            let extracted = ExtractedArchive::unpack(archive, |path| path.extension().is_some_and(|e| e == "py")).unwrap();
+            assert!(extracted.dir.join("project/src/app.py").is_file());
+            assert!(!extracted.dir.join("project/src/notes.txt").exists());
+        }
+        
+        let broken = dir.path().join("broken.tar");
+        std::fs::write(&broken, "not an archive").unwrap();
+        assert!(AnalysisSession::new().analyze_path(&broken, false).await.is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_python_package_imports_in_include_cycles() {
+        let dir = tempfile::tempdir().unwrap();
+        let pkg = dir.path().join("pkg");
+        std::fs::create_dir(&pkg).unwrap();
+        std::fs::write(pkg.join("__init__.py"), "from .core import run\n").unwrap();
+        std::fs::write(pkg.join("core.py"), "from . import helpers\n\ndef run():\n    pass\n").unwrap();
+        std::fs::write(pkg.join("helpers.py"), "from pkg import core\n").unwrap();
+        std::fs::write(dir.path().join("main.py"), "import pkg\n").unwrap();
+        
+        let analysis = AnalysisSession::new().analyze_path(dir.path(), false).await.unwrap();
+        let manager = SessionManager::with_dir(dir.path().to_path_buf()).unwrap();
+        let mut session_info = SessionInfo {
+            metadata: HashMap::from([(PATHS_METADATA_KEY.to_string(), "relative".to_string())]),
+            ..session_info(dir.path(), analysis.files)
+        };
+        for result in &mut session_info.analysis_results {
+            result.file_info.path = relative_to_root(&result.file_info.path, dir.path());
+        }
+        
+        let report = manager.find_session_include_cycles(&session_info).unwrap();
+        let path = |p: &str| Path::new(p).to_string_lossy().to_string();
+        let deps = |file: &str| -> Vec<String> {
+            serde_json::from_value(report["dependency_graph"][path(file)].clone()).unwrap()
+        };
+        assert_eq!(deps("main.py"), vec![path("pkg/__init__.py")]);
+        assert_eq!(deps("pkg/__init__.py"), vec![path("pkg/core.py")]);
+        assert_eq!(deps("pkg/core.py"), vec![path("pkg/helpers.py")]);
+        assert_eq!(deps("pkg/helpers.py"), vec![path("pkg/core.py")]);
+        
+        assert_eq!(report["cycles_found"], 1);
+        let cycle: Vec<String> = serde_json::from_value(report["cycles"][0].clone()).unwrap();
+        assert!(cycle.contains(&path("pkg/core.py")) && cycle.contains(&path("pkg/helpers.py")));
+    }
+    
+    #[test]
+    fn test_prune_by_age_and_count() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut manager = SessionManager::with_dir(dir.path().to_path_buf()).unwrap();
+        for (id, days_ago) in [("fresh", 0), ("week", 7), ("stale", 30)] {
+            let info = SessionInfo {
+                id: id.to_string(),
+                last_accessed: Utc::now() - chrono::Duration::days(days_ago),
+                ..session_info(dir.path(), Vec::new())
+            };
+            manager.save_session_info(&info).unwrap();
+            manager.session_info.insert(id.to_string(), info);
+        }
+        std::fs::write(dir.path().join("stale.watch.log"), "{}\n").unwrap();
+        let stale_undo = undo_dir(dir.path(), Some("stale"));
+        std::fs::create_dir_all(&stale_undo).unwrap();
+        std::fs::write(stale_undo.join("preview_1.json"), "[]").unwrap();
+        
+        let ids = |report: &PruneReport| report.removed.iter().map(|s| s.id.clone()).collect::<Vec<_>>();
+        let preview = manager.prune(None, Some(1), true).unwrap();
+        assert_eq!(ids(&preview), vec!["week", "stale"]);
+        assert!(preview.freed_bytes > 0);
+        assert!(dir.path().join("stale.json").exists());
+        
+        let report = manager.prune(Some(10), None, false).unwrap();
+        assert_eq!(ids(&report), vec!["stale"]);
+        assert_eq!(report.kept, 2);
+        assert!(!dir.path().join("stale.json").exists());
+        assert!(!dir.path().join("stale.watch.log").exists());
+        assert!(!stale_undo.exists());
+        assert!(dir.path().join("week.json").exists());
+        
+        assert!(manager.prune(None, None, false).is_err());
+        assert!(manager.delete_session("stale").is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_session_paths_are_relative_to_the_root() {
+        let project = tempfile::tempdir().unwrap();
+        let sessions = tempfile::tempdir().unwrap();
+        std::fs::create_dir(project.path().join("lib")).unwrap();
+        std::fs::write(project.path().join("lib").join("app.py"), "def run():\n    pass  # TODO: retry\n").unwrap();
+        let mut manager = SessionManager::with_dir(sessions.path().to_path_buf()).unwrap();
+        
+        let id = manager.create_session(project.path()).await.unwrap();
+        let stored = manager.get_session_info(&id).unwrap().analysis_results[0].file_info.path.clone();
+        assert_eq!(stored, Path::new("lib").join("app.py"));
+        // Commands that read the sources resolve them against the root
+        let annotations = manager.execute_session_command(&id, "annotations", &[]).unwrap();
+        assert!(annotations.contains("retry"), "{}", annotations);
+        manager.set_annotation_tags(vec!["FIXME".to_string()]);
+        let annotations = manager.execute_session_command(&id, "annotations", &[]).unwrap();
+        assert!(!annotations.contains("retry"), "{}", annotations);
+        
+        let id = manager.create_session_with_paths(project.path(), true).await.unwrap();
+        let info = manager.get_session_info(&id).unwrap();
+        assert_eq!(info.analysis_results[0].file_info.path, project.path().join("lib").join("app.py").canonicalize().unwrap());
+        assert_eq!(info.relative_path(&info.analysis_results[0].file_info.path), Path::new("lib").join("app.py"));
+        
+        // Sessions saved before normalization are converted when loaded
+        let mut legacy = manager.get_session_info(&id).unwrap().clone();
+        legacy.id = "legacy".to_string();
+        legacy.path = project.path().to_path_buf();
+        legacy.metadata.clear();
+        legacy.analysis_results[0].file_info.path = project.path().join("lib").join("app.py");
+        manager.save_session_info(&legacy).unwrap();
+        let loaded = manager.load_session_info("legacy").unwrap();
+        assert_eq!(loaded.analysis_results[0].file_info.path, Path::new("lib").join("app.py"));
+        assert!(!loaded.stores_absolute_paths());
+    }
+}
@@ -0,0 +1,82 @@
+//! Whitespace consistency across files
+//!
+//! Groups files by the indentation and line endings `FileStyle::detect`
+//! found, so the few files that differ from the rest of a project stand out,
+//! and lists the files with trailing whitespace. Works from text alone, so
+//! every language is covered.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::core::types::FileStyle;
+
+/// Files grouped by whitespace style, for the `style` session command
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct StyleReport {
+    /// `spaces:4`, `tabs`, `mixed` or `none` → files
+    pub indentation: BTreeMap<String, Vec<PathBuf>>,
+    /// `lf`, `crlf`, `mixed` or `none` → files
+    pub line_endings: BTreeMap<String, Vec<PathBuf>>,
+    /// Files with trailing whitespace, most affected lines first
+    pub trailing_whitespace: Vec<TrailingWhitespace>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TrailingWhitespace {
+    pub file: PathBuf,
+    pub lines: u32,
+}
+
+/// Group `files` by indentation and line endings
+pub fn style_report<'a>(files: impl IntoIterator<Item = (&'a Path, &'a FileStyle)>) -> StyleReport {
+    let mut report = StyleReport::default();
+
+    for (file, style) in files {
+        report.indentation.entry(style.indent_key()).or_default().push(file.to_path_buf());
+        report.line_endings.entry(style.line_endings.clone()).or_default().push(file.to_path_buf());
+        if style.trailing_whitespace_lines > 0 {
+            report.trailing_whitespace.push(TrailingWhitespace {
+                file: file.to_path_buf(),
+                lines: style.trailing_whitespace_lines,
+            });
+        }
+    }
+
+    for files in report.indentation.values_mut().chain(report.line_endings.values_mut()) {
+        files.sort();
+    }
+    report.trailing_whitespace.sort_by(|a, b| b.lines.cmp(&a.lines).then(a.file.cmp(&b.file)));
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_and_group_styles() {
+        let spaces = FileStyle::detect("class A:\n    def f(self):\n        pass  \n\n    x = 1\n");
+        assert_eq!((spaces.indent.as_str(), spaces.indent_width, spaces.line_endings.as_str()), ("spaces", 4, "lf"));
+        assert_eq!(spaces.trailing_whitespace_lines, 1);
+
+        // ` * ` comment continuations do not make a tab-indented file mixed
+        let tabs = FileStyle::detect("/**\r\n * Doc\r\n */\r\nint f() {\r\n\treturn 1;\r\n}\r\n");
+        assert_eq!((tabs.indent_key(), tabs.line_endings.as_str()), ("tabs".to_string(), "crlf"));
+        let mixed = FileStyle::detect("a\n\tb\r\n  c");
+        assert_eq!((mixed.indent_key(), mixed.line_endings.as_str()), ("mixed:2".to_string(), "mixed"));
+        assert_eq!(FileStyle::detect("x = 1").line_endings, "none");
+
+        let styles = [
+            (PathBuf::from("b.py"), spaces.clone()),
+            (PathBuf::from("a.py"), spaces),
+            (PathBuf::from("main.c"), tabs),
+        ];
+        let report = style_report(styles.iter().map(|(file, style)| (file.as_path(), style)));
+        assert_eq!(report.indentation["spaces:4"], vec![PathBuf::from("a.py"), PathBuf::from("b.py")]);
+        assert_eq!(report.indentation["tabs"], vec![PathBuf::from("main.c")]);
+        assert_eq!(report.line_endings["crlf"], vec![PathBuf::from("main.c")]);
+        assert_eq!(report.trailing_whitespace.len(), 2);
+        assert_eq!(report.trailing_whitespace[0].file, PathBuf::from("a.py"));
+    }
+}
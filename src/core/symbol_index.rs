@@ -0,0 +1,199 @@
+//! Name index over analysis results
+//!
+//! Built once per set of results so repeated lookups are hash lookups rather
+//! than scans of every file. Definitions (classes, methods, functions) keep
+//! the order a file-by-file scan finds them in: per file the classes, each
+//! followed by its methods, then the functions. Methods that analyzers also
+//! report as functions are indexed once, as methods. Mentions record which
+//! files call, import, export or define a name, for reference searches that
+//! only need to look at those files.
+
+use std::collections::HashMap;
+
+use crate::core::types::AnalysisResult;
+
+/// A class, method or function definition
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymbolEntry {
+    pub name: String,
+    /// `class`, `method` or `function`
+    pub kind: &'static str,
+    /// Position of the defining file in the indexed results
+    pub file: usize,
+    pub line_start: u32,
+    pub line_end: u32,
+    /// Class of a method, `global` otherwise
+    pub scope: String,
+    pub parameters: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SymbolIndex {
+    /// Definitions in scan order
+    definitions: Vec<SymbolEntry>,
+    /// Name → positions in `definitions`, ascending
+    by_name: HashMap<String, Vec<usize>>,
+    /// Name → files that call, import, export or define it, ascending
+    mentions: HashMap<String, Vec<usize>>,
+}
+
+impl SymbolIndex {
+    pub fn build(results: &[AnalysisResult]) -> Self {
+        let mut index = Self::default();
+
+        for (file, result) in results.iter().enumerate() {
+            for class in &result.classes {
+                index.define(SymbolEntry {
+                    name: class.name.clone(),
+                    kind: "class",
+                    file,
+                    line_start: class.start_line,
+                    line_end: class.end_line,
+                    scope: "global".to_string(),
+                    parameters: Vec::new(),
+                });
+                for method in &class.methods {
+                    index.define(SymbolEntry {
+                        name: method.name.clone(),
+                        kind: "method",
+                        file,
+                        line_start: method.start_line,
+                        line_end: method.end_line,
+                        scope: class.name.clone(),
+                        parameters: method.parameters.clone(),
+                    });
+                }
+            }
+            for function in &result.functions {
+                index.mention(&function.name, file);
+            }
+            for function in result.free_functions() {
+                index.define(SymbolEntry {
+                    name: function.name.clone(),
+                    kind: "function",
+                    file,
+                    line_start: function.start_line,
+                    line_end: function.end_line,
+                    scope: "global".to_string(),
+                    parameters: function.parameters.clone(),
+                });
+            }
+
+            // Calls are mentioned by their bare name: `Namespace::f`, `obj.f` → `f`
+            for call in &result.function_calls {
+                let name = call.function_name.rsplit_once("::")
+                    .or_else(|| call.function_name.rsplit_once('.'))
+                    .map_or(call.function_name.as_str(), |(_, name)| name);
+                index.mention(name, file);
+            }
+            for name in result.imports.iter().flat_map(|import| &import.imported_names) {
+                index.mention(name, file);
+            }
+            for name in result.exports.iter().flat_map(|export| &export.exported_names) {
+                index.mention(name, file);
+            }
+        }
+
+        index
+    }
+
+    fn define(&mut self, entry: SymbolEntry) {
+        self.mention(&entry.name, entry.file);
+        self.by_name.entry(entry.name.clone()).or_default().push(self.definitions.len());
+        self.definitions.push(entry);
+    }
+
+    fn mention(&mut self, name: &str, file: usize) {
+        let files = self.mentions.entry(name.to_string()).or_default();
+        // Files are indexed in order, so a repeat is always the last entry
+        if files.last() != Some(&file) {
+            files.push(file);
+        }
+    }
+
+    /// Definitions named exactly `name`, in scan order
+    pub fn definitions_named(&self, name: &str) -> Vec<&SymbolEntry> {
+        self.by_name.get(name)
+            .map(|positions| positions.iter().map(|&i| &self.definitions[i]).collect())
+            .unwrap_or_default()
+    }
+
+    /// Definitions whose name satisfies `matches`, in scan order; each
+    /// distinct name is tested once
+    pub fn definitions_matching(&self, matches: impl Fn(&str) -> bool) -> Vec<&SymbolEntry> {
+        let mut positions: Vec<usize> = self.by_name.iter()
+            .filter(|(name, _)| matches(name))
+            .flat_map(|(_, positions)| positions.iter().copied())
+            .collect();
+        positions.sort_unstable();
+        positions.into_iter().map(|i| &self.definitions[i]).collect()
+    }
+
+    /// Files that call, import, export or define `name`, in result order
+    pub fn files_mentioning(&self, name: &str) -> &[usize] {
+        self.mentions.get(name).map(Vec::as_slice).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::{ClassInfo, FileInfo, FunctionCall, ImportInfo, ImportType, Language};
+    use crate::core::types::test_support::function;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_index_matches_a_full_scan() {
+        let mut models = AnalysisResult::new(FileInfo::new(PathBuf::from("models.py")), Language::Python);
+        let mut user = ClassInfo::new("User".to_string());
+        user.start_line = 1;
+        user.methods = vec![function("save", 2, 4), function("load", 5, 7)];
+        models.classes.push(user);
+        models.functions = vec![function("save", 2, 4), function("load", 5, 7), function("helper", 10, 12)];
+
+        let mut app = AnalysisResult::new(FileInfo::new(PathBuf::from("app.py")), Language::Python);
+        app.functions = vec![function("main", 1, 3), function("save", 8, 10)];
+        app.function_calls = vec![FunctionCall::new("db.helper".to_string(), 2)];
+        app.imports.push(ImportInfo {
+            import_type: ImportType::PythonFromImport,
+            module_path: "models".to_string(),
+            imported_names: vec!["User".to_string()],
+            alias: None,
+            line_number: 1,
+            metadata: Default::default(),
+        });
+        let results = [models, app];
+        let index = SymbolIndex::build(&results);
+
+        // Brute force: every definition, in file order, methods listed once
+        let mut scanned = Vec::new();
+        for (file, result) in results.iter().enumerate() {
+            for class in &result.classes {
+                scanned.push((class.name.as_str(), "class", file, class.start_line));
+                scanned.extend(class.methods.iter().map(|m| (m.name.as_str(), "method", file, m.start_line)));
+            }
+            for function in &result.functions {
+                if !scanned.contains(&(function.name.as_str(), "method", file, function.start_line)) {
+                    scanned.push((function.name.as_str(), "function", file, function.start_line));
+                }
+            }
+        }
+        let indexed = |entries: Vec<&SymbolEntry>| -> Vec<(String, &str, usize, u32)> {
+            entries.into_iter().map(|e| (e.name.clone(), e.kind, e.file, e.line_start)).collect()
+        };
+        let owned = |entries: Vec<&(&str, &'static str, usize, u32)>| -> Vec<(String, &str, usize, u32)> {
+            entries.into_iter().map(|&(name, kind, file, line)| (name.to_string(), kind, file, line)).collect()
+        };
+
+        assert_eq!(indexed(index.definitions_matching(|_| true)), owned(scanned.iter().collect()));
+        for name in ["User", "save", "load", "helper", "main", "missing"] {
+            assert_eq!(indexed(index.definitions_named(name)), owned(scanned.iter().filter(|s| s.0 == name).collect()));
+        }
+        assert_eq!(indexed(index.definitions_matching(|name| name.contains('l'))).len(), 2);
+
+        assert_eq!(index.files_mentioning("helper"), &[0, 1]);
+        assert_eq!(index.files_mentioning("User"), &[0, 1]);
+        assert_eq!(index.files_mentioning("main"), &[1]);
+        assert!(index.files_mentioning("missing").is_empty());
+    }
+}
@@ -4,14 +4,15 @@
 //! the analysis system, ported from the C++ types.hpp file.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::PathBuf;
+use schemars::JsonSchema;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use chrono::{DateTime, Utc};
 
 use crate::core::ast::{ASTNode, ASTStatistics};
 
 /// Supported programming languages
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 pub enum Language {
     #[serde(rename = "javascript")]
     JavaScript,
@@ -29,6 +30,10 @@ pub enum Language {
     Go,
     #[serde(rename = "rust")]
     Rust,
+    #[serde(rename = "java")]
+    Java,
+    #[serde(rename = "kotlin")]
+    Kotlin,
     #[serde(rename = "unknown")]
     Unknown,
 }
@@ -44,13 +49,143 @@ impl Language {
             ".cs" => Language::CSharp,
             ".go" => Language::Go,
             ".rs" => Language::Rust,
+            ".java" => Language::Java,
+            ".kt" | ".kts" => Language::Kotlin,
             _ => Language::Unknown,
         }
     }
+    
+    /// Detect the language of a file from its extension, falling back to its
+    /// content: a `#!` line for extensionless files, and C++-only constructs
+    /// to tell C++ headers from C headers for `.h`.
+    pub fn detect(path: &Path, content: &str) -> Self {
+        let Some(extension) = path.extension().and_then(|e| e.to_str()) else {
+            return content.lines().next()
+                .and_then(Self::from_shebang)
+                .unwrap_or(Language::Unknown);
+        };
+        
+        match Self::from_extension(&format!(".{}", extension)) {
+            Language::C if extension.eq_ignore_ascii_case("h") && Self::looks_like_cpp(content) => Language::Cpp,
+            language => language,
+        }
+    }
+    
+    /// Language of the interpreter named by a shebang line
+    /// (`#!/usr/bin/env python3`, `#!/usr/local/bin/node`, ...)
+    pub fn from_shebang(line: &str) -> Option<Self> {
+        let command = line.strip_prefix("#!")?;
+        let mut words = command.split_whitespace();
+        let mut program = words.next()?.rsplit('/').next()?;
+        if program == "env" {
+            // Skip `env` options such as `-S`
+            program = words.find(|word| !word.starts_with('-'))?;
+        }
+        
+        let name = program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+        match name {
+            "python" | "pypy" => Some(Language::Python),
+            "node" | "nodejs" => Some(Language::JavaScript),
+            "deno" | "ts-node" | "tsx" | "bun" => Some(Language::TypeScript),
+            "rust-script" => Some(Language::Rust),
+            "kotlin" | "kscript" => Some(Language::Kotlin),
+            _ => None,
+        }
+    }
+    
+    /// Whether header content uses constructs that only exist in C++
+    fn looks_like_cpp(content: &str) -> bool {
+        content.lines().any(|line| {
+            let line = line.trim_start();
+            ["class ", "namespace ", "namespace{", "template<", "template ", "using namespace ", "public:", "private:", "protected:"]
+                .iter()
+                .any(|token| line.starts_with(token))
+                || line.contains("std::")
+        })
+    }
+    
+    /// Parse a language name as given on the command line (`rust`, `ts`, `c#`, ...)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "javascript" | "js" => Some(Language::JavaScript),
+            "typescript" | "ts" => Some(Language::TypeScript),
+            "cpp" | "c++" | "cxx" => Some(Language::Cpp),
+            "c" => Some(Language::C),
+            "python" | "py" => Some(Language::Python),
+            "csharp" | "c#" | "cs" => Some(Language::CSharp),
+            "go" | "golang" => Some(Language::Go),
+            "rust" | "rs" => Some(Language::Rust),
+            "java" => Some(Language::Java),
+            "kotlin" | "kt" => Some(Language::Kotlin),
+            _ => None,
+        }
+    }
+    
+    /// How comments are written in this language. Files of unknown type get
+    /// both `//` and `#` line comments, which covers shell, Ruby and YAML.
+    pub fn comment_syntax(&self) -> CommentSyntax {
+        const C_BLOCK: &[(&str, &str)] = &[("/*", "*/")];
+        match self {
+            Language::Python => CommentSyntax {
+                line: &["#"],
+                block: &[],
+                multiline_strings: &["\"\"\"", "'''"],
+                docstrings: true,
+            },
+            Language::JavaScript | Language::TypeScript | Language::Go => CommentSyntax {
+                line: &["//"],
+                block: C_BLOCK,
+                multiline_strings: &["`"],
+                docstrings: false,
+            },
+            Language::Java | Language::Kotlin => CommentSyntax {
+                line: &["//"],
+                block: C_BLOCK,
+                multiline_strings: &["\"\"\""],
+                docstrings: false,
+            },
+            Language::Cpp | Language::C | Language::CSharp | Language::Rust => CommentSyntax {
+                line: &["//"],
+                block: C_BLOCK,
+                multiline_strings: &[],
+                docstrings: false,
+            },
+            Language::Unknown => CommentSyntax {
+                line: &["//", "#"],
+                block: C_BLOCK,
+                multiline_strings: &[],
+                docstrings: false,
+            },
+        }
+    }
+}
+
+/// Comment and string delimiters of a language, for counting comment lines
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommentSyntax {
+    /// Prefixes that comment out the rest of the line
+    pub line: &'static [&'static str],
+    /// Block comment open and close delimiters
+    pub block: &'static [(&'static str, &'static str)],
+    /// String delimiters that may span lines and close with themselves
+    pub multiline_strings: &'static [&'static str],
+    /// A multiline string that starts a line is documentation (Python docstrings)
+    pub docstrings: bool,
+}
+
+impl CommentSyntax {
+    /// Byte offset where the first comment on each line starts, `None` for
+    /// lines without one. Block comments and docstrings are followed across
+    /// lines as in [`LineStats::from_lines`], and comment markers inside
+    /// string literals are skipped.
+    pub fn comment_starts<'a>(&self, lines: impl IntoIterator<Item = &'a str>) -> Vec<Option<usize>> {
+        let mut state = LineState::Code;
+        lines.into_iter().map(|line| state.scan(line, self).1).collect()
+    }
 }
 
 /// File information structure
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct FileInfo {
     pub name: String,
     pub path: PathBuf,
@@ -62,6 +197,10 @@ pub struct FileInfo {
     pub code_ratio: f64,
     pub analyzed_at: DateTime<Utc>,
     pub metadata: HashMap<String, String>,
+    /// Indentation, line endings and trailing whitespace; absent for files
+    /// analyzed before they were recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub style: Option<FileStyle>,
 }
 
 impl FileInfo {
@@ -80,12 +219,52 @@ impl FileInfo {
             code_ratio: 0.0,
             analyzed_at: Utc::now(),
             metadata: HashMap::new(),
+            style: None,
         }
     }
+    
+    /// Line counts and code ratio of `content`, with comments recognized the
+    /// way `language` writes them
+    pub fn set_line_stats(&mut self, content: &str, language: Language) {
+        let stats = LineStats::from_lines(content.lines(), language);
+        self.total_lines = content.lines().count() as u32;
+        self.code_lines = stats.code_lines;
+        self.comment_lines = stats.comment_lines;
+        self.empty_lines = stats.empty_lines;
+        self.code_ratio = if self.total_lines > 0 {
+            self.code_lines as f64 / self.total_lines as f64
+        } else {
+            0.0
+        };
+    }
+}
+
+/// `path` relative to `root`, the analyzed directory or file. Paths that do
+/// not share a literal prefix with `root` (`a.rs` under `.`) are compared in
+/// canonical form; a path outside `root` is returned unchanged.
+pub fn relative_to_root(path: &Path, root: &Path) -> PathBuf {
+    let relative = match path.strip_prefix(root) {
+        Ok(relative) => relative.to_path_buf(),
+        Err(_) => match (path.canonicalize(), root.canonicalize()) {
+            (Ok(canonical), Ok(canonical_root)) => match canonical.strip_prefix(&canonical_root) {
+                Ok(relative) => relative.to_path_buf(),
+                Err(_) => return path.to_path_buf(),
+            },
+            _ => return path.to_path_buf(),
+        },
+    };
+    // `path` is the root itself
+    if relative.as_os_str().is_empty() {
+        return match path.file_name() {
+            Some(name) if path.is_file() => PathBuf::from(name),
+            _ => PathBuf::from("."),
+        };
+    }
+    relative
 }
 
 /// Complexity rating levels
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub enum ComplexityRating {
     #[serde(rename = "simple")]
     Simple,      // <= 10
@@ -98,7 +277,7 @@ pub enum ComplexityRating {
 }
 
 /// Complexity analysis information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ComplexityInfo {
     pub cyclomatic_complexity: u32,
     pub max_nesting_depth: u32,
@@ -154,7 +333,7 @@ impl Default for ComplexityInfo {
 }
 
 /// Function information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct FunctionInfo {
     pub name: String,
     pub start_line: u32,
@@ -164,6 +343,9 @@ pub struct FunctionInfo {
     pub is_arrow_function: bool,
     pub complexity: ComplexityInfo,
     pub metadata: HashMap<String, String>,
+    /// Line breakdown of the function body (`start_line..=end_line`)
+    #[serde(default)]
+    pub line_stats: LineStats,
 }
 
 impl FunctionInfo {
@@ -177,12 +359,233 @@ impl FunctionInfo {
             is_arrow_function: false,
             complexity: ComplexityInfo::new(),
             metadata: HashMap::new(),
+            line_stats: LineStats::default(),
+        }
+    }
+}
+
+/// Code, comment and blank line counts for a range of lines
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct LineStats {
+    pub code_lines: u32,
+    pub comment_lines: u32,
+    pub empty_lines: u32,
+}
+
+impl LineStats {
+    /// Classify each line as blank, comment or code. Block comments and
+    /// multiline strings are followed across lines, so the inside of a
+    /// `/* ... */` block or a docstring counts as comment and the inside of an
+    /// ordinary multiline string as code. A line with any code on it is code.
+    pub fn from_lines<'a>(lines: impl IntoIterator<Item = &'a str>, language: Language) -> Self {
+        let syntax = language.comment_syntax();
+        let mut stats = Self::default();
+        let mut state = LineState::Code;
+        for line in lines {
+            let (code, comment) = state.scan(line, &syntax);
+            if code {
+                stats.code_lines += 1;
+            } else if comment.is_some() {
+                stats.comment_lines += 1;
+            } else {
+                stats.empty_lines += 1;
+            }
+        }
+        stats
+    }
+    
+    /// Comment lines per non-blank line, 0.0 when there are none
+    pub fn comment_ratio(&self) -> f64 {
+        let non_blank = self.code_lines + self.comment_lines;
+        if non_blank == 0 {
+            0.0
+        } else {
+            self.comment_lines as f64 / non_blank as f64
+        }
+    }
+}
+
+/// Where a line starts: in code, or inside a construct opened on an earlier line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineState {
+    Code,
+    /// Inside a block comment, waiting for the close delimiter
+    BlockComment(&'static str),
+    /// Inside a multiline string; `true` for docstrings
+    String(&'static str, bool),
+}
+
+impl LineState {
+    /// Whether `line` holds code and the byte offset where its comment text
+    /// starts, if it has any, moving the state to where the next line starts
+    fn scan(&mut self, line: &str, syntax: &CommentSyntax) -> (bool, Option<usize>) {
+        let (mut code, mut comment) = (false, None);
+        // `rest` is always a suffix of the line without trailing whitespace
+        let line = line.trim_end();
+        let mut rest = line.trim_start();
+        while !rest.is_empty() {
+            match *self {
+                LineState::BlockComment(close) | LineState::String(close, _) => {
+                    match self {
+                        LineState::String(_, false) => code = true,
+                        _ => {
+                            comment.get_or_insert(line.len() - rest.len());
+                        }
+                    }
+                    match rest.find(close) {
+                        Some(end) => {
+                            rest = &rest[end + close.len()..];
+                            *self = LineState::Code;
+                        }
+                        None => break,
+                    }
+                }
+                LineState::Code => {
+                    rest = rest.trim_start();
+                    if rest.is_empty() {
+                        break;
+                    }
+                    if syntax.line.iter().any(|prefix| rest.starts_with(prefix)) {
+                        comment.get_or_insert(line.len() - rest.len());
+                        break;
+                    }
+                    if let Some((open, close)) = syntax.block.iter().find(|(open, _)| rest.starts_with(open)) {
+                        comment.get_or_insert(line.len() - rest.len());
+                        rest = &rest[open.len()..];
+                        *self = LineState::BlockComment(close);
+                        continue;
+                    }
+                    if let Some(delimiter) = syntax.multiline_strings.iter().find(|d| rest.starts_with(*d)) {
+                        let docstring = syntax.docstrings && !code;
+                        if docstring {
+                            comment.get_or_insert(line.len() - rest.len());
+                        } else {
+                            code = true;
+                        }
+                        rest = &rest[delimiter.len()..];
+                        *self = LineState::String(delimiter, docstring);
+                        continue;
+                    }
+                    code = true;
+                    rest = skip_token(rest);
+                }
+            }
+        }
+        (code, comment)
+    }
+}
+
+/// `rest` after its first character, or after a quoted string closed on the
+/// same line so comment markers inside strings are not mistaken for comments
+fn skip_token(rest: &str) -> &str {
+    let mut chars = rest.char_indices();
+    let Some((_, first)) = chars.next() else {
+        return rest;
+    };
+    if first == '"' || first == '\'' {
+        let mut escaped = false;
+        for (i, c) in chars {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                _ if c == first => return &rest[i + c.len_utf8()..],
+                _ => {}
+            }
+        }
+    }
+    // Unclosed quotes (Rust lifetimes, apostrophes) are a single character
+    &rest[first.len_utf8()..]
+}
+
+/// Whitespace conventions of a file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct FileStyle {
+    /// `tabs`, `spaces`, `mixed`, or `none` when no line is indented
+    pub indent: String,
+    /// Most common indentation step of space-indented lines; 0 without them
+    pub indent_width: u32,
+    /// `lf`, `crlf`, `mixed`, or `none` for a single unterminated line
+    pub line_endings: String,
+    /// Lines ending in spaces or tabs
+    pub trailing_whitespace_lines: u32,
+}
+
+impl FileStyle {
+    pub fn detect(content: &str) -> Self {
+        let crlf = content.matches("\r\n").count();
+        let lf = content.matches('\n').count() - crlf;
+        
+        let mut tab_lines = 0;
+        let mut space_lines = 0;
+        let mut trailing = 0;
+        // How often each step between an indented line and the one above occurs
+        let mut steps: HashMap<u32, u32> = HashMap::new();
+        let mut previous = 0;
+        for line in content.lines() {
+            if line.ends_with([' ', '\t']) {
+                trailing += 1;
+            }
+            if line.trim().is_empty() {
+                continue;
+            }
+            let spaces = line.len() - line.trim_start_matches(' ').len();
+            if line.starts_with('\t') {
+                tab_lines += 1;
+            } else if spaces > 1 {
+                // A single space is usually a ` * ` comment continuation, not indentation
+                space_lines += 1;
+            }
+            if !line.starts_with('\t') {
+                let spaces = spaces as u32;
+                if spaces > previous {
+                    *steps.entry(spaces - previous).or_insert(0) += 1;
+                }
+                previous = spaces;
+            }
+        }
+        
+        let indent = match (tab_lines > 0, space_lines > 0) {
+            (true, true) => "mixed",
+            (true, false) => "tabs",
+            (false, true) => "spaces",
+            (false, false) => "none",
+        };
+        let indent_width = if space_lines > 0 {
+            // Ties go to the smaller step
+            steps.into_iter()
+                .filter(|&(step, _)| step > 1)
+                .max_by_key(|&(step, count)| (count, std::cmp::Reverse(step)))
+                .map_or(0, |(step, _)| step)
+        } else {
+            0
+        };
+        let line_endings = match (lf > 0, crlf > 0) {
+            (true, true) => "mixed",
+            (true, false) => "lf",
+            (false, true) => "crlf",
+            (false, false) => "none",
+        };
+        
+        Self {
+            indent: indent.to_string(),
+            indent_width,
+            line_endings: line_endings.to_string(),
+            trailing_whitespace_lines: trailing,
+        }
+    }
+    
+    /// Group key such as `spaces:4` or `tabs`
+    pub fn indent_key(&self) -> String {
+        if self.indent_width > 0 && self.indent != "tabs" {
+            format!("{}:{}", self.indent, self.indent_width)
+        } else {
+            self.indent.clone()
         }
     }
 }
 
 /// Member variable information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct MemberVariable {
     pub name: String,
     pub var_type: String,
@@ -212,7 +615,7 @@ impl MemberVariable {
 }
 
 /// Class information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ClassInfo {
     pub name: String,
     pub parent_class: Option<String>,
@@ -240,7 +643,7 @@ impl ClassInfo {
 }
 
 /// Import types
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub enum ImportType {
     #[serde(rename = "es6_import")]
     ES6Import,      // import ... from
@@ -260,10 +663,14 @@ pub enum ImportType {
     GoImport,       // import "package"
     #[serde(rename = "rust_use")]
     RustUse,        // use crate::module
+    #[serde(rename = "java_import")]
+    JavaImport,     // import java.util.List
+    #[serde(rename = "kotlin_import")]
+    KotlinImport,   // import kotlinx.coroutines.launch
 }
 
 /// Export types  
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub enum ExportType {
     #[serde(rename = "es6_export")]
     ES6Export,      // export ...
@@ -281,10 +688,16 @@ pub enum ExportType {
     GoExported,     // Capitalized names (exported)
     #[serde(rename = "rust_pub")]
     RustPub,        // pub declarations
+    #[serde(rename = "rust_pub_crate")]
+    RustPubCrate,   // pub(crate) / pub(super) / pub(in path) declarations
+    #[serde(rename = "rust_reexport")]
+    RustReexport,   // pub use a::b
+    #[serde(rename = "ts_ambient_module")]
+    TsAmbientModule, // declare module 'name' { ... }
 }
 
 /// Import information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ImportInfo {
     #[serde(rename = "type")]
     pub import_type: ImportType,
@@ -309,13 +722,17 @@ impl ImportInfo {
 }
 
 /// Export information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ExportInfo {
     #[serde(rename = "type")]
     pub export_type: ExportType,
     pub exported_names: Vec<String>,
     pub is_default: bool,
     pub line_number: u32,
+    /// Module a re-export comes from (`export { x } from './y'`), or the
+    /// module an ambient declaration describes (`declare module 'y'`)
+    #[serde(default)]
+    pub source_module: Option<String>,
 }
 
 impl ExportInfo {
@@ -325,16 +742,22 @@ impl ExportInfo {
             exported_names: Vec::new(),
             is_default: false,
             line_number: 0,
+            source_module: None,
         }
     }
 }
 
 /// Function call information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct FunctionCall {
     pub function_name: String,
     pub object_name: Option<String>,
     pub line_number: u32,
+    /// 0-based columns of the call expression; 0 when the analyzer does not track them
+    #[serde(default)]
+    pub column_start: u32,
+    #[serde(default)]
+    pub column_end: u32,
     pub is_method_call: bool,
 }
 
@@ -344,6 +767,8 @@ impl FunctionCall {
             function_name,
             object_name: None,
             line_number,
+            column_start: 0,
+            column_end: 0,
             is_method_call: false,
         }
     }
@@ -362,7 +787,7 @@ impl FunctionCall {
 }
 
 /// Comment information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CommentInfo {
     pub line_start: u32,
     pub line_end: u32,
@@ -385,7 +810,7 @@ impl CommentInfo {
 }
 
 /// Analysis statistics
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Statistics {
     pub class_count: u32,
     pub function_count: u32,
@@ -411,7 +836,7 @@ impl Default for Statistics {
 }
 
 /// Complete analysis result
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AnalysisResult {
     // Basic information
     pub file_info: FileInfo,
@@ -479,19 +904,38 @@ impl AnalysisResult {
         self.stats.total_calls = self.function_calls.len() as u32;
         self.stats.commented_lines_count = self.commented_lines.len() as u32;
     }
+    
+    /// Functions that are not methods; some analyzers list methods under
+    /// their class and in `functions` as well
+    pub fn free_functions(&self) -> impl Iterator<Item = &FunctionInfo> {
+        let methods: HashSet<(&str, u32)> = self.classes.iter()
+            .flat_map(|class| class.methods.iter().map(|m| (m.name.as_str(), m.start_line)))
+            .collect();
+        self.functions.iter().filter(move |f| !methods.contains(&(f.name.as_str(), f.start_line)))
+    }
 }
 
 /// Directory analysis summary
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct DirectoryAnalysis {
     pub directory_path: PathBuf,
     pub files: Vec<AnalysisResult>,
     pub summary: DirectorySummary,
     pub generated_at: DateTime<Utc>,
+    /// Files that could not be analyzed; a non-empty list means a partial analysis
+    #[serde(default)]
+    pub errors: Vec<FileAnalysisError>,
+}
+
+/// A file that failed to analyze
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FileAnalysisError {
+    pub path: PathBuf,
+    pub message: String,
 }
 
 /// Directory analysis summary statistics
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct DirectorySummary {
     pub total_files: u32,
     pub total_lines: u32,
@@ -528,6 +972,34 @@ impl Default for DirectorySummary {
     }
 }
 
+impl DirectorySummary {
+    /// Count one more analyzed file
+    pub fn add_file(&mut self, file: &AnalysisResult) {
+        self.total_files += 1;
+        self.total_lines += file.file_info.total_lines;
+        self.total_size += file.file_info.size_bytes;
+        self.total_classes += file.stats.class_count;
+        self.total_functions += file.stats.function_count;
+        
+        if file.file_info.total_lines > 500 {
+            self.large_files += 1;
+        }
+        
+        if matches!(file.complexity.rating, ComplexityRating::Complex | ComplexityRating::VeryComplex) {
+            self.complex_files += 1;
+        }
+        
+        self.total_complexity += file.complexity.cyclomatic_complexity;
+        
+        if file.complexity.cyclomatic_complexity > self.max_complexity {
+            self.max_complexity = file.complexity.cyclomatic_complexity;
+            self.most_complex_file = file.file_info.name.clone();
+        }
+        
+        self.average_complexity = self.total_complexity as f64 / self.total_files as f64;
+    }
+}
+
 impl DirectoryAnalysis {
     pub fn new(directory_path: PathBuf) -> Self {
         Self {
@@ -535,41 +1007,26 @@ impl DirectoryAnalysis {
             files: Vec::new(),
             summary: DirectorySummary::default(),
             generated_at: Utc::now(),
+            errors: Vec::new(),
         }
     }
     
+    /// Record a per-file failure, replacing any earlier one for the same path
+    pub fn record_error(&mut self, path: PathBuf, error: &anyhow::Error) {
+        self.clear_error(&path);
+        self.errors.push(FileAnalysisError { path, message: format!("{:#}", error) });
+    }
+    
+    /// Forget a previously recorded failure, e.g. after the file was fixed
+    pub fn clear_error(&mut self, path: &Path) {
+        self.errors.retain(|e| e.path != path);
+    }
+    
     pub fn update_summary(&mut self) {
         let mut summary = DirectorySummary::default();
-        summary.total_files = self.files.len() as u32;
-        
         for file in &self.files {
-            summary.total_lines += file.file_info.total_lines;
-            summary.total_size += file.file_info.size_bytes;
-            summary.total_classes += file.stats.class_count;
-            summary.total_functions += file.stats.function_count;
-            
-            if file.file_info.total_lines > 500 {
-                summary.large_files += 1;
-            }
-            
-            if matches!(file.complexity.rating, ComplexityRating::Complex | ComplexityRating::VeryComplex) {
-                summary.complex_files += 1;
-            }
-            
-            summary.total_complexity += file.complexity.cyclomatic_complexity;
-            
-            if file.complexity.cyclomatic_complexity > summary.max_complexity {
-                summary.max_complexity = file.complexity.cyclomatic_complexity;
-                summary.most_complex_file = file.file_info.name.clone();
-            }
+            summary.add_file(file);
         }
-        
-        summary.average_complexity = if summary.total_files > 0 {
-            summary.total_complexity as f64 / summary.total_files as f64
-        } else {
-            0.0
-        };
-        
         self.summary = summary;
     }
 }
@@ -590,6 +1047,25 @@ pub struct AnalysisConfig {
     pub include_line_numbers: bool,
     /// 🚀 Parser type: "pest" (default) or "tree-sitter" (100x faster!)
     pub parser_type: String,
+    /// Abort on the first file that fails to analyze instead of recording it
+    #[serde(default)]
+    pub fail_on_error: bool,
+    /// Analyze files as this language instead of detecting it
+    #[serde(default)]
+    pub language_override: Option<Language>,
+    /// Give up on a file whose parse takes longer than this and record it
+    /// under `errors`
+    #[serde(default)]
+    pub per_file_timeout_ms: Option<u64>,
+    /// Skip files larger than this when discovering files
+    #[serde(default)]
+    pub max_file_size_bytes: Option<u64>,
+    /// Walk into symlinked directories and analyze symlinked files
+    #[serde(default)]
+    pub follow_symlinks: bool,
+    /// Analyze dotfiles and walk into dot-directories such as `.git` or `.venv`
+    #[serde(default)]
+    pub include_hidden: bool,
 }
 
 impl Default for AnalysisConfig {
@@ -612,6 +1088,11 @@ impl Default for AnalysisConfig {
                 ".go".to_string(),
                 // Rust
                 ".rs".to_string(),
+                // Java
+                ".java".to_string(),
+                // Kotlin
+                ".kt".to_string(),
+                ".kts".to_string(),
             ],
             excluded_patterns: vec![
                 "node_modules".to_string(), ".git".to_string(), "dist".to_string(), 
@@ -627,6 +1108,26 @@ impl Default for AnalysisConfig {
             verbose_output: false,
             include_line_numbers: true,
             parser_type: "pest".to_string(), // Default to PEST for backward compatibility
+            fail_on_error: false,
+            language_override: None,
+            per_file_timeout_ms: None,
+            max_file_size_bytes: None,
+            follow_symlinks: false,
+            include_hidden: false,
         }
     }
-}
\ No newline at end of file
+}
+
+/// Builders shared by the unit tests of the analysis modules
+#[cfg(test)]
+pub(crate) mod test_support {
+    use super::FunctionInfo;
+    
+    /// A function named `name` spanning `start_line..=end_line`
+    pub(crate) fn function(name: &str, start_line: u32, end_line: u32) -> FunctionInfo {
+        let mut function = FunctionInfo::new(name.to_string());
+        function.start_line = start_line;
+        function.end_line = end_line;
+        function
+    }
+}
@@ -0,0 +1,234 @@
+//! Unreachable statements after `return`, `throw`, `break`, `continue` and `panic`
+//!
+//! Session ASTs stop at declarations, so sources are parsed again with the
+//! tree-sitter grammar of their language. Within one block, the first
+//! statement after an unconditional jump is reported, once per block. Labels
+//! and `case` arms after the jump can be reached from elsewhere and end the
+//! dead stretch; hoisted declarations (JavaScript functions, Rust items) are
+//! not executed in place and are skipped. Kotlin has no grammar here and is
+//! not checked.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tree_sitter::{Node, Parser};
+
+use crate::core::types::Language;
+
+/// Nodes whose named children run one after another
+const BLOCK_KINDS: &[&str] = &[
+    "program", "module", "source_file", "translation_unit",
+    "block", "statement_block", "compound_statement", "statement_list",
+    "switch_case", "switch_default", "switch_section", "switch_block_statement_group",
+    "case_statement", "expression_case", "type_case", "default_case", "communication_case",
+];
+
+/// Statements that control can jump to, so code after them is live again
+const JUMP_TARGETS: &[&str] = &[
+    "labeled_statement", "case_statement", "switch_label", "switch_case", "switch_default",
+];
+
+/// Rust macros that never return
+const DIVERGING_MACROS: &[&str] = &["panic", "unreachable", "todo", "unimplemented"];
+
+/// Nodes reported as the enclosing function
+const FUNCTION_KINDS: &[&str] = &[
+    "function_declaration", "generator_function_declaration", "function_definition",
+    "function_item", "function_expression", "arrow_function", "method_definition",
+    "method_declaration", "constructor_declaration", "local_function_statement",
+    "func_literal", "closure_expression", "lambda_expression",
+];
+
+/// A statement that can never run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnreachableCode {
+    pub file: PathBuf,
+    pub line: u32,
+    /// Enclosing function; `<anonymous>` for unnamed closures, absent at top level
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function: Option<String>,
+    /// `return`, `throw`, `raise`, `break`, `continue`, `goto`, `panic` or `name!`
+    pub after: String,
+    pub after_line: u32,
+}
+
+/// Grammar for `language`; `None` for languages without one
+fn grammar(language: Language, file: &Path) -> Option<tree_sitter::Language> {
+    Some(match language {
+        Language::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
+        Language::TypeScript if file.extension().is_some_and(|ext| ext == "tsx") => {
+            tree_sitter_typescript::LANGUAGE_TSX.into()
+        }
+        Language::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+        Language::Python => tree_sitter_python::LANGUAGE.into(),
+        Language::C => tree_sitter_c::LANGUAGE.into(),
+        Language::Cpp => tree_sitter_cpp::LANGUAGE.into(),
+        Language::CSharp => tree_sitter_c_sharp::LANGUAGE.into(),
+        Language::Go => tree_sitter_go::LANGUAGE.into(),
+        Language::Rust => tree_sitter_rust::LANGUAGE.into(),
+        Language::Java => tree_sitter_java::LANGUAGE.into(),
+        Language::Kotlin | Language::Unknown => return None,
+    })
+}
+
+/// Unreachable statements in `content`, in line order. Returns nothing for
+/// languages without a grammar.
+pub fn find_unreachable(file: &Path, language: Language, content: &str) -> Result<Vec<UnreachableCode>> {
+    let Some(grammar) = grammar(language, file) else {
+        return Ok(Vec::new());
+    };
+    let mut parser = Parser::new();
+    parser.set_language(&grammar)
+        .map_err(|e| anyhow::anyhow!("Failed to load {:?} grammar: {}", language, e))?;
+    let tree = parser.parse(content, None)
+        .ok_or_else(|| anyhow::anyhow!("Failed to parse {}", file.display()))?;
+    let source = content.as_bytes();
+
+    let mut found = Vec::new();
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.named_children(&mut cursor).collect();
+        if BLOCK_KINDS.contains(&node.kind()) {
+            if let Some((jump, dead)) = first_dead_statement(&children, language, source) {
+                found.push(UnreachableCode {
+                    file: file.to_path_buf(),
+                    line: dead.start_position().row as u32 + 1,
+                    function: enclosing_function(dead, source),
+                    after: jump.1,
+                    after_line: jump.0.start_position().row as u32 + 1,
+                });
+            }
+        }
+        stack.extend(children);
+    }
+
+    found.sort_by_key(|code| (code.line, code.after_line));
+    Ok(found)
+}
+
+/// The jump and the first statement after it that cannot run
+fn first_dead_statement<'t>(
+    statements: &[Node<'t>],
+    language: Language,
+    source: &[u8],
+) -> Option<((Node<'t>, String), Node<'t>)> {
+    let mut jump: Option<(Node<'t>, String)> = None;
+    for &statement in statements {
+        if statement.is_extra() || statement.kind().contains("comment") {
+            continue;
+        }
+        match jump {
+            Some(_) if JUMP_TARGETS.contains(&statement.kind()) => jump = None,
+            Some(_) if is_hoisted(statement) || statement.kind() == "empty_statement" => {}
+            Some(jump) => return Some((jump, statement)),
+            None => jump = jump_kind(statement, language, source).map(|kind| (statement, kind)),
+        }
+    }
+    None
+}
+
+/// What kind of unconditional jump `node` is, if any
+fn jump_kind(node: Node, language: Language, source: &[u8]) -> Option<String> {
+    let kind = match node.kind() {
+        "return_statement" | "return_expression" => "return",
+        "throw_statement" | "throw_expression" => "throw",
+        "raise_statement" => "raise",
+        "break_statement" | "break_expression" => "break",
+        "continue_statement" | "continue_expression" => "continue",
+        "goto_statement" => "goto",
+        // Rust `return x;`, C++ `throw e;`, Go `panic(err)`
+        "expression_statement" => return jump_kind(node.named_child(0)?, language, source),
+        "macro_invocation" => {
+            let name = node.child_by_field_name("macro")?.utf8_text(source).ok()?;
+            return DIVERGING_MACROS.contains(&name).then(|| format!("{}!", name));
+        }
+        "call_expression" if language == Language::Go => {
+            let function = node.child_by_field_name("function")?;
+            return (function.utf8_text(source).ok()? == "panic").then(|| "panic".to_string());
+        }
+        _ => return None,
+    };
+    Some(kind.to_string())
+}
+
+/// Declarations that take effect before the statements around them run
+fn is_hoisted(node: Node) -> bool {
+    let kind = node.kind();
+    matches!(kind, "function_declaration" | "generator_function_declaration" | "use_declaration"
+        | "interface_declaration" | "type_alias_declaration")
+        || kind.ends_with("_item")
+}
+
+/// Name of the innermost function around `node`
+fn enclosing_function(node: Node, source: &[u8]) -> Option<String> {
+    let mut current = node.parent();
+    while let Some(ancestor) = current {
+        if FUNCTION_KINDS.contains(&ancestor.kind()) {
+            return Some(function_name(ancestor, source).unwrap_or_else(|| "<anonymous>".to_string()));
+        }
+        current = ancestor.parent();
+    }
+    None
+}
+
+fn function_name(function: Node, source: &[u8]) -> Option<String> {
+    if let Some(name) = function.child_by_field_name("name") {
+        return name.utf8_text(source).ok().map(str::to_string);
+    }
+    // C and C++ nest the name in declarators: `int *f(void)`, `Foo::bar()`
+    if let Some(mut declarator) = function.child_by_field_name("declarator") {
+        while let Some(inner) = declarator.child_by_field_name("declarator") {
+            declarator = inner;
+        }
+        return declarator.utf8_text(source).ok().map(str::to_string);
+    }
+    // `const f = () => { ... }`
+    let parent = function.parent()?;
+    if parent.kind() == "variable_declarator" {
+        return parent.child_by_field_name("name")?.utf8_text(source).ok().map(str::to_string);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unreachable(file: &str, language: Language, content: &str) -> Vec<(u32, Option<String>, String)> {
+        find_unreachable(Path::new(file), language, content).unwrap()
+            .into_iter()
+            .map(|code| (code.line, code.function, code.after))
+            .collect()
+    }
+
+    #[test]
+    fn test_statements_after_jumps() {
+        let js = "function f(x) {\n  if (x) return 1; else g();\n  return 2;\n  // done\n  g();\n  function g() {}\n}\nconst h = () => { throw new Error(); h(); };\n";
+        assert_eq!(unreachable("a.js", Language::JavaScript, js), vec![
+            (5, Some("f".to_string()), "return".to_string()),
+            (8, Some("h".to_string()), "throw".to_string()),
+        ]);
+
+        let py = "def f(items):\n    for item in items:\n        continue\n        print(item)\n    raise ValueError()\n    return 1\n";
+        assert_eq!(unreachable("a.py", Language::Python, py), vec![
+            (4, Some("f".to_string()), "continue".to_string()),
+            (6, Some("f".to_string()), "raise".to_string()),
+        ]);
+
+        let rust = "fn f() -> u32 {\n    panic!(\"no\");\n    fn helper() {}\n    let x = 1;\n    x\n}\nfn g() -> u32 { return 1; }\n";
+        assert_eq!(unreachable("a.rs", Language::Rust, rust), vec![(4, Some("f".to_string()), "panic!".to_string())]);
+
+        let go = "package main\n\nfunc f() int {\n\tpanic(\"no\")\n\treturn 1\n}\n";
+        assert_eq!(unreachable("a.go", Language::Go, go), vec![(5, Some("f".to_string()), "panic".to_string())]);
+
+        // A label after `goto` is reachable, and so is every `case`
+        let c = "int f(int x) {\n  goto done;\n  x++;\ndone:\n  switch (x) {\n  case 1: return 1;\n  case 2: break; x--;\n  }\n  return x;\n}\n";
+        assert_eq!(unreachable("a.c", Language::C, c), vec![
+            (3, Some("f".to_string()), "goto".to_string()),
+            (7, Some("f".to_string()), "break".to_string()),
+        ]);
+
+        assert!(unreachable("a.kt", Language::Kotlin, "fun f() { return; g() }").is_empty());
+    }
+}
@@ -2,22 +2,68 @@ mod core;
 mod analyzers;
 mod commands;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
+use std::io::{Read, Write};
 use std::path::PathBuf;
 
-use crate::core::session::{AnalysisSession, SessionManager};
-use crate::core::types::{AnalysisConfig, DirectoryAnalysis};
-use crate::core::config::ConfigManager;
-use crate::core::memory::{MemoryManager, MemoryType};
-use crate::core::preview::PreviewManager;
+use crate::core::ast::DumpFilter;
+use crate::core::baseline::{load_baseline, save_baseline as save_baseline_file, BaselineComparison};
+use crate::core::session::{AnalysisSession, SessionManager, SnippetOptions, DEFAULT_SNIPPET_MAX_BYTES};
+use crate::core::output::{set_compact, to_json};
+use crate::core::types::{AnalysisConfig, AnalysisResult, DirectoryAnalysis, Language};
+use crate::core::config::{ConfigManager, DEFAULT_CONFIG_FILE};
+use crate::core::memory::{parse_time_bound, MemoryManager, MemoryType};
+use crate::core::preview::{PreviewManager, ReplaceOptions, DEFAULT_PREVIEW_CONTEXT};
+use crate::core::schema::schema_for_type;
 use crate::core::impact::{ImpactAnalyzer, ImpactConfig, OutputFormatter, RiskLevel};
+use crate::commands::watch::{DebouncedWatcher, WatchConfig};
+
+// Exit statuses of `analyze` and `analyze-impact`: 0 on success, 1 when the
+// command itself fails (or --fail-if-complexity-increases trips), and the
+// two below
+/// `--fail-on-error` found files that failed to analyze, or `--fail-on`
+/// matched the impact risk
+const EXIT_CHECK_FAILED: i32 = 2;
+/// Invalid arguments, values or output format
+const EXIT_USAGE: i32 = 3;
+
+/// An invalid argument or value, reported with `EXIT_USAGE`
+#[derive(Debug)]
+struct UsageError(String);
+
+impl std::fmt::Display for UsageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for UsageError {}
+
+fn usage_error(message: impl Into<String>) -> anyhow::Error {
+    UsageError(message.into()).into()
+}
 
 #[derive(Parser)]
 #[command(name = "nekocode-rust")]
 #[command(about = "🦀 NekoCode Rust - High-performance code analysis tool")]
 #[command(version = "1.0.0")]
 struct Cli {
+    /// Config file to use instead of .nekocode_config.json; it must exist
+    /// and be valid unless a config command is creating or checking it
+    #[arg(long, global = true, value_name = "PATH")]
+    config: Option<PathBuf>,
+    
+    /// Worker threads for the runtime and for parallel file analysis in
+    /// analyze, session-create, session-update and analyze-impact.
+    /// `--threads 1` analyzes files one at a time in a deterministic order.
+    #[arg(short, long, global = true, default_value = "16")]
+    threads: usize,
+    
+    /// Print JSON output on a single line instead of indented
+    #[arg(long, global = true)]
+    compact: bool,
+    
     #[command(subcommand)]
     command: Commands,
 }
@@ -27,17 +73,40 @@ enum Commands {
     /// Analyze source code files (powered by ultra-fast Tree-sitter)
     Analyze {
         /// Path to analyze (file or directory)
-        #[arg(value_name = "PATH")]
-        path: PathBuf,
+        #[arg(value_name = "PATH", required_unless_present_any = ["stdin", "files_from"])]
+        path: Option<PathBuf>,
         
-        /// Output format
-        #[arg(short, long, default_value = "json")]
-        format: String,
+        /// Read source from stdin instead of PATH and print its analysis result as JSON
+        #[arg(long, conflicts_with_all = ["path", "watch"])]
+        stdin: bool,
+        
+        /// Analyze the files listed in FILE, one path per line (`-` reads the list from stdin)
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["path", "watch", "stdin", "language"])]
+        files_from: Option<PathBuf>,
+        
+        /// Paths in the --files-from list are separated by NUL instead of newlines
+        #[arg(long, requires = "files_from")]
+        null: bool,
+        
+        /// Language of the stdin source or of a single-file PATH, overriding detection
+        /// (rust, python, javascript, typescript, cpp, c, csharp, go, java, kotlin)
+        #[arg(long, value_name = "LANG")]
+        language: Option<String>,
+        
+        /// Output format (json, markdown, csv, ndjson) [default: json]. ndjson
+        /// prints one analysis result per line as each file finishes, then a
+        /// summary line. With --stats-only, json prints the summary as JSON.
+        #[arg(short, long)]
+        format: Option<String>,
         
         /// Enable verbose output
         #[arg(short, long)]
         verbose: bool,
         
+        /// Print only the results: no progress, notices or warnings on stderr
+        #[arg(short, long, conflicts_with = "verbose")]
+        quiet: bool,
+        
         /// Include test files
         #[arg(long)]
         include_tests: bool,
@@ -46,9 +115,54 @@ enum Commands {
         #[arg(long)]
         stats_only: bool,
         
-        /// Number of worker threads (default: 16)
-        #[arg(short, long, default_value = "16")]
-        threads: usize,
+        /// Bucket the --stats-only totals by language, top-level directory or file extension
+        #[arg(long, value_name = "KEY", requires = "stats_only", default_value = "language",
+              value_parser = ["language", "directory", "extension"])]
+        group_by: String,
+        
+        /// Keep watching the path and re-analyze changed files (Ctrl+C to stop)
+        #[arg(long)]
+        watch: bool,
+        
+        /// Exclude paths containing this pattern (repeatable, added to the defaults)
+        #[arg(long, value_name = "PATTERN")]
+        exclude: Vec<String>,
+        
+        /// Only analyze files with this extension, e.g. `rs` or `.rs` (repeatable, replaces the defaults)
+        #[arg(long, value_name = "EXT")]
+        include_ext: Vec<String>,
+        
+        /// Exit with status 2 when any file failed to analyze (they are still listed under `errors`)
+        #[arg(long)]
+        fail_on_error: bool,
+        
+        /// Give up on a file after this many milliseconds and list it under `errors`
+        #[arg(long, value_name = "MS")]
+        timeout_ms: Option<u64>,
+        
+        /// Skip files larger than this many bytes
+        #[arg(long, value_name = "BYTES")]
+        max_file_size: Option<u64>,
+        
+        /// Walk into symlinked directories (symlink loops are skipped)
+        #[arg(long)]
+        follow_symlinks: bool,
+        
+        /// Also analyze dotfiles and dot-directories such as .git or .venv
+        #[arg(long)]
+        include_hidden: bool,
+        
+        /// Save the analysis to FILE for a later --baseline comparison
+        #[arg(long, value_name = "FILE", conflicts_with = "watch")]
+        save_baseline: Option<PathBuf>,
+        
+        /// Compare against a saved baseline and print the per-file deltas instead of the analysis
+        #[arg(long, value_name = "FILE", conflicts_with = "watch")]
+        baseline: Option<PathBuf>,
+        
+        /// Exit with status 1 if a file or function got more complex than in the baseline
+        #[arg(long, requires = "baseline")]
+        fail_if_complexity_increases: bool,
     },
     
     /// Analyze code changes and show their impact across the codebase
@@ -57,7 +171,7 @@ enum Commands {
         #[arg(value_name = "PATH")]
         path: PathBuf,
         
-        /// Output format (plain, json, github-comment)
+        /// Output format (plain, json, github-comment, sarif)
         #[arg(short, long, default_value = "plain")]
         format: String,
         
@@ -69,17 +183,43 @@ enum Commands {
         #[arg(long)]
         include_tests: bool,
         
-        /// Compare against specific git reference (branch, commit, tag)
+        /// Compare against specific git reference (branch, commit, tag) [default: HEAD]
         #[arg(long)]
         compare_ref: Option<String>,
         
+        /// Guess changed symbols from naming patterns instead of git (for directories outside git)
+        #[arg(long, conflicts_with = "compare_ref")]
+        heuristic: bool,
+        
         /// Skip circular dependency analysis
         #[arg(long)]
         skip_circular: bool,
         
-        /// Risk threshold for reporting (low, medium, high)
-        #[arg(long, default_value = "low")]
-        risk_threshold: String,
+        /// Exit with status 2 when the overall risk reaches this level (low,
+        /// medium, high; default: high). Same gate as `--fail-on risk=LEVEL`
+        #[arg(long, value_name = "LEVEL", conflicts_with_all = ["fail_on", "no_fail"])]
+        risk_threshold: Option<String>,
+        
+        /// Attribute each reference to the author who last changed it (git blame)
+        #[arg(long)]
+        blame: bool,
+        
+        /// Report full file paths instead of paths relative to PATH
+        #[arg(long)]
+        absolute: bool,
+        
+        /// Take changed files and lines from a unified diff instead of git ('-' reads stdin)
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["compare_ref", "heuristic"])]
+        diff_file: Option<PathBuf>,
+        
+        /// Exit with status 2 when the overall risk reaches a level, e.g. `risk=medium`;
+        /// another spelling of --risk-threshold
+        #[arg(long, value_name = "CONDITION")]
+        fail_on: Option<String>,
+        
+        /// Only report: always exit with status 0 whatever the risk
+        #[arg(long, conflicts_with = "fail_on")]
+        no_fail: bool,
     },
     
     // SESSION MODE
@@ -88,6 +228,10 @@ enum Commands {
         /// Path to analyze
         #[arg(value_name = "PATH")]
         path: PathBuf,
+        
+        /// Store full file paths instead of paths relative to PATH
+        #[arg(long)]
+        absolute: bool,
     },
     
     /// Execute command in a session
@@ -96,12 +240,12 @@ enum Commands {
         #[arg(value_name = "SESSION_ID")]
         session_id: String,
         
-        /// Command to execute (stats, complexity, structure, find, include-cycles)
+        /// Command to execute (stats, complexity [MIN], metrics [--format json|sonar|codeclimate], structure, find TERM [--type KIND] [--exact], include-cycles, duplicates [THRESHOLD], annotations [--tags TAG,...], callgraph [--dot], unused, api-surface [--markdown], lint [--max-function-lines N] [--max-file-lines N] [--max-parameters N] [--format json|sarif], style, unreachable, compare-files FILE_A FILE_B)
         #[arg(value_name = "COMMAND")]
         command: String,
         
         /// Additional arguments for the command
-        #[arg(value_name = "ARGS")]
+        #[arg(value_name = "ARGS", trailing_var_arg = true, allow_hyphen_values = true)]
         args: Vec<String>,
     },
     
@@ -119,6 +263,21 @@ enum Commands {
         #[arg(long)]
         dry_run: bool,
     },
+    
+    /// Delete old sessions with their watch logs and PID files
+    SessionPrune {
+        /// Delete sessions not accessed for this many days
+        #[arg(long, value_name = "DAYS")]
+        older_than_days: Option<u32>,
+        
+        /// Keep only the N most recently accessed sessions
+        #[arg(long, value_name = "N")]
+        keep: Option<usize>,
+        
+        /// List what would be deleted without deleting anything
+        #[arg(long)]
+        dry_run: bool,
+    },
 
     // FILE WATCHING SYSTEM
     /// Start file watching for a session
@@ -133,6 +292,21 @@ enum Commands {
         /// Optional session ID (if not provided, shows all)
         #[arg(value_name = "SESSION_ID")]
         session_id: Option<String>,
+        
+        /// Print statuses as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Show file changes processed by a session's watcher
+    WatchLogs {
+        /// Session ID
+        #[arg(value_name = "SESSION_ID")]
+        session_id: String,
+        
+        /// Only show the last N events
+        #[arg(long)]
+        tail: Option<usize>,
     },
 
     /// Stop file watching for a session
@@ -164,9 +338,64 @@ enum Commands {
         #[arg(value_name = "PATTERN")]
         pattern: String,
         
-        /// Replacement text
+        /// Replacement text (with --regex, `$1` / `${name}` insert capture groups)
+        #[arg(value_name = "REPLACEMENT")]
+        replacement: String,
+        
+        /// Use regex pattern
+        #[arg(long)]
+        regex: bool,
+        
+        /// Case insensitive search
+        #[arg(short = 'i', long)]
+        ignore_case: bool,
+        
+        /// Match whole words only
+        #[arg(short = 'w', long)]
+        whole_word: bool,
+        
+        /// Print a unified diff (for `git apply`) instead of the preview text
+        #[arg(long)]
+        diff: bool,
+        
+        /// Lines of context around each change, in the preview text and the diff (like `diff -U`)
+        #[arg(long, value_name = "N", default_value_t = DEFAULT_PREVIEW_CONTEXT)]
+        context: u32,
+    },
+    
+    /// Preview a replacement in every file of a session (confirmed as one operation)
+    SessionReplacePreview {
+        /// Session ID
+        #[arg(value_name = "SESSION_ID")]
+        session_id: String,
+        
+        /// Pattern to replace
+        #[arg(value_name = "PATTERN")]
+        pattern: String,
+        
+        /// Replacement text (with --regex, `$1` / `${name}` insert capture groups)
         #[arg(value_name = "REPLACEMENT")]
         replacement: String,
+        
+        /// Use regex pattern
+        #[arg(long)]
+        regex: bool,
+        
+        /// Case insensitive search
+        #[arg(short = 'i', long)]
+        ignore_case: bool,
+        
+        /// Match whole words only
+        #[arg(short = 'w', long)]
+        whole_word: bool,
+        
+        /// Print a unified diff (for `git apply`) instead of the preview text
+        #[arg(long)]
+        diff: bool,
+        
+        /// Lines of context around each change, in the preview text and the diff (like `diff -U`)
+        #[arg(long, value_name = "N", default_value_t = DEFAULT_PREVIEW_CONTEXT)]
+        context: u32,
     },
     
     /// Confirm a replacement operation
@@ -189,6 +418,14 @@ enum Commands {
         /// Content to insert
         #[arg(value_name = "CONTENT")]
         content: String,
+        
+        /// Print a unified diff (for `git apply`) instead of the preview text
+        #[arg(long)]
+        diff: bool,
+        
+        /// Lines of context around each change, in the preview text and the diff (like `diff -U`)
+        #[arg(long, value_name = "N", default_value_t = DEFAULT_PREVIEW_CONTEXT)]
+        context: u32,
     },
     
     /// Confirm an insertion operation
@@ -219,6 +456,10 @@ enum Commands {
         /// Target position in destination
         #[arg(value_name = "POSITION")]
         position: u32,
+        
+        /// Print a unified diff (for `git apply`) instead of the preview text
+        #[arg(long)]
+        diff: bool,
     },
     
     /// Confirm a line movement operation
@@ -241,6 +482,10 @@ enum Commands {
         /// Target file
         #[arg(value_name = "TARGET")]
         target: PathBuf,
+        
+        /// Print a unified diff (for `git apply`) instead of the preview text
+        #[arg(long)]
+        diff: bool,
     },
     
     /// Confirm a class movement operation
@@ -250,6 +495,13 @@ enum Commands {
         preview_id: String,
     },
     
+    /// Undo a confirmed preview, restoring the files it changed
+    UndoPreview {
+        /// Preview ID to undo
+        #[arg(value_name = "PREVIEW_ID")]
+        preview_id: String,
+    },
+    
     // AST REVOLUTION
     /// Show AST statistics for a session
     AstStats {
@@ -268,6 +520,8 @@ enum Commands {
     ///   "MyClass::*"           Search all members in MyClass
     ///   "*"                    Search all nodes
     ///   "test"                 Partial search for anything containing "test"
+    ///   "app::*::render"       `*` matches any single scope segment
+    ///   "::App::run"           Leading `::` anchors the path at the file root
     AstQuery {
         /// Session ID from session-create
         #[arg(value_name = "SESSION_ID")]
@@ -284,9 +538,26 @@ enum Commands {
         ///   "test"                 Partial name matching
         #[arg(value_name = "PATH")]
         path: String,
+        
+        /// Treat each path segment as a regex matched against the whole name
+        /// (e.g. "Button::render.*")
+        #[arg(long)]
+        regex: bool,
+        
+        /// Include each match's source text under `source`
+        #[arg(long)]
+        output_lines: bool,
+        
+        /// Lines of context around each match (with --output-lines)
+        #[arg(long, value_name = "N", default_value = "0", requires = "output_lines")]
+        context: u32,
+        
+        /// Cut source text off after this many bytes per match (with --output-lines)
+        #[arg(long, value_name = "BYTES", default_value_t = DEFAULT_SNIPPET_MAX_BYTES, requires = "output_lines")]
+        max_bytes: usize,
     },
     
-    /// Analyze scope at a specific line
+    /// Find the innermost scope enclosing a line and its ancestors
     ScopeAnalysis {
         /// Session ID
         #[arg(value_name = "SESSION_ID")]
@@ -295,6 +566,25 @@ enum Commands {
         /// Line number to analyze
         #[arg(value_name = "LINE")]
         line: u32,
+        
+        /// Only report the file whose path ends with this path
+        #[arg(long)]
+        file: Option<PathBuf>,
+        
+        /// Report the deepest node per file without its ancestor chain (legacy output)
+        #[arg(long)]
+        no_chain: bool,
+    },
+    
+    /// Compare AST structure between two sessions (added/removed/renamed/moved nodes)
+    AstDiff {
+        /// Session ID of the base version
+        #[arg(value_name = "BASE_SESSION")]
+        base_session: String,
+        
+        /// Session ID of the new version
+        #[arg(value_name = "HEAD_SESSION")]
+        head_session: String,
     },
     
     /// Dump AST structure
@@ -303,9 +593,17 @@ enum Commands {
         #[arg(value_name = "SESSION_ID")]
         session_id: String,
         
-        /// Output format (tree, json)
+        /// Output format (tree, json, flat)
         #[arg(value_name = "FORMAT", default_value = "tree")]
         format: String,
+        
+        /// Do not descend below this depth (the file root is depth 0)
+        #[arg(long)]
+        depth: Option<u32>,
+        
+        /// Only include these node types, comma-separated (e.g. function,class)
+        #[arg(long)]
+        node_type: Option<String>,
     },
     
     // MEMORY SYSTEM
@@ -324,6 +622,17 @@ enum Commands {
     
     /// List supported languages
     Languages,
+    
+    /// Print the JSON Schema of a result type (analysis, file, session, impact)
+    Schema {
+        /// Result type to describe
+        #[arg(value_name = "TYPE", default_value = "analysis")]
+        target: String,
+        
+        /// Result type to describe (alternative to TYPE)
+        #[arg(long = "type", value_name = "TYPE", conflicts_with = "target")]
+        type_name: Option<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -361,15 +670,60 @@ enum MemoryOperation {
         memory_type: Option<String>,
     },
     
+    /// Replace the content of a memory (keeps its creation time)
+    Edit {
+        /// Memory type
+        #[arg(value_name = "TYPE")]
+        memory_type: String,
+        
+        /// Memory name/key
+        #[arg(value_name = "NAME")]
+        name: String,
+        
+        /// New content (optional, can be read from stdin)
+        #[arg(value_name = "CONTENT")]
+        content: Option<String>,
+    },
+    
+    /// Delete memories by name; `*` globs are allowed (e.g. `delete cache '*'` or `delete 'cache:*'`)
+    Delete {
+        /// Memory type, or TYPE:NAME
+        #[arg(value_name = "TYPE")]
+        memory_type: String,
+        
+        /// Memory name/key or glob pattern
+        #[arg(value_name = "NAME")]
+        name: Option<String>,
+    },
+    
+    /// Search memories by name or content (case-insensitive)
+    Search {
+        /// Text to search for
+        #[arg(value_name = "QUERY")]
+        query: String,
+        
+        /// Optional memory type filter
+        #[arg(value_name = "TYPE")]
+        memory_type: Option<String>,
+    },
+    
     /// Show memory timeline
     Timeline {
         /// Optional memory type filter
         #[arg(value_name = "TYPE")]
         memory_type: Option<String>,
         
-        /// Number of days to show
-        #[arg(long, default_value = "7")]
-        days: u32,
+        /// Number of days to show (default 7, used when --since is not given)
+        #[arg(long, conflicts_with = "since")]
+        days: Option<u32>,
+        
+        /// Start of the window: RFC 3339 timestamp, YYYY-MM-DD date or duration ago (e.g. 2h)
+        #[arg(long)]
+        since: Option<String>,
+        
+        /// End of the window, in the same formats as --since (default: now)
+        #[arg(long)]
+        until: Option<String>,
     },
 }
 
@@ -388,10 +742,79 @@ enum ConfigOperation {
         #[arg(value_name = "VALUE")]
         value: String,
     },
+    
+    /// Check the configuration file for unknown keys and invalid values
+    Validate,
+    
+    /// Write the full configuration, defaults included, to a file
+    Export {
+        #[arg(value_name = "PATH")]
+        path: PathBuf,
+    },
+    
+    /// Validate a configuration file and make it the active configuration
+    Import {
+        #[arg(value_name = "PATH")]
+        path: PathBuf,
+        
+        /// Only change the keys present in the file instead of replacing the whole configuration
+        #[arg(long)]
+        merge: bool,
+    },
+    
+    /// Restore the default configuration
+    Reset,
+}
+
+/// Totals of one `--group-by` bucket
+#[derive(Debug, Default, serde::Serialize)]
+struct GroupTotals {
+    files: usize,
+    total_lines: u32,
+    code_lines: u32,
+    functions: usize,
+    classes: usize,
+    complexity: u32,
+}
+
+/// Bucket key of a file: its language, the top-level directory under the
+/// analyzed path (`.` for files directly inside it) or its extension
+fn group_key(result: &DirectoryAnalysis, file: &AnalysisResult, group_by: &str) -> String {
+    match group_by {
+        "directory" => {
+            let path = &file.file_info.path;
+            let relative = path.strip_prefix(&result.directory_path).unwrap_or(path);
+            let mut components = relative.components()
+                .filter(|c| !matches!(c, std::path::Component::CurDir));
+            match (components.next(), components.next()) {
+                (Some(first), Some(_)) => first.as_os_str().to_string_lossy().to_string(),
+                _ => ".".to_string(),
+            }
+        }
+        "extension" => match file.file_info.path.extension() {
+            Some(ext) => format!(".{}", ext.to_string_lossy()),
+            None => "(none)".to_string(),
+        },
+        _ => format!("{:?}", file.language),
+    }
+}
+
+fn group_files(result: &DirectoryAnalysis, group_by: &str) -> std::collections::BTreeMap<String, GroupTotals> {
+    let mut groups: std::collections::BTreeMap<String, GroupTotals> = std::collections::BTreeMap::new();
+    for file in &result.files {
+        let group = groups.entry(group_key(result, file, group_by)).or_default();
+        group.files += 1;
+        group.total_lines += file.file_info.total_lines;
+        group.code_lines += file.file_info.code_lines;
+        group.functions += file.functions.len();
+        group.classes += file.classes.len();
+        group.complexity += file.complexity.cyclomatic_complexity;
+    }
+    groups
 }
 
 /// Extract summary statistics from analysis result
-fn extract_summary(result: &DirectoryAnalysis) -> String {
+fn extract_summary(result: &DirectoryAnalysis, group_by: &str) -> String {
     let mut summary = Vec::new();
     summary.push("📊 **解析結果サマリー**\n".to_string());
     
@@ -402,26 +825,12 @@ fn extract_summary(result: &DirectoryAnalysis) -> String {
     let total_files = result.files.len();
     summary.push(format!("📄 総ファイル数: {}", total_files));
     
-    // 言語別統計と総計
-    let mut lang_counts = std::collections::HashMap::new();
-    let mut total_functions = 0;
-    let mut total_classes = 0;
-    let mut total_lines = 0;
-    let mut total_code_lines = 0;
-    
-    for file in &result.files {
-        // 言語カウント
-        let lang = &file.language;
-        *lang_counts.entry(format!("{:?}", lang)).or_insert(0) += 1;
-        
-        // 機能カウント
-        total_functions += file.functions.len();
-        total_classes += file.classes.len();
-        
-        // 行数カウント
-        total_lines += file.file_info.total_lines;
-        total_code_lines += file.file_info.code_lines;
-    }
+    // グループ別統計と総計
+    let groups = group_files(result, group_by);
+    let total_functions: usize = groups.values().map(|g| g.functions).sum();
+    let total_classes: usize = groups.values().map(|g| g.classes).sum();
+    let total_lines: u32 = groups.values().map(|g| g.total_lines).sum();
+    let total_code_lines: u32 = groups.values().map(|g| g.code_lines).sum();
     
     summary.push(format!("\n📈 **統計情報:**"));
     summary.push(format!("  • 総行数: {}", total_lines));
@@ -429,103 +838,468 @@ fn extract_summary(result: &DirectoryAnalysis) -> String {
     summary.push(format!("  • 関数数: {}", total_functions));
     summary.push(format!("  • クラス数: {}", total_classes));
     
-    if !lang_counts.is_empty() {
-        summary.push(format!("\n🗂️ **言語別:**"));
-        let mut sorted_langs: Vec<_> = lang_counts.into_iter().collect();
-        sorted_langs.sort_by(|a, b| a.0.cmp(&b.0));
-        for (lang, count) in sorted_langs {
-            summary.push(format!("  • {}: {} files", lang, count));
+    if !groups.is_empty() {
+        let heading = match group_by {
+            "directory" => "ディレクトリ別",
+            "extension" => "拡張子別",
+            _ => "言語別",
+        };
+        summary.push(format!("\n🗂️ **{}:**", heading));
+        for (key, group) in &groups {
+            summary.push(format!(
+                "  • {}: {} files, {} lines, {} functions, complexity {}",
+                key, group.files, group.total_lines, group.functions, group.complexity
+            ));
+        }
+    }
+    
+    if !result.errors.is_empty() {
+        summary.push(format!("\n⚠️ **解析エラー:** {} files", result.errors.len()));
+        for error in &result.errors {
+            summary.push(format!("  • {}: {}", error.path.display(), error.message));
         }
     }
     
     summary.join("\n")
 }
 
+/// `--stats-only --format json`: the totals with the per-group breakdown under `groups`
+fn summary_json(result: &DirectoryAnalysis, group_by: &str) -> serde_json::Value {
+    let groups = group_files(result, group_by);
+    serde_json::json!({
+        "directory_path": result.directory_path,
+        "total_files": result.files.len(),
+        "total_lines": groups.values().map(|g| g.total_lines).sum::<u32>(),
+        "code_lines": groups.values().map(|g| g.code_lines).sum::<u32>(),
+        "functions": groups.values().map(|g| g.functions).sum::<usize>(),
+        "classes": groups.values().map(|g| g.classes).sum::<usize>(),
+        "group_by": group_by,
+        "groups": groups,
+        "errors": result.errors,
+    })
+}
+
+/// Render a Markdown report (per-file table plus per-language summary)
+fn render_markdown_report(result: &DirectoryAnalysis) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("## NekoCode Analysis: `{}`\n\n", result.directory_path.display()));
+    
+    out.push_str("| File | Language | Functions | Classes | Lines |\n");
+    out.push_str("|------|----------|----------:|--------:|------:|\n");
+    
+    // language -> (files, functions, classes, lines)
+    let mut by_language: std::collections::BTreeMap<String, (usize, usize, usize, u32)> =
+        std::collections::BTreeMap::new();
+    
+    for file in &result.files {
+        let lang = format!("{:?}", file.language);
+        out.push_str(&format!(
+            "| `{}` | {} | {} | {} | {} |\n",
+            file.file_info.path.display(),
+            lang,
+            file.functions.len(),
+            file.classes.len(),
+            file.file_info.total_lines
+        ));
+        
+        let entry = by_language.entry(lang).or_default();
+        entry.0 += 1;
+        entry.1 += file.functions.len();
+        entry.2 += file.classes.len();
+        entry.3 += file.file_info.total_lines;
+    }
+    
+    out.push_str("\n### Summary by language\n\n");
+    out.push_str("| Language | Files | Functions | Classes | Lines |\n");
+    out.push_str("|----------|------:|----------:|--------:|------:|\n");
+    
+    let mut totals = (0, 0, 0, 0u32);
+    for (lang, (files, functions, classes, lines)) in &by_language {
+        out.push_str(&format!("| {} | {} | {} | {} | {} |\n", lang, files, functions, classes, lines));
+        totals.0 += files;
+        totals.1 += functions;
+        totals.2 += classes;
+        totals.3 += lines;
+    }
+    out.push_str(&format!(
+        "| **Total** | **{}** | **{}** | **{}** | **{}** |\n",
+        totals.0, totals.1, totals.2, totals.3
+    ));
+    
+    out
+}
+
+/// Render a CSV export with the same columns as the workspace `export --format csv`
+fn render_csv(result: &DirectoryAnalysis) -> String {
+    let mut csv = String::from("file,language,functions,classes,lines\n");
+    for file in &result.files {
+        csv.push_str(&format!(
+            "{},{:?},{},{},{}\n",
+            csv_field(&file.file_info.path.display().to_string()),
+            file.language,
+            file.functions.len(),
+            file.classes.len(),
+            file.file_info.total_lines
+        ));
+    }
+    csv
+}
+
+/// Quote a CSV field when it contains a delimiter, quote or newline
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Read the paths of an `analyze --files-from` list, from stdin for `-`
+fn read_file_list(list: &std::path::Path, null: bool) -> Result<Vec<PathBuf>> {
+    let mut content = String::new();
+    if list.as_os_str() == "-" {
+        std::io::stdin().read_to_string(&mut content)
+            .context("Failed to read the file list from stdin")?;
+    } else {
+        content = std::fs::read_to_string(list)
+            .with_context(|| format!("Failed to read file list: {}", list.display()))?;
+    }
+    
+    let separator = if null { '\0' } else { '\n' };
+    Ok(content.split(separator)
+        .map(|line| line.trim_end_matches('\r'))
+        .filter(|line| !line.trim().is_empty())
+        .map(PathBuf::from)
+        .collect())
+}
+
+/// Print an analysis result as a summary (`--stats-only`, grouped by
+/// `group_by`) or in the requested format
+fn print_analysis(result: &DirectoryAnalysis, format: &str, stats_only: bool, group_by: &str) -> Result<()> {
+    if stats_only {
+        match format {
+            "json" => println!("{}", to_json(&summary_json(result, group_by))?),
+            _ => println!("{}", extract_summary(result, group_by)),
+        }
+        return Ok(());
+    }
+    
+    match format {
+        "json" => {
+            let json = to_json(result)?;
+            println!("{}", json);
+        }
+        "markdown" | "md" => {
+            print!("{}", render_markdown_report(result));
+        }
+        "csv" => {
+            print!("{}", render_csv(result));
+        }
+        _ => {
+            return Err(usage_error(format!("Unsupported output format: {}", format)));
+        }
+    }
+    
+    Ok(())
+}
+
+/// With `--fail-on-error`, exit with `EXIT_CHECK_FAILED` when any file failed to analyze
+fn exit_on_file_errors(analysis: &DirectoryAnalysis, fail_on_error: bool, quiet: bool) {
+    if fail_on_error && !analysis.errors.is_empty() {
+        if !quiet {
+            eprintln!("❌ {} file(s) failed to analyze", analysis.errors.len());
+        }
+        exit_flushed(EXIT_CHECK_FAILED);
+    }
+}
+
+/// `std::process::exit` skips destructors, so write out buffered stdout first
+fn exit_flushed(code: i32) -> ! {
+    let _ = std::io::stdout().flush();
+    std::process::exit(code)
+}
+
+/// Print one JSON line per analyzed file as it completes, then a line with
+/// the summary and any per-file errors
+async fn stream_ndjson(session: &mut AnalysisSession, path: &std::path::Path, include_tests: bool) -> Result<DirectoryAnalysis> {
+    use std::io::Write;
+    
+    let mut out = std::io::stdout().lock();
+    let analysis = session.analyze_path_streaming(path, include_tests, |result| {
+        serde_json::to_writer(&mut out, &result)?;
+        writeln!(out)?;
+        Ok(())
+    }).await?;
+    
+    serde_json::to_writer(&mut out, &serde_json::json!({
+        "directory_path": analysis.directory_path,
+        "summary": analysis.summary,
+        "errors": analysis.errors,
+        "generated_at": analysis.generated_at,
+    }))?;
+    writeln!(out)?;
+    Ok(analysis)
+}
+
+/// Print a freshly created preview. With `--diff` only the patch goes to
+/// stdout so it can be piped into `git apply --check`.
+fn print_preview(preview_manager: &PreviewManager, preview_id: &str, diff: bool) -> Result<()> {
+    if diff {
+        eprintln!("Preview ID: {}", preview_id);
+        print!("{}", preview_manager.diff(preview_id)?);
+    } else {
+        let preview = preview_manager.get_preview(preview_id).unwrap();
+        println!("Preview ID: {}", preview_id);
+        println!("{}", preview.preview_text);
+    }
+    Ok(())
+}
+
 fn main() -> Result<()> {
-    // Parse CLI to get thread count first
-    let cli: Cli = clap::Parser::parse();
-    let threads = match &cli.command {
-        Commands::Analyze { threads, .. } => *threads,
-        _ => 16, // Default for other commands
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        // --help and --version also arrive here and exit with 0
+        Err(e) if !e.use_stderr() => e.exit(),
+        Err(e) => {
+            let _ = e.print();
+            std::process::exit(EXIT_USAGE);
+        }
     };
     
+    let result = run(cli);
+    if let Err(e) = &result {
+        if e.downcast_ref::<UsageError>().is_some() {
+            eprintln!("Error: {:#}", e);
+            std::process::exit(EXIT_USAGE);
+        }
+    }
+    result
+}
+
+fn run(cli: Cli) -> Result<()> {
+    if cli.threads == 0 {
+        return Err(usage_error("--threads must be at least 1"));
+    }
+    
     // 🚀 Build custom tokio runtime with configurable worker threads
     let rt = tokio::runtime::Builder::new_multi_thread()
-        .worker_threads(threads)
+        .worker_threads(cli.threads)
         .enable_all()
         .build()?;
     
-    rt.block_on(async_main())
+    rt.block_on(async_main(cli))
 }
 
-async fn async_main() -> Result<()> {
-    env_logger::init();
+async fn async_main(cli: Cli) -> Result<()> {
+    let threads = cli.threads;
+    set_compact(cli.compact);
     
-    let cli = Cli::parse();
+    // An explicit --config must exist and be valid for every command except
+    // those that check, create or overwrite the file
+    let manages_file = matches!(cli.command, Commands::Config {
+        operation: ConfigOperation::Set { .. } | ConfigOperation::Validate | ConfigOperation::Import { .. } | ConfigOperation::Reset
+    });
+    let config = match &cli.config {
+        Some(path) if !manages_file => ConfigManager::load(path)?,
+        _ => ConfigManager::with_path(cli.config.clone().unwrap_or_else(|| PathBuf::from(DEFAULT_CONFIG_FILE))),
+    };
+    let settings = config.get().clone();
+    
+    // Diagnostics go to stderr; `analyze --verbose` shows progress unless RUST_LOG overrides it
+    let verbose = matches!(cli.command, Commands::Analyze { verbose: true, .. }) || settings.general.verbose;
+    let quiet = matches!(cli.command, Commands::Analyze { quiet: true, .. });
+    let filter = if verbose { "info" } else if quiet { "off" } else { "warn" };
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(filter))
+        .init();
     
     match cli.command {
-        Commands::Analyze { path, format, verbose, include_tests, stats_only, threads } => {
+        Commands::Analyze { stdin: true, language, .. } => {
+            let language = language.ok_or_else(|| usage_error(
+                "--stdin requires --language: the language cannot be detected without a file extension"
+            ))?;
+            let language = Language::from_name(&language)
+                .ok_or_else(|| usage_error(format!("Unknown language '{}'", language)))?;
+            
+            let mut content = String::new();
+            std::io::stdin().read_to_string(&mut content)
+                .context("Failed to read source from stdin")?;
+            
+            let session = AnalysisSession::new();
+            let result = session.analyze_content(&content, language).await?;
+            println!("{}", to_json(&result)?);
+        }
+        
+        Commands::Analyze { path, files_from, null, language, format, verbose, quiet, include_tests, stats_only, group_by, watch, exclude, include_ext, fail_on_error, timeout_ms, max_file_size, follow_symlinks, include_hidden, save_baseline, baseline, fail_if_complexity_increases, .. } => {
+            let path = path.unwrap_or_else(|| PathBuf::from("."));
+            // --stats-only stays a text summary unless JSON is asked for explicitly
+            let format = format.unwrap_or_else(|| if stats_only { "text" } else { "json" }.to_string());
+            // Summaries and baseline comparisons print anything but json as text
+            if !stats_only && baseline.is_none() && !matches!(format.as_str(), "json" | "markdown" | "md" | "csv" | "ndjson") {
+                return Err(usage_error(format!("Unsupported output format: {}. Use json, markdown, csv or ndjson", format)));
+            }
             let mut config = AnalysisConfig::default();
+            if let Some(language) = language {
+                if !path.is_file() {
+                    return Err(usage_error(format!("--language applies to --stdin or a single file, not to {}", path.display())));
+                }
+                config.language_override = Some(Language::from_name(&language)
+                    .ok_or_else(|| usage_error(format!("Unknown language '{}'", language)))?);
+            }
+            // Flags add to the config file's settings
+            let verbose = verbose || settings.general.verbose;
+            let include_tests = include_tests || settings.analysis.include_tests;
             config.verbose_output = verbose;
             config.include_test_files = include_tests;
+            config.max_threads = threads;
+            config.per_file_timeout_ms = timeout_ms;
+            config.max_file_size_bytes = max_file_size
+                .or(Some(settings.analysis.max_file_size_mb as u64 * 1024 * 1024));
+            config.follow_symlinks = follow_symlinks;
+            config.include_hidden = include_hidden;
+            config.excluded_patterns.extend(settings.analysis.exclude_patterns.iter().cloned());
+            config.excluded_patterns.extend(exclude);
+            if !include_ext.is_empty() {
+                config.included_extensions = include_ext.iter()
+                    .map(|ext| format!(".{}", ext.trim_start_matches('.')))
+                    .collect();
+            }
             
             // Create session for Tree-sitter analysis
             let mut session = AnalysisSession::with_config(config);
             
             if verbose {
-                println!("🦀 NekoCode Rust Analysis Starting...");
-                println!("📂 Target: {}", path.display());
-                println!("⚡ Parser: TREE-SITTER 🚀");
-                println!("🧵 Worker Threads: {}", threads);
+                log::info!("🦀 NekoCode Rust Analysis Starting...");
+                match &files_from {
+                    Some(list) => log::info!("📂 Files from: {}", list.display()),
+                    None => log::info!("📂 Target: {}", path.display()),
+                }
+                log::info!("🧵 Worker Threads: {}", threads);
             }
             
-            let result = session.analyze_path(&path, include_tests).await?;
+            if format == "ndjson" {
+                if watch || files_from.is_some() || stats_only || save_baseline.is_some() || baseline.is_some() {
+                    return Err(usage_error("--format ndjson cannot be combined with --watch, --files-from, --stats-only or baselines"));
+                }
+                let analysis = stream_ndjson(&mut session, &path, include_tests).await?;
+                exit_on_file_errors(&analysis, fail_on_error, quiet);
+                return Ok(());
+            }
             
-            // Check if stats_only mode is enabled
-            if stats_only {
-                let summary = extract_summary(&result);
-                println!("{}", summary);
-            } else {
+            let mut result = match files_from {
+                Some(list) => session.analyze_files(&read_file_list(&list, null)?).await?,
+                None => session.analyze_path(&path, include_tests).await?,
+            };
+            
+            if let Some(baseline_path) = &save_baseline {
+                save_baseline_file(&result, baseline_path)?;
+                if !quiet {
+                    eprintln!("💾 Saved baseline to {}", baseline_path.display());
+                }
+            }
+            
+            if let Some(baseline_path) = &baseline {
+                let comparison = BaselineComparison::between(&load_baseline(baseline_path)?, &result);
                 match format.as_str() {
-                    "json" => {
-                        let json = serde_json::to_string_pretty(&result)?;
-                        println!("{}", json);
+                    "json" => println!("{}", to_json(&comparison)?),
+                    _ => print!("{}", comparison.render_text()),
+                }
+                if fail_if_complexity_increases && comparison.complexity_increased() {
+                    if !quiet {
+                        eprintln!("❌ Complexity increased compared to {}", baseline_path.display());
                     }
-                    _ => {
-                        anyhow::bail!("Unsupported output format: {}", format);
+                    exit_flushed(1);
+                }
+                exit_on_file_errors(&result, fail_on_error, quiet);
+                return Ok(());
+            }
+            
+            print_analysis(&result, &format, stats_only, &group_by)?;
+            
+            if watch {
+                let mut watcher = DebouncedWatcher::new(&path, WatchConfig::default())?;
+                if !quiet {
+                    eprintln!("👀 Watching {} for changes (Ctrl+C to stop)", path.display());
+                }
+                
+                loop {
+                    let changed = tokio::select! {
+                        changed = watcher.next_changes() => match changed {
+                            Some(changed) => changed,
+                            None => break,
+                        },
+                        _ = tokio::signal::ctrl_c() => break,
+                    };
+                    
+                    match session.reanalyze_files(&mut result, &changed).await {
+                        Ok(0) => continue,
+                        Ok(updated) => {
+                            if !quiet {
+                                eprintln!("🔄 Re-analyzed {} changed file(s)", updated);
+                            }
+                            print_analysis(&result, &format, stats_only, &group_by)?;
+                        }
+                        // Failures are still reported, like the fatal errors `main` prints
+                        Err(e) => eprintln!("❌ Re-analysis failed: {:#}", e),
                     }
                 }
+                
+                if !quiet {
+                    eprintln!("👋 Stopped watching {}", path.display());
+                }
+            } else {
+                exit_on_file_errors(&result, fail_on_error, quiet);
             }
             
             if verbose {
-                println!("✅ Analysis completed!");
+                log::info!("✅ Analysis completed!");
             }
         }
         
-        Commands::AnalyzeImpact { path, format, verbose, include_tests, compare_ref, skip_circular, risk_threshold } => {
+        Commands::AnalyzeImpact { path, format, verbose, include_tests, compare_ref, heuristic, skip_circular, risk_threshold, blame, absolute, diff_file, fail_on, no_fail } => {
             if verbose {
                 println!("🔍 NekoCode Impact Analysis Starting...");
                 println!("📂 Target: {}", path.display());
                 println!("📊 Format: {}", format);
             }
             
-            // Parse risk threshold
-            let risk_level = match risk_threshold.as_str() {
-                "low" => RiskLevel::Low,
-                "medium" => RiskLevel::Medium,
-                "high" => RiskLevel::High,
-                _ => {
-                    anyhow::bail!("Invalid risk threshold: {}. Use 'low', 'medium', or 'high'", risk_threshold);
+            if !matches!(format.as_str(), "plain" | "json" | "github-comment" | "sarif") {
+                return Err(usage_error(format!("Unsupported output format: {}. Use 'plain', 'json', 'github-comment', or 'sarif'", format)));
+            }
+            
+            // The risk gate, from --risk-threshold or --fail-on
+            let risk_level = match (risk_threshold, fail_on) {
+                (Some(level), _) => RiskLevel::from_name(&level).ok_or_else(|| usage_error(format!(
+                    "Invalid risk threshold: {}. Use 'low', 'medium', or 'high'", level
+                )))?,
+                (None, Some(condition)) => condition.strip_prefix("risk=").and_then(RiskLevel::from_name).ok_or_else(|| usage_error(format!(
+                    "Invalid --fail-on condition: {}. Use risk=low, risk=medium or risk=high", condition
+                )))?,
+                (None, None) => ImpactConfig::default().risk_threshold,
+            };
+            
+            let diff = match diff_file {
+                Some(file) if file.as_os_str() == "-" => {
+                    let mut diff = String::new();
+                    std::io::stdin().read_to_string(&mut diff).context("Failed to read the diff from stdin")?;
+                    Some(diff)
                 }
+                Some(file) => Some(std::fs::read_to_string(&file)
+                    .with_context(|| format!("Failed to read diff {}", file.display()))?),
+                None => None,
             };
             
             // Create impact configuration
             let config = ImpactConfig {
-                include_tests,
+                include_tests: include_tests || settings.analysis.include_tests,
                 compare_ref,
                 skip_circular,
                 risk_threshold: risk_level,
                 verbose,
+                heuristic,
+                blame,
+                threads,
+                absolute_paths: absolute,
+                diff,
             };
             
             // Create analyzer and run analysis
@@ -544,45 +1318,89 @@ async fn async_main() -> Result<()> {
                 "github-comment" => {
                     println!("{}", OutputFormatter::format_github_comment(&result));
                 }
-                _ => {
-                    anyhow::bail!("Unsupported output format: {}. Use 'plain', 'json', or 'github-comment'", format);
+                "sarif" => {
+                    println!("{}", OutputFormatter::format_sarif(&result)?);
                 }
+                _ => unreachable!("output format checked above"),
             }
             
             if verbose {
                 println!("✅ Impact analysis completed!");
             }
+            
+            // The verdict goes to stderr so json, github-comment and sarif output stay intact
+            if !no_fail {
+                if result.overall_risk >= risk_level {
+                    eprintln!("❌ Risk gate failed: overall risk {} reaches the {} threshold",
+                        result.overall_risk.as_str(), risk_level.as_str());
+                    exit_flushed(EXIT_CHECK_FAILED);
+                }
+                eprintln!("✅ Risk gate passed: overall risk {} is below the {} threshold",
+                    result.overall_risk.as_str(), risk_level.as_str());
+            }
         }
         
         // SESSION MODE
-        Commands::SessionCreate { path } => {
+        Commands::SessionCreate { path, absolute } => {
             let mut session_manager = SessionManager::new()?;
-            let session_id = session_manager.create_session(&path).await?;
+            session_manager.set_analysis_threads(threads);
+            let session_id = session_manager.create_session_with_paths(&path, absolute).await?;
             println!("Session created: {}", session_id);
         }
         
         Commands::SessionCommand { session_id, command, args } => {
             let mut session_manager = SessionManager::new()?;
+            session_manager.set_annotation_tags(config.get().analysis.annotation_tags.clone());
+            session_manager.set_lint_thresholds(config.get().lint);
             let result = session_manager.execute_session_command(&session_id, &command, &args)?;
             println!("{}", result);
         }
         
         Commands::SessionUpdate { session_id, verbose, dry_run } => {
             use nekocode_rust::commands::session_update::handle_session_update;
-            let result = handle_session_update(&session_id, verbose, dry_run).await?;
+            let mut session_manager = nekocode_rust::core::session::SessionManager::new()?;
+            session_manager.set_analysis_threads(threads);
+            let result = handle_session_update(&mut session_manager, &session_id, verbose, dry_run).await?;
             println!("{}", result);
         }
+        
+        Commands::SessionPrune { older_than_days, keep, dry_run } => {
+            let mut session_manager = SessionManager::new()?;
+            let report = session_manager.prune(older_than_days, keep, dry_run)?;
+            let verb = if dry_run { "Would delete" } else { "Deleted" };
+            for session in &report.removed {
+                println!(
+                    "🗑️  {} {} ({}, last accessed {}, {:.1} KB)",
+                    verb, session.id, session.path.display(),
+                    session.last_accessed.format("%Y-%m-%d %H:%M"), session.bytes as f64 / 1024.0
+                );
+            }
+            for id in &report.skipped_watched {
+                println!("👀 Skipped {} (watcher running)", id);
+            }
+            println!(
+                "{} {} session(s), kept {}, {} {:.1} KB",
+                verb, report.removed.len(), report.kept,
+                if dry_run { "would free" } else { "freed" }, report.freed_bytes as f64 / 1024.0
+            );
+        }
 
         // FILE WATCHING SYSTEM
         Commands::WatchStart { session_id } => {
             use crate::commands::watch::handle_watch_start;
-            let result = handle_watch_start(&session_id)?;
+            let result = handle_watch_start(&SessionManager::new()?, &session_id)?;
             println!("{}", result);
         }
 
-        Commands::WatchStatus { session_id } => {
+        Commands::WatchStatus { session_id, json } => {
             use crate::commands::watch::handle_watch_status;
-            let result = handle_watch_status(session_id.as_deref())?;
+            let result = handle_watch_status(&SessionManager::new()?, session_id.as_deref(), json)?;
+            println!("{}", result);
+        }
+
+        Commands::WatchLogs { session_id, tail } => {
+            use crate::commands::watch::handle_watch_logs;
+            let result = handle_watch_logs(&SessionManager::new()?, &session_id, tail)?;
             println!("{}", result);
         }
 
@@ -594,7 +1412,7 @@ async fn async_main() -> Result<()> {
 
         Commands::WatchStopAll => {
             use crate::commands::watch::handle_watch_stop_all;
-            let result = handle_watch_stop_all()?;
+            let result = handle_watch_stop_all(&SessionManager::new()?)?;
             println!("{}", result);
         }
 
@@ -608,12 +1426,35 @@ async fn async_main() -> Result<()> {
         }
         
         // DIRECT EDIT
-        Commands::ReplacePreview { file, pattern, replacement } => {
+        Commands::ReplacePreview { file, pattern, replacement, regex, ignore_case, whole_word, diff, context } => {
+            let options = ReplaceOptions {
+                use_regex: regex,
+                ignore_case,
+                whole_word,
+            };
             let mut preview_manager = PreviewManager::new()?;
-            let preview_id = preview_manager.create_replace_preview(&file, &pattern, &replacement)?;
-            let preview = preview_manager.get_preview(&preview_id).unwrap();
-            println!("Preview ID: {}", preview_id);
-            println!("{}", preview.preview_text);
+            preview_manager.set_context(context);
+            let preview_id = preview_manager.create_replace_preview(&file, &pattern, &replacement, options)?;
+            print_preview(&preview_manager, &preview_id, diff)?;
+        }
+        
+        Commands::SessionReplacePreview { session_id, pattern, replacement, regex, ignore_case, whole_word, diff, context } => {
+            let options = ReplaceOptions {
+                use_regex: regex,
+                ignore_case,
+                whole_word,
+            };
+            let session_manager = SessionManager::new()?;
+            let session_info = session_manager.get_session_info(&session_id)
+                .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+            let files: Vec<PathBuf> = session_info.analysis_results.iter()
+                .map(|result| session_info.source_path(&result.file_info.path))
+                .collect();
+            
+            let mut preview_manager = PreviewManager::new()?;
+            preview_manager.set_context(context);
+            let preview_id = preview_manager.create_session_replace_preview(&session_id, &files, &pattern, &replacement, options)?;
+            print_preview(&preview_manager, &preview_id, diff)?;
         }
         
         Commands::ReplaceConfirm { preview_id } => {
@@ -622,12 +1463,11 @@ async fn async_main() -> Result<()> {
             println!("{}", result);
         }
         
-        Commands::InsertPreview { file, position, content } => {
+        Commands::InsertPreview { file, position, content, diff, context } => {
             let mut preview_manager = PreviewManager::new()?;
+            preview_manager.set_context(context);
             let preview_id = preview_manager.create_insert_preview(&file, position, &content)?;
-            let preview = preview_manager.get_preview(&preview_id).unwrap();
-            println!("Preview ID: {}", preview_id);
-            println!("{}", preview.preview_text);
+            print_preview(&preview_manager, &preview_id, diff)?;
         }
         
         Commands::InsertConfirm { preview_id } => {
@@ -636,12 +1476,10 @@ async fn async_main() -> Result<()> {
             println!("{}", result);
         }
         
-        Commands::MovelinesPreview { source, start, count, destination, position } => {
+        Commands::MovelinesPreview { source, start, count, destination, position, diff } => {
             let mut preview_manager = PreviewManager::new()?;
             let preview_id = preview_manager.create_movelines_preview(&source, start, count, &destination, position)?;
-            let preview = preview_manager.get_preview(&preview_id).unwrap();
-            println!("Preview ID: {}", preview_id);
-            println!("{}", preview.preview_text);
+            print_preview(&preview_manager, &preview_id, diff)?;
         }
         
         Commands::MovelinesConfirm { preview_id } => {
@@ -650,12 +1488,10 @@ async fn async_main() -> Result<()> {
             println!("{}", result);
         }
         
-        Commands::MoveclassPreview { session_id, symbol_id, target } => {
+        Commands::MoveclassPreview { session_id, symbol_id, target, diff } => {
             let mut preview_manager = PreviewManager::new()?;
             let preview_id = preview_manager.create_moveclass_preview(&session_id, &symbol_id, &target)?;
-            let preview = preview_manager.get_preview(&preview_id).unwrap();
-            println!("Preview ID: {}", preview_id);
-            println!("{}", preview.preview_text);
+            print_preview(&preview_manager, &preview_id, diff)?;
         }
         
         Commands::MoveclassConfirm { preview_id } => {
@@ -664,6 +1500,12 @@ async fn async_main() -> Result<()> {
             println!("{}", result);
         }
         
+        Commands::UndoPreview { preview_id } => {
+            let mut preview_manager = PreviewManager::new()?;
+            let result = preview_manager.undo(&preview_id)?;
+            println!("{}", result);
+        }
+        
         // AST REVOLUTION - Real implementations
         Commands::AstStats { session_id } => {
             let mut session_manager = SessionManager::new()?;
@@ -671,27 +1513,34 @@ async fn async_main() -> Result<()> {
             println!("{}", result);
         }
         
-        Commands::AstQuery { session_id, path } => {
+        Commands::AstQuery { session_id, path, regex, output_lines, context, max_bytes } => {
             let session_manager = SessionManager::new()?;
-            let result = session_manager.handle_ast_query(&session_id, &path)?;
+            let snippet = output_lines.then_some(SnippetOptions { context, max_bytes });
+            let result = session_manager.handle_ast_query(&session_id, &path, regex, snippet)?;
             println!("{}", result);
         }
         
-        Commands::ScopeAnalysis { session_id, line } => {
+        Commands::ScopeAnalysis { session_id, line, file, no_chain } => {
             let session_manager = SessionManager::new()?;
-            let result = session_manager.handle_scope_analysis(&session_id, line)?;
+            let result = session_manager.handle_scope_analysis(&session_id, line, file.as_deref(), !no_chain)?;
             println!("{}", result);
         }
         
-        Commands::AstDump { session_id, format } => {
+        Commands::AstDiff { base_session, head_session } => {
             let session_manager = SessionManager::new()?;
-            let result = session_manager.handle_ast_dump(&session_id, &format)?;
+            let result = session_manager.handle_ast_diff(&base_session, &head_session)?;
+            println!("{}", result);
+        }
+        
+        Commands::AstDump { session_id, format, depth, node_type } => {
+            let session_manager = SessionManager::new()?;
+            let filter = DumpFilter::parse(depth, node_type.as_deref())?;
+            let result = session_manager.handle_ast_dump(&session_id, &format, &filter)?;
             println!("{}", result);
         }
         
         // MEMORY SYSTEM
         Commands::Memory { operation } => {
-            let config = ConfigManager::new();
             let memory_manager = MemoryManager::new(config.get().memory.storage_path.clone())?;
             
             match operation {
@@ -732,15 +1581,73 @@ async fn async_main() -> Result<()> {
                     }
                 }
                 
-                MemoryOperation::Timeline { memory_type, days } => {
+                MemoryOperation::Edit { memory_type, name, content } => {
+                    let mem_type: MemoryType = memory_type.parse()?;
+                    let content = content.unwrap_or_else(|| {
+                        // Read from stdin if no content provided
+                        use std::io::Read;
+                        let mut buffer = String::new();
+                        std::io::stdin().read_to_string(&mut buffer).unwrap_or_default();
+                        buffer
+                    });
+                    let entry = memory_manager.update(&name, mem_type, &content)?;
+                    println!("Memory updated: {} ({})", entry.name, entry.id);
+                }
+                
+                MemoryOperation::Delete { memory_type, name } => {
+                    let (memory_type, name) = match name {
+                        Some(name) => (memory_type, name),
+                        None => match memory_type.split_once(':') {
+                            Some((t, n)) => (t.to_string(), n.to_string()),
+                            None => anyhow::bail!("Missing memory name: use `delete TYPE NAME` or `delete TYPE:NAME`"),
+                        },
+                    };
+                    let mem_type: MemoryType = memory_type.parse()?;
+                    let deleted = memory_manager.delete(&name, mem_type)?;
+                    println!("Deleted {} memory entr{}", deleted, if deleted == 1 { "y" } else { "ies" });
+                }
+                
+                MemoryOperation::Search { query, memory_type } => {
+                    let mem_type = if let Some(t) = memory_type {
+                        Some(t.parse()?)
+                    } else {
+                        None
+                    };
+                    let entries = memory_manager.search(&query, mem_type)?;
+                    
+                    println!("Memory Search: \"{}\" ({} matches)", query, entries.len());
+                    for entry in entries {
+                        println!("  {} [{}] {} - {}", 
+                                entry.id, 
+                                entry.memory_type, 
+                                entry.name,
+                                entry.created_at.format("%Y-%m-%d %H:%M:%S"));
+                        if let Some(snippet) = entry.metadata.get("snippet") {
+                            println!("      {}", snippet);
+                        }
+                    }
+                }
+                
+                MemoryOperation::Timeline { memory_type, days, since, until } => {
                     let mem_type = if let Some(t) = memory_type {
                         Some(t.parse()?)
                     } else {
                         None
                     };
-                    let entries = memory_manager.timeline(mem_type, days)?;
+                    let (since, mut window) = match since {
+                        Some(since) => (parse_time_bound(&since)?, format!("since {}", since)),
+                        None => {
+                            let days = days.unwrap_or(7);
+                            (chrono::Utc::now() - chrono::Duration::days(days as i64), format!("last {} days", days))
+                        }
+                    };
+                    if let Some(until) = &until {
+                        window.push_str(&format!(" until {}", until));
+                    }
+                    let until = until.as_deref().map(parse_time_bound).transpose()?;
+                    let entries = memory_manager.timeline(mem_type, since, until)?;
                     
-                    println!("Memory Timeline (last {} days):", days);
+                    println!("Memory Timeline ({}):", window);
                     for entry in entries {
                         println!("  {} [{}] {} - {}", 
                                 entry.id, 
@@ -754,7 +1661,7 @@ async fn async_main() -> Result<()> {
         
         // SYSTEM
         Commands::Config { operation } => {
-            let mut config_manager = ConfigManager::new();
+            let mut config_manager = config;
             
             match operation {
                 ConfigOperation::Show => {
@@ -766,9 +1673,41 @@ async fn async_main() -> Result<()> {
                     config_manager.set(&key, &value)?;
                     println!("Configuration updated: {} = {}", key, value);
                 }
+                
+                ConfigOperation::Validate => {
+                    let problems = config_manager.validate()?;
+                    if problems.is_empty() {
+                        println!("✅ {} is valid", config_manager.config_path().display());
+                    } else {
+                        for problem in &problems {
+                            println!("❌ {}", problem);
+                        }
+                        anyhow::bail!("{} problem(s) in {}", problems.len(), config_manager.config_path().display());
+                    }
+                }
+                
+                ConfigOperation::Export { path } => {
+                    config_manager.export(&path)?;
+                    println!("Configuration exported to {}", path.display());
+                }
+                
+                ConfigOperation::Import { path, merge } => {
+                    config_manager.import(&path, merge)?;
+                    println!("Configuration {} from {}", if merge { "merged" } else { "imported" }, path.display());
+                }
+                
+                ConfigOperation::Reset => {
+                    config_manager.reset()?;
+                    println!("Configuration reset to defaults");
+                }
             }
         }
         
+        Commands::Schema { target, type_name } => {
+            let schema = schema_for_type(type_name.as_deref().unwrap_or(&target))?;
+            println!("{}", to_json(&schema)?);
+        }
+        
         Commands::Languages => {
             println!("Supported Languages:");
             println!("  🟨 JavaScript (.js, .mjs, .jsx, .cjs)");
@@ -779,6 +1718,8 @@ async fn async_main() -> Result<()> {
             println!("  🟦 C# (.cs)");
             println!("  🐹 Go (.go)");
             println!("  🦀 Rust (.rs)");
+            println!("  ☕ Java (.java)");
+            println!("  🟣 Kotlin (.kt, .kts)");
         }
     }
     
@@ -10,9 +10,17 @@ mod tests {
     use std::path::PathBuf;
     
     use nekocode_rust::core::incremental::{ChangeDetector, ChangeType, IncrementalSummary};
-    use nekocode_rust::core::session::SessionManager;
+    use nekocode_rust::core::session::{AnalysisSession, SessionManager};
+    use nekocode_rust::core::types::AnalysisConfig;
     use nekocode_rust::commands::session_update::handle_session_update;
     
+    /// Session manager keeping its sessions in a temporary directory
+    fn temp_session_manager() -> (TempDir, SessionManager) {
+        let dir = TempDir::new().unwrap();
+        let manager = SessionManager::with_dir(dir.path().to_path_buf()).unwrap();
+        (dir, manager)
+    }
+    
     /// Test basic change detection functionality
     #[tokio::test]
     async fn test_change_detection_basic() {
@@ -91,7 +99,7 @@ mod tests {
         fs::write(&test_file, "console.log('initial');").unwrap();
         
         // Create session
-        let mut session_manager = SessionManager::new().unwrap();
+        let (_sessions, mut session_manager) = temp_session_manager();
         let session_id = session_manager.create_session(temp_dir.path()).await.unwrap();
         
         // Update session without any changes
@@ -115,7 +123,7 @@ mod tests {
         fs::write(&file2, "print('file2')").unwrap();
         
         // Create session
-        let mut session_manager = SessionManager::new().unwrap();
+        let (_sessions, mut session_manager) = temp_session_manager();
         let session_id = session_manager.create_session(temp_dir.path()).await.unwrap();
         
         // Modify one file
@@ -140,7 +148,7 @@ mod tests {
         fs::write(&file1, "console.log('file1');").unwrap();
         
         // Create session
-        let mut session_manager = SessionManager::new().unwrap();
+        let (_sessions, mut session_manager) = temp_session_manager();
         let session_id = session_manager.create_session(temp_dir.path()).await.unwrap();
         
         // Add new files
@@ -172,7 +180,7 @@ mod tests {
         fs::write(&file3, "fn main() {}").unwrap();
         
         // Create session
-        let mut session_manager = SessionManager::new().unwrap();
+        let (_sessions, mut session_manager) = temp_session_manager();
         let session_id = session_manager.create_session(temp_dir.path()).await.unwrap();
         
         // Delete files
@@ -198,18 +206,18 @@ mod tests {
         fs::write(&test_file, "console.log('initial');").unwrap();
         
         // Create session
-        let mut session_manager = SessionManager::new().unwrap();
+        let (_sessions, mut session_manager) = temp_session_manager();
         let session_id = session_manager.create_session(temp_dir.path()).await.unwrap();
         
         // Test basic update
-        let result = handle_session_update(&session_id, false, false).await.unwrap();
+        let result = handle_session_update(&mut session_manager, &session_id, false, false).await.unwrap();
         assert!(result.contains("0 modified"));
         assert!(result.contains("0 added"));
         
         // Modify file and test again
         fs::write(&test_file, "console.log('modified');").unwrap();
         
-        let result = handle_session_update(&session_id, false, false).await.unwrap();
+        let result = handle_session_update(&mut session_manager, &session_id, false, false).await.unwrap();
         assert!(result.contains("1 modified") || result.contains("Updated 1 files"));
     }
     
@@ -223,7 +231,7 @@ mod tests {
         fs::write(&test_file, "console.log('initial');").unwrap();
         
         // Create session
-        let mut session_manager = SessionManager::new().unwrap();
+        let (_sessions, mut session_manager) = temp_session_manager();
         let session_id = session_manager.create_session(temp_dir.path()).await.unwrap();
         
         // Add new file
@@ -231,7 +239,7 @@ mod tests {
         fs::write(&new_file, "console.log('new');").unwrap();
         
         // Test dry run
-        let result = handle_session_update(&session_id, false, true).await.unwrap();
+        let result = handle_session_update(&mut session_manager, &session_id, false, true).await.unwrap();
         assert!(result.contains("pending changes"));
         assert!(result.contains("new.js"));
         assert!(result.contains("Run without --dry-run"));
@@ -251,11 +259,11 @@ mod tests {
         fs::write(&test_file, "console.log('initial');").unwrap();
         
         // Create session
-        let mut session_manager = SessionManager::new().unwrap();
+        let (_sessions, mut session_manager) = temp_session_manager();
         let session_id = session_manager.create_session(temp_dir.path()).await.unwrap();
         
         // Test verbose output
-        let result = handle_session_update(&session_id, true, false).await.unwrap();
+        let result = handle_session_update(&mut session_manager, &session_id, true, false).await.unwrap();
         
         // Should be valid JSON
         let json_result: serde_json::Value = serde_json::from_str(&result).unwrap();
@@ -276,7 +284,7 @@ mod tests {
         }
         
         // Create session
-        let mut session_manager = SessionManager::new().unwrap();
+        let (_sessions, mut session_manager) = temp_session_manager();
         let session_id = session_manager.create_session(temp_dir.path()).await.unwrap();
         
         // Modify only one file
@@ -302,7 +310,8 @@ mod tests {
     /// Test error handling for invalid session ID
     #[tokio::test]
     async fn test_invalid_session_id() {
-        let result = handle_session_update("invalid-session-id", false, false).await;
+        let (_sessions, mut session_manager) = temp_session_manager();
+        let result = handle_session_update(&mut session_manager, "invalid-session-id", false, false).await;
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("Session not found"));
     }
@@ -329,7 +338,7 @@ mod tests {
         fs::write(&test_file, "console.log('initial');").unwrap();
         
         // Create session
-        let mut session_manager = SessionManager::new().unwrap();
+        let (_sessions, mut session_manager) = temp_session_manager();
         let session_id = session_manager.create_session(temp_dir.path()).await.unwrap();
         
         // Update should work (change detector is initialized during session creation)
@@ -338,4 +347,78 @@ mod tests {
         // Should succeed without errors
         assert_eq!(summary.total_files, 1);
     }
-}
\ No newline at end of file
+    
+    /// Test re-analyzing only changed files (used by `analyze --watch`)
+    #[tokio::test]
+    async fn test_reanalyze_changed_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let app = temp_dir.path().join("app.js");
+        let util = temp_dir.path().join("util.js");
+        fs::write(&app, "function a() {}").unwrap();
+        fs::write(&util, "function u() {}").unwrap();
+        
+        let mut session = AnalysisSession::new();
+        let mut analysis = session.analyze_path(temp_dir.path(), false).await.unwrap();
+        assert_eq!(analysis.summary.total_functions, 2);
+        
+        // Modify one file, add one, delete one
+        let extra = temp_dir.path().join("extra.js");
+        fs::write(&app, "function a() {}\nfunction b() {}").unwrap();
+        fs::write(&extra, "class Extra {}").unwrap();
+        fs::remove_file(&util).unwrap();
+        
+        let updated = session.reanalyze_files(&mut analysis, &[app.clone(), extra, util]).await.unwrap();
+        assert_eq!(updated, 3);
+        assert_eq!(analysis.files.len(), 2);
+        assert_eq!(analysis.summary.total_functions, 2);
+        assert_eq!(analysis.summary.total_classes, 1);
+        
+        // Ignored files are not picked up
+        let notes = temp_dir.path().join("notes.txt");
+        fs::write(&notes, "hello").unwrap();
+        assert_eq!(session.reanalyze_files(&mut analysis, &[notes]).await.unwrap(), 0);
+    }
+    
+    /// Test that `.nekocodeignore` at the analysis root excludes paths during discovery
+    #[tokio::test]
+    async fn test_nekocodeignore_excludes_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("latest")).unwrap();
+        fs::create_dir_all(root.join("generated")).unwrap();
+        fs::write(root.join("latest/index.js"), "function current() {}").unwrap();
+        fs::write(root.join("generated/api.js"), "function api() {}").unwrap();
+        fs::write(root.join("generated/keep.js"), "function keep() {}").unwrap();
+        fs::write(root.join("app.min.js"), "function min() {}").unwrap();
+        fs::write(root.join(".nekocodeignore"), "*.min.js\ngenerated/*\n!generated/keep.js\n").unwrap();
+        
+        let mut session = AnalysisSession::new();
+        let analysis = session.analyze_path(root, false).await.unwrap();
+        
+        let mut names: Vec<_> = analysis.files.iter().map(|f| f.file_info.name.clone()).collect();
+        names.sort();
+        assert_eq!(names, vec!["index.js", "keep.js"]);
+    }
+    
+    /// Test that per-file failures are collected instead of aborting the analysis
+    #[tokio::test]
+    async fn test_directory_analysis_collects_file_errors() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("good.js"), "function ok() {}").unwrap();
+        fs::write(temp_dir.path().join("broken.js"), [0xff, 0xfe, 0x00, 0x80]).unwrap();
+        
+        let mut session = AnalysisSession::new();
+        let analysis = session.analyze_path(temp_dir.path(), false).await.unwrap();
+        assert_eq!(analysis.files.len(), 1);
+        assert_eq!(analysis.errors.len(), 1);
+        assert!(analysis.errors[0].path.ends_with("broken.js"));
+        
+        let json = serde_json::to_value(&analysis).unwrap();
+        assert_eq!(json["errors"].as_array().unwrap().len(), 1);
+        
+        let mut config = AnalysisConfig::default();
+        config.fail_on_error = true;
+        let mut strict = AnalysisSession::with_config(config);
+        assert!(strict.analyze_path(temp_dir.path(), false).await.is_err());
+    }
+}